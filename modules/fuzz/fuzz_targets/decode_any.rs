@@ -0,0 +1,12 @@
+//! Decodes arbitrary bytes as an IBC message. Decoding may fail, but must not panic.
+
+#![no_main]
+
+use ibc::ics26_routing::handler::decode;
+use ibc::mock::fuzz::RawMsg;
+use libfuzzer_sys::fuzz_target;
+use prost_types::Any;
+
+fuzz_target!(|msg: RawMsg| {
+    let _ = decode(Any::from(msg));
+});
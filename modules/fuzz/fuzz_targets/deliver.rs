@@ -0,0 +1,14 @@
+//! Delivers arbitrary batches of messages to a mock chain, which checks its invariants after each
+//! batch (see `ibc::mock::fuzz::Harness`).
+
+#![no_main]
+
+use ibc::mock::fuzz::{FuzzMsg, Harness};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|batches: Vec<Vec<FuzzMsg>>| {
+    let mut harness = Harness::new();
+    for msgs in batches {
+        harness.deliver(msgs);
+    }
+});
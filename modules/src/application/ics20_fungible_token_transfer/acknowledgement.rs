@@ -0,0 +1,6 @@
+//! The acknowledgements of ICS20 packets, which are the standard acknowledgements of ICS4 (see
+//! `ics04_channel::acknowledgement`).
+
+/// The reason of every error acknowledgement. Like `ibc-go`, the actual error is left out of the
+/// acknowledgement, which is committed to by the receiving chain and hence must be deterministic.
+pub const ACK_ERR_STR: &str = "error handling packet on destination chain: see events for details";
@@ -0,0 +1,58 @@
+use std::convert::TryFrom;
+
+use serde_derive::{Deserialize, Serialize};
+
+use ibc_proto::cosmos::base::v1beta1::Coin as RawCoin;
+
+use crate::application::ics20_fungible_token_transfer::error::{Error, Kind};
+
+/// An amount of tokens of a given denomination, as held by the bank of the host chain.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct Coin {
+    pub denom: String,
+    pub amount: u64,
+}
+
+impl Coin {
+    pub fn new(denom: impl Into<String>, amount: u64) -> Self {
+        Self {
+            denom: denom.into(),
+            amount,
+        }
+    }
+}
+
+impl TryFrom<RawCoin> for Coin {
+    type Error = Error;
+
+    fn try_from(raw: RawCoin) -> Result<Self, Self::Error> {
+        if raw.denom.trim().is_empty() {
+            return Err(Kind::InvalidDenom(raw.denom).into());
+        }
+
+        let amount = raw
+            .amount
+            .parse::<u64>()
+            .map_err(|e| Kind::InvalidAmount(raw.amount.clone()).context(e))?;
+        if amount == 0 {
+            return Err(Kind::InvalidAmount(raw.amount).into());
+        }
+
+        Ok(Coin::new(raw.denom, amount))
+    }
+}
+
+impl From<Coin> for RawCoin {
+    fn from(coin: Coin) -> Self {
+        RawCoin {
+            denom: coin.denom,
+            amount: coin.amount.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for Coin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.amount, self.denom)
+    }
+}
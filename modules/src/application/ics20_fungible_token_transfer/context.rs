@@ -1,5 +1,42 @@
+use crate::application::ics20_fungible_token_transfer::coin::Coin;
+use crate::application::ics20_fungible_token_transfer::denom::DenomTrace;
+use crate::application::ics20_fungible_token_transfer::error::Error;
 use crate::ics04_channel::context::{ChannelKeeper, ChannelReader};
+use crate::ics05_port::context::PortReader;
+use crate::ics24_host::identifier::{ChannelId, PortId};
+use crate::signer::Signer;
+
+/// A context supplying all the necessary read-only dependencies for processing ICS20 transfers.
+pub trait Ics20Reader {
+    /// Returns the denomination trace with the given hash, i.e., the trace of the vouchers
+    /// denominated `ibc/{hash}`, if known to the host chain.
+    fn get_denom_trace(&self, denom_hash: &str) -> Option<DenomTrace>;
+}
+
+/// A context supplying all the necessary write-only dependencies for processing ICS20 transfers.
+pub trait Ics20Keeper {
+    /// Records the trace of the vouchers denominated `ibc/{trace.hash()}`.
+    fn store_denom_trace(&mut self, trace: DenomTrace) -> Result<(), Error>;
+}
+
+/// The interface of the bank module of the host chain, which holds the balances of all accounts.
+pub trait BankKeeper {
+    /// Moves `coin` from the account `from` to the account `to`.
+    fn send_coins(&mut self, from: &Signer, to: &Signer, coin: &Coin) -> Result<(), Error>;
+
+    /// Creates `coin` out of thin air, and credits it to `account`.
+    fn mint_coins(&mut self, account: &Signer, coin: &Coin) -> Result<(), Error>;
+
+    /// Removes `coin` from the balance of `account`, and destroys it.
+    fn burn_coins(&mut self, account: &Signer, coin: &Coin) -> Result<(), Error>;
+
+    /// Returns the account which escrows the native tokens sent over the given port and channel.
+    fn get_escrow_address(&self, port_id: &PortId, channel_id: &ChannelId) -> Signer;
+}
 
 /// Captures all the dependencies which the ICS20 module requires to be able to dispatch and
 /// process IBC messages.
-pub trait Ics20Context: ChannelReader + ChannelKeeper + Clone {}
+pub trait Ics20Context:
+    Ics20Reader + Ics20Keeper + BankKeeper + ChannelReader + ChannelKeeper + PortReader + Clone
+{
+}
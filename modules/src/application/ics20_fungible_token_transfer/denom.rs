@@ -0,0 +1,230 @@
+//! Denomination traces of fungible tokens. A trace records the sequence of (port, channel) hops
+//! over which a token was transferred away from its source chain, e.g., the trace with path
+//! `transfer/channel-1` and base denomination `uatom` denotes vouchers of `uatom` which were
+//! received over `channel-1`.
+
+use std::convert::TryFrom;
+
+use serde_derive::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use subtle_encoding::hex;
+use tendermint_proto::Protobuf;
+
+use ibc_proto::ibc::apps::transfer::v1::DenomTrace as RawDenomTrace;
+
+use crate::application::ics20_fungible_token_transfer::error::{Error, Kind};
+use crate::ics24_host::identifier::{ChannelId, PortId};
+
+/// The prefix of the denominations of vouchers, which are of the form `ibc/{hash}`.
+pub const DENOM_PREFIX: &str = "ibc";
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct DenomTrace {
+    /// The chain of port/channel identifiers used for tracing the source of the token.
+    pub path: String,
+    /// The base denomination of the token, as defined on its source chain.
+    pub base_denom: String,
+}
+
+impl DenomTrace {
+    pub fn new(path: impl Into<String>, base_denom: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            base_denom: base_denom.into(),
+        }
+    }
+
+    /// Parses a full denomination path, e.g. `transfer/channel-0/uatom`, into a trace. The last
+    /// element of the path is the base denomination, all the preceding ones form the trace path.
+    pub fn parse(full_denom: &str) -> Self {
+        match full_denom.rfind('/') {
+            None => Self::new("", full_denom),
+            Some(idx) => Self::new(&full_denom[..idx], &full_denom[idx + 1..]),
+        }
+    }
+
+    /// Returns the full denomination path, i.e., `{path}/{base_denom}`.
+    pub fn full_path(&self) -> String {
+        if self.path.is_empty() {
+            self.base_denom.clone()
+        } else {
+            format!("{}/{}", self.path, self.base_denom)
+        }
+    }
+
+    /// The hash of this trace, namely the upper-case hex encoding of `sha256(full_path)`.
+    pub fn hash(&self) -> String {
+        let digest = Sha256::digest(self.full_path().as_bytes());
+        String::from_utf8(hex::encode_upper(digest)).unwrap()
+    }
+
+    /// Returns the denomination under which the token is held by the bank. For native tokens this
+    /// is the base denomination, while vouchers are denominated as `ibc/{hash}`.
+    pub fn ibc_denom(&self) -> String {
+        if self.path.is_empty() {
+            self.base_denom.clone()
+        } else {
+            format!("{}/{}", DENOM_PREFIX, self.hash())
+        }
+    }
+
+    /// Returns a copy of this trace, with the given port and channel prepended to its path.
+    pub fn add_prefix(&self, port_id: &PortId, channel_id: &ChannelId) -> Self {
+        let prefix = format!("{}/{}", port_id, channel_id);
+        if self.path.is_empty() {
+            Self::new(prefix, self.base_denom.clone())
+        } else {
+            Self::new(format!("{}/{}", prefix, self.path), self.base_denom.clone())
+        }
+    }
+
+    /// Returns a copy of this trace without its first port and channel hop. The trace of a native
+    /// token is returned unchanged.
+    pub fn remove_prefix(&self) -> Self {
+        let mut hops = self.path.splitn(3, '/');
+        let _port = hops.next();
+        let _channel = hops.next();
+        Self::new(hops.next().unwrap_or_default(), self.base_denom.clone())
+    }
+
+    pub fn validate_basic(&self) -> Result<(), Error> {
+        if self.base_denom.trim().is_empty() {
+            return Err(Kind::InvalidDenom(self.full_path())
+                .context("base denomination cannot be blank")
+                .into());
+        }
+
+        if !self.path.is_empty() {
+            let hops: Vec<&str> = self.path.split('/').collect();
+            if hops.len() % 2 != 0 || hops.iter().any(|hop| hop.trim().is_empty()) {
+                return Err(Kind::InvalidDenom(self.full_path())
+                    .context("trace path must consist of port and channel identifier pairs")
+                    .into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Protobuf<RawDenomTrace> for DenomTrace {}
+
+impl TryFrom<RawDenomTrace> for DenomTrace {
+    type Error = Error;
+
+    fn try_from(raw: RawDenomTrace) -> Result<Self, Self::Error> {
+        let trace = DenomTrace::new(raw.path, raw.base_denom);
+        trace.validate_basic()?;
+        Ok(trace)
+    }
+}
+
+impl From<DenomTrace> for RawDenomTrace {
+    fn from(trace: DenomTrace) -> Self {
+        RawDenomTrace {
+            path: trace.path,
+            base_denom: trace.base_denom,
+        }
+    }
+}
+
+impl std::fmt::Display for DenomTrace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.full_path())
+    }
+}
+
+/// Returns the prefix which the given port and channel add to a denomination, `{port}/{channel}/`.
+pub fn get_denom_prefix(port_id: &PortId, channel_id: &ChannelId) -> String {
+    format!("{}/{}/", port_id, channel_id)
+}
+
+/// Returns true if the chain sending `denom` over the given source port and channel is the source
+/// of the token, namely if `denom` does not originate from the receiving chain. In this case the
+/// sending chain escrows the tokens, and the receiving chain mints vouchers for them.
+pub fn sender_chain_is_source(
+    source_port: &PortId,
+    source_channel: &ChannelId,
+    denom: &str,
+) -> bool {
+    !receiver_chain_is_source(source_port, source_channel, denom)
+}
+
+/// Returns true if the chain receiving `denom` over the given source port and channel is the
+/// source of the token, namely if the token was previously sent from the receiving chain over the
+/// same channel. In this case the denomination is prefixed with the source port and channel.
+pub fn receiver_chain_is_source(
+    source_port: &PortId,
+    source_channel: &ChannelId,
+    denom: &str,
+) -> bool {
+    denom.starts_with(&get_denom_prefix(source_port, source_channel))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use test_env_log::test;
+
+    use super::{receiver_chain_is_source, sender_chain_is_source, DenomTrace};
+    use crate::ics24_host::identifier::{ChannelId, PortId};
+
+    #[test]
+    fn parse_and_prefix_traces() {
+        let port = PortId::from_str("transfer").unwrap();
+        let channel = ChannelId::from_str("channel-0").unwrap();
+
+        let native = DenomTrace::parse("uatom");
+        assert_eq!(native, DenomTrace::new("", "uatom"));
+        assert_eq!(native.ibc_denom(), "uatom");
+        assert_eq!(native.remove_prefix(), native);
+
+        let voucher = native.add_prefix(&port, &channel);
+        assert_eq!(voucher.full_path(), "transfer/channel-0/uatom");
+        assert_eq!(DenomTrace::parse(&voucher.full_path()), voucher);
+        assert!(voucher.validate_basic().is_ok());
+
+        let two_hops = voucher.add_prefix(&port, &ChannelId::from_str("channel-1").unwrap());
+        assert_eq!(two_hops.path, "transfer/channel-1/transfer/channel-0");
+        assert_eq!(two_hops.remove_prefix(), voucher);
+        assert_eq!(voucher.remove_prefix(), native);
+
+        assert!(DenomTrace::new("transfer", "uatom")
+            .validate_basic()
+            .is_err());
+        assert!(DenomTrace::new("", " ").validate_basic().is_err());
+    }
+
+    #[test]
+    fn ibc_denom_matches_ibc_go() {
+        // Upper-case hex encoding of the SHA-256 digest of the full path, as computed by `ibc-go`.
+        let trace = DenomTrace::parse("transfer/channel-0/uatom");
+        assert_eq!(
+            trace.ibc_denom(),
+            "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2"
+        );
+    }
+
+    #[test]
+    fn source_chain_detection() {
+        let port = PortId::from_str("transfer").unwrap();
+        let channel = ChannelId::from_str("channel-0").unwrap();
+
+        assert!(sender_chain_is_source(&port, &channel, "uatom"));
+        assert!(sender_chain_is_source(
+            &port,
+            &channel,
+            "transfer/channel-1/uatom"
+        ));
+        assert!(receiver_chain_is_source(
+            &port,
+            &channel,
+            "transfer/channel-0/uatom"
+        ));
+        assert!(!receiver_chain_is_source(
+            &port,
+            &channel,
+            "transfer/channel-01/uatom"
+        ));
+    }
+}
@@ -1,7 +1,9 @@
 use anomaly::{BoxError, Context};
 use thiserror::Error;
 
+use crate::ics04_channel::channel::Order;
 use crate::ics24_host::identifier::{ChannelId, PortId};
+use crate::signer::Signer;
 
 pub type Error = anomaly::Error<Kind>;
 
@@ -10,6 +12,9 @@ pub enum Kind {
     #[error("unrecognized ICS-20 transfer message type URL {0}")]
     UnknownMessageTypeUrl(String),
 
+    #[error("the transfer message is malformed and cannot be decoded")]
+    MalformedMessageBytes,
+
     #[error("error raised by message handler")]
     HandlerRaisedError,
 
@@ -35,6 +40,42 @@ pub enum Kind {
 
     #[error("invalid packet timeout timestamp value")]
     InvalidPacketTimeoutTimestamp(u64),
+
+    #[error("no token specified for transfer")]
+    MissingToken,
+
+    #[error("invalid token amount {0}")]
+    InvalidAmount(String),
+
+    #[error("invalid token denomination {0}")]
+    InvalidDenom(String),
+
+    #[error("no denomination trace found for hash {0}")]
+    DenomTraceNotFound(String),
+
+    #[error("invalid fungible token packet data")]
+    InvalidPacketData,
+
+    #[error("account {0} has insufficient funds of denomination {1}")]
+    InsufficientFunds(Signer, String),
+
+    #[error("the bank failed to move funds")]
+    BankError,
+
+    #[error("invalid fungible token packet acknowledgement")]
+    InvalidAcknowledgement,
+
+    #[error("invalid channel order {0}, transfer channels must be unordered")]
+    InvalidChannelOrder(Order),
+
+    #[error("invalid transfer channel version {0}")]
+    InvalidVersion(String),
+
+    #[error("transfer channels cannot be closed by users")]
+    ChannelClosingNotAllowed,
+
+    #[error("no capability found for port_id {0} and channel_id {1}")]
+    ChannelCapabilityNotFound(PortId, ChannelId),
 }
 
 impl Kind {
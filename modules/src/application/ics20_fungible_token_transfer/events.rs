@@ -0,0 +1,279 @@
+//! Types for the IBC events emitted by the ICS20 fungible token transfer application.
+use anomaly::BoxError;
+use serde_derive::{Deserialize, Serialize};
+use tendermint::abci::Event as AbciEvent;
+
+use crate::events::{extract_attribute, optional_attribute, tag, IbcEvent};
+use crate::ics02_client::height::Height;
+use crate::ics04_channel::acknowledgement::Acknowledgement;
+use crate::signer::Signer;
+
+/// The event types, as `ibc-go` names them.
+const TRANSFER_EVENT_TYPE: &str = "ibc_transfer";
+const PACKET_EVENT_TYPE: &str = "fungible_token_packet";
+const DENOM_TRACE_EVENT_TYPE: &str = "denomination_trace";
+const TIMEOUT_EVENT_TYPE: &str = "timeout";
+
+/// The attribute keys, as `ibc-go` names them.
+const MODULE_ATTRIBUTE_KEY: &str = "module";
+const SENDER_ATTRIBUTE_KEY: &str = "sender";
+const RECEIVER_ATTRIBUTE_KEY: &str = "receiver";
+const DENOM_ATTRIBUTE_KEY: &str = "denom";
+const AMOUNT_ATTRIBUTE_KEY: &str = "amount";
+const SUCCESS_ATTRIBUTE_KEY: &str = "success";
+const ACK_ATTRIBUTE_KEY: &str = "acknowledgement";
+const TRACE_HASH_ATTRIBUTE_KEY: &str = "trace_hash";
+const REFUND_RECEIVER_ATTRIBUTE_KEY: &str = "refund_receiver";
+const REFUND_DENOM_ATTRIBUTE_KEY: &str = "refund_denom";
+const REFUND_AMOUNT_ATTRIBUTE_KEY: &str = "refund_amount";
+
+/// The value of the `module` attribute.
+const MODULE_NAME: &str = "transfer";
+
+/// Parses an ICS20 event from the attributes of `event`. Returns `None` if `event` is not an ICS20
+/// event, and an error if it is a malformed one.
+pub fn try_from_abci_event(event: &AbciEvent) -> Result<Option<IbcEvent>, BoxError> {
+    let ibc_event = match event.type_str.as_str() {
+        TRANSFER_EVENT_TYPE => IbcEvent::Transfer(Transfer {
+            height: Default::default(),
+            sender: extract_attribute(event, SENDER_ATTRIBUTE_KEY)?.parse()?,
+            receiver: extract_attribute(event, RECEIVER_ATTRIBUTE_KEY)?.parse()?,
+        }),
+        PACKET_EVENT_TYPE => IbcEvent::FungibleTokenPacket(extract_packet_from_tx(event)?),
+        DENOM_TRACE_EVENT_TYPE => IbcEvent::DenominationTrace(DenominationTrace {
+            height: Default::default(),
+            trace_hash: extract_attribute(event, TRACE_HASH_ATTRIBUTE_KEY)?.to_string(),
+            denom: extract_attribute(event, DENOM_ATTRIBUTE_KEY)?.to_string(),
+        }),
+        TIMEOUT_EVENT_TYPE => IbcEvent::TransferTimeout(Timeout {
+            height: Default::default(),
+            refund_receiver: extract_attribute(event, REFUND_RECEIVER_ATTRIBUTE_KEY)?.parse()?,
+            refund_denom: extract_attribute(event, REFUND_DENOM_ATTRIBUTE_KEY)?.to_string(),
+            refund_amount: extract_attribute(event, REFUND_AMOUNT_ATTRIBUTE_KEY)?.parse()?,
+        }),
+        _ => return Ok(None),
+    };
+
+    Ok(Some(ibc_event))
+}
+
+/// The event of a received packet only tells whether the tokens were received, while that of a
+/// processed acknowledgement carries the acknowledgement instead.
+fn extract_packet_from_tx(event: &AbciEvent) -> Result<FungibleTokenPacket, BoxError> {
+    let acknowledgement = optional_attribute(event, ACK_ATTRIBUTE_KEY)
+        .map(|ack| Acknowledgement::from_bytes(ack.as_bytes()))
+        .transpose()?;
+    let success = match &acknowledgement {
+        Some(ack) => ack.is_success(),
+        None => extract_attribute(event, SUCCESS_ATTRIBUTE_KEY)?.parse()?,
+    };
+    Ok(FungibleTokenPacket {
+        height: Default::default(),
+        receiver: extract_attribute(event, RECEIVER_ATTRIBUTE_KEY)?.parse()?,
+        denom: extract_attribute(event, DENOM_ATTRIBUTE_KEY)?.to_string(),
+        amount: extract_attribute(event, AMOUNT_ATTRIBUTE_KEY)?.parse()?,
+        success,
+        acknowledgement,
+    })
+}
+
+/// Emitted when tokens are sent to another chain, i.e., upon processing a `MsgTransfer`. The
+/// tokens and the packet carrying them are in the accompanying `send_packet` event.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Transfer {
+    pub height: Height,
+    pub sender: Signer,
+    pub receiver: Signer,
+}
+
+impl Transfer {
+    pub fn height(&self) -> Height {
+        self.height
+    }
+    pub fn set_height(&mut self, height: Height) {
+        self.height = height;
+    }
+}
+
+impl From<Transfer> for IbcEvent {
+    fn from(v: Transfer) -> Self {
+        IbcEvent::Transfer(v)
+    }
+}
+
+impl From<Transfer> for AbciEvent {
+    fn from(v: Transfer) -> Self {
+        AbciEvent {
+            type_str: TRANSFER_EVENT_TYPE.to_string(),
+            attributes: vec![
+                tag(SENDER_ATTRIBUTE_KEY, v.sender),
+                tag(RECEIVER_ATTRIBUTE_KEY, v.receiver),
+            ],
+        }
+    }
+}
+
+impl std::fmt::Display for Transfer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(
+            f,
+            "Transfer - h:{}, {} -> {}",
+            self.height, self.sender, self.receiver
+        )
+    }
+}
+
+/// Emitted when a fungible token packet is received, and when its acknowledgement is processed.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct FungibleTokenPacket {
+    pub height: Height,
+    pub receiver: Signer,
+    /// The full denomination path of the tokens, as carried by the packet.
+    pub denom: String,
+    pub amount: u64,
+    /// Whether the tokens were received.
+    pub success: bool,
+    /// The acknowledgement, if this is the event of a processed acknowledgement.
+    pub acknowledgement: Option<Acknowledgement>,
+}
+
+impl FungibleTokenPacket {
+    pub fn height(&self) -> Height {
+        self.height
+    }
+    pub fn set_height(&mut self, height: Height) {
+        self.height = height;
+    }
+}
+
+impl From<FungibleTokenPacket> for IbcEvent {
+    fn from(v: FungibleTokenPacket) -> Self {
+        IbcEvent::FungibleTokenPacket(v)
+    }
+}
+
+impl From<FungibleTokenPacket> for AbciEvent {
+    fn from(v: FungibleTokenPacket) -> Self {
+        let mut attributes = vec![
+            tag(MODULE_ATTRIBUTE_KEY, MODULE_NAME),
+            tag(RECEIVER_ATTRIBUTE_KEY, v.receiver),
+            tag(DENOM_ATTRIBUTE_KEY, v.denom),
+            tag(AMOUNT_ATTRIBUTE_KEY, v.amount),
+        ];
+        // `ibc-go` emits the result or error of a processed acknowledgement in a separate event.
+        attributes.push(match &v.acknowledgement {
+            Some(ack) => tag(ACK_ATTRIBUTE_KEY, String::from_utf8_lossy(&ack.to_bytes())),
+            None => tag(SUCCESS_ATTRIBUTE_KEY, v.success),
+        });
+
+        AbciEvent {
+            type_str: PACKET_EVENT_TYPE.to_string(),
+            attributes,
+        }
+    }
+}
+
+impl std::fmt::Display for FungibleTokenPacket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(
+            f,
+            "FungibleTokenPacket - h:{}, to {}: {}{}, success: {}",
+            self.height, self.receiver, self.amount, self.denom, self.success
+        )
+    }
+}
+
+/// Emitted when vouchers of a new denomination are minted, i.e., when their trace is recorded.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct DenominationTrace {
+    pub height: Height,
+    pub trace_hash: String,
+    /// The denomination of the vouchers, `ibc/{trace_hash}`.
+    pub denom: String,
+}
+
+impl DenominationTrace {
+    pub fn height(&self) -> Height {
+        self.height
+    }
+    pub fn set_height(&mut self, height: Height) {
+        self.height = height;
+    }
+}
+
+impl From<DenominationTrace> for IbcEvent {
+    fn from(v: DenominationTrace) -> Self {
+        IbcEvent::DenominationTrace(v)
+    }
+}
+
+impl From<DenominationTrace> for AbciEvent {
+    fn from(v: DenominationTrace) -> Self {
+        AbciEvent {
+            type_str: DENOM_TRACE_EVENT_TYPE.to_string(),
+            attributes: vec![
+                tag(TRACE_HASH_ATTRIBUTE_KEY, v.trace_hash),
+                tag(DENOM_ATTRIBUTE_KEY, v.denom),
+            ],
+        }
+    }
+}
+
+impl std::fmt::Display for DenominationTrace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(
+            f,
+            "DenominationTrace - h:{}, {} ({})",
+            self.height, self.denom, self.trace_hash
+        )
+    }
+}
+
+/// Emitted when the tokens of a timed out packet are refunded to their sender.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Timeout {
+    pub height: Height,
+    pub refund_receiver: Signer,
+    /// The full denomination path of the refunded tokens.
+    pub refund_denom: String,
+    pub refund_amount: u64,
+}
+
+impl Timeout {
+    pub fn height(&self) -> Height {
+        self.height
+    }
+    pub fn set_height(&mut self, height: Height) {
+        self.height = height;
+    }
+}
+
+impl From<Timeout> for IbcEvent {
+    fn from(v: Timeout) -> Self {
+        IbcEvent::TransferTimeout(v)
+    }
+}
+
+impl From<Timeout> for AbciEvent {
+    fn from(v: Timeout) -> Self {
+        AbciEvent {
+            type_str: TIMEOUT_EVENT_TYPE.to_string(),
+            attributes: vec![
+                tag(MODULE_ATTRIBUTE_KEY, MODULE_NAME),
+                tag(REFUND_RECEIVER_ATTRIBUTE_KEY, v.refund_receiver),
+                tag(REFUND_DENOM_ATTRIBUTE_KEY, v.refund_denom),
+                tag(REFUND_AMOUNT_ATTRIBUTE_KEY, v.refund_amount),
+            ],
+        }
+    }
+}
+
+impl std::fmt::Display for Timeout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(
+            f,
+            "Timeout - h:{}, refund to {}: {}{}",
+            self.height, self.refund_receiver, self.refund_amount, self.refund_denom
+        )
+    }
+}
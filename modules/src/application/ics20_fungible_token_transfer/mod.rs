@@ -1,5 +1,11 @@
 //! ICS 20: IBC Transfer implementation
+pub mod acknowledgement;
+pub mod coin;
 pub mod context;
+pub mod denom;
 pub mod error;
+pub mod events;
+pub mod module;
 pub mod msgs;
+pub mod packet;
 pub mod relay_application_logic;
@@ -0,0 +1,144 @@
+//! The ICS20 application module, i.e., the callbacks through which the IBC handlers hand the
+//! transfer channels and packets over to the token transfer application.
+
+use prost_types::Any;
+use tendermint_proto::Protobuf;
+
+use crate::application::ics20_fungible_token_transfer::context::Ics20Context;
+use crate::application::ics20_fungible_token_transfer::error::{Error, Kind};
+use crate::application::ics20_fungible_token_transfer::msgs::transfer::{self, MsgTransfer};
+use crate::application::ics20_fungible_token_transfer::relay_application_logic::on_acknowledgement_packet::on_acknowledgement_packet;
+use crate::application::ics20_fungible_token_transfer::relay_application_logic::on_recv_packet::on_recv_packet;
+use crate::application::ics20_fungible_token_transfer::relay_application_logic::on_timeout_packet::on_timeout_packet;
+use crate::application::ics20_fungible_token_transfer::relay_application_logic::send_transfer::send_transfer;
+use crate::handler::HandlerOutput;
+use crate::ics04_channel::channel::{Counterparty, Order};
+use crate::ics04_channel::error::{Error as Ics04Error, Kind as Ics04Kind};
+use crate::ics04_channel::packet::Packet;
+use crate::ics24_host::identifier::{ChannelId, ConnectionId, PortId};
+use crate::ics26_routing::module::Module;
+
+/// The version of the ICS20 protocol, which all transfer channels use.
+pub const VERSION: &str = "ics20-1";
+
+/// The identifier of the token transfer application, under which it owns the capabilities of its
+/// ports and channels.
+pub const MODULE_ID: &str = "transfer";
+
+/// The token transfer application, which can be bound to any port of a chain which implements
+/// `Ics20Context`, provided that the port is bound to `MODULE_ID`.
+#[derive(Clone, Debug, Default)]
+pub struct Ics20Module;
+
+fn validate_channel(order: Order, version: &str) -> Result<(), Error> {
+    if order != Order::Unordered {
+        return Err(Kind::InvalidChannelOrder(order).into());
+    }
+    if version != VERSION {
+        return Err(Kind::InvalidVersion(version.to_string()).into());
+    }
+    Ok(())
+}
+
+fn app_error(e: Error) -> Ics04Error {
+    Ics04Kind::AppModule.context(e).into()
+}
+
+impl<Ctx: Ics20Context> Module<Ctx> for Ics20Module {
+    /// Sends the tokens of a `MsgTransfer` in a fungible token packet.
+    fn deliver(&self, ctx: &mut Ctx, msg: Any) -> Result<HandlerOutput<()>, Ics04Error> {
+        if msg.type_url != transfer::TYPE_URL {
+            return Err(app_error(Kind::UnknownMessageTypeUrl(msg.type_url).into()));
+        }
+        let msg = MsgTransfer::decode_vec(&msg.value)
+            .map_err(|e| app_error(Kind::MalformedMessageBytes.context(e).into()))?;
+
+        let output = send_transfer(ctx, msg).map_err(app_error)?;
+        ctx.store_packet_result(output.result)?;
+
+        Ok(HandlerOutput::builder()
+            .with_log(output.log)
+            .with_events(output.events)
+            .with_result(()))
+    }
+
+    fn on_chan_open_init(
+        &self,
+        _ctx: &mut Ctx,
+        order: Order,
+        _connection_hops: &[ConnectionId],
+        _port_id: &PortId,
+        _channel_id: &ChannelId,
+        _counterparty: &Counterparty,
+        version: &str,
+    ) -> Result<String, Ics04Error> {
+        validate_channel(order, version).map_err(app_error)?;
+        Ok(version.to_string())
+    }
+
+    fn on_chan_open_try(
+        &self,
+        _ctx: &mut Ctx,
+        order: Order,
+        _connection_hops: &[ConnectionId],
+        _port_id: &PortId,
+        _channel_id: &ChannelId,
+        _counterparty: &Counterparty,
+        _version: &str,
+        counterparty_version: &str,
+    ) -> Result<String, Ics04Error> {
+        validate_channel(order, counterparty_version).map_err(app_error)?;
+        Ok(VERSION.to_string())
+    }
+
+    fn on_chan_open_ack(
+        &self,
+        _ctx: &mut Ctx,
+        _port_id: &PortId,
+        _channel_id: &ChannelId,
+        counterparty_version: &str,
+    ) -> Result<(), Ics04Error> {
+        if counterparty_version != VERSION {
+            return Err(app_error(
+                Kind::InvalidVersion(counterparty_version.to_string()).into(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn on_chan_close_init(
+        &self,
+        _ctx: &mut Ctx,
+        _port_id: &PortId,
+        _channel_id: &ChannelId,
+    ) -> Result<(), Ics04Error> {
+        // Closing a transfer channel would prevent any vouchers in circulation from returning.
+        Err(app_error(Kind::ChannelClosingNotAllowed.into()))
+    }
+
+    fn on_recv_packet(&self, ctx: &mut Ctx, packet: &Packet) -> HandlerOutput<Option<Vec<u8>>> {
+        let output = on_recv_packet(ctx, packet);
+
+        HandlerOutput::builder()
+            .with_log(output.log)
+            .with_events(output.events)
+            .with_result(Some(output.result.to_bytes()))
+    }
+
+    fn on_acknowledgement_packet(
+        &self,
+        ctx: &mut Ctx,
+        packet: &Packet,
+        acknowledgement: &[u8],
+    ) -> Result<HandlerOutput<()>, Ics04Error> {
+        on_acknowledgement_packet(ctx, packet, acknowledgement).map_err(app_error)
+    }
+
+    fn on_timeout_packet(
+        &self,
+        ctx: &mut Ctx,
+        packet: &Packet,
+    ) -> Result<HandlerOutput<()>, Ics04Error> {
+        on_timeout_packet(ctx, packet).map_err(app_error)
+    }
+}
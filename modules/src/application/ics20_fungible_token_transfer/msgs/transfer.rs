@@ -51,6 +51,32 @@ impl Msg for MsgTransfer {
     }
 }
 
+/// The token is mostly a small amount of a known denomination, so that transfers can succeed.
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for MsgTransfer {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let token = if u.ratio(1, 4)? {
+            u.arbitrary::<Option<(String, String)>>()?
+                .map(|(denom, amount)| ibc_proto::cosmos::base::v1beta1::Coin { denom, amount })
+        } else {
+            Some(ibc_proto::cosmos::base::v1beta1::Coin {
+                denom: u.choose(&["uatom", "stake"])?.to_string(),
+                amount: u.int_in_range(0..=100u64)?.to_string(),
+            })
+        };
+
+        Ok(MsgTransfer {
+            source_port: u.arbitrary()?,
+            source_channel: u.arbitrary()?,
+            token,
+            sender: u.arbitrary()?,
+            receiver: u.arbitrary()?,
+            timeout_height: u.arbitrary()?,
+            timeout_timestamp: u.arbitrary()?,
+        })
+    }
+}
+
 impl Protobuf<RawMsgTransfer> for MsgTransfer {}
 
 impl TryFrom<RawMsgTransfer> for MsgTransfer {
@@ -108,8 +134,14 @@ pub mod test_util {
     };
 
     use super::MsgTransfer;
+    use crate::application::ics20_fungible_token_transfer::coin::Coin;
     use crate::timestamp::Timestamp;
 
+    /// The token transferred by the dummy `MsgTransfer`.
+    pub fn get_dummy_coin() -> Coin {
+        Coin::new("uatom", 10)
+    }
+
     // Returns a dummy `RawMsgTransfer`, for testing only!
     pub fn get_dummy_msg_transfer(height: u64) -> MsgTransfer {
         let id = get_dummy_account_id();
@@ -117,7 +149,7 @@ pub mod test_util {
         MsgTransfer {
             source_port: PortId::default(),
             source_channel: ChannelId::default(),
-            token: None,
+            token: Some(get_dummy_coin().into()),
             sender: id.clone(),
             receiver: id,
             timeout_timestamp: Timestamp::from_nanoseconds(1).unwrap(),
@@ -0,0 +1,132 @@
+//! The payload of the packets exchanged by the ICS20 application. On the wire, the payload is the
+//! JSON encoding of `FungibleTokenPacketData`, with the keys sorted alphabetically and the amount
+//! encoded as a string, which matches the encoding used by `ibc-go`.
+
+use std::convert::TryFrom;
+
+use serde_derive::{Deserialize, Serialize};
+
+use ibc_proto::ibc::apps::transfer::v1::FungibleTokenPacketData as RawFungibleTokenPacketData;
+
+use crate::application::ics20_fungible_token_transfer::denom::DenomTrace;
+use crate::application::ics20_fungible_token_transfer::error::{Error, Kind};
+use crate::signer::Signer;
+
+/// The fields are declared in alphabetical order, so that the JSON encoding has sorted keys.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct FungibleTokenPacketData {
+    /// The amount of tokens to be transferred.
+    #[serde(with = "amount_as_string")]
+    pub amount: u64,
+    /// The full denomination path (i.e., including the trace) of the transferred tokens.
+    pub denom: String,
+    /// The recipient address on the destination chain.
+    pub receiver: Signer,
+    /// The sender address on the source chain.
+    pub sender: Signer,
+}
+
+impl FungibleTokenPacketData {
+    pub fn validate_basic(&self) -> Result<(), Error> {
+        if self.amount == 0 {
+            return Err(Kind::InvalidAmount(self.amount.to_string()).into());
+        }
+        if self.sender.as_str().trim().is_empty() {
+            return Err(Kind::InvalidPacketData
+                .context("sender address cannot be blank")
+                .into());
+        }
+        if self.receiver.as_str().trim().is_empty() {
+            return Err(Kind::InvalidPacketData
+                .context("receiver address cannot be blank")
+                .into());
+        }
+        DenomTrace::parse(&self.denom).validate_basic()
+    }
+
+    /// Returns the bytes which make up the data field of an ICS20 packet.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        // Serializing a struct of strings cannot fail.
+        serde_json::to_vec(self).unwrap()
+    }
+
+    /// Decodes and validates the data field of an ICS20 packet.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let data: Self =
+            serde_json::from_slice(bytes).map_err(|e| Kind::InvalidPacketData.context(e))?;
+        data.validate_basic()?;
+        Ok(data)
+    }
+}
+
+impl TryFrom<RawFungibleTokenPacketData> for FungibleTokenPacketData {
+    type Error = Error;
+
+    fn try_from(raw: RawFungibleTokenPacketData) -> Result<Self, Self::Error> {
+        let data = FungibleTokenPacketData {
+            amount: raw.amount,
+            denom: raw.denom,
+            receiver: raw.receiver.into(),
+            sender: raw.sender.into(),
+        };
+        data.validate_basic()?;
+        Ok(data)
+    }
+}
+
+impl From<FungibleTokenPacketData> for RawFungibleTokenPacketData {
+    fn from(data: FungibleTokenPacketData) -> Self {
+        RawFungibleTokenPacketData {
+            denom: data.denom,
+            amount: data.amount,
+            sender: data.sender.to_string(),
+            receiver: data.receiver.to_string(),
+        }
+    }
+}
+
+/// The proto3 JSON mapping encodes 64-bit integers as strings.
+mod amount_as_string {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(amount: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&amount.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_env_log::test;
+
+    use super::FungibleTokenPacketData;
+
+    #[test]
+    fn packet_data_json_encoding() {
+        let data = FungibleTokenPacketData {
+            amount: 100,
+            denom: "transfer/channel-0/uatom".to_string(),
+            receiver: "cosmos1receiver".to_string().into(),
+            sender: "cosmos1sender".to_string().into(),
+        };
+
+        let bytes = data.to_bytes();
+        assert_eq!(
+            String::from_utf8(bytes.clone()).unwrap(),
+            r#"{"amount":"100","denom":"transfer/channel-0/uatom","receiver":"cosmos1receiver","sender":"cosmos1sender"}"#
+        );
+        assert_eq!(FungibleTokenPacketData::from_bytes(&bytes).unwrap(), data);
+
+        // Zero amounts, numeric amounts and malformed payloads are all rejected.
+        let zero = br#"{"amount":"0","denom":"uatom","receiver":"a","sender":"b"}"#;
+        assert!(FungibleTokenPacketData::from_bytes(zero).is_err());
+        let numeric = br#"{"amount":100,"denom":"uatom","receiver":"a","sender":"b"}"#;
+        assert!(FungibleTokenPacketData::from_bytes(numeric).is_err());
+        assert!(FungibleTokenPacketData::from_bytes(&[0]).is_err());
+    }
+}
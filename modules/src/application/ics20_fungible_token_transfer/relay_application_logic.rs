@@ -1,3 +1,297 @@
 //! This module implements the processing logic for ICS20 (token transfer) message.
 
+pub mod on_acknowledgement_packet;
+pub mod on_recv_packet;
+pub mod on_timeout_packet;
 pub mod send_transfer;
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use test_env_log::test;
+
+    use crate::application::ics20_fungible_token_transfer::acknowledgement::ACK_ERR_STR;
+    use crate::application::ics20_fungible_token_transfer::coin::Coin;
+    use crate::application::ics20_fungible_token_transfer::context::BankKeeper;
+    use crate::application::ics20_fungible_token_transfer::denom::DenomTrace;
+    use crate::application::ics20_fungible_token_transfer::module::{
+        Ics20Module, MODULE_ID, VERSION,
+    };
+    use crate::application::ics20_fungible_token_transfer::msgs::transfer::MsgTransfer;
+    use crate::events::IbcEvent;
+    use crate::ics02_client::msgs::update_client::MsgUpdateAnyClient;
+    use crate::ics02_client::msgs::ClientMsg;
+    use crate::ics03_connection::connection::ConnectionEnd;
+    use crate::ics03_connection::connection::Counterparty as ConnectionCounterparty;
+    use crate::ics03_connection::connection::State as ConnectionState;
+    use crate::ics03_connection::version::get_compatible_versions;
+    use crate::ics04_channel::acknowledgement::Acknowledgement;
+    use crate::ics04_channel::channel::{ChannelEnd, Counterparty, Order, State};
+    use crate::ics04_channel::msgs::acknowledgement::MsgAcknowledgement;
+    use crate::ics04_channel::msgs::recv_packet::MsgRecvPacket;
+    use crate::ics04_channel::msgs::timeout::MsgTimeout;
+    use crate::ics04_channel::msgs::PacketMsg;
+    use crate::ics04_channel::packet::Packet;
+    use crate::ics05_port::capabilities::ModuleId;
+    use crate::ics24_host::identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId};
+    use crate::ics26_routing::handler::dispatch;
+    use crate::ics26_routing::msgs::Ics26Envelope;
+    use crate::mock::context::MockContext;
+    use crate::mock::header::MockHeader;
+    use crate::mock::host::HostType;
+    use crate::proofs::Proofs;
+    use crate::signer::Signer;
+    use crate::test_utils::{get_dummy_account_id, get_dummy_proof};
+    use crate::timestamp::{Timestamp, ZERO_DURATION};
+    use crate::Height;
+
+    /// The height of the (mock) clients of both chains, at which all proofs are produced.
+    const PROOF_HEIGHT: u64 = 5;
+
+    fn port_id() -> PortId {
+        PortId::from_str("transfer").unwrap()
+    }
+
+    /// Returns a chain having a transfer channel `transfer/channel-0` open towards its
+    /// counterparty, which is tracked by a mock client.
+    fn transfer_chain(name: &str) -> MockContext {
+        let channel_end = ChannelEnd::new(
+            State::Open,
+            Order::Unordered,
+            Counterparty::new(port_id(), Some(ChannelId::default())),
+            vec![ConnectionId::default()],
+            VERSION.to_string(),
+        );
+
+        let connection_end = ConnectionEnd::new(
+            ConnectionState::Open,
+            ClientId::default(),
+            ConnectionCounterparty::new(
+                ClientId::default(),
+                Some(ConnectionId::default()),
+                Default::default(),
+            ),
+            get_compatible_versions(),
+            ZERO_DURATION,
+        );
+
+        MockContext::new(
+            ChainId::new(name.to_string(), 0),
+            HostType::Mock,
+            5,
+            Height::new(0, 5),
+        )
+        .with_client(&ClientId::default(), Height::new(0, PROOF_HEIGHT))
+        .with_connection(ConnectionId::default(), connection_end)
+        .with_module(ModuleId::new(MODULE_ID), port_id(), Ics20Module)
+        .with_channel(port_id(), ChannelId::default(), channel_end)
+        .with_send_sequence(port_id(), ChannelId::default(), 1.into())
+    }
+
+    fn proofs(height: u64) -> Proofs {
+        Proofs::new(
+            get_dummy_proof().into(),
+            None,
+            None,
+            None,
+            Height::new(0, height),
+        )
+        .unwrap()
+    }
+
+    /// Submits a `MsgTransfer` on `ctx` and returns the packet that was sent.
+    fn transfer(
+        ctx: &mut MockContext,
+        sender: &Signer,
+        receiver: &Signer,
+        token: Coin,
+        timeout_height: u64,
+    ) -> Packet {
+        let msg = MsgTransfer {
+            source_port: port_id(),
+            source_channel: ChannelId::default(),
+            token: Some(token.into()),
+            sender: sender.clone(),
+            receiver: receiver.clone(),
+            timeout_height: Height::new(0, timeout_height),
+            timeout_timestamp: Timestamp::none(),
+        };
+
+        let output = dispatch(ctx, Ics26Envelope::Ics20Msg(msg)).unwrap();
+        output
+            .events
+            .into_iter()
+            .find_map(|event| match event {
+                IbcEvent::SendPacket(ev) => Some(ev.packet),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    /// Delivers `packet` to `ctx`, and returns the acknowledgement written by the receiver.
+    fn receive(ctx: &mut MockContext, packet: Packet) -> Vec<u8> {
+        let msg = MsgRecvPacket::new(packet, proofs(PROOF_HEIGHT), get_dummy_account_id());
+        let output = dispatch(
+            ctx,
+            Ics26Envelope::Ics4PacketMsg(PacketMsg::RecvPacket(msg)),
+        )
+        .unwrap();
+        output
+            .events
+            .into_iter()
+            .find_map(|event| match event {
+                IbcEvent::WriteAcknowledgement(ev) => Some(ev.ack),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    /// Delivers the acknowledgement `ack` of `packet` back to `ctx`, the sender of the packet.
+    fn acknowledge(ctx: &mut MockContext, packet: Packet, ack: Vec<u8>) -> bool {
+        let msg =
+            MsgAcknowledgement::new(packet, ack, proofs(PROOF_HEIGHT), get_dummy_account_id());
+        dispatch(ctx, Ics26Envelope::Ics4PacketMsg(PacketMsg::AckPacket(msg))).is_ok()
+    }
+
+    #[test]
+    fn transfer_round_trip() {
+        let (alice, bob) = (Signer::new("alice"), Signer::new("bob"));
+        let mut chain_a = transfer_chain("chain_a").with_balance(&alice, Coin::new("uatom", 100));
+        let mut chain_b = transfer_chain("chain_b");
+
+        let escrow_a = chain_a.get_escrow_address(&port_id(), &ChannelId::default());
+        let voucher = DenomTrace::new("transfer/channel-0", "uatom");
+
+        // The native tokens are escrowed on chain A, and vouchers are minted on chain B.
+        let packet = transfer(&mut chain_a, &alice, &bob, Coin::new("uatom", 30), 100);
+        assert_eq!(chain_a.balance(&alice, "uatom"), 70);
+        assert_eq!(chain_a.balance(&escrow_a, "uatom"), 30);
+
+        let ack = receive(&mut chain_b, packet.clone());
+        assert_eq!(
+            Acknowledgement::from_bytes(&ack).unwrap(),
+            Acknowledgement::success()
+        );
+        assert_eq!(chain_b.balance(&bob, &voucher.ibc_denom()), 30);
+
+        assert!(acknowledge(&mut chain_a, packet.clone(), ack.clone()));
+        assert_eq!(chain_a.balance(&alice, "uatom"), 70);
+        assert_eq!(chain_a.balance(&escrow_a, "uatom"), 30);
+
+        // A packet cannot be acknowledged twice.
+        assert!(!acknowledge(&mut chain_a, packet, ack));
+
+        // Sending the vouchers back burns them on chain B, and unescrows the tokens on chain A.
+        let packet = transfer(
+            &mut chain_b,
+            &bob,
+            &alice,
+            Coin::new(voucher.ibc_denom(), 10),
+            100,
+        );
+        assert_eq!(chain_b.balance(&bob, &voucher.ibc_denom()), 20);
+
+        let ack = receive(&mut chain_a, packet.clone());
+        assert_eq!(
+            Acknowledgement::from_bytes(&ack).unwrap(),
+            Acknowledgement::success()
+        );
+        assert_eq!(chain_a.balance(&alice, "uatom"), 80);
+        assert_eq!(chain_a.balance(&escrow_a, "uatom"), 20);
+
+        assert!(acknowledge(&mut chain_b, packet, ack));
+        assert_eq!(chain_b.balance(&bob, &voucher.ibc_denom()), 20);
+    }
+
+    #[test]
+    fn error_acknowledgement_refunds_sender() {
+        let (alice, bob) = (Signer::new("alice"), Signer::new("bob"));
+
+        // Alice holds vouchers of tokens which supposedly originate from chain B, but chain B
+        // did not escrow any such tokens.
+        let voucher = DenomTrace::new("transfer/channel-0", "ustake");
+        let mut chain_a = transfer_chain("chain_a")
+            .with_denom_trace(voucher.clone())
+            .with_balance(&alice, Coin::new(voucher.ibc_denom(), 50));
+        let mut chain_b = transfer_chain("chain_b");
+
+        // The vouchers are burned when sent back to their source chain.
+        let packet = transfer(
+            &mut chain_a,
+            &alice,
+            &bob,
+            Coin::new(voucher.ibc_denom(), 50),
+            100,
+        );
+        assert_eq!(chain_a.balance(&alice, &voucher.ibc_denom()), 0);
+
+        // Chain B fails to unescrow the tokens, hence it acknowledges the packet with an error,
+        // which does not reveal the cause of the failure.
+        let ack = receive(&mut chain_b, packet.clone());
+        assert_eq!(
+            Acknowledgement::from_bytes(&ack).unwrap(),
+            Acknowledgement::error(ACK_ERR_STR)
+        );
+        assert_eq!(chain_b.balance(&bob, "ustake"), 0);
+
+        // Upon the error acknowledgement, the vouchers are minted back to Alice.
+        assert!(acknowledge(&mut chain_a, packet, ack));
+        assert_eq!(chain_a.balance(&alice, &voucher.ibc_denom()), 50);
+    }
+
+    #[test]
+    fn timeout_refunds_sender() {
+        let (alice, bob) = (Signer::new("alice"), Signer::new("bob"));
+        let mut chain_a = transfer_chain("chain_a").with_balance(&alice, Coin::new("uatom", 100));
+
+        let timeout_height = PROOF_HEIGHT + 5;
+        let packet = transfer(
+            &mut chain_a,
+            &alice,
+            &bob,
+            Coin::new("uatom", 30),
+            timeout_height,
+        );
+        assert_eq!(chain_a.balance(&alice, "uatom"), 70);
+
+        // Chain A learns that chain B went past the timeout height without receiving the packet.
+        let proof_height = Height::new(0, timeout_height + 1);
+        let update = MsgUpdateAnyClient {
+            client_id: ClientId::default(),
+            header: MockHeader::new(proof_height).into(),
+            signer: get_dummy_account_id(),
+        };
+        dispatch(
+            &mut chain_a,
+            Ics26Envelope::Ics2Msg(ClientMsg::UpdateClient(update)),
+        )
+        .unwrap();
+
+        let msg = MsgTimeout::new(
+            packet,
+            1.into(),
+            proofs(proof_height.revision_height),
+            get_dummy_account_id(),
+        );
+        let output = dispatch(
+            &mut chain_a,
+            Ics26Envelope::Ics4PacketMsg(PacketMsg::ToPacket(msg.clone())),
+        )
+        .unwrap();
+        assert!(output
+            .events
+            .iter()
+            .any(|event| matches!(event, IbcEvent::TransferTimeout(_))));
+
+        let escrow_a = chain_a.get_escrow_address(&port_id(), &ChannelId::default());
+        assert_eq!(chain_a.balance(&alice, "uatom"), 100);
+        assert_eq!(chain_a.balance(&escrow_a, "uatom"), 0);
+
+        // The packet commitment is gone, so the refund cannot be claimed twice.
+        assert!(dispatch(
+            &mut chain_a,
+            Ics26Envelope::Ics4PacketMsg(PacketMsg::ToPacket(msg))
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,46 @@
+use crate::application::ics20_fungible_token_transfer::context::Ics20Context;
+use crate::application::ics20_fungible_token_transfer::error::{Error, Kind};
+use crate::application::ics20_fungible_token_transfer::events::FungibleTokenPacket;
+use crate::application::ics20_fungible_token_transfer::packet::FungibleTokenPacketData;
+use crate::application::ics20_fungible_token_transfer::relay_application_logic::on_timeout_packet::refund_packet_token;
+use crate::handler::HandlerOutput;
+use crate::ics04_channel::acknowledgement::Acknowledgement;
+use crate::ics04_channel::packet::Packet;
+
+/// Processes the acknowledgement of a fungible token packet sent by this chain. The sender is
+/// refunded if the receiving chain failed to credit the receiver.
+pub(crate) fn on_acknowledgement_packet<Ctx>(
+    ctx: &mut Ctx,
+    packet: &Packet,
+    acknowledgement: &[u8],
+) -> Result<HandlerOutput<()>, Error>
+where
+    Ctx: Ics20Context,
+{
+    let mut output = HandlerOutput::builder();
+
+    let data = FungibleTokenPacketData::from_bytes(&packet.data)?;
+    let ack = Acknowledgement::from_bytes(acknowledgement)
+        .map_err(|e| Kind::InvalidAcknowledgement.context(e))?;
+
+    if ack.is_success() {
+        output.log("success: fungible tokens transferred");
+    } else {
+        refund_packet_token(ctx, packet, &data)?;
+        output.log("success: fungible tokens refunded");
+    }
+
+    output.emit(
+        FungibleTokenPacket {
+            height: ctx.host_height(),
+            receiver: data.receiver,
+            denom: data.denom,
+            amount: data.amount,
+            success: ack.is_success(),
+            acknowledgement: Some(ack),
+        }
+        .into(),
+    );
+
+    Ok(output.with_result(()))
+}
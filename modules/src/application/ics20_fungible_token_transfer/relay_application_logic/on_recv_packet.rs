@@ -0,0 +1,100 @@
+use crate::application::ics20_fungible_token_transfer::acknowledgement::ACK_ERR_STR;
+use crate::application::ics20_fungible_token_transfer::coin::Coin;
+use crate::application::ics20_fungible_token_transfer::context::Ics20Context;
+use crate::application::ics20_fungible_token_transfer::denom::{
+    get_denom_prefix, receiver_chain_is_source, DenomTrace,
+};
+use crate::application::ics20_fungible_token_transfer::error::Error;
+use crate::application::ics20_fungible_token_transfer::events::{
+    DenominationTrace, FungibleTokenPacket,
+};
+use crate::application::ics20_fungible_token_transfer::packet::FungibleTokenPacketData;
+use crate::handler::{HandlerOutput, HandlerOutputBuilder};
+use crate::ics04_channel::acknowledgement::Acknowledgement;
+use crate::ics04_channel::packet::Packet;
+
+/// Credits the receiver of a fungible token packet. This never fails: if the tokens cannot be
+/// received, the resulting acknowledgement is an error acknowledgement, which lets the sending
+/// chain refund the sender.
+pub(crate) fn on_recv_packet<Ctx>(ctx: &mut Ctx, packet: &Packet) -> HandlerOutput<Acknowledgement>
+where
+    Ctx: Ics20Context,
+{
+    let mut output = HandlerOutput::builder();
+
+    let ack = match receive_tokens(ctx, packet, &mut output) {
+        Ok(()) => {
+            output.log("success: fungible tokens received");
+            Acknowledgement::success()
+        }
+        Err(e) => {
+            output.log(format!("failure: fungible tokens not received: {}", e));
+            Acknowledgement::error(ACK_ERR_STR)
+        }
+    };
+
+    if let Ok(data) = FungibleTokenPacketData::from_bytes(&packet.data) {
+        output.emit(
+            FungibleTokenPacket {
+                height: ctx.host_height(),
+                receiver: data.receiver,
+                denom: data.denom,
+                amount: data.amount,
+                success: ack.is_success(),
+                acknowledgement: None,
+            }
+            .into(),
+        );
+    }
+
+    output.with_result(ack)
+}
+
+fn receive_tokens<Ctx>(
+    ctx: &mut Ctx,
+    packet: &Packet,
+    output: &mut HandlerOutputBuilder<Acknowledgement>,
+) -> Result<(), Error>
+where
+    Ctx: Ics20Context,
+{
+    let data = FungibleTokenPacketData::from_bytes(&packet.data)?;
+
+    if receiver_chain_is_source(&packet.source_port, &packet.source_channel, &data.denom) {
+        // The tokens return to this chain, which escrowed them when they were first sent. Their
+        // denomination on this chain is the packet denomination without the prefix of the hop
+        // they last took.
+        let prefix = get_denom_prefix(&packet.source_port, &packet.source_channel);
+        let denom = DenomTrace::parse(&data.denom[prefix.len()..]).ibc_denom();
+
+        let escrow_address =
+            ctx.get_escrow_address(&packet.destination_port, &packet.destination_channel);
+        ctx.send_coins(
+            &escrow_address,
+            &data.receiver,
+            &Coin::new(denom, data.amount),
+        )
+    } else {
+        // The tokens come from their source chain (or from another sink chain), so vouchers are
+        // minted for them, traced over the channel they were received on.
+        let trace = DenomTrace::parse(&data.denom)
+            .add_prefix(&packet.destination_port, &packet.destination_channel);
+        let voucher = Coin::new(trace.ibc_denom(), data.amount);
+
+        // The trace is recorded before the vouchers are minted, so that their denomination is
+        // always known to the chain.
+        if ctx.get_denom_trace(&trace.hash()).is_none() {
+            output.emit(
+                DenominationTrace {
+                    height: ctx.host_height(),
+                    trace_hash: trace.hash(),
+                    denom: voucher.denom.clone(),
+                }
+                .into(),
+            );
+            ctx.store_denom_trace(trace)?;
+        }
+
+        ctx.mint_coins(&data.receiver, &voucher)
+    }
+}
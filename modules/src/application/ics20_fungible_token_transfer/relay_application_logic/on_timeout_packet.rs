@@ -0,0 +1,58 @@
+use crate::application::ics20_fungible_token_transfer::coin::Coin;
+use crate::application::ics20_fungible_token_transfer::context::Ics20Context;
+use crate::application::ics20_fungible_token_transfer::denom::{
+    sender_chain_is_source, DenomTrace,
+};
+use crate::application::ics20_fungible_token_transfer::error::Error;
+use crate::application::ics20_fungible_token_transfer::events::Timeout;
+use crate::application::ics20_fungible_token_transfer::packet::FungibleTokenPacketData;
+use crate::handler::HandlerOutput;
+use crate::ics04_channel::packet::Packet;
+
+/// Refunds the sender of a fungible token packet which timed out.
+pub(crate) fn on_timeout_packet<Ctx>(
+    ctx: &mut Ctx,
+    packet: &Packet,
+) -> Result<HandlerOutput<()>, Error>
+where
+    Ctx: Ics20Context,
+{
+    let mut output = HandlerOutput::builder();
+
+    let data = FungibleTokenPacketData::from_bytes(&packet.data)?;
+    refund_packet_token(ctx, packet, &data)?;
+
+    output.log("success: fungible tokens refunded");
+
+    output.emit(
+        Timeout {
+            height: ctx.host_height(),
+            refund_receiver: data.sender,
+            refund_denom: data.denom,
+            refund_amount: data.amount,
+        }
+        .into(),
+    );
+
+    Ok(output.with_result(()))
+}
+
+/// Reverts the effects which sending `packet` had on the balance of its sender, i.e., either
+/// unescrows or re-mints the tokens, following the decision taken by `send_transfer`.
+pub(crate) fn refund_packet_token<Ctx>(
+    ctx: &mut Ctx,
+    packet: &Packet,
+    data: &FungibleTokenPacketData,
+) -> Result<(), Error>
+where
+    Ctx: Ics20Context,
+{
+    let token = Coin::new(DenomTrace::parse(&data.denom).ibc_denom(), data.amount);
+
+    if sender_chain_is_source(&packet.source_port, &packet.source_channel, &data.denom) {
+        let escrow_address = ctx.get_escrow_address(&packet.source_port, &packet.source_channel);
+        ctx.send_coins(&escrow_address, &data.sender, &token)
+    } else {
+        ctx.mint_coins(&data.sender, &token)
+    }
+}
@@ -1,18 +1,31 @@
+use std::convert::TryFrom;
+
+use crate::application::ics20_fungible_token_transfer::coin::Coin;
 use crate::application::ics20_fungible_token_transfer::context::Ics20Context;
+use crate::application::ics20_fungible_token_transfer::denom::{
+    sender_chain_is_source, DENOM_PREFIX,
+};
 use crate::application::ics20_fungible_token_transfer::error::{Error, Kind};
+use crate::application::ics20_fungible_token_transfer::events::Transfer;
+use crate::application::ics20_fungible_token_transfer::module::MODULE_ID;
 use crate::application::ics20_fungible_token_transfer::msgs::transfer::MsgTransfer;
+use crate::application::ics20_fungible_token_transfer::packet::FungibleTokenPacketData;
+use crate::events::IbcEvent;
 use crate::handler::HandlerOutput;
 use crate::ics04_channel::handler::send_packet::send_packet;
 use crate::ics04_channel::packet::Packet;
 use crate::ics04_channel::packet::PacketResult;
+use crate::ics05_port::capabilities::{CapabilityName, ModuleId};
 
 pub(crate) fn send_transfer<Ctx>(
-    ctx: &Ctx,
+    ctx: &mut Ctx,
     msg: MsgTransfer,
 ) -> Result<HandlerOutput<PacketResult>, Error>
 where
     Ctx: Ics20Context,
 {
+    let token = Coin::try_from(msg.token.clone().ok_or(Kind::MissingToken)?)?;
+
     let source_channel_end = ctx
         .channel_end(&(msg.source_port.clone(), msg.source_channel.clone()))
         .ok_or_else(|| {
@@ -34,22 +47,243 @@ where
             Kind::SequenceSendNotFound(msg.source_port.clone(), msg.source_channel.clone())
         })?;
 
-    //TODO: Application LOGIC.
+    // Vouchers are held under the `ibc/{hash}` denomination, but the packet carries the full
+    // denomination path, which the receiving chain needs to determine the source of the token.
+    let full_denom = match token.denom.strip_prefix(&format!("{}/", DENOM_PREFIX)) {
+        Some(hash) => ctx
+            .get_denom_trace(hash)
+            .ok_or_else(|| Kind::DenomTraceNotFound(hash.to_string()))?
+            .full_path(),
+        None => token.denom.clone(),
+    };
+
+    let packet_data = FungibleTokenPacketData {
+        amount: token.amount,
+        denom: full_denom.clone(),
+        receiver: msg.receiver.clone(),
+        sender: msg.sender.clone(),
+    };
+    packet_data.validate_basic()?;
 
     let packet = Packet {
         sequence,
-        source_port: msg.source_port,
-        source_channel: msg.source_channel,
+        source_port: msg.source_port.clone(),
+        source_channel: msg.source_channel.clone(),
         destination_port,
         destination_channel: destination_channel.clone(),
-        data: vec![0],
+        data: packet_data.to_bytes(),
         timeout_height: msg.timeout_height,
         timeout_timestamp: msg.timeout_timestamp,
     };
 
+    // The tokens are sent on behalf of the transfer module, which must own the source channel.
+    let channel_cap = ctx
+        .get_capability(
+            &ModuleId::new(MODULE_ID),
+            &CapabilityName::channel(&msg.source_port, &msg.source_channel),
+        )
+        .ok_or_else(|| {
+            Kind::ChannelCapabilityNotFound(msg.source_port.clone(), msg.source_channel.clone())
+        })?;
+
     let handler_output =
-        send_packet(ctx, packet).map_err(|e| Kind::HandlerRaisedError.context(e))?;
+        send_packet(ctx, &channel_cap, packet).map_err(|e| Kind::HandlerRaisedError.context(e))?;
+
+    if sender_chain_is_source(&msg.source_port, &msg.source_channel, &full_denom) {
+        // The tokens are native to this chain (or came from a third chain), so they are escrowed
+        // until they return, or the packet times out.
+        let escrow_address = ctx.get_escrow_address(&msg.source_port, &msg.source_channel);
+        ctx.send_coins(&msg.sender, &escrow_address, &token)?;
+    } else {
+        // The tokens are vouchers of tokens native to the receiving chain, which are destroyed
+        // here and unescrowed on the receiving chain.
+        ctx.burn_coins(&msg.sender, &token)?;
+    }
+
+    let mut events = handler_output.events;
+    events.push(IbcEvent::from(Transfer {
+        height: ctx.host_height(),
+        sender: msg.sender,
+        receiver: msg.receiver,
+    }));
+
+    Ok(HandlerOutput::builder()
+        .with_log(handler_output.log)
+        .with_events(events)
+        .with_result(handler_output.result))
+}
+
+#[cfg(test)]
+mod tests {
+    use test_env_log::test;
+
+    use crate::application::ics20_fungible_token_transfer::coin::Coin;
+    use crate::application::ics20_fungible_token_transfer::context::BankKeeper;
+    use crate::application::ics20_fungible_token_transfer::denom::DenomTrace;
+    use crate::application::ics20_fungible_token_transfer::error::Kind;
+    use crate::application::ics20_fungible_token_transfer::module::{Ics20Module, MODULE_ID};
+    use crate::application::ics20_fungible_token_transfer::msgs::transfer::test_util::get_dummy_msg_transfer;
+    use crate::application::ics20_fungible_token_transfer::packet::FungibleTokenPacketData;
+    use crate::events::IbcEvent;
+    use crate::ics03_connection::connection::ConnectionEnd;
+    use crate::ics03_connection::connection::Counterparty as ConnectionCounterparty;
+    use crate::ics03_connection::connection::State as ConnectionState;
+    use crate::ics03_connection::version::get_compatible_versions;
+    use crate::ics04_channel::channel::{ChannelEnd, Counterparty, Order, State};
+    use crate::ics04_channel::packet::PacketResult;
+    use crate::ics05_port::capabilities::ModuleId;
+    use crate::ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId};
+    use crate::mock::context::MockContext;
+    use crate::test_utils::get_dummy_account_id;
+    use crate::timestamp::ZERO_DURATION;
+    use crate::Height;
+
+    use super::send_transfer;
+
+    #[test]
+    fn send_transfer_processing() {
+        struct Test {
+            name: String,
+            ctx: MockContext,
+            token: Option<Coin>,
+            // The expected denomination in the packet, and the balances of the sender and of the
+            // escrow account after the transfer.
+            want: Option<(String, u64, u64)>,
+            error_kind: Option<Kind>,
+        }
+
+        let sender = get_dummy_account_id();
+        let (port_id, channel_id) = (PortId::default(), ChannelId::default());
+
+        let channel_end = ChannelEnd::new(
+            State::Open,
+            Order::default(),
+            Counterparty::new(PortId::default(), Some(ChannelId::default())),
+            vec![ConnectionId::default()],
+            "ics20-1".to_string(),
+        );
+
+        let connection_end = ConnectionEnd::new(
+            ConnectionState::Open,
+            ClientId::default(),
+            ConnectionCounterparty::new(
+                ClientId::default(),
+                Some(ConnectionId::default()),
+                Default::default(),
+            ),
+            get_compatible_versions(),
+            ZERO_DURATION,
+        );
+
+        let base_context = MockContext::default()
+            .with_client(&ClientId::default(), Height::new(0, 5))
+            .with_connection(ConnectionId::default(), connection_end);
+        let context = base_context
+            .clone()
+            .with_module(ModuleId::new(MODULE_ID), port_id.clone(), Ics20Module)
+            .with_channel(port_id.clone(), channel_id.clone(), channel_end.clone())
+            .with_send_sequence(port_id.clone(), channel_id.clone(), 1.into());
+
+        // The port, hence the channel, belongs to a module other than the transfer module.
+        let foreign_context = base_context
+            .with_port_capability(port_id.clone())
+            .with_channel(port_id.clone(), channel_id.clone(), channel_end)
+            .with_send_sequence(port_id.clone(), channel_id.clone(), 1.into())
+            .with_balance(&sender, Coin::new("uatom", 100));
+
+        // Vouchers minted by this chain for tokens it received over the transfer channel.
+        let voucher = DenomTrace::new(format!("{}/{}", port_id, channel_id), "uatom");
+
+        let tests: Vec<Test> = vec![
+            Test {
+                name: "Native tokens are escrowed".to_string(),
+                ctx: context
+                    .clone()
+                    .with_balance(&sender, Coin::new("uatom", 100)),
+                token: Some(Coin::new("uatom", 10)),
+                want: Some(("uatom".to_string(), 90, 10)),
+                error_kind: None,
+            },
+            Test {
+                name: "Vouchers of the receiver chain tokens are burned".to_string(),
+                ctx: context
+                    .clone()
+                    .with_denom_trace(voucher.clone())
+                    .with_balance(&sender, Coin::new(voucher.ibc_denom(), 100)),
+                token: Some(Coin::new(voucher.ibc_denom(), 10)),
+                want: Some((voucher.full_path(), 90, 0)),
+                error_kind: None,
+            },
+            Test {
+                name: "Processing fails because the denomination trace is unknown".to_string(),
+                ctx: context
+                    .clone()
+                    .with_balance(&sender, Coin::new(voucher.ibc_denom(), 100)),
+                token: Some(Coin::new(voucher.ibc_denom(), 10)),
+                want: None,
+                error_kind: Some(Kind::DenomTraceNotFound(voucher.hash())),
+            },
+            Test {
+                name: "Processing fails because the sender has insufficient funds".to_string(),
+                ctx: context.clone().with_balance(&sender, Coin::new("uatom", 5)),
+                token: Some(Coin::new("uatom", 10)),
+                want: None,
+                error_kind: Some(Kind::InsufficientFunds(sender.clone(), "uatom".to_string())),
+            },
+            Test {
+                name: "Processing fails because the transfer module does not own the channel"
+                    .to_string(),
+                ctx: foreign_context,
+                token: Some(Coin::new("uatom", 10)),
+                want: None,
+                error_kind: Some(Kind::ChannelCapabilityNotFound(
+                    port_id.clone(),
+                    channel_id.clone(),
+                )),
+            },
+            Test {
+                name: "Processing fails because no token is specified".to_string(),
+                ctx: context,
+                token: None,
+                want: None,
+                error_kind: Some(Kind::MissingToken),
+            },
+        ];
+
+        for mut test in tests {
+            let mut msg = get_dummy_msg_transfer(10);
+            msg.token = test.token.clone().map(Into::into);
+
+            let res = send_transfer(&mut test.ctx, msg);
+            match (res, test.want) {
+                (Ok(output), Some((packet_denom, sender_balance, escrow_balance))) => {
+                    let denom = test.token.unwrap().denom;
+                    let escrow_address = test.ctx.get_escrow_address(&port_id, &channel_id);
+                    assert_eq!(test.ctx.balance(&sender, &denom), sender_balance);
+                    assert_eq!(test.ctx.balance(&escrow_address, &denom), escrow_balance);
+
+                    // The packet carries the full denomination path of the tokens.
+                    let data = match output.result {
+                        PacketResult::Send(result) => {
+                            FungibleTokenPacketData::from_bytes(&result.data).unwrap()
+                        }
+                        result => panic!("unexpected packet result: {:?}", result),
+                    };
+                    assert_eq!(data.denom, packet_denom);
 
-    //TODO:  add event/atributes and writes to the store issued by the application logic for packet sending.
-    Ok(handler_output)
+                    assert!(matches!(output.events.last(), Some(&IbcEvent::Transfer(_))));
+                }
+                (Err(e), None) => assert_eq!(
+                    Some(e.kind()),
+                    test.error_kind.as_ref(),
+                    "send_transfer: unexpected error for test: {}",
+                    test.name
+                ),
+                (res, _) => panic!(
+                    "send_transfer: unexpected result for test: {}\n{:?}",
+                    test.name, res
+                ),
+            }
+        }
+    }
 }
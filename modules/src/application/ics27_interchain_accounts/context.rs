@@ -0,0 +1,74 @@
+use prost_types::Any;
+
+use crate::application::ics27_interchain_accounts::error::Error;
+use crate::ics04_channel::context::{ChannelKeeper, ChannelReader};
+use crate::ics05_port::context::PortReader;
+use crate::ics24_host::identifier::{ChannelId, ConnectionId, PortId};
+use crate::signer::Signer;
+
+/// A context supplying all the necessary read-only dependencies for managing interchain accounts.
+///
+/// Accounts and channels are indexed by the connection they are reached over and by the port of
+/// the controller, i.e., on the host chain, by the counterparty port of the channel.
+pub trait InterchainAccountReader {
+    /// Returns the address of the interchain account registered for `port_id` over
+    /// `connection_id`.
+    fn get_interchain_account(
+        &self,
+        connection_id: &ConnectionId,
+        port_id: &PortId,
+    ) -> Option<Signer>;
+
+    /// Returns the channel over which the controller port `port_id` sends its transactions.
+    fn get_active_channel(
+        &self,
+        connection_id: &ConnectionId,
+        port_id: &PortId,
+    ) -> Option<ChannelId>;
+}
+
+/// A context supplying all the necessary write-only dependencies for managing interchain accounts.
+pub trait InterchainAccountKeeper {
+    fn store_interchain_account(
+        &mut self,
+        connection_id: ConnectionId,
+        port_id: PortId,
+        address: Signer,
+    ) -> Result<(), Error>;
+
+    fn store_active_channel(
+        &mut self,
+        connection_id: ConnectionId,
+        port_id: PortId,
+        channel_id: ChannelId,
+    ) -> Result<(), Error>;
+}
+
+/// The interface through which the host chain executes the messages of interchain accounts.
+pub trait MessageExecutor {
+    /// Returns true if interchain accounts may execute messages of type `type_url`.
+    fn is_allowed(&self, type_url: &str) -> bool;
+
+    /// Executes `message` on behalf of `account`. The executor must check that `account` is the
+    /// signer of the message.
+    fn execute(&mut self, account: &Signer, message: &Any) -> Result<(), Error>;
+}
+
+/// Captures all the dependencies which the controller module of interchain accounts requires.
+pub trait Ics27ControllerContext:
+    InterchainAccountReader
+    + InterchainAccountKeeper
+    + ChannelReader
+    + ChannelKeeper
+    + PortReader
+    + Clone
+{
+}
+
+/// Captures all the dependencies which the host module of interchain accounts requires. The
+/// context is cloned before executing the messages of a packet, so that they are executed
+/// atomically.
+pub trait Ics27HostContext:
+    InterchainAccountReader + InterchainAccountKeeper + MessageExecutor + ChannelReader + Clone
+{
+}
@@ -0,0 +1,257 @@
+//! The controller module of interchain accounts, which registers accounts on a host chain and then
+//! sends them the transactions to execute. Each owner of an interchain account has a controller
+//! port of its own, `icacontroller-{owner}`, which the controller module must be bound to.
+
+use std::str::FromStr;
+
+use crate::application::ics27_interchain_accounts::context::Ics27ControllerContext;
+use crate::application::ics27_interchain_accounts::error::{Error, Kind};
+use crate::application::ics27_interchain_accounts::packet::InterchainAccountPacketData;
+use crate::application::ics27_interchain_accounts::version::{parse_account_version, VERSION};
+use crate::handler::HandlerOutput;
+use crate::ics02_client::height::Height;
+use crate::ics04_channel::acknowledgement::Acknowledgement;
+use crate::ics04_channel::channel::{Counterparty, Order};
+use crate::ics04_channel::error::{Error as Ics04Error, Kind as Ics04Kind};
+use crate::ics04_channel::handler::send_packet::send_packet;
+use crate::ics04_channel::packet::Packet;
+use crate::ics05_port::capabilities::{CapabilityName, ModuleId};
+use crate::ics24_host::identifier::{ChannelId, ConnectionId, PortId};
+use crate::ics26_routing::module::Module;
+use crate::signer::Signer;
+use crate::timestamp::Timestamp;
+
+/// The prefix of the ports of the controller module.
+pub const CONTROLLER_PORT_PREFIX: &str = "icacontroller-";
+
+/// The identifier of the controller module, under which it owns the capabilities of its ports and
+/// channels.
+pub const CONTROLLER_MODULE_ID: &str = "icacontroller";
+
+/// Returns the controller port of the interchain accounts of `owner`.
+pub fn controller_port_id(owner: &Signer) -> Result<PortId, Error> {
+    PortId::from_str(&format!("{}{}", CONTROLLER_PORT_PREFIX, owner))
+        .map_err(|e| Kind::InvalidOwner(owner.to_string()).context(e).into())
+}
+
+/// The controller of interchain accounts, which can be bound to any port prefixed with
+/// `CONTROLLER_PORT_PREFIX` of a chain which implements `Ics27ControllerContext`, provided that the
+/// port is bound to `CONTROLLER_MODULE_ID`.
+#[derive(Clone, Debug, Default)]
+pub struct ControllerModule;
+
+fn app_error(e: Error) -> Ics04Error {
+    Ics04Kind::AppModule.context(e).into()
+}
+
+fn validate_channel<Ctx>(
+    ctx: &Ctx,
+    order: Order,
+    connection_id: &ConnectionId,
+    port_id: &PortId,
+    version: &str,
+) -> Result<(), Error>
+where
+    Ctx: Ics27ControllerContext,
+{
+    if order != Order::Ordered {
+        return Err(Kind::InvalidChannelOrder(order).into());
+    }
+    if !port_id.as_str().starts_with(CONTROLLER_PORT_PREFIX) {
+        return Err(Kind::InvalidControllerPortId(
+            port_id.clone(),
+            CONTROLLER_PORT_PREFIX.to_string(),
+        )
+        .into());
+    }
+    if version != VERSION {
+        return Err(Kind::InvalidVersion(version.to_string()).into());
+    }
+
+    // Another channel may be opened only once the active one is closed, e.g., after a timeout.
+    let active_channel = ctx
+        .get_active_channel(connection_id, port_id)
+        .and_then(|channel_id| ctx.channel_end(&(port_id.clone(), channel_id)));
+    if matches!(active_channel, Some(channel_end) if channel_end.is_open()) {
+        return Err(Kind::ActiveChannelAlreadySet(connection_id.clone(), port_id.clone()).into());
+    }
+
+    Ok(())
+}
+
+/// Records the account which the host registered, as per the version it chose for the channel,
+/// and makes the channel the active one of the controller port.
+fn register_account<Ctx>(
+    ctx: &mut Ctx,
+    port_id: &PortId,
+    channel_id: &ChannelId,
+    counterparty_version: &str,
+) -> Result<(), Error>
+where
+    Ctx: Ics27ControllerContext,
+{
+    let address = parse_account_version(counterparty_version)?;
+
+    // The channel handler ensured that the channel has exactly one connection hop.
+    let connection_id = ctx
+        .channel_end(&(port_id.clone(), channel_id.clone()))
+        .map(|channel_end| channel_end.connection_hops()[0].clone())
+        .ok_or_else(|| Kind::ChannelNotFound(port_id.clone(), channel_id.clone()))?;
+
+    ctx.store_interchain_account(connection_id.clone(), port_id.clone(), address)?;
+    ctx.store_active_channel(connection_id, port_id.clone(), channel_id.clone())
+}
+
+impl<Ctx: Ics27ControllerContext> Module<Ctx> for ControllerModule {
+    fn on_chan_open_init(
+        &self,
+        ctx: &mut Ctx,
+        order: Order,
+        connection_hops: &[ConnectionId],
+        port_id: &PortId,
+        _channel_id: &ChannelId,
+        _counterparty: &Counterparty,
+        version: &str,
+    ) -> Result<String, Ics04Error> {
+        // The channel handler ensured that the channel has exactly one connection hop.
+        validate_channel(ctx, order, &connection_hops[0], port_id, version).map_err(app_error)?;
+        Ok(version.to_string())
+    }
+
+    fn on_chan_open_try(
+        &self,
+        _ctx: &mut Ctx,
+        _order: Order,
+        _connection_hops: &[ConnectionId],
+        _port_id: &PortId,
+        _channel_id: &ChannelId,
+        _counterparty: &Counterparty,
+        _version: &str,
+        _counterparty_version: &str,
+    ) -> Result<String, Ics04Error> {
+        Err(app_error(Kind::InvalidHandshakeRole.into()))
+    }
+
+    fn on_chan_open_ack(
+        &self,
+        ctx: &mut Ctx,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        counterparty_version: &str,
+    ) -> Result<(), Ics04Error> {
+        register_account(ctx, port_id, channel_id, counterparty_version).map_err(app_error)
+    }
+
+    fn on_chan_close_init(
+        &self,
+        _ctx: &mut Ctx,
+        _port_id: &PortId,
+        _channel_id: &ChannelId,
+    ) -> Result<(), Ics04Error> {
+        Err(app_error(Kind::ChannelClosingNotAllowed.into()))
+    }
+
+    fn on_recv_packet(&self, _ctx: &mut Ctx, _packet: &Packet) -> HandlerOutput<Option<Vec<u8>>> {
+        let mut output = HandlerOutput::builder();
+        output.log("failure: controllers do not receive packets");
+        output.with_result(Some(
+            Acknowledgement::error(Kind::UnexpectedPacket).to_bytes(),
+        ))
+    }
+
+    fn on_acknowledgement_packet(
+        &self,
+        _ctx: &mut Ctx,
+        _packet: &Packet,
+        acknowledgement: &[u8],
+    ) -> Result<HandlerOutput<()>, Ics04Error> {
+        let ack = Acknowledgement::from_bytes(acknowledgement)
+            .map_err(|e| app_error(Kind::InvalidAcknowledgement.context(e).into()))?;
+
+        let mut output = HandlerOutput::builder();
+        if ack.is_success() {
+            output.log("success: interchain account transaction executed");
+        } else {
+            output.log(format!(
+                "failure: interchain account transaction not executed: {}",
+                ack
+            ));
+        }
+        Ok(output.with_result(()))
+    }
+
+    fn on_timeout_packet(
+        &self,
+        _ctx: &mut Ctx,
+        _packet: &Packet,
+    ) -> Result<HandlerOutput<()>, Ics04Error> {
+        // The ordered channel is closed by the timeout, and the account stays registered, so
+        // that a new channel can be opened to reach it.
+        let mut output = HandlerOutput::builder();
+        output.log("failure: interchain account transaction timed out");
+        Ok(output.with_result(()))
+    }
+}
+
+/// Sends the transaction `data` to the interchain account of the controller port `port_id` over
+/// `connection_id`, on the active channel of the port. The packet is stored right away, hence the
+/// output carries the events only.
+pub fn send_tx<Ctx>(
+    ctx: &mut Ctx,
+    connection_id: &ConnectionId,
+    port_id: &PortId,
+    data: InterchainAccountPacketData,
+    timeout_height: Height,
+    timeout_timestamp: Timestamp,
+) -> Result<HandlerOutput<()>, Error>
+where
+    Ctx: Ics27ControllerContext,
+{
+    data.validate_basic()?;
+
+    let channel_id = ctx
+        .get_active_channel(connection_id, port_id)
+        .ok_or_else(|| Kind::ActiveChannelNotFound(connection_id.clone(), port_id.clone()))?;
+    let port_channel_id = (port_id.clone(), channel_id.clone());
+
+    let channel_end = ctx
+        .channel_end(&port_channel_id)
+        .ok_or_else(|| Kind::ChannelNotFound(port_id.clone(), channel_id.clone()))?;
+    let destination_channel = channel_end
+        .counterparty()
+        .channel_id()
+        .ok_or_else(|| Kind::DestinationChannelNotFound(port_id.clone(), channel_id.clone()))?;
+
+    let sequence = ctx
+        .get_next_sequence_send(&port_channel_id)
+        .ok_or_else(|| Kind::SequenceSendNotFound(port_id.clone(), channel_id.clone()))?;
+
+    let packet = Packet {
+        sequence,
+        source_port: port_id.clone(),
+        source_channel: channel_id.clone(),
+        destination_port: channel_end.counterparty().port_id().clone(),
+        destination_channel: destination_channel.clone(),
+        data: data.to_bytes(),
+        timeout_height,
+        timeout_timestamp,
+    };
+
+    // The transaction is sent on behalf of the controller module, which must own the channel.
+    let channel_cap = ctx
+        .get_capability(
+            &ModuleId::new(CONTROLLER_MODULE_ID),
+            &CapabilityName::channel(port_id, &channel_id),
+        )
+        .ok_or_else(|| Kind::ChannelCapabilityNotFound(port_id.clone(), channel_id.clone()))?;
+
+    let handler_output =
+        send_packet(ctx, &channel_cap, packet).map_err(|e| Kind::HandlerRaisedError.context(e))?;
+    ctx.store_packet_result(handler_output.result)
+        .map_err(|e| Kind::HandlerRaisedError.context(e))?;
+
+    Ok(HandlerOutput::builder()
+        .with_log(handler_output.log)
+        .with_events(handler_output.events)
+        .with_result(()))
+}
@@ -0,0 +1,73 @@
+use anomaly::{BoxError, Context};
+use thiserror::Error;
+
+use crate::ics04_channel::channel::Order;
+use crate::ics24_host::identifier::{ChannelId, ConnectionId, PortId};
+
+pub type Error = anomaly::Error<Kind>;
+
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum Kind {
+    #[error("invalid channel order {0}, interchain account channels must be ordered")]
+    InvalidChannelOrder(Order),
+
+    #[error("invalid interchain account channel version {0}")]
+    InvalidVersion(String),
+
+    #[error("invalid controller port identifier {0}, expected the prefix {1}")]
+    InvalidControllerPortId(PortId, String),
+
+    #[error("the controller of an interchain account must initiate the channel handshake")]
+    InvalidHandshakeRole,
+
+    #[error("invalid owner {0}, which cannot make up a controller port identifier")]
+    InvalidOwner(String),
+
+    #[error("missing channel for port_id {0} and channel_id {1}")]
+    ChannelNotFound(PortId, ChannelId),
+
+    #[error("destination channel not found in the counterparty of port_id {0} and channel_id {1}")]
+    DestinationChannelNotFound(PortId, ChannelId),
+
+    #[error("sending sequence number not found for port {0} and channel {1}")]
+    SequenceSendNotFound(PortId, ChannelId),
+
+    #[error("only the controller of an interchain account sends packets")]
+    UnexpectedPacket,
+
+    #[error("an active channel is already open for port {1} over connection {0}")]
+    ActiveChannelAlreadySet(ConnectionId, PortId),
+
+    #[error("no active channel for port {1} over connection {0}")]
+    ActiveChannelNotFound(ConnectionId, PortId),
+
+    #[error("no interchain account registered for port {1} over connection {0}")]
+    AccountNotFound(ConnectionId, PortId),
+
+    #[error("invalid interchain account packet data")]
+    InvalidPacketData,
+
+    #[error("invalid interchain account packet acknowledgement")]
+    InvalidAcknowledgement,
+
+    #[error("message type {0} cannot be executed by interchain accounts")]
+    MessageNotAllowed(String),
+
+    #[error("failed to execute message of type {0}")]
+    MessageExecutionFailed(String),
+
+    #[error("interchain account channels cannot be closed by users")]
+    ChannelClosingNotAllowed,
+
+    #[error("no capability found for port_id {0} and channel_id {1}")]
+    ChannelCapabilityNotFound(PortId, ChannelId),
+
+    #[error("error raised by the channel handlers")]
+    HandlerRaisedError,
+}
+
+impl Kind {
+    pub fn context(self, source: impl Into<BoxError>) -> Context<Self> {
+        Context::new(self, Some(source.into()))
+    }
+}
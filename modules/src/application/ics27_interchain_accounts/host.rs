@@ -0,0 +1,223 @@
+//! The host module of interchain accounts, which registers an account for each controller port
+//! that opens a channel to it, and executes the transactions which the controller sends to the
+//! account through the `MessageExecutor` of the host chain.
+
+use sha2::{Digest, Sha256};
+use subtle_encoding::hex;
+
+use crate::application::ics27_interchain_accounts::context::Ics27HostContext;
+use crate::application::ics27_interchain_accounts::controller::CONTROLLER_PORT_PREFIX;
+use crate::application::ics27_interchain_accounts::error::{Error, Kind};
+use crate::application::ics27_interchain_accounts::packet::InterchainAccountPacketData;
+use crate::application::ics27_interchain_accounts::version::{account_version, VERSION};
+use crate::handler::HandlerOutput;
+use crate::ics04_channel::acknowledgement::Acknowledgement;
+use crate::ics04_channel::channel::{Counterparty, Order};
+use crate::ics04_channel::error::{Error as Ics04Error, Kind as Ics04Kind};
+use crate::ics04_channel::packet::Packet;
+use crate::ics24_host::identifier::{ChannelId, ConnectionId, PortId};
+use crate::ics26_routing::module::Module;
+use crate::signer::Signer;
+
+/// The port which the host module is bound to.
+pub const HOST_PORT_ID: &str = "ibcaccount";
+
+/// The identifier of the host module, under which it owns the capabilities of its port and
+/// channels.
+pub const HOST_MODULE_ID: &str = "icahost";
+
+/// The reason of the acknowledgement of a transaction which the host failed to execute. The
+/// acknowledgement is committed to by the host, so the error itself, whose text is not part of
+/// consensus, is only logged.
+pub const ACK_ERR_STR: &str =
+    "error executing interchain account transaction: see logs for details";
+
+/// The length of an account address, in bytes.
+const ADDRESS_LENGTH: usize = 20;
+
+/// Returns the address of the interchain account of the controller port `port_id`, reached over
+/// `connection_id`, namely the hex encoding of the first 20 bytes of
+/// `sha256({connection_id}{port_id})`.
+pub fn generate_address(connection_id: &ConnectionId, port_id: &PortId) -> Signer {
+    let digest = Sha256::digest(format!("{}{}", connection_id, port_id).as_bytes());
+    // The hex alphabet is ASCII.
+    Signer::new(String::from_utf8(hex::encode(&digest[..ADDRESS_LENGTH])).unwrap())
+}
+
+/// The host of interchain accounts, which can be bound to the port of a chain which implements
+/// `Ics27HostContext`, usually `HOST_PORT_ID`.
+#[derive(Clone, Debug, Default)]
+pub struct HostModule;
+
+fn app_error(e: Error) -> Ics04Error {
+    Ics04Kind::AppModule.context(e).into()
+}
+
+/// Registers the account of the controller port at the other end of the channel, unless it was
+/// registered over a previous channel, and returns the version of the channel, which carries the
+/// address of the account.
+fn register_account<Ctx>(
+    ctx: &mut Ctx,
+    order: Order,
+    connection_id: &ConnectionId,
+    counterparty: &Counterparty,
+    counterparty_version: &str,
+) -> Result<String, Error>
+where
+    Ctx: Ics27HostContext,
+{
+    if order != Order::Ordered {
+        return Err(Kind::InvalidChannelOrder(order).into());
+    }
+    if counterparty_version != VERSION {
+        return Err(Kind::InvalidVersion(counterparty_version.to_string()).into());
+    }
+
+    let controller_port = counterparty.port_id();
+    if !controller_port.as_str().starts_with(CONTROLLER_PORT_PREFIX) {
+        return Err(Kind::InvalidControllerPortId(
+            controller_port.clone(),
+            CONTROLLER_PORT_PREFIX.to_string(),
+        )
+        .into());
+    }
+
+    let address = match ctx.get_interchain_account(connection_id, controller_port) {
+        Some(address) => address,
+        None => {
+            let address = generate_address(connection_id, controller_port);
+            ctx.store_interchain_account(
+                connection_id.clone(),
+                controller_port.clone(),
+                address.clone(),
+            )?;
+            address
+        }
+    };
+
+    Ok(account_version(&address))
+}
+
+/// Executes the transaction carried by `packet` on behalf of the interchain account of the
+/// controller which sent it. Either all the messages of the transaction are executed, or none.
+fn execute_tx<Ctx>(ctx: &mut Ctx, packet: &Packet) -> Result<(), Error>
+where
+    Ctx: Ics27HostContext,
+{
+    let data = InterchainAccountPacketData::from_bytes(&packet.data)?;
+
+    // The channel handler ensured that the channel has exactly one connection hop.
+    let connection_id = ctx
+        .channel_end(&(
+            packet.destination_port.clone(),
+            packet.destination_channel.clone(),
+        ))
+        .map(|channel_end| channel_end.connection_hops()[0].clone())
+        .ok_or_else(|| {
+            Kind::ChannelNotFound(
+                packet.destination_port.clone(),
+                packet.destination_channel.clone(),
+            )
+        })?;
+
+    let account = ctx
+        .get_interchain_account(&connection_id, &packet.source_port)
+        .ok_or_else(|| Kind::AccountNotFound(connection_id, packet.source_port.clone()))?;
+
+    if let Some(message) = data.data.iter().find(|m| !ctx.is_allowed(&m.type_url)) {
+        return Err(Kind::MessageNotAllowed(message.type_url.clone()).into());
+    }
+
+    // The messages are executed against a copy of the context, which replaces the context only
+    // once all of them succeeded.
+    let mut ctx_interim = ctx.clone();
+    for message in data.data.iter() {
+        ctx_interim.execute(&account, message)?;
+    }
+    *ctx = ctx_interim;
+
+    Ok(())
+}
+
+impl<Ctx: Ics27HostContext> Module<Ctx> for HostModule {
+    fn on_chan_open_init(
+        &self,
+        _ctx: &mut Ctx,
+        _order: Order,
+        _connection_hops: &[ConnectionId],
+        _port_id: &PortId,
+        _channel_id: &ChannelId,
+        _counterparty: &Counterparty,
+        _version: &str,
+    ) -> Result<String, Ics04Error> {
+        Err(app_error(Kind::InvalidHandshakeRole.into()))
+    }
+
+    fn on_chan_open_try(
+        &self,
+        ctx: &mut Ctx,
+        order: Order,
+        connection_hops: &[ConnectionId],
+        _port_id: &PortId,
+        _channel_id: &ChannelId,
+        counterparty: &Counterparty,
+        _version: &str,
+        counterparty_version: &str,
+    ) -> Result<String, Ics04Error> {
+        // The channel handler ensured that the channel has exactly one connection hop.
+        register_account(
+            ctx,
+            order,
+            &connection_hops[0],
+            counterparty,
+            counterparty_version,
+        )
+        .map_err(app_error)
+    }
+
+    fn on_chan_close_init(
+        &self,
+        _ctx: &mut Ctx,
+        _port_id: &PortId,
+        _channel_id: &ChannelId,
+    ) -> Result<(), Ics04Error> {
+        Err(app_error(Kind::ChannelClosingNotAllowed.into()))
+    }
+
+    fn on_recv_packet(&self, ctx: &mut Ctx, packet: &Packet) -> HandlerOutput<Option<Vec<u8>>> {
+        let mut output = HandlerOutput::builder();
+
+        let ack = match execute_tx(ctx, packet) {
+            Ok(()) => {
+                output.log("success: interchain account transaction executed");
+                Acknowledgement::success()
+            }
+            Err(e) => {
+                output.log(format!(
+                    "failure: interchain account transaction not executed: {}",
+                    e
+                ));
+                Acknowledgement::error(ACK_ERR_STR)
+            }
+        };
+
+        output.with_result(Some(ack.to_bytes()))
+    }
+
+    fn on_acknowledgement_packet(
+        &self,
+        _ctx: &mut Ctx,
+        _packet: &Packet,
+        _acknowledgement: &[u8],
+    ) -> Result<HandlerOutput<()>, Ics04Error> {
+        Err(app_error(Kind::UnexpectedPacket.into()))
+    }
+
+    fn on_timeout_packet(
+        &self,
+        _ctx: &mut Ctx,
+        _packet: &Packet,
+    ) -> Result<HandlerOutput<()>, Ics04Error> {
+        Err(app_error(Kind::UnexpectedPacket.into()))
+    }
+}
@@ -0,0 +1,356 @@
+//! ICS 27: Interchain Accounts implementation
+pub mod context;
+pub mod controller;
+pub mod error;
+pub mod host;
+pub mod packet;
+pub mod version;
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use test_env_log::test;
+
+    use prost_types::Any;
+
+    use crate::application::ics20_fungible_token_transfer::coin::Coin;
+    use crate::application::ics20_fungible_token_transfer::context::BankKeeper;
+    use crate::application::ics20_fungible_token_transfer::module::{
+        Ics20Module, MODULE_ID as TRANSFER_MODULE_ID, VERSION as TRANSFER_VERSION,
+    };
+    use crate::application::ics20_fungible_token_transfer::msgs::transfer::MsgTransfer;
+    use crate::application::ics27_interchain_accounts::context::InterchainAccountReader;
+    use crate::application::ics27_interchain_accounts::controller::{
+        controller_port_id, send_tx, ControllerModule, CONTROLLER_MODULE_ID,
+    };
+    use crate::application::ics27_interchain_accounts::host::{
+        generate_address, HostModule, ACK_ERR_STR, HOST_MODULE_ID, HOST_PORT_ID,
+    };
+    use crate::application::ics27_interchain_accounts::packet::InterchainAccountPacketData;
+    use crate::application::ics27_interchain_accounts::version::VERSION;
+    use crate::events::IbcEvent;
+    use crate::ics03_connection::connection::ConnectionEnd;
+    use crate::ics03_connection::connection::Counterparty as ConnectionCounterparty;
+    use crate::ics03_connection::connection::State as ConnectionState;
+    use crate::ics03_connection::version::get_compatible_versions;
+    use crate::ics04_channel::acknowledgement::Acknowledgement;
+    use crate::ics04_channel::channel::{ChannelEnd, Counterparty, Order, State};
+    use crate::ics04_channel::context::ChannelReader;
+    use crate::ics04_channel::msgs::acknowledgement::MsgAcknowledgement;
+    use crate::ics04_channel::msgs::chan_open_ack::MsgChannelOpenAck;
+    use crate::ics04_channel::msgs::chan_open_confirm::MsgChannelOpenConfirm;
+    use crate::ics04_channel::msgs::chan_open_init::MsgChannelOpenInit;
+    use crate::ics04_channel::msgs::chan_open_try::MsgChannelOpenTry;
+    use crate::ics04_channel::msgs::recv_packet::MsgRecvPacket;
+    use crate::ics04_channel::msgs::{ChannelMsg, PacketMsg};
+    use crate::ics04_channel::packet::Packet;
+    use crate::ics05_port::capabilities::ModuleId;
+    use crate::ics24_host::identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId};
+    use crate::ics26_routing::handler::dispatch;
+    use crate::ics26_routing::msgs::Ics26Envelope;
+    use crate::mock::context::MockContext;
+    use crate::mock::host::HostType;
+    use crate::proofs::Proofs;
+    use crate::signer::Signer;
+    use crate::test_utils::{get_dummy_account_id, get_dummy_proof};
+    use crate::timestamp::{Timestamp, ZERO_DURATION};
+    use crate::tx_msg::Msg;
+    use crate::Height;
+
+    /// The height of the (mock) clients of both chains, at which all proofs are produced.
+    const PROOF_HEIGHT: u64 = 5;
+
+    fn controller_port() -> PortId {
+        controller_port_id(&Signer::new("alice")).unwrap()
+    }
+
+    fn host_port() -> PortId {
+        PortId::from_str(HOST_PORT_ID).unwrap()
+    }
+
+    fn transfer_port() -> PortId {
+        PortId::from_str("transfer").unwrap()
+    }
+
+    /// The interchain account channel is the first channel of the host chain, hence the transfer
+    /// channel of the host chain is its second channel.
+    fn transfer_channel() -> ChannelId {
+        ChannelId::new(1)
+    }
+
+    /// Returns a chain having a connection `connection-0` open towards its counterparty, which is
+    /// tracked by a mock client.
+    fn chain(name: &str) -> MockContext {
+        let connection_end = ConnectionEnd::new(
+            ConnectionState::Open,
+            ClientId::default(),
+            ConnectionCounterparty::new(
+                ClientId::default(),
+                Some(ConnectionId::default()),
+                Default::default(),
+            ),
+            get_compatible_versions(),
+            ZERO_DURATION,
+        );
+
+        MockContext::new(
+            ChainId::new(name.to_string(), 0),
+            HostType::Mock,
+            5,
+            Height::new(0, 5),
+        )
+        .with_client(&ClientId::default(), Height::new(0, PROOF_HEIGHT))
+        .with_connection(ConnectionId::default(), connection_end)
+    }
+
+    fn controller_chain() -> MockContext {
+        chain("controller").with_module(
+            ModuleId::new(CONTROLLER_MODULE_ID),
+            controller_port(),
+            ControllerModule,
+        )
+    }
+
+    /// Returns the host chain, on which interchain accounts can send tokens over the transfer
+    /// channel `transfer/channel-1`.
+    fn host_chain() -> MockContext {
+        let transfer_channel_end = ChannelEnd::new(
+            State::Open,
+            Order::Unordered,
+            Counterparty::new(transfer_port(), Some(ChannelId::default())),
+            vec![ConnectionId::default()],
+            TRANSFER_VERSION.to_string(),
+        );
+
+        chain("host")
+            .with_module(ModuleId::new(HOST_MODULE_ID), host_port(), HostModule)
+            .with_module(
+                ModuleId::new(TRANSFER_MODULE_ID),
+                transfer_port(),
+                Ics20Module,
+            )
+            .with_channel(transfer_port(), transfer_channel(), transfer_channel_end)
+            .with_send_sequence(transfer_port(), transfer_channel(), 1.into())
+    }
+
+    fn proofs() -> Proofs {
+        Proofs::new(
+            get_dummy_proof().into(),
+            None,
+            None,
+            None,
+            Height::new(0, PROOF_HEIGHT),
+        )
+        .unwrap()
+    }
+
+    fn chan_open_init() -> Ics26Envelope {
+        let msg = MsgChannelOpenInit::new(
+            controller_port(),
+            ChannelEnd::new(
+                State::Init,
+                Order::Ordered,
+                Counterparty::new(host_port(), None),
+                vec![ConnectionId::default()],
+                VERSION.to_string(),
+            ),
+            get_dummy_account_id(),
+        );
+        Ics26Envelope::Ics4ChannelMsg(ChannelMsg::ChannelOpenInit(msg))
+    }
+
+    /// Opens the channel `channel-0` between the controller port and the host port, over which
+    /// the interchain account gets registered.
+    fn register_account(controller: &mut MockContext, host: &mut MockContext) {
+        let channel_id = ChannelId::default();
+
+        dispatch(controller, chan_open_init()).unwrap();
+
+        let msg = MsgChannelOpenTry::new(
+            host_port(),
+            None,
+            ChannelEnd::new(
+                State::TryOpen,
+                Order::Ordered,
+                Counterparty::new(controller_port(), Some(channel_id.clone())),
+                vec![ConnectionId::default()],
+                VERSION.to_string(),
+            ),
+            VERSION.to_string(),
+            proofs(),
+            get_dummy_account_id(),
+        );
+        dispatch(
+            host,
+            Ics26Envelope::Ics4ChannelMsg(ChannelMsg::ChannelOpenTry(msg)),
+        )
+        .unwrap();
+
+        // The version chosen by the host carries the address of the account.
+        let host_version = host
+            .channel_end(&(host_port(), channel_id.clone()))
+            .unwrap()
+            .version();
+        let msg = MsgChannelOpenAck::new(
+            controller_port(),
+            channel_id.clone(),
+            channel_id.clone(),
+            host_version,
+            proofs(),
+            get_dummy_account_id(),
+        );
+        dispatch(
+            controller,
+            Ics26Envelope::Ics4ChannelMsg(ChannelMsg::ChannelOpenAck(msg)),
+        )
+        .unwrap();
+
+        let msg =
+            MsgChannelOpenConfirm::new(host_port(), channel_id, proofs(), get_dummy_account_id());
+        dispatch(
+            host,
+            Ics26Envelope::Ics4ChannelMsg(ChannelMsg::ChannelOpenConfirm(msg)),
+        )
+        .unwrap();
+    }
+
+    /// Returns a message which sends `amount` uatom from `sender` over the transfer channel.
+    fn transfer(sender: &Signer, amount: u64) -> Any {
+        MsgTransfer {
+            source_port: transfer_port(),
+            source_channel: transfer_channel(),
+            token: Some(Coin::new("uatom", amount).into()),
+            sender: sender.clone(),
+            receiver: Signer::new("bob"),
+            timeout_height: Height::new(0, 100),
+            timeout_timestamp: Timestamp::none(),
+        }
+        .to_any()
+    }
+
+    /// Sends the transaction made of `messages` from the controller chain, and returns the packet
+    /// that was sent.
+    fn send(controller: &mut MockContext, messages: Vec<Any>) -> Packet {
+        let output = send_tx(
+            controller,
+            &ConnectionId::default(),
+            &controller_port(),
+            InterchainAccountPacketData::execute_tx(messages),
+            Height::new(0, 100),
+            Timestamp::none(),
+        )
+        .unwrap();
+        output
+            .events
+            .into_iter()
+            .find_map(|event| match event {
+                IbcEvent::SendPacket(ev) => Some(ev.packet),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    /// Delivers `packet` to the host chain, and returns the acknowledgement it wrote.
+    fn receive(host: &mut MockContext, packet: Packet) -> Acknowledgement {
+        let msg = MsgRecvPacket::new(packet, proofs(), get_dummy_account_id());
+        let output = dispatch(
+            host,
+            Ics26Envelope::Ics4PacketMsg(PacketMsg::RecvPacket(msg)),
+        )
+        .unwrap();
+        let ack = output
+            .events
+            .into_iter()
+            .find_map(|event| match event {
+                IbcEvent::WriteAcknowledgement(ev) => Some(ev.ack),
+                _ => None,
+            })
+            .unwrap();
+        Acknowledgement::from_bytes(&ack).unwrap()
+    }
+
+    /// Delivers the acknowledgement `ack` of `packet` back to the controller chain.
+    fn acknowledge(controller: &mut MockContext, packet: Packet, ack: Acknowledgement) {
+        let msg = MsgAcknowledgement::new(packet, ack.to_bytes(), proofs(), get_dummy_account_id());
+        dispatch(
+            controller,
+            Ics26Envelope::Ics4PacketMsg(PacketMsg::AckPacket(msg)),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn register_account_and_execute_tx() {
+        let mut controller = controller_chain();
+        let mut host = host_chain();
+        register_account(&mut controller, &mut host);
+
+        // Both chains know the address of the account, which the host derived.
+        let account = generate_address(&ConnectionId::default(), &controller_port());
+        for chain in [&controller, &host].iter() {
+            assert_eq!(
+                chain.get_interchain_account(&ConnectionId::default(), &controller_port()),
+                Some(account.clone())
+            );
+        }
+        assert_eq!(
+            controller.get_active_channel(&ConnectionId::default(), &controller_port()),
+            Some(ChannelId::default())
+        );
+
+        // No other channel can be opened for the controller port while this one is active.
+        assert!(dispatch(&mut controller, chan_open_init()).is_err());
+
+        // The account sends tokens, over the transfer channel of the host chain.
+        let mut host = host.with_balance(&account, Coin::new("uatom", 100));
+        let packet = send(
+            &mut controller,
+            vec![transfer(&account, 30), transfer(&account, 20)],
+        );
+
+        let ack = receive(&mut host, packet.clone());
+        assert!(ack.is_success());
+        assert_eq!(host.balance(&account, "uatom"), 50);
+        let escrow = host.get_escrow_address(&transfer_port(), &transfer_channel());
+        assert_eq!(host.balance(&escrow, "uatom"), 50);
+
+        acknowledge(&mut controller, packet, ack);
+    }
+
+    #[test]
+    fn txs_are_atomic_and_restricted_to_allowed_messages() {
+        let mut controller = controller_chain();
+        let mut host = host_chain();
+        register_account(&mut controller, &mut host);
+
+        let account = generate_address(&ConnectionId::default(), &controller_port());
+        let bob = Signer::new("bob");
+        let mut host = host
+            .with_balance(&account, Coin::new("uatom", 100))
+            .with_balance(&bob, Coin::new("uatom", 100));
+
+        let disallowed = Any {
+            type_url: "/cosmos.bank.v1beta1.MsgSend".to_string(),
+            value: vec![],
+        };
+        let txs = vec![
+            // The second transfer fails, so the first one is reverted.
+            vec![transfer(&account, 30), transfer(&account, 90)],
+            // The account cannot spend the tokens of another account.
+            vec![transfer(&bob, 10)],
+            // Only `MsgTransfer` is allowed on the (mock) host chain.
+            vec![transfer(&account, 10), disallowed],
+        ];
+
+        // Each transaction fails, and the next one is still received, as the channel is ordered.
+        for messages in txs {
+            let packet = send(&mut controller, messages);
+            let ack = receive(&mut host, packet.clone());
+            assert_eq!(ack, Acknowledgement::error(ACK_ERR_STR));
+            acknowledge(&mut controller, packet, ack);
+        }
+
+        assert_eq!(host.balance(&account, "uatom"), 100);
+        assert_eq!(host.balance(&bob, "uatom"), 100);
+    }
+}
@@ -0,0 +1,139 @@
+//! The payload of the packets which the controller of an interchain account sends to its host. On
+//! the wire, the payload is the JSON encoding of `InterchainAccountPacketData`, in which the
+//! messages are encoded as a protobuf `CosmosTx` and then base64 encoded, which matches the
+//! encoding used by `ibc-go`.
+
+use prost_types::Any;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::application::ics27_interchain_accounts::error::{Error, Kind};
+
+/// The kind of request which an interchain account packet carries.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Type {
+    #[serde(rename = "TYPE_UNSPECIFIED")]
+    Unspecified,
+    /// Execute the messages in the packet on behalf of the interchain account.
+    #[serde(rename = "TYPE_EXECUTE_TX")]
+    ExecuteTx,
+}
+
+/// The fields are declared in the order of the protobuf definition, like `ibc-go` encodes them.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct InterchainAccountPacketData {
+    #[serde(rename = "type")]
+    pub packet_type: Type,
+    /// The messages to be executed by the interchain account, in order.
+    #[serde(with = "messages_as_cosmos_tx")]
+    pub data: Vec<Any>,
+    pub memo: String,
+}
+
+impl InterchainAccountPacketData {
+    /// Returns packet data requesting the execution of `messages`.
+    pub fn execute_tx(messages: Vec<Any>) -> Self {
+        Self {
+            packet_type: Type::ExecuteTx,
+            data: messages,
+            memo: String::new(),
+        }
+    }
+
+    pub fn validate_basic(&self) -> Result<(), Error> {
+        if self.packet_type != Type::ExecuteTx {
+            return Err(Kind::InvalidPacketData
+                .context("unspecified packet type")
+                .into());
+        }
+        if self.data.is_empty() {
+            return Err(Kind::InvalidPacketData
+                .context("no messages to execute")
+                .into());
+        }
+        Ok(())
+    }
+
+    /// Returns the bytes which make up the data field of an ICS27 packet.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        // Serializing strings and bytes cannot fail.
+        serde_json::to_vec(self).unwrap()
+    }
+
+    /// Decodes and validates the data field of an ICS27 packet.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let data: Self =
+            serde_json::from_slice(bytes).map_err(|e| Kind::InvalidPacketData.context(e))?;
+        data.validate_basic()?;
+        Ok(data)
+    }
+}
+
+/// The protobuf `CosmosTx` message, i.e., the list of messages of a transaction.
+#[derive(Clone, PartialEq, prost::Message)]
+struct RawCosmosTx {
+    #[prost(message, repeated, tag = "1")]
+    messages: Vec<Any>,
+}
+
+/// The proto3 JSON mapping encodes bytes in base64.
+mod messages_as_cosmos_tx {
+    use prost::Message;
+    use prost_types::Any;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+    use subtle_encoding::base64;
+
+    use super::RawCosmosTx;
+
+    pub fn serialize<S: Serializer>(messages: &[Any], serializer: S) -> Result<S::Ok, S::Error> {
+        let raw = RawCosmosTx {
+            messages: messages.to_vec(),
+        };
+        let mut bytes = Vec::new();
+        // Encoding into a vector cannot fail.
+        raw.encode(&mut bytes).unwrap();
+
+        // The base64 alphabet is ASCII.
+        let encoded = String::from_utf8(base64::encode(bytes)).unwrap();
+        serializer.serialize_str(&encoded)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Any>, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        let bytes = base64::decode(encoded.as_bytes()).map_err(D::Error::custom)?;
+        let raw = RawCosmosTx::decode(bytes.as_slice()).map_err(D::Error::custom)?;
+        Ok(raw.messages)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prost_types::Any;
+    use test_env_log::test;
+
+    use super::InterchainAccountPacketData;
+
+    #[test]
+    fn packet_data_json_encoding() {
+        let data = InterchainAccountPacketData::execute_tx(vec![Any {
+            type_url: "/x".to_string(),
+            value: vec![1],
+        }]);
+
+        let bytes = data.to_bytes();
+        assert_eq!(
+            String::from_utf8(bytes.clone()).unwrap(),
+            r#"{"type":"TYPE_EXECUTE_TX","data":"CgcKAi94EgEB","memo":""}"#
+        );
+        assert_eq!(
+            InterchainAccountPacketData::from_bytes(&bytes).unwrap(),
+            data
+        );
+
+        // Unspecified packet types, empty transactions and malformed payloads are all rejected.
+        let unspecified = br#"{"type":"TYPE_UNSPECIFIED","data":"CgcKAi94EgEB","memo":""}"#;
+        assert!(InterchainAccountPacketData::from_bytes(unspecified).is_err());
+        let empty = br#"{"type":"TYPE_EXECUTE_TX","data":"","memo":""}"#;
+        assert!(InterchainAccountPacketData::from_bytes(empty).is_err());
+        assert!(InterchainAccountPacketData::from_bytes(&[0]).is_err());
+    }
+}
@@ -0,0 +1,46 @@
+//! The version of interchain account channels. The controller proposes `VERSION`, to which the
+//! host answers with the address of the account it registered, as in `ics27-1|{address}`. This
+//! way, the controller learns the address of its account upon `MsgChannelOpenAck`.
+
+use crate::application::ics27_interchain_accounts::error::{Error, Kind};
+use crate::signer::Signer;
+
+/// The version of the ICS27 protocol.
+pub const VERSION: &str = "ics27-1";
+
+/// Separates the protocol version from the account address in the version of an open channel.
+pub const DELIMITER: char = '|';
+
+/// Returns the version with which the host of the interchain account `address` opens a channel.
+pub fn account_version(address: &Signer) -> String {
+    format!("{}{}{}", VERSION, DELIMITER, address)
+}
+
+/// Extracts the account address from the version chosen by the host of an interchain account.
+pub fn parse_account_version(version: &str) -> Result<Signer, Error> {
+    let mut parts = version.splitn(2, DELIMITER);
+    match (parts.next(), parts.next()) {
+        (Some(VERSION), Some(address)) if !address.trim().is_empty() => Ok(Signer::new(address)),
+        _ => Err(Kind::InvalidVersion(version.to_string()).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_env_log::test;
+
+    use super::{account_version, parse_account_version, VERSION};
+    use crate::signer::Signer;
+
+    #[test]
+    fn account_version_round_trip() {
+        let address = Signer::new("cosmos1account");
+        let version = account_version(&address);
+        assert_eq!(version, "ics27-1|cosmos1account");
+        assert_eq!(parse_account_version(&version).unwrap(), address);
+
+        assert!(parse_account_version(VERSION).is_err());
+        assert!(parse_account_version("ics27-1|").is_err());
+        assert!(parse_account_version("ics20-1|cosmos1account").is_err());
+    }
+}
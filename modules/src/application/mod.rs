@@ -1 +1,2 @@
 pub mod ics20_fungible_token_transfer;
+pub mod ics27_interchain_accounts;
@@ -1,8 +1,12 @@
 use std::collections::HashMap;
+use std::convert::TryFrom;
 
 use anomaly::BoxError;
 use serde_derive::{Deserialize, Serialize};
+use tendermint::abci::tag::Tag;
+use tendermint::abci::Event as AbciEvent;
 
+use crate::application::ics20_fungible_token_transfer::events as Ics20Events;
 use crate::ics02_client::events as ClientEvents;
 use crate::ics02_client::events::NewBlock;
 use crate::ics03_connection::events as ConnectionEvents;
@@ -41,6 +45,7 @@ pub enum IbcEvent {
     UpdateClient(ClientEvents::UpdateClient),
     UpgradeClient(ClientEvents::UpgradeClient),
     ClientMisbehaviour(ClientEvents::ClientMisbehaviour),
+    UpdateClientProposal(ClientEvents::UpdateClientProposal),
 
     OpenInitConnection(ConnectionEvents::OpenInit),
     OpenTryConnection(ConnectionEvents::OpenTry),
@@ -61,6 +66,11 @@ pub enum IbcEvent {
     TimeoutPacket(ChannelEvents::TimeoutPacket),
     TimeoutOnClosePacket(ChannelEvents::TimeoutOnClosePacket),
 
+    Transfer(Ics20Events::Transfer),
+    FungibleTokenPacket(Ics20Events::FungibleTokenPacket),
+    DenominationTrace(Ics20Events::DenominationTrace),
+    TransferTimeout(Ics20Events::Timeout),
+
     Empty(String),      // Special event, signifying empty response
     ChainError(String), // Special event, signifying an error on CheckTx or DeliverTx
 }
@@ -86,6 +96,7 @@ impl fmt::Display for IbcEvent {
             IbcEvent::UpdateClient(ev) => write!(f, "UpdateClientEv({})", ev),
             IbcEvent::UpgradeClient(ev) => write!(f, "UpgradeClientEv({:?})", ev),
             IbcEvent::ClientMisbehaviour(ev) => write!(f, "ClientMisbehaviourEv({:?})", ev),
+            IbcEvent::UpdateClientProposal(ev) => write!(f, "UpdateClientProposalEv({:?})", ev),
 
             IbcEvent::OpenInitConnection(ev) => write!(f, "OpenInitConnectionEv({:?})", ev),
             IbcEvent::OpenTryConnection(ev) => write!(f, "OpenTryConnectionEv({:?})", ev),
@@ -106,26 +117,143 @@ impl fmt::Display for IbcEvent {
             IbcEvent::TimeoutPacket(ev) => write!(f, "TimeoutPacketEv({})", ev),
             IbcEvent::TimeoutOnClosePacket(ev) => write!(f, "TimeoutOnClosePacketEv({})", ev),
 
+            IbcEvent::Transfer(ev) => write!(f, "TransferEv({})", ev),
+            IbcEvent::FungibleTokenPacket(ev) => write!(f, "FungibleTokenPacketEv({})", ev),
+            IbcEvent::DenominationTrace(ev) => write!(f, "DenominationTraceEv({})", ev),
+            IbcEvent::TransferTimeout(ev) => write!(f, "TransferTimeoutEv({})", ev),
+
             IbcEvent::Empty(ev) => write!(f, "EmptyEv({})", ev),
             IbcEvent::ChainError(ev) => write!(f, "ChainErrorEv({})", ev),
         }
     }
 }
 
+/// The `type` of the ABCI event for the `NewBlock` event. This and the following event types
+/// are not emitted by chains, but allow the conversion of every `IbcEvent` into an ABCI event.
+const NEW_BLOCK_EVENT_TYPE: &str = "new_block";
+const EMPTY_EVENT_TYPE: &str = "empty";
+const CHAIN_ERROR_EVENT_TYPE: &str = "chain_error";
+
+/// The `key` of the attributes of the `NewBlock` event and of the special events.
+const HEIGHT_ATTRIBUTE_KEY: &str = "height";
+const MESSAGE_ATTRIBUTE_KEY: &str = "message";
+
 // This is tendermint specific
-pub fn from_tx_response_event(height: Height, event: &tendermint::abci::Event) -> Option<IbcEvent> {
-    // Return the first hit we find
-    if let Some(mut client_res) = ClientEvents::try_from_tx(event) {
-        client_res.set_height(height);
-        Some(client_res)
-    } else if let Some(mut conn_res) = ConnectionEvents::try_from_tx(event) {
-        conn_res.set_height(height);
-        Some(conn_res)
-    } else if let Some(mut chan_res) = ChannelEvents::try_from_tx(event) {
-        chan_res.set_height(height);
-        Some(chan_res)
-    } else {
-        None
+pub fn from_tx_response_event(height: Height, event: &AbciEvent) -> Option<IbcEvent> {
+    let mut ibc_event = IbcEvent::try_from(event).ok()?;
+    ibc_event.set_height(height);
+    Some(ibc_event)
+}
+
+/// Returns an ABCI event attribute with the given `key` and `value`.
+pub(crate) fn tag(key: &str, value: impl ToString) -> Tag {
+    Tag {
+        key: key.parse().unwrap(),
+        value: value.to_string().parse().unwrap(),
+    }
+}
+
+/// Returns the value of the attribute with the given `key` of the ABCI `event`.
+pub(crate) fn extract_attribute<'a>(event: &'a AbciEvent, key: &str) -> Result<&'a str, BoxError> {
+    optional_attribute(event, key)
+        .ok_or_else(|| format!("missing attribute {} in {} event", key, event.type_str).into())
+}
+
+/// Returns the value of the attribute with the given `key` of the ABCI `event`, if present.
+pub(crate) fn optional_attribute<'a>(event: &'a AbciEvent, key: &str) -> Option<&'a str> {
+    event
+        .attributes
+        .iter()
+        .find(|tag| tag.key.as_ref() == key)
+        .map(|tag| tag.value.as_ref())
+}
+
+/// Encodes an event in the format of the events which `ibc-go` emits, so that relayers and
+/// indexers understand it. The height of the event is not encoded, as ABCI events carry none:
+/// it is the height of the block whose execution emitted them.
+impl From<IbcEvent> for AbciEvent {
+    fn from(event: IbcEvent) -> Self {
+        match event {
+            IbcEvent::NewBlock(ev) => AbciEvent {
+                type_str: NEW_BLOCK_EVENT_TYPE.to_string(),
+                attributes: vec![tag(HEIGHT_ATTRIBUTE_KEY, ev.height)],
+            },
+
+            IbcEvent::CreateClient(ev) => ev.into(),
+            IbcEvent::UpdateClient(ev) => ev.into(),
+            IbcEvent::UpgradeClient(ev) => ev.into(),
+            IbcEvent::ClientMisbehaviour(ev) => ev.into(),
+            IbcEvent::UpdateClientProposal(ev) => ev.into(),
+
+            IbcEvent::OpenInitConnection(ev) => ev.into(),
+            IbcEvent::OpenTryConnection(ev) => ev.into(),
+            IbcEvent::OpenAckConnection(ev) => ev.into(),
+            IbcEvent::OpenConfirmConnection(ev) => ev.into(),
+
+            IbcEvent::OpenInitChannel(ev) => ev.into(),
+            IbcEvent::OpenTryChannel(ev) => ev.into(),
+            IbcEvent::OpenAckChannel(ev) => ev.into(),
+            IbcEvent::OpenConfirmChannel(ev) => ev.into(),
+            IbcEvent::CloseInitChannel(ev) => ev.into(),
+            IbcEvent::CloseConfirmChannel(ev) => ev.into(),
+
+            IbcEvent::SendPacket(ev) => ev.into(),
+            IbcEvent::ReceivePacket(ev) => ev.into(),
+            IbcEvent::WriteAcknowledgement(ev) => ev.into(),
+            IbcEvent::AcknowledgePacket(ev) => ev.into(),
+            IbcEvent::TimeoutPacket(ev) => ev.into(),
+            IbcEvent::TimeoutOnClosePacket(ev) => ev.into(),
+
+            IbcEvent::Transfer(ev) => ev.into(),
+            IbcEvent::FungibleTokenPacket(ev) => ev.into(),
+            IbcEvent::DenominationTrace(ev) => ev.into(),
+            IbcEvent::TransferTimeout(ev) => ev.into(),
+
+            IbcEvent::Empty(message) => AbciEvent {
+                type_str: EMPTY_EVENT_TYPE.to_string(),
+                attributes: vec![tag(MESSAGE_ATTRIBUTE_KEY, message)],
+            },
+            IbcEvent::ChainError(message) => AbciEvent {
+                type_str: CHAIN_ERROR_EVENT_TYPE.to_string(),
+                attributes: vec![tag(MESSAGE_ATTRIBUTE_KEY, message)],
+            },
+        }
+    }
+}
+
+/// Decodes an ABCI event, in the format of the events which `ibc-go` emits. Fails if the type of
+/// the event is unknown, or if any of its attributes is missing or malformed. The height of the
+/// decoded event is left unset (see `IbcEvent::set_height`).
+impl TryFrom<&AbciEvent> for IbcEvent {
+    type Error = BoxError;
+
+    fn try_from(event: &AbciEvent) -> Result<Self, Self::Error> {
+        if let Some(ev) = ClientEvents::try_from_abci_event(event)? {
+            return Ok(ev);
+        }
+        if let Some(ev) = ConnectionEvents::try_from_abci_event(event)? {
+            return Ok(ev);
+        }
+        if let Some(ev) = ChannelEvents::try_from_abci_event(event)? {
+            return Ok(ev);
+        }
+        if let Some(ev) = Ics20Events::try_from_abci_event(event)? {
+            return Ok(ev);
+        }
+
+        match event.type_str.as_str() {
+            NEW_BLOCK_EVENT_TYPE => {
+                let height = extract_attribute(event, HEIGHT_ATTRIBUTE_KEY)?.parse()?;
+                Ok(IbcEvent::NewBlock(NewBlock::new(height)))
+            }
+            EMPTY_EVENT_TYPE => Ok(IbcEvent::Empty(
+                extract_attribute(event, MESSAGE_ATTRIBUTE_KEY)?.to_string(),
+            )),
+            CHAIN_ERROR_EVENT_TYPE => Ok(IbcEvent::ChainError(
+                extract_attribute(event, MESSAGE_ATTRIBUTE_KEY)?.to_string(),
+            )),
+            event_type => Err(format!("unknown IBC event type {}", event_type).into()),
+        }
     }
 }
 
@@ -142,7 +270,9 @@ impl IbcEvent {
             IbcEvent::NewBlock(bl) => bl.height(),
             IbcEvent::CreateClient(ev) => ev.height(),
             IbcEvent::UpdateClient(ev) => ev.height(),
+            IbcEvent::UpgradeClient(ev) => ev.height(),
             IbcEvent::ClientMisbehaviour(ev) => ev.height(),
+            IbcEvent::UpdateClientProposal(ev) => ev.height(),
             IbcEvent::OpenInitConnection(ev) => ev.height(),
             IbcEvent::OpenTryConnection(ev) => ev.height(),
             IbcEvent::OpenAckConnection(ev) => ev.height(),
@@ -158,7 +288,12 @@ impl IbcEvent {
             IbcEvent::WriteAcknowledgement(ev) => ev.height(),
             IbcEvent::AcknowledgePacket(ev) => ev.height(),
             IbcEvent::TimeoutPacket(ev) => ev.height(),
-            _ => unimplemented!(),
+            IbcEvent::TimeoutOnClosePacket(ev) => ev.height(),
+            IbcEvent::Transfer(ev) => ev.height(),
+            IbcEvent::FungibleTokenPacket(ev) => ev.height(),
+            IbcEvent::DenominationTrace(ev) => ev.height(),
+            IbcEvent::TransferTimeout(ev) => ev.height(),
+            IbcEvent::Empty(_) | IbcEvent::ChainError(_) => Height::zero(),
         }
     }
 
@@ -169,6 +304,7 @@ impl IbcEvent {
             IbcEvent::UpdateClient(ev) => ev.set_height(height),
             IbcEvent::UpgradeClient(ev) => ev.set_height(height),
             IbcEvent::ClientMisbehaviour(ev) => ev.set_height(height),
+            IbcEvent::UpdateClientProposal(ev) => ev.set_height(height),
             IbcEvent::OpenInitConnection(ev) => ev.set_height(height),
             IbcEvent::OpenTryConnection(ev) => ev.set_height(height),
             IbcEvent::OpenAckConnection(ev) => ev.set_height(height),
@@ -184,7 +320,13 @@ impl IbcEvent {
             IbcEvent::WriteAcknowledgement(ev) => ev.set_height(height),
             IbcEvent::AcknowledgePacket(ev) => ev.set_height(height),
             IbcEvent::TimeoutPacket(ev) => ev.set_height(height),
-            _ => unimplemented!(),
+            IbcEvent::TimeoutOnClosePacket(ev) => ev.set_height(height),
+            IbcEvent::Transfer(ev) => ev.set_height(height),
+            IbcEvent::FungibleTokenPacket(ev) => ev.set_height(height),
+            IbcEvent::DenominationTrace(ev) => ev.set_height(height),
+            IbcEvent::TransferTimeout(ev) => ev.set_height(height),
+            // The special events have no height.
+            IbcEvent::Empty(_) | IbcEvent::ChainError(_) => {}
         }
     }
 
@@ -273,3 +415,346 @@ macro_rules! some_attribute {
             .map_or_else(|| None, |tags| tags[$a.idx].parse().ok())
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use tendermint::abci::Event as AbciEvent;
+    use test_env_log::test;
+
+    use super::IbcEvent;
+    use crate::application::ics20_fungible_token_transfer::events as Ics20Events;
+    use crate::ics02_client::client_type::ClientType;
+    use crate::ics02_client::events as ClientEvents;
+    use crate::ics02_client::header::AnyHeader;
+    use crate::ics03_connection::events as ConnectionEvents;
+    use crate::ics04_channel::acknowledgement::Acknowledgement;
+    use crate::ics04_channel::channel::Order;
+    use crate::ics04_channel::events as ChannelEvents;
+    use crate::ics04_channel::packet::{Packet, Sequence};
+    use crate::ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId};
+    use crate::mock::header::MockHeader;
+    use crate::test_utils::get_dummy_account_id;
+    use crate::timestamp::Timestamp;
+    use crate::Height;
+
+    /// Returns an event of every kind, with all its attributes set.
+    fn all_events() -> Vec<IbcEvent> {
+        let height = Height::new(1, 42);
+
+        let client_attributes = ClientEvents::Attributes {
+            height,
+            client_id: ClientId::new(ClientType::Tendermint, 3).unwrap(),
+            client_type: ClientType::Tendermint,
+            consensus_height: Height::new(2, 10),
+        };
+        let connection_attributes = ConnectionEvents::Attributes {
+            height,
+            connection_id: Some(ConnectionId::new(2)),
+            client_id: ClientId::new(ClientType::Tendermint, 3).unwrap(),
+            counterparty_connection_id: Some(ConnectionId::new(5)),
+            counterparty_client_id: ClientId::new(ClientType::Tendermint, 7).unwrap(),
+        };
+        let channel_attributes = ChannelEvents::Attributes {
+            height,
+            port_id: PortId::default(),
+            channel_id: Some(ChannelId::new(4)),
+            connection_id: ConnectionId::new(2),
+            counterparty_port_id: PortId::default(),
+            counterparty_channel_id: Some(ChannelId::new(6)),
+        };
+        // The data is not valid UTF-8, so it only survives through its hex encoding.
+        let packet = Packet {
+            sequence: Sequence::from(8),
+            source_port: PortId::default(),
+            source_channel: ChannelId::new(4),
+            destination_port: PortId::default(),
+            destination_channel: ChannelId::new(6),
+            data: vec![0xff, 0x00, 0x2a],
+            timeout_height: Height::new(2, 100),
+            timeout_timestamp: Timestamp::from_nanoseconds(1_000_000).unwrap(),
+        };
+        // The acknowledgement and timeout events do not carry the packet data.
+        let packet_without_data = Packet {
+            data: vec![],
+            ..packet.clone()
+        };
+
+        vec![
+            IbcEvent::NewBlock(ClientEvents::NewBlock::new(height)),
+            IbcEvent::CreateClient(client_attributes.clone().into()),
+            IbcEvent::UpdateClient(ClientEvents::UpdateClient {
+                common: client_attributes.clone(),
+                header: Some(AnyHeader::Mock(MockHeader::new(Height::new(2, 10)))),
+            }),
+            IbcEvent::UpgradeClient(client_attributes.clone().into()),
+            IbcEvent::ClientMisbehaviour(client_attributes.clone().into()),
+            IbcEvent::UpdateClientProposal(client_attributes.into()),
+            IbcEvent::OpenInitConnection(
+                ConnectionEvents::Attributes {
+                    counterparty_connection_id: None,
+                    ..connection_attributes.clone()
+                }
+                .into(),
+            ),
+            IbcEvent::OpenTryConnection(connection_attributes.clone().into()),
+            IbcEvent::OpenAckConnection(connection_attributes.clone().into()),
+            IbcEvent::OpenConfirmConnection(connection_attributes.into()),
+            IbcEvent::OpenInitChannel(
+                ChannelEvents::Attributes {
+                    counterparty_channel_id: None,
+                    ..channel_attributes.clone()
+                }
+                .into(),
+            ),
+            IbcEvent::OpenTryChannel(channel_attributes.clone().into()),
+            IbcEvent::OpenAckChannel(channel_attributes.clone().into()),
+            IbcEvent::OpenConfirmChannel(channel_attributes.clone().into()),
+            IbcEvent::CloseInitChannel(channel_attributes.clone().into()),
+            IbcEvent::CloseConfirmChannel(channel_attributes.into()),
+            IbcEvent::SendPacket(ChannelEvents::SendPacket {
+                height,
+                packet: packet.clone(),
+                channel_ordering: Some(Order::Unordered),
+                connection_id: Some(ConnectionId::new(2)),
+            }),
+            IbcEvent::ReceivePacket(ChannelEvents::ReceivePacket {
+                height,
+                packet: packet.clone(),
+                channel_ordering: Some(Order::Unordered),
+                connection_id: Some(ConnectionId::new(2)),
+            }),
+            IbcEvent::WriteAcknowledgement(ChannelEvents::WriteAcknowledgement {
+                height,
+                packet: packet.clone(),
+                ack: vec![0x01, 0xfe],
+                connection_id: Some(ConnectionId::new(2)),
+            }),
+            IbcEvent::AcknowledgePacket(ChannelEvents::AcknowledgePacket {
+                height,
+                packet: packet_without_data.clone(),
+                channel_ordering: Some(Order::Unordered),
+                connection_id: Some(ConnectionId::new(2)),
+            }),
+            IbcEvent::TimeoutPacket(ChannelEvents::TimeoutPacket {
+                height,
+                packet: packet_without_data.clone(),
+                channel_ordering: Some(Order::Ordered),
+            }),
+            IbcEvent::TimeoutOnClosePacket(ChannelEvents::TimeoutOnClosePacket {
+                height,
+                packet: packet_without_data,
+                channel_ordering: Some(Order::Ordered),
+            }),
+            IbcEvent::Transfer(Ics20Events::Transfer {
+                height,
+                sender: get_dummy_account_id(),
+                receiver: get_dummy_account_id(),
+            }),
+            IbcEvent::FungibleTokenPacket(Ics20Events::FungibleTokenPacket {
+                height,
+                receiver: get_dummy_account_id(),
+                denom: "transfer/channel-6/uatom".to_string(),
+                amount: 100,
+                success: false,
+                acknowledgement: None,
+            }),
+            IbcEvent::FungibleTokenPacket(Ics20Events::FungibleTokenPacket {
+                height,
+                receiver: get_dummy_account_id(),
+                denom: "uatom".to_string(),
+                amount: 100,
+                success: false,
+                acknowledgement: Some(Acknowledgement::error("insufficient funds")),
+            }),
+            IbcEvent::DenominationTrace(Ics20Events::DenominationTrace {
+                height,
+                trace_hash: "27A6394C3F9FF9C9DCF5DFFADF9BB5FE9A37C7E92B006199894CF1824DF9AC7C"
+                    .to_string(),
+                denom: "transfer/channel-6/uatom".to_string(),
+            }),
+            IbcEvent::TransferTimeout(Ics20Events::Timeout {
+                height,
+                refund_receiver: get_dummy_account_id(),
+                refund_denom: "uatom".to_string(),
+                refund_amount: 100,
+            }),
+            IbcEvent::Empty("no events".to_string()),
+            IbcEvent::ChainError("out of gas".to_string()),
+        ]
+    }
+
+    #[test]
+    fn abci_event_round_trip() {
+        for event in all_events() {
+            let abci_event = AbciEvent::from(event.clone());
+            let mut decoded = IbcEvent::try_from(&abci_event)
+                .unwrap_or_else(|e| panic!("failed to decode {:?}: {}", abci_event, e));
+            // ABCI events carry no height.
+            decoded.set_height(event.height());
+            assert_eq!(decoded.to_json(), event.to_json());
+        }
+    }
+
+    #[test]
+    fn abci_event_attribute_keys() {
+        let events = all_events();
+        let keys = |event: &IbcEvent| -> (String, Vec<String>) {
+            let abci_event = AbciEvent::from(event.clone());
+            let keys = abci_event
+                .attributes
+                .iter()
+                .map(|tag| tag.key.as_ref().to_string())
+                .collect();
+            (abci_event.type_str, keys)
+        };
+
+        let (event_type, attribute_keys) = keys(&events[1]);
+        assert_eq!(event_type, "create_client");
+        assert_eq!(
+            attribute_keys,
+            vec!["client_id", "client_type", "consensus_height"]
+        );
+
+        let (event_type, attribute_keys) = keys(&events[5]);
+        assert_eq!(event_type, "update_client_proposal");
+        assert_eq!(
+            attribute_keys,
+            vec!["subject_client_id", "client_type", "consensus_height"]
+        );
+
+        let (event_type, attribute_keys) = keys(&events[7]);
+        assert_eq!(event_type, "connection_open_try");
+        assert_eq!(
+            attribute_keys,
+            vec![
+                "connection_id",
+                "client_id",
+                "counterparty_connection_id",
+                "counterparty_client_id"
+            ]
+        );
+
+        let (event_type, attribute_keys) = keys(&events[16]);
+        assert_eq!(event_type, "send_packet");
+        assert_eq!(
+            attribute_keys,
+            vec![
+                "packet_data",
+                "packet_data_hex",
+                "packet_timeout_height",
+                "packet_timeout_timestamp",
+                "packet_sequence",
+                "packet_src_port",
+                "packet_src_channel",
+                "packet_dst_port",
+                "packet_dst_channel",
+                "packet_channel_ordering",
+                "packet_connection"
+            ]
+        );
+
+        let (event_type, attribute_keys) = keys(&events[18]);
+        assert_eq!(event_type, "write_acknowledgement");
+        assert_eq!(
+            attribute_keys,
+            vec![
+                "packet_data",
+                "packet_data_hex",
+                "packet_timeout_height",
+                "packet_timeout_timestamp",
+                "packet_sequence",
+                "packet_src_port",
+                "packet_src_channel",
+                "packet_dst_port",
+                "packet_dst_channel",
+                "packet_ack",
+                "packet_ack_hex",
+                "packet_connection"
+            ]
+        );
+
+        let (event_type, attribute_keys) = keys(&events[19]);
+        assert_eq!(event_type, "acknowledge_packet");
+        assert_eq!(
+            attribute_keys,
+            vec![
+                "packet_timeout_height",
+                "packet_timeout_timestamp",
+                "packet_sequence",
+                "packet_src_port",
+                "packet_src_channel",
+                "packet_dst_port",
+                "packet_dst_channel",
+                "packet_channel_ordering",
+                "packet_connection"
+            ]
+        );
+
+        let (event_type, attribute_keys) = keys(&events[20]);
+        assert_eq!(event_type, "timeout_packet");
+        assert_eq!(
+            attribute_keys,
+            vec![
+                "packet_timeout_height",
+                "packet_timeout_timestamp",
+                "packet_sequence",
+                "packet_src_port",
+                "packet_src_channel",
+                "packet_dst_port",
+                "packet_dst_channel",
+                "packet_channel_ordering"
+            ]
+        );
+
+        let (event_type, attribute_keys) = keys(&events[22]);
+        assert_eq!(event_type, "ibc_transfer");
+        assert_eq!(attribute_keys, vec!["sender", "receiver"]);
+
+        let (event_type, attribute_keys) = keys(&events[23]);
+        assert_eq!(event_type, "fungible_token_packet");
+        assert_eq!(
+            attribute_keys,
+            vec!["module", "receiver", "denom", "amount", "success"]
+        );
+
+        let (event_type, attribute_keys) = keys(&events[24]);
+        assert_eq!(event_type, "fungible_token_packet");
+        assert_eq!(
+            attribute_keys,
+            vec!["module", "receiver", "denom", "amount", "acknowledgement"]
+        );
+    }
+
+    #[test]
+    fn abci_events_without_optional_attributes_are_decoded() {
+        let optional = ["packet_channel_ordering", "packet_connection"];
+        for event in all_events().into_iter().skip(16).take(6) {
+            let mut abci_event = AbciEvent::from(event);
+            abci_event
+                .attributes
+                .retain(|tag| !optional.contains(&tag.key.as_ref()));
+            assert!(IbcEvent::try_from(&abci_event).is_ok());
+        }
+    }
+
+    #[test]
+    fn malformed_abci_events_are_rejected() {
+        let unknown = AbciEvent {
+            type_str: "unknown".to_string(),
+            attributes: vec![],
+        };
+        assert!(IbcEvent::try_from(&unknown).is_err());
+
+        let mut missing_attribute = AbciEvent::from(all_events().remove(1));
+        missing_attribute.attributes.pop();
+        assert!(IbcEvent::try_from(&missing_attribute).is_err());
+
+        let bad_height = AbciEvent {
+            type_str: "new_block".to_string(),
+            attributes: vec![super::tag("height", "42")],
+        };
+        assert!(IbcEvent::try_from(&bad_height).is_err());
+    }
+}
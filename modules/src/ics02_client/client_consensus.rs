@@ -12,7 +12,9 @@ use crate::events::IbcEventType;
 use crate::ics02_client::client_type::ClientType;
 use crate::ics02_client::error::{Error, Kind};
 use crate::ics02_client::height::Height;
+use crate::ics06_solomachine::consensus_state as solomachine_consensus_state;
 use crate::ics07_tendermint::consensus_state;
+use crate::ics09_localhost::consensus_state as localhost_consensus_state;
 use crate::ics23_commitment::commitment::CommitmentRoot;
 use crate::ics24_host::identifier::ClientId;
 use crate::timestamp::Timestamp;
@@ -24,6 +26,12 @@ use crate::mock::client_state::MockConsensusState;
 pub const TENDERMINT_CONSENSUS_STATE_TYPE_URL: &str =
     "/ibc.lightclients.tendermint.v1.ConsensusState";
 
+pub const SOLOMACHINE_CONSENSUS_STATE_TYPE_URL: &str =
+    "/ibc.lightclients.solomachine.v1.ConsensusState";
+
+pub const LOCALHOST_CONSENSUS_STATE_TYPE_URL: &str =
+    "/ibc.lightclients.localhost.v1.ConsensusState";
+
 pub const MOCK_CONSENSUS_STATE_TYPE_URL: &str = "/ibc.mock.ConsensusState";
 
 #[dyn_clonable::clonable]
@@ -45,6 +53,8 @@ pub trait ConsensusState: Clone + std::fmt::Debug + Send + Sync {
 #[serde(tag = "type")]
 pub enum AnyConsensusState {
     Tendermint(consensus_state::ConsensusState),
+    SoloMachine(solomachine_consensus_state::ConsensusState),
+    Localhost(localhost_consensus_state::ConsensusState),
 
     #[cfg(any(test, feature = "mocks"))]
     Mock(MockConsensusState),
@@ -57,6 +67,8 @@ impl AnyConsensusState {
                 let date: DateTime<Utc> = cs_state.timestamp.into();
                 Timestamp::from_datetime(date)
             }
+            Self::SoloMachine(sm_state) => sm_state.timestamp(),
+            Self::Localhost(lh_state) => lh_state.timestamp(),
 
             #[cfg(any(test, feature = "mocks"))]
             Self::Mock(mock_state) => mock_state.timestamp(),
@@ -66,6 +78,8 @@ impl AnyConsensusState {
     pub fn client_type(&self) -> ClientType {
         match self {
             AnyConsensusState::Tendermint(_cs) => ClientType::Tendermint,
+            AnyConsensusState::SoloMachine(_cs) => ClientType::SoloMachine,
+            AnyConsensusState::Localhost(_cs) => ClientType::Localhost,
 
             #[cfg(any(test, feature = "mocks"))]
             AnyConsensusState::Mock(_cs) => ClientType::Mock,
@@ -73,6 +87,19 @@ impl AnyConsensusState {
     }
 }
 
+/// Mock consensus states are generated half of the time, like mock client states.
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for AnyConsensusState {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(match u.int_in_range(0..=5)? {
+            0 => Self::Tendermint(u.arbitrary()?),
+            1 => Self::SoloMachine(u.arbitrary()?),
+            2 => Self::Localhost(u.arbitrary()?),
+            _ => Self::Mock(u.arbitrary()?),
+        })
+    }
+}
+
 impl Protobuf<Any> for AnyConsensusState {}
 
 impl TryFrom<Any> for AnyConsensusState {
@@ -87,6 +114,16 @@ impl TryFrom<Any> for AnyConsensusState {
                     .map_err(|e| Kind::InvalidRawConsensusState.context(e))?,
             )),
 
+            SOLOMACHINE_CONSENSUS_STATE_TYPE_URL => Ok(AnyConsensusState::SoloMachine(
+                solomachine_consensus_state::ConsensusState::decode_vec(&value.value)
+                    .map_err(|e| Kind::InvalidRawConsensusState.context(e))?,
+            )),
+
+            LOCALHOST_CONSENSUS_STATE_TYPE_URL => Ok(AnyConsensusState::Localhost(
+                localhost_consensus_state::ConsensusState::decode_vec(&value.value)
+                    .map_err(|e| Kind::InvalidRawConsensusState.context(e))?,
+            )),
+
             #[cfg(any(test, feature = "mocks"))]
             MOCK_CONSENSUS_STATE_TYPE_URL => Ok(AnyConsensusState::Mock(
                 MockConsensusState::decode_vec(&value.value)
@@ -107,6 +144,18 @@ impl From<AnyConsensusState> for Any {
                     .encode_vec()
                     .expect("encoding to `Any` from `AnyConsensusState::Tendermint`"),
             },
+            AnyConsensusState::SoloMachine(value) => Any {
+                type_url: SOLOMACHINE_CONSENSUS_STATE_TYPE_URL.to_string(),
+                value: value
+                    .encode_vec()
+                    .expect("encoding to `Any` from `AnyConsensusState::SoloMachine`"),
+            },
+            AnyConsensusState::Localhost(value) => Any {
+                type_url: LOCALHOST_CONSENSUS_STATE_TYPE_URL.to_string(),
+                value: value
+                    .encode_vec()
+                    .expect("encoding to `Any` from `AnyConsensusState::Localhost`"),
+            },
             #[cfg(any(test, feature = "mocks"))]
             AnyConsensusState::Mock(value) => Any {
                 type_url: MOCK_CONSENSUS_STATE_TYPE_URL.to_string(),
@@ -163,11 +212,25 @@ impl ConsensusState for AnyConsensusState {
     }
 
     fn root(&self) -> &CommitmentRoot {
-        todo!()
+        match self {
+            Self::Tendermint(cs_state) => cs_state.root(),
+            Self::SoloMachine(sm_state) => sm_state.root(),
+            Self::Localhost(lh_state) => lh_state.root(),
+
+            #[cfg(any(test, feature = "mocks"))]
+            Self::Mock(mock_state) => mock_state.root(),
+        }
     }
 
     fn validate_basic(&self) -> Result<(), Box<dyn std::error::Error>> {
-        todo!()
+        match self {
+            Self::Tendermint(cs_state) => cs_state.validate_basic(),
+            Self::SoloMachine(sm_state) => sm_state.validate_basic(),
+            Self::Localhost(lh_state) => lh_state.validate_basic(),
+
+            #[cfg(any(test, feature = "mocks"))]
+            Self::Mock(mock_state) => mock_state.validate_basic(),
+        }
     }
 
     fn wrap_any(self) -> AnyConsensusState {
@@ -4,12 +4,19 @@ use crate::downcast;
 use crate::ics02_client::client_consensus::{AnyConsensusState, ConsensusState};
 use crate::ics02_client::client_state::{AnyClientState, ClientState};
 use crate::ics02_client::client_type::ClientType;
+use crate::ics02_client::context::ClientReader;
 use crate::ics02_client::error::Kind;
 use crate::ics02_client::header::{AnyHeader, Header};
+use crate::ics02_client::misbehaviour::{AnyMisbehaviour, Misbehaviour};
 use crate::ics03_connection::connection::ConnectionEnd;
+use crate::ics03_connection::context::ConnectionReader;
 use crate::ics04_channel::channel::ChannelEnd;
+use crate::ics04_channel::commitment::PacketCommitment;
+use crate::ics04_channel::context::ChannelReader;
 use crate::ics04_channel::packet::Sequence;
+use crate::ics06_solomachine::client_def::SoloMachineClient;
 use crate::ics07_tendermint::client_def::TendermintClient;
+use crate::ics09_localhost::client_def::LocalhostClient;
 use crate::ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes, CommitmentRoot};
 use crate::ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId};
 use crate::Height;
@@ -21,22 +28,56 @@ pub trait ClientDef: Clone {
     type Header: Header;
     type ClientState: ClientState;
     type ConsensusState: ConsensusState;
+    type Misbehaviour: Misbehaviour;
 
-    /// TODO
+    /// Verifies the `header` against the trusted state of client `client_id`, as recorded in
+    /// `ctx`. Returns the updated client state and the consensus state derived from `header`.
     fn check_header_and_update_state(
         &self,
+        ctx: &dyn ClientReader,
+        client_id: ClientId,
         client_state: Self::ClientState,
         header: Self::Header,
     ) -> Result<(Self::ClientState, Self::ConsensusState), Box<dyn std::error::Error>>;
 
+    /// Checks that `misbehaviour` is valid evidence of misbehaviour of the chain tracked by client
+    /// `client_id`, against the trusted states recorded in `ctx`. Returns the client state,
+    /// frozen at the height of the misbehaviour.
+    fn check_misbehaviour_and_update_state(
+        &self,
+        ctx: &dyn ClientReader,
+        client_id: ClientId,
+        client_state: Self::ClientState,
+        misbehaviour: Self::Misbehaviour,
+    ) -> Result<Self::ClientState, Box<dyn std::error::Error>>;
+
+    /// Verifies the proofs that the chain tracked by client `client_id` committed to upgrade to
+    /// `upgraded_client_state` and `upgraded_consensus_state`, against the latest consensus state
+    /// of the client. Returns the client and consensus states of the upgraded client.
+    #[allow(clippy::too_many_arguments)]
     fn verify_upgrade_and_update_state(
         &self,
+        ctx: &dyn ClientReader,
+        client_id: &ClientId,
         client_state: &Self::ClientState,
-        consensus_state: &Self::ConsensusState,
+        upgraded_client_state: &Self::ClientState,
+        upgraded_consensus_state: &Self::ConsensusState,
         proof_upgrade_client: MerkleProof,
         proof_upgrade_consensus_state: MerkleProof,
     ) -> Result<(Self::ClientState, Self::ConsensusState), Box<dyn std::error::Error>>;
 
+    /// Checks that client `subject_client_id`, which is frozen or expired, may be recovered with
+    /// the state of an active substitute client, as permitted by the parameters of the subject.
+    /// Returns the client state of the recovered subject, which tracks the chain from the latest
+    /// height of the substitute.
+    fn check_substitute_and_update_state(
+        &self,
+        ctx: &dyn ClientReader,
+        subject_client_id: &ClientId,
+        subject_client_state: &Self::ClientState,
+        substitute_client_state: &Self::ClientState,
+    ) -> Result<Self::ClientState, Box<dyn std::error::Error>>;
+
     /// Verification functions as specified in:
     /// https://github.com/cosmos/ics/tree/master/spec/ics-002-client-semantics
     ///
@@ -47,22 +88,27 @@ pub trait ClientDef: Clone {
     #[allow(clippy::too_many_arguments)]
     fn verify_client_consensus_state(
         &self,
+        ctx: &dyn ConnectionReader,
         client_state: &Self::ClientState,
         height: Height,
         prefix: &CommitmentPrefix,
         proof: &CommitmentProofBytes,
+        root: &CommitmentRoot,
         client_id: &ClientId,
         consensus_height: Height,
         expected_consensus_state: &AnyConsensusState,
     ) -> Result<(), Box<dyn std::error::Error>>;
 
     /// Verify a `proof` that a connection state matches that of the input `connection_end`.
+    #[allow(clippy::too_many_arguments)]
     fn verify_connection_state(
         &self,
+        ctx: &dyn ConnectionReader,
         client_state: &Self::ClientState,
         height: Height,
         prefix: &CommitmentPrefix,
         proof: &CommitmentProofBytes,
+        root: &CommitmentRoot,
         connection_id: Option<&ConnectionId>,
         expected_connection_end: &ConnectionEnd,
     ) -> Result<(), Box<dyn std::error::Error>>;
@@ -71,10 +117,12 @@ pub trait ClientDef: Clone {
     #[allow(clippy::too_many_arguments)]
     fn verify_channel_state(
         &self,
+        ctx: &dyn ChannelReader,
         client_state: &Self::ClientState,
         height: Height,
         prefix: &CommitmentPrefix,
         proof: &CommitmentProofBytes,
+        root: &CommitmentRoot,
         port_id: &PortId,
         channel_id: &ChannelId,
         expected_channel_end: &ChannelEnd,
@@ -84,6 +132,7 @@ pub trait ClientDef: Clone {
     #[allow(clippy::too_many_arguments)]
     fn verify_client_full_state(
         &self,
+        ctx: &dyn ConnectionReader,
         _client_state: &Self::ClientState,
         height: Height,
         root: &CommitmentRoot,
@@ -97,22 +146,28 @@ pub trait ClientDef: Clone {
     #[allow(clippy::too_many_arguments)]
     fn verify_packet_data(
         &self,
+        ctx: &dyn ChannelReader,
         client_state: &Self::ClientState,
         height: Height,
+        prefix: &CommitmentPrefix,
         proof: &CommitmentProofBytes,
+        root: &CommitmentRoot,
         port_id: &PortId,
         channel_id: &ChannelId,
         seq: &Sequence,
-        commitment: String,
+        commitment: PacketCommitment,
     ) -> Result<(), Box<dyn std::error::Error>>;
 
     /// Verify a `proof` that a packet has been commited.
     #[allow(clippy::too_many_arguments)]
     fn verify_packet_acknowledgement(
         &self,
+        ctx: &dyn ChannelReader,
         client_state: &Self::ClientState,
         height: Height,
+        prefix: &CommitmentPrefix,
         proof: &CommitmentProofBytes,
+        root: &CommitmentRoot,
         port_id: &PortId,
         channel_id: &ChannelId,
         seq: &Sequence,
@@ -123,9 +178,12 @@ pub trait ClientDef: Clone {
     #[allow(clippy::too_many_arguments)]
     fn verify_next_sequence_recv(
         &self,
+        ctx: &dyn ChannelReader,
         client_state: &Self::ClientState,
         height: Height,
+        prefix: &CommitmentPrefix,
         proof: &CommitmentProofBytes,
+        root: &CommitmentRoot,
         port_id: &PortId,
         channel_id: &ChannelId,
         seq: &Sequence,
@@ -135,18 +193,36 @@ pub trait ClientDef: Clone {
     #[allow(clippy::too_many_arguments)]
     fn verify_packet_receipt_absence(
         &self,
+        ctx: &dyn ChannelReader,
         client_state: &Self::ClientState,
         height: Height,
+        prefix: &CommitmentPrefix,
         proof: &CommitmentProofBytes,
+        root: &CommitmentRoot,
         port_id: &PortId,
         channel_id: &ChannelId,
         seq: &Sequence,
     ) -> Result<(), Box<dyn std::error::Error>>;
+
+    /// Returns the state of the client once it verified `proof`, a proof at `height` which passed
+    /// one of the verification functions above, or `None` if verifying proofs does not change
+    /// the client. Solo machine clients, for instance, advance their sequence with each signature
+    /// they verify, so that signatures cannot be replayed.
+    fn client_state_after_verification(
+        &self,
+        _client_state: &Self::ClientState,
+        _height: Height,
+        _proof: &CommitmentProofBytes,
+    ) -> Result<Option<Self::ClientState>, Box<dyn std::error::Error>> {
+        Ok(None)
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum AnyClient {
     Tendermint(TendermintClient),
+    SoloMachine(SoloMachineClient),
+    Localhost(LocalhostClient),
 
     #[cfg(any(test, feature = "mocks"))]
     Mock(MockClient),
@@ -156,6 +232,8 @@ impl AnyClient {
     pub fn from_client_type(client_type: ClientType) -> AnyClient {
         match client_type {
             ClientType::Tendermint => Self::Tendermint(TendermintClient),
+            ClientType::SoloMachine => Self::SoloMachine(SoloMachineClient),
+            ClientType::Localhost => Self::Localhost(LocalhostClient),
 
             #[cfg(any(test, feature = "mocks"))]
             ClientType::Mock => Self::Mock(MockClient),
@@ -168,10 +246,13 @@ impl ClientDef for AnyClient {
     type Header = AnyHeader;
     type ClientState = AnyClientState;
     type ConsensusState = AnyConsensusState;
+    type Misbehaviour = AnyMisbehaviour;
 
     /// Validates an incoming `header` against the latest consensus state of this client.
     fn check_header_and_update_state(
         &self,
+        ctx: &dyn ClientReader,
+        client_id: ClientId,
         client_state: AnyClientState,
         header: AnyHeader,
     ) -> Result<(AnyClientState, AnyConsensusState), Box<dyn std::error::Error>> {
@@ -184,7 +265,7 @@ impl ClientDef for AnyClient {
                 .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Tendermint))?;
 
                 let (new_state, new_consensus) =
-                    client.check_header_and_update_state(client_state, header)?;
+                    client.check_header_and_update_state(ctx, client_id, client_state, header)?;
 
                 Ok((
                     AnyClientState::Tendermint(new_state),
@@ -192,6 +273,38 @@ impl ClientDef for AnyClient {
                 ))
             }
 
+            Self::SoloMachine(client) => {
+                let (client_state, header) = downcast!(
+                    client_state => AnyClientState::SoloMachine,
+                    header => AnyHeader::SoloMachine,
+                )
+                .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::SoloMachine))?;
+
+                let (new_state, new_consensus) =
+                    client.check_header_and_update_state(ctx, client_id, client_state, header)?;
+
+                Ok((
+                    AnyClientState::SoloMachine(new_state),
+                    AnyConsensusState::SoloMachine(new_consensus),
+                ))
+            }
+
+            Self::Localhost(client) => {
+                let (client_state, header) = downcast!(
+                    client_state => AnyClientState::Localhost,
+                    header => AnyHeader::Localhost,
+                )
+                .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Localhost))?;
+
+                let (new_state, new_consensus) =
+                    client.check_header_and_update_state(ctx, client_id, client_state, header)?;
+
+                Ok((
+                    AnyClientState::Localhost(new_state),
+                    AnyConsensusState::Localhost(new_consensus),
+                ))
+            }
+
             #[cfg(any(test, feature = "mocks"))]
             Self::Mock(client) => {
                 let (client_state, header) = downcast!(
@@ -201,7 +314,7 @@ impl ClientDef for AnyClient {
                 .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Mock))?;
 
                 let (new_state, new_consensus) =
-                    client.check_header_and_update_state(client_state, header)?;
+                    client.check_header_and_update_state(ctx, client_id, client_state, header)?;
 
                 Ok((
                     AnyClientState::Mock(new_state),
@@ -211,12 +324,92 @@ impl ClientDef for AnyClient {
         }
     }
 
+    fn check_misbehaviour_and_update_state(
+        &self,
+        ctx: &dyn ClientReader,
+        client_id: ClientId,
+        client_state: AnyClientState,
+        misbehaviour: AnyMisbehaviour,
+    ) -> Result<AnyClientState, Box<dyn std::error::Error>> {
+        match self {
+            Self::Tendermint(client) => {
+                let (client_state, misbehaviour) = downcast!(
+                    client_state => AnyClientState::Tendermint,
+                    misbehaviour => AnyMisbehaviour::Tendermint,
+                )
+                .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Tendermint))?;
+
+                let new_state = client.check_misbehaviour_and_update_state(
+                    ctx,
+                    client_id,
+                    client_state,
+                    misbehaviour,
+                )?;
+
+                Ok(AnyClientState::Tendermint(new_state))
+            }
+
+            Self::SoloMachine(client) => {
+                let (client_state, misbehaviour) = downcast!(
+                    client_state => AnyClientState::SoloMachine,
+                    misbehaviour => AnyMisbehaviour::SoloMachine,
+                )
+                .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::SoloMachine))?;
+
+                let new_state = client.check_misbehaviour_and_update_state(
+                    ctx,
+                    client_id,
+                    client_state,
+                    misbehaviour,
+                )?;
+
+                Ok(AnyClientState::SoloMachine(new_state))
+            }
+
+            Self::Localhost(client) => {
+                let client_state = downcast!(
+                    client_state => AnyClientState::Localhost
+                )
+                .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Localhost))?;
+
+                let new_state = client.check_misbehaviour_and_update_state(
+                    ctx,
+                    client_id,
+                    client_state,
+                    misbehaviour,
+                )?;
+
+                Ok(AnyClientState::Localhost(new_state))
+            }
+
+            #[cfg(any(test, feature = "mocks"))]
+            Self::Mock(client) => {
+                let (client_state, misbehaviour) = downcast!(
+                    client_state => AnyClientState::Mock,
+                    misbehaviour => AnyMisbehaviour::Mock,
+                )
+                .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Mock))?;
+
+                let new_state = client.check_misbehaviour_and_update_state(
+                    ctx,
+                    client_id,
+                    client_state,
+                    misbehaviour,
+                )?;
+
+                Ok(AnyClientState::Mock(new_state))
+            }
+        }
+    }
+
     fn verify_client_consensus_state(
         &self,
+        ctx: &dyn ConnectionReader,
         client_state: &Self::ClientState,
         height: Height,
         prefix: &CommitmentPrefix,
         proof: &CommitmentProofBytes,
+        root: &CommitmentRoot,
         client_id: &ClientId,
         consensus_height: Height,
         expected_consensus_state: &AnyConsensusState,
@@ -229,10 +422,50 @@ impl ClientDef for AnyClient {
                 .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Tendermint))?;
 
                 client.verify_client_consensus_state(
+                    ctx,
+                    client_state,
+                    height,
+                    prefix,
+                    proof,
+                    root,
+                    client_id,
+                    consensus_height,
+                    expected_consensus_state,
+                )
+            }
+
+            Self::SoloMachine(client) => {
+                let client_state = downcast!(
+                    client_state => AnyClientState::SoloMachine
+                )
+                .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::SoloMachine))?;
+
+                client.verify_client_consensus_state(
+                    ctx,
                     client_state,
                     height,
                     prefix,
                     proof,
+                    root,
+                    client_id,
+                    consensus_height,
+                    expected_consensus_state,
+                )
+            }
+
+            Self::Localhost(client) => {
+                let client_state = downcast!(
+                    client_state => AnyClientState::Localhost
+                )
+                .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Localhost))?;
+
+                client.verify_client_consensus_state(
+                    ctx,
+                    client_state,
+                    height,
+                    prefix,
+                    proof,
+                    root,
                     client_id,
                     consensus_height,
                     expected_consensus_state,
@@ -247,10 +480,12 @@ impl ClientDef for AnyClient {
                 .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Mock))?;
 
                 client.verify_client_consensus_state(
+                    ctx,
                     client_state,
                     height,
                     prefix,
                     proof,
+                    root,
                     client_id,
                     consensus_height,
                     expected_consensus_state,
@@ -261,10 +496,12 @@ impl ClientDef for AnyClient {
 
     fn verify_connection_state(
         &self,
+        ctx: &dyn ConnectionReader,
         client_state: &AnyClientState,
         height: Height,
         prefix: &CommitmentPrefix,
         proof: &CommitmentProofBytes,
+        root: &CommitmentRoot,
         connection_id: Option<&ConnectionId>,
         expected_connection_end: &ConnectionEnd,
     ) -> Result<(), Box<dyn std::error::Error>> {
@@ -274,10 +511,44 @@ impl ClientDef for AnyClient {
                     .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Tendermint))?;
 
                 client.verify_connection_state(
+                    ctx,
+                    client_state,
+                    height,
+                    prefix,
+                    proof,
+                    root,
+                    connection_id,
+                    expected_connection_end,
+                )
+            }
+
+            Self::SoloMachine(client) => {
+                let client_state = downcast!(client_state => AnyClientState::SoloMachine)
+                    .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::SoloMachine))?;
+
+                client.verify_connection_state(
+                    ctx,
+                    client_state,
+                    height,
+                    prefix,
+                    proof,
+                    root,
+                    connection_id,
+                    expected_connection_end,
+                )
+            }
+
+            Self::Localhost(client) => {
+                let client_state = downcast!(client_state => AnyClientState::Localhost)
+                    .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Localhost))?;
+
+                client.verify_connection_state(
+                    ctx,
                     client_state,
                     height,
                     prefix,
                     proof,
+                    root,
                     connection_id,
                     expected_connection_end,
                 )
@@ -289,10 +560,12 @@ impl ClientDef for AnyClient {
                     .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Mock))?;
 
                 client.verify_connection_state(
+                    ctx,
                     client_state,
                     height,
                     prefix,
                     proof,
+                    root,
                     connection_id,
                     expected_connection_end,
                 )
@@ -302,10 +575,12 @@ impl ClientDef for AnyClient {
 
     fn verify_channel_state(
         &self,
+        ctx: &dyn ChannelReader,
         client_state: &AnyClientState,
         height: Height,
         prefix: &CommitmentPrefix,
         proof: &CommitmentProofBytes,
+        root: &CommitmentRoot,
         port_id: &PortId,
         channel_id: &ChannelId,
         expected_channel_end: &ChannelEnd,
@@ -316,10 +591,46 @@ impl ClientDef for AnyClient {
                     .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Tendermint))?;
 
                 client.verify_channel_state(
+                    ctx,
+                    client_state,
+                    height,
+                    prefix,
+                    proof,
+                    root,
+                    port_id,
+                    channel_id,
+                    expected_channel_end,
+                )
+            }
+
+            Self::SoloMachine(client) => {
+                let client_state = downcast!(client_state => AnyClientState::SoloMachine)
+                    .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::SoloMachine))?;
+
+                client.verify_channel_state(
+                    ctx,
+                    client_state,
+                    height,
+                    prefix,
+                    proof,
+                    root,
+                    port_id,
+                    channel_id,
+                    expected_channel_end,
+                )
+            }
+
+            Self::Localhost(client) => {
+                let client_state = downcast!(client_state => AnyClientState::Localhost)
+                    .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Localhost))?;
+
+                client.verify_channel_state(
+                    ctx,
                     client_state,
                     height,
                     prefix,
                     proof,
+                    root,
                     port_id,
                     channel_id,
                     expected_channel_end,
@@ -332,10 +643,12 @@ impl ClientDef for AnyClient {
                     .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Mock))?;
 
                 client.verify_channel_state(
+                    ctx,
                     client_state,
                     height,
                     prefix,
                     proof,
+                    root,
                     port_id,
                     channel_id,
                     expected_channel_end,
@@ -346,6 +659,7 @@ impl ClientDef for AnyClient {
 
     fn verify_client_full_state(
         &self,
+        ctx: &dyn ConnectionReader,
         client_state: &Self::ClientState,
         height: Height,
         root: &CommitmentRoot,
@@ -362,6 +676,43 @@ impl ClientDef for AnyClient {
                 .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Tendermint))?;
 
                 client.verify_client_full_state(
+                    ctx,
+                    client_state,
+                    height,
+                    root,
+                    prefix,
+                    client_id,
+                    proof,
+                    client_state_on_counterparty,
+                )
+            }
+
+            Self::SoloMachine(client) => {
+                let client_state = downcast!(
+                    client_state => AnyClientState::SoloMachine
+                )
+                .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::SoloMachine))?;
+
+                client.verify_client_full_state(
+                    ctx,
+                    client_state,
+                    height,
+                    root,
+                    prefix,
+                    client_id,
+                    proof,
+                    client_state_on_counterparty,
+                )
+            }
+
+            Self::Localhost(client) => {
+                let client_state = downcast!(
+                    client_state => AnyClientState::Localhost
+                )
+                .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Localhost))?;
+
+                client.verify_client_full_state(
+                    ctx,
                     client_state,
                     height,
                     root,
@@ -380,6 +731,7 @@ impl ClientDef for AnyClient {
                 .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Mock))?;
 
                 client.verify_client_full_state(
+                    ctx,
                     client_state,
                     height,
                     root,
@@ -393,13 +745,16 @@ impl ClientDef for AnyClient {
     }
     fn verify_packet_data(
         &self,
+        ctx: &dyn ChannelReader,
         client_state: &Self::ClientState,
         height: Height,
+        prefix: &CommitmentPrefix,
         proof: &CommitmentProofBytes,
+        root: &CommitmentRoot,
         port_id: &PortId,
         channel_id: &ChannelId,
         seq: &Sequence,
-        commitment: String,
+        commitment: PacketCommitment,
     ) -> Result<(), Box<dyn std::error::Error>> {
         match self {
             Self::Tendermint(client) => {
@@ -409,9 +764,52 @@ impl ClientDef for AnyClient {
                 .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Tendermint))?;
 
                 client.verify_packet_data(
+                    ctx,
+                    client_state,
+                    height,
+                    prefix,
+                    proof,
+                    root,
+                    port_id,
+                    channel_id,
+                    seq,
+                    commitment,
+                )
+            }
+
+            Self::SoloMachine(client) => {
+                let client_state = downcast!(
+                    client_state => AnyClientState::SoloMachine
+                )
+                .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::SoloMachine))?;
+
+                client.verify_packet_data(
+                    ctx,
+                    client_state,
+                    height,
+                    prefix,
+                    proof,
+                    root,
+                    port_id,
+                    channel_id,
+                    seq,
+                    commitment,
+                )
+            }
+
+            Self::Localhost(client) => {
+                let client_state = downcast!(
+                    client_state => AnyClientState::Localhost
+                )
+                .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Localhost))?;
+
+                client.verify_packet_data(
+                    ctx,
                     client_state,
                     height,
+                    prefix,
                     proof,
+                    root,
                     port_id,
                     channel_id,
                     seq,
@@ -427,9 +825,12 @@ impl ClientDef for AnyClient {
                 .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Mock))?;
 
                 client.verify_packet_data(
+                    ctx,
                     client_state,
                     height,
+                    prefix,
                     proof,
+                    root,
                     port_id,
                     channel_id,
                     seq,
@@ -441,9 +842,12 @@ impl ClientDef for AnyClient {
 
     fn verify_packet_acknowledgement(
         &self,
+        ctx: &dyn ChannelReader,
         client_state: &Self::ClientState,
         height: Height,
+        prefix: &CommitmentPrefix,
         proof: &CommitmentProofBytes,
+        root: &CommitmentRoot,
         port_id: &PortId,
         channel_id: &ChannelId,
         seq: &Sequence,
@@ -457,9 +861,52 @@ impl ClientDef for AnyClient {
                 .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Tendermint))?;
 
                 client.verify_packet_acknowledgement(
+                    ctx,
                     client_state,
                     height,
+                    prefix,
                     proof,
+                    root,
+                    port_id,
+                    channel_id,
+                    seq,
+                    ack,
+                )
+            }
+
+            Self::SoloMachine(client) => {
+                let client_state = downcast!(
+                    client_state => AnyClientState::SoloMachine
+                )
+                .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::SoloMachine))?;
+
+                client.verify_packet_acknowledgement(
+                    ctx,
+                    client_state,
+                    height,
+                    prefix,
+                    proof,
+                    root,
+                    port_id,
+                    channel_id,
+                    seq,
+                    ack,
+                )
+            }
+
+            Self::Localhost(client) => {
+                let client_state = downcast!(
+                    client_state => AnyClientState::Localhost
+                )
+                .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Localhost))?;
+
+                client.verify_packet_acknowledgement(
+                    ctx,
+                    client_state,
+                    height,
+                    prefix,
+                    proof,
+                    root,
                     port_id,
                     channel_id,
                     seq,
@@ -475,9 +922,12 @@ impl ClientDef for AnyClient {
                 .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Mock))?;
 
                 client.verify_packet_acknowledgement(
+                    ctx,
                     client_state,
                     height,
+                    prefix,
                     proof,
+                    root,
                     port_id,
                     channel_id,
                     seq,
@@ -489,9 +939,12 @@ impl ClientDef for AnyClient {
 
     fn verify_next_sequence_recv(
         &self,
+        ctx: &dyn ChannelReader,
         client_state: &Self::ClientState,
         height: Height,
+        prefix: &CommitmentPrefix,
         proof: &CommitmentProofBytes,
+        root: &CommitmentRoot,
         port_id: &PortId,
         channel_id: &ChannelId,
         seq: &Sequence,
@@ -504,9 +957,50 @@ impl ClientDef for AnyClient {
                 .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Tendermint))?;
 
                 client.verify_next_sequence_recv(
+                    ctx,
                     client_state,
                     height,
+                    prefix,
                     proof,
+                    root,
+                    port_id,
+                    channel_id,
+                    seq,
+                )
+            }
+
+            Self::SoloMachine(client) => {
+                let client_state = downcast!(
+                    client_state => AnyClientState::SoloMachine
+                )
+                .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::SoloMachine))?;
+
+                client.verify_next_sequence_recv(
+                    ctx,
+                    client_state,
+                    height,
+                    prefix,
+                    proof,
+                    root,
+                    port_id,
+                    channel_id,
+                    seq,
+                )
+            }
+
+            Self::Localhost(client) => {
+                let client_state = downcast!(
+                    client_state => AnyClientState::Localhost
+                )
+                .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Localhost))?;
+
+                client.verify_next_sequence_recv(
+                    ctx,
+                    client_state,
+                    height,
+                    prefix,
+                    proof,
+                    root,
                     port_id,
                     channel_id,
                     seq,
@@ -521,9 +1015,12 @@ impl ClientDef for AnyClient {
                 .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Mock))?;
 
                 client.verify_next_sequence_recv(
+                    ctx,
                     client_state,
                     height,
+                    prefix,
                     proof,
+                    root,
                     port_id,
                     channel_id,
                     seq,
@@ -533,9 +1030,12 @@ impl ClientDef for AnyClient {
     }
     fn verify_packet_receipt_absence(
         &self,
+        ctx: &dyn ChannelReader,
         client_state: &Self::ClientState,
         height: Height,
+        prefix: &CommitmentPrefix,
         proof: &CommitmentProofBytes,
+        root: &CommitmentRoot,
         port_id: &PortId,
         channel_id: &ChannelId,
         seq: &Sequence,
@@ -548,9 +1048,50 @@ impl ClientDef for AnyClient {
                 .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Tendermint))?;
 
                 client.verify_packet_receipt_absence(
+                    ctx,
+                    client_state,
+                    height,
+                    prefix,
+                    proof,
+                    root,
+                    port_id,
+                    channel_id,
+                    seq,
+                )
+            }
+
+            Self::SoloMachine(client) => {
+                let client_state = downcast!(
+                    client_state => AnyClientState::SoloMachine
+                )
+                .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::SoloMachine))?;
+
+                client.verify_packet_receipt_absence(
+                    ctx,
                     client_state,
                     height,
+                    prefix,
                     proof,
+                    root,
+                    port_id,
+                    channel_id,
+                    seq,
+                )
+            }
+
+            Self::Localhost(client) => {
+                let client_state = downcast!(
+                    client_state => AnyClientState::Localhost
+                )
+                .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Localhost))?;
+
+                client.verify_packet_receipt_absence(
+                    ctx,
+                    client_state,
+                    height,
+                    prefix,
+                    proof,
+                    root,
                     port_id,
                     channel_id,
                     seq,
@@ -565,9 +1106,12 @@ impl ClientDef for AnyClient {
                 .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Mock))?;
 
                 client.verify_packet_receipt_absence(
+                    ctx,
                     client_state,
                     height,
+                    prefix,
                     proof,
+                    root,
                     port_id,
                     channel_id,
                     seq,
@@ -578,22 +1122,29 @@ impl ClientDef for AnyClient {
 
     fn verify_upgrade_and_update_state(
         &self,
+        ctx: &dyn ClientReader,
+        client_id: &ClientId,
         client_state: &Self::ClientState,
-        consensus_state: &Self::ConsensusState,
+        upgraded_client_state: &Self::ClientState,
+        upgraded_consensus_state: &Self::ConsensusState,
         proof_upgrade_client: MerkleProof,
         proof_upgrade_consensus_state: MerkleProof,
     ) -> Result<(Self::ClientState, Self::ConsensusState), Box<dyn std::error::Error>> {
         match self {
             Self::Tendermint(client) => {
-                let (client_state, consensus_state) = downcast!(
+                let (client_state, (upgraded_client_state, upgraded_consensus_state)) = downcast!(
                     client_state => AnyClientState::Tendermint,
-                    consensus_state => AnyConsensusState::Tendermint,
+                    upgraded_client_state => AnyClientState::Tendermint,
+                    upgraded_consensus_state => AnyConsensusState::Tendermint,
                 )
                 .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Tendermint))?;
 
                 let (new_state, new_consensus) = client.verify_upgrade_and_update_state(
+                    ctx,
+                    client_id,
                     client_state,
-                    consensus_state,
+                    upgraded_client_state,
+                    upgraded_consensus_state,
                     proof_upgrade_client,
                     proof_upgrade_consensus_state,
                 )?;
@@ -604,17 +1155,69 @@ impl ClientDef for AnyClient {
                 ))
             }
 
+            Self::SoloMachine(client) => {
+                let (client_state, (upgraded_client_state, upgraded_consensus_state)) = downcast!(
+                    client_state => AnyClientState::SoloMachine,
+                    upgraded_client_state => AnyClientState::SoloMachine,
+                    upgraded_consensus_state => AnyConsensusState::SoloMachine,
+                )
+                .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::SoloMachine))?;
+
+                let (new_state, new_consensus) = client.verify_upgrade_and_update_state(
+                    ctx,
+                    client_id,
+                    client_state,
+                    upgraded_client_state,
+                    upgraded_consensus_state,
+                    proof_upgrade_client,
+                    proof_upgrade_consensus_state,
+                )?;
+
+                Ok((
+                    AnyClientState::SoloMachine(new_state),
+                    AnyConsensusState::SoloMachine(new_consensus),
+                ))
+            }
+
+            Self::Localhost(client) => {
+                let (client_state, (upgraded_client_state, upgraded_consensus_state)) = downcast!(
+                    client_state => AnyClientState::Localhost,
+                    upgraded_client_state => AnyClientState::Localhost,
+                    upgraded_consensus_state => AnyConsensusState::Localhost,
+                )
+                .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Localhost))?;
+
+                let (new_state, new_consensus) = client.verify_upgrade_and_update_state(
+                    ctx,
+                    client_id,
+                    client_state,
+                    upgraded_client_state,
+                    upgraded_consensus_state,
+                    proof_upgrade_client,
+                    proof_upgrade_consensus_state,
+                )?;
+
+                Ok((
+                    AnyClientState::Localhost(new_state),
+                    AnyConsensusState::Localhost(new_consensus),
+                ))
+            }
+
             #[cfg(any(test, feature = "mocks"))]
             Self::Mock(client) => {
-                let (client_state, consensus_state) = downcast!(
+                let (client_state, (upgraded_client_state, upgraded_consensus_state)) = downcast!(
                     client_state => AnyClientState::Mock,
-                    consensus_state => AnyConsensusState::Mock,
+                    upgraded_client_state => AnyClientState::Mock,
+                    upgraded_consensus_state => AnyConsensusState::Mock,
                 )
                 .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Mock))?;
 
                 let (new_state, new_consensus) = client.verify_upgrade_and_update_state(
+                    ctx,
+                    client_id,
                     client_state,
-                    consensus_state,
+                    upgraded_client_state,
+                    upgraded_consensus_state,
                     proof_upgrade_client,
                     proof_upgrade_consensus_state,
                 )?;
@@ -626,4 +1229,140 @@ impl ClientDef for AnyClient {
             }
         }
     }
+
+    fn check_substitute_and_update_state(
+        &self,
+        ctx: &dyn ClientReader,
+        subject_client_id: &ClientId,
+        subject_client_state: &Self::ClientState,
+        substitute_client_state: &Self::ClientState,
+    ) -> Result<Self::ClientState, Box<dyn std::error::Error>> {
+        match self {
+            Self::Tendermint(client) => {
+                let (subject_client_state, substitute_client_state) = downcast!(
+                    subject_client_state => AnyClientState::Tendermint,
+                    substitute_client_state => AnyClientState::Tendermint,
+                )
+                .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Tendermint))?;
+
+                let new_state = client.check_substitute_and_update_state(
+                    ctx,
+                    subject_client_id,
+                    subject_client_state,
+                    substitute_client_state,
+                )?;
+
+                Ok(AnyClientState::Tendermint(new_state))
+            }
+
+            Self::SoloMachine(client) => {
+                let (subject_client_state, substitute_client_state) = downcast!(
+                    subject_client_state => AnyClientState::SoloMachine,
+                    substitute_client_state => AnyClientState::SoloMachine,
+                )
+                .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::SoloMachine))?;
+
+                let new_state = client.check_substitute_and_update_state(
+                    ctx,
+                    subject_client_id,
+                    subject_client_state,
+                    substitute_client_state,
+                )?;
+
+                Ok(AnyClientState::SoloMachine(new_state))
+            }
+
+            Self::Localhost(client) => {
+                let (subject_client_state, substitute_client_state) = downcast!(
+                    subject_client_state => AnyClientState::Localhost,
+                    substitute_client_state => AnyClientState::Localhost,
+                )
+                .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Localhost))?;
+
+                let new_state = client.check_substitute_and_update_state(
+                    ctx,
+                    subject_client_id,
+                    subject_client_state,
+                    substitute_client_state,
+                )?;
+
+                Ok(AnyClientState::Localhost(new_state))
+            }
+
+            #[cfg(any(test, feature = "mocks"))]
+            Self::Mock(client) => {
+                let (subject_client_state, substitute_client_state) = downcast!(
+                    subject_client_state => AnyClientState::Mock,
+                    substitute_client_state => AnyClientState::Mock,
+                )
+                .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Mock))?;
+
+                let new_state = client.check_substitute_and_update_state(
+                    ctx,
+                    subject_client_id,
+                    subject_client_state,
+                    substitute_client_state,
+                )?;
+
+                Ok(AnyClientState::Mock(new_state))
+            }
+        }
+    }
+    fn client_state_after_verification(
+        &self,
+        client_state: &AnyClientState,
+        height: Height,
+        proof: &CommitmentProofBytes,
+    ) -> Result<Option<AnyClientState>, Box<dyn std::error::Error>> {
+        match self {
+            Self::Tendermint(client) => {
+                let client_state = downcast!(
+                    client_state => AnyClientState::Tendermint
+                )
+                .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Tendermint))?;
+
+                let new_state =
+                    client.client_state_after_verification(client_state, height, proof)?;
+
+                Ok(new_state.map(AnyClientState::Tendermint))
+            }
+
+            Self::SoloMachine(client) => {
+                let client_state = downcast!(
+                    client_state => AnyClientState::SoloMachine
+                )
+                .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::SoloMachine))?;
+
+                let new_state =
+                    client.client_state_after_verification(client_state, height, proof)?;
+
+                Ok(new_state.map(AnyClientState::SoloMachine))
+            }
+
+            Self::Localhost(client) => {
+                let client_state = downcast!(
+                    client_state => AnyClientState::Localhost
+                )
+                .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Localhost))?;
+
+                let new_state =
+                    client.client_state_after_verification(client_state, height, proof)?;
+
+                Ok(new_state.map(AnyClientState::Localhost))
+            }
+
+            #[cfg(any(test, feature = "mocks"))]
+            Self::Mock(client) => {
+                let client_state = downcast!(
+                    client_state => AnyClientState::Mock
+                )
+                .ok_or_else(|| Kind::ClientArgsTypeMismatch(ClientType::Mock))?;
+
+                let new_state =
+                    client.client_state_after_verification(client_state, height, proof)?;
+
+                Ok(new_state.map(AnyClientState::Mock))
+            }
+        }
+    }
 }
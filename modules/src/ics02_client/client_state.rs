@@ -8,18 +8,45 @@ use tendermint_proto::Protobuf;
 
 use ibc_proto::ibc::core::client::v1::IdentifiedClientState;
 
+use crate::ics02_client::client_consensus::AnyConsensusState;
 use crate::ics02_client::client_type::ClientType;
 use crate::ics02_client::error::{Error, Kind};
+use crate::ics06_solomachine::client_state as solomachine_client_state;
 use crate::ics07_tendermint::client_state;
+use crate::ics09_localhost::client_state as localhost_client_state;
 use crate::ics24_host::error::ValidationError;
 use crate::ics24_host::identifier::{ChainId, ClientId};
 #[cfg(any(test, feature = "mocks"))]
 use crate::mock::client_state::MockClientState;
+use crate::timestamp::Timestamp;
 use crate::Height;
 
 pub const TENDERMINT_CLIENT_STATE_TYPE_URL: &str = "/ibc.lightclients.tendermint.v1.ClientState";
+pub const SOLOMACHINE_CLIENT_STATE_TYPE_URL: &str = "/ibc.lightclients.solomachine.v1.ClientState";
+pub const LOCALHOST_CLIENT_STATE_TYPE_URL: &str = "/ibc.lightclients.localhost.v1.ClientState";
 pub const MOCK_CLIENT_STATE_TYPE_URL: &str = "/ibc.mock.ClientState";
 
+/// The status of a client, which determines whether the client can be updated and used to verify
+/// proofs. Only active clients can.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Status {
+    /// The client is neither frozen nor expired.
+    Active,
+    /// The latest consensus state of the client is older than the trusting period of the client,
+    /// or it is missing.
+    Expired,
+    /// The client was frozen upon evidence of misbehaviour.
+    Frozen,
+    /// The status of the client cannot be determined, e.g., because the client does not exist.
+    Unknown,
+}
+
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
 #[dyn_clonable::clonable]
 pub trait ClientState: Clone + std::fmt::Debug + Send + Sync {
     /// Return the chain identifier which this client is serving (i.e., the client is verifying
@@ -35,6 +62,21 @@ pub trait ClientState: Clone + std::fmt::Debug + Send + Sync {
     /// Freeze status of the client
     fn is_frozen(&self) -> bool;
 
+    /// Returns the status of the client, given the current timestamp of the host chain and the
+    /// latest consensus state of the client, if the host still stores it. Clients which cannot
+    /// expire are active unless they are frozen.
+    fn status(
+        &self,
+        _host_timestamp: Timestamp,
+        _latest_consensus_state: Option<&AnyConsensusState>,
+    ) -> Status {
+        if self.is_frozen() {
+            Status::Frozen
+        } else {
+            Status::Active
+        }
+    }
+
     /// Wrap into an `AnyClientState`
     fn wrap_any(self) -> AnyClientState;
 }
@@ -43,6 +85,8 @@ pub trait ClientState: Clone + std::fmt::Debug + Send + Sync {
 #[serde(tag = "type")]
 pub enum AnyClientState {
     Tendermint(client_state::ClientState),
+    SoloMachine(solomachine_client_state::ClientState),
+    Localhost(localhost_client_state::ClientState),
 
     #[cfg(any(test, feature = "mocks"))]
     Mock(MockClientState),
@@ -52,6 +96,8 @@ impl AnyClientState {
     pub fn latest_height(&self) -> Height {
         match self {
             Self::Tendermint(tm_state) => tm_state.latest_height(),
+            Self::SoloMachine(sm_state) => sm_state.latest_height(),
+            Self::Localhost(lh_state) => lh_state.latest_height(),
 
             #[cfg(any(test, feature = "mocks"))]
             Self::Mock(mock_state) => mock_state.latest_height(),
@@ -61,6 +107,8 @@ impl AnyClientState {
     pub fn client_type(&self) -> ClientType {
         match self {
             Self::Tendermint(state) => state.client_type(),
+            Self::SoloMachine(state) => state.client_type(),
+            Self::Localhost(state) => state.client_type(),
 
             #[cfg(any(test, feature = "mocks"))]
             Self::Mock(state) => state.client_type(),
@@ -70,6 +118,10 @@ impl AnyClientState {
     pub fn refresh_period(&self) -> Option<Duration> {
         match self {
             AnyClientState::Tendermint(tm_state) => tm_state.refresh_time(),
+            // Solo machine clients do not expire, hence need no refreshing.
+            AnyClientState::SoloMachine(_) => None,
+            // Localhost clients are updated to the host height, and do not expire either.
+            AnyClientState::Localhost(_) => None,
 
             #[cfg(any(test, feature = "mocks"))]
             AnyClientState::Mock(mock_state) => mock_state.refresh_time(),
@@ -79,11 +131,41 @@ impl AnyClientState {
     pub fn expired(&self, elapsed_since_latest: Duration) -> bool {
         match self {
             AnyClientState::Tendermint(tm_state) => tm_state.expired(elapsed_since_latest),
+            AnyClientState::SoloMachine(_) => false,
+            AnyClientState::Localhost(_) => false,
 
             #[cfg(any(test, feature = "mocks"))]
             AnyClientState::Mock(mock_state) => mock_state.expired(elapsed_since_latest),
         }
     }
+
+    /// The consensus state which the client keeps in its own state, if any. Such clients verify
+    /// all proofs against it, whatever their height, instead of against the consensus states
+    /// which the host stores per height for the other clients. Solo machine clients, for instance,
+    /// only track the current public key of the machine.
+    pub fn own_consensus_state(&self) -> Option<AnyConsensusState> {
+        match self {
+            AnyClientState::SoloMachine(sm_state) => Some(AnyConsensusState::SoloMachine(
+                sm_state.consensus_state.clone(),
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// Mock client states are generated half of the time, as the handlers only accept the generated
+/// proofs through mock clients, which verify none since their headers are generated without a root.
+/// The other light clients still exercise their validation.
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for AnyClientState {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(match u.int_in_range(0..=5)? {
+            0 => Self::Tendermint(u.arbitrary()?),
+            1 => Self::SoloMachine(u.arbitrary()?),
+            2 => Self::Localhost(u.arbitrary()?),
+            _ => Self::Mock(u.arbitrary()?),
+        })
+    }
 }
 
 impl Protobuf<Any> for AnyClientState {}
@@ -100,6 +182,16 @@ impl TryFrom<Any> for AnyClientState {
                     .map_err(|e| Kind::InvalidRawClientState.context(e))?,
             )),
 
+            SOLOMACHINE_CLIENT_STATE_TYPE_URL => Ok(AnyClientState::SoloMachine(
+                solomachine_client_state::ClientState::decode_vec(&raw.value)
+                    .map_err(|e| Kind::InvalidRawClientState.context(e))?,
+            )),
+
+            LOCALHOST_CLIENT_STATE_TYPE_URL => Ok(AnyClientState::Localhost(
+                localhost_client_state::ClientState::decode_vec(&raw.value)
+                    .map_err(|e| Kind::InvalidRawClientState.context(e))?,
+            )),
+
             #[cfg(any(test, feature = "mocks"))]
             MOCK_CLIENT_STATE_TYPE_URL => Ok(AnyClientState::Mock(
                 MockClientState::decode_vec(&raw.value)
@@ -120,6 +212,18 @@ impl From<AnyClientState> for Any {
                     .encode_vec()
                     .expect("encoding to `Any` from `AnyClientState::Tendermint`"),
             },
+            AnyClientState::SoloMachine(value) => Any {
+                type_url: SOLOMACHINE_CLIENT_STATE_TYPE_URL.to_string(),
+                value: value
+                    .encode_vec()
+                    .expect("encoding to `Any` from `AnyClientState::SoloMachine`"),
+            },
+            AnyClientState::Localhost(value) => Any {
+                type_url: LOCALHOST_CLIENT_STATE_TYPE_URL.to_string(),
+                value: value
+                    .encode_vec()
+                    .expect("encoding to `Any` from `AnyClientState::Localhost`"),
+            },
             #[cfg(any(test, feature = "mocks"))]
             AnyClientState::Mock(value) => Any {
                 type_url: MOCK_CLIENT_STATE_TYPE_URL.to_string(),
@@ -135,6 +239,8 @@ impl ClientState for AnyClientState {
     fn chain_id(&self) -> ChainId {
         match self {
             AnyClientState::Tendermint(tm_state) => tm_state.chain_id(),
+            AnyClientState::SoloMachine(sm_state) => sm_state.chain_id(),
+            AnyClientState::Localhost(lh_state) => lh_state.chain_id(),
 
             #[cfg(any(test, feature = "mocks"))]
             AnyClientState::Mock(mock_state) => mock_state.chain_id(),
@@ -152,12 +258,37 @@ impl ClientState for AnyClientState {
     fn is_frozen(&self) -> bool {
         match self {
             AnyClientState::Tendermint(tm_state) => tm_state.is_frozen(),
+            AnyClientState::SoloMachine(sm_state) => sm_state.is_frozen(),
+            AnyClientState::Localhost(lh_state) => lh_state.is_frozen(),
 
             #[cfg(any(test, feature = "mocks"))]
             AnyClientState::Mock(mock_state) => mock_state.is_frozen(),
         }
     }
 
+    fn status(
+        &self,
+        host_timestamp: Timestamp,
+        latest_consensus_state: Option<&AnyConsensusState>,
+    ) -> Status {
+        match self {
+            AnyClientState::Tendermint(tm_state) => {
+                tm_state.status(host_timestamp, latest_consensus_state)
+            }
+            AnyClientState::SoloMachine(sm_state) => {
+                sm_state.status(host_timestamp, latest_consensus_state)
+            }
+            AnyClientState::Localhost(lh_state) => {
+                lh_state.status(host_timestamp, latest_consensus_state)
+            }
+
+            #[cfg(any(test, feature = "mocks"))]
+            AnyClientState::Mock(mock_state) => {
+                mock_state.status(host_timestamp, latest_consensus_state)
+            }
+        }
+    }
+
     fn wrap_any(self) -> AnyClientState {
         self
     }
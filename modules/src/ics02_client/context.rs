@@ -3,11 +3,14 @@
 //! "ADR 003: IBC protocol implementation" for more details.
 
 use crate::ics02_client::client_consensus::AnyConsensusState;
-use crate::ics02_client::client_state::AnyClientState;
+use crate::ics02_client::client_state::{AnyClientState, ClientState, Status};
 use crate::ics02_client::client_type::ClientType;
 use crate::ics02_client::error::Error;
-use crate::ics02_client::handler::ClientResult::{self, Create, Update, Upgrade};
+use crate::ics02_client::handler::ClientResult::{
+    self, Create, Misbehaviour, Recover, Update, Upgrade,
+};
 use crate::ics24_host::identifier::ClientId;
+use crate::timestamp::Timestamp;
 use crate::Height;
 
 /// Defines the read-only part of ICS2 (client functions) context.
@@ -16,6 +19,38 @@ pub trait ClientReader {
     fn client_state(&self, client_id: &ClientId) -> Option<AnyClientState>;
     fn consensus_state(&self, client_id: &ClientId, height: Height) -> Option<AnyConsensusState>;
 
+    /// Returns the heights of all the consensus states stored for the client, in ascending order.
+    fn consensus_state_heights(&self, client_id: &ClientId) -> Vec<Height>;
+
+    /// Returns the status of the client, as determined by its latest consensus state and the
+    /// current timestamp of the host chain, or `Status::Unknown` if there is no such client.
+    fn client_status(&self, client_id: &ClientId) -> Status {
+        match self.client_state(client_id) {
+            Some(client_state) => {
+                let latest_consensus_state =
+                    self.consensus_state(client_id, client_state.latest_height());
+                client_state.status(self.host_timestamp(), latest_consensus_state.as_ref())
+            }
+            None => Status::Unknown,
+        }
+    }
+
+    /// Returns the current timestamp of the local (host) chain. Client updates use it to check
+    /// that the trusted state has not expired and that headers do not come from the future.
+    fn host_timestamp(&self) -> Timestamp;
+
+    /// Returns the current height of the local (host) chain. Localhost clients, which track the
+    /// host chain itself, can only be updated to this height.
+    fn host_height(&self) -> Height;
+
+    /// Returns the time at which the consensus state of the client at `height` was stored on the
+    /// host chain, if any.
+    fn client_update_time(&self, client_id: &ClientId, height: Height) -> Option<Timestamp>;
+
+    /// Returns the height of the host chain at which the consensus state of the client at `height`
+    /// was stored, if any.
+    fn client_update_height(&self, client_id: &ClientId, height: Height) -> Option<Height>;
+
     /// Returns a natural number, counting how many clients have been created thus far.
     /// The value of this counter should increase only via method `ClientKeeper::increase_client_counter`.
     fn client_counter(&self) -> u64;
@@ -31,20 +66,47 @@ pub trait ClientKeeper {
                 self.store_client_type(client_id.clone(), res.client_type)?;
                 self.store_client_state(client_id.clone(), res.client_state.clone())?;
                 self.store_consensus_state(
-                    client_id,
+                    client_id.clone(),
                     res.client_state.latest_height(),
                     res.consensus_state,
                 )?;
+                self.store_update_time(
+                    client_id.clone(),
+                    res.client_state.latest_height(),
+                    res.processed_time,
+                )?;
+                self.store_update_height(
+                    client_id,
+                    res.client_state.latest_height(),
+                    res.processed_height,
+                )?;
                 self.increase_client_counter();
                 Ok(())
             }
             Update(res) => {
+                // Prune the expired consensus states, together with their processing records.
+                for height in res.pruned_heights {
+                    self.delete_consensus_state(res.client_id.clone(), height)?;
+                    self.delete_update_time(res.client_id.clone(), height)?;
+                    self.delete_update_height(res.client_id.clone(), height)?;
+                }
+
                 self.store_client_state(res.client_id.clone(), res.client_state.clone())?;
                 self.store_consensus_state(
                     res.client_id.clone(),
                     res.client_state.latest_height(),
                     res.consensus_state,
                 )?;
+                self.store_update_time(
+                    res.client_id.clone(),
+                    res.client_state.latest_height(),
+                    res.processed_time,
+                )?;
+                self.store_update_height(
+                    res.client_id,
+                    res.client_state.latest_height(),
+                    res.processed_height,
+                )?;
                 Ok(())
             }
             Upgrade(res) => {
@@ -54,6 +116,39 @@ pub trait ClientKeeper {
                     res.client_state.latest_height(),
                     res.consensus_state,
                 )?;
+                self.store_update_time(
+                    res.client_id.clone(),
+                    res.client_state.latest_height(),
+                    res.processed_time,
+                )?;
+                self.store_update_height(
+                    res.client_id,
+                    res.client_state.latest_height(),
+                    res.processed_height,
+                )?;
+                Ok(())
+            }
+            Misbehaviour(res) => {
+                self.store_client_state(res.client_id, res.client_state)?;
+                Ok(())
+            }
+            Recover(res) => {
+                self.store_client_state(res.client_id.clone(), res.client_state.clone())?;
+                self.store_consensus_state(
+                    res.client_id.clone(),
+                    res.client_state.latest_height(),
+                    res.consensus_state,
+                )?;
+                self.store_update_time(
+                    res.client_id.clone(),
+                    res.client_state.latest_height(),
+                    res.processed_time,
+                )?;
+                self.store_update_height(
+                    res.client_id,
+                    res.client_state.latest_height(),
+                    res.processed_height,
+                )?;
                 Ok(())
             }
         }
@@ -66,7 +161,7 @@ pub trait ClientKeeper {
         client_type: ClientType,
     ) -> Result<(), Error>;
 
-    /// Called upon successful client creation and update
+    /// Called upon successful client creation, update and misbehaviour handling
     fn store_client_state(
         &mut self,
         client_id: ClientId,
@@ -81,6 +176,34 @@ pub trait ClientKeeper {
         consensus_state: AnyConsensusState,
     ) -> Result<(), Error>;
 
+    /// Called upon successful client creation, update and upgrade, to record the time of the
+    /// host chain at which the consensus state of the client at `height` was stored.
+    fn store_update_time(
+        &mut self,
+        client_id: ClientId,
+        height: Height,
+        timestamp: Timestamp,
+    ) -> Result<(), Error>;
+
+    /// Called upon successful client creation, update and upgrade, to record the height of the
+    /// host chain at which the consensus state of the client at `height` was stored.
+    fn store_update_height(
+        &mut self,
+        client_id: ClientId,
+        height: Height,
+        host_height: Height,
+    ) -> Result<(), Error>;
+
+    /// Called upon successful client update, to prune an expired consensus state.
+    fn delete_consensus_state(&mut self, client_id: ClientId, height: Height) -> Result<(), Error>;
+
+    /// Called upon the pruning of a consensus state, to delete the time at which it was stored.
+    fn delete_update_time(&mut self, client_id: ClientId, height: Height) -> Result<(), Error>;
+
+    /// Called upon the pruning of a consensus state, to delete the host height at which it was
+    /// stored.
+    fn delete_update_height(&mut self, client_id: ClientId, height: Height) -> Result<(), Error>;
+
     /// Called upon client creation.
     /// Increases the counter which keeps track of how many clients have been created.
     /// Should never fail.
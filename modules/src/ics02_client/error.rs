@@ -3,6 +3,7 @@ use std::num::ParseIntError;
 use anomaly::{BoxError, Context};
 use thiserror::Error;
 
+use crate::ics02_client::client_state::Status;
 use crate::ics02_client::client_type::ClientType;
 use crate::ics23_commitment::error::Error as Ics23Error;
 use crate::ics24_host::error::ValidationKind;
@@ -28,6 +29,9 @@ pub enum Kind {
     #[error("client is frozen: {0}")]
     ClientFrozen(ClientId),
 
+    #[error("client {0} is not active, its status is {1}")]
+    ClientNotActive(ClientId, Status),
+
     #[error("consensus state not found at: {0} at height {1}")]
     ConsensusStateNotFound(ClientId, Height),
 
@@ -85,6 +89,9 @@ pub enum Kind {
     #[error("cannot convert into a `Height` type from string {0}")]
     HeightConversion(String, ParseIntError),
 
+    #[error("invalid height {0}: expected the format `revision_number-revision_height`")]
+    InvalidHeightFormat(String),
+
     #[error("invalid address")]
     InvalidAddress,
 
@@ -109,8 +116,29 @@ pub enum Kind {
     #[error("upgrade verification failed")]
     UpgradeVerificationFailure,
 
+    #[error("misbehaviour handling failed")]
+    MisbehaviourHandlingFailure,
+
     #[error("upgraded client height {0} must be at greater than current client height {1}")]
     LowUpgradeHeight(Height, Height),
+
+    #[error("the subject client {0} cannot be recovered by itself")]
+    SameSubjectAndSubstituteClient(ClientId),
+
+    #[error("the subject client {0} cannot be recovered, as its status is {1}")]
+    InvalidSubjectClientStatus(ClientId, Status),
+
+    #[error("the substitute client {0} must be active, but its status is {1}")]
+    InvalidSubstituteClientStatus(ClientId, Status),
+
+    #[error("the subject client type {0} does not match the substitute client type {1}")]
+    MismatchedSubstituteClientType(ClientType, ClientType),
+
+    #[error("the substitute client height {0} must be greater than the subject client height {1}")]
+    LowSubstituteClientHeight(Height, Height),
+
+    #[error("client recovery failed")]
+    ClientRecoveryFailure,
 }
 
 impl Kind {
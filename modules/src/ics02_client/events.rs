@@ -4,9 +4,11 @@ use std::convert::{TryFrom, TryInto};
 use anomaly::BoxError;
 use serde_derive::{Deserialize, Serialize};
 use subtle_encoding::hex;
+use tendermint::abci::tag::Tag;
+use tendermint::abci::Event as AbciEvent;
 use tendermint_proto::Protobuf;
 
-use crate::events::{IbcEvent, RawObject};
+use crate::events::{extract_attribute, tag, IbcEvent, RawObject};
 use crate::ics02_client::client_type::ClientType;
 use crate::ics02_client::header::AnyHeader;
 use crate::ics02_client::height::Height;
@@ -18,10 +20,15 @@ const CREATE_EVENT_TYPE: &str = "create_client";
 const UPDATE_EVENT_TYPE: &str = "update_client";
 const MISBEHAVIOUR_EVENT_TYPE: &str = "client_misbehaviour";
 const UPGRADE_EVENT_TYPE: &str = "upgrade_client";
+const UPDATE_PROPOSAL_EVENT_TYPE: &str = "update_client_proposal";
 
 /// The content of the `key` field for the attribute containing the client identifier.
 const CLIENT_ID_ATTRIBUTE_KEY: &str = "client_id";
 
+/// The content of the `key` field for the attribute containing the identifier of the client
+/// recovered by a client update proposal.
+const SUBJECT_CLIENT_ID_ATTRIBUTE_KEY: &str = "subject_client_id";
+
 /// The content of the `key` field for the attribute containing the client type.
 const CLIENT_TYPE_ATTRIBUTE_KEY: &str = "client_type";
 
@@ -31,54 +38,73 @@ const CONSENSUS_HEIGHT_ATTRIBUTE_KEY: &str = "consensus_height";
 /// The content of the `key` field for the header in update client event.
 const HEADER: &str = "header";
 
-pub fn try_from_tx(event: &tendermint::abci::Event) -> Option<IbcEvent> {
-    match event.type_str.as_ref() {
-        CREATE_EVENT_TYPE => Some(IbcEvent::CreateClient(CreateClient(
-            extract_attributes_from_tx(event),
-        ))),
-        UPDATE_EVENT_TYPE => Some(IbcEvent::UpdateClient(UpdateClient {
-            common: extract_attributes_from_tx(event),
-            header: extract_header_from_tx(event),
-        })),
-        MISBEHAVIOUR_EVENT_TYPE => Some(IbcEvent::ClientMisbehaviour(ClientMisbehaviour(
-            extract_attributes_from_tx(event),
-        ))),
-        UPGRADE_EVENT_TYPE => Some(IbcEvent::UpgradeClient(UpgradeClient(
-            extract_attributes_from_tx(event),
-        ))),
-        _ => None,
-    }
-}
-
-fn extract_attributes_from_tx(event: &tendermint::abci::Event) -> Attributes {
-    let mut attr = Attributes::default();
-
-    for tag in &event.attributes {
-        let key = tag.key.as_ref();
-        let value = tag.value.as_ref();
-        match key {
-            CLIENT_ID_ATTRIBUTE_KEY => attr.client_id = value.parse().unwrap(),
-            CLIENT_TYPE_ATTRIBUTE_KEY => attr.client_type = value.parse().unwrap(),
-            CONSENSUS_HEIGHT_ATTRIBUTE_KEY => attr.consensus_height = value.parse().unwrap(),
-            // TODO: `Attributes` has 4 fields and we're only parsing 3
-            _ => {}
+/// Parses a client event from the attributes of `event`. Returns `None` if `event` is not a client
+/// event, or if it is malformed.
+pub fn try_from_tx(event: &AbciEvent) -> Option<IbcEvent> {
+    try_from_abci_event(event).ok().flatten()
+}
+
+/// Parses a client event from the attributes of `event`. Returns `None` if `event` is not a client
+/// event, and an error if it is a malformed one.
+pub fn try_from_abci_event(event: &AbciEvent) -> Result<Option<IbcEvent>, BoxError> {
+    let ibc_event = match event.type_str.as_ref() {
+        CREATE_EVENT_TYPE => {
+            IbcEvent::CreateClient(CreateClient(extract_attributes_from_tx(event)?))
         }
-    }
+        UPDATE_EVENT_TYPE => IbcEvent::UpdateClient(UpdateClient {
+            common: extract_attributes_from_tx(event)?,
+            header: extract_header_from_tx(event)?,
+        }),
+        MISBEHAVIOUR_EVENT_TYPE => {
+            IbcEvent::ClientMisbehaviour(ClientMisbehaviour(extract_attributes_from_tx(event)?))
+        }
+        UPGRADE_EVENT_TYPE => {
+            IbcEvent::UpgradeClient(UpgradeClient(extract_attributes_from_tx(event)?))
+        }
+        UPDATE_PROPOSAL_EVENT_TYPE => {
+            IbcEvent::UpdateClientProposal(UpdateClientProposal(Attributes {
+                height: Default::default(),
+                client_id: extract_attribute(event, SUBJECT_CLIENT_ID_ATTRIBUTE_KEY)?.parse()?,
+                client_type: extract_attribute(event, CLIENT_TYPE_ATTRIBUTE_KEY)?.parse()?,
+                consensus_height: extract_attribute(event, CONSENSUS_HEIGHT_ATTRIBUTE_KEY)?
+                    .parse()?,
+            }))
+        }
+        _ => return Ok(None),
+    };
+
+    Ok(Some(ibc_event))
+}
 
-    attr
+fn extract_attributes_from_tx(event: &AbciEvent) -> Result<Attributes, BoxError> {
+    Ok(Attributes {
+        height: Default::default(),
+        client_id: extract_attribute(event, CLIENT_ID_ATTRIBUTE_KEY)?.parse()?,
+        client_type: extract_attribute(event, CLIENT_TYPE_ATTRIBUTE_KEY)?.parse()?,
+        consensus_height: extract_attribute(event, CONSENSUS_HEIGHT_ATTRIBUTE_KEY)?.parse()?,
+    })
 }
 
-pub fn extract_header_from_tx(event: &tendermint::abci::Event) -> Option<AnyHeader> {
-    for tag in &event.attributes {
-        let key = tag.key.as_ref();
-        let value = tag.value.as_ref();
-        if let HEADER = key {
-            let header_bytes = hex::decode(value).unwrap();
-            let header: AnyHeader = Protobuf::decode(header_bytes.as_ref()).unwrap();
-            return Some(header);
+/// Decodes the header of an update client event, if it has one.
+pub fn extract_header_from_tx(event: &AbciEvent) -> Result<Option<AnyHeader>, BoxError> {
+    match extract_attribute(event, HEADER) {
+        Ok(value) => {
+            let header_bytes = hex::decode(value)?;
+            Ok(Some(Protobuf::decode(header_bytes.as_ref())?))
         }
+        Err(_) => Ok(None),
+    }
+}
+
+/// The attributes of a client event, as `ibc-go` emits them. The height is not among them.
+impl From<Attributes> for Vec<Tag> {
+    fn from(attrs: Attributes) -> Self {
+        vec![
+            tag(CLIENT_ID_ATTRIBUTE_KEY, attrs.client_id),
+            tag(CLIENT_TYPE_ATTRIBUTE_KEY, attrs.client_type.as_string()),
+            tag(CONSENSUS_HEIGHT_ATTRIBUTE_KEY, attrs.consensus_height),
+        ]
     }
-    None
 }
 
 /// NewBlock event signals the committing & execution of a new block.
@@ -176,6 +202,15 @@ impl From<CreateClient> for IbcEvent {
     }
 }
 
+impl From<CreateClient> for AbciEvent {
+    fn from(v: CreateClient) -> Self {
+        AbciEvent {
+            type_str: CREATE_EVENT_TYPE.to_string(),
+            attributes: v.0.into(),
+        }
+    }
+}
+
 impl std::fmt::Display for CreateClient {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
         write!(f, "{}", self.0)
@@ -249,6 +284,26 @@ impl From<UpdateClient> for IbcEvent {
     }
 }
 
+impl From<UpdateClient> for AbciEvent {
+    fn from(v: UpdateClient) -> Self {
+        let mut attributes: Vec<Tag> = v.common.into();
+        if let Some(header) = v.header {
+            let header_bytes = header
+                .encode_vec()
+                .expect("encoding the header of an update client event");
+            attributes.push(tag(
+                HEADER,
+                String::from_utf8(hex::encode(header_bytes)).unwrap(),
+            ));
+        }
+
+        AbciEvent {
+            type_str: UPDATE_EVENT_TYPE.to_string(),
+            attributes,
+        }
+    }
+}
+
 impl std::fmt::Display for UpdateClient {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
         write!(f, "{}", self.common)
@@ -272,6 +327,12 @@ impl ClientMisbehaviour {
     }
 }
 
+impl From<Attributes> for ClientMisbehaviour {
+    fn from(attrs: Attributes) -> Self {
+        ClientMisbehaviour(attrs)
+    }
+}
+
 impl TryFrom<RawObject> for ClientMisbehaviour {
     type Error = BoxError;
     fn try_from(obj: RawObject) -> Result<Self, Self::Error> {
@@ -291,11 +352,23 @@ impl From<ClientMisbehaviour> for IbcEvent {
     }
 }
 
+impl From<ClientMisbehaviour> for AbciEvent {
+    fn from(v: ClientMisbehaviour) -> Self {
+        AbciEvent {
+            type_str: MISBEHAVIOUR_EVENT_TYPE.to_string(),
+            attributes: v.0.into(),
+        }
+    }
+}
+
 /// Signals a recent upgrade of an on-chain client (IBC Client).
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
 pub struct UpgradeClient(Attributes);
 
 impl UpgradeClient {
+    pub fn height(&self) -> Height {
+        self.0.height
+    }
     pub fn set_height(&mut self, height: Height) {
         self.0.height = height;
     }
@@ -309,3 +382,61 @@ impl From<Attributes> for UpgradeClient {
         UpgradeClient(attrs)
     }
 }
+
+impl From<UpgradeClient> for IbcEvent {
+    fn from(v: UpgradeClient) -> Self {
+        IbcEvent::UpgradeClient(v)
+    }
+}
+
+impl From<UpgradeClient> for AbciEvent {
+    fn from(v: UpgradeClient) -> Self {
+        AbciEvent {
+            type_str: UPGRADE_EVENT_TYPE.to_string(),
+            attributes: v.0.into(),
+        }
+    }
+}
+
+/// Signals the recovery of a frozen or expired client with the state of a substitute client, once
+/// a client update proposal passed. The client is the subject of the proposal.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+pub struct UpdateClientProposal(Attributes);
+
+impl UpdateClientProposal {
+    pub fn height(&self) -> Height {
+        self.0.height
+    }
+    pub fn set_height(&mut self, height: Height) {
+        self.0.height = height;
+    }
+    pub fn client_id(&self) -> &ClientId {
+        &self.0.client_id
+    }
+}
+
+impl From<Attributes> for UpdateClientProposal {
+    fn from(attrs: Attributes) -> Self {
+        UpdateClientProposal(attrs)
+    }
+}
+
+impl From<UpdateClientProposal> for IbcEvent {
+    fn from(v: UpdateClientProposal) -> Self {
+        IbcEvent::UpdateClientProposal(v)
+    }
+}
+
+/// Unlike the other client events, the client is identified as the subject of the proposal.
+impl From<UpdateClientProposal> for AbciEvent {
+    fn from(v: UpdateClientProposal) -> Self {
+        AbciEvent {
+            type_str: UPDATE_PROPOSAL_EVENT_TYPE.to_string(),
+            attributes: vec![
+                tag(SUBJECT_CLIENT_ID_ATTRIBUTE_KEY, v.0.client_id),
+                tag(CLIENT_TYPE_ATTRIBUTE_KEY, v.0.client_type.as_string()),
+                tag(CONSENSUS_HEIGHT_ATTRIBUTE_KEY, v.0.consensus_height),
+            ],
+        }
+    }
+}
@@ -1,11 +1,15 @@
 //! This module implements the processing logic for ICS2 (client abstractions and functions) msgs.
 
 use crate::handler::HandlerOutput;
+use crate::ics02_client::client_state::Status;
 use crate::ics02_client::context::ClientReader;
-use crate::ics02_client::error::Error;
+use crate::ics02_client::error::{Error, Kind};
 use crate::ics02_client::msgs::ClientMsg;
+use crate::ics24_host::identifier::ClientId;
 
 pub mod create_client;
+pub mod misbehaviour;
+pub mod recover_client;
 pub mod update_client;
 pub mod upgrade_client;
 
@@ -14,6 +18,8 @@ pub enum ClientResult {
     Create(create_client::Result),
     Update(update_client::Result),
     Upgrade(upgrade_client::Result),
+    Misbehaviour(misbehaviour::Result),
+    Recover(recover_client::Result),
 }
 
 /// General entry point for processing any message related to ICS2 (client functions) protocols.
@@ -25,8 +31,16 @@ where
         ClientMsg::CreateClient(msg) => create_client::process(ctx, msg),
         ClientMsg::UpdateClient(msg) => update_client::process(ctx, msg),
         ClientMsg::UpgradeClient(msg) => upgrade_client::process(ctx, msg),
-        _ => {
-            unimplemented!()
-        }
+        ClientMsg::Misbehaviour(msg) => misbehaviour::process(ctx, msg),
+    }
+}
+
+/// Checks that the client `client_id` is active, i.e., neither frozen nor expired, as only active
+/// clients can be updated, upgraded or frozen.
+fn check_client_active(ctx: &dyn ClientReader, client_id: &ClientId) -> Result<(), Error> {
+    match ctx.client_status(client_id) {
+        Status::Active => Ok(()),
+        Status::Frozen => Err(Kind::ClientFrozen(client_id.clone()).into()),
+        status => Err(Kind::ClientNotActive(client_id.clone(), status).into()),
     }
 }
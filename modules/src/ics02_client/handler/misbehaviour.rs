@@ -0,0 +1,234 @@
+//! Protocol logic specific to processing ICS2 messages of type `MsgSubmitAnyMisbehaviour`.
+//!
+use crate::events::IbcEvent;
+use crate::handler::{HandlerOutput, HandlerResult};
+use crate::ics02_client::client_def::{AnyClient, ClientDef};
+use crate::ics02_client::client_state::AnyClientState;
+use crate::ics02_client::context::ClientReader;
+use crate::ics02_client::error::{Error, Kind};
+use crate::ics02_client::events::Attributes;
+use crate::ics02_client::handler::{check_client_active, ClientResult};
+use crate::ics02_client::misbehaviour::Misbehaviour;
+use crate::ics02_client::msgs::misbehavior::MsgSubmitAnyMisbehaviour;
+use crate::ics24_host::identifier::ClientId;
+
+/// The result following the successful processing of a `MsgSubmitAnyMisbehaviour` message.
+/// This data type should be used with a qualified name `misbehaviour::Result` to avoid ambiguity.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Result {
+    pub client_id: ClientId,
+    pub client_state: AnyClientState,
+}
+
+pub fn process(
+    ctx: &dyn ClientReader,
+    msg: MsgSubmitAnyMisbehaviour,
+) -> HandlerResult<ClientResult, Error> {
+    let mut output = HandlerOutput::builder();
+
+    let MsgSubmitAnyMisbehaviour {
+        client_id,
+        misbehaviour,
+        signer: _,
+    } = msg;
+
+    // Read client type from the host chain store. The client should already exist.
+    let client_type = ctx
+        .client_type(&client_id)
+        .ok_or_else(|| Kind::ClientNotFound(client_id.clone()))?;
+
+    let client_def = AnyClient::from_client_type(client_type);
+
+    // Read client state from the host chain store.
+    let client_state = ctx
+        .client_state(&client_id)
+        .ok_or_else(|| Kind::ClientNotFound(client_id.clone()))?;
+
+    check_client_active(ctx, &client_id)?;
+
+    let misbehaviour_height = misbehaviour.height();
+
+    // Check the misbehaviour evidence against the trusted states of the client. This returns the
+    // client state, frozen at the misbehaviour height, to be persisted by the keeper.
+    let new_client_state = client_def
+        .check_misbehaviour_and_update_state(ctx, client_id.clone(), client_state, misbehaviour)
+        .map_err(|e| Kind::MisbehaviourHandlingFailure.context(e.to_string()))?;
+
+    output.log(format!(
+        "Misbehaviour handling: client {} frozen at height {}",
+        client_id, misbehaviour_height
+    ));
+
+    let result = ClientResult::Misbehaviour(Result {
+        client_id: client_id.clone(),
+        client_state: new_client_state,
+    });
+
+    let event_attributes = Attributes {
+        client_id,
+        client_type,
+        consensus_height: misbehaviour_height,
+        height: ctx.host_height(),
+    };
+    output.emit(IbcEvent::ClientMisbehaviour(event_attributes.into()));
+
+    Ok(output.with_result(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use test_env_log::test;
+
+    use crate::events::IbcEvent;
+    use crate::handler::HandlerOutput;
+    use crate::ics02_client::client_state::{AnyClientState, ClientState};
+    use crate::ics02_client::context::ClientKeeper;
+    use crate::ics02_client::error::Kind;
+    use crate::ics02_client::handler::dispatch;
+    use crate::ics02_client::handler::ClientResult::Misbehaviour;
+    use crate::ics02_client::misbehaviour::AnyMisbehaviour;
+    use crate::ics02_client::msgs::misbehavior::MsgSubmitAnyMisbehaviour;
+    use crate::ics02_client::msgs::ClientMsg;
+    use crate::ics24_host::identifier::ClientId;
+    use crate::mock::client_state::MockClientState;
+    use crate::mock::context::MockContext;
+    use crate::mock::header::MockHeader;
+    use crate::mock::misbehaviour::Misbehaviour as MockMisbehaviour;
+    use crate::test_utils::get_dummy_account_id;
+    use crate::timestamp::Timestamp;
+    use crate::Height;
+
+    /// Returns a misbehaviour message for client `client_id`, made of two distinct mock headers
+    /// at `height`.
+    fn misbehaviour_msg(client_id: &ClientId, height: Height) -> MsgSubmitAnyMisbehaviour {
+        let header1 = MockHeader {
+            height,
+            timestamp: Timestamp::now(),
+            root: None,
+        };
+
+        MsgSubmitAnyMisbehaviour {
+            client_id: client_id.clone(),
+            misbehaviour: AnyMisbehaviour::Mock(MockMisbehaviour {
+                client_id: client_id.clone(),
+                header1,
+                header2: MockHeader::new(height),
+            }),
+            signer: get_dummy_account_id(),
+        }
+    }
+
+    #[test]
+    fn test_misbehaviour_ok() {
+        let client_id = ClientId::default();
+        let misbehaviour_height = Height::new(0, 40);
+
+        let ctx = MockContext::default().with_client(&client_id, Height::new(0, 42));
+        let msg = misbehaviour_msg(&client_id, misbehaviour_height);
+
+        let output = dispatch(&ctx, ClientMsg::Misbehaviour(msg));
+
+        match output {
+            Ok(HandlerOutput {
+                result,
+                mut events,
+                log,
+            }) => {
+                assert_eq!(events.len(), 1);
+                let event = events.pop().unwrap();
+                assert!(
+                    matches!(event, IbcEvent::ClientMisbehaviour(e) if e.client_id() == &client_id)
+                );
+                assert!(!log.is_empty());
+                match result {
+                    Misbehaviour(res) => {
+                        assert_eq!(res.client_id, client_id);
+                        assert!(res.client_state.is_frozen());
+                        assert!(matches!(
+                            res.client_state,
+                            AnyClientState::Mock(MockClientState {
+                                frozen_height: Some(h),
+                                ..
+                            }) if h == misbehaviour_height
+                        ));
+                    }
+                    _ => panic!("misbehaviour handler result has incorrect type"),
+                }
+            }
+            Err(err) => {
+                panic!("unexpected error: {}", err);
+            }
+        }
+    }
+
+    #[test]
+    fn test_misbehaviour_nonexisting_client() {
+        let client_id = ClientId::from_str("mockclient1").unwrap();
+
+        let ctx = MockContext::default().with_client(&client_id, Height::new(0, 42));
+        let msg = misbehaviour_msg(
+            &ClientId::from_str("nonexistingclient").unwrap(),
+            Height::new(0, 40),
+        );
+
+        let output = dispatch(&ctx, ClientMsg::Misbehaviour(msg.clone()));
+
+        match output {
+            Ok(_) => {
+                panic!("unexpected success (expected error)");
+            }
+            Err(err) => {
+                assert_eq!(err.kind(), &Kind::ClientNotFound(msg.client_id));
+            }
+        }
+    }
+
+    #[test]
+    fn test_misbehaviour_frozen_client() {
+        let client_id = ClientId::default();
+        let client_height = Height::new(0, 42);
+
+        let mut ctx = MockContext::default().with_client(&client_id, client_height);
+        let frozen_state = MockClientState::new(MockHeader::new(client_height))
+            .with_frozen_height(Height::new(0, 40));
+        ctx.store_client_state(client_id.clone(), frozen_state.into())
+            .unwrap();
+
+        let output = dispatch(
+            &ctx,
+            ClientMsg::Misbehaviour(misbehaviour_msg(&client_id, Height::new(0, 41))),
+        );
+
+        match output {
+            Ok(_) => {
+                panic!("unexpected success (expected error)");
+            }
+            Err(err) => {
+                assert_eq!(err.kind(), &Kind::ClientFrozen(client_id));
+            }
+        }
+    }
+
+    #[test]
+    fn test_misbehaviour_identical_headers() {
+        let client_id = ClientId::default();
+
+        let ctx = MockContext::default().with_client(&client_id, Height::new(0, 42));
+        let mut msg = misbehaviour_msg(&client_id, Height::new(0, 40));
+        if let AnyMisbehaviour::Mock(misbehaviour) = &mut msg.misbehaviour {
+            misbehaviour.header2 = misbehaviour.header1;
+        }
+
+        let output = dispatch(&ctx, ClientMsg::Misbehaviour(msg));
+
+        match output {
+            Ok(_) => {
+                panic!("unexpected success (expected error)");
+            }
+            Err(err) => {
+                assert_eq!(err.kind(), &Kind::MisbehaviourHandlingFailure);
+            }
+        }
+    }
+}
@@ -0,0 +1,393 @@
+//! Protocol logic specific to the recovery of a frozen or expired client with the state of a
+//! substitute client. The recovery is not triggered by a message, but by the host chain itself,
+//! e.g., once a client update proposal passes governance.
+
+use crate::events::IbcEvent;
+use crate::handler::{HandlerOutput, HandlerResult};
+use crate::ics02_client::client_consensus::AnyConsensusState;
+use crate::ics02_client::client_def::{AnyClient, ClientDef};
+use crate::ics02_client::client_state::{AnyClientState, Status};
+use crate::ics02_client::context::ClientReader;
+use crate::ics02_client::error::{Error, Kind};
+use crate::ics02_client::events::{Attributes, UpdateClientProposal};
+use crate::ics02_client::handler::ClientResult;
+use crate::ics24_host::identifier::ClientId;
+use crate::timestamp::Timestamp;
+use crate::Height;
+
+/// The result following the successful recovery of a client.
+/// This data type should be used with a qualified name `recover_client::Result` to avoid ambiguity.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Result {
+    pub client_id: ClientId,
+    pub client_state: AnyClientState,
+    pub consensus_state: AnyConsensusState,
+    pub processed_time: Timestamp,
+    pub processed_height: Height,
+}
+
+/// Recovers client `subject_client_id`, which is frozen or expired, with the state of client
+/// `substitute_client_id`, which is active and of the same type. Whether the subject may be
+/// recovered is up to its client type, e.g., Tendermint clients must allow it in their parameters.
+/// The subject keeps its identifier, hence the connections and channels built on top of it, and
+/// takes the latest height and consensus state of the substitute.
+pub fn process(
+    ctx: &dyn ClientReader,
+    subject_client_id: ClientId,
+    substitute_client_id: ClientId,
+) -> HandlerResult<ClientResult, Error> {
+    let mut output = HandlerOutput::builder();
+
+    if subject_client_id == substitute_client_id {
+        return Err(Kind::SameSubjectAndSubstituteClient(subject_client_id).into());
+    }
+
+    let subject_client_state = ctx
+        .client_state(&subject_client_id)
+        .ok_or_else(|| Kind::ClientNotFound(subject_client_id.clone()))?;
+    let substitute_client_state = ctx
+        .client_state(&substitute_client_id)
+        .ok_or_else(|| Kind::ClientNotFound(substitute_client_id.clone()))?;
+
+    match ctx.client_status(&subject_client_id) {
+        Status::Frozen | Status::Expired => {}
+        status => return Err(Kind::InvalidSubjectClientStatus(subject_client_id, status).into()),
+    }
+    match ctx.client_status(&substitute_client_id) {
+        Status::Active => {}
+        status => {
+            return Err(Kind::InvalidSubstituteClientStatus(substitute_client_id, status).into())
+        }
+    }
+
+    let client_type = ctx
+        .client_type(&subject_client_id)
+        .ok_or_else(|| Kind::ClientNotFound(subject_client_id.clone()))?;
+    let substitute_client_type = ctx
+        .client_type(&substitute_client_id)
+        .ok_or_else(|| Kind::ClientNotFound(substitute_client_id.clone()))?;
+    if client_type != substitute_client_type {
+        return Err(
+            Kind::MismatchedSubstituteClientType(client_type, substitute_client_type).into(),
+        );
+    }
+
+    // The subject must not go back to heights for which it may still store consensus states.
+    let subject_height = subject_client_state.latest_height();
+    let substitute_height = substitute_client_state.latest_height();
+    if substitute_height <= subject_height {
+        return Err(Kind::LowSubstituteClientHeight(substitute_height, subject_height).into());
+    }
+
+    let consensus_state = ctx
+        .consensus_state(&substitute_client_id, substitute_height)
+        .ok_or_else(|| {
+            Kind::ConsensusStateNotFound(substitute_client_id.clone(), substitute_height)
+        })?;
+
+    let client_def = AnyClient::from_client_type(client_type);
+
+    let new_client_state = client_def
+        .check_substitute_and_update_state(
+            ctx,
+            &subject_client_id,
+            &subject_client_state,
+            &substitute_client_state,
+        )
+        .map_err(|e| Kind::ClientRecoveryFailure.context(e.to_string()))?;
+
+    output.log(format!(
+        "client {} recovered with the state of client {}",
+        subject_client_id, substitute_client_id
+    ));
+
+    let result = ClientResult::Recover(Result {
+        client_id: subject_client_id.clone(),
+        client_state: new_client_state,
+        consensus_state,
+        processed_time: ctx.host_timestamp(),
+        processed_height: ctx.host_height(),
+    });
+
+    let event_attributes = Attributes {
+        client_id: subject_client_id,
+        client_type,
+        consensus_height: substitute_height,
+        height: ctx.host_height(),
+    };
+    output.emit(IbcEvent::UpdateClientProposal(UpdateClientProposal::from(
+        event_attributes,
+    )));
+
+    Ok(output.with_result(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use test_env_log::test;
+
+    use crate::events::IbcEvent;
+    use crate::ics02_client::client_state::{AnyClientState, ClientState, Status};
+    use crate::ics02_client::client_type::ClientType;
+    use crate::ics02_client::context::{ClientKeeper, ClientReader};
+    use crate::ics02_client::error::Kind;
+    use crate::ics02_client::handler::dispatch;
+    use crate::ics02_client::handler::recover_client::process;
+    use crate::ics02_client::handler::ClientResult::Recover;
+    use crate::ics02_client::header::AnyHeader;
+    use crate::ics02_client::msgs::update_client::MsgUpdateAnyClient;
+    use crate::ics02_client::msgs::ClientMsg;
+    use crate::ics07_tendermint::client_state::{
+        AllowUpdate, ClientState as TendermintClientState,
+    };
+    use crate::ics07_tendermint::header::Header as TmHeader;
+    use crate::ics24_host::identifier::{ChainId, ClientId};
+    use crate::mock::client_state::MockClientState;
+    use crate::mock::context::MockContext;
+    use crate::mock::header::MockHeader;
+    use crate::mock::host::{HostBlock, HostType};
+    use crate::test_utils::get_dummy_account_id;
+    use crate::timestamp::Timestamp;
+    use crate::Height;
+
+    /// Returns a context (on a mock host chain) with two Tendermint clients tracking the chain
+    /// `chain_id`, which allow updates as given by `allow_update`: the subject at height
+    /// `subject_height` and the substitute at height `substitute_height`.
+    fn context_with_clients(
+        chain_id: &ChainId,
+        subject_client_id: &ClientId,
+        subject_height: Height,
+        substitute_client_id: &ClientId,
+        substitute_height: Height,
+        allow_update: AllowUpdate,
+    ) -> MockContext {
+        let mut ctx = MockContext::new(
+            ChainId::new("mockgaiaA".to_string(), 1),
+            HostType::Mock,
+            5,
+            Height::new(1, 1),
+        )
+        .with_client_parametrized_with_chain_id(
+            chain_id.clone(),
+            subject_client_id,
+            subject_height,
+            Some(ClientType::Tendermint),
+            Some(subject_height),
+        )
+        .with_client_parametrized_with_chain_id(
+            chain_id.clone(),
+            substitute_client_id,
+            substitute_height,
+            Some(ClientType::Tendermint),
+            Some(substitute_height),
+        );
+
+        // The substitute must have the same parameters as the subject.
+        for client_id in &[subject_client_id, substitute_client_id] {
+            let client_state = match ClientReader::client_state(&ctx, client_id) {
+                Some(AnyClientState::Tendermint(client_state)) => client_state,
+                _ => panic!("client {} is not a Tendermint client", client_id),
+            };
+            ctx.store_client_state(
+                (*client_id).clone(),
+                AnyClientState::Tendermint(TendermintClientState {
+                    allow_update,
+                    ..client_state
+                }),
+            )
+            .unwrap();
+        }
+
+        // The timestamp of a synthetic block at height `h` is `h` seconds after the Unix epoch,
+        // and the trusting period of the clients is 64000 seconds.
+        ctx.with_timestamp(Timestamp::from_nanoseconds(64040 * 1_000_000_000).unwrap())
+    }
+
+    #[test]
+    fn test_recover_expired_tendermint_client() {
+        let chain_id = ChainId::new("mockgaiaB".to_string(), 1);
+        let subject_client_id = ClientId::new(ClientType::Tendermint, 0).unwrap();
+        let substitute_client_id = ClientId::new(ClientType::Tendermint, 1).unwrap();
+        let substitute_height = Height::new(1, 64030);
+
+        let mut ctx = context_with_clients(
+            &chain_id,
+            &subject_client_id,
+            Height::new(1, 20),
+            &substitute_client_id,
+            substitute_height,
+            AllowUpdate {
+                after_expiry: true,
+                after_misbehaviour: false,
+            },
+        );
+        assert_eq!(ctx.client_status(&subject_client_id), Status::Expired);
+
+        let output = process(&ctx, subject_client_id.clone(), substitute_client_id).unwrap();
+        assert!(matches!(
+            output.events.as_slice(),
+            [IbcEvent::UpdateClientProposal(e)] if e.client_id() == &subject_client_id
+        ));
+        match &output.result {
+            Recover(res) => {
+                assert_eq!(res.client_id, subject_client_id);
+                assert_eq!(res.client_state.latest_height(), substitute_height);
+                assert!(!res.client_state.is_frozen());
+            }
+            _ => panic!("recover handler result has incorrect type"),
+        }
+        ctx.store_client_result(output.result).unwrap();
+
+        assert_eq!(ctx.client_status(&subject_client_id), Status::Active);
+        assert!(
+            ClientReader::consensus_state(&ctx, &subject_client_id, substitute_height).is_some()
+        );
+
+        // The subject client can be updated again, from the consensus state of the substitute.
+        let update_height = substitute_height.increment();
+        let light_block = HostBlock::generate_tm_block(chain_id, update_height.revision_height);
+        let mut header = TmHeader::from(light_block);
+        header.trusted_height = substitute_height;
+        let msg = MsgUpdateAnyClient {
+            client_id: subject_client_id,
+            header: AnyHeader::Tendermint(header),
+            signer: get_dummy_account_id(),
+        };
+        assert!(dispatch(&ctx, ClientMsg::UpdateClient(msg)).is_ok());
+    }
+
+    #[test]
+    fn test_recover_mock_client() {
+        let subject_client_id = ClientId::new(ClientType::Mock, 0).unwrap();
+        let substitute_client_id = ClientId::new(ClientType::Mock, 1).unwrap();
+        let subject_height = Height::new(0, 10);
+
+        // Returns a context with the subject frozen and the substitute in state `substitute`.
+        let context_with_substitute = |substitute: MockClientState| {
+            let mut ctx = MockContext::default()
+                .with_client(&subject_client_id, subject_height)
+                .with_client(&substitute_client_id, substitute.latest_height());
+            let subject = MockClientState::new(MockHeader::new(subject_height))
+                .with_frozen_height(subject_height);
+            ctx.store_client_state(subject_client_id.clone(), subject.into())
+                .unwrap();
+            ctx.store_client_state(substitute_client_id.clone(), substitute.into())
+                .unwrap();
+            ctx
+        };
+
+        let ctx =
+            context_with_substitute(MockClientState::new(MockHeader::new(Height::new(0, 20))));
+        assert!(process(
+            &ctx,
+            subject_client_id.clone(),
+            substitute_client_id.clone()
+        )
+        .is_ok());
+
+        let rejected_substitutes = vec![
+            // The substitute tracks another revision of the chain.
+            MockClientState::new(MockHeader::new(Height::new(1, 20))),
+            // The substitute tracks a chain which keeps a provable store, unlike the subject.
+            MockClientState::new(MockHeader {
+                root: Some([0; 32]),
+                ..MockHeader::new(Height::new(0, 20))
+            }),
+        ];
+        for substitute in rejected_substitutes {
+            let ctx = context_with_substitute(substitute);
+            let err = process(
+                &ctx,
+                subject_client_id.clone(),
+                substitute_client_id.clone(),
+            )
+            .unwrap_err();
+            assert_eq!(err.kind(), &Kind::ClientRecoveryFailure);
+        }
+    }
+
+    #[test]
+    fn test_recover_client_failures() {
+        let chain_id = ChainId::new("mockgaiaB".to_string(), 1);
+        let subject_client_id = ClientId::new(ClientType::Tendermint, 0).unwrap();
+        let substitute_client_id = ClientId::new(ClientType::Tendermint, 1).unwrap();
+
+        // The subject does not allow updates after expiry.
+        let ctx = context_with_clients(
+            &chain_id,
+            &subject_client_id,
+            Height::new(1, 20),
+            &substitute_client_id,
+            Height::new(1, 64030),
+            AllowUpdate {
+                after_expiry: false,
+                after_misbehaviour: true,
+            },
+        );
+        let err = process(
+            &ctx,
+            subject_client_id.clone(),
+            substitute_client_id.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), &Kind::ClientRecoveryFailure);
+
+        let err = process(&ctx, subject_client_id.clone(), subject_client_id.clone()).unwrap_err();
+        assert_eq!(
+            err.kind(),
+            &Kind::SameSubjectAndSubstituteClient(subject_client_id.clone())
+        );
+
+        // Both clients are active.
+        let ctx = context_with_clients(
+            &chain_id,
+            &subject_client_id,
+            Height::new(1, 64020),
+            &substitute_client_id,
+            Height::new(1, 64030),
+            AllowUpdate {
+                after_expiry: true,
+                after_misbehaviour: true,
+            },
+        );
+        let err = process(
+            &ctx,
+            subject_client_id.clone(),
+            substitute_client_id.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.kind(),
+            &Kind::InvalidSubjectClientStatus(subject_client_id.clone(), Status::Active)
+        );
+
+        // The subject is frozen, but the substitute is not ahead of it.
+        let height = Height::new(1, 64030);
+        let mut ctx = context_with_clients(
+            &chain_id,
+            &subject_client_id,
+            height,
+            &substitute_client_id,
+            height,
+            AllowUpdate {
+                after_expiry: true,
+                after_misbehaviour: true,
+            },
+        );
+        let frozen_client_state = match ClientReader::client_state(&ctx, &subject_client_id) {
+            Some(AnyClientState::Tendermint(client_state)) => {
+                client_state.with_frozen_height(Height::new(1, 1))
+            }
+            _ => panic!("client {} is not a Tendermint client", subject_client_id),
+        };
+        ctx.store_client_state(
+            subject_client_id.clone(),
+            AnyClientState::Tendermint(frozen_client_state),
+        )
+        .unwrap();
+        assert_eq!(ctx.client_status(&subject_client_id), Status::Frozen);
+
+        let err = process(&ctx, subject_client_id, substitute_client_id).unwrap_err();
+        assert_eq!(err.kind(), &Kind::LowSubstituteClientHeight(height, height));
+    }
+}
@@ -7,10 +7,12 @@ use crate::ics02_client::client_def::{AnyClient, ClientDef};
 use crate::ics02_client::client_state::AnyClientState;
 use crate::ics02_client::context::ClientReader;
 use crate::ics02_client::error::{Error, Kind};
-use crate::ics02_client::events::Attributes;
-use crate::ics02_client::handler::ClientResult;
+use crate::ics02_client::events::{Attributes, UpdateClient};
+use crate::ics02_client::handler::{check_client_active, ClientResult};
 use crate::ics02_client::msgs::update_client::MsgUpdateAnyClient;
 use crate::ics24_host::identifier::ClientId;
+use crate::timestamp::Timestamp;
+use crate::Height;
 
 /// The result following the successful processing of a `MsgUpdateAnyClient` message. Preferably
 /// this data type should be used with a qualified name `update_client::Result` to avoid ambiguity.
@@ -19,6 +21,10 @@ pub struct Result {
     pub client_id: ClientId,
     pub client_state: AnyClientState,
     pub consensus_state: AnyConsensusState,
+    pub processed_time: Timestamp,
+    pub processed_height: Height,
+    /// The heights of the expired consensus states of the client, to be pruned.
+    pub pruned_heights: Vec<Height>,
 }
 
 pub fn process(
@@ -45,6 +51,8 @@ pub fn process(
         .client_state(&client_id)
         .ok_or_else(|| Kind::ClientNotFound(client_id.clone()))?;
 
+    check_client_active(ctx, &client_id)?;
+
     let latest_height = client_state.latest_height();
     ctx.consensus_state(&client_id, latest_height)
         .ok_or_else(|| Kind::ConsensusStateNotFound(client_id.clone(), latest_height))?;
@@ -53,20 +61,53 @@ pub fn process(
     // This function will return the new client_state (its latest_height changed) and a
     // consensus_state obtained from header. These will be later persisted by the keeper.
     let (new_client_state, new_consensus_state) = client_def
-        .check_header_and_update_state(client_state, header)
+        .check_header_and_update_state(ctx, client_id.clone(), client_state, header.clone())
         .map_err(|e| Kind::HeaderVerificationFailure.context(e.to_string()))?;
 
+    // Prune the consensus states which have expired, so that the host does not keep them forever.
+    // The states are visited from the oldest one on, until the first one which has not expired.
+    let host_timestamp = ctx.host_timestamp();
+    let pruned_heights: Vec<Height> = ctx
+        .consensus_state_heights(&client_id)
+        .into_iter()
+        .filter(|height| *height != new_client_state.latest_height())
+        .take_while(|height| {
+            ctx.consensus_state(&client_id, *height)
+                .and_then(|consensus_state| {
+                    host_timestamp.duration_since(&consensus_state.timestamp())
+                })
+                .map_or(false, |elapsed| new_client_state.expired(elapsed))
+        })
+        .collect();
+    if !pruned_heights.is_empty() {
+        output.log(format!(
+            "pruned {} expired consensus states of client {}",
+            pruned_heights.len(),
+            client_id
+        ));
+    }
+
+    let consensus_height = new_client_state.latest_height();
+
     let result = ClientResult::Update(Result {
         client_id: client_id.clone(),
         client_state: new_client_state,
         consensus_state: new_consensus_state,
+        processed_time: host_timestamp,
+        processed_height: ctx.host_height(),
+        pruned_heights,
     });
 
     let event_attributes = Attributes {
+        height: ctx.host_height(),
         client_id,
-        ..Default::default()
+        client_type,
+        consensus_height,
     };
-    output.emit(IbcEvent::UpdateClient(event_attributes.into()));
+    output.emit(IbcEvent::UpdateClient(UpdateClient {
+        common: event_attributes,
+        header: Some(header),
+    }));
 
     Ok(output.with_result(result))
 }
@@ -78,18 +119,23 @@ mod tests {
 
     use crate::events::IbcEvent;
     use crate::handler::HandlerOutput;
-    use crate::ics02_client::client_state::AnyClientState;
+    use crate::ics02_client::client_state::{AnyClientState, Status};
+    use crate::ics02_client::client_type::ClientType;
+    use crate::ics02_client::context::{ClientKeeper, ClientReader};
     use crate::ics02_client::error::Kind;
     use crate::ics02_client::handler::dispatch;
     use crate::ics02_client::handler::ClientResult::Update;
-    use crate::ics02_client::header::Header;
+    use crate::ics02_client::header::{AnyHeader, Header};
     use crate::ics02_client::msgs::update_client::MsgUpdateAnyClient;
     use crate::ics02_client::msgs::ClientMsg;
-    use crate::ics24_host::identifier::ClientId;
+    use crate::ics07_tendermint::header::Header as TmHeader;
+    use crate::ics24_host::identifier::{ChainId, ClientId};
     use crate::mock::client_state::MockClientState;
     use crate::mock::context::MockContext;
     use crate::mock::header::MockHeader;
+    use crate::mock::host::{HostBlock, HostType};
     use crate::test_utils::get_dummy_account_id;
+    use crate::timestamp::Timestamp;
     use crate::Height;
 
     #[test]
@@ -124,7 +170,7 @@ mod tests {
                         assert_eq!(upd_res.client_id, client_id);
                         assert_eq!(
                             upd_res.client_state,
-                            AnyClientState::Mock(MockClientState(MockHeader::new(
+                            AnyClientState::Mock(MockClientState::new(MockHeader::new(
                                 msg.header.height()
                             )))
                         )
@@ -208,4 +254,207 @@ mod tests {
             }
         }
     }
+
+    /// Returns a context (on a mock host chain) with a Tendermint client tracking the chain
+    /// `chain_id`, at height `client_height`. The host time is 100 seconds after the Unix epoch.
+    fn context_with_tendermint_client(
+        client_id: &ClientId,
+        chain_id: ChainId,
+        client_height: Height,
+    ) -> MockContext {
+        MockContext::new(
+            ChainId::new("mockgaiaA".to_string(), 1),
+            HostType::Mock,
+            5,
+            Height::new(1, 1),
+        )
+        .with_client_parametrized_with_chain_id(
+            chain_id,
+            client_id,
+            client_height,
+            Some(ClientType::Tendermint),
+            Some(client_height),
+        )
+        .with_timestamp(Timestamp::from_nanoseconds(100 * 1_000_000_000).unwrap())
+    }
+
+    /// Returns a Tendermint header for `chain_id` at `height`, trusting the state at
+    /// `trusted_height`.
+    fn tendermint_header(chain_id: ChainId, height: Height, trusted_height: Height) -> AnyHeader {
+        let light_block = HostBlock::generate_tm_block(chain_id, height.revision_height);
+        let mut header = TmHeader::from(light_block);
+        header.trusted_height = trusted_height;
+
+        AnyHeader::Tendermint(header)
+    }
+
+    #[test]
+    fn test_update_tendermint_client_ok() {
+        let client_id = ClientId::new(ClientType::Tendermint, 0).unwrap();
+        let chain_id = ChainId::new("mockgaiaB".to_string(), 1);
+        let client_height = Height::new(1, 20);
+
+        let ctx = context_with_tendermint_client(&client_id, chain_id.clone(), client_height);
+
+        // Both adjacent and non-adjacent headers are accepted.
+        for &update_height in &[Height::new(1, 21), Height::new(1, 30)] {
+            let msg = MsgUpdateAnyClient {
+                client_id: client_id.clone(),
+                header: tendermint_header(chain_id.clone(), update_height, client_height),
+                signer: get_dummy_account_id(),
+            };
+
+            let output = dispatch(&ctx, ClientMsg::UpdateClient(msg));
+
+            match output {
+                Ok(HandlerOutput {
+                    result: Update(upd_res),
+                    ..
+                }) => {
+                    assert_eq!(upd_res.client_id, client_id);
+                    assert_eq!(upd_res.client_state.latest_height(), update_height);
+                }
+                Ok(_) => panic!("update handler result has incorrect type"),
+                Err(err) => panic!("unexpected error: {}", err),
+            }
+        }
+    }
+
+    #[test]
+    fn test_update_tendermint_client_verification_failures() {
+        let client_id = ClientId::new(ClientType::Tendermint, 0).unwrap();
+        let chain_id = ChainId::new("mockgaiaB".to_string(), 1);
+        let client_height = Height::new(1, 20);
+        let update_height = Height::new(1, 21);
+
+        let ctx = context_with_tendermint_client(&client_id, chain_id.clone(), client_height);
+
+        let headers = vec![
+            // No consensus state exists at the trusted height.
+            tendermint_header(chain_id.clone(), update_height, Height::new(1, 19)),
+            // The header is for a different chain.
+            tendermint_header(
+                ChainId::new("mockgaiaC".to_string(), 1),
+                update_height,
+                client_height,
+            ),
+            // The trusted height is from a different revision.
+            tendermint_header(chain_id.clone(), update_height, Height::new(2, 20)),
+        ];
+
+        for header in headers {
+            let msg = MsgUpdateAnyClient {
+                client_id: client_id.clone(),
+                header,
+                signer: get_dummy_account_id(),
+            };
+
+            let output = dispatch(&ctx, ClientMsg::UpdateClient(msg));
+
+            match output {
+                Ok(_) => panic!("unexpected success (expected error)"),
+                Err(err) => assert_eq!(err.kind(), &Kind::HeaderVerificationFailure),
+            }
+        }
+    }
+
+    #[test]
+    fn test_update_tendermint_client_prunes_expired_consensus_states() {
+        let client_id = ClientId::new(ClientType::Tendermint, 0).unwrap();
+        let chain_id = ChainId::new("mockgaiaB".to_string(), 1);
+        let client_height = Height::new(1, 20);
+
+        let ctx = context_with_tendermint_client(&client_id, chain_id.clone(), client_height);
+
+        // The timestamp of a synthetic block at height `h` is `h` seconds after the Unix epoch,
+        // and the trusting period of the client is 64000 seconds.
+        let update = |ctx: &mut MockContext, height: Height, trusted_height: Height| {
+            let msg = MsgUpdateAnyClient {
+                client_id: client_id.clone(),
+                header: tendermint_header(chain_id.clone(), height, trusted_height),
+                signer: get_dummy_account_id(),
+            };
+            let output = dispatch(&*ctx, ClientMsg::UpdateClient(msg)).unwrap();
+            ctx.store_client_result(output.result.clone()).unwrap();
+            output.result
+        };
+
+        // No consensus state is expired yet.
+        let recent_height = Height::new(1, 64010);
+        let mut ctx =
+            ctx.with_timestamp(Timestamp::from_nanoseconds(64010 * 1_000_000_000).unwrap());
+        match update(&mut ctx, recent_height, client_height) {
+            Update(upd_res) => assert!(upd_res.pruned_heights.is_empty()),
+            _ => panic!("update handler result has incorrect type"),
+        }
+
+        // The first consensus state expires, while the client remains active.
+        let mut ctx =
+            ctx.with_timestamp(Timestamp::from_nanoseconds(64040 * 1_000_000_000).unwrap());
+        assert_eq!(ctx.client_status(&client_id), Status::Active);
+
+        match update(&mut ctx, recent_height.increment(), recent_height) {
+            Update(upd_res) => assert_eq!(upd_res.pruned_heights, vec![client_height]),
+            _ => panic!("update handler result has incorrect type"),
+        }
+        assert!(ClientReader::consensus_state(&ctx, &client_id, client_height).is_none());
+        assert!(ClientReader::client_update_time(&ctx, &client_id, client_height).is_none());
+        assert!(ClientReader::client_update_height(&ctx, &client_id, client_height).is_none());
+        assert_eq!(
+            ctx.consensus_state_heights(&client_id),
+            vec![recent_height, recent_height.increment()]
+        );
+
+        // Once the latest consensus state expires too, the client can no longer be updated.
+        let ctx = ctx.with_timestamp(Timestamp::from_nanoseconds(200000 * 1_000_000_000).unwrap());
+        assert_eq!(ctx.client_status(&client_id), Status::Expired);
+
+        let msg = MsgUpdateAnyClient {
+            client_id: client_id.clone(),
+            header: tendermint_header(chain_id, Height::new(1, 200000), recent_height),
+            signer: get_dummy_account_id(),
+        };
+        let err = dispatch(&ctx, ClientMsg::UpdateClient(msg)).unwrap_err();
+        assert_eq!(
+            err.kind(),
+            &Kind::ClientNotActive(client_id, Status::Expired)
+        );
+    }
+
+    #[test]
+    fn test_update_tendermint_client_prunes_up_to_the_first_unexpired_state() {
+        let client_id = ClientId::new(ClientType::Tendermint, 0).unwrap();
+        let chain_id = ChainId::new("mockgaiaB".to_string(), 1);
+        let client_height = Height::new(1, 20);
+        let recent_height = Height::new(1, 64010);
+
+        let mut ctx = context_with_tendermint_client(&client_id, chain_id.clone(), client_height)
+            .with_timestamp(Timestamp::from_nanoseconds(64010 * 1_000_000_000).unwrap());
+        let msg = MsgUpdateAnyClient {
+            client_id: client_id.clone(),
+            header: tendermint_header(chain_id.clone(), recent_height, client_height),
+            signer: get_dummy_account_id(),
+        };
+        let output = dispatch(&ctx, ClientMsg::UpdateClient(msg)).unwrap();
+        ctx.store_client_result(output.result).unwrap();
+
+        // A consensus state older than the one at `client_height`, which has not expired yet.
+        let older_height = Height::new(1, 10);
+        let older_state = HostBlock::generate_tm_block(chain_id.clone(), 64030).into();
+        ctx.store_consensus_state(client_id.clone(), older_height, older_state)
+            .unwrap();
+
+        // The state at `client_height` has expired, but it is not reached by the pruning.
+        let ctx = ctx.with_timestamp(Timestamp::from_nanoseconds(64040 * 1_000_000_000).unwrap());
+        let msg = MsgUpdateAnyClient {
+            client_id: client_id.clone(),
+            header: tendermint_header(chain_id, recent_height.increment(), recent_height),
+            signer: get_dummy_account_id(),
+        };
+        match dispatch(&ctx, ClientMsg::UpdateClient(msg)).unwrap().result {
+            Update(upd_res) => assert!(upd_res.pruned_heights.is_empty()),
+            _ => panic!("update handler result has incorrect type"),
+        }
+        assert!(ClientReader::consensus_state(&ctx, &client_id, client_height).is_some());
+    }
 }
@@ -5,13 +5,14 @@ use crate::handler::{HandlerOutput, HandlerResult};
 use crate::ics02_client::client_consensus::AnyConsensusState;
 use crate::ics02_client::client_def::{AnyClient, ClientDef};
 use crate::ics02_client::client_state::AnyClientState;
-use crate::ics02_client::client_state::ClientState;
 use crate::ics02_client::context::ClientReader;
 use crate::ics02_client::error::{Error, Kind};
 use crate::ics02_client::events::Attributes;
-use crate::ics02_client::handler::ClientResult;
+use crate::ics02_client::handler::{check_client_active, ClientResult};
 use crate::ics02_client::msgs::upgrade_client::MsgUpgradeAnyClient;
 use crate::ics24_host::identifier::ClientId;
+use crate::timestamp::Timestamp;
+use crate::Height;
 
 /// The result following the successful processing of a `MsgUpgradeAnyClient` message.
 /// This data type should be used with a qualified name `upgrade_client::Result` to avoid ambiguity.
@@ -20,6 +21,8 @@ pub struct Result {
     pub client_id: ClientId,
     pub client_state: AnyClientState,
     pub consensus_state: AnyConsensusState,
+    pub processed_time: Timestamp,
+    pub processed_height: Height,
 }
 
 pub fn process(
@@ -34,9 +37,7 @@ pub fn process(
         .client_state(&client_id)
         .ok_or_else(|| Kind::ClientNotFound(client_id.clone()))?;
 
-    if client_state.is_frozen() {
-        return Err(Kind::ClientFrozen(client_id).into());
-    }
+    check_client_active(ctx, &client_id)?;
 
     let upgrade_client_state = msg.client_state.clone();
 
@@ -56,24 +57,30 @@ pub fn process(
 
     let (new_client_state, new_consensus_state) = client_def
         .verify_upgrade_and_update_state(
+            ctx,
+            &client_id,
+            &client_state,
             &upgrade_client_state,
             &msg.consensus_state,
-            msg.proof_upgrade_client.clone(),
+            msg.proof_upgrade_client,
             msg.proof_upgrade_consensus_state,
         )
         .map_err(|e| Kind::UpgradeVerificationFailure.context(e.to_string()))?;
 
-    // Not implemented yet: https://github.com/informalsystems/ibc-rs/issues/722
-    // todo!()
+    let consensus_height = new_client_state.latest_height();
 
     let result = ClientResult::Upgrade(Result {
         client_id: client_id.clone(),
         client_state: new_client_state,
         consensus_state: new_consensus_state,
+        processed_time: ctx.host_timestamp(),
+        processed_height: ctx.host_height(),
     });
     let event_attributes = Attributes {
+        height: ctx.host_height(),
         client_id,
-        ..Default::default()
+        client_type,
+        consensus_height,
     };
 
     output.emit(IbcEvent::UpgradeClient(event_attributes.into()));
@@ -115,8 +122,8 @@ mod tests {
 
         let msg = MsgUpgradeAnyClient {
             client_id: client_id.clone(),
-            client_state: MockClientState(MockHeader::new(Height::new(1, 26))).into(),
-            consensus_state: MockConsensusState(MockHeader::new(Height::new(1, 26))).into(),
+            client_state: MockClientState::new(MockHeader::new(Height::new(1, 26))).into(),
+            consensus_state: MockConsensusState::new(MockHeader::new(Height::new(1, 26))).into(),
             proof_upgrade_client: MerkleProof::try_from(c_bytes).unwrap(),
             proof_upgrade_consensus_state: MerkleProof::try_from(cs_bytes).unwrap(),
             signer,
@@ -166,8 +173,8 @@ mod tests {
 
         let msg = MsgUpgradeAnyClient {
             client_id: ClientId::from_str("nonexistingclient").unwrap(),
-            client_state: MockClientState(MockHeader::new(Height::new(1, 26))).into(),
-            consensus_state: MockConsensusState(MockHeader::new(Height::new(1, 26))).into(),
+            client_state: MockClientState::new(MockHeader::new(Height::new(1, 26))).into(),
+            consensus_state: MockConsensusState::new(MockHeader::new(Height::new(1, 26))).into(),
             proof_upgrade_client: MerkleProof::try_from(c_bytes).unwrap(),
             proof_upgrade_consensus_state: MerkleProof::try_from(cs_bytes).unwrap(),
             signer,
@@ -200,8 +207,8 @@ mod tests {
 
         let msg = MsgUpgradeAnyClient {
             client_id,
-            client_state: MockClientState(MockHeader::new(Height::new(0, 26))).into(),
-            consensus_state: MockConsensusState(MockHeader::new(Height::new(0, 26))).into(),
+            client_state: MockClientState::new(MockHeader::new(Height::new(0, 26))).into(),
+            consensus_state: MockConsensusState::new(MockHeader::new(Height::new(0, 26))).into(),
             proof_upgrade_client: MerkleProof::try_from(c_bytes).unwrap(),
             proof_upgrade_consensus_state: MerkleProof::try_from(cs_bytes).unwrap(),
             signer,
@@ -79,6 +79,15 @@ impl Default for Height {
     }
 }
 
+/// Heights are generated close to the heights of freshly created mock chains, in the first two
+/// revisions, such that they are often found among the heights of the chain and of its clients.
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Height {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self::new(u.int_in_range(0..=1)?, u.int_in_range(0..=32)?))
+    }
+}
+
 impl PartialOrd for Height {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
@@ -144,6 +153,9 @@ impl TryFrom<&str> for Height {
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
         let split: Vec<&str> = value.split('-').collect();
+        if split.len() != 2 {
+            return Err(Kind::InvalidHeightFormat(value.to_owned()));
+        }
         Ok(Height {
             revision_number: split[0]
                 .parse::<u64>()
@@ -4,6 +4,7 @@ use prost_types::Any;
 use tendermint_proto::Protobuf;
 
 use crate::ics02_client::error::{Error, Kind};
+use crate::ics06_solomachine::misbehaviour::Misbehaviour as SoloMachineMisbehaviour;
 use crate::ics07_tendermint::misbehaviour::Misbehaviour as TmMisbehaviour;
 
 #[cfg(any(test, feature = "mocks"))]
@@ -13,6 +14,7 @@ use crate::ics24_host::identifier::ClientId;
 use crate::Height;
 
 pub const TENDERMINT_MISBEHAVIOR_TYPE_URL: &str = "/ibc.lightclients.tendermint.v1.Misbehaviour";
+pub const SOLOMACHINE_MISBEHAVIOUR_TYPE_URL: &str = "/ibc.lightclients.solomachine.v1.Misbehaviour";
 
 #[cfg(any(test, feature = "mocks"))]
 pub const MOCK_MISBEHAVIOUR_TYPE_URL: &str = "/ibc.mock.Misbehavior";
@@ -32,6 +34,7 @@ pub trait Misbehaviour: Clone + std::fmt::Debug + Send + Sync {
 #[allow(clippy::large_enum_variant)]
 pub enum AnyMisbehaviour {
     Tendermint(TmMisbehaviour),
+    SoloMachine(SoloMachineMisbehaviour),
 
     #[cfg(any(test, feature = "mocks"))]
     Mock(MockMisbehaviour),
@@ -41,6 +44,7 @@ impl Misbehaviour for AnyMisbehaviour {
     fn client_id(&self) -> &ClientId {
         match self {
             Self::Tendermint(misbehaviour) => misbehaviour.client_id(),
+            Self::SoloMachine(misbehaviour) => misbehaviour.client_id(),
 
             #[cfg(any(test, feature = "mocks"))]
             Self::Mock(misbehaviour) => misbehaviour.client_id(),
@@ -50,6 +54,7 @@ impl Misbehaviour for AnyMisbehaviour {
     fn height(&self) -> Height {
         match self {
             Self::Tendermint(misbehaviour) => misbehaviour.height(),
+            Self::SoloMachine(misbehaviour) => misbehaviour.height(),
 
             #[cfg(any(test, feature = "mocks"))]
             Self::Mock(misbehaviour) => misbehaviour.height(),
@@ -61,6 +66,18 @@ impl Misbehaviour for AnyMisbehaviour {
     }
 }
 
+/// Mock misbehaviours are generated half of the time, like mock client states.
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for AnyMisbehaviour {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(match u.int_in_range(0..=3)? {
+            0 => Self::Tendermint(u.arbitrary()?),
+            1 => Self::SoloMachine(u.arbitrary()?),
+            _ => Self::Mock(u.arbitrary()?),
+        })
+    }
+}
+
 impl Protobuf<Any> for AnyMisbehaviour {}
 
 impl TryFrom<Any> for AnyMisbehaviour {
@@ -73,6 +90,11 @@ impl TryFrom<Any> for AnyMisbehaviour {
                     .map_err(|e| Kind::InvalidRawMisbehaviour.context(e))?,
             )),
 
+            SOLOMACHINE_MISBEHAVIOUR_TYPE_URL => Ok(AnyMisbehaviour::SoloMachine(
+                SoloMachineMisbehaviour::decode_vec(&raw.value)
+                    .map_err(|e| Kind::InvalidRawMisbehaviour.context(e))?,
+            )),
+
             #[cfg(any(test, feature = "mocks"))]
             MOCK_MISBEHAVIOUR_TYPE_URL => Ok(AnyMisbehaviour::Mock(
                 MockMisbehaviour::decode_vec(&raw.value)
@@ -93,6 +115,13 @@ impl From<AnyMisbehaviour> for Any {
                     .expect("encoding to `Any` from `AnyMisbehavior::Tendermint`"),
             },
 
+            AnyMisbehaviour::SoloMachine(misbehaviour) => Any {
+                type_url: SOLOMACHINE_MISBEHAVIOUR_TYPE_URL.to_string(),
+                value: misbehaviour
+                    .encode_vec()
+                    .expect("encoding to `Any` from `AnyMisbehavior::SoloMachine`"),
+            },
+
             #[cfg(any(test, feature = "mocks"))]
             AnyMisbehaviour::Mock(misbehaviour) => Any {
                 type_url: MOCK_MISBEHAVIOUR_TYPE_URL.to_string(),
@@ -108,6 +137,7 @@ impl std::fmt::Display for AnyMisbehaviour {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
         match self {
             AnyMisbehaviour::Tendermint(tm) => write!(f, "{}", tm),
+            AnyMisbehaviour::SoloMachine(sm) => write!(f, "{}", sm),
 
             #[cfg(any(test, feature = "mocks"))]
             AnyMisbehaviour::Mock(mock) => write!(f, "{:?}", mock),
@@ -61,6 +61,32 @@ impl Msg for MsgUpgradeAnyClient {
     }
 }
 
+/// The raw upgrade proofs do not implement `Arbitrary`, so they are decoded from arbitrary proof
+/// bytes, or made of a single empty commitment proof if these do not decode. Such proofs only
+/// get through to the upgrade of mock clients, which do not verify them.
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for MsgUpgradeAnyClient {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        fn proof(u: &mut arbitrary::Unstructured<'_>) -> arbitrary::Result<RawMerkleProof> {
+            let bytes: CommitmentProofBytes = u.arbitrary()?;
+            Ok(
+                RawMerkleProof::try_from(bytes).unwrap_or_else(|_| RawMerkleProof {
+                    proofs: vec![ibc_proto::ics23::CommitmentProof { proof: None }],
+                }),
+            )
+        }
+
+        Ok(MsgUpgradeAnyClient {
+            client_id: u.arbitrary()?,
+            client_state: u.arbitrary()?,
+            consensus_state: u.arbitrary()?,
+            proof_upgrade_client: proof(u)?,
+            proof_upgrade_consensus_state: proof(u)?,
+            signer: u.arbitrary()?,
+        })
+    }
+}
+
 impl Protobuf<RawMsgUpgradeClient> for MsgUpgradeAnyClient {}
 
 impl From<MsgUpgradeAnyClient> for RawMsgUpgradeClient {
@@ -138,10 +164,10 @@ pub mod test_util {
         RawMsgUpgradeClient {
             client_id: "tendermint".parse().unwrap(),
             client_state: Some(
-                AnyClientState::Mock(MockClientState(MockHeader::new(height))).into(),
+                AnyClientState::Mock(MockClientState::new(MockHeader::new(height))).into(),
             ),
             consensus_state: Some(
-                AnyConsensusState::Mock(MockConsensusState(MockHeader::new(height))).into(),
+                AnyConsensusState::Mock(MockConsensusState::new(MockHeader::new(height))).into(),
             ),
             proof_upgrade_client: get_dummy_proof(),
             proof_upgrade_consensus_state: get_dummy_proof(),
@@ -177,8 +203,9 @@ mod tests {
 
         let height = Height::new(1, 1);
 
-        let client_state = AnyClientState::Mock(MockClientState(MockHeader::new(height)));
-        let consensus_state = AnyConsensusState::Mock(MockConsensusState(MockHeader::new(height)));
+        let client_state = AnyClientState::Mock(MockClientState::new(MockHeader::new(height)));
+        let consensus_state =
+            AnyConsensusState::Mock(MockConsensusState::new(MockHeader::new(height)));
 
         let proof = get_dummy_merkle_proof();
 
@@ -153,6 +153,15 @@ impl ConnectionEnd {
         self.versions.clone()
     }
 
+    /// Getter for the features of the version negotiated during the handshake. Returns `None` as
+    /// long as this end has several candidate versions, i.e., before the negotiation completes.
+    pub fn negotiated_features(&self) -> Option<Vec<String>> {
+        match self.versions.as_slice() {
+            [version] => Some(version.features().to_vec()),
+            _ => None,
+        }
+    }
+
     /// Getter for the counterparty. Returns a `clone()`.
     pub fn counterparty(&self) -> Counterparty {
         self.counterparty.clone()
@@ -194,6 +203,7 @@ impl IdentifiedConnectionEnd {
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Counterparty {
     client_id: ClientId,
     connection_id: Option<ConnectionId>,
@@ -10,6 +10,7 @@ use crate::ics03_connection::handler::{ConnectionIdState, ConnectionResult};
 use crate::ics03_connection::version::{get_compatible_versions, pick_version, Version};
 use crate::ics23_commitment::commitment::CommitmentPrefix;
 use crate::ics24_host::identifier::{ClientId, ConnectionId};
+use crate::timestamp::Timestamp;
 use crate::Height;
 
 /// A context supplying all the necessary read-only dependencies for processing any `ConnectionMsg`.
@@ -23,6 +24,9 @@ pub trait ConnectionReader {
     /// Returns the current height of the local chain.
     fn host_current_height(&self) -> Height;
 
+    /// Returns the current timestamp of the local chain.
+    fn host_timestamp(&self) -> Timestamp;
+
     /// Returns the oldest height available on the local chain.
     fn host_oldest_height(&self) -> Height;
 
@@ -39,6 +43,14 @@ pub trait ConnectionReader {
     /// Returns the ConsensusState of the host (local) chain at a specific height.
     fn host_consensus_state(&self, height: Height) -> Option<AnyConsensusState>;
 
+    /// Checks that `counterparty_client_state`, the client state which the counterparty chain
+    /// claims to store for the host (local) chain, is a valid client of the host chain: its chain
+    /// identifier, revision and parameters must match those of the host, it must not be frozen, and
+    /// its latest height must be lower than the current height of the host, whose block is not
+    /// committed yet.
+    fn validate_self_client(&self, counterparty_client_state: &AnyClientState)
+        -> Result<(), Error>;
+
     /// Function required by ICS 03. Returns the list of all possible versions that the connection
     /// handshake protocol supports.
     fn get_compatible_versions(&self) -> Vec<Version> {
@@ -1,6 +1,7 @@
 use anomaly::{BoxError, Context};
 use thiserror::Error;
 
+use crate::ics02_client::client_state::Status;
 use crate::ics24_host::identifier::{ClientId, ConnectionId};
 use crate::Height;
 
@@ -80,6 +81,9 @@ pub enum Kind {
     #[error("the client {0} running locally is frozen")]
     FrozenClient(ClientId),
 
+    #[error("the client {0} running locally is not active, its status is {1}")]
+    ClientNotActive(ClientId, Status),
+
     #[error("the connection proof verification failed")]
     ConnectionVerificationFailure,
 
@@ -94,6 +98,9 @@ pub enum Kind {
 
     #[error("the client state proof verification failed for client id: {0}")]
     ClientStateVerificationFailure(ClientId),
+
+    #[error("the client state of the host chain stored by the counterparty is invalid")]
+    InvalidSelfClient,
 }
 
 impl Kind {
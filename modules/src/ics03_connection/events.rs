@@ -1,11 +1,13 @@
 //! Types for the IBC events emitted from Tendermint Websocket by the connection module.
-use crate::events::{IbcEvent, RawObject};
+use crate::events::{extract_attribute, tag, IbcEvent, RawObject};
 use crate::ics02_client::height::Height;
 use crate::ics24_host::identifier::{ClientId, ConnectionId};
 use crate::{attribute, some_attribute};
 use anomaly::BoxError;
 use serde_derive::{Deserialize, Serialize};
 use std::convert::TryFrom;
+use tendermint::abci::tag::Tag;
+use tendermint::abci::Event as AbciEvent;
 
 /// The content of the `type` field for the event that a chain produces upon executing a connection handshake transaction.
 const INIT_EVENT_TYPE: &str = "connection_open_init";
@@ -19,45 +21,76 @@ const CLIENT_ID_ATTRIBUTE_KEY: &str = "client_id";
 const COUNTERPARTY_CONN_ID_ATTRIBUTE_KEY: &str = "counterparty_connection_id";
 const COUNTERPARTY_CLIENT_ID_ATTRIBUTE_KEY: &str = "counterparty_client_id";
 
-pub fn try_from_tx(event: &tendermint::abci::Event) -> Option<IbcEvent> {
-    match event.type_str.as_ref() {
-        INIT_EVENT_TYPE => Some(IbcEvent::OpenInitConnection(OpenInit::from(
-            extract_attributes_from_tx(event),
-        ))),
-        TRY_EVENT_TYPE => Some(IbcEvent::OpenTryConnection(OpenTry::from(
-            extract_attributes_from_tx(event),
-        ))),
-        ACK_EVENT_TYPE => Some(IbcEvent::OpenAckConnection(OpenAck::from(
-            extract_attributes_from_tx(event),
-        ))),
-        CONFIRM_EVENT_TYPE => Some(IbcEvent::OpenConfirmConnection(OpenConfirm::from(
-            extract_attributes_from_tx(event),
-        ))),
-        _ => None,
-    }
+/// Parses a connection event from the attributes of `event`. Returns `None` if `event` is not a
+/// connection event, or if it is malformed.
+pub fn try_from_tx(event: &AbciEvent) -> Option<IbcEvent> {
+    try_from_abci_event(event).ok().flatten()
 }
 
-fn extract_attributes_from_tx(event: &tendermint::abci::Event) -> Attributes {
-    let mut attr = Attributes::default();
-
-    for tag in &event.attributes {
-        let key = tag.key.as_ref();
-        let value = tag.value.as_ref();
-        match key {
-            CONN_ID_ATTRIBUTE_KEY => attr.connection_id = value.parse().ok(),
-            CLIENT_ID_ATTRIBUTE_KEY => attr.client_id = value.parse().unwrap(),
-            COUNTERPARTY_CONN_ID_ATTRIBUTE_KEY => {
-                attr.counterparty_connection_id = value.parse().ok()
-            }
-            COUNTERPARTY_CLIENT_ID_ATTRIBUTE_KEY => {
-                attr.counterparty_client_id = value.parse().unwrap()
-            }
-            // TODO: `Attributes` has 5 fields and we're only parsing 4
-            _ => {}
+/// Parses a connection event from the attributes of `event`. Returns `None` if `event` is not a
+/// connection event, and an error if it is a malformed one.
+pub fn try_from_abci_event(event: &AbciEvent) -> Result<Option<IbcEvent>, BoxError> {
+    let ibc_event = match event.type_str.as_ref() {
+        INIT_EVENT_TYPE => {
+            IbcEvent::OpenInitConnection(OpenInit::from(extract_attributes_from_tx(event)?))
+        }
+        TRY_EVENT_TYPE => {
+            IbcEvent::OpenTryConnection(OpenTry::from(extract_attributes_from_tx(event)?))
+        }
+        ACK_EVENT_TYPE => {
+            IbcEvent::OpenAckConnection(OpenAck::from(extract_attributes_from_tx(event)?))
         }
+        CONFIRM_EVENT_TYPE => {
+            IbcEvent::OpenConfirmConnection(OpenConfirm::from(extract_attributes_from_tx(event)?))
+        }
+        _ => return Ok(None),
+    };
+
+    Ok(Some(ibc_event))
+}
+
+/// The connection identifiers are empty until the connection ends are initialized.
+fn extract_attributes_from_tx(event: &AbciEvent) -> Result<Attributes, BoxError> {
+    Ok(Attributes {
+        height: Default::default(),
+        connection_id: optional_id(extract_attribute(event, CONN_ID_ATTRIBUTE_KEY)?)?,
+        client_id: extract_attribute(event, CLIENT_ID_ATTRIBUTE_KEY)?.parse()?,
+        counterparty_connection_id: optional_id(extract_attribute(
+            event,
+            COUNTERPARTY_CONN_ID_ATTRIBUTE_KEY,
+        )?)?,
+        counterparty_client_id: extract_attribute(event, COUNTERPARTY_CLIENT_ID_ATTRIBUTE_KEY)?
+            .parse()?,
+    })
+}
+
+/// Parses a connection identifier, which is empty if unknown.
+fn optional_id(value: &str) -> Result<Option<ConnectionId>, BoxError> {
+    if value.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(value.parse()?))
     }
+}
 
-    attr
+/// The attributes of a connection event, as `ibc-go` emits them. The height is not among them.
+impl From<Attributes> for Vec<Tag> {
+    fn from(attrs: Attributes) -> Self {
+        let id_or_empty =
+            |id: Option<ConnectionId>| id.map_or_else(String::new, |id| id.to_string());
+        vec![
+            tag(CONN_ID_ATTRIBUTE_KEY, id_or_empty(attrs.connection_id)),
+            tag(CLIENT_ID_ATTRIBUTE_KEY, attrs.client_id),
+            tag(
+                COUNTERPARTY_CONN_ID_ATTRIBUTE_KEY,
+                id_or_empty(attrs.counterparty_connection_id),
+            ),
+            tag(
+                COUNTERPARTY_CLIENT_ID_ATTRIBUTE_KEY,
+                attrs.counterparty_client_id,
+            ),
+        ]
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -124,6 +157,15 @@ impl From<OpenInit> for IbcEvent {
     }
 }
 
+impl From<OpenInit> for AbciEvent {
+    fn from(v: OpenInit) -> Self {
+        AbciEvent {
+            type_str: INIT_EVENT_TYPE.to_string(),
+            attributes: v.0.into(),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct OpenTry(Attributes);
 
@@ -167,6 +209,15 @@ impl From<OpenTry> for IbcEvent {
     }
 }
 
+impl From<OpenTry> for AbciEvent {
+    fn from(v: OpenTry) -> Self {
+        AbciEvent {
+            type_str: TRY_EVENT_TYPE.to_string(),
+            attributes: v.0.into(),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct OpenAck(Attributes);
 
@@ -210,6 +261,15 @@ impl From<OpenAck> for IbcEvent {
     }
 }
 
+impl From<OpenAck> for AbciEvent {
+    fn from(v: OpenAck) -> Self {
+        AbciEvent {
+            type_str: ACK_EVENT_TYPE.to_string(),
+            attributes: v.0.into(),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct OpenConfirm(Attributes);
 
@@ -255,3 +315,12 @@ impl From<OpenConfirm> for IbcEvent {
         IbcEvent::OpenConfirmConnection(v)
     }
 }
+
+impl From<OpenConfirm> for AbciEvent {
+    fn from(v: OpenConfirm) -> Self {
+        AbciEvent {
+            type_str: CONFIRM_EVENT_TYPE.to_string(),
+            attributes: v.0.into(),
+        }
+    }
+}
@@ -1,11 +1,14 @@
 //! This module implements the processing logic for ICS3 (connection open handshake) messages.
 
 use crate::handler::HandlerOutput;
+use crate::ics02_client::client_state::AnyClientState;
 use crate::ics03_connection::connection::ConnectionEnd;
 use crate::ics03_connection::context::ConnectionReader;
 use crate::ics03_connection::error::Error;
+use crate::ics03_connection::events::Attributes;
 use crate::ics03_connection::msgs::ConnectionMsg;
-use crate::ics24_host::identifier::ConnectionId;
+use crate::ics24_host::identifier::{ClientId, ConnectionId};
+use crate::Height;
 
 pub mod conn_open_ack;
 pub mod conn_open_confirm;
@@ -37,6 +40,24 @@ pub struct ConnectionResult {
 
     /// The connection end, which the handler produced as a result of processing the message.
     pub connection_end: ConnectionEnd,
+
+    /// The new state of the client which verified the proofs of the message, if verifying them
+    /// changed it.
+    pub client_state: Option<(ClientId, AnyClientState)>,
+}
+
+impl ConnectionResult {
+    /// Returns the attributes of the event which the handler emits at `height`.
+    pub(crate) fn event_attributes(&self, height: Height) -> Attributes {
+        let counterparty = self.connection_end.counterparty();
+        Attributes {
+            height,
+            connection_id: Some(self.connection_id.clone()),
+            client_id: self.connection_end.client_id().clone(),
+            counterparty_connection_id: counterparty.connection_id().cloned(),
+            counterparty_client_id: counterparty.client_id().clone(),
+        }
+    }
 }
 
 /// General entry point for processing any type of message related to the ICS3 connection open
@@ -5,7 +5,6 @@ use crate::handler::{HandlerOutput, HandlerResult};
 use crate::ics03_connection::connection::{ConnectionEnd, Counterparty, State};
 use crate::ics03_connection::context::ConnectionReader;
 use crate::ics03_connection::error::{Error, Kind};
-use crate::ics03_connection::events::Attributes;
 use crate::ics03_connection::handler::verify::{check_client_consensus_height, verify_proofs};
 use crate::ics03_connection::handler::{ConnectionIdState, ConnectionResult};
 use crate::ics03_connection::msgs::conn_open_ack::MsgConnectionOpenAck;
@@ -24,9 +23,13 @@ pub(crate) fn process(
         // A connection end must exist and must be Init or TryOpen; otherwise we return an error.
         Some(old_conn_end) => {
             // Check if the connection state is either Init or TryOpen and message version
-            // is compatible.
+            // is compatible. The version picked by the counterparty may carry a subset of the
+            // features that this end proposed.
             let state_is_consistent = old_conn_end.state_matches(&State::Init)
-                && old_conn_end.versions().contains(msg.version())
+                && old_conn_end
+                    .versions()
+                    .iter()
+                    .any(|v| v.verify_proposed_version(msg.version()))
                 || old_conn_end.state_matches(&State::TryOpen)
                     && old_conn_end.versions().get(0).eq(&Some(msg.version()));
 
@@ -57,6 +60,11 @@ pub(crate) fn process(
         }
     }?;
 
+    // The counterparty must run a valid client of this chain.
+    if let Some(client_state) = msg.client_state() {
+        ctx.validate_self_client(&client_state)?;
+    }
+
     // Proof verification.
     let expected_conn = ConnectionEnd::new(
         State::TryOpen,
@@ -64,15 +72,24 @@ pub(crate) fn process(
         Counterparty::new(
             // The counterparty is the local chain.
             new_conn_end.client_id().clone(), // The local client identifier.
-            Some(msg.counterparty_connection_id().clone()), // This chain's connection id as known on counterparty.
-            ctx.commitment_prefix(),                        // Local commitment prefix.
+            Some(msg.connection_id().clone()), // Local connection id.
+            ctx.commitment_prefix(),          // Local commitment prefix.
         ),
         vec![msg.version().clone()],
         new_conn_end.delay_period(),
     );
 
+    // Record the connection identifier chosen by the counterparty, if it was not known yet. The
+    // proof of the counterparty connection end is verified under this identifier.
+    let counterparty = new_conn_end.counterparty();
+    new_conn_end.set_counterparty(Counterparty::new(
+        counterparty.client_id().clone(),
+        Some(msg.counterparty_connection_id().clone()),
+        counterparty.prefix().clone(),
+    ));
+
     // 2. Pass the details to the verification function.
-    verify_proofs(
+    let new_client_state = verify_proofs(
         ctx,
         msg.client_state(),
         &new_conn_end,
@@ -85,16 +102,15 @@ pub(crate) fn process(
     new_conn_end.set_state(State::Open);
     new_conn_end.set_version(msg.version().clone());
 
+    let client_id = new_conn_end.client_id().clone();
     let result = ConnectionResult {
         connection_id: msg.connection_id().clone(),
         connection_id_state: ConnectionIdState::Reused,
         connection_end: new_conn_end,
+        client_state: new_client_state.map(|cs| (client_id, cs)),
     };
 
-    let event_attributes = Attributes {
-        connection_id: Some(result.connection_id.clone()),
-        ..Default::default()
-    };
+    let event_attributes = result.event_attributes(ctx.host_current_height());
     output.emit(IbcEvent::OpenAckConnection(event_attributes.into()));
 
     Ok(output.with_result(result))
@@ -107,15 +123,19 @@ mod tests {
     use test_env_log::test;
 
     use crate::events::IbcEvent;
+    use crate::ics02_client::context::ClientKeeper;
     use crate::ics03_connection::connection::{ConnectionEnd, Counterparty, State};
     use crate::ics03_connection::error::Kind;
     use crate::ics03_connection::handler::{dispatch, ConnectionResult};
     use crate::ics03_connection::msgs::conn_open_ack::test_util::get_dummy_raw_msg_conn_open_ack;
     use crate::ics03_connection::msgs::conn_open_ack::MsgConnectionOpenAck;
     use crate::ics03_connection::msgs::ConnectionMsg;
+    use crate::ics03_connection::version::Version;
     use crate::ics23_commitment::commitment::CommitmentPrefix;
     use crate::ics24_host::identifier::{ChainId, ClientId};
+    use crate::mock::client_state::MockClientState;
     use crate::mock::context::MockContext;
+    use crate::mock::header::MockHeader;
     use crate::mock::host::HostType;
     use crate::timestamp::ZERO_DURATION;
 
@@ -174,6 +194,27 @@ mod tests {
             Some(msg_ack.counterparty_connection_id().clone()),
             CommitmentPrefix::from(vec![]), // incorrect field
         ));
+        // The mock client only uses the prefix if it verifies proofs, namely if the headers of the
+        // counterparty carry a root.
+        let mut ctx_prefix = default_context
+            .clone()
+            .with_client(&client_id, proof_height)
+            .with_connection(conn_id.clone(), conn_end_prefix);
+        let header = MockHeader {
+            root: Some([0; 32]),
+            ..MockHeader::new(proof_height)
+        };
+        ctx_prefix
+            .store_client_state(client_id.clone(), MockClientState::new(header).into())
+            .unwrap();
+
+        // The counterparty may pick a subset of the features proposed by this end, but no others.
+        let mut msg_ack_unordered = msg_ack.clone();
+        msg_ack_unordered.version =
+            Version::new("1".to_string(), vec!["ORDER_UNORDERED".to_string()]);
+        let mut msg_ack_unknown_feature = msg_ack.clone();
+        msg_ack_unknown_feature.version =
+            Version::new("1".to_string(), vec!["ORDER_RANDOM".to_string()]);
 
         let tests: Vec<Test> = vec![
             Test {
@@ -181,36 +222,57 @@ mod tests {
                 ctx: default_context
                     .clone()
                     .with_client(&client_id, proof_height)
-                    .with_connection(conn_id.clone(), default_conn_end),
+                    .with_connection(conn_id.clone(), default_conn_end.clone()),
                 msg: ConnectionMsg::ConnectionOpenAck(Box::new(msg_ack.clone())),
                 want_pass: true,
                 error_kind: None,
             },
             Test {
-                name: "Processing fails because the connection does not exist in the context".to_string(),
+                name: "Successful processing of an Ack message with a subset of the features"
+                    .to_string(),
+                ctx: default_context
+                    .clone()
+                    .with_client(&client_id, proof_height)
+                    .with_connection(conn_id.clone(), default_conn_end.clone()),
+                msg: ConnectionMsg::ConnectionOpenAck(Box::new(msg_ack_unordered)),
+                want_pass: true,
+                error_kind: None,
+            },
+            Test {
+                name: "Processing fails due to connections mismatch (feature not proposed)"
+                    .to_string(),
+                ctx: default_context
+                    .clone()
+                    .with_client(&client_id, proof_height)
+                    .with_connection(conn_id.clone(), default_conn_end),
+                msg: ConnectionMsg::ConnectionOpenAck(Box::new(msg_ack_unknown_feature)),
+                want_pass: false,
+                error_kind: Some(Kind::ConnectionMismatch(conn_id.clone())),
+            },
+            Test {
+                name: "Processing fails because the connection does not exist in the context"
+                    .to_string(),
                 ctx: default_context.clone(),
                 msg: ConnectionMsg::ConnectionOpenAck(Box::new(msg_ack.clone())),
                 want_pass: false,
                 error_kind: Some(Kind::UninitializedConnection(conn_id.clone())),
             },
             Test {
-                name: "Processing fails due to connections mismatch (incorrect 'open' state)".to_string(),
+                name: "Processing fails due to connections mismatch (incorrect 'open' state)"
+                    .to_string(),
                 ctx: default_context
-                    .clone()
                     .with_client(&client_id, proof_height)
                     .with_connection(conn_id.clone(), conn_end_open),
                 msg: ConnectionMsg::ConnectionOpenAck(Box::new(msg_ack.clone())),
                 want_pass: false,
-                error_kind: Some(Kind::ConnectionMismatch(conn_id.clone()))
+                error_kind: Some(Kind::ConnectionMismatch(conn_id)),
             },
             Test {
-                name: "Processing fails: ConsensusStateVerificationFailure due to empty counterparty prefix".to_string(),
-                ctx: default_context
-                    .with_client(&client_id, proof_height)
-                    .with_connection(conn_id, conn_end_prefix),
+                name: "Processing fails: InvalidProof due to empty counterparty prefix".to_string(),
+                ctx: ctx_prefix,
                 msg: ConnectionMsg::ConnectionOpenAck(Box::new(msg_ack)),
                 want_pass: false,
-                error_kind: Some(Kind::ConsensusStateVerificationFailure(proof_height))
+                error_kind: Some(Kind::InvalidProof),
             },
             /*
             Test {
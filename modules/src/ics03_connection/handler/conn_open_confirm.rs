@@ -5,7 +5,6 @@ use crate::handler::{HandlerOutput, HandlerResult};
 use crate::ics03_connection::connection::{ConnectionEnd, Counterparty, State};
 use crate::ics03_connection::context::ConnectionReader;
 use crate::ics03_connection::error::{Error, Kind};
-use crate::ics03_connection::events::Attributes;
 use crate::ics03_connection::handler::verify::verify_proofs;
 use crate::ics03_connection::handler::{ConnectionIdState, ConnectionResult};
 use crate::ics03_connection::msgs::conn_open_confirm::MsgConnectionOpenConfirm;
@@ -52,23 +51,22 @@ pub(crate) fn process(
     );
 
     // 2. Pass the details to the verification function.
-    verify_proofs(ctx, None, &new_conn_end, &expected_conn, msg.proofs())?;
+    let new_client_state = verify_proofs(ctx, None, &new_conn_end, &expected_conn, msg.proofs())?;
 
     output.log("success: connection verification passed");
 
     // Transition our own end of the connection to state OPEN.
     new_conn_end.set_state(State::Open);
 
+    let client_id = new_conn_end.client_id().clone();
     let result = ConnectionResult {
         connection_id: msg.connection_id().clone(),
         connection_id_state: ConnectionIdState::Reused,
         connection_end: new_conn_end,
+        client_state: new_client_state.map(|cs| (client_id, cs)),
     };
 
-    let event_attributes = Attributes {
-        connection_id: Some(result.connection_id.clone()),
-        ..Default::default()
-    };
+    let event_attributes = result.event_attributes(ctx.host_current_height());
     output.emit(IbcEvent::OpenConfirmConnection(event_attributes.into()));
 
     Ok(output.with_result(result))
@@ -5,7 +5,6 @@ use crate::handler::{HandlerOutput, HandlerResult};
 use crate::ics03_connection::connection::{ConnectionEnd, State};
 use crate::ics03_connection::context::ConnectionReader;
 use crate::ics03_connection::error::{Error, Kind};
-use crate::ics03_connection::events::Attributes;
 use crate::ics03_connection::handler::{ConnectionIdState, ConnectionResult};
 use crate::ics03_connection::msgs::conn_open_init::MsgConnectionOpenInit;
 use crate::ics24_host::identifier::ConnectionId;
@@ -39,15 +38,13 @@ pub(crate) fn process(
     ));
 
     let result = ConnectionResult {
-        connection_id: conn_id.clone(),
+        connection_id: conn_id,
         connection_id_state: ConnectionIdState::Generated,
         connection_end: new_connection_end,
+        client_state: None,
     };
 
-    let event_attributes = Attributes {
-        connection_id: Some(conn_id),
-        ..Default::default()
-    };
+    let event_attributes = result.event_attributes(ctx.host_current_height());
     output.emit(IbcEvent::OpenInitConnection(event_attributes.into()));
 
     Ok(output.with_result(result))
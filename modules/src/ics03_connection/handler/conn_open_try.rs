@@ -5,7 +5,6 @@ use crate::handler::{HandlerOutput, HandlerResult};
 use crate::ics03_connection::connection::{ConnectionEnd, Counterparty, State};
 use crate::ics03_connection::context::ConnectionReader;
 use crate::ics03_connection::error::{Error, Kind};
-use crate::ics03_connection::events::Attributes;
 use crate::ics03_connection::handler::verify::{check_client_consensus_height, verify_proofs};
 use crate::ics03_connection::handler::{ConnectionIdState, ConnectionResult};
 use crate::ics03_connection::msgs::conn_open_try::MsgConnectionOpenTry;
@@ -68,6 +67,11 @@ pub(crate) fn process(
         }
     }?;
 
+    // The counterparty must run a valid client of this chain.
+    if let Some(client_state) = msg.client_state() {
+        ctx.validate_self_client(&client_state)?;
+    }
+
     // Proof verification in two steps:
     // 1. Setup: build the ConnectionEnd as we expect to find it on the other party.
     let expected_conn = ConnectionEnd::new(
@@ -79,7 +83,7 @@ pub(crate) fn process(
     );
 
     // 2. Pass the details to the verification function.
-    verify_proofs(
+    let new_client_state = verify_proofs(
         ctx,
         msg.client_state(),
         &new_connection_end,
@@ -100,20 +104,19 @@ pub(crate) fn process(
 
     output.log("success: connection verification passed");
 
+    let client_id = new_connection_end.client_id().clone();
     let result = ConnectionResult {
-        connection_id: conn_id.clone(),
+        connection_id: conn_id,
         connection_id_state: if matches!(msg.previous_connection_id, None) {
             ConnectionIdState::Generated
         } else {
             ConnectionIdState::Reused
         },
         connection_end: new_connection_end,
+        client_state: new_client_state.map(|cs| (client_id, cs)),
     };
 
-    let event_attributes = Attributes {
-        connection_id: Some(conn_id),
-        ..Default::default()
-    };
+    let event_attributes = result.event_attributes(ctx.host_current_height());
     output.emit(IbcEvent::OpenTryConnection(event_attributes.into()));
 
     Ok(output.with_result(result))
@@ -125,13 +128,17 @@ mod tests {
     use test_env_log::test;
 
     use crate::events::IbcEvent;
+    use crate::ics02_client::client_state::AnyClientState;
     use crate::ics03_connection::connection::State;
+    use crate::ics03_connection::error::Kind;
     use crate::ics03_connection::handler::{dispatch, ConnectionResult};
     use crate::ics03_connection::msgs::conn_open_try::test_util::get_dummy_raw_msg_conn_open_try;
     use crate::ics03_connection::msgs::conn_open_try::MsgConnectionOpenTry;
     use crate::ics03_connection::msgs::ConnectionMsg;
     use crate::ics24_host::identifier::ChainId;
+    use crate::mock::client_state::MockClientState;
     use crate::mock::context::MockContext;
+    use crate::mock::header::MockHeader;
     use crate::mock::host::HostType;
     use crate::Height;
 
@@ -263,4 +270,59 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn conn_open_try_validates_self_client() {
+        let host_chain_height = Height::new(0, 35);
+        let client_consensus_state_height = 10;
+        let context = MockContext::new(
+            ChainId::new("mockgaia".to_string(), 0),
+            HostType::Mock,
+            5,
+            host_chain_height,
+        );
+
+        let msg = MsgConnectionOpenTry::try_from(get_dummy_raw_msg_conn_open_try(
+            client_consensus_state_height,
+            host_chain_height.revision_height,
+        ))
+        .unwrap()
+        .with_previous_connection_id(None);
+        let context = context.with_client(
+            msg.client_id(),
+            Height::new(0, client_consensus_state_height),
+        );
+
+        let process = |client_state: AnyClientState| {
+            let msg = MsgConnectionOpenTry {
+                client_state: Some(client_state),
+                ..msg.clone()
+            };
+            dispatch(&context, ConnectionMsg::ConnectionOpenTry(Box::new(msg)))
+        };
+        let mock_client_state =
+            |height| AnyClientState::from(MockClientState::new(MockHeader::new(height)));
+
+        // The counterparty client of this chain is ahead of this chain.
+        let err = process(mock_client_state(host_chain_height.increment())).unwrap_err();
+        assert!(matches!(err.kind(), Kind::InvalidSelfClient));
+
+        // The counterparty client of this chain tracks the current, uncommitted height.
+        let err = process(mock_client_state(host_chain_height)).unwrap_err();
+        assert!(matches!(err.kind(), Kind::InvalidSelfClient));
+
+        // The counterparty client of this chain tracks another revision.
+        let err = process(mock_client_state(Height::new(1, 10))).unwrap_err();
+        assert!(matches!(err.kind(), Kind::InvalidSelfClient));
+
+        // The counterparty client of this chain is frozen.
+        let frozen = MockClientState::new(MockHeader::new(Height::new(0, 10)))
+            .with_frozen_height(Height::new(0, 10));
+        let err = process(AnyClientState::from(frozen)).unwrap_err();
+        assert!(matches!(err.kind(), Kind::InvalidSelfClient));
+
+        // A valid client passes the validation, yet the message carries no client proof.
+        let err = process(mock_client_state(host_chain_height.decrement().unwrap())).unwrap_err();
+        assert!(matches!(err.kind(), Kind::NullClientProof));
+    }
 }
@@ -1,7 +1,7 @@
 //! ICS3 verification functions, common across all four handlers of ICS3.
 
-use crate::ics02_client::client_consensus::ConsensusState;
-use crate::ics02_client::client_state::{AnyClientState, ClientState};
+use crate::ics02_client::client_consensus::{AnyConsensusState, ConsensusState};
+use crate::ics02_client::client_state::{AnyClientState, ClientState, Status};
 use crate::ics02_client::{client_def::AnyClient, client_def::ClientDef};
 use crate::ics03_connection::connection::ConnectionEnd;
 use crate::ics03_connection::context::ConnectionReader;
@@ -10,16 +10,28 @@ use crate::ics23_commitment::commitment::CommitmentProofBytes;
 use crate::proofs::{ConsensusProof, Proofs};
 use crate::Height;
 
-/// Entry point for verifying all proofs bundled in any ICS3 message.
+/// Entry point for verifying all proofs bundled in any ICS3 message. Each proof is verified by the
+/// client underlying `connection_end`, as left by the verification of the previous proofs.
+/// Returns the state of this client once it verified all the proofs, if verifying them changed it
+/// (see `ClientDef::client_state_after_verification`).
+///
+/// The proofs are verified in order: connection, client, then consensus proof. The first one is
+/// verified at the proof height of the message. Once a proof changed the client, the next one is
+/// verified at the new latest height of the client, e.g., a solo machine signs the proofs of a
+/// message at consecutive sequences, starting from the proof height.
 pub fn verify_proofs(
     ctx: &dyn ConnectionReader,
     client_state: Option<AnyClientState>,
     connection_end: &ConnectionEnd,
     expected_conn: &ConnectionEnd,
     proofs: &Proofs,
-) -> Result<(), Error> {
-    verify_connection_proof(
+) -> Result<Option<AnyClientState>, Error> {
+    // Fetch the client state (IBC client on the local chain), which must be active.
+    let local_client_state = active_client_state(ctx, connection_end)?;
+
+    let mut new_client_state = verify_connection_proof(
         ctx,
+        &local_client_state,
         connection_end,
         expected_conn,
         proofs.height(),
@@ -28,29 +40,39 @@ pub fn verify_proofs(
 
     // If the message includes a client state, then verify the proof for that state.
     if let Some(expected_client_state) = client_state {
-        verify_client_proof(
+        let verified_client_state = verify_client_proof(
             ctx,
+            new_client_state.as_ref().unwrap_or(&local_client_state),
             connection_end,
             expected_client_state,
-            proofs.height(),
+            next_proof_height(new_client_state.as_ref(), proofs),
             proofs
                 .client_proof()
                 .as_ref()
                 .ok_or(Kind::NullClientProof)?,
         )?;
+        new_client_state = verified_client_state.or(new_client_state);
     }
 
     // If a consensus proof is attached to the message, then verify it.
     if let Some(proof) = proofs.consensus_proof() {
-        Ok(verify_consensus_proof(
+        let verified_client_state = verify_consensus_proof(
             ctx,
+            new_client_state.as_ref().unwrap_or(&local_client_state),
             connection_end,
-            proofs.height(),
+            next_proof_height(new_client_state.as_ref(), proofs),
             &proof,
-        )?)
-    } else {
-        Ok(())
+        )?;
+        new_client_state = verified_client_state.or(new_client_state);
     }
+
+    Ok(new_client_state)
+}
+
+/// The height at which the next proof of a message is verified, given the state of the client
+/// once it verified the previous proofs, if verifying them changed it.
+fn next_proof_height(new_client_state: Option<&AnyClientState>, proofs: &Proofs) -> Height {
+    new_client_state.map_or_else(|| proofs.height(), |cs| cs.latest_height())
 }
 
 /// Verifies the authenticity and semantic correctness of a commitment `proof`. The commitment
@@ -58,48 +80,34 @@ pub fn verify_proofs(
 /// which created this proof). This object must match the state of `expected_conn`.
 pub fn verify_connection_proof(
     ctx: &dyn ConnectionReader,
+    client_state: &AnyClientState,
     connection_end: &ConnectionEnd,
     expected_conn: &ConnectionEnd,
     proof_height: Height,
     proof: &CommitmentProofBytes,
-) -> Result<(), Error> {
-    // Fetch the client state (IBC client on the local/host chain).
-    let client_state = ctx
-        .client_state(connection_end.client_id())
-        .ok_or_else(|| Kind::MissingClient(connection_end.client_id().clone()))?;
-
-    // The client must not be frozen.
-    if client_state.is_frozen() {
-        return Err(Kind::FrozenClient(connection_end.client_id().clone()).into());
-    }
-
-    // The client must have the consensus state for the height where this proof was created.
-    if ctx
-        .client_consensus_state(connection_end.client_id(), proof_height)
-        .is_none()
-    {
-        return Err(Kind::MissingClientConsensusState(
-            proof_height,
-            connection_end.client_id().clone(),
-        )
-        .into());
-    }
+) -> Result<Option<AnyClientState>, Error> {
+    let consensus_state =
+        verification_consensus_state(ctx, client_state, connection_end, proof_height)?;
 
     let client_def = AnyClient::from_client_type(client_state.client_type());
 
     // Verify the proof for the connection state against the expected connection end.
     // A counterparty connection id of None causes `unwrap()` below and indicates an internal
     // error as this is the connection id on the counterparty chain that must always be present.
-    Ok(client_def
+    client_def
         .verify_connection_state(
-            &client_state,
+            ctx,
+            client_state,
             proof_height,
             connection_end.counterparty().prefix(),
             proof,
+            consensus_state.root(),
             connection_end.counterparty().connection_id(),
             expected_conn,
         )
-        .map_err(|_| Kind::InvalidProof)?)
+        .map_err(|_| Kind::InvalidProof)?;
+
+    client_state_after_verification(client_state, proof_height, proof)
 }
 
 /// Verifies the client `proof` from a connection handshake message, typically from a
@@ -111,31 +119,21 @@ pub fn verify_connection_proof(
 /// `proof` is correct.
 pub fn verify_client_proof(
     ctx: &dyn ConnectionReader,
+    client_state: &AnyClientState,
     connection_end: &ConnectionEnd,
     expected_client_state: AnyClientState,
     proof_height: Height,
     proof: &CommitmentProofBytes,
-) -> Result<(), Error> {
-    // Fetch the local client state (IBC client running on the host chain).
-    let client_state = ctx
-        .client_state(connection_end.client_id())
-        .ok_or_else(|| Kind::MissingClient(connection_end.client_id().clone()))?;
-
-    if client_state.is_frozen() {
-        return Err(Kind::FrozenClient(connection_end.client_id().clone()).into());
-    }
-
-    let consensus_state = ctx
-        .client_consensus_state(connection_end.client_id(), proof_height)
-        .ok_or_else(|| {
-            Kind::MissingClientConsensusState(proof_height, connection_end.client_id().clone())
-        })?;
+) -> Result<Option<AnyClientState>, Error> {
+    let consensus_state =
+        verification_consensus_state(ctx, client_state, connection_end, proof_height)?;
 
     let client_def = AnyClient::from_client_type(client_state.client_type());
 
-    Ok(client_def
+    client_def
         .verify_client_full_state(
-            &client_state,
+            ctx,
+            client_state,
             proof_height,
             consensus_state.root(),
             connection_end.counterparty().prefix(),
@@ -146,23 +144,20 @@ pub fn verify_client_proof(
         .map_err(|e| {
             Kind::ClientStateVerificationFailure(connection_end.client_id().clone())
                 .context(e.to_string())
-        })?)
+        })?;
+
+    client_state_after_verification(client_state, proof_height, proof)
 }
 
 pub fn verify_consensus_proof(
     ctx: &dyn ConnectionReader,
+    client_state: &AnyClientState,
     connection_end: &ConnectionEnd,
     proof_height: Height,
     proof: &ConsensusProof,
-) -> Result<(), Error> {
-    // Fetch the client state (IBC client on the local chain).
-    let client_state = ctx
-        .client_state(connection_end.client_id())
-        .ok_or_else(|| Kind::MissingClient(connection_end.client_id().clone()))?;
-
-    if client_state.is_frozen() {
-        return Err(Kind::FrozenClient(connection_end.client_id().clone()).into());
-    }
+) -> Result<Option<AnyClientState>, Error> {
+    let consensus_state =
+        verification_consensus_state(ctx, client_state, connection_end, proof_height)?;
 
     // Fetch the expected consensus state from the historical (local) header data.
     let expected_consensus = ctx
@@ -171,19 +166,75 @@ pub fn verify_consensus_proof(
 
     let client = AnyClient::from_client_type(client_state.client_type());
 
-    Ok(client
+    client
         .verify_client_consensus_state(
-            &client_state,
+            ctx,
+            client_state,
             proof_height,
             connection_end.counterparty().prefix(),
             proof.proof(),
+            consensus_state.root(),
             connection_end.counterparty().client_id(),
             proof.height(),
             &expected_consensus,
         )
         .map_err(|e| {
             Kind::ConsensusStateVerificationFailure(proof.height()).context(e.to_string())
-        })?)
+        })?;
+
+    client_state_after_verification(client_state, proof_height, proof.proof())
+}
+
+/// Fetches the consensus state of `client_state`, the client underlying `connection_end`, against
+/// which a proof at `proof_height` is verified: the one the client keeps in its state, if any, or
+/// the one the client has for the height where the proof was created.
+fn verification_consensus_state(
+    ctx: &dyn ConnectionReader,
+    client_state: &AnyClientState,
+    connection_end: &ConnectionEnd,
+    proof_height: Height,
+) -> Result<AnyConsensusState, Error> {
+    client_state
+        .own_consensus_state()
+        .or_else(|| ctx.client_consensus_state(connection_end.client_id(), proof_height))
+        .ok_or_else(|| {
+            Kind::MissingClientConsensusState(proof_height, connection_end.client_id().clone())
+                .into()
+        })
+}
+
+/// Returns the state of the client once it verified `proof`, at `proof_height`, if verifying the
+/// proof changed it.
+fn client_state_after_verification(
+    client_state: &AnyClientState,
+    proof_height: Height,
+    proof: &CommitmentProofBytes,
+) -> Result<Option<AnyClientState>, Error> {
+    let client_def = AnyClient::from_client_type(client_state.client_type());
+
+    Ok(client_def
+        .client_state_after_verification(client_state, proof_height, proof)
+        .map_err(|e| Kind::InvalidProof.context(e.to_string()))?)
+}
+
+/// Fetches the state of the client underlying `connection_end`, which must be active, i.e.,
+/// neither frozen nor expired.
+fn active_client_state(
+    ctx: &dyn ConnectionReader,
+    connection_end: &ConnectionEnd,
+) -> Result<AnyClientState, Error> {
+    let client_id = connection_end.client_id();
+    let client_state = ctx
+        .client_state(client_id)
+        .ok_or_else(|| Kind::MissingClient(client_id.clone()))?;
+
+    let latest_consensus_state =
+        ctx.client_consensus_state(client_id, client_state.latest_height());
+    match client_state.status(ctx.host_timestamp(), latest_consensus_state.as_ref()) {
+        Status::Active => Ok(client_state),
+        Status::Frozen => Err(Kind::FrozenClient(client_id.clone()).into()),
+        status => Err(Kind::ClientNotActive(client_id.clone(), status).into()),
+    }
 }
 
 /// Checks that `claimed_height` is within normal bounds, i.e., fresh enough so that the chain has
@@ -17,10 +17,36 @@ pub struct Version {
 }
 
 impl Version {
+    pub fn new(identifier: String, features: Vec<String>) -> Self {
+        Self {
+            identifier,
+            features,
+        }
+    }
+
+    /// Getter for the identifier of this version.
+    pub fn identifier(&self) -> &str {
+        &self.identifier
+    }
+
+    /// Getter for the features supported by this version.
+    pub fn features(&self) -> &[String] {
+        &self.features
+    }
+
     /// Checks whether or not the given feature is supported in this versin
     pub fn is_supported_feature(&self, feature: String) -> bool {
         self.features.contains(&feature)
     }
+
+    /// Checks whether `proposed` can be agreed upon given that this version is supported, i.e.,
+    /// whether both have the same identifier and `proposed` has a non-empty set of features, all
+    /// of which are supported.
+    pub fn verify_proposed_version(&self, proposed: &Version) -> bool {
+        self.identifier == proposed.identifier
+            && !proposed.features.is_empty()
+            && proposed.features.iter().all(|f| self.features.contains(f))
+    }
 }
 
 impl Protobuf<RawVersion> for Version {}
@@ -65,12 +91,28 @@ impl Default for Version {
     }
 }
 
+/// Half of the generated versions are the supported version, which the handshake accepts.
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Version {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        if u.arbitrary()? {
+            return Ok(Self::default());
+        }
+        Ok(Version {
+            identifier: u.arbitrary()?,
+            features: u.arbitrary()?,
+        })
+    }
+}
+
 /// Returns the lists of supported versions
 pub fn get_compatible_versions() -> Vec<Version> {
     vec![Version::default()]
 }
 
-/// Selects a version from the intersection of locally supported and counterparty versions.
+/// Selects a version from the intersection of locally supported and counterparty versions. The
+/// picked version carries the features which both parties support, and versions which have no
+/// feature in common are not picked.
 pub fn pick_version(
     supported_versions: Vec<Version>,
     counterparty_versions: Vec<Version>,
@@ -81,8 +123,18 @@ pub fn pick_version(
             if c.identifier != s.identifier {
                 continue;
             }
-            // TODO - perform feature intersection and error if empty
-            intersection.append(&mut vec![s.clone()]);
+
+            let features: Vec<String> = s
+                .features
+                .iter()
+                .filter(|f| c.features.contains(f))
+                .cloned()
+                .collect();
+            if features.is_empty() {
+                continue;
+            }
+
+            intersection.push(Version::new(s.identifier.clone(), features));
         }
     }
     intersection.sort_by(|a, b| a.identifier.cmp(&b.identifier));
@@ -137,11 +189,11 @@ mod tests {
                 Version::default(),
                 Version {
                     identifier: "3".to_string(),
-                    features: vec![],
+                    features: vec!["ORDER_ORDERED".to_string(), "ORDER_UNORDERED".to_string()],
                 },
                 Version {
                     identifier: "4".to_string(),
-                    features: vec![],
+                    features: vec!["ORDER_ORDERED".to_string()],
                 },
             ]
             .into_iter()
@@ -149,27 +201,45 @@ mod tests {
             vec![
                 Version {
                     identifier: "2".to_string(),
-                    features: vec![],
+                    features: vec!["ORDER_ORDERED".to_string()],
                 },
                 Version {
                     identifier: "4".to_string(),
-                    features: vec![],
+                    features: vec!["ORDER_ORDERED".to_string()],
                 },
                 Version {
                     identifier: "3".to_string(),
-                    features: vec![],
+                    features: vec!["ORDER_UNORDERED".to_string()],
                 },
             ]
             .into_iter()
             .collect(),
-            // Should pick version 3 as it's the lowest of the intersection {3, 4}
+            // Should pick version 3 as it's the lowest of the intersection {3, 4}, with the
+            // features common to both parties only
             Version {
                 identifier: "3".to_string(),
-                features: vec![],
+                features: vec!["ORDER_UNORDERED".to_string()],
             },
         )
     }
 
+    fn unordered_only() -> Vec<Version> {
+        vec![Version {
+            identifier: "1".to_string(),
+            features: vec!["ORDER_UNORDERED".to_string()],
+        }]
+    }
+
+    fn disjoint_features() -> (Vec<Version>, Vec<Version>) {
+        (
+            vec![Version {
+                identifier: "1".to_string(),
+                features: vec!["ORDER_ORDERED".to_string()],
+            }],
+            unordered_only(),
+        )
+    }
+
     fn disjoint() -> (Vec<Version>, Vec<Version>) {
         (
             vec![Version {
@@ -262,6 +332,20 @@ mod tests {
                 picked: Some(overlapping().2),
                 want_pass: true,
             },
+            Test {
+                name: "Intersecting features".to_string(),
+                supported: get_compatible_versions(),
+                counterparty: unordered_only(),
+                picked: Some(unordered_only()[0].clone()),
+                want_pass: true,
+            },
+            Test {
+                name: "Disjoint features".to_string(),
+                supported: disjoint_features().0,
+                counterparty: disjoint_features().1,
+                picked: None,
+                want_pass: false,
+            },
             Test {
                 name: "Disjoint versions".to_string(),
                 supported: disjoint().0,
@@ -286,6 +370,27 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn verify_proposed() {
+        let supported = Version::default();
+
+        assert!(supported.verify_proposed_version(&Version::default()));
+        assert!(supported.verify_proposed_version(&unordered_only()[0]));
+        assert!(!supported.verify_proposed_version(&Version {
+            identifier: "1".to_string(),
+            features: vec![],
+        }));
+        assert!(!supported.verify_proposed_version(&Version {
+            identifier: "1".to_string(),
+            features: vec!["ORDER_RANDOM".to_string()],
+        }));
+        assert!(!supported.verify_proposed_version(&Version {
+            identifier: "2".to_string(),
+            features: vec!["ORDER_UNORDERED".to_string()],
+        }));
+    }
+
     #[test]
     fn serialize() {
         let def = Version::default();
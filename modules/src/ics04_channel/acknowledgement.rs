@@ -0,0 +1,79 @@
+//! The standard acknowledgement which applications write after receiving a packet, either the
+//! result of processing the packet or the reason it failed. It is JSON encoded, in the same way as
+//! `ibc-go` encodes its `channeltypes.Acknowledgement`.
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::ics04_channel::error::{Error, Kind};
+
+/// The result of a successful acknowledgement, namely the base64 encoding of the byte `0x01`.
+pub const ACK_SUCCESS_B64: &str = "AQ==";
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Acknowledgement {
+    /// The packet was processed; holds the base64 encoded result.
+    Result(String),
+    /// The packet could not be processed; holds the reason of the failure.
+    Error(String),
+}
+
+impl Acknowledgement {
+    pub fn success() -> Self {
+        Self::Result(ACK_SUCCESS_B64.to_string())
+    }
+
+    pub fn error(reason: impl ToString) -> Self {
+        Self::Error(reason.to_string())
+    }
+
+    pub fn is_success(&self) -> bool {
+        matches!(self, Self::Result(_))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        // Serializing a single string field cannot fail.
+        serde_json::to_vec(self).unwrap()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        serde_json::from_slice(bytes).map_err(|e| Kind::MalformedAcknowledgement.context(e).into())
+    }
+}
+
+impl std::fmt::Display for Acknowledgement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Result(result) => write!(f, "result: {}", result),
+            Self::Error(reason) => write!(f, "error: {}", reason),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_env_log::test;
+
+    use super::Acknowledgement;
+
+    #[test]
+    fn acknowledgement_json_encoding() {
+        let success = Acknowledgement::success();
+        assert_eq!(success.to_bytes(), br#"{"result":"AQ=="}"#.to_vec());
+        assert!(Acknowledgement::from_bytes(&success.to_bytes())
+            .unwrap()
+            .is_success());
+
+        let error = Acknowledgement::error("insufficient funds");
+        assert_eq!(
+            error.to_bytes(),
+            br#"{"error":"insufficient funds"}"#.to_vec()
+        );
+        assert_eq!(
+            Acknowledgement::from_bytes(&error.to_bytes()).unwrap(),
+            error
+        );
+
+        assert!(Acknowledgement::from_bytes(&[0]).is_err());
+    }
+}
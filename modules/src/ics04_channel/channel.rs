@@ -81,6 +81,7 @@ impl From<IdentifiedChannelEnd> for RawIdentifiedChannel {
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ChannelEnd {
     pub state: State,
     pub ordering: Order,
@@ -252,6 +253,7 @@ impl ChannelEnd {
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Counterparty {
     pub port_id: PortId,
     pub channel_id: Option<ChannelId>,
@@ -320,6 +322,7 @@ impl From<Counterparty> for RawCounterparty {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum Order {
     None = 0,
     Unordered,
@@ -363,16 +366,18 @@ impl FromStr for Order {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // The latter names are those which `ibc-go` emits in the packet events.
         match s.to_lowercase().as_str() {
-            "uninitialized" => Ok(Self::None),
-            "unordered" => Ok(Self::Unordered),
-            "ordered" => Ok(Self::Ordered),
+            "uninitialized" | "order_none_unspecified" => Ok(Self::None),
+            "unordered" | "order_unordered" => Ok(Self::Unordered),
+            "ordered" | "order_ordered" => Ok(Self::Ordered),
             _ => fail!(error::Kind::UnknownOrderType, s),
         }
     }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum State {
     Uninitialized = 0,
     Init = 1,
@@ -0,0 +1,129 @@
+//! The commitments which a chain stores for the packets it sends and for the acknowledgements it
+//! writes, computed as per ICS4 so that they match those of `ibc-go` byte for byte.
+
+use sha2::{Digest, Sha256};
+
+use crate::timestamp::Timestamp;
+use crate::Height;
+
+/// The commitment to a packet sent by this chain, stored under the `commitments` path.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PacketCommitment(Vec<u8>);
+
+impl PacketCommitment {
+    /// Computes the commitment to a packet, i.e., the SHA-256 hash of the concatenation of the
+    /// timeout timestamp, the revision number and the revision height of the timeout height, all
+    /// three encoded as big-endian 64-bit integers, and of the SHA-256 hash of the packet data.
+    pub fn compute(data: &[u8], timeout_height: Height, timeout_timestamp: Timestamp) -> Self {
+        let mut hash_input = Vec::with_capacity(3 * 8 + 32);
+        hash_input.extend_from_slice(&timeout_timestamp.as_nanoseconds().to_be_bytes());
+        hash_input.extend_from_slice(&timeout_height.revision_number.to_be_bytes());
+        hash_input.extend_from_slice(&timeout_height.revision_height.to_be_bytes());
+        hash_input.extend_from_slice(&Sha256::digest(data));
+
+        Self(Sha256::digest(&hash_input).to_vec())
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn into_vec(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+impl From<Vec<u8>> for PacketCommitment {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+}
+
+/// The commitment to an acknowledgement written by this chain, stored under the `acks` path.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AcknowledgementCommitment(Vec<u8>);
+
+impl AcknowledgementCommitment {
+    /// Computes the commitment to an acknowledgement, i.e., its SHA-256 hash.
+    pub fn compute(ack: &[u8]) -> Self {
+        Self(Sha256::digest(ack).to_vec())
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn into_vec(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+impl From<Vec<u8>> for AcknowledgementCommitment {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use subtle_encoding::hex;
+    use test_env_log::test;
+
+    use crate::timestamp::Timestamp;
+    use crate::Height;
+
+    use super::{AcknowledgementCommitment, PacketCommitment};
+
+    /// The expected values are the outputs of `CommitPacket` and `CommitAcknowledgement` of the
+    /// `ibc-go` channel types on the same inputs.
+    #[test]
+    fn commitments_match_ibc_go() {
+        let data = br#"{"amount":"100","denom":"stake","receiver":"cosmos1receiver","sender":"cosmos1sender"}"#;
+
+        struct Test {
+            name: String,
+            timeout_height: Height,
+            timeout_timestamp: Timestamp,
+            commitment: &'static str,
+        }
+
+        let tests: Vec<Test> = vec![
+            Test {
+                name: "Timeout height only".to_string(),
+                timeout_height: Height::new(0, 10),
+                timeout_timestamp: Timestamp::none(),
+                commitment: "efb598d871bc2d5437866f11639243b6a3bab6e886fc471db8cb0eb917f24b21",
+            },
+            Test {
+                name: "Timeout timestamp only".to_string(),
+                timeout_height: Height::zero(),
+                timeout_timestamp: Timestamp::from_nanoseconds(1_600_000_000_000_000_000).unwrap(),
+                commitment: "54ac6945a8ba7bf0ed3c079e69c47ada18df7ebcaa43b0ab15fb98c4d1f5a312",
+            },
+            Test {
+                name: "Timeout height and timestamp".to_string(),
+                timeout_height: Height::new(1, 100),
+                timeout_timestamp: Timestamp::from_nanoseconds(1_600_000_000_000_000_000).unwrap(),
+                commitment: "fedae20b054425096334323ab663ae731207eb0563791353a613cba0d248553f",
+            },
+        ];
+
+        for test in tests {
+            let commitment =
+                PacketCommitment::compute(data, test.timeout_height, test.timeout_timestamp);
+            assert_eq!(
+                hex::encode(commitment.as_bytes()),
+                test.commitment.as_bytes(),
+                "{}",
+                test.name
+            );
+        }
+
+        // The acknowledgement of a successful ICS20 transfer.
+        let ack = AcknowledgementCommitment::compute(br#"{"result":"AQ=="}"#);
+        assert_eq!(
+            hex::encode(ack.as_bytes()),
+            b"08f7557ed51826fe18d84512bf24ec75001edbaf2123a477df72a0a9f3640a7c".to_vec()
+        );
+    }
+}
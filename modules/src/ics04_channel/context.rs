@@ -2,10 +2,13 @@
 //! the interface that any host chain must implement to be able to process any `ChannelMsg`.
 //!
 
+use std::time::Duration;
+
 use crate::ics02_client::client_consensus::AnyConsensusState;
 use crate::ics02_client::client_state::AnyClientState;
 use crate::ics03_connection::connection::ConnectionEnd;
 use crate::ics04_channel::channel::ChannelEnd;
+use crate::ics04_channel::commitment::{AcknowledgementCommitment, PacketCommitment};
 use crate::ics04_channel::handler::{ChannelIdState, ChannelResult};
 use crate::ics04_channel::{error::Error, packet::Receipt};
 use crate::ics05_port::capabilities::Capability;
@@ -35,7 +38,26 @@ pub trait ChannelReader {
         height: Height,
     ) -> Option<AnyConsensusState>;
 
-    fn authenticated_capability(&self, port_id: &PortId) -> Result<Capability, Error>;
+    /// Returns the time at which the consensus state of the client at `height` was stored on the
+    /// local chain. Packet proofs verified against this consensus state are accepted only once the
+    /// delay period of the connection has passed since this time.
+    fn client_update_time(&self, client_id: &ClientId, height: Height) -> Option<Timestamp>;
+
+    /// Returns the height of the local chain at which the consensus state of the client at
+    /// `height` was stored.
+    fn client_update_height(&self, client_id: &ClientId, height: Height) -> Option<Height>;
+
+    /// Returns true if `capability` is the capability of the port `port_id`, i.e., if the caller
+    /// presenting it is the module which bound the port.
+    fn authenticate_port_capability(&self, port_id: &PortId, capability: &Capability) -> bool;
+
+    /// Returns true if `capability` is the capability of the channel `port_channel_id`, i.e., if
+    /// the caller presenting it is one of the modules which own the channel.
+    fn authenticate_channel_capability(
+        &self,
+        port_channel_id: &(PortId, ChannelId),
+        capability: &Capability,
+    ) -> bool;
 
     fn get_next_sequence_send(&self, port_channel_id: &(PortId, ChannelId)) -> Option<Sequence>;
 
@@ -43,14 +65,17 @@ pub trait ChannelReader {
 
     fn get_next_sequence_ack(&self, port_channel_id: &(PortId, ChannelId)) -> Option<Sequence>;
 
-    fn get_packet_commitment(&self, key: &(PortId, ChannelId, Sequence)) -> Option<String>;
+    fn get_packet_commitment(
+        &self,
+        key: &(PortId, ChannelId, Sequence),
+    ) -> Option<PacketCommitment>;
 
     fn get_packet_receipt(&self, key: &(PortId, ChannelId, Sequence)) -> Option<Receipt>;
 
-    fn get_packet_acknowledgement(&self, key: &(PortId, ChannelId, Sequence)) -> Option<String>;
-
-    /// A hashing function for packet commitments
-    fn hash(&self, value: String) -> String;
+    fn get_packet_acknowledgement(
+        &self,
+        key: &(PortId, ChannelId, Sequence),
+    ) -> Option<AcknowledgementCommitment>;
 
     /// Returns the current height of the local chain.
     fn host_height(&self) -> Height;
@@ -58,6 +83,10 @@ pub trait ChannelReader {
     /// Returns the current timestamp of the local chain.
     fn host_timestamp(&self) -> Timestamp;
 
+    /// Returns the maximum expected time between two blocks of the local chain, from which the
+    /// number of blocks of a connection delay period is derived.
+    fn max_expected_time_per_block(&self) -> Duration;
+
     /// Returns a counter on the number of channel ids have been created thus far.
     /// The value of this counter should increase only via method
     /// `ChannelKeeper::increase_channel_counter`.
@@ -79,6 +108,9 @@ pub trait ChannelKeeper {
         if matches!(result.channel_id_state, ChannelIdState::Generated) {
             self.increase_channel_counter();
 
+            // The module which owns the port also owns the new channel.
+            self.create_channel_capability((result.port_id.clone(), result.channel_id.clone()))?;
+
             // Associate also the channel end to its connection.
             self.store_connection_channels(
                 result.channel_end.connection_hops()[0].clone(),
@@ -110,9 +142,7 @@ pub trait ChannelKeeper {
 
                 self.store_packet_commitment(
                     (res.port_id.clone(), res.channel_id.clone(), res.seq),
-                    res.timeout_timestamp,
-                    res.timeout_height,
-                    res.data,
+                    res.commitment,
                 )?;
             }
             PacketResult::Recv(res) => {
@@ -136,24 +166,16 @@ pub trait ChannelKeeper {
             PacketResult::WriteAck(res) => {
                 self.store_packet_acknowledgement(
                     (res.port_id.clone(), res.channel_id.clone(), res.seq),
-                    res.ack,
+                    res.ack_commitment,
                 )?;
             }
             PacketResult::Ack(res) => {
-                match res.seq_number {
-                    Some(s) => {
-                        //Ordered Channel
-                        self.store_next_sequence_ack((res.port_id.clone(), res.channel_id), s)?;
-                    }
-                    None => {
-                        //Unordered Channel
-                        self.delete_packet_acknowledgement((
-                            res.port_id.clone(),
-                            res.channel_id.clone(),
-                            res.seq,
-                        ))?;
-                    }
+                if let Some(s) = res.seq_number {
+                    //Ordered Channel
+                    self.store_next_sequence_ack((res.port_id.clone(), res.channel_id.clone()), s)?;
                 }
+                // The packet is acknowledged, so it can neither be acknowledged again, nor time out.
+                self.delete_packet_commitment((res.port_id, res.channel_id, res.seq))?;
             }
             PacketResult::Timeout(res) => {
                 if let Some(c) = res.channel {
@@ -173,9 +195,7 @@ pub trait ChannelKeeper {
     fn store_packet_commitment(
         &mut self,
         key: (PortId, ChannelId, Sequence),
-        timestamp: Timestamp,
-        heigh: Height,
-        data: Vec<u8>,
+        commitment: PacketCommitment,
     ) -> Result<(), Error>;
 
     fn delete_packet_commitment(&mut self, key: (PortId, ChannelId, Sequence))
@@ -190,7 +210,7 @@ pub trait ChannelKeeper {
     fn store_packet_acknowledgement(
         &mut self,
         key: (PortId, ChannelId, Sequence),
-        ack: Vec<u8>,
+        ack_commitment: AcknowledgementCommitment,
     ) -> Result<(), Error>;
 
     fn delete_packet_acknowledgement(
@@ -229,6 +249,12 @@ pub trait ChannelKeeper {
         seq: Sequence,
     ) -> Result<(), Error>;
 
+    /// Creates the capability of a new channel, owned by the module which owns its port.
+    fn create_channel_capability(
+        &mut self,
+        port_channel_id: (PortId, ChannelId),
+    ) -> Result<Capability, Error>;
+
     /// Called upon channel identifier creation (Init or Try message processing).
     /// Increases the counter which keeps track of how many channels have been created.
     /// Should never fail.
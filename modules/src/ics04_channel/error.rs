@@ -4,7 +4,8 @@ use thiserror::Error;
 pub type Error = anomaly::Error<Kind>;
 
 use super::packet::Sequence;
-use crate::ics04_channel::channel::State;
+use crate::ics02_client::client_state::Status;
+use crate::ics04_channel::channel::{Order, State};
 use crate::ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId};
 use crate::timestamp::Timestamp;
 use crate::{ics02_client, Height};
@@ -82,8 +83,8 @@ pub enum Kind {
     #[error("single version must be negociated on connection before opening channel")]
     InvalidVersionLengthConnection,
 
-    #[error("the channel ordering is not supported by connection ")]
-    ChannelFeatureNotSuportedByConnection,
+    #[error("the channel ordering {0} is not supported by connection ")]
+    ChannelFeatureNotSuportedByConnection(Order),
 
     #[error("the channel end ({0}, {1}) does not exist")]
     ChannelNotFound(PortId, ChannelId),
@@ -108,6 +109,9 @@ pub enum Kind {
     #[error("Acknowledgment cannot be empty")]
     InvalidAcknowledgement,
 
+    #[error("the acknowledgement is not a JSON encoded result or error")]
+    MalformedAcknowledgement,
+
     #[error("Packet acknowledgement exists for the packet with the sequence {0}")]
     AcknowledgementExists(Sequence),
 
@@ -144,9 +148,27 @@ pub enum Kind {
     #[error("Client with id {0} is frozen")]
     FrozenClient(ClientId),
 
+    #[error("Client with id {0} is not active, its status is {1}")]
+    ClientNotActive(ClientId, Status),
+
     #[error("Missing client consensus state for client id {0} at height {1}")]
     MissingClientConsensusState(ClientId, Height),
 
+    #[error("Missing processed time of the consensus state of client {0} at height {1}")]
+    ProcessedTimeNotFound(ClientId, Height),
+
+    #[error("Missing processed height of the consensus state of client {0} at height {1}")]
+    ProcessedHeightNotFound(ClientId, Height),
+
+    #[error("Connection delay not passed: chain timestamp {0} < earliest valid timestamp {1}")]
+    DelayTimeNotPassed(Timestamp, Timestamp),
+
+    #[error("Connection delay not passed: chain height {0} < earliest valid height {1}")]
+    DelayHeightNotPassed(Height, Height),
+
+    #[error("Connection delay cannot be checked: chain timestamp {0}, processed time {1}")]
+    MissingDelayTimestamp(Timestamp, Timestamp),
+
     #[error("Invalid channel id in counterparty")]
     InvalidCounterpartyChannelId,
 
@@ -173,6 +195,18 @@ pub enum Kind {
 
     #[error("Missing sequence number for ack packets")]
     MissingNextAckSeq,
+
+    #[error("The capability does not authenticate the channel {0}/{1}")]
+    InvalidChannelCapability(PortId, ChannelId),
+
+    #[error("No application module is bound to port {0}")]
+    RouteNotFound(PortId),
+
+    #[error("error raised by the application module")]
+    AppModule,
+
+    #[error("The application module does not accept messages of type {0}")]
+    UnsupportedAppMessage(String),
 }
 
 impl Kind {
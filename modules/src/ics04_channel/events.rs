@@ -1,12 +1,16 @@
 //! Types for the IBC events emitted from Tendermint Websocket by the channels module.
-use crate::events::{IbcEvent, RawObject};
+use crate::events::{extract_attribute, optional_attribute, tag, IbcEvent, RawObject};
 use crate::ics02_client::height::Height;
+use crate::ics04_channel::channel::Order;
 use crate::ics04_channel::packet::Packet;
 use crate::ics24_host::identifier::{ChannelId, ConnectionId, PortId};
 use crate::{attribute, some_attribute};
 use anomaly::BoxError;
 use serde_derive::{Deserialize, Serialize};
 use std::convert::{TryFrom, TryInto};
+use subtle_encoding::hex;
+use tendermint::abci::tag::Tag;
+use tendermint::abci::Event as AbciEvent;
 
 /// Channel event types
 const OPEN_INIT_EVENT_TYPE: &str = "channel_open_init";
@@ -25,131 +29,234 @@ const COUNTERPARTY_PORT_ID_ATTRIBUTE_KEY: &str = "counterparty_port_id";
 
 /// Packet event types
 const SEND_PACKET: &str = "send_packet";
+const RECEIVE_PACKET: &str = "recv_packet";
 const WRITE_ACK: &str = "write_acknowledgement";
 const ACK_PACKET: &str = "acknowledge_packet";
 const TIMEOUT: &str = "timeout_packet";
+const TIMEOUT_ON_CLOSE: &str = "timeout_on_close_packet";
 
 /// Packet event attribute keys
 const PKT_SEQ_ATTRIBUTE_KEY: &str = "packet_sequence";
 const PKT_DATA_ATTRIBUTE_KEY: &str = "packet_data";
+const PKT_DATA_HEX_ATTRIBUTE_KEY: &str = "packet_data_hex";
 const PKT_SRC_PORT_ATTRIBUTE_KEY: &str = "packet_src_port";
 const PKT_SRC_CHANNEL_ATTRIBUTE_KEY: &str = "packet_src_channel";
 const PKT_DST_PORT_ATTRIBUTE_KEY: &str = "packet_dst_port";
 const PKT_DST_CHANNEL_ATTRIBUTE_KEY: &str = "packet_dst_channel";
 const PKT_TIMEOUT_HEIGHT_ATTRIBUTE_KEY: &str = "packet_timeout_height";
 const PKT_TIMEOUT_TIMESTAMP_ATTRIBUTE_KEY: &str = "packet_timeout_timestamp";
+const PKT_CHANNEL_ORDERING_ATTRIBUTE_KEY: &str = "packet_channel_ordering";
+const PKT_CONNECTION_ATTRIBUTE_KEY: &str = "packet_connection";
 
 const PKT_ACK_ATTRIBUTE_KEY: &str = "packet_ack";
+const PKT_ACK_HEX_ATTRIBUTE_KEY: &str = "packet_ack_hex";
 
-pub fn try_from_tx(event: &tendermint::abci::Event) -> Option<IbcEvent> {
-    match event.type_str.as_str() {
-        OPEN_INIT_EVENT_TYPE => Some(IbcEvent::OpenInitChannel(OpenInit::from(
-            extract_attributes_from_tx(event),
-        ))),
-        OPEN_TRY_EVENT_TYPE => Some(IbcEvent::OpenTryChannel(OpenTry::from(
-            extract_attributes_from_tx(event),
-        ))),
-        OPEN_ACK_EVENT_TYPE => Some(IbcEvent::OpenAckChannel(OpenAck::from(
-            extract_attributes_from_tx(event),
-        ))),
-        OPEN_CONFIRM_EVENT_TYPE => Some(IbcEvent::OpenConfirmChannel(OpenConfirm::from(
-            extract_attributes_from_tx(event),
-        ))),
-        CLOSE_INIT_EVENT_TYPE => Some(IbcEvent::CloseInitChannel(CloseInit::from(
-            extract_attributes_from_tx(event),
-        ))),
-        CLOSE_CONFIRM_EVENT_TYPE => Some(IbcEvent::CloseConfirmChannel(CloseConfirm::from(
-            extract_attributes_from_tx(event),
-        ))),
-        SEND_PACKET => {
-            let (packet, write_ack) = extract_packet_and_write_ack_from_tx(event);
-            // This event should not have a write ack.
-            assert!(write_ack.is_none());
-            Some(IbcEvent::SendPacket(SendPacket {
-                height: Default::default(),
-                packet,
-            }))
+/// Parses a channel or packet event from the attributes of `event`. Returns `None` if `event` is
+/// not a channel or packet event, or if it is malformed.
+pub fn try_from_tx(event: &AbciEvent) -> Option<IbcEvent> {
+    try_from_abci_event(event).ok().flatten()
+}
+
+/// Parses a channel or packet event from the attributes of `event`. Returns `None` if `event` is
+/// not a channel or packet event, and an error if it is a malformed one.
+pub fn try_from_abci_event(event: &AbciEvent) -> Result<Option<IbcEvent>, BoxError> {
+    let ibc_event = match event.type_str.as_str() {
+        OPEN_INIT_EVENT_TYPE => {
+            IbcEvent::OpenInitChannel(OpenInit::from(extract_attributes_from_tx(event)?))
         }
-        WRITE_ACK => {
-            let (packet, write_ack) = extract_packet_and_write_ack_from_tx(event);
-            // This event should have a write ack.
-            let write_ack = write_ack.unwrap();
-            Some(IbcEvent::WriteAcknowledgement(WriteAcknowledgement {
-                height: Default::default(),
-                packet,
-                ack: write_ack,
-            }))
+        OPEN_TRY_EVENT_TYPE => {
+            IbcEvent::OpenTryChannel(OpenTry::from(extract_attributes_from_tx(event)?))
         }
-        ACK_PACKET => {
-            let (packet, write_ack) = extract_packet_and_write_ack_from_tx(event);
-            // This event should not have a write ack.
-            assert!(write_ack.is_none());
-            Some(IbcEvent::AcknowledgePacket(AcknowledgePacket {
-                height: Default::default(),
-                packet,
-            }))
+        OPEN_ACK_EVENT_TYPE => {
+            IbcEvent::OpenAckChannel(OpenAck::from(extract_attributes_from_tx(event)?))
         }
-        TIMEOUT => {
-            let (packet, write_ack) = extract_packet_and_write_ack_from_tx(event);
-            // This event should not have a write ack.
-            assert!(write_ack.is_none());
-            Some(IbcEvent::TimeoutPacket(TimeoutPacket {
-                height: Default::default(),
-                packet,
-            }))
+        OPEN_CONFIRM_EVENT_TYPE => {
+            IbcEvent::OpenConfirmChannel(OpenConfirm::from(extract_attributes_from_tx(event)?))
         }
-        _ => None,
-    }
-}
-
-fn extract_attributes_from_tx(event: &tendermint::abci::Event) -> Attributes {
-    let mut attr = Attributes::default();
-
-    for tag in &event.attributes {
-        let key = tag.key.as_ref();
-        let value = tag.value.as_ref();
-        match key {
-            PORT_ID_ATTRIBUTE_KEY => attr.port_id = value.parse().unwrap(),
-            CHANNEL_ID_ATTRIBUTE_KEY => attr.channel_id = value.parse().ok(),
-            CONNECTION_ID_ATTRIBUTE_KEY => attr.connection_id = value.parse().unwrap(),
-            COUNTERPARTY_PORT_ID_ATTRIBUTE_KEY => {
-                attr.counterparty_port_id = value.parse().unwrap()
-            }
-            COUNTERPARTY_CHANNEL_ID_ATTRIBUTE_KEY => {
-                attr.counterparty_channel_id = value.parse().ok()
-            }
-            _ => {}
+        CLOSE_INIT_EVENT_TYPE => {
+            IbcEvent::CloseInitChannel(CloseInit::from(extract_attributes_from_tx(event)?))
         }
+        CLOSE_CONFIRM_EVENT_TYPE => {
+            IbcEvent::CloseConfirmChannel(CloseConfirm::from(extract_attributes_from_tx(event)?))
+        }
+        SEND_PACKET => IbcEvent::SendPacket(SendPacket {
+            height: Default::default(),
+            packet: extract_packet_from_tx(event)?,
+            channel_ordering: extract_channel_ordering_from_tx(event)?,
+            connection_id: extract_connection_from_tx(event)?,
+        }),
+        RECEIVE_PACKET => IbcEvent::ReceivePacket(ReceivePacket {
+            height: Default::default(),
+            packet: extract_packet_from_tx(event)?,
+            channel_ordering: extract_channel_ordering_from_tx(event)?,
+            connection_id: extract_connection_from_tx(event)?,
+        }),
+        WRITE_ACK => IbcEvent::WriteAcknowledgement(WriteAcknowledgement {
+            height: Default::default(),
+            packet: extract_packet_from_tx(event)?,
+            ack: extract_bytes_from_tx(event, PKT_ACK_HEX_ATTRIBUTE_KEY, PKT_ACK_ATTRIBUTE_KEY)?,
+            connection_id: extract_connection_from_tx(event)?,
+        }),
+        ACK_PACKET => IbcEvent::AcknowledgePacket(AcknowledgePacket {
+            height: Default::default(),
+            packet: extract_packet_from_tx(event)?,
+            channel_ordering: extract_channel_ordering_from_tx(event)?,
+            connection_id: extract_connection_from_tx(event)?,
+        }),
+        TIMEOUT => IbcEvent::TimeoutPacket(TimeoutPacket {
+            height: Default::default(),
+            packet: extract_packet_from_tx(event)?,
+            channel_ordering: extract_channel_ordering_from_tx(event)?,
+        }),
+        TIMEOUT_ON_CLOSE => IbcEvent::TimeoutOnClosePacket(TimeoutOnClosePacket {
+            height: Default::default(),
+            packet: extract_packet_from_tx(event)?,
+            channel_ordering: extract_channel_ordering_from_tx(event)?,
+        }),
+        _ => return Ok(None),
+    };
+
+    Ok(Some(ibc_event))
+}
+
+/// The channel identifiers are empty until the channel ends are initialized.
+fn extract_attributes_from_tx(event: &AbciEvent) -> Result<Attributes, BoxError> {
+    Ok(Attributes {
+        height: Default::default(),
+        port_id: extract_attribute(event, PORT_ID_ATTRIBUTE_KEY)?.parse()?,
+        channel_id: optional_id(extract_attribute(event, CHANNEL_ID_ATTRIBUTE_KEY)?)?,
+        connection_id: extract_attribute(event, CONNECTION_ID_ATTRIBUTE_KEY)?.parse()?,
+        counterparty_port_id: extract_attribute(event, COUNTERPARTY_PORT_ID_ATTRIBUTE_KEY)?
+            .parse()?,
+        counterparty_channel_id: optional_id(extract_attribute(
+            event,
+            COUNTERPARTY_CHANNEL_ID_ATTRIBUTE_KEY,
+        )?)?,
+    })
+}
+
+/// Parses a channel identifier, which is empty if unknown.
+fn optional_id(value: &str) -> Result<Option<ChannelId>, BoxError> {
+    if value.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(value.parse()?))
     }
+}
+
+/// The acknowledgement and timeout events carry no packet data, which is then left empty.
+fn extract_packet_from_tx(event: &AbciEvent) -> Result<Packet, BoxError> {
+    Ok(Packet {
+        sequence: extract_attribute(event, PKT_SEQ_ATTRIBUTE_KEY)?.parse()?,
+        source_port: extract_attribute(event, PKT_SRC_PORT_ATTRIBUTE_KEY)?.parse()?,
+        source_channel: extract_attribute(event, PKT_SRC_CHANNEL_ATTRIBUTE_KEY)?.parse()?,
+        destination_port: extract_attribute(event, PKT_DST_PORT_ATTRIBUTE_KEY)?.parse()?,
+        destination_channel: extract_attribute(event, PKT_DST_CHANNEL_ATTRIBUTE_KEY)?.parse()?,
+        data: optional_bytes_from_tx(event, PKT_DATA_HEX_ATTRIBUTE_KEY, PKT_DATA_ATTRIBUTE_KEY)?
+            .unwrap_or_default(),
+        timeout_height: extract_attribute(event, PKT_TIMEOUT_HEIGHT_ATTRIBUTE_KEY)?.parse()?,
+        timeout_timestamp: extract_attribute(event, PKT_TIMEOUT_TIMESTAMP_ATTRIBUTE_KEY)?
+            .parse()?,
+    })
+}
+
+/// The ordering of the channel of a packet event. Older `ibc-go` versions do not emit it.
+fn extract_channel_ordering_from_tx(event: &AbciEvent) -> Result<Option<Order>, BoxError> {
+    Ok(
+        optional_attribute(event, PKT_CHANNEL_ORDERING_ATTRIBUTE_KEY)
+            .map(str::parse)
+            .transpose()?,
+    )
+}
+
+/// The connection of the channel of a packet event. Older `ibc-go` versions do not emit it.
+fn extract_connection_from_tx(event: &AbciEvent) -> Result<Option<ConnectionId>, BoxError> {
+    Ok(optional_attribute(event, PKT_CONNECTION_ATTRIBUTE_KEY)
+        .map(str::parse)
+        .transpose()?)
+}
 
-    attr
+/// Extracts the bytes which are emitted both hex encoded, under `hex_key`, and as a string, under
+/// `key`. The former is preferred, as the string is lossy if the bytes are not valid UTF-8.
+fn extract_bytes_from_tx(event: &AbciEvent, hex_key: &str, key: &str) -> Result<Vec<u8>, BoxError> {
+    optional_bytes_from_tx(event, hex_key, key)?
+        .ok_or_else(|| format!("missing attribute {} in {} event", key, event.type_str).into())
 }
 
-fn extract_packet_and_write_ack_from_tx(
-    event: &tendermint::abci::Event,
-) -> (Packet, Option<Vec<u8>>) {
-    let mut packet = Packet::default();
-    let mut write_ack = None;
-    for tag in &event.attributes {
-        let key = tag.key.as_ref();
-        let value = tag.value.as_ref();
-        match key {
-            PKT_SRC_PORT_ATTRIBUTE_KEY => packet.source_port = value.parse().unwrap(),
-            PKT_SRC_CHANNEL_ATTRIBUTE_KEY => packet.source_channel = value.parse().unwrap(),
-            PKT_DST_PORT_ATTRIBUTE_KEY => packet.destination_port = value.parse().unwrap(),
-            PKT_DST_CHANNEL_ATTRIBUTE_KEY => packet.destination_channel = value.parse().unwrap(),
-            PKT_SEQ_ATTRIBUTE_KEY => packet.sequence = value.parse::<u64>().unwrap().into(),
-            PKT_TIMEOUT_HEIGHT_ATTRIBUTE_KEY => packet.timeout_height = value.parse().unwrap(),
-            PKT_TIMEOUT_TIMESTAMP_ATTRIBUTE_KEY => {
-                packet.timeout_timestamp = value.parse().unwrap()
-            }
-            PKT_DATA_ATTRIBUTE_KEY => packet.data = Vec::from(value.as_bytes()),
-            PKT_ACK_ATTRIBUTE_KEY => write_ack = Some(Vec::from(value.as_bytes())),
-            _ => {}
-        };
+/// Like `extract_bytes_from_tx`, but returns `None` if neither attribute is present.
+fn optional_bytes_from_tx(
+    event: &AbciEvent,
+    hex_key: &str,
+    key: &str,
+) -> Result<Option<Vec<u8>>, BoxError> {
+    match optional_attribute(event, hex_key) {
+        Some(value) => Ok(Some(hex::decode(value)?)),
+        None => Ok(optional_attribute(event, key).map(|value| value.as_bytes().to_vec())),
     }
+}
+
+/// Returns the attributes holding `bytes`, as a string under `key` and hex encoded under `hex_key`.
+fn bytes_attributes(key: &str, hex_key: &str, bytes: &[u8]) -> Vec<Tag> {
+    vec![
+        tag(key, String::from_utf8_lossy(bytes)),
+        tag(hex_key, String::from_utf8(hex::encode(bytes)).unwrap()),
+    ]
+}
+
+/// The attributes of the packet data, which only the send, receive and write acknowledgement
+/// events carry.
+fn packet_data_attributes(packet: &Packet) -> Vec<Tag> {
+    bytes_attributes(
+        PKT_DATA_ATTRIBUTE_KEY,
+        PKT_DATA_HEX_ATTRIBUTE_KEY,
+        &packet.data,
+    )
+}
+
+/// The attributes which all packet events carry, as `ibc-go` emits them.
+fn packet_attributes(packet: Packet) -> Vec<Tag> {
+    vec![
+        tag(PKT_TIMEOUT_HEIGHT_ATTRIBUTE_KEY, packet.timeout_height),
+        tag(
+            PKT_TIMEOUT_TIMESTAMP_ATTRIBUTE_KEY,
+            packet.timeout_timestamp.as_nanoseconds(),
+        ),
+        tag(PKT_SEQ_ATTRIBUTE_KEY, packet.sequence),
+        tag(PKT_SRC_PORT_ATTRIBUTE_KEY, packet.source_port),
+        tag(PKT_SRC_CHANNEL_ATTRIBUTE_KEY, packet.source_channel),
+        tag(PKT_DST_PORT_ATTRIBUTE_KEY, packet.destination_port),
+        tag(PKT_DST_CHANNEL_ATTRIBUTE_KEY, packet.destination_channel),
+    ]
+}
 
-    (packet, write_ack)
+/// The attributes of the channel of a packet, each emitted only if known.
+fn channel_attributes(
+    channel_ordering: Option<Order>,
+    connection_id: Option<ConnectionId>,
+) -> Vec<Tag> {
+    let ordering = channel_ordering.map(|o| tag(PKT_CHANNEL_ORDERING_ATTRIBUTE_KEY, o));
+    let connection = connection_id.map(|c| tag(PKT_CONNECTION_ATTRIBUTE_KEY, c));
+    ordering.into_iter().chain(connection).collect()
+}
+
+/// The attributes of a channel event, as `ibc-go` emits them. The height is not among them.
+impl From<Attributes> for Vec<Tag> {
+    fn from(attrs: Attributes) -> Self {
+        let id_or_empty = |id: Option<ChannelId>| id.map_or_else(String::new, |id| id.to_string());
+        vec![
+            tag(PORT_ID_ATTRIBUTE_KEY, attrs.port_id),
+            tag(CHANNEL_ID_ATTRIBUTE_KEY, id_or_empty(attrs.channel_id)),
+            tag(
+                COUNTERPARTY_PORT_ID_ATTRIBUTE_KEY,
+                attrs.counterparty_port_id,
+            ),
+            tag(
+                COUNTERPARTY_CHANNEL_ID_ATTRIBUTE_KEY,
+                id_or_empty(attrs.counterparty_channel_id),
+            ),
+            tag(CONNECTION_ID_ATTRIBUTE_KEY, attrs.connection_id),
+        ]
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -234,6 +341,15 @@ impl From<OpenInit> for IbcEvent {
     }
 }
 
+impl From<OpenInit> for AbciEvent {
+    fn from(v: OpenInit) -> Self {
+        AbciEvent {
+            type_str: OPEN_INIT_EVENT_TYPE.to_string(),
+            attributes: v.0.into(),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct OpenTry(Attributes);
 
@@ -284,6 +400,15 @@ impl From<OpenTry> for IbcEvent {
     }
 }
 
+impl From<OpenTry> for AbciEvent {
+    fn from(v: OpenTry) -> Self {
+        AbciEvent {
+            type_str: OPEN_TRY_EVENT_TYPE.to_string(),
+            attributes: v.0.into(),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct OpenAck(Attributes);
 
@@ -338,6 +463,15 @@ impl From<OpenAck> for IbcEvent {
     }
 }
 
+impl From<OpenAck> for AbciEvent {
+    fn from(v: OpenAck) -> Self {
+        AbciEvent {
+            type_str: OPEN_ACK_EVENT_TYPE.to_string(),
+            attributes: v.0.into(),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct OpenConfirm(Attributes);
 
@@ -388,6 +522,15 @@ impl From<OpenConfirm> for IbcEvent {
     }
 }
 
+impl From<OpenConfirm> for AbciEvent {
+    fn from(v: OpenConfirm) -> Self {
+        AbciEvent {
+            type_str: OPEN_CONFIRM_EVENT_TYPE.to_string(),
+            attributes: v.0.into(),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct CloseInit(Attributes);
 
@@ -450,6 +593,15 @@ impl From<CloseInit> for IbcEvent {
     }
 }
 
+impl From<CloseInit> for AbciEvent {
+    fn from(v: CloseInit) -> Self {
+        AbciEvent {
+            type_str: CLOSE_INIT_EVENT_TYPE.to_string(),
+            attributes: v.0.into(),
+        }
+    }
+}
+
 impl std::fmt::Display for CloseInit {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
         write!(
@@ -506,6 +658,15 @@ impl From<CloseConfirm> for IbcEvent {
     }
 }
 
+impl From<CloseConfirm> for AbciEvent {
+    fn from(v: CloseConfirm) -> Self {
+        AbciEvent {
+            type_str: CLOSE_CONFIRM_EVENT_TYPE.to_string(),
+            attributes: v.0.into(),
+        }
+    }
+}
+
 #[macro_export]
 macro_rules! p_attribute {
     ($a:ident, $b:literal) => {{
@@ -514,6 +675,16 @@ macro_rules! p_attribute {
     }};
 }
 
+#[macro_export]
+macro_rules! p_some_attribute {
+    ($a:ident, $b:literal) => {{
+        let nb = format!("{}.{}", $a.action, $b);
+        $a.events
+            .get(&nb)
+            .and_then(|tags| tags[$a.idx].parse().ok())
+    }};
+}
+
 impl TryFrom<RawObject> for Packet {
     type Error = BoxError;
     fn try_from(obj: RawObject) -> Result<Self, Self::Error> {
@@ -536,6 +707,10 @@ impl TryFrom<RawObject> for Packet {
 pub struct SendPacket {
     pub height: Height,
     pub packet: Packet,
+    /// The ordering of the channel of the packet, if known.
+    pub channel_ordering: Option<Order>,
+    /// The connection of the channel of the packet, if known.
+    pub connection_id: Option<ConnectionId>,
 }
 
 impl SendPacket {
@@ -564,9 +739,16 @@ impl TryFrom<RawObject> for SendPacket {
     fn try_from(obj: RawObject) -> Result<Self, Self::Error> {
         let height = obj.height;
         let data_str: String = p_attribute!(obj, "packet_data");
+        let channel_ordering = p_some_attribute!(obj, "packet_channel_ordering");
+        let connection_id = p_some_attribute!(obj, "packet_connection");
         let mut packet = Packet::try_from(obj)?;
         packet.data = Vec::from(data_str.as_str().as_bytes());
-        Ok(SendPacket { height, packet })
+        Ok(SendPacket {
+            height,
+            packet,
+            channel_ordering,
+            connection_id,
+        })
     }
 }
 
@@ -576,6 +758,18 @@ impl From<SendPacket> for IbcEvent {
     }
 }
 
+impl From<SendPacket> for AbciEvent {
+    fn from(v: SendPacket) -> Self {
+        let mut attributes = packet_data_attributes(&v.packet);
+        attributes.extend(packet_attributes(v.packet));
+        attributes.extend(channel_attributes(v.channel_ordering, v.connection_id));
+        AbciEvent {
+            type_str: SEND_PACKET.to_string(),
+            attributes,
+        }
+    }
+}
+
 impl std::fmt::Display for SendPacket {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
         write!(f, "SendPacket - h:{}, {}", self.height, self.packet)
@@ -586,6 +780,10 @@ impl std::fmt::Display for SendPacket {
 pub struct ReceivePacket {
     pub height: Height,
     pub packet: Packet,
+    /// The ordering of the channel of the packet, if known.
+    pub channel_ordering: Option<Order>,
+    /// The connection of the channel of the packet, if known.
+    pub connection_id: Option<ConnectionId>,
 }
 
 impl ReceivePacket {
@@ -614,9 +812,16 @@ impl TryFrom<RawObject> for ReceivePacket {
     fn try_from(obj: RawObject) -> Result<Self, Self::Error> {
         let height = obj.height;
         let data_str: String = p_attribute!(obj, "packet_data");
+        let channel_ordering = p_some_attribute!(obj, "packet_channel_ordering");
+        let connection_id = p_some_attribute!(obj, "packet_connection");
         let mut packet = Packet::try_from(obj)?;
         packet.data = Vec::from(data_str.as_str().as_bytes());
-        Ok(ReceivePacket { height, packet })
+        Ok(ReceivePacket {
+            height,
+            packet,
+            channel_ordering,
+            connection_id,
+        })
     }
 }
 
@@ -626,6 +831,18 @@ impl From<ReceivePacket> for IbcEvent {
     }
 }
 
+impl From<ReceivePacket> for AbciEvent {
+    fn from(v: ReceivePacket) -> Self {
+        let mut attributes = packet_data_attributes(&v.packet);
+        attributes.extend(packet_attributes(v.packet));
+        attributes.extend(channel_attributes(v.channel_ordering, v.connection_id));
+        AbciEvent {
+            type_str: RECEIVE_PACKET.to_string(),
+            attributes,
+        }
+    }
+}
+
 impl std::fmt::Display for ReceivePacket {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
         write!(f, "ReceivePacket - h:{}, {}", self.height, self.packet)
@@ -638,6 +855,8 @@ pub struct WriteAcknowledgement {
     pub packet: Packet,
     #[serde(serialize_with = "crate::serializers::ser_hex_upper")]
     pub ack: Vec<u8>,
+    /// The connection of the channel of the packet, if known.
+    pub connection_id: Option<ConnectionId>,
 }
 
 impl WriteAcknowledgement {
@@ -667,12 +886,14 @@ impl TryFrom<RawObject> for WriteAcknowledgement {
         let height = obj.height;
         let data_str: String = p_attribute!(obj, "packet_data");
         let ack_str: String = p_attribute!(obj, "packet_ack");
+        let connection_id = p_some_attribute!(obj, "packet_connection");
         let mut packet = Packet::try_from(obj)?;
         packet.data = Vec::from(data_str.as_str().as_bytes());
         Ok(WriteAcknowledgement {
             height,
             packet,
             ack: Vec::from(ack_str.as_str().as_bytes()),
+            connection_id,
         })
     }
 }
@@ -683,6 +904,23 @@ impl From<WriteAcknowledgement> for IbcEvent {
     }
 }
 
+impl From<WriteAcknowledgement> for AbciEvent {
+    fn from(v: WriteAcknowledgement) -> Self {
+        let mut attributes = packet_data_attributes(&v.packet);
+        attributes.extend(packet_attributes(v.packet));
+        attributes.extend(bytes_attributes(
+            PKT_ACK_ATTRIBUTE_KEY,
+            PKT_ACK_HEX_ATTRIBUTE_KEY,
+            &v.ack,
+        ));
+        attributes.extend(channel_attributes(None, v.connection_id));
+        AbciEvent {
+            type_str: WRITE_ACK.to_string(),
+            attributes,
+        }
+    }
+}
+
 impl std::fmt::Display for WriteAcknowledgement {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
         write!(
@@ -697,6 +935,10 @@ impl std::fmt::Display for WriteAcknowledgement {
 pub struct AcknowledgePacket {
     pub height: Height,
     pub packet: Packet,
+    /// The ordering of the channel of the packet, if known.
+    pub channel_ordering: Option<Order>,
+    /// The connection of the channel of the packet, if known.
+    pub connection_id: Option<ConnectionId>,
 }
 
 impl AcknowledgePacket {
@@ -718,8 +960,15 @@ impl TryFrom<RawObject> for AcknowledgePacket {
     type Error = BoxError;
     fn try_from(obj: RawObject) -> Result<Self, Self::Error> {
         let height = obj.height;
+        let channel_ordering = p_some_attribute!(obj, "packet_channel_ordering");
+        let connection_id = p_some_attribute!(obj, "packet_connection");
         let packet = Packet::try_from(obj)?;
-        Ok(AcknowledgePacket { height, packet })
+        Ok(AcknowledgePacket {
+            height,
+            packet,
+            channel_ordering,
+            connection_id,
+        })
     }
 }
 
@@ -729,6 +978,17 @@ impl From<AcknowledgePacket> for IbcEvent {
     }
 }
 
+impl From<AcknowledgePacket> for AbciEvent {
+    fn from(v: AcknowledgePacket) -> Self {
+        let mut attributes = packet_attributes(v.packet);
+        attributes.extend(channel_attributes(v.channel_ordering, v.connection_id));
+        AbciEvent {
+            type_str: ACK_PACKET.to_string(),
+            attributes,
+        }
+    }
+}
+
 impl std::fmt::Display for AcknowledgePacket {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
         write!(f, "h:{}, {}", self.height, self.packet)
@@ -739,6 +999,8 @@ impl std::fmt::Display for AcknowledgePacket {
 pub struct TimeoutPacket {
     pub height: Height,
     pub packet: Packet,
+    /// The ordering of the channel of the packet, if known.
+    pub channel_ordering: Option<Order>,
 }
 
 impl TimeoutPacket {
@@ -767,6 +1029,7 @@ impl TryFrom<RawObject> for TimeoutPacket {
     fn try_from(obj: RawObject) -> Result<Self, Self::Error> {
         Ok(TimeoutPacket {
             height: obj.height,
+            channel_ordering: p_some_attribute!(obj, "packet_channel_ordering"),
             packet: Packet::try_from(obj)?,
         })
     }
@@ -778,6 +1041,17 @@ impl From<TimeoutPacket> for IbcEvent {
     }
 }
 
+impl From<TimeoutPacket> for AbciEvent {
+    fn from(v: TimeoutPacket) -> Self {
+        let mut attributes = packet_attributes(v.packet);
+        attributes.extend(channel_attributes(v.channel_ordering, None));
+        AbciEvent {
+            type_str: TIMEOUT.to_string(),
+            attributes,
+        }
+    }
+}
+
 impl std::fmt::Display for TimeoutPacket {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
         write!(f, "TimeoutPacket - h:{}, {}", self.height, self.packet)
@@ -788,6 +1062,8 @@ impl std::fmt::Display for TimeoutPacket {
 pub struct TimeoutOnClosePacket {
     pub height: Height,
     pub packet: Packet,
+    /// The ordering of the channel of the packet, if known.
+    pub channel_ordering: Option<Order>,
 }
 
 impl TimeoutOnClosePacket {
@@ -816,6 +1092,7 @@ impl TryFrom<RawObject> for TimeoutOnClosePacket {
     fn try_from(obj: RawObject) -> Result<Self, Self::Error> {
         Ok(TimeoutOnClosePacket {
             height: obj.height,
+            channel_ordering: p_some_attribute!(obj, "packet_channel_ordering"),
             packet: Packet::try_from(obj)?,
         })
     }
@@ -827,6 +1104,17 @@ impl From<TimeoutOnClosePacket> for IbcEvent {
     }
 }
 
+impl From<TimeoutOnClosePacket> for AbciEvent {
+    fn from(v: TimeoutOnClosePacket) -> Self {
+        let mut attributes = packet_attributes(v.packet);
+        attributes.extend(channel_attributes(v.channel_ordering, None));
+        AbciEvent {
+            type_str: TIMEOUT_ON_CLOSE.to_string(),
+            attributes,
+        }
+    }
+}
+
 impl std::fmt::Display for TimeoutOnClosePacket {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
         write!(
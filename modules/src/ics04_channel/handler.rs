@@ -1,13 +1,20 @@
 //! This module implements the processing logic for ICS4 (channel) messages.
 
+use std::sync::Arc;
+
 use crate::handler::HandlerOutput;
+use crate::ics02_client::client_state::AnyClientState;
 use crate::ics04_channel::channel::ChannelEnd;
 use crate::ics04_channel::context::ChannelReader;
-use crate::ics04_channel::error::Error;
+use crate::ics04_channel::error::{Error, Kind};
+use crate::ics04_channel::events::Attributes;
 use crate::ics04_channel::msgs::ChannelMsg;
 use crate::ics04_channel::{msgs::PacketMsg, packet::PacketResult};
-use crate::ics05_port::capabilities::Capability;
-use crate::ics24_host::identifier::{ChannelId, PortId};
+use crate::ics05_port::capabilities::{Capability, CapabilityName};
+use crate::ics24_host::identifier::{ChannelId, ClientId, PortId};
+use crate::ics26_routing::context::Ics26Context;
+use crate::ics26_routing::module::Module;
+use crate::Height;
 
 pub mod acknowledgement;
 pub mod chan_close_confirm;
@@ -41,36 +48,253 @@ pub struct ChannelResult {
     pub channel_id_state: ChannelIdState,
     pub channel_cap: Capability,
     pub channel_end: ChannelEnd,
+    /// The new state of the client which verified the proofs of the message, if verifying them
+    /// changed it.
+    pub client_state: Option<(ClientId, AnyClientState)>,
+}
+
+impl ChannelResult {
+    /// Returns the attributes of the event which the handler emits at `height`.
+    pub(crate) fn event_attributes(&self, height: Height) -> Attributes {
+        let counterparty = self.channel_end.counterparty();
+        Attributes {
+            height,
+            port_id: self.port_id.clone(),
+            channel_id: Some(self.channel_id.clone()),
+            connection_id: self
+                .channel_end
+                .connection_hops()
+                .first()
+                .cloned()
+                .unwrap_or_default(),
+            counterparty_port_id: counterparty.port_id().clone(),
+            counterparty_channel_id: counterparty.channel_id().cloned(),
+        }
+    }
 }
 
 /// General entry point for processing any type of message related to the ICS4 channel open and
-/// channel close handshake protocols.
+/// channel close handshake protocols. The message is processed on behalf of the module presenting
+/// `capability` (see `channel_capability`).
 pub fn channel_dispatch<Ctx>(
     ctx: &Ctx,
+    capability: &Capability,
     msg: ChannelMsg,
 ) -> Result<HandlerOutput<ChannelResult>, Error>
 where
     Ctx: ChannelReader,
 {
     match msg {
-        ChannelMsg::ChannelOpenInit(msg) => chan_open_init::process(ctx, msg),
-        ChannelMsg::ChannelOpenTry(msg) => chan_open_try::process(ctx, msg),
-        ChannelMsg::ChannelOpenAck(msg) => chan_open_ack::process(ctx, msg),
-        ChannelMsg::ChannelOpenConfirm(msg) => chan_open_confirm::process(ctx, msg),
-        ChannelMsg::ChannelCloseInit(msg) => chan_close_init::process(ctx, msg),
-        ChannelMsg::ChannelCloseConfirm(msg) => chan_close_confirm::process(ctx, msg),
+        ChannelMsg::ChannelOpenInit(msg) => chan_open_init::process(ctx, capability, msg),
+        ChannelMsg::ChannelOpenTry(msg) => chan_open_try::process(ctx, capability, msg),
+        ChannelMsg::ChannelOpenAck(msg) => chan_open_ack::process(ctx, capability, msg),
+        ChannelMsg::ChannelOpenConfirm(msg) => chan_open_confirm::process(ctx, capability, msg),
+        ChannelMsg::ChannelCloseInit(msg) => chan_close_init::process(ctx, capability, msg),
+        ChannelMsg::ChannelCloseConfirm(msg) => chan_close_confirm::process(ctx, capability, msg),
     }
 }
 
-/// Dispatcher for processing any type of message related to the ICS4 packet protocols.
-pub fn packet_dispatch<Ctx>(ctx: &Ctx, msg: PacketMsg) -> Result<HandlerOutput<PacketResult>, Error>
+/// Dispatcher for processing any type of message related to the ICS4 packet protocols. The
+/// message is processed on behalf of the module presenting `capability` (see `packet_capability`).
+pub fn packet_dispatch<Ctx>(
+    ctx: &Ctx,
+    capability: &Capability,
+    msg: PacketMsg,
+) -> Result<HandlerOutput<PacketResult>, Error>
 where
     Ctx: ChannelReader,
 {
     match msg {
-        PacketMsg::RecvPacket(msg) => recv_packet::process(ctx, msg),
-        PacketMsg::AckPacket(msg) => acknowledgement::process(ctx, msg),
-        PacketMsg::ToPacket(msg) => timeout::process(ctx, msg),
-        PacketMsg::ToClosePacket(msg) => timeout_on_close::process(ctx, msg),
+        PacketMsg::RecvPacket(msg) => recv_packet::process(ctx, capability, msg),
+        PacketMsg::AckPacket(msg) => acknowledgement::process(ctx, capability, msg),
+        PacketMsg::ToPacket(msg) => timeout::process(ctx, capability, msg),
+        PacketMsg::ToClosePacket(msg) => timeout_on_close::process(ctx, capability, msg),
+    }
+}
+
+/// Returns the capability which the application module bound to `port_id` holds for the port
+/// itself, or for its channel `channel_id` if given. Fails if the module does not hold it.
+fn module_capability<Ctx>(
+    ctx: &Ctx,
+    port_id: &PortId,
+    channel_id: Option<&ChannelId>,
+) -> Result<Capability, Error>
+where
+    Ctx: Ics26Context,
+{
+    let (module_id, port_cap) = ctx
+        .lookup_module_by_port(port_id)
+        .ok_or_else(|| Kind::NoPortCapability(port_id.clone()))?;
+
+    match channel_id {
+        None => Ok(port_cap),
+        Some(channel_id) => ctx
+            .get_capability(&module_id, &CapabilityName::channel(port_id, channel_id))
+            .ok_or_else(|| {
+                Kind::InvalidChannelCapability(port_id.clone(), channel_id.clone()).into()
+            }),
+    }
+}
+
+/// Returns the capability with which the application module bound to the port of the channel
+/// acts upon the handshake message `msg`: the capability of the port to open a new channel, or
+/// that of the channel otherwise.
+pub fn channel_capability<Ctx>(ctx: &Ctx, msg: &ChannelMsg) -> Result<Capability, Error>
+where
+    Ctx: Ics26Context,
+{
+    let (port_id, channel_id) = match msg {
+        ChannelMsg::ChannelOpenInit(msg) => (msg.port_id(), None),
+        ChannelMsg::ChannelOpenTry(msg) => (msg.port_id(), None),
+        ChannelMsg::ChannelOpenAck(msg) => (msg.port_id(), Some(msg.channel_id())),
+        ChannelMsg::ChannelOpenConfirm(msg) => (msg.port_id(), Some(msg.channel_id())),
+        ChannelMsg::ChannelCloseInit(msg) => (msg.port_id(), Some(msg.channel_id())),
+        ChannelMsg::ChannelCloseConfirm(msg) => (msg.port_id(), Some(msg.channel_id())),
+    };
+
+    module_capability(ctx, port_id, channel_id)
+}
+
+/// Returns the capability with which the application module bound to the port of the packet
+/// acts upon the packet message `msg`, namely that of the destination channel of a received
+/// packet, or that of the source channel otherwise.
+pub fn packet_capability<Ctx>(ctx: &Ctx, msg: &PacketMsg) -> Result<Capability, Error>
+where
+    Ctx: Ics26Context,
+{
+    let (port_id, channel_id) = match msg {
+        PacketMsg::RecvPacket(msg) => (
+            &msg.packet.destination_port,
+            &msg.packet.destination_channel,
+        ),
+        PacketMsg::AckPacket(msg) => (&msg.packet.source_port, &msg.packet.source_channel),
+        PacketMsg::ToPacket(msg) => (&msg.packet.source_port, &msg.packet.source_channel),
+        PacketMsg::ToClosePacket(msg) => (&msg.packet.source_port, &msg.packet.source_channel),
+    };
+
+    module_capability(ctx, port_id, Some(channel_id))
+}
+
+/// Returns the application module bound to `port_id`.
+fn route<Ctx>(ctx: &Ctx, port_id: &PortId) -> Result<Arc<dyn Module<Ctx>>, Error>
+where
+    Ctx: Ics26Context,
+{
+    ctx.router()
+        .route(port_id)
+        .ok_or_else(|| Kind::RouteNotFound(port_id.clone()).into())
+}
+
+/// Hands a channel handshake message, as processed by `channel_dispatch`, over to the application
+/// module bound to the port of the channel. The module may abort the handshake by returning an
+/// error, and it determines the version of the channel upon `MsgChannelOpenInit` and
+/// `MsgChannelOpenTry`, in which case the version in `result` is updated accordingly.
+pub fn channel_callback<Ctx>(
+    ctx: &mut Ctx,
+    msg: &ChannelMsg,
+    result: &mut ChannelResult,
+) -> Result<(), Error>
+where
+    Ctx: Ics26Context,
+{
+    let module = route(ctx, &result.port_id)?;
+    let channel_end = &result.channel_end;
+
+    match msg {
+        ChannelMsg::ChannelOpenInit(msg) => {
+            let version = module.on_chan_open_init(
+                ctx,
+                *channel_end.ordering(),
+                channel_end.connection_hops(),
+                &result.port_id,
+                &result.channel_id,
+                channel_end.counterparty(),
+                &msg.channel().version(),
+            )?;
+            result.channel_end.set_version(version);
+        }
+        ChannelMsg::ChannelOpenTry(msg) => {
+            let version = module.on_chan_open_try(
+                ctx,
+                *channel_end.ordering(),
+                channel_end.connection_hops(),
+                &result.port_id,
+                &result.channel_id,
+                channel_end.counterparty(),
+                &msg.channel().version(),
+                msg.counterparty_version(),
+            )?;
+            result.channel_end.set_version(version);
+        }
+        ChannelMsg::ChannelOpenAck(msg) => module.on_chan_open_ack(
+            ctx,
+            &result.port_id,
+            &result.channel_id,
+            msg.counterparty_version(),
+        )?,
+        ChannelMsg::ChannelOpenConfirm(_) => {
+            module.on_chan_open_confirm(ctx, &result.port_id, &result.channel_id)?
+        }
+        ChannelMsg::ChannelCloseInit(_) => {
+            module.on_chan_close_init(ctx, &result.port_id, &result.channel_id)?
+        }
+        ChannelMsg::ChannelCloseConfirm(_) => {
+            module.on_chan_close_confirm(ctx, &result.port_id, &result.channel_id)?
+        }
+    }
+
+    Ok(())
+}
+
+/// Hands a packet message, once processed by `packet_dispatch` and stored, over to the application
+/// module which sent or received the packet. The acknowledgement which the module returns for a
+/// received packet is written to the host chain store.
+pub fn packet_callback<Ctx>(ctx: &mut Ctx, msg: &PacketMsg) -> Result<HandlerOutput<()>, Error>
+where
+    Ctx: Ics26Context,
+{
+    match msg {
+        PacketMsg::RecvPacket(msg) => {
+            let module = route(ctx, &msg.packet.destination_port)?;
+            let module_output = module.on_recv_packet(ctx, &msg.packet);
+
+            let output = HandlerOutput::builder()
+                .with_log(module_output.log)
+                .with_events(module_output.events);
+
+            // Without an acknowledgement, the module writes it later on, by itself.
+            let ack = match module_output.result {
+                Some(ack) => ack,
+                None => return Ok(output.with_result(())),
+            };
+
+            // The acknowledgement is written on behalf of the module which owns the channel.
+            let channel_cap = module_capability(
+                ctx,
+                &msg.packet.destination_port,
+                Some(&msg.packet.destination_channel),
+            )?;
+
+            let ack_output =
+                write_acknowledgement::process(ctx, &channel_cap, msg.packet.clone(), ack)?;
+            ctx.store_packet_result(ack_output.result)?;
+
+            Ok(output
+                .with_log(ack_output.log)
+                .with_events(ack_output.events)
+                .with_result(()))
+        }
+        PacketMsg::AckPacket(msg) => {
+            let module = route(ctx, &msg.packet.source_port)?;
+            module.on_acknowledgement_packet(ctx, &msg.packet, msg.acknowledgement())
+        }
+        PacketMsg::ToPacket(msg) => {
+            let module = route(ctx, &msg.packet.source_port)?;
+            module.on_timeout_packet(ctx, &msg.packet)
+        }
+        PacketMsg::ToClosePacket(msg) => {
+            let module = route(ctx, &msg.packet.source_port)?;
+            module.on_timeout_packet(ctx, &msg.packet)
+        }
     }
 }
@@ -1,15 +1,19 @@
 use crate::events::IbcEvent;
 use crate::handler::{HandlerOutput, HandlerResult};
-use crate::ics02_client::height::Height;
+use crate::ics02_client::client_state::AnyClientState;
 use crate::ics03_connection::connection::State as ConnectionState;
 use crate::ics04_channel::channel::State;
 use crate::ics04_channel::channel::{Counterparty, Order};
+use crate::ics04_channel::commitment::PacketCommitment;
 use crate::ics04_channel::events::AcknowledgePacket;
-use crate::ics04_channel::handler::verify::verify_packet_acknowledgement_proofs;
+use crate::ics04_channel::handler::verify::{
+    active_client_state, verify_packet_acknowledgement_proofs,
+};
 use crate::ics04_channel::msgs::acknowledgement::MsgAcknowledgement;
 use crate::ics04_channel::packet::{PacketResult, Sequence};
 use crate::ics04_channel::{context::ChannelReader, error::Error, error::Kind};
-use crate::ics24_host::identifier::{ChannelId, PortId};
+use crate::ics05_port::capabilities::Capability;
+use crate::ics24_host::identifier::{ChannelId, ClientId, PortId};
 
 #[derive(Clone, Debug)]
 pub struct AckPacketResult {
@@ -17,10 +21,14 @@ pub struct AckPacketResult {
     pub channel_id: ChannelId,
     pub seq: Sequence,
     pub seq_number: Option<Sequence>,
+    /// The new state of the client which verified the proofs of the message, if verifying them
+    /// changed it.
+    pub client_state: Option<(ClientId, AnyClientState)>,
 }
 
 pub fn process(
     ctx: &dyn ChannelReader,
+    channel_cap: &Capability,
     msg: MsgAcknowledgement,
 ) -> HandlerResult<PacketResult, Error> {
     let mut output = HandlerOutput::builder();
@@ -38,7 +46,11 @@ pub fn process(
         return Err(Kind::ChannelClosed(packet.source_channel.clone()).into());
     }
 
-    let _channel_cap = ctx.authenticated_capability(&packet.source_port)?;
+    // The caller must be one of the modules which own the channel.
+    let port_channel_id = (packet.source_port.clone(), packet.source_channel.clone());
+    if !ctx.authenticate_channel_capability(&port_channel_id, channel_cap) {
+        return Err(Kind::InvalidChannelCapability(port_channel_id.0, port_channel_id.1).into());
+    }
 
     let counterparty = Counterparty::new(
         packet.destination_port.clone(),
@@ -63,8 +75,6 @@ pub fn process(
         );
     }
 
-    let client_id = connection_end.client_id().clone();
-
     // Verify packet commitment
     let packet_commitment = ctx
         .get_packet_commitment(&(
@@ -74,23 +84,26 @@ pub fn process(
         ))
         .ok_or(Kind::PacketCommitmentNotFound(packet.sequence))?;
 
-    let input = format!(
-        "{:?},{:?},{:?}",
-        packet.timeout_timestamp, packet.timeout_height, packet.data,
+    let expected_commitment = PacketCommitment::compute(
+        &packet.data,
+        packet.timeout_height,
+        packet.timeout_timestamp,
     );
-
-    if packet_commitment != ctx.hash(input) {
+    if packet_commitment != expected_commitment {
         return Err(Kind::IncorrectPacketCommitment(packet.sequence).into());
     }
 
     // Verify the acknowledgement proof
-    verify_packet_acknowledgement_proofs(
+    let client_state = active_client_state(ctx, connection_end.client_id())?;
+    let new_client_state = verify_packet_acknowledgement_proofs(
         ctx,
+        &client_state,
         &packet,
         msg.acknowledgement().clone(),
-        client_id,
+        &connection_end,
         msg.proofs(),
-    )?;
+    )?
+    .map(|cs| (connection_end.client_id().clone(), cs));
 
     let result = if source_channel_end.order_matches(&Order::Ordered) {
         let next_seq_ack = ctx
@@ -106,6 +119,7 @@ pub fn process(
             channel_id: packet.source_channel.clone(),
             seq: packet.sequence,
             seq_number: Some(next_seq_ack.increment()),
+            client_state: new_client_state,
         })
     } else {
         PacketResult::Ack(AckPacketResult {
@@ -113,14 +127,17 @@ pub fn process(
             channel_id: packet.source_channel.clone(),
             seq: packet.sequence,
             seq_number: None,
+            client_state: new_client_state,
         })
     };
 
     output.log("success: packet ack");
 
     output.emit(IbcEvent::AcknowledgePacket(AcknowledgePacket {
-        height: Height::zero(),
+        height: ctx.host_height(),
         packet: packet.clone(),
+        channel_ordering: Some(*source_channel_end.ordering()),
+        connection_id: Some(source_channel_end.connection_hops()[0].clone()),
     }));
 
     Ok(output.with_result(result))
@@ -136,10 +153,11 @@ mod tests {
     use crate::ics03_connection::connection::State as ConnectionState;
     use crate::ics03_connection::version::get_compatible_versions;
     use crate::ics04_channel::channel::{ChannelEnd, Counterparty, Order, State};
-    use crate::ics04_channel::context::ChannelReader;
+    use crate::ics04_channel::commitment::PacketCommitment;
     use crate::ics04_channel::handler::acknowledgement::process;
     use crate::ics04_channel::msgs::acknowledgement::test_util::get_dummy_raw_msg_acknowledgement;
     use crate::ics04_channel::msgs::acknowledgement::MsgAcknowledgement;
+    use crate::ics05_port::capabilities::test_util::get_dummy_capability;
     use crate::ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId};
     use crate::mock::context::MockContext;
     use crate::timestamp::ZERO_DURATION;
@@ -166,13 +184,11 @@ mod tests {
         .unwrap();
         let packet = msg.packet.clone();
 
-        let input = format!(
-            "{:?},{:?},{:?}",
+        let data = PacketCommitment::compute(
+            &packet.data,
+            packet.timeout_height,
             packet.timeout_timestamp,
-            packet.timeout_height.clone(),
-            packet.data.clone()
         );
-        let data = ChannelReader::hash(&context, input);
 
         let source_channel_end = ChannelEnd::new(
             State::Open,
@@ -205,8 +221,7 @@ mod tests {
                 want_pass: false,
             },
             Test {
-                name: "Processing fails because the port does not have a capability associated"
-                    .to_string(),
+                name: "Processing fails because no module owns the channel".to_string(),
                 ctx: context.clone().with_channel(
                     PortId::default(),
                     ChannelId::default(),
@@ -245,7 +260,14 @@ mod tests {
         .collect();
 
         for test in tests {
-            let res = process(&test.ctx, test.msg.clone());
+            let channel_cap = test
+                .ctx
+                .channel_capability(
+                    &test.msg.packet.source_port,
+                    &test.msg.packet.source_channel,
+                )
+                .unwrap_or_else(get_dummy_capability);
+            let res = process(&test.ctx, &channel_cap, test.msg.clone());
             // Additionally check the events and the output objects in the result.
             match res {
                 Ok(proto_output) => {
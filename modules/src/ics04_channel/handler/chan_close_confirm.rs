@@ -5,13 +5,14 @@ use crate::ics03_connection::connection::State as ConnectionState;
 use crate::ics04_channel::channel::{ChannelEnd, Counterparty, State};
 use crate::ics04_channel::context::ChannelReader;
 use crate::ics04_channel::error::{Error, Kind};
-use crate::ics04_channel::events::Attributes;
-use crate::ics04_channel::handler::verify::verify_channel_proofs;
+use crate::ics04_channel::handler::verify::{active_client_state, verify_channel_proofs};
 use crate::ics04_channel::handler::{ChannelIdState, ChannelResult};
 use crate::ics04_channel::msgs::chan_close_confirm::MsgChannelCloseConfirm;
+use crate::ics05_port::capabilities::Capability;
 
 pub(crate) fn process(
     ctx: &dyn ChannelReader,
+    channel_cap: &Capability,
     msg: MsgChannelCloseConfirm,
 ) -> HandlerResult<ChannelResult, Error> {
     let mut output = HandlerOutput::builder();
@@ -26,8 +27,11 @@ pub(crate) fn process(
         return Err(Kind::ChannelClosed(msg.channel_id().clone()).into());
     }
 
-    // Channel capabilities
-    let channel_cap = ctx.authenticated_capability(&msg.port_id().clone())?;
+    // The caller must be one of the modules which own the channel.
+    let port_channel_id = (msg.port_id().clone(), msg.channel_id().clone());
+    if !ctx.authenticate_channel_capability(&port_channel_id, channel_cap) {
+        return Err(Kind::InvalidChannelCapability(port_channel_id.0, port_channel_id.1).into());
+    }
 
     // An OPEN IBC connection running on the local (host) chain should exist.
     if channel_end.connection_hops().len() != 1 {
@@ -63,8 +67,11 @@ pub(crate) fn process(
         channel_end.version(),
     );
 
-    verify_channel_proofs(
+    // The client underlying the connection, which must be active, verifies the proofs.
+    let client_state = active_client_state(ctx, conn.client_id())?;
+    let new_client_state = verify_channel_proofs(
         ctx,
+        &client_state,
         &channel_end,
         &conn,
         &expected_channel_end,
@@ -81,14 +88,12 @@ pub(crate) fn process(
         port_id: msg.port_id().clone(),
         channel_id: msg.channel_id().clone(),
         channel_id_state: ChannelIdState::Reused,
-        channel_cap,
+        channel_cap: channel_cap.clone(),
         channel_end,
+        client_state: new_client_state.map(|cs| (conn.client_id().clone(), cs)),
     };
 
-    let event_attributes = Attributes {
-        channel_id: Some(msg.channel_id().clone()),
-        ..Default::default()
-    };
+    let event_attributes = result.event_attributes(ctx.host_height());
     output.emit(IbcEvent::CloseConfirmChannel(event_attributes.into()));
 
     Ok(output.with_result(result))
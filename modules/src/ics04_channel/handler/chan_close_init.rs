@@ -5,12 +5,13 @@ use crate::ics03_connection::connection::State as ConnectionState;
 use crate::ics04_channel::channel::State;
 use crate::ics04_channel::context::ChannelReader;
 use crate::ics04_channel::error::{Error, Kind};
-use crate::ics04_channel::events::Attributes;
 use crate::ics04_channel::handler::{ChannelIdState, ChannelResult};
 use crate::ics04_channel::msgs::chan_close_init::MsgChannelCloseInit;
+use crate::ics05_port::capabilities::Capability;
 
 pub(crate) fn process(
     ctx: &dyn ChannelReader,
+    channel_cap: &Capability,
     msg: MsgChannelCloseInit,
 ) -> HandlerResult<ChannelResult, Error> {
     let mut output = HandlerOutput::builder();
@@ -28,8 +29,12 @@ pub(crate) fn process(
         )));
     }
 
-    // Channel capabilities
-    let channel_cap = ctx.authenticated_capability(&msg.port_id().clone())?;
+    // The caller must be one of the modules which own the channel.
+    let port_channel_id = (msg.port_id().clone(), msg.channel_id().clone());
+    if !ctx.authenticate_channel_capability(&port_channel_id, channel_cap) {
+        return Err(Kind::InvalidChannelCapability(port_channel_id.0, port_channel_id.1).into());
+    }
+
     // An OPEN IBC connection running on the local (host) chain should exist.
 
     if channel_end.connection_hops().len() != 1 {
@@ -55,14 +60,12 @@ pub(crate) fn process(
         port_id: msg.port_id().clone(),
         channel_id: msg.channel_id().clone(),
         channel_id_state: ChannelIdState::Reused,
-        channel_cap,
+        channel_cap: channel_cap.clone(),
         channel_end,
+        client_state: None,
     };
 
-    let event_attributes = Attributes {
-        channel_id: Some(msg.channel_id().clone()),
-        ..Default::default()
-    };
+    let event_attributes = result.event_attributes(ctx.host_height());
     output.emit(IbcEvent::CloseInitChannel(event_attributes.into()));
 
     Ok(output.with_result(result))
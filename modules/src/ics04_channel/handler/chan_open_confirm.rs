@@ -5,13 +5,14 @@ use crate::ics03_connection::connection::State as ConnectionState;
 use crate::ics04_channel::channel::{ChannelEnd, Counterparty, State};
 use crate::ics04_channel::context::ChannelReader;
 use crate::ics04_channel::error::{Error, Kind};
-use crate::ics04_channel::events::Attributes;
-use crate::ics04_channel::handler::verify::verify_channel_proofs;
+use crate::ics04_channel::handler::verify::{active_client_state, verify_channel_proofs};
 use crate::ics04_channel::handler::{ChannelIdState, ChannelResult};
 use crate::ics04_channel::msgs::chan_open_confirm::MsgChannelOpenConfirm;
+use crate::ics05_port::capabilities::Capability;
 
 pub(crate) fn process(
     ctx: &dyn ChannelReader,
+    channel_cap: &Capability,
     msg: MsgChannelOpenConfirm,
 ) -> HandlerResult<ChannelResult, Error> {
     let mut output = HandlerOutput::builder();
@@ -26,8 +27,11 @@ pub(crate) fn process(
         return Err(Kind::InvalidChannelState(msg.channel_id().clone(), channel_end.state).into());
     }
 
-    // Channel capabilities
-    let channel_cap = ctx.authenticated_capability(&msg.port_id().clone())?;
+    // The caller must be one of the modules which own the channel.
+    let port_channel_id = (msg.port_id().clone(), msg.channel_id().clone());
+    if !ctx.authenticate_channel_capability(&port_channel_id, channel_cap) {
+        return Err(Kind::InvalidChannelCapability(port_channel_id.0, port_channel_id.1).into());
+    }
 
     // An OPEN IBC connection running on the local (host) chain should exist.
     if channel_end.connection_hops().len() != 1 {
@@ -65,8 +69,10 @@ pub(crate) fn process(
         channel_end.version(),
     );
     //2. Verify proofs
-    verify_channel_proofs(
+    let client_state = active_client_state(ctx, conn.client_id())?;
+    let new_client_state = verify_channel_proofs(
         ctx,
+        &client_state,
         &channel_end,
         &conn,
         &expected_channel_end,
@@ -83,14 +89,12 @@ pub(crate) fn process(
         port_id: msg.port_id().clone(),
         channel_id: msg.channel_id().clone(),
         channel_id_state: ChannelIdState::Reused,
-        channel_cap,
+        channel_cap: channel_cap.clone(),
         channel_end,
+        client_state: new_client_state.map(|cs| (conn.client_id().clone(), cs)),
     };
 
-    let event_attributes = Attributes {
-        channel_id: Some(msg.channel_id().clone()),
-        ..Default::default()
-    };
+    let event_attributes = result.event_attributes(ctx.host_height());
     output.emit(IbcEvent::OpenConfirmChannel(event_attributes.into()));
 
     Ok(output.with_result(result))
@@ -110,7 +114,7 @@ mod tests {
     use crate::ics03_connection::msgs::test_util::get_dummy_raw_counterparty;
     use crate::ics03_connection::version::get_compatible_versions;
     use crate::ics04_channel::channel::{ChannelEnd, Counterparty, Order, State};
-    use crate::ics04_channel::handler::{channel_dispatch, ChannelResult};
+    use crate::ics04_channel::handler::{channel_capability, channel_dispatch, ChannelResult};
     use crate::ics04_channel::msgs::chan_open_confirm::test_util::get_dummy_raw_msg_chan_open_confirm;
     use crate::ics04_channel::msgs::chan_open_confirm::MsgChannelOpenConfirm;
     use crate::ics04_channel::msgs::ChannelMsg;
@@ -176,7 +180,8 @@ mod tests {
         .collect();
 
         for test in tests {
-            let res = channel_dispatch(&test.ctx, test.msg.clone());
+            let res = channel_capability(&test.ctx, &test.msg)
+                .and_then(|cap| channel_dispatch(&test.ctx, &cap, test.msg.clone()));
             // Additionally check the events and the output objects in the result.
             match res {
                 Ok(proto_output) => {
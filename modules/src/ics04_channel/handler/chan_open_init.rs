@@ -5,19 +5,22 @@ use crate::handler::{HandlerOutput, HandlerResult};
 use crate::ics04_channel::channel::{ChannelEnd, State};
 use crate::ics04_channel::context::ChannelReader;
 use crate::ics04_channel::error::{Error, Kind};
-use crate::ics04_channel::events::Attributes;
 use crate::ics04_channel::handler::{ChannelIdState, ChannelResult};
 use crate::ics04_channel::msgs::chan_open_init::MsgChannelOpenInit;
+use crate::ics05_port::capabilities::Capability;
 use crate::ics24_host::identifier::ChannelId;
 
 pub(crate) fn process(
     ctx: &dyn ChannelReader,
+    port_cap: &Capability,
     msg: MsgChannelOpenInit,
 ) -> HandlerResult<ChannelResult, Error> {
     let mut output = HandlerOutput::builder();
 
-    // Channel capabilities
-    let channel_cap = ctx.authenticated_capability(&msg.port_id().clone())?;
+    // The caller must be the module which bound the port.
+    if !ctx.authenticate_port_capability(msg.port_id(), port_cap) {
+        return Err(Kind::InvalidPortCapability.into());
+    }
 
     if msg.channel().connection_hops().len() != 1 {
         return Err(
@@ -31,15 +34,13 @@ pub(crate) fn process(
     let conn = connection_end
         .ok_or_else(|| Kind::MissingConnection(msg.channel().connection_hops()[0].clone()))?;
 
-    let get_versions = conn.versions();
-    let version = match get_versions.as_slice() {
-        [version] => version,
-        _ => return Err(Kind::InvalidVersionLengthConnection.into()),
-    };
-
-    let channel_feature = msg.channel().ordering().to_string();
-    if !version.is_supported_feature(channel_feature) {
-        return Err(Kind::ChannelFeatureNotSuportedByConnection.into());
+    // The ordering of the channel must be one of the features negotiated on the connection.
+    let features = conn
+        .negotiated_features()
+        .ok_or(Kind::InvalidVersionLengthConnection)?;
+    let ordering = *msg.channel().ordering();
+    if !features.iter().any(|feature| feature == ordering.as_str()) {
+        return Err(Kind::ChannelFeatureNotSuportedByConnection(ordering).into());
     }
 
     // TODO: Check that `version` is non empty but not necessary coherent
@@ -68,16 +69,14 @@ pub(crate) fn process(
 
     let result = ChannelResult {
         port_id: msg.port_id().clone(),
-        channel_id: chan_id.clone(),
+        channel_id: chan_id,
         channel_end: new_channel_end,
         channel_id_state: ChannelIdState::Generated,
-        channel_cap,
+        channel_cap: port_cap.clone(),
+        client_state: None,
     };
 
-    let event_attributes = Attributes {
-        channel_id: Some(chan_id),
-        ..Default::default()
-    };
+    let event_attributes = result.event_attributes(ctx.host_height());
     output.emit(IbcEvent::OpenInitChannel(event_attributes.into()));
 
     Ok(output.with_result(result))
@@ -93,9 +92,9 @@ mod tests {
     use crate::ics03_connection::connection::State as ConnectionState;
     use crate::ics03_connection::msgs::conn_open_init::test_util::get_dummy_raw_msg_conn_open_init;
     use crate::ics03_connection::msgs::conn_open_init::MsgConnectionOpenInit;
-    use crate::ics03_connection::version::get_compatible_versions;
+    use crate::ics03_connection::version::{get_compatible_versions, Version};
     use crate::ics04_channel::channel::State;
-    use crate::ics04_channel::handler::{channel_dispatch, ChannelResult};
+    use crate::ics04_channel::handler::{channel_capability, channel_dispatch, ChannelResult};
     use crate::ics04_channel::msgs::chan_open_init::test_util::get_dummy_raw_msg_chan_open_init;
     use crate::ics04_channel::msgs::chan_open_init::MsgChannelOpenInit;
     use crate::ics04_channel::msgs::ChannelMsg;
@@ -127,6 +126,18 @@ mod tests {
             msg_conn_init.delay_period,
         );
 
+        // The channel in the message is unordered, which this connection does not support.
+        let ordered_conn_end = ConnectionEnd::new(
+            ConnectionState::Init,
+            msg_conn_init.client_id().clone(),
+            msg_conn_init.counterparty().clone(),
+            vec![Version::new(
+                "1".to_string(),
+                vec!["ORDER_ORDERED".to_string()],
+            )],
+            msg_conn_init.delay_period,
+        );
+
         let cid = ConnectionId::default();
 
         let tests: Vec<Test> = vec![
@@ -145,6 +156,16 @@ mod tests {
                 msg: ChannelMsg::ChannelOpenInit(msg_chan_init.clone()),
                 want_pass: false,
             },
+            Test {
+                name: "Processing fails because the connection does not support the ordering"
+                    .to_string(),
+                ctx: context
+                    .clone()
+                    .with_connection(cid.clone(), ordered_conn_end)
+                    .with_port_capability(msg_chan_init.port_id().clone()),
+                msg: ChannelMsg::ChannelOpenInit(msg_chan_init.clone()),
+                want_pass: false,
+            },
             Test {
                 name: "Good parameters".to_string(),
                 ctx: context
@@ -163,7 +184,8 @@ mod tests {
         .collect();
 
         for test in tests {
-            let res = channel_dispatch(&test.ctx, test.msg.clone());
+            let res = channel_capability(&test.ctx, &test.msg)
+                .and_then(|cap| channel_dispatch(&test.ctx, &cap, test.msg.clone()));
             // Additionally check the events and the output objects in the result.
             match res {
                 Ok(proto_output) => {
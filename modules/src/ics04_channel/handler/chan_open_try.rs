@@ -6,14 +6,15 @@ use crate::ics03_connection::connection::State as ConnectionState;
 use crate::ics04_channel::channel::{ChannelEnd, Counterparty, State};
 use crate::ics04_channel::context::ChannelReader;
 use crate::ics04_channel::error::{Error, Kind};
-use crate::ics04_channel::events::Attributes;
-use crate::ics04_channel::handler::verify::verify_channel_proofs;
+use crate::ics04_channel::handler::verify::{active_client_state, verify_channel_proofs};
 use crate::ics04_channel::handler::{ChannelIdState, ChannelResult};
 use crate::ics04_channel::msgs::chan_open_try::MsgChannelOpenTry;
+use crate::ics05_port::capabilities::Capability;
 use crate::ics24_host::identifier::ChannelId;
 
 pub(crate) fn process(
     ctx: &dyn ChannelReader,
+    port_cap: &Capability,
     msg: MsgChannelOpenTry,
 ) -> HandlerResult<ChannelResult, Error> {
     let mut output = HandlerOutput::builder();
@@ -78,19 +79,19 @@ pub(crate) fn process(
         return Err(Kind::ConnectionNotOpen(msg.channel.connection_hops()[0].clone()).into());
     }
 
-    let get_versions = conn.versions();
-    let version = match get_versions.as_slice() {
-        [version] => version,
-        _ => return Err(Kind::InvalidVersionLengthConnection.into()),
-    };
-
-    let channel_feature = msg.channel().ordering().to_string();
-    if !version.is_supported_feature(channel_feature) {
-        return Err(Kind::ChannelFeatureNotSuportedByConnection.into());
+    // The ordering of the channel must be one of the features negotiated on the connection.
+    let features = conn
+        .negotiated_features()
+        .ok_or(Kind::InvalidVersionLengthConnection)?;
+    let ordering = *msg.channel().ordering();
+    if !features.iter().any(|feature| feature == ordering.as_str()) {
+        return Err(Kind::ChannelFeatureNotSuportedByConnection(ordering).into());
     }
 
-    // Channel capabilities
-    let channel_cap = ctx.authenticated_capability(&msg.port_id().clone())?;
+    // The caller must be the module which bound the port.
+    if !ctx.authenticate_port_capability(msg.port_id(), port_cap) {
+        return Err(Kind::InvalidPortCapability.into());
+    }
 
     if msg.channel().version().is_empty() {
         return Err(Kind::InvalidVersion.into());
@@ -117,8 +118,10 @@ pub(crate) fn process(
     );
 
     // 2. Actual proofs are verified now.
-    verify_channel_proofs(
+    let client_state = active_client_state(ctx, conn.client_id())?;
+    let new_client_state = verify_channel_proofs(
         ctx,
+        &client_state,
         &new_channel_end,
         &conn,
         &expected_channel_end,
@@ -133,20 +136,18 @@ pub(crate) fn process(
 
     let result = ChannelResult {
         port_id: msg.port_id().clone(),
-        channel_cap,
+        channel_cap: port_cap.clone(),
         channel_id_state: if matches!(msg.previous_channel_id, None) {
             ChannelIdState::Generated
         } else {
             ChannelIdState::Reused
         },
-        channel_id: channel_id.clone(),
+        channel_id,
         channel_end: new_channel_end,
+        client_state: new_client_state.map(|cs| (conn.client_id().clone(), cs)),
     };
 
-    let event_attributes = Attributes {
-        channel_id: Some(channel_id),
-        ..Default::default()
-    };
+    let event_attributes = result.event_attributes(ctx.host_height());
     output.emit(IbcEvent::OpenTryChannel(event_attributes.into()));
 
     Ok(output.with_result(result))
@@ -166,7 +167,7 @@ mod tests {
     use crate::ics03_connection::version::get_compatible_versions;
     use crate::ics04_channel::channel::{ChannelEnd, State};
     use crate::ics04_channel::error::Kind;
-    use crate::ics04_channel::handler::{channel_dispatch, ChannelResult};
+    use crate::ics04_channel::handler::{channel_capability, channel_dispatch, ChannelResult};
     use crate::ics04_channel::msgs::chan_open_try::test_util::get_dummy_raw_msg_chan_open_try;
     use crate::ics04_channel::msgs::chan_open_try::MsgChannelOpenTry;
     use crate::ics04_channel::msgs::ChannelMsg;
@@ -349,7 +350,8 @@ mod tests {
         .collect();
 
         for test in tests {
-            let res = channel_dispatch(&test.ctx, test.msg.clone());
+            let res = channel_capability(&test.ctx, &test.msg)
+                .and_then(|cap| channel_dispatch(&test.ctx, &cap, test.msg.clone()));
             // Additionally check the events and the output objects in the result.
             match res {
                 Ok(handler_output) => {
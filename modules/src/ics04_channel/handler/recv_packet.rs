@@ -1,15 +1,16 @@
 use crate::events::IbcEvent;
 use crate::handler::{HandlerOutput, HandlerResult};
-use crate::ics02_client::height::Height;
+use crate::ics02_client::client_state::AnyClientState;
 use crate::ics03_connection::connection::State as ConnectionState;
 use crate::ics04_channel::channel::{Counterparty, Order, State};
 use crate::ics04_channel::context::ChannelReader;
 use crate::ics04_channel::error::{Error, Kind};
 use crate::ics04_channel::events::ReceivePacket;
-use crate::ics04_channel::handler::verify::verify_packet_recv_proofs;
+use crate::ics04_channel::handler::verify::{active_client_state, verify_packet_recv_proofs};
 use crate::ics04_channel::msgs::recv_packet::MsgRecvPacket;
 use crate::ics04_channel::packet::{PacketResult, Receipt, Sequence};
-use crate::ics24_host::identifier::{ChannelId, PortId};
+use crate::ics05_port::capabilities::Capability;
+use crate::ics24_host::identifier::{ChannelId, ClientId, PortId};
 use crate::timestamp::Expiry;
 
 #[derive(Clone, Debug)]
@@ -19,9 +20,16 @@ pub struct RecvPacketResult {
     pub seq: Sequence,
     pub seq_number: Sequence,
     pub receipt: Option<Receipt>,
+    /// The new state of the client which verified the proofs of the message, if verifying them
+    /// changed it.
+    pub client_state: Option<(ClientId, AnyClientState)>,
 }
 
-pub fn process(ctx: &dyn ChannelReader, msg: MsgRecvPacket) -> HandlerResult<PacketResult, Error> {
+pub fn process(
+    ctx: &dyn ChannelReader,
+    channel_cap: &Capability,
+    msg: MsgRecvPacket,
+) -> HandlerResult<PacketResult, Error> {
     let mut output = HandlerOutput::builder();
 
     let packet = &msg.packet;
@@ -46,7 +54,14 @@ pub fn process(ctx: &dyn ChannelReader, msg: MsgRecvPacket) -> HandlerResult<Pac
         .into());
     }
 
-    let _channel_cap = ctx.authenticated_capability(&packet.destination_port)?;
+    // The caller must be one of the modules which own the channel.
+    let port_channel_id = (
+        packet.destination_port.clone(),
+        packet.destination_channel.clone(),
+    );
+    if !ctx.authenticate_channel_capability(&port_channel_id, channel_cap) {
+        return Err(Kind::InvalidChannelCapability(port_channel_id.0, port_channel_id.1).into());
+    }
 
     let counterparty = Counterparty::new(
         packet.source_port.clone(),
@@ -69,8 +84,6 @@ pub fn process(ctx: &dyn ChannelReader, msg: MsgRecvPacket) -> HandlerResult<Pac
         return Err(Kind::ConnectionNotOpen(dest_channel_end.connection_hops()[0].clone()).into());
     }
 
-    let client_id = connection_end.client_id().clone();
-
     // Check if packet height is newer than the height of the local host chain
     let latest_height = ctx.host_height();
     if (!packet.timeout_height.is_zero()) && (packet.timeout_height <= latest_height) {
@@ -83,7 +96,10 @@ pub fn process(ctx: &dyn ChannelReader, msg: MsgRecvPacket) -> HandlerResult<Pac
         return Err(Kind::LowPacketTimestamp.into());
     }
 
-    verify_packet_recv_proofs(ctx, &packet, client_id, &msg.proofs)?;
+    let client_state = active_client_state(ctx, connection_end.client_id())?;
+    let new_client_state =
+        verify_packet_recv_proofs(ctx, &client_state, &packet, &connection_end, &msg.proofs)?
+            .map(|cs| (connection_end.client_id().clone(), cs));
 
     let result = if dest_channel_end.order_matches(&Order::Ordered) {
         let next_seq_recv = ctx
@@ -100,6 +116,7 @@ pub fn process(ctx: &dyn ChannelReader, msg: MsgRecvPacket) -> HandlerResult<Pac
             seq: packet.sequence,
             seq_number: next_seq_recv.increment(),
             receipt: None,
+            client_state: new_client_state,
         })
     } else {
         let packet_rec = ctx.get_packet_receipt(&(
@@ -118,6 +135,7 @@ pub fn process(ctx: &dyn ChannelReader, msg: MsgRecvPacket) -> HandlerResult<Pac
                     seq: packet.sequence,
                     seq_number: 1.into(),
                     receipt: Some(Receipt::Ok),
+                    client_state: new_client_state,
                 })
             }
         }
@@ -126,8 +144,10 @@ pub fn process(ctx: &dyn ChannelReader, msg: MsgRecvPacket) -> HandlerResult<Pac
     output.log("success: packet receive");
 
     output.emit(IbcEvent::ReceivePacket(ReceivePacket {
-        height: Height::zero(),
+        height: ctx.host_height(),
         packet: msg.packet,
+        channel_ordering: Some(*dest_channel_end.ordering()),
+        connection_id: Some(dest_channel_end.connection_hops()[0].clone()),
     }));
 
     Ok(output.with_result(result))
@@ -136,6 +156,7 @@ pub fn process(ctx: &dyn ChannelReader, msg: MsgRecvPacket) -> HandlerResult<Pac
 #[cfg(test)]
 mod tests {
     use std::convert::TryFrom;
+    use std::time::Duration;
     use test_env_log::test;
 
     use crate::ics03_connection::connection::ConnectionEnd;
@@ -143,9 +164,11 @@ mod tests {
     use crate::ics03_connection::connection::State as ConnectionState;
     use crate::ics03_connection::version::get_compatible_versions;
     use crate::ics04_channel::channel::{ChannelEnd, Counterparty, Order, State};
+    use crate::ics04_channel::error::Kind;
     use crate::ics04_channel::handler::recv_packet::process;
     use crate::ics04_channel::msgs::recv_packet::test_util::get_dummy_raw_msg_recv_packet;
     use crate::ics04_channel::msgs::recv_packet::MsgRecvPacket;
+    use crate::ics05_port::capabilities::test_util::get_dummy_capability;
     use crate::ics18_relayer::context::Ics18Context;
     use crate::ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId};
     use crate::mock::context::MockContext;
@@ -220,8 +243,7 @@ mod tests {
                 want_pass: false,
             },
             Test {
-                name: "Processing fails because the port does not have a capability associated"
-                    .to_string(),
+                name: "Processing fails because no module owns the channel".to_string(),
                 ctx: context.clone().with_channel(
                     PortId::default(),
                     ChannelId::default(),
@@ -276,7 +298,14 @@ mod tests {
         .collect();
 
         for test in tests {
-            let res = process(&test.ctx, test.msg.clone());
+            let channel_cap = test
+                .ctx
+                .channel_capability(
+                    &test.msg.packet.destination_port,
+                    &test.msg.packet.destination_channel,
+                )
+                .unwrap_or_else(get_dummy_capability);
+            let res = process(&test.ctx, &channel_cap, test.msg.clone());
             // Additionally check the events and the output objects in the result.
             match res {
                 Ok(proto_output) => {
@@ -307,4 +336,94 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn recv_packet_waits_for_connection_delay() {
+        let delay_period = Duration::from_secs(10);
+        let processed_time = Timestamp::from_nanoseconds(1_000_000_000).unwrap();
+
+        // The client is created at the host height 5, with the host timestamp `processed_time`.
+        let context = MockContext::default()
+            .with_max_expected_time_per_block(Duration::from_secs(30))
+            .with_timestamp(processed_time);
+        let host_height = context.query_latest_height();
+        let client_height = host_height.add(2);
+
+        let msg =
+            MsgRecvPacket::try_from(get_dummy_raw_msg_recv_packet(client_height.revision_height))
+                .unwrap();
+        let packet = Packet {
+            timeout_timestamp: Timestamp::none(),
+            ..msg.packet.clone()
+        };
+        let msg = MsgRecvPacket::new(packet.clone(), msg.proofs, get_dummy_account_id());
+
+        let connection_end = ConnectionEnd::new(
+            ConnectionState::Open,
+            ClientId::default(),
+            ConnectionCounterparty::new(
+                ClientId::default(),
+                Some(ConnectionId::default()),
+                Default::default(),
+            ),
+            get_compatible_versions(),
+            delay_period,
+        );
+        let dest_channel_end = ChannelEnd::new(
+            State::Open,
+            Order::Unordered,
+            Counterparty::new(packet.source_port.clone(), Some(packet.source_channel)),
+            vec![ConnectionId::default()],
+            "ics20".to_string(),
+        );
+
+        let context = context
+            .with_client(&ClientId::default(), client_height)
+            .with_connection(ConnectionId::default(), connection_end)
+            .with_port_capability(packet.destination_port.clone())
+            .with_channel(
+                packet.destination_port.clone(),
+                packet.destination_channel.clone(),
+                dest_channel_end,
+            );
+        let channel_cap = context
+            .channel_capability(&packet.destination_port, &packet.destination_channel)
+            .unwrap();
+
+        let after = |secs| (processed_time + Duration::from_secs(secs)).unwrap();
+
+        // Not enough time has passed since the consensus state was stored.
+        let ctx = context
+            .clone()
+            .with_height(host_height.increment())
+            .with_timestamp(after(5));
+        let err = process(&ctx, &channel_cap, msg.clone()).unwrap_err();
+        assert!(matches!(err.kind(), Kind::DelayTimeNotPassed(_, _)));
+
+        // Without a timestamp, the local chain cannot tell whether enough time has passed.
+        let ctx = context
+            .clone()
+            .with_height(host_height.increment())
+            .with_timestamp(Timestamp::none());
+        let err = process(&ctx, &channel_cap, msg.clone()).unwrap_err();
+        assert!(matches!(err.kind(), Kind::MissingDelayTimestamp(_, _)));
+
+        // Enough time has passed, but the delay of one block (of at most 30 seconds) has not.
+        let ctx = context.clone().with_timestamp(after(10));
+        let err = process(&ctx, &channel_cap, msg.clone()).unwrap_err();
+        assert!(matches!(err.kind(), Kind::DelayHeightNotPassed(_, _)));
+
+        // Both delays have passed.
+        let ctx = context
+            .with_height(host_height.increment())
+            .with_timestamp(after(10));
+        assert!(process(&ctx, &channel_cap, msg.clone()).is_ok());
+
+        // Only the modules which own the channel can receive packets on it.
+        let err = process(&ctx, &get_dummy_capability(), msg).unwrap_err();
+        assert_eq!(
+            err.kind(),
+            &Kind::InvalidChannelCapability(packet.destination_port, packet.destination_channel)
+        );
+    }
 }
@@ -1,14 +1,15 @@
 use crate::events::IbcEvent;
 use crate::handler::{HandlerOutput, HandlerResult};
-use crate::ics02_client::client_state::ClientState;
 use crate::ics04_channel::channel::Counterparty;
 use crate::ics04_channel::channel::State;
+use crate::ics04_channel::commitment::PacketCommitment;
 use crate::ics04_channel::events::SendPacket;
+use crate::ics04_channel::handler::verify::{active_client_state, verification_consensus_state};
 use crate::ics04_channel::packet::{PacketResult, Sequence};
 use crate::ics04_channel::{context::ChannelReader, error::Error, error::Kind, packet::Packet};
+use crate::ics05_port::capabilities::Capability;
 use crate::ics24_host::identifier::{ChannelId, PortId};
-use crate::timestamp::{Expiry, Timestamp};
-use crate::Height;
+use crate::timestamp::Expiry;
 
 #[derive(Clone, Debug)]
 pub struct SendPacketResult {
@@ -16,12 +17,16 @@ pub struct SendPacketResult {
     pub channel_id: ChannelId,
     pub seq: Sequence,
     pub seq_number: Sequence,
-    pub timeout_height: Height,
-    pub timeout_timestamp: Timestamp,
-    pub data: Vec<u8>,
+    pub commitment: PacketCommitment,
 }
 
-pub fn send_packet(ctx: &dyn ChannelReader, packet: Packet) -> HandlerResult<PacketResult, Error> {
+/// Sends `packet` on behalf of the module presenting `channel_cap`, which must own the source
+/// channel of the packet.
+pub fn send_packet(
+    ctx: &dyn ChannelReader,
+    channel_cap: &Capability,
+    packet: Packet,
+) -> HandlerResult<PacketResult, Error> {
     let mut output = HandlerOutput::builder();
 
     let source_channel_end = ctx
@@ -35,7 +40,10 @@ pub fn send_packet(ctx: &dyn ChannelReader, packet: Packet) -> HandlerResult<Pac
         return Err(Kind::ChannelClosed(packet.source_channel).into());
     }
 
-    let _channel_cap = ctx.authenticated_capability(&packet.source_port)?;
+    let port_channel_id = (packet.source_port.clone(), packet.source_channel.clone());
+    if !ctx.authenticate_channel_capability(&port_channel_id, channel_cap) {
+        return Err(Kind::InvalidChannelCapability(port_channel_id.0, port_channel_id.1).into());
+    }
 
     let counterparty = Counterparty::new(
         packet.destination_port.clone(),
@@ -56,14 +64,8 @@ pub fn send_packet(ctx: &dyn ChannelReader, packet: Packet) -> HandlerResult<Pac
 
     let client_id = connection_end.client_id().clone();
 
-    let client_state = ctx
-        .client_state(&client_id)
-        .ok_or_else(|| Kind::MissingClientState(client_id.clone()))?;
-
     // prevent accidental sends with clients that cannot be updated
-    if client_state.is_frozen() {
-        return Err(Kind::FrozenClient(connection_end.client_id().clone()).into());
-    }
+    let client_state = active_client_state(ctx, &client_id)?;
 
     // check if packet height is newer than the height of the latest client state on the receiving chain
     let latest_height = client_state.latest_height();
@@ -74,9 +76,8 @@ pub fn send_packet(ctx: &dyn ChannelReader, packet: Packet) -> HandlerResult<Pac
     }
 
     //check if packet timestamp is newer than the timestamp of the latest consensus state of the receiving chain
-    let consensus_state = ctx
-        .client_consensus_state(&client_id, latest_height)
-        .ok_or_else(|| Kind::MissingClientConsensusState(client_id.clone(), latest_height))?;
+    let consensus_state =
+        verification_consensus_state(ctx, &client_state, &client_id, latest_height)?;
 
     let latest_timestamp = consensus_state.timestamp();
 
@@ -101,14 +102,18 @@ pub fn send_packet(ctx: &dyn ChannelReader, packet: Packet) -> HandlerResult<Pac
         channel_id: packet.source_channel.clone(),
         seq: packet.sequence,
         seq_number: next_seq_send.increment(),
-        data: packet.clone().data,
-        timeout_height: packet.timeout_height,
-        timeout_timestamp: packet.timeout_timestamp,
+        commitment: PacketCommitment::compute(
+            &packet.data,
+            packet.timeout_height,
+            packet.timeout_timestamp,
+        ),
     });
 
     output.emit(IbcEvent::SendPacket(SendPacket {
-        height: packet_height,
+        height: ctx.host_height(),
         packet,
+        channel_ordering: Some(*source_channel_end.ordering()),
+        connection_id: Some(source_channel_end.connection_hops()[0].clone()),
     }));
 
     Ok(output.with_result(result))
@@ -117,6 +122,7 @@ pub fn send_packet(ctx: &dyn ChannelReader, packet: Packet) -> HandlerResult<Pac
 #[cfg(test)]
 mod tests {
     use std::convert::TryInto;
+    use std::str::FromStr;
     use test_env_log::test;
 
     use crate::events::IbcEvent;
@@ -126,9 +132,13 @@ mod tests {
     use crate::ics03_connection::connection::State as ConnectionState;
     use crate::ics03_connection::version::get_compatible_versions;
     use crate::ics04_channel::channel::{ChannelEnd, Counterparty, Order, State};
+    use crate::ics04_channel::error::Kind;
     use crate::ics04_channel::handler::send_packet::send_packet;
     use crate::ics04_channel::packet::test_utils::get_dummy_raw_packet;
     use crate::ics04_channel::packet::Packet;
+    use crate::ics05_port::capabilities::test_util::get_dummy_capability;
+    use crate::ics05_port::capabilities::{CapabilityName, ModuleId};
+    use crate::ics05_port::context::CapabilityReader;
     use crate::ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId};
     use crate::mock::context::MockContext;
     use crate::timestamp::ZERO_DURATION;
@@ -220,7 +230,11 @@ mod tests {
         .collect();
 
         for test in tests {
-            let res = send_packet(&test.ctx, test.packet.clone());
+            let channel_cap = test
+                .ctx
+                .channel_capability(&test.packet.source_port, &test.packet.source_channel)
+                .unwrap_or_else(get_dummy_capability);
+            let res = send_packet(&test.ctx, &channel_cap, test.packet.clone());
             // Additionally check the events and the output objects in the result.
             match res {
                 Ok(proto_output) => {
@@ -253,4 +267,69 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn one_module_cannot_send_on_the_port_of_another() {
+        let other_port = PortId::from_str("other").unwrap();
+
+        let channel_end = ChannelEnd::new(
+            State::Open,
+            Order::default(),
+            Counterparty::new(PortId::default(), Some(ChannelId::default())),
+            vec![ConnectionId::default()],
+            "ics20".to_string(),
+        );
+
+        let connection_end = ConnectionEnd::new(
+            ConnectionState::Open,
+            ClientId::default(),
+            ConnectionCounterparty::new(
+                ClientId::default(),
+                Some(ConnectionId::default()),
+                Default::default(),
+            ),
+            get_compatible_versions(),
+            ZERO_DURATION,
+        );
+
+        // Each port is bound to a module named after it, which owns the channel on that port.
+        let ctx = MockContext::default()
+            .with_client(&ClientId::default(), Height::default())
+            .with_connection(ConnectionId::default(), connection_end)
+            .with_port_capability(PortId::default())
+            .with_port_capability(other_port.clone())
+            .with_channel(PortId::default(), ChannelId::default(), channel_end.clone())
+            .with_channel(other_port.clone(), ChannelId::default(), channel_end)
+            .with_send_sequence(PortId::default(), ChannelId::default(), 1.into());
+
+        let mut packet: Packet = get_dummy_raw_packet(1, 6).try_into().unwrap();
+        packet.sequence = 1.into();
+        packet.data = vec![0];
+        let channel_name = CapabilityName::channel(&packet.source_port, &packet.source_channel);
+
+        // The other module cannot obtain the capability of the channel, and its own capability
+        // does not authenticate it.
+        let other = ModuleId::new(other_port.as_str());
+        assert!(ctx.get_capability(&other, &channel_name).is_none());
+
+        let other_cap = ctx
+            .get_capability(
+                &other,
+                &CapabilityName::channel(&other_port, &ChannelId::default()),
+            )
+            .unwrap();
+        let res = send_packet(&ctx, &other_cap, packet.clone());
+        assert_eq!(
+            res.unwrap_err().kind(),
+            &Kind::InvalidChannelCapability(
+                packet.source_port.clone(),
+                packet.source_channel.clone()
+            )
+        );
+
+        // The module which owns the channel can send on it.
+        let owner = ModuleId::new(PortId::default().as_str());
+        let owner_cap = ctx.get_capability(&owner, &channel_name).unwrap();
+        assert!(send_packet(&ctx, &owner_cap, packet).is_ok());
+    }
 }
@@ -1,15 +1,19 @@
 use crate::events::IbcEvent;
 use crate::handler::{HandlerOutput, HandlerResult};
+use crate::ics02_client::client_state::AnyClientState;
 use crate::ics04_channel::channel::State;
 use crate::ics04_channel::channel::{ChannelEnd, Counterparty, Order};
+use crate::ics04_channel::commitment::PacketCommitment;
 use crate::ics04_channel::events::TimeoutPacket;
 use crate::ics04_channel::handler::verify::{
-    verify_next_sequence_recv, verify_packet_receipt_absence,
+    active_client_state, verification_consensus_state, verify_next_sequence_recv,
+    verify_packet_receipt_absence,
 };
 use crate::ics04_channel::msgs::timeout::MsgTimeout;
 use crate::ics04_channel::packet::{PacketResult, Sequence};
 use crate::ics04_channel::{context::ChannelReader, error::Error, error::Kind};
-use crate::ics24_host::identifier::{ChannelId, PortId};
+use crate::ics05_port::capabilities::Capability;
+use crate::ics24_host::identifier::{ChannelId, ClientId, PortId};
 use crate::timestamp::Expiry;
 
 #[derive(Clone, Debug)]
@@ -18,9 +22,16 @@ pub struct TimeoutPacketResult {
     pub channel_id: ChannelId,
     pub seq: Sequence,
     pub channel: Option<ChannelEnd>,
+    /// The new state of the client which verified the proofs of the message, if verifying them
+    /// changed it.
+    pub client_state: Option<(ClientId, AnyClientState)>,
 }
 
-pub fn process(ctx: &dyn ChannelReader, msg: MsgTimeout) -> HandlerResult<PacketResult, Error> {
+pub fn process(
+    ctx: &dyn ChannelReader,
+    channel_cap: &Capability,
+    msg: MsgTimeout,
+) -> HandlerResult<PacketResult, Error> {
     let mut output = HandlerOutput::builder();
 
     let packet = &msg.packet;
@@ -36,7 +47,11 @@ pub fn process(ctx: &dyn ChannelReader, msg: MsgTimeout) -> HandlerResult<Packet
         return Err(Kind::ChannelClosed(packet.source_channel.clone()).into());
     }
 
-    let _channel_cap = ctx.authenticated_capability(&packet.source_port)?;
+    // The caller must be one of the modules which own the channel.
+    let port_channel_id = (packet.source_port.clone(), packet.source_channel.clone());
+    if !ctx.authenticate_channel_capability(&port_channel_id, channel_cap) {
+        return Err(Kind::InvalidChannelCapability(port_channel_id.0, port_channel_id.1).into());
+    }
 
     let counterparty = Counterparty::new(
         packet.destination_port.clone(),
@@ -67,9 +82,10 @@ pub fn process(ctx: &dyn ChannelReader, msg: MsgTimeout) -> HandlerResult<Packet
         );
     }
 
-    let consensus_state = ctx
-        .client_consensus_state(&client_id, proof_height)
-        .ok_or_else(|| Kind::MissingClientConsensusState(client_id.clone(), proof_height))?;
+    let client_state = active_client_state(ctx, &client_id)?;
+
+    let consensus_state =
+        verification_consensus_state(ctx, &client_state, &client_id, proof_height)?;
 
     let proof_timestamp = consensus_state.timestamp();
 
@@ -89,24 +105,28 @@ pub fn process(ctx: &dyn ChannelReader, msg: MsgTimeout) -> HandlerResult<Packet
         ))
         .ok_or(Kind::PacketCommitmentNotFound(packet.sequence))?;
 
-    let input = format!(
-        "{:?},{:?},{:?}",
-        packet.timeout_timestamp, packet.timeout_height, packet.data,
+    let expected_commitment = PacketCommitment::compute(
+        &packet.data,
+        packet.timeout_height,
+        packet.timeout_timestamp,
     );
-
-    if packet_commitment != ChannelReader::hash(ctx, input) {
+    if packet_commitment != expected_commitment {
         return Err(Kind::IncorrectPacketCommitment(packet.sequence).into());
     }
 
+    // Read before the channel end is moved into the result.
+    let channel_ordering = *source_channel_end.ordering();
+
     let result = if source_channel_end.order_matches(&Order::Ordered) {
         if packet.sequence < msg.next_sequence_recv {
             return Err(
                 Kind::InvalidPacketSequence(packet.sequence, msg.next_sequence_recv).into(),
             );
         }
-        verify_next_sequence_recv(
+        let new_client_state = verify_next_sequence_recv(
             ctx,
-            client_id,
+            &client_state,
+            &connection_end,
             packet.clone(),
             msg.next_sequence_recv,
             &msg.proofs.clone(),
@@ -118,23 +138,32 @@ pub fn process(ctx: &dyn ChannelReader, msg: MsgTimeout) -> HandlerResult<Packet
             channel_id: packet.source_channel.clone(),
             seq: packet.sequence,
             channel: Some(source_channel_end),
+            client_state: new_client_state.map(|cs| (client_id, cs)),
         })
     } else {
-        verify_packet_receipt_absence(ctx, client_id, packet.clone(), &msg.proofs.clone())?;
+        let new_client_state = verify_packet_receipt_absence(
+            ctx,
+            &client_state,
+            &connection_end,
+            packet.clone(),
+            &msg.proofs.clone(),
+        )?;
 
         PacketResult::Timeout(TimeoutPacketResult {
             port_id: packet.source_port.clone(),
             channel_id: packet.source_channel.clone(),
             seq: packet.sequence,
             channel: None,
+            client_state: new_client_state.map(|cs| (client_id, cs)),
         })
     };
 
     output.log("success: packet timeout ");
 
     output.emit(IbcEvent::TimeoutPacket(TimeoutPacket {
-        height: Default::default(),
+        height: ctx.host_height(),
         packet: packet.clone(),
+        channel_ordering: Some(channel_ordering),
     }));
 
     Ok(output.with_result(result))
@@ -150,10 +179,11 @@ mod tests {
     use crate::ics03_connection::connection::State as ConnectionState;
     use crate::ics03_connection::version::get_compatible_versions;
     use crate::ics04_channel::channel::{ChannelEnd, Counterparty, Order, State};
-    use crate::ics04_channel::context::ChannelReader;
+    use crate::ics04_channel::commitment::PacketCommitment;
     use crate::ics04_channel::handler::timeout::process;
     use crate::ics04_channel::msgs::timeout::test_util::get_dummy_raw_msg_timeout;
     use crate::ics04_channel::msgs::timeout::MsgTimeout;
+    use crate::ics05_port::capabilities::test_util::get_dummy_capability;
     use crate::ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId};
     use crate::timestamp::ZERO_DURATION;
 
@@ -185,13 +215,11 @@ mod tests {
         let mut msg_ok = msg.clone();
         msg_ok.packet.timeout_timestamp = Default::default();
 
-        let input = format!(
-            "{:?},{:?},{:?}",
+        let data = PacketCommitment::compute(
+            &msg_ok.packet.data,
+            msg_ok.packet.timeout_height,
             msg_ok.packet.timeout_timestamp,
-            msg_ok.packet.timeout_height.clone(),
-            msg_ok.packet.data.clone()
         );
-        let data = ChannelReader::hash(&context, input);
 
         let source_channel_end = ChannelEnd::new(
             State::Open,
@@ -229,12 +257,13 @@ mod tests {
             Test {
                 name: "Processing fails because the client does not have a consensus state for the required height"
                     .to_string(),
-                ctx: context.clone().with_channel(
+                ctx: context.clone()
+                .with_port_capability(packet.destination_port.clone())
+                .with_channel(
                     PortId::default(),
                     ChannelId::default(),
                     source_channel_end.clone(),
                 )
-                .with_port_capability(packet.destination_port.clone())
                 .with_connection(ConnectionId::default(), connection_end.clone()),
                 msg: msg.clone(),
                 want_pass: false,
@@ -242,13 +271,14 @@ mod tests {
             Test {
                 name: "Processing fails because the proof's timeout has not been reached "
                     .to_string(),
-                ctx: context.clone().with_channel(
+                ctx: context.clone()
+                .with_port_capability(packet.destination_port.clone())
+                .with_channel(
                     PortId::default(),
                     ChannelId::default(),
                     source_channel_end.clone(),
                 )
                 .with_client(&ClientId::default(), client_height)
-                .with_port_capability(packet.destination_port.clone())
                 .with_connection(ConnectionId::default(), connection_end.clone()),
                 msg,
                 want_pass: false,
@@ -303,7 +333,14 @@ mod tests {
         .collect();
 
         for test in tests {
-            let res = process(&test.ctx, test.msg.clone());
+            let channel_cap = test
+                .ctx
+                .channel_capability(
+                    &test.msg.packet.source_port,
+                    &test.msg.packet.source_channel,
+                )
+                .unwrap_or_else(get_dummy_capability);
+            let res = process(&test.ctx, &channel_cap, test.msg.clone());
             // Additionally check the events and the output objects in the result.
             match res {
                 Ok(proto_output) => {
@@ -2,19 +2,22 @@ use crate::events::IbcEvent;
 use crate::handler::{HandlerOutput, HandlerResult};
 use crate::ics04_channel::channel::State;
 use crate::ics04_channel::channel::{ChannelEnd, Counterparty, Order};
+use crate::ics04_channel::commitment::PacketCommitment;
 use crate::ics04_channel::events::TimeoutOnClosePacket;
-use crate::ics04_channel::handler::verify::verify_channel_proofs;
 use crate::ics04_channel::handler::verify::{
-    verify_next_sequence_recv, verify_packet_receipt_absence,
+    active_client_state, verify_channel_proofs, verify_next_sequence_recv,
+    verify_packet_receipt_absence,
 };
 use crate::ics04_channel::msgs::timeout_on_close::MsgTimeoutOnClose;
 use crate::ics04_channel::packet::PacketResult;
 use crate::ics04_channel::{
     context::ChannelReader, error::Error, error::Kind, handler::timeout::TimeoutPacketResult,
 };
+use crate::ics05_port::capabilities::Capability;
 
 pub fn process(
     ctx: &dyn ChannelReader,
+    channel_cap: &Capability,
     msg: MsgTimeoutOnClose,
 ) -> HandlerResult<PacketResult, Error> {
     let mut output = HandlerOutput::builder();
@@ -28,7 +31,11 @@ pub fn process(
                 .context(packet.source_channel.to_string())
         })?;
 
-    let _channel_cap = ctx.authenticated_capability(&packet.source_port)?;
+    // The caller must be one of the modules which own the channel.
+    let port_channel_id = (packet.source_port.clone(), packet.source_channel.clone());
+    if !ctx.authenticate_channel_capability(&port_channel_id, channel_cap) {
+        return Err(Kind::InvalidChannelCapability(port_channel_id.0, port_channel_id.1).into());
+    }
 
     let counterparty = Counterparty::new(
         packet.destination_port.clone(),
@@ -47,8 +54,6 @@ pub fn process(
         .connection_end(&source_channel_end.connection_hops()[0])
         .ok_or_else(|| Kind::MissingConnection(source_channel_end.connection_hops()[0].clone()))?;
 
-    let client_id = connection_end.client_id().clone();
-
     //verify the packet was sent, check the store
     let packet_commitment = ctx
         .get_packet_commitment(&(
@@ -58,12 +63,12 @@ pub fn process(
         ))
         .ok_or(Kind::PacketCommitmentNotFound(packet.sequence))?;
 
-    let input = format!(
-        "{:?},{:?},{:?}",
-        packet.timeout_timestamp, packet.timeout_height, packet.data,
+    let expected_commitment = PacketCommitment::compute(
+        &packet.data,
+        packet.timeout_height,
+        packet.timeout_timestamp,
     );
-
-    if packet_commitment != ChannelReader::hash(ctx, input) {
+    if packet_commitment != expected_commitment {
         return Err(Kind::IncorrectPacketCommitment(packet.sequence).into());
     }
 
@@ -87,50 +92,71 @@ pub fn process(
         source_channel_end.version(),
     );
 
-    verify_channel_proofs(
+    let client_state = active_client_state(ctx, connection_end.client_id())?;
+    let channel_client_state = verify_channel_proofs(
         ctx,
+        &client_state,
         &source_channel_end,
         &connection_end,
         &expected_channel_end,
         &msg.proofs.clone(),
     )?;
 
+    // The remaining proof is verified by the client as left by the verification of the channel
+    // proof.
+    let verifier = channel_client_state.as_ref().unwrap_or(&client_state);
+
+    // Read before the channel end is moved into the result.
+    let channel_ordering = *source_channel_end.ordering();
+
     let result = if source_channel_end.order_matches(&Order::Ordered) {
         if packet.sequence < msg.next_sequence_recv {
             return Err(
                 Kind::InvalidPacketSequence(packet.sequence, msg.next_sequence_recv).into(),
             );
         }
-        verify_next_sequence_recv(
+        let new_client_state = verify_next_sequence_recv(
             ctx,
-            client_id,
+            verifier,
+            &connection_end,
             packet.clone(),
             msg.next_sequence_recv,
             &msg.proofs.clone(),
-        )?;
+        )?
+        .or(channel_client_state);
 
         PacketResult::Timeout(TimeoutPacketResult {
             port_id: packet.source_port.clone(),
             channel_id: packet.source_channel.clone(),
             seq: packet.sequence,
             channel: Some(source_channel_end),
+            client_state: new_client_state.map(|cs| (connection_end.client_id().clone(), cs)),
         })
     } else {
-        verify_packet_receipt_absence(ctx, client_id, packet.clone(), &msg.proofs.clone())?;
+        let new_client_state = verify_packet_receipt_absence(
+            ctx,
+            verifier,
+            &connection_end,
+            packet.clone(),
+            &msg.proofs.clone(),
+        )?
+        .or(channel_client_state);
 
         PacketResult::Timeout(TimeoutPacketResult {
             port_id: packet.source_port.clone(),
             channel_id: packet.source_channel.clone(),
             seq: packet.sequence,
             channel: None,
+            client_state: new_client_state.map(|cs| (connection_end.client_id().clone(), cs)),
         })
     };
 
     output.log("success: packet timeout ");
 
     output.emit(IbcEvent::TimeoutOnClosePacket(TimeoutOnClosePacket {
-        height: Default::default(),
+        height: ctx.host_height(),
         packet: packet.clone(),
+        channel_ordering: Some(channel_ordering),
     }));
 
     Ok(output.with_result(result))
@@ -146,10 +172,11 @@ mod tests {
     use crate::ics03_connection::connection::State as ConnectionState;
     use crate::ics03_connection::version::get_compatible_versions;
     use crate::ics04_channel::channel::{ChannelEnd, Counterparty, Order, State};
-    use crate::ics04_channel::context::ChannelReader;
+    use crate::ics04_channel::commitment::PacketCommitment;
     use crate::ics04_channel::handler::timeout_on_close::process;
     use crate::ics04_channel::msgs::timeout_on_close::test_util::get_dummy_raw_msg_timeout_on_close;
     use crate::ics04_channel::msgs::timeout_on_close::MsgTimeoutOnClose;
+    use crate::ics05_port::capabilities::test_util::get_dummy_capability;
     use crate::ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId};
     use crate::timestamp::ZERO_DURATION;
 
@@ -181,13 +208,11 @@ mod tests {
         .unwrap();
         let packet = msg.packet.clone();
 
-        let input = format!(
-            "{:?},{:?},{:?}",
+        let data = PacketCommitment::compute(
+            &msg.packet.data,
+            msg.packet.timeout_height,
             msg.packet.timeout_timestamp,
-            msg.packet.timeout_height.clone(),
-            msg.packet.data.clone()
         );
-        let data = ChannelReader::hash(&context, input);
 
         let source_channel_end = ChannelEnd::new(
             State::Open,
@@ -223,12 +248,12 @@ mod tests {
                 name: "Processing fails no packet commitment is found".to_string(),
                 ctx: context
                     .clone()
+                    .with_port_capability(packet.destination_port.clone())
                     .with_channel(
                         PortId::default(),
                         ChannelId::default(),
                         source_channel_end.clone(),
                     )
-                    .with_port_capability(packet.destination_port.clone())
                     .with_connection(ConnectionId::default(), connection_end.clone()),
                 msg: msg.clone(),
                 want_pass: false,
@@ -258,7 +283,14 @@ mod tests {
         .collect();
 
         for test in tests {
-            let res = process(&test.ctx, test.msg.clone());
+            let channel_cap = test
+                .ctx
+                .channel_capability(
+                    &test.msg.packet.source_port,
+                    &test.msg.packet.source_channel,
+                )
+                .unwrap_or_else(get_dummy_capability);
+            let res = process(&test.ctx, &channel_cap, test.msg.clone());
             // Additionally check the events and the output objects in the result.
             match res {
                 Ok(proto_output) => {
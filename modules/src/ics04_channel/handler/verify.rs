@@ -1,193 +1,320 @@
-use crate::ics02_client::client_state::ClientState;
+//! ICS4 verification functions, common across the channel and packet handlers. The proofs are
+//! verified by the given state of the client underlying the connection, and each function returns
+//! the state of this client once it verified them, if verifying them changed it (see
+//! `ClientDef::client_state_after_verification`).
+
+use std::time::Duration;
+
+use crate::ics02_client::client_consensus::{AnyConsensusState, ConsensusState};
+use crate::ics02_client::client_state::{AnyClientState, ClientState, Status};
 use crate::ics02_client::{client_def::AnyClient, client_def::ClientDef};
 use crate::ics03_connection::connection::ConnectionEnd;
 use crate::ics04_channel::channel::ChannelEnd;
+use crate::ics04_channel::commitment::PacketCommitment;
 use crate::ics04_channel::context::ChannelReader;
 use crate::ics04_channel::error::{Error, Kind};
 use crate::ics04_channel::packet::{Packet, Sequence};
+use crate::ics23_commitment::commitment::CommitmentRoot;
 use crate::ics24_host::identifier::ClientId;
 use crate::proofs::Proofs;
+use crate::timestamp::Expiry;
+use crate::Height;
 
 /// Entry point for verifying all proofs bundled in any ICS4 message for channel protocols.
 pub fn verify_channel_proofs(
     ctx: &dyn ChannelReader,
+    client_state: &AnyClientState,
     channel_end: &ChannelEnd,
     connection_end: &ConnectionEnd,
     expected_chan: &ChannelEnd,
     proofs: &Proofs,
-) -> Result<(), Error> {
-    // This is the client which will perform proof verification.
-    let client_id = connection_end.client_id().clone();
-
-    let client_state = ctx
-        .client_state(&client_id)
-        .ok_or_else(|| Kind::MissingClientState(client_id.clone()))?;
-
-    // The client must not be frozen.
-    if client_state.is_frozen() {
-        return Err(Kind::FrozenClient(client_id).into());
-    }
-
-    if ctx
-        .client_consensus_state(&client_id, proofs.height())
-        .is_none()
-    {
-        return Err(Kind::MissingClientConsensusState(client_id, proofs.height()).into());
-    }
+) -> Result<Option<AnyClientState>, Error> {
+    let root = verification_root(ctx, client_state, connection_end, proofs.height())?;
 
     let client_def = AnyClient::from_client_type(client_state.client_type());
 
+    // The counterparty channel id is only known once the channel is past its `Init` state, and
+    // the handlers may reach here for channels that are not (e.g. on close confirm).
+    let counterparty_channel_id = channel_end
+        .counterparty()
+        .channel_id()
+        .ok_or(Kind::InvalidCounterpartyChannelId)?;
+
     // Verify the proof for the channel state against the expected channel end.
-    // A counterparty channel id of None in not possible, and is checked by validate_basic in msg.
-    Ok(client_def
+    client_def
         .verify_channel_state(
-            &client_state,
+            ctx,
+            client_state,
             proofs.height(),
             connection_end.counterparty().prefix(),
             proofs.object_proof(),
+            &root,
             &channel_end.counterparty().port_id(),
-            &channel_end.counterparty().channel_id().unwrap(),
+            counterparty_channel_id,
             expected_chan,
         )
-        .map_err(|_| Kind::InvalidProof)?)
+        .map_err(|_| Kind::InvalidProof)?;
+
+    client_state_after_verification(client_state, proofs)
 }
 
 /// Entry point for verifying all proofs bundled in a ICS4 packet recv. message.
 pub fn verify_packet_recv_proofs(
     ctx: &dyn ChannelReader,
+    client_state: &AnyClientState,
     packet: &Packet,
-    client_id: ClientId,
+    connection_end: &ConnectionEnd,
     proofs: &Proofs,
-) -> Result<(), Error> {
-    let client_state = ctx
-        .client_state(&client_id)
-        .ok_or_else(|| Kind::MissingClientState(client_id.clone()))?;
-
-    // The client must not be frozen.
-    if client_state.is_frozen() {
-        return Err(Kind::FrozenClient(client_id).into());
-    }
-
-    if ctx
-        .client_consensus_state(&client_id, proofs.height())
-        .is_none()
-    {
-        return Err(Kind::MissingClientConsensusState(client_id, proofs.height()).into());
-    }
+) -> Result<Option<AnyClientState>, Error> {
+    let root = verification_root(ctx, client_state, connection_end, proofs.height())?;
+    verify_delay_passed(ctx, client_state, connection_end, proofs.height())?;
 
     let client_def = AnyClient::from_client_type(client_state.client_type());
 
-    let input = format!(
-        "{:?},{:?},{:?}",
-        packet.timeout_timestamp, packet.timeout_height, packet.data
+    let commitment = PacketCommitment::compute(
+        &packet.data,
+        packet.timeout_height,
+        packet.timeout_timestamp,
     );
-    let commitment = ctx.hash(input);
 
     // Verify the proof for the packet against the chain store.
-    Ok(client_def
+    client_def
         .verify_packet_data(
-            &client_state,
+            ctx,
+            client_state,
             proofs.height(),
+            connection_end.counterparty().prefix(),
             proofs.object_proof(),
+            &root,
             &packet.source_port,
             &packet.source_channel,
             &packet.sequence,
             commitment,
         )
-        .map_err(|_| Kind::PacketVerificationFailed(packet.sequence))?)
+        .map_err(|_| Kind::PacketVerificationFailed(packet.sequence))?;
+
+    client_state_after_verification(client_state, proofs)
 }
 
 /// Entry point for verifying all proofs bundled in an ICS4 packet ack message.
 pub fn verify_packet_acknowledgement_proofs(
     ctx: &dyn ChannelReader,
+    client_state: &AnyClientState,
     packet: &Packet,
     acknowledgement: Vec<u8>,
-    client_id: ClientId,
+    connection_end: &ConnectionEnd,
     proofs: &Proofs,
-) -> Result<(), Error> {
-    let client_state = ctx
-        .client_state(&client_id)
-        .ok_or_else(|| Kind::MissingClientState(client_id.clone()))?;
-
-    // The client must not be frozen.
-    if client_state.is_frozen() {
-        return Err(Kind::FrozenClient(client_id).into());
-    }
+) -> Result<Option<AnyClientState>, Error> {
+    let root = verification_root(ctx, client_state, connection_end, proofs.height())?;
+    verify_delay_passed(ctx, client_state, connection_end, proofs.height())?;
 
     let client_def = AnyClient::from_client_type(client_state.client_type());
 
-    // Verify the proof for the packet against the chain store.
-    Ok(client_def
+    // Verify the proof for the packet against the chain store. The acknowledgement is written
+    // by the receiving chain, hence it is stored under the destination port and channel.
+    client_def
         .verify_packet_acknowledgement(
-            &client_state,
+            ctx,
+            client_state,
             proofs.height(),
+            connection_end.counterparty().prefix(),
             proofs.object_proof(),
-            &packet.source_port,
-            &packet.source_channel,
+            &root,
+            &packet.destination_port,
+            &packet.destination_channel,
             &packet.sequence,
             acknowledgement,
         )
-        .map_err(|_| Kind::PacketVerificationFailed(packet.sequence))?)
+        .map_err(|_| Kind::PacketVerificationFailed(packet.sequence))?;
+
+    client_state_after_verification(client_state, proofs)
 }
 
 /// Entry point for verifying all timeout proofs.
 pub fn verify_next_sequence_recv(
     ctx: &dyn ChannelReader,
-    client_id: ClientId,
+    client_state: &AnyClientState,
+    connection_end: &ConnectionEnd,
     packet: Packet,
     seq: Sequence,
     proofs: &Proofs,
-) -> Result<(), Error> {
-    let client_state = ctx
-        .client_state(&client_id)
-        .ok_or_else(|| Kind::MissingClientState(client_id.clone()))?;
-
-    // The client must not be frozen.
-    if client_state.is_frozen() {
-        return Err(Kind::FrozenClient(client_id).into());
-    }
+) -> Result<Option<AnyClientState>, Error> {
+    let root = verification_root(ctx, client_state, connection_end, proofs.height())?;
+    verify_delay_passed(ctx, client_state, connection_end, proofs.height())?;
 
     let client_def = AnyClient::from_client_type(client_state.client_type());
 
     // Verify the proof for the packet against the chain store.
-    Ok(client_def
+    client_def
         .verify_next_sequence_recv(
-            &client_state,
+            ctx,
+            client_state,
             proofs.height(),
+            connection_end.counterparty().prefix(),
             proofs.object_proof(),
+            &root,
             &packet.destination_port,
             &packet.destination_channel,
             &seq,
         )
-        .map_err(|_| Kind::PacketVerificationFailed(seq))?)
+        .map_err(|_| Kind::PacketVerificationFailed(seq))?;
+
+    client_state_after_verification(client_state, proofs)
 }
 
 pub fn verify_packet_receipt_absence(
     ctx: &dyn ChannelReader,
-    client_id: ClientId,
+    client_state: &AnyClientState,
+    connection_end: &ConnectionEnd,
     packet: Packet,
     proofs: &Proofs,
-) -> Result<(), Error> {
-    let client_state = ctx
-        .client_state(&client_id)
-        .ok_or_else(|| Kind::MissingClientState(client_id.clone()))?;
-
-    // The client must not be frozen.
-    if client_state.is_frozen() {
-        return Err(Kind::FrozenClient(client_id).into());
-    }
+) -> Result<Option<AnyClientState>, Error> {
+    let root = verification_root(ctx, client_state, connection_end, proofs.height())?;
+    verify_delay_passed(ctx, client_state, connection_end, proofs.height())?;
 
     let client_def = AnyClient::from_client_type(client_state.client_type());
 
     // Verify the proof for the packet against the chain store.
-    Ok(client_def
+    client_def
         .verify_packet_receipt_absence(
-            &client_state,
+            ctx,
+            client_state,
             proofs.height(),
+            connection_end.counterparty().prefix(),
             proofs.object_proof(),
+            &root,
             &packet.destination_port,
             &packet.destination_channel,
             &packet.sequence,
         )
-        .map_err(|_| Kind::PacketVerificationFailed(packet.sequence))?)
+        .map_err(|_| Kind::PacketVerificationFailed(packet.sequence))?;
+
+    client_state_after_verification(client_state, proofs)
+}
+
+/// Checks that the delay period of `connection_end` has passed since the consensus state at
+/// `proof_height` was stored on the local chain, both in time and in blocks. The number of blocks
+/// is derived from the delay period and the maximum expected time per block of the local chain.
+/// A non-zero delay cannot be checked, hence is not passed, unless both the current timestamp
+/// of the local chain and the processed time of the consensus state are set.
+///
+/// Clients which keep their consensus state in their own state (see
+/// `AnyClientState::own_consensus_state`) do not store consensus states on the local chain, hence
+/// the delay period does not apply to them, as for solo machines in ibc-go.
+fn verify_delay_passed(
+    ctx: &dyn ChannelReader,
+    client_state: &AnyClientState,
+    connection_end: &ConnectionEnd,
+    proof_height: Height,
+) -> Result<(), Error> {
+    if client_state.own_consensus_state().is_some() {
+        return Ok(());
+    }
+
+    let client_id = connection_end.client_id();
+    let delay_period = connection_end.delay_period();
+
+    let processed_time = ctx
+        .client_update_time(client_id, proof_height)
+        .ok_or_else(|| Kind::ProcessedTimeNotFound(client_id.clone(), proof_height))?;
+    let processed_height = ctx
+        .client_update_height(client_id, proof_height)
+        .ok_or_else(|| Kind::ProcessedHeightNotFound(client_id.clone(), proof_height))?;
+
+    let current_time = ctx.host_timestamp();
+    if delay_period.as_nanos() != 0 {
+        let earliest_time =
+            (processed_time + delay_period).map_err(|e| Kind::InvalidPacketTimestamp.context(e))?;
+        match earliest_time.check_expiry(&current_time) {
+            Expiry::NotExpired => {}
+            Expiry::Expired => {
+                return Err(Kind::DelayTimeNotPassed(current_time, earliest_time).into())
+            }
+            Expiry::InvalidTimestamp => {
+                return Err(Kind::MissingDelayTimestamp(current_time, processed_time).into())
+            }
+        }
+    }
+
+    let current_height = ctx.host_height();
+    let earliest_height =
+        processed_height.add(block_delay(delay_period, ctx.max_expected_time_per_block()));
+    if current_height < earliest_height {
+        return Err(Kind::DelayHeightNotPassed(current_height, earliest_height).into());
+    }
+
+    Ok(())
+}
+
+/// The number of blocks which the local chain produces at least during `delay_period`, given
+/// the maximum expected time per block.
+fn block_delay(delay_period: Duration, max_expected_time_per_block: Duration) -> u64 {
+    if max_expected_time_per_block.as_nanos() == 0 {
+        return 0;
+    }
+
+    let delay = delay_period.as_nanos();
+    let block_time = max_expected_time_per_block.as_nanos();
+    ((delay + block_time - 1) / block_time) as u64
+}
+
+/// Fetches the commitment root against which `client_state`, the client underlying
+/// `connection_end`, verifies proofs at `proof_height`.
+fn verification_root(
+    ctx: &dyn ChannelReader,
+    client_state: &AnyClientState,
+    connection_end: &ConnectionEnd,
+    proof_height: Height,
+) -> Result<CommitmentRoot, Error> {
+    let consensus_state =
+        verification_consensus_state(ctx, client_state, connection_end.client_id(), proof_height)?;
+
+    Ok(consensus_state.root().clone())
+}
+
+/// Fetches the consensus state of `client_state`, the client `client_id`, against which a proof
+/// at `proof_height` is verified: the one the client keeps in its state, if any, or the one the
+/// client has for the height where the proof was created.
+pub fn verification_consensus_state(
+    ctx: &dyn ChannelReader,
+    client_state: &AnyClientState,
+    client_id: &ClientId,
+    proof_height: Height,
+) -> Result<AnyConsensusState, Error> {
+    client_state
+        .own_consensus_state()
+        .or_else(|| ctx.client_consensus_state(client_id, proof_height))
+        .ok_or_else(|| Kind::MissingClientConsensusState(client_id.clone(), proof_height).into())
+}
+
+/// Returns the state of the client once it verified the object proof in `proofs`, if verifying
+/// the proof changed it.
+fn client_state_after_verification(
+    client_state: &AnyClientState,
+    proofs: &Proofs,
+) -> Result<Option<AnyClientState>, Error> {
+    let client_def = AnyClient::from_client_type(client_state.client_type());
+
+    Ok(client_def
+        .client_state_after_verification(client_state, proofs.height(), proofs.object_proof())
+        .map_err(|e| Kind::InvalidProof.context(e.to_string()))?)
+}
+
+/// Fetches the state of the client `client_id`, which must be active, i.e., neither frozen nor
+/// expired.
+pub fn active_client_state(
+    ctx: &dyn ChannelReader,
+    client_id: &ClientId,
+) -> Result<AnyClientState, Error> {
+    let client_state = ctx
+        .client_state(client_id)
+        .ok_or_else(|| Kind::MissingClientState(client_id.clone()))?;
+
+    let latest_consensus_state =
+        ctx.client_consensus_state(client_id, client_state.latest_height());
+    match client_state.status(ctx.host_timestamp(), latest_consensus_state.as_ref()) {
+        Status::Active => Ok(client_state),
+        Status::Frozen => Err(Kind::FrozenClient(client_id.clone()).into()),
+        status => Err(Kind::ClientNotActive(client_id.clone(), status).into()),
+    }
 }
@@ -1,7 +1,9 @@
 use crate::ics04_channel::channel::State;
+use crate::ics04_channel::commitment::AcknowledgementCommitment;
 use crate::ics04_channel::events::WriteAcknowledgement;
 use crate::ics04_channel::packet::{Packet, PacketResult, Sequence};
 use crate::ics04_channel::{context::ChannelReader, error::Error, error::Kind};
+use crate::ics05_port::capabilities::Capability;
 use crate::ics24_host::identifier::{ChannelId, PortId};
 use crate::{
     events::IbcEvent,
@@ -13,11 +15,14 @@ pub struct WriteAckPacketResult {
     pub port_id: PortId,
     pub channel_id: ChannelId,
     pub seq: Sequence,
-    pub ack: Vec<u8>,
+    pub ack_commitment: AcknowledgementCommitment,
 }
 
+/// Writes the acknowledgement of `packet` on behalf of the module presenting `channel_cap`, which
+/// must own the destination channel of the packet.
 pub fn process(
     ctx: &dyn ChannelReader,
+    channel_cap: &Capability,
     packet: Packet,
     ack: Vec<u8>,
 ) -> HandlerResult<PacketResult, Error> {
@@ -41,7 +46,13 @@ pub fn process(
         );
     }
 
-    let _channel_cap = ctx.authenticated_capability(&packet.destination_port)?;
+    let port_channel_id = (
+        packet.destination_port.clone(),
+        packet.destination_channel.clone(),
+    );
+    if !ctx.authenticate_channel_capability(&port_channel_id, channel_cap) {
+        return Err(Kind::InvalidChannelCapability(port_channel_id.0, port_channel_id.1).into());
+    }
 
     // NOTE: IBC app modules might have written the acknowledgement synchronously on
     // the OnRecvPacket callback so we need to check if the acknowledgement is already
@@ -61,19 +72,21 @@ pub fn process(
         return Err(Kind::InvalidAcknowledgement.into());
     }
 
+    // The acknowledgement is stored under the port and channel of this (i.e., receiving) chain.
     let result = PacketResult::WriteAck(WriteAckPacketResult {
-        port_id: packet.source_port.clone(),
-        channel_id: packet.source_channel.clone(),
+        port_id: packet.destination_port.clone(),
+        channel_id: packet.destination_channel.clone(),
         seq: packet.sequence,
-        ack: ack.clone(),
+        ack_commitment: AcknowledgementCommitment::compute(&ack),
     });
 
     output.log("success: packet write acknowledgement");
 
     output.emit(IbcEvent::WriteAcknowledgement(WriteAcknowledgement {
-        height: Default::default(),
+        height: ctx.host_height(),
         packet,
         ack,
+        connection_id: Some(dest_channel_end.connection_hops()[0].clone()),
     }));
 
     Ok(output.with_result(result))
@@ -92,6 +105,7 @@ mod tests {
     use crate::ics04_channel::channel::{ChannelEnd, Counterparty, Order, State};
     use crate::ics04_channel::handler::write_acknowledgement::process;
     use crate::ics04_channel::packet::test_utils::get_dummy_raw_packet;
+    use crate::ics05_port::capabilities::test_util::get_dummy_capability;
     use crate::ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId};
     use crate::mock::context::MockContext;
     use crate::timestamp::ZERO_DURATION;
@@ -193,7 +207,14 @@ mod tests {
         .collect();
 
         for test in tests {
-            let res = process(&test.ctx, test.packet.clone(), test.ack);
+            let channel_cap = test
+                .ctx
+                .channel_capability(
+                    &test.packet.destination_port,
+                    &test.packet.destination_channel,
+                )
+                .unwrap_or_else(get_dummy_capability);
+            let res = process(&test.ctx, &channel_cap, test.packet.clone(), test.ack);
             // Additionally check the events and the output objects in the result.
             match res {
                 Ok(proto_output) => {
@@ -1,6 +1,8 @@
 //! ICS 04: IBC Channel implementation
 
+pub mod acknowledgement;
 pub mod channel;
+pub mod commitment;
 pub mod context;
 pub mod error;
 pub mod events;
@@ -16,6 +16,7 @@ pub const TYPE_URL: &str = "/ibc.core.channel.v1.MsgAcknowledgement";
 /// Message definition for packet acknowledgements.
 ///
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct MsgAcknowledgement {
     pub packet: Packet,
     pub acknowledgement: Vec<u8>, // TODO(romac): Introduce a newtype for this
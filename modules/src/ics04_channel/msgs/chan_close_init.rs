@@ -15,6 +15,7 @@ pub const TYPE_URL: &str = "/ibc.core.channel.v1.MsgChannelCloseInit";
 /// Message definition for the first step in the channel close handshake (`ChanCloseInit` datagram).
 ///
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct MsgChannelCloseInit {
     pub port_id: PortId,
     pub channel_id: ChannelId,
@@ -16,6 +16,7 @@ pub const TYPE_URL: &str = "/ibc.core.channel.v1.MsgChannelOpenAck";
 /// Message definition for the third step in the channel open handshake (`ChanOpenAck` datagram).
 ///
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct MsgChannelOpenAck {
     pub port_id: PortId,
     pub channel_id: ChannelId,
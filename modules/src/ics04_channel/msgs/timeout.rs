@@ -16,6 +16,7 @@ pub const TYPE_URL: &str = "/ibc.core.channel.v1.MsgTimeout";
 /// Message definition for packet timeout domain type.
 ///
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct MsgTimeout {
     pub packet: Packet,
     pub next_sequence_recv: Sequence,
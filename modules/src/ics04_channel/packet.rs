@@ -5,8 +5,9 @@ use serde_derive::{Deserialize, Serialize};
 
 use ibc_proto::ibc::core::channel::v1::Packet as RawPacket;
 
+use crate::ics02_client::client_state::AnyClientState;
 use crate::ics04_channel::error::Kind;
-use crate::ics24_host::identifier::{ChannelId, PortId};
+use crate::ics24_host::identifier::{ChannelId, ClientId, PortId};
 use crate::timestamp::{Expiry::Expired, Timestamp};
 use crate::Height;
 
@@ -34,6 +35,19 @@ pub enum PacketResult {
     Timeout(TimeoutPacketResult),
 }
 
+impl PacketResult {
+    /// Returns the new state of the client which verified the proofs of the packet message, if
+    /// verifying them changed it.
+    pub fn client_state(&self) -> Option<&(ClientId, AnyClientState)> {
+        match self {
+            PacketResult::Recv(res) => res.client_state.as_ref(),
+            PacketResult::Ack(res) => res.client_state.as_ref(),
+            PacketResult::Timeout(res) => res.client_state.as_ref(),
+            PacketResult::Send(_) | PacketResult::WriteAck(_) => None,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum Receipt {
     Ok,
@@ -81,6 +95,14 @@ impl Sequence {
     }
 }
 
+/// Sequences are generated small, like those of the first packets sent on a channel.
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Sequence {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self(u.int_in_range(0..=8)?))
+    }
+}
+
 impl From<u64> for Sequence {
     fn from(seq: u64) -> Self {
         Sequence(seq)
@@ -100,6 +122,7 @@ impl std::fmt::Display for Sequence {
 }
 
 #[derive(PartialEq, Deserialize, Serialize, Hash, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Packet {
     pub sequence: Sequence,
     pub source_port: PortId,
@@ -1,18 +1,233 @@
-//! Capabilities: this is a placeholder.
+//! Capabilities, as specified by ICS5: unforgeable keys which grant their owners the right to act
+//! on a port or on a channel.
 
-#[derive(Clone, Debug, PartialEq)]
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::ics05_port::error::{Error, Kind};
+use crate::ics24_host::identifier::{ChannelId, PortId};
+
+/// An object-capability key. As there is no way to construct a `Capability` outside of a
+/// `CapabilityStore`, holding one proves that it was handed out by the store.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Capability {
     index: u64,
 }
 
 impl Capability {
-    pub fn new() -> Capability {
-        Self { index: 0x0 }
+    pub fn index(&self) -> u64 {
+        self.index
+    }
+}
+
+/// Identifies the module, e.g., an IBC application, which owns a capability.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ModuleId(String);
+
+impl ModuleId {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self(name.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for ModuleId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// The name under which a capability is stored, which is derived from the object it protects.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CapabilityName(String);
+
+impl CapabilityName {
+    /// The name of the capability of a port, `ports/{port_id}`.
+    pub fn port(port_id: &PortId) -> Self {
+        Self(format!("ports/{}", port_id))
+    }
+
+    /// The name of the capability of a channel, `capabilities/ports/{port_id}/channels/{channel_id}`.
+    pub fn channel(port_id: &PortId, channel_id: &ChannelId) -> Self {
+        Self(format!(
+            "capabilities/ports/{}/channels/{}",
+            port_id, channel_id
+        ))
+    }
+}
+
+impl fmt::Display for CapabilityName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Creates capabilities and keeps track of the modules which own each of them. A host chain can
+/// embed a `CapabilityStore` to implement the `CapabilityReader` and `CapabilityKeeper` traits.
+#[derive(Clone, Debug, Default)]
+pub struct CapabilityStore {
+    /// Each capability along with its owners, indexed by name.
+    capabilities: HashMap<CapabilityName, (Capability, Vec<ModuleId>)>,
+    /// The index of the next capability to be created. Like the identifier counters of the
+    /// host chain, it is part of the state, so that every node hands out the same capabilities.
+    next_index: u64,
+}
+
+impl CapabilityStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a capability named `name`, owned by `module_id`. Fails if a capability with the
+    /// same name exists already, regardless of its owners.
+    pub fn new_capability(
+        &mut self,
+        module_id: &ModuleId,
+        name: CapabilityName,
+    ) -> Result<Capability, Error> {
+        if self.capabilities.contains_key(&name) {
+            return Err(Kind::CapabilityTaken(name).into());
+        }
+
+        let capability = Capability {
+            index: self.next_index,
+        };
+        self.next_index += 1;
+        self.capabilities
+            .insert(name, (capability.clone(), vec![module_id.clone()]));
+        Ok(capability)
+    }
+
+    /// Makes `module_id` an owner of `capability`, which must be the capability named `name`.
+    pub fn claim_capability(
+        &mut self,
+        module_id: &ModuleId,
+        name: CapabilityName,
+        capability: &Capability,
+    ) -> Result<(), Error> {
+        let owners = match self.capabilities.get_mut(&name) {
+            Some((cap, owners)) if cap == capability => owners,
+            _ => return Err(Kind::UnknownCapability(name).into()),
+        };
+
+        if owners.contains(module_id) {
+            return Err(Kind::CapabilityAlreadyOwned(module_id.clone(), name).into());
+        }
+        owners.push(module_id.clone());
+        Ok(())
+    }
+
+    /// Returns the capability named `name`, provided that it is owned by `module_id`.
+    pub fn get_capability(
+        &self,
+        module_id: &ModuleId,
+        name: &CapabilityName,
+    ) -> Option<Capability> {
+        self.capabilities
+            .get(name)
+            .filter(|(_, owners)| owners.contains(module_id))
+            .map(|(capability, _)| capability.clone())
+    }
+
+    /// Returns true if `capability` is the capability named `name`.
+    pub fn authenticate_capability(&self, name: &CapabilityName, capability: &Capability) -> bool {
+        matches!(self.capabilities.get(name), Some((cap, _)) if cap == capability)
+    }
+
+    /// Returns the owners of the capability named `name`, in the order they acquired it.
+    pub fn lookup_modules(&self, name: &CapabilityName) -> Vec<ModuleId> {
+        self.capabilities
+            .get(name)
+            .map(|(_, owners)| owners.clone())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+pub mod test_util {
+    use super::Capability;
+
+    /// Returns a capability which no context hands out, for testing only!
+    pub fn get_dummy_capability() -> Capability {
+        Capability { index: u64::MAX }
     }
 }
 
-impl Default for Capability {
-    fn default() -> Self {
-        Self::new()
+#[cfg(test)]
+mod tests {
+    use test_env_log::test;
+
+    use crate::ics05_port::error::Kind;
+    use crate::ics24_host::identifier::{ChannelId, PortId};
+
+    use super::{CapabilityName, CapabilityStore, ModuleId};
+
+    #[test]
+    fn capabilities_are_unique_and_owned() {
+        let mut store = CapabilityStore::new();
+        let (transfer, other) = (ModuleId::new("transfer"), ModuleId::new("other"));
+        let port_name = CapabilityName::port(&PortId::default());
+        let channel_name = CapabilityName::channel(&PortId::default(), &ChannelId::default());
+
+        let port_cap = store.new_capability(&transfer, port_name.clone()).unwrap();
+        let channel_cap = store
+            .new_capability(&transfer, channel_name.clone())
+            .unwrap();
+        assert_ne!(port_cap, channel_cap);
+
+        // Names are unique, even across modules.
+        let res = store.new_capability(&other, port_name.clone());
+        assert_eq!(
+            res.unwrap_err().kind(),
+            &Kind::CapabilityTaken(port_name.clone())
+        );
+
+        // Only the owners can retrieve a capability, which authenticates only under its own name.
+        assert_eq!(
+            store.get_capability(&transfer, &port_name),
+            Some(port_cap.clone())
+        );
+        assert_eq!(store.get_capability(&other, &port_name), None);
+        assert!(store.authenticate_capability(&port_name, &port_cap));
+        assert!(!store.authenticate_capability(&port_name, &channel_cap));
+
+        // A capability can be claimed only with the capability itself, and only once per module.
+        let res = store.claim_capability(&other, port_name.clone(), &channel_cap);
+        assert_eq!(
+            res.unwrap_err().kind(),
+            &Kind::UnknownCapability(port_name.clone())
+        );
+        store
+            .claim_capability(&other, port_name.clone(), &port_cap)
+            .unwrap();
+        let res = store.claim_capability(&other, port_name.clone(), &port_cap);
+        assert_eq!(
+            res.unwrap_err().kind(),
+            &Kind::CapabilityAlreadyOwned(other.clone(), port_name.clone())
+        );
+
+        assert_eq!(store.get_capability(&other, &port_name), Some(port_cap));
+        assert_eq!(store.lookup_modules(&port_name), vec![transfer, other]);
+    }
+
+    #[test]
+    fn capability_indices_depend_on_the_store_only() {
+        let module_id = ModuleId::new("transfer");
+        let names = vec![
+            CapabilityName::port(&PortId::default()),
+            CapabilityName::channel(&PortId::default(), &ChannelId::default()),
+        ];
+
+        // Two stores which create the same capabilities hand out the same indices, in sequence.
+        let (mut a, mut b) = (CapabilityStore::new(), CapabilityStore::new());
+        for (index, name) in names.into_iter().enumerate() {
+            let cap_a = a.new_capability(&module_id, name.clone()).unwrap();
+            let cap_b = b.new_capability(&module_id, name).unwrap();
+            assert_eq!(cap_a.index(), index as u64);
+            assert_eq!(cap_a, cap_b);
+        }
     }
 }
@@ -1,10 +1,60 @@
-use crate::ics05_port::capabilities::Capability;
+use crate::ics05_port::capabilities::{Capability, CapabilityName, ModuleId};
+use crate::ics05_port::error::{Error, Kind};
 use crate::ics24_host::identifier::PortId;
 
+/// A context supplying read-only access to the capabilities held by the modules of a chain.
+pub trait CapabilityReader {
+    /// Returns the capability named `name`, provided that `module_id` owns it.
+    fn get_capability(&self, module_id: &ModuleId, name: &CapabilityName) -> Option<Capability>;
+
+    /// Returns true if `capability` is the capability named `name`.
+    fn authenticate_capability(&self, name: &CapabilityName, capability: &Capability) -> bool;
+
+    /// Returns the owners of the capability named `name`, in the order they acquired it.
+    fn lookup_modules(&self, name: &CapabilityName) -> Vec<ModuleId>;
+}
+
+/// A context supplying the means to create and claim capabilities.
+pub trait CapabilityKeeper {
+    /// Creates a new capability named `name`, owned by `module_id`.
+    fn new_capability(
+        &mut self,
+        module_id: &ModuleId,
+        name: CapabilityName,
+    ) -> Result<Capability, Error>;
+
+    /// Makes `module_id` an owner of `capability`, which must be the capability named `name`.
+    fn claim_capability(
+        &mut self,
+        module_id: &ModuleId,
+        name: CapabilityName,
+        capability: &Capability,
+    ) -> Result<(), Error>;
+}
+
 // A context supplying all the necessary read-only dependencies for processing any information regarding a port.
-pub trait PortReader {
-    fn lookup_module_by_port(&self, port_id: &PortId) -> Option<Capability>;
-    fn authenticate(&self, key: &Capability, port_id: &PortId) -> bool;
+pub trait PortReader: CapabilityReader {
+    /// Returns the module which bound `port_id`, along with the capability of the port.
+    fn lookup_module_by_port(&self, port_id: &PortId) -> Option<(ModuleId, Capability)> {
+        let name = CapabilityName::port(port_id);
+        let module_id = self.lookup_modules(&name).into_iter().next()?;
+        let capability = self.get_capability(&module_id, &name)?;
+        Some((module_id, capability))
+    }
+
+    /// Returns true if `key` is the capability of `port_id`.
+    fn authenticate(&self, key: &Capability, port_id: &PortId) -> bool {
+        self.authenticate_capability(&CapabilityName::port(port_id), key)
+    }
 }
 
-//  Result<Capability, Error>//return Ok(Capability::new());
+/// A context supplying the means to bind ports to modules.
+pub trait PortKeeper: PortReader + CapabilityKeeper {
+    /// Binds `port_id` to `module_id`, which becomes the owner of the port capability.
+    fn bind_port(&mut self, module_id: &ModuleId, port_id: &PortId) -> Result<Capability, Error> {
+        if self.lookup_module_by_port(port_id).is_some() {
+            return Err(Kind::PortAlreadyBound(port_id.clone()).into());
+        }
+        self.new_capability(module_id, CapabilityName::port(port_id))
+    }
+}
@@ -1,12 +1,27 @@
 use anomaly::{BoxError, Context};
 use thiserror::Error;
 
+use crate::ics05_port::capabilities::{CapabilityName, ModuleId};
+use crate::ics24_host::identifier::PortId;
+
 pub type Error = anomaly::Error<Kind>;
 
-#[derive(Clone, Debug, Error)]
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
 pub enum Kind {
     #[error("port unknown")]
     UnknownPort,
+
+    #[error("port {0} is already bound to a module")]
+    PortAlreadyBound(PortId),
+
+    #[error("capability {0} already exists")]
+    CapabilityTaken(CapabilityName),
+
+    #[error("capability {0} does not exist, or does not match the given capability")]
+    UnknownCapability(CapabilityName),
+
+    #[error("module {0} already owns capability {1}")]
+    CapabilityAlreadyOwned(ModuleId, CapabilityName),
 }
 
 impl Kind {
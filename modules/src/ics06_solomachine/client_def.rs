@@ -0,0 +1,715 @@
+use ibc_proto::ibc::core::commitment::v1::MerkleProof;
+use ibc_proto::ibc::lightclients::solomachine::v1::{
+    ChannelStateData, ClientStateData, ConnectionStateData, ConsensusStateData, DataType,
+    NextSequenceRecvData, PacketAcknowledgementData, PacketCommitmentData,
+    PacketReceiptAbsenceData,
+};
+
+use crate::ics02_client::client_consensus::AnyConsensusState;
+use crate::ics02_client::client_def::ClientDef;
+use crate::ics02_client::client_state::{AnyClientState, ClientState as _};
+use crate::ics02_client::context::ClientReader;
+use crate::ics03_connection::connection::ConnectionEnd;
+use crate::ics03_connection::context::ConnectionReader;
+use crate::ics04_channel::channel::ChannelEnd;
+use crate::ics04_channel::commitment::PacketCommitment;
+use crate::ics04_channel::context::ChannelReader;
+use crate::ics04_channel::packet::Sequence;
+use crate::ics06_solomachine::client_state::ClientState;
+use crate::ics06_solomachine::consensus_state::ConsensusState;
+use crate::ics06_solomachine::error::{Error, Kind};
+use crate::ics06_solomachine::header::Header;
+use crate::ics06_solomachine::misbehaviour::Misbehaviour;
+use crate::ics06_solomachine::proof;
+use crate::ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes, CommitmentRoot};
+use crate::ics23_commitment::merkle::apply_prefix;
+use crate::ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId};
+use crate::ics24_host::Path;
+use crate::Height;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SoloMachineClient;
+
+impl ClientDef for SoloMachineClient {
+    type Header = Header;
+    type ClientState = ClientState;
+    type ConsensusState = ConsensusState;
+    type Misbehaviour = Misbehaviour;
+
+    fn check_header_and_update_state(
+        &self,
+        _ctx: &dyn ClientReader,
+        _client_id: ClientId,
+        client_state: Self::ClientState,
+        header: Self::Header,
+    ) -> Result<(Self::ClientState, Self::ConsensusState), Box<dyn std::error::Error>> {
+        if client_state.is_frozen() {
+            return Err(Error::from(Kind::FrozenClient).into());
+        }
+
+        let consensus_state = &client_state.consensus_state;
+        if header.sequence != client_state.sequence {
+            return Err(Error::from(Kind::SequenceMismatch(
+                client_state.sequence,
+                header.sequence,
+            ))
+            .into());
+        }
+        if header.timestamp < consensus_state.timestamp {
+            return Err(Error::from(Kind::TimestampTooOld(
+                consensus_state.timestamp,
+                header.timestamp,
+            ))
+            .into());
+        }
+
+        // The new public key and diversifier must be signed with the current public key.
+        let sign_bytes = proof::sign_bytes(
+            header.sequence,
+            header.timestamp,
+            &consensus_state.diversifier,
+            DataType::Header,
+            proof::header_data(&header.new_public_key, &header.new_diversifier),
+        );
+        proof::verify_signature(&consensus_state.public_key, &sign_bytes, &header.signature)?;
+
+        let new_consensus_state = ConsensusState::new(
+            header.new_public_key,
+            header.new_diversifier,
+            header.timestamp,
+        );
+
+        Ok((
+            ClientState {
+                sequence: header.sequence + 1,
+                consensus_state: new_consensus_state.clone(),
+                ..client_state
+            },
+            new_consensus_state,
+        ))
+    }
+
+    fn check_misbehaviour_and_update_state(
+        &self,
+        _ctx: &dyn ClientReader,
+        _client_id: ClientId,
+        client_state: Self::ClientState,
+        misbehaviour: Self::Misbehaviour,
+    ) -> Result<Self::ClientState, Box<dyn std::error::Error>> {
+        if client_state.is_frozen() {
+            return Err(Error::from(Kind::FrozenClient).into());
+        }
+
+        let signature_one = &misbehaviour.signature_one;
+        let signature_two = &misbehaviour.signature_two;
+        if signature_one.signature == signature_two.signature {
+            return Err(Error::from(Kind::MisbehaviourSignaturesEqual).into());
+        }
+        if signature_one.data == signature_two.data {
+            return Err(Error::from(Kind::MisbehaviourDataEqual).into());
+        }
+
+        // Both signatures must be valid signatures of the solo machine at the misbehaviour
+        // sequence. Their timestamps are not checked, so that past misbehaviour is processed.
+        let consensus_state = &client_state.consensus_state;
+        for signature in [signature_one, signature_two].iter() {
+            let sign_bytes = proof::sign_bytes(
+                misbehaviour.sequence,
+                signature.timestamp,
+                &consensus_state.diversifier,
+                signature.data_type,
+                signature.data.clone(),
+            );
+            proof::verify_signature(
+                &consensus_state.public_key,
+                &sign_bytes,
+                &signature.signature,
+            )?;
+        }
+
+        Ok(client_state.with_frozen_sequence(misbehaviour.sequence))
+    }
+
+    fn verify_client_consensus_state(
+        &self,
+        _ctx: &dyn ConnectionReader,
+        client_state: &Self::ClientState,
+        height: Height,
+        prefix: &CommitmentPrefix,
+        proof: &CommitmentProofBytes,
+        _root: &CommitmentRoot,
+        client_id: &ClientId,
+        consensus_height: Height,
+        expected_consensus_state: &AnyConsensusState,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let path = Path::ClientConsensusState {
+            client_id: client_id.clone(),
+            epoch: consensus_height.revision_number,
+            height: consensus_height.revision_height,
+        };
+        let data = proof::encode(&ConsensusStateData {
+            path: signed_path(prefix, path)?,
+            consensus_state: Some(expected_consensus_state.clone().into()),
+        });
+
+        Ok(verify_signed_data(
+            client_state,
+            height,
+            proof,
+            DataType::ConsensusState,
+            data,
+        )?)
+    }
+
+    fn verify_connection_state(
+        &self,
+        _ctx: &dyn ConnectionReader,
+        client_state: &Self::ClientState,
+        height: Height,
+        prefix: &CommitmentPrefix,
+        proof: &CommitmentProofBytes,
+        _root: &CommitmentRoot,
+        connection_id: Option<&ConnectionId>,
+        expected_connection_end: &ConnectionEnd,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let connection_id = connection_id.ok_or_else(|| -> Error {
+            Kind::VerificationFailure
+                .context("missing counterparty connection identifier")
+                .into()
+        })?;
+        let path = Path::Connections(connection_id.clone());
+        let data = proof::encode(&ConnectionStateData {
+            path: signed_path(prefix, path)?,
+            connection: Some(expected_connection_end.clone().into()),
+        });
+
+        Ok(verify_signed_data(
+            client_state,
+            height,
+            proof,
+            DataType::ConnectionState,
+            data,
+        )?)
+    }
+
+    fn verify_channel_state(
+        &self,
+        _ctx: &dyn ChannelReader,
+        client_state: &Self::ClientState,
+        height: Height,
+        prefix: &CommitmentPrefix,
+        proof: &CommitmentProofBytes,
+        _root: &CommitmentRoot,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        expected_channel_end: &ChannelEnd,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let path = Path::ChannelEnds(port_id.clone(), channel_id.clone());
+        let data = proof::encode(&ChannelStateData {
+            path: signed_path(prefix, path)?,
+            channel: Some(expected_channel_end.clone().into()),
+        });
+
+        Ok(verify_signed_data(
+            client_state,
+            height,
+            proof,
+            DataType::ChannelState,
+            data,
+        )?)
+    }
+
+    fn verify_client_full_state(
+        &self,
+        _ctx: &dyn ConnectionReader,
+        client_state: &Self::ClientState,
+        height: Height,
+        _root: &CommitmentRoot,
+        prefix: &CommitmentPrefix,
+        client_id: &ClientId,
+        proof: &CommitmentProofBytes,
+        expected_client_state: &AnyClientState,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let path = Path::ClientState(client_id.clone());
+        let data = proof::encode(&ClientStateData {
+            path: signed_path(prefix, path)?,
+            client_state: Some(expected_client_state.clone().into()),
+        });
+
+        Ok(verify_signed_data(
+            client_state,
+            height,
+            proof,
+            DataType::ClientState,
+            data,
+        )?)
+    }
+
+    fn verify_packet_data(
+        &self,
+        _ctx: &dyn ChannelReader,
+        client_state: &Self::ClientState,
+        height: Height,
+        prefix: &CommitmentPrefix,
+        proof: &CommitmentProofBytes,
+        _root: &CommitmentRoot,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        seq: &Sequence,
+        commitment: PacketCommitment,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let path = Path::Commitments {
+            port_id: port_id.clone(),
+            channel_id: channel_id.clone(),
+            sequence: *seq,
+        };
+        let data = proof::encode(&PacketCommitmentData {
+            path: signed_path(prefix, path)?,
+            commitment: commitment.into_vec(),
+        });
+
+        Ok(verify_signed_data(
+            client_state,
+            height,
+            proof,
+            DataType::PacketCommitment,
+            data,
+        )?)
+    }
+
+    fn verify_packet_acknowledgement(
+        &self,
+        _ctx: &dyn ChannelReader,
+        client_state: &Self::ClientState,
+        height: Height,
+        prefix: &CommitmentPrefix,
+        proof: &CommitmentProofBytes,
+        _root: &CommitmentRoot,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        seq: &Sequence,
+        ack: Vec<u8>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let path = Path::Acks {
+            port_id: port_id.clone(),
+            channel_id: channel_id.clone(),
+            sequence: *seq,
+        };
+        let data = proof::encode(&PacketAcknowledgementData {
+            path: signed_path(prefix, path)?,
+            acknowledgement: ack,
+        });
+
+        Ok(verify_signed_data(
+            client_state,
+            height,
+            proof,
+            DataType::PacketAcknowledgement,
+            data,
+        )?)
+    }
+
+    fn verify_next_sequence_recv(
+        &self,
+        _ctx: &dyn ChannelReader,
+        client_state: &Self::ClientState,
+        height: Height,
+        prefix: &CommitmentPrefix,
+        proof: &CommitmentProofBytes,
+        _root: &CommitmentRoot,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        seq: &Sequence,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let path = Path::SeqRecvs(port_id.clone(), channel_id.clone());
+        let data = proof::encode(&NextSequenceRecvData {
+            path: signed_path(prefix, path)?,
+            next_seq_recv: u64::from(*seq),
+        });
+
+        Ok(verify_signed_data(
+            client_state,
+            height,
+            proof,
+            DataType::NextSequenceRecv,
+            data,
+        )?)
+    }
+
+    fn verify_packet_receipt_absence(
+        &self,
+        _ctx: &dyn ChannelReader,
+        client_state: &Self::ClientState,
+        height: Height,
+        prefix: &CommitmentPrefix,
+        proof: &CommitmentProofBytes,
+        _root: &CommitmentRoot,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        seq: &Sequence,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let path = Path::Receipts {
+            port_id: port_id.clone(),
+            channel_id: channel_id.clone(),
+            sequence: *seq,
+        };
+        let data = proof::encode(&PacketReceiptAbsenceData {
+            path: signed_path(prefix, path)?,
+        });
+
+        Ok(verify_signed_data(
+            client_state,
+            height,
+            proof,
+            DataType::PacketReceiptAbsence,
+            data,
+        )?)
+    }
+
+    fn verify_upgrade_and_update_state(
+        &self,
+        _ctx: &dyn ClientReader,
+        _client_id: &ClientId,
+        _client_state: &Self::ClientState,
+        _upgraded_client_state: &Self::ClientState,
+        _upgraded_consensus_state: &Self::ConsensusState,
+        _proof_upgrade_client: MerkleProof,
+        _proof_upgrade_consensus_state: MerkleProof,
+    ) -> Result<(Self::ClientState, Self::ConsensusState), Box<dyn std::error::Error>> {
+        Err(Kind::VerificationFailure
+            .context("solo machine clients cannot be upgraded")
+            .into())
+    }
+
+    fn check_substitute_and_update_state(
+        &self,
+        _ctx: &dyn ClientReader,
+        _subject_client_id: &ClientId,
+        subject_client_state: &Self::ClientState,
+        substitute_client_state: &Self::ClientState,
+    ) -> Result<Self::ClientState, Box<dyn std::error::Error>> {
+        if !subject_client_state.allow_update_after_proposal {
+            return Err(Error::from(Kind::UpdateAfterProposalNotAllowed).into());
+        }
+
+        // The subject takes over the public key and sequence of the substitute.
+        Ok(ClientState {
+            sequence: substitute_client_state.sequence,
+            frozen_sequence: 0,
+            consensus_state: substitute_client_state.consensus_state.clone(),
+            allow_update_after_proposal: subject_client_state.allow_update_after_proposal,
+        })
+    }
+
+    fn client_state_after_verification(
+        &self,
+        client_state: &Self::ClientState,
+        _height: Height,
+        proof: &CommitmentProofBytes,
+    ) -> Result<Option<Self::ClientState>, Box<dyn std::error::Error>> {
+        // Each signature is valid at a single sequence, and the signatures of the solo machine
+        // may not be older than the last one the client verified.
+        let signature = proof::decode_proof(proof)?;
+        let mut new_client_state = client_state.clone();
+        new_client_state.sequence += 1;
+        new_client_state.consensus_state.timestamp = signature.timestamp;
+
+        Ok(Some(new_client_state))
+    }
+}
+
+/// Verifies that `proof` is a signature of `data` by the solo machine, at the current sequence of
+/// `client_state`. The proof height is the sequence at which the solo machine signed.
+///
+/// Once the proof is verified, the handler stores the client state returned by
+/// `client_state_after_verification`, which advances the sequence of the client.
+fn verify_signed_data(
+    client_state: &ClientState,
+    height: Height,
+    proof: &CommitmentProofBytes,
+    data_type: DataType,
+    data: Vec<u8>,
+) -> Result<(), Error> {
+    if height.revision_number != 0 {
+        return Err(Kind::InvalidRevisionNumber(height).into());
+    }
+    if client_state.is_frozen() {
+        return Err(Kind::FrozenClient.into());
+    }
+    if height.revision_height != client_state.sequence {
+        return Err(Kind::SequenceMismatch(client_state.sequence, height.revision_height).into());
+    }
+
+    let signature = proof::decode_proof(proof)?;
+    let consensus_state = &client_state.consensus_state;
+    if signature.timestamp < consensus_state.timestamp {
+        return Err(Kind::TimestampTooOld(consensus_state.timestamp, signature.timestamp).into());
+    }
+
+    let sign_bytes = proof::sign_bytes(
+        client_state.sequence,
+        signature.timestamp,
+        &consensus_state.diversifier,
+        data_type,
+        data,
+    );
+
+    proof::verify_signature(
+        &consensus_state.public_key,
+        &sign_bytes,
+        &signature.signature_data,
+    )
+}
+
+/// Returns the full path of a value, as included in the data a solo machine signs over.
+pub(crate) fn signed_path(prefix: &CommitmentPrefix, path: Path) -> Result<Vec<u8>, Error> {
+    let merkle_path = apply_prefix(prefix, vec![path.to_string()])
+        .map_err(|e| Kind::InvalidPrefix.context(e.to_string()))?;
+
+    Ok(merkle_path
+        .key_path
+        .iter()
+        .map(|key| format!("/{}", key))
+        .collect::<String>()
+        .into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use test_env_log::test;
+
+    use ibc_proto::ibc::lightclients::solomachine::v1::DataType;
+
+    use crate::ics02_client::client_def::ClientDef;
+    use crate::ics04_channel::packet::Sequence;
+    use crate::ics06_solomachine::client_def::SoloMachineClient;
+    use crate::ics06_solomachine::client_state::ClientState;
+    use crate::ics06_solomachine::error::{Error, Kind};
+    use crate::ics06_solomachine::misbehaviour::{Misbehaviour, SignatureAndData};
+    use crate::ics06_solomachine::test_util::{
+        get_dummy_header, get_dummy_keypair, get_dummy_proof, get_dummy_solomachine_client_state,
+        sign, DUMMY_TIMESTAMP,
+    };
+    use crate::ics23_commitment::commitment::{CommitmentPrefix, CommitmentRoot};
+    use crate::ics24_host::identifier::{ChannelId, ClientId, PortId};
+    use crate::mock::context::MockContext;
+    use crate::Height;
+
+    fn error_kind(err: Box<dyn std::error::Error>) -> Kind {
+        err.downcast_ref::<Error>()
+            .expect("expected a solo machine client error")
+            .kind()
+            .clone()
+    }
+
+    /// Returns a proof, by the dummy solo machine at `sequence`, of the next sequence to be
+    /// received on the default port and channel.
+    fn next_sequence_recv_proof(sequence: u64, next_seq_recv: u64) -> Vec<u8> {
+        use ibc_proto::ibc::lightclients::solomachine::v1::NextSequenceRecvData;
+
+        use crate::ics06_solomachine::client_def::signed_path;
+        use crate::ics06_solomachine::proof::encode;
+        use crate::ics24_host::Path;
+
+        let path = Path::SeqRecvs(PortId::default(), ChannelId::default());
+        let data = encode(&NextSequenceRecvData {
+            path: signed_path(&CommitmentPrefix::from(b"ibc".to_vec()), path).unwrap(),
+            next_seq_recv,
+        });
+
+        get_dummy_proof(sequence, DataType::NextSequenceRecv, data)
+    }
+
+    fn verify_next_seq_recv(
+        client_state: &ClientState,
+        height: Height,
+        proof: Vec<u8>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        SoloMachineClient.verify_next_sequence_recv(
+            &MockContext::default(),
+            client_state,
+            height,
+            &CommitmentPrefix::from(b"ibc".to_vec()),
+            &proof.into(),
+            &CommitmentRoot::from(vec![]),
+            &PortId::default(),
+            &ChannelId::default(),
+            &Sequence::from(7),
+        )
+    }
+
+    #[test]
+    fn update_rotates_public_key() {
+        let ctx = MockContext::default();
+        let client_state = get_dummy_solomachine_client_state(1);
+
+        let header = get_dummy_header(1, DUMMY_TIMESTAMP + 1, "new diversifier");
+        let (new_client_state, new_consensus_state) = SoloMachineClient
+            .check_header_and_update_state(&ctx, ClientId::default(), client_state, header)
+            .unwrap();
+
+        assert_eq!(new_client_state.sequence, 2);
+        assert_eq!(new_consensus_state.diversifier, "new diversifier");
+        assert_eq!(new_consensus_state.timestamp, DUMMY_TIMESTAMP + 1);
+        assert_eq!(new_client_state.consensus_state, new_consensus_state);
+    }
+
+    #[test]
+    fn update_rejects_invalid_headers() {
+        let ctx = MockContext::default();
+
+        // Wrong sequence.
+        let header = get_dummy_header(2, DUMMY_TIMESTAMP, "diversifier");
+        let err = SoloMachineClient
+            .check_header_and_update_state(
+                &ctx,
+                ClientId::default(),
+                get_dummy_solomachine_client_state(1),
+                header,
+            )
+            .unwrap_err();
+        assert!(matches!(error_kind(err), Kind::SequenceMismatch(1, 2)));
+
+        // Timestamp lower than the consensus state timestamp.
+        let header = get_dummy_header(1, DUMMY_TIMESTAMP - 1, "diversifier");
+        let err = SoloMachineClient
+            .check_header_and_update_state(
+                &ctx,
+                ClientId::default(),
+                get_dummy_solomachine_client_state(1),
+                header,
+            )
+            .unwrap_err();
+        assert!(matches!(error_kind(err), Kind::TimestampTooOld(_, _)));
+
+        // Signature by another key.
+        let mut header = get_dummy_header(1, DUMMY_TIMESTAMP, "diversifier");
+        header.new_diversifier = "tampered".to_string();
+        let err = SoloMachineClient
+            .check_header_and_update_state(
+                &ctx,
+                ClientId::default(),
+                get_dummy_solomachine_client_state(1),
+                header,
+            )
+            .unwrap_err();
+        assert!(matches!(
+            error_kind(err),
+            Kind::SignatureVerificationFailure
+        ));
+    }
+
+    #[test]
+    fn verify_signed_next_sequence_recv() {
+        let client_state = get_dummy_solomachine_client_state(5);
+        let height = Height::new(0, 5);
+
+        assert!(
+            verify_next_seq_recv(&client_state, height, next_sequence_recv_proof(5, 7)).is_ok()
+        );
+
+        // The solo machine signed another value.
+        let err = verify_next_seq_recv(&client_state, height, next_sequence_recv_proof(5, 8))
+            .unwrap_err();
+        assert!(matches!(
+            error_kind(err),
+            Kind::SignatureVerificationFailure
+        ));
+
+        // The proof height must be the current sequence of the client.
+        let err = verify_next_seq_recv(
+            &client_state,
+            Height::new(0, 4),
+            next_sequence_recv_proof(4, 7),
+        )
+        .unwrap_err();
+        assert!(matches!(error_kind(err), Kind::SequenceMismatch(5, 4)));
+
+        // Frozen clients cannot verify proofs.
+        let frozen_state = client_state.with_frozen_sequence(3);
+        let err = verify_next_seq_recv(&frozen_state, height, next_sequence_recv_proof(5, 7))
+            .unwrap_err();
+        assert!(matches!(error_kind(err), Kind::FrozenClient));
+    }
+
+    #[test]
+    fn verification_advances_sequence() {
+        let client_state = get_dummy_solomachine_client_state(5);
+
+        let height = Height::new(0, 5);
+        let proof = next_sequence_recv_proof(5, 7);
+        verify_next_seq_recv(&client_state, height, proof.clone()).unwrap();
+        let client_state = SoloMachineClient
+            .client_state_after_verification(&client_state, height, &proof.clone().into())
+            .unwrap()
+            .unwrap();
+        assert_eq!(client_state.sequence, 6);
+        assert_eq!(client_state.consensus_state.timestamp, DUMMY_TIMESTAMP);
+
+        // The signature cannot be replayed.
+        let err = verify_next_seq_recv(&client_state, height, proof).unwrap_err();
+        assert!(matches!(error_kind(err), Kind::SequenceMismatch(6, 5)));
+
+        // The next proof is signed at the next sequence.
+        let height = Height::new(0, 6);
+        let proof = next_sequence_recv_proof(6, 7);
+        verify_next_seq_recv(&client_state, height, proof.clone()).unwrap();
+        let client_state = SoloMachineClient
+            .client_state_after_verification(&client_state, height, &proof.into())
+            .unwrap()
+            .unwrap();
+        assert_eq!(client_state.sequence, 7);
+    }
+
+    #[test]
+    fn misbehaviour_freezes_client() {
+        let ctx = MockContext::default();
+        let keypair = get_dummy_keypair();
+
+        let signature_and_data = |data: &[u8]| {
+            let sign_bytes = crate::ics06_solomachine::proof::sign_bytes(
+                3,
+                DUMMY_TIMESTAMP,
+                "diversifier",
+                DataType::PacketCommitment,
+                data.to_vec(),
+            );
+            SignatureAndData {
+                signature: sign(&keypair, &sign_bytes),
+                data_type: DataType::PacketCommitment,
+                data: data.to_vec(),
+                timestamp: DUMMY_TIMESTAMP,
+            }
+        };
+
+        let misbehaviour = Misbehaviour {
+            client_id: ClientId::default(),
+            sequence: 3,
+            signature_one: signature_and_data(b"data one"),
+            signature_two: signature_and_data(b"data two"),
+        };
+
+        let client_state = SoloMachineClient
+            .check_misbehaviour_and_update_state(
+                &ctx,
+                ClientId::default(),
+                get_dummy_solomachine_client_state(5),
+                misbehaviour.clone(),
+            )
+            .unwrap();
+        assert_eq!(client_state.frozen_sequence, 3);
+
+        // The same signature twice is not misbehaviour.
+        let mut misbehaviour = misbehaviour;
+        misbehaviour.signature_two = misbehaviour.signature_one.clone();
+        let err = SoloMachineClient
+            .check_misbehaviour_and_update_state(
+                &ctx,
+                ClientId::default(),
+                get_dummy_solomachine_client_state(5),
+                misbehaviour,
+            )
+            .unwrap_err();
+        assert!(matches!(error_kind(err), Kind::MisbehaviourSignaturesEqual));
+    }
+}
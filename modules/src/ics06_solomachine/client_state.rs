@@ -0,0 +1,113 @@
+use std::convert::{TryFrom, TryInto};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use tendermint_proto::Protobuf;
+
+use ibc_proto::ibc::lightclients::solomachine::v1::ClientState as RawClientState;
+
+use crate::ics02_client::client_state::AnyClientState;
+use crate::ics02_client::client_type::ClientType;
+use crate::ics06_solomachine::consensus_state::ConsensusState;
+use crate::ics06_solomachine::error::{Error, Kind};
+use crate::ics24_host::identifier::ChainId;
+use crate::Height;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClientState {
+    /// The sequence of the next header or proof the solo machine is expected to sign.
+    pub sequence: u64,
+    /// The sequence at which the client was frozen due to misbehaviour, or 0 if not frozen.
+    pub frozen_sequence: u64,
+    pub consensus_state: ConsensusState,
+    pub allow_update_after_proposal: bool,
+}
+
+impl ClientState {
+    pub fn new(sequence: u64, consensus_state: ConsensusState) -> Self {
+        Self {
+            sequence,
+            frozen_sequence: 0,
+            consensus_state,
+            allow_update_after_proposal: false,
+        }
+    }
+
+    /// Solo machines have a single revision, hence their heights are of the form
+    /// `0-{sequence}`.
+    pub fn latest_height(&self) -> Height {
+        Height::new(0, self.sequence)
+    }
+
+    /// Returns a copy of this client state, frozen at sequence `sequence`.
+    pub fn with_frozen_sequence(self, sequence: u64) -> Self {
+        ClientState {
+            frozen_sequence: sequence,
+            ..self
+        }
+    }
+}
+
+impl crate::ics02_client::client_state::ClientState for ClientState {
+    /// A solo machine is not a chain, the closest notion of an identifier is its diversifier.
+    fn chain_id(&self) -> ChainId {
+        ChainId::from_str(&self.consensus_state.diversifier).unwrap()
+    }
+
+    fn client_type(&self) -> ClientType {
+        ClientType::SoloMachine
+    }
+
+    fn latest_height(&self) -> Height {
+        self.latest_height()
+    }
+
+    fn is_frozen(&self) -> bool {
+        // If 'frozen_sequence' is set to a non-zero value, then the client state is frozen.
+        self.frozen_sequence != 0
+    }
+
+    fn wrap_any(self) -> AnyClientState {
+        AnyClientState::SoloMachine(self)
+    }
+}
+
+/// Client states are generated unfrozen, at sequences close to the heights of the other clients.
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for ClientState {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(ClientState {
+            allow_update_after_proposal: u.arbitrary()?,
+            ..Self::new(u.int_in_range(1..=32)?, u.arbitrary()?)
+        })
+    }
+}
+
+impl Protobuf<RawClientState> for ClientState {}
+
+impl TryFrom<RawClientState> for ClientState {
+    type Error = Error;
+
+    fn try_from(raw: RawClientState) -> Result<Self, Self::Error> {
+        Ok(Self {
+            sequence: raw.sequence,
+            frozen_sequence: raw.frozen_sequence,
+            consensus_state: raw
+                .consensus_state
+                .ok_or_else(|| Kind::InvalidRawClientState.context("missing consensus state"))?
+                .try_into()?,
+            allow_update_after_proposal: raw.allow_update_after_proposal,
+        })
+    }
+}
+
+impl From<ClientState> for RawClientState {
+    fn from(value: ClientState) -> Self {
+        RawClientState {
+            sequence: value.sequence,
+            frozen_sequence: value.frozen_sequence,
+            consensus_state: Some(value.consensus_state.into()),
+            allow_update_after_proposal: value.allow_update_after_proposal,
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use std::convert::{TryFrom, TryInto};
+
+use serde::{Deserialize, Serialize};
+use tendermint_proto::Protobuf;
+
+use ibc_proto::ibc::lightclients::solomachine::v1::ConsensusState as RawConsensusState;
+
+use crate::ics02_client::client_consensus::AnyConsensusState;
+use crate::ics02_client::client_type::ClientType;
+use crate::ics06_solomachine::error::{Error, Kind};
+use crate::ics06_solomachine::public_key::PublicKey;
+use crate::ics23_commitment::commitment::CommitmentRoot;
+use crate::timestamp::Timestamp;
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ConsensusState {
+    /// The public key the solo machine currently signs with.
+    pub public_key: PublicKey,
+    /// Allows the same public key to be re-used across different solo machine clients.
+    pub diversifier: String,
+    /// The timestamp (in nanoseconds) of the last update or proof of the solo machine.
+    pub timestamp: u64,
+    /// A solo machine does not commit to its state with a root, its proofs are signatures
+    /// instead. This is always empty.
+    #[serde(skip, default = "empty_root")]
+    root: CommitmentRoot,
+}
+
+fn empty_root() -> CommitmentRoot {
+    CommitmentRoot::from(vec![])
+}
+
+impl ConsensusState {
+    pub fn new(public_key: PublicKey, diversifier: String, timestamp: u64) -> Self {
+        Self {
+            public_key,
+            diversifier,
+            timestamp,
+            root: empty_root(),
+        }
+    }
+
+    pub fn timestamp(&self) -> Timestamp {
+        Timestamp::from_nanoseconds(self.timestamp).unwrap_or_else(|_| Timestamp::none())
+    }
+}
+
+impl crate::ics02_client::client_consensus::ConsensusState for ConsensusState {
+    fn client_type(&self) -> ClientType {
+        ClientType::SoloMachine
+    }
+
+    fn root(&self) -> &CommitmentRoot {
+        &self.root
+    }
+
+    fn validate_basic(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if self.timestamp == 0 {
+            return Err(Kind::InvalidRawConsensusState
+                .context("timestamp cannot be 0")
+                .into());
+        }
+        if self.diversifier.trim().is_empty() && !self.diversifier.is_empty() {
+            return Err(Kind::InvalidRawConsensusState
+                .context("diversifier cannot contain only spaces")
+                .into());
+        }
+        Ok(())
+    }
+
+    fn wrap_any(self) -> AnyConsensusState {
+        AnyConsensusState::SoloMachine(self)
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for ConsensusState {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self::new(u.arbitrary()?, u.arbitrary()?, u.arbitrary()?))
+    }
+}
+
+impl Protobuf<RawConsensusState> for ConsensusState {}
+
+impl TryFrom<RawConsensusState> for ConsensusState {
+    type Error = Error;
+
+    fn try_from(raw: RawConsensusState) -> Result<Self, Self::Error> {
+        let public_key = raw
+            .public_key
+            .ok_or_else(|| Kind::InvalidRawConsensusState.context("missing public key"))?
+            .try_into()?;
+
+        Ok(Self::new(public_key, raw.diversifier, raw.timestamp))
+    }
+}
+
+impl From<ConsensusState> for RawConsensusState {
+    fn from(value: ConsensusState) -> Self {
+        RawConsensusState {
+            public_key: Some(value.public_key.into()),
+            diversifier: value.diversifier,
+            timestamp: value.timestamp,
+        }
+    }
+}
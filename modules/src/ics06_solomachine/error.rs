@@ -0,0 +1,69 @@
+use anomaly::{BoxError, Context};
+use thiserror::Error;
+
+use crate::Height;
+
+pub type Error = anomaly::Error<Kind>;
+
+#[derive(Clone, Debug, Error)]
+pub enum Kind {
+    #[error("invalid raw client state")]
+    InvalidRawClientState,
+
+    #[error("invalid raw client consensus state")]
+    InvalidRawConsensusState,
+
+    #[error("invalid raw header")]
+    InvalidRawHeader,
+
+    #[error("invalid raw misbehaviour")]
+    InvalidRawMisbehaviour,
+
+    #[error("invalid public key")]
+    InvalidPublicKey,
+
+    #[error("unsupported public key type: {0}")]
+    UnsupportedPublicKeyType(String),
+
+    #[error("invalid signature data")]
+    InvalidSignatureData,
+
+    #[error("signature verification failed")]
+    SignatureVerificationFailure,
+
+    #[error("the client is frozen")]
+    FrozenClient,
+
+    #[error("the sequence {1} does not match the client sequence {0}")]
+    SequenceMismatch(u64, u64),
+
+    #[error("the timestamp {1} is lower than the consensus state timestamp {0}")]
+    TimestampTooOld(u64, u64),
+
+    #[error("solo machine heights must have revision number 0, got height {0}")]
+    InvalidRevisionNumber(Height),
+
+    #[error("invalid commitment prefix")]
+    InvalidPrefix,
+
+    #[error("invalid proof")]
+    InvalidProof,
+
+    #[error("the misbehaviour signatures must be different")]
+    MisbehaviourSignaturesEqual,
+
+    #[error("the misbehaviour signed data must be different")]
+    MisbehaviourDataEqual,
+
+    #[error("proof verification failed")]
+    VerificationFailure,
+
+    #[error("the client cannot be recovered, as it does not allow updates after a proposal")]
+    UpdateAfterProposalNotAllowed,
+}
+
+impl Kind {
+    pub fn context(self, source: impl Into<BoxError>) -> Context<Self> {
+        Context::new(self, Some(source.into()))
+    }
+}
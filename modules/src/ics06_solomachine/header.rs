@@ -0,0 +1,92 @@
+use std::convert::{TryFrom, TryInto};
+
+use serde_derive::{Deserialize, Serialize};
+use tendermint_proto::Protobuf;
+
+use ibc_proto::ibc::lightclients::solomachine::v1::Header as RawHeader;
+
+use crate::ics02_client::client_type::ClientType;
+use crate::ics02_client::header::AnyHeader;
+use crate::ics06_solomachine::error::{Error, Kind};
+use crate::ics06_solomachine::public_key::PublicKey;
+use crate::Height;
+
+/// A solo machine header, which rotates the public key (and diversifier) of the solo machine.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct Header {
+    /// The sequence at which the public key is updated.
+    pub sequence: u64,
+    pub timestamp: u64,
+    /// The proto encoded signature data, signed with the current public key.
+    pub signature: Vec<u8>,
+    pub new_public_key: PublicKey,
+    pub new_diversifier: String,
+}
+
+impl Header {
+    pub fn height(&self) -> Height {
+        Height::new(0, self.sequence)
+    }
+}
+
+impl crate::ics02_client::header::Header for Header {
+    fn client_type(&self) -> ClientType {
+        ClientType::SoloMachine
+    }
+
+    fn height(&self) -> Height {
+        self.height()
+    }
+
+    fn wrap_any(self) -> AnyHeader {
+        AnyHeader::SoloMachine(self)
+    }
+}
+
+impl Protobuf<RawHeader> for Header {}
+
+impl TryFrom<RawHeader> for Header {
+    type Error = Error;
+
+    fn try_from(raw: RawHeader) -> Result<Self, Self::Error> {
+        if raw.sequence == 0 {
+            return Err(Kind::InvalidRawHeader
+                .context("sequence cannot be 0")
+                .into());
+        }
+        if raw.timestamp == 0 {
+            return Err(Kind::InvalidRawHeader
+                .context("timestamp cannot be 0")
+                .into());
+        }
+        if raw.signature.is_empty() {
+            return Err(Kind::InvalidRawHeader
+                .context("signature cannot be empty")
+                .into());
+        }
+
+        Ok(Self {
+            sequence: raw.sequence,
+            timestamp: raw.timestamp,
+            signature: raw.signature,
+            new_public_key: raw
+                .new_public_key
+                .ok_or_else(|| Kind::InvalidRawHeader.context("missing new public key"))?
+                .try_into()?,
+            new_diversifier: raw.new_diversifier,
+        })
+    }
+}
+
+impl From<Header> for RawHeader {
+    fn from(value: Header) -> Self {
+        RawHeader {
+            sequence: value.sequence,
+            timestamp: value.timestamp,
+            signature: value.signature,
+            new_public_key: Some(value.new_public_key.into()),
+            new_diversifier: value.new_diversifier,
+        }
+    }
+}
@@ -0,0 +1,13 @@
+//! ICS 06: Solo Machine Client
+
+pub mod client_def;
+pub mod client_state;
+pub mod consensus_state;
+pub mod error;
+pub mod header;
+pub mod misbehaviour;
+pub mod proof;
+pub mod public_key;
+
+#[cfg(any(test, feature = "mocks"))]
+pub mod test_util;
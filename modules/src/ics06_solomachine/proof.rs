@@ -0,0 +1,80 @@
+//! The messages a solo machine signs over, and the verification of its signatures. These follow
+//! the encoding of the `06-solomachine` client of the Cosmos SDK.
+
+use ibc_proto::cosmos::tx::signing::v1beta1::signature_descriptor::{
+    data::Sum, Data as SignatureData,
+};
+use ibc_proto::ibc::lightclients::solomachine::v1::{
+    DataType, HeaderData, SignBytes, TimestampedSignatureData,
+};
+
+use crate::ics06_solomachine::error::{Error, Kind};
+use crate::ics06_solomachine::public_key::PublicKey;
+use crate::ics23_commitment::commitment::CommitmentProofBytes;
+
+/// Returns the bytes a solo machine signs over to produce a signature of `data` at `sequence`.
+pub fn sign_bytes(
+    sequence: u64,
+    timestamp: u64,
+    diversifier: &str,
+    data_type: DataType,
+    data: Vec<u8>,
+) -> Vec<u8> {
+    encode(&SignBytes {
+        sequence,
+        timestamp,
+        diversifier: diversifier.to_string(),
+        data_type: data_type as i32,
+        data,
+    })
+}
+
+/// Returns the data a solo machine signs over to rotate its public key and diversifier.
+pub fn header_data(new_public_key: &PublicKey, new_diversifier: &str) -> Vec<u8> {
+    encode(&HeaderData {
+        new_pub_key: Some(new_public_key.clone().into()),
+        new_diversifier: new_diversifier.to_string(),
+    })
+}
+
+/// Verifies that the proto encoded `signature_data` is a signature of `sign_bytes` by
+/// `public_key`. Only signatures by a single signer are supported.
+pub fn verify_signature(
+    public_key: &PublicKey,
+    sign_bytes: &[u8],
+    signature_data: &[u8],
+) -> Result<(), Error> {
+    let signature_data = <SignatureData as prost::Message>::decode(signature_data)
+        .map_err(|e| Kind::InvalidSignatureData.context(e))?;
+
+    match signature_data.sum {
+        Some(Sum::Single(single)) => public_key.verify_signature(sign_bytes, &single.signature),
+        Some(Sum::Multi(_)) => Err(Kind::InvalidSignatureData
+            .context("multisig signatures are not supported")
+            .into()),
+        None => Err(Kind::InvalidSignatureData
+            .context("missing signature")
+            .into()),
+    }
+}
+
+/// Decodes the proof of a solo machine, which is a timestamped signature.
+pub fn decode_proof(proof: &CommitmentProofBytes) -> Result<TimestampedSignatureData, Error> {
+    let bytes: Vec<u8> = proof.clone().into();
+    let proof = <TimestampedSignatureData as prost::Message>::decode(bytes.as_slice())
+        .map_err(|e| Kind::InvalidProof.context(e))?;
+
+    if proof.signature_data.is_empty() {
+        return Err(Kind::InvalidProof.context("empty signature data").into());
+    }
+
+    Ok(proof)
+}
+
+pub(crate) fn encode<M: prost::Message>(message: &M) -> Vec<u8> {
+    let mut buf = Vec::new();
+    message
+        .encode(&mut buf)
+        .expect("encoding into a vector cannot fail");
+    buf
+}
@@ -0,0 +1,143 @@
+use std::convert::TryFrom;
+
+use prost_types::Any;
+use serde_derive::{Deserialize, Serialize};
+use tendermint_proto::Protobuf;
+
+use crate::ics06_solomachine::error::{Error, Kind};
+
+pub const SECP256K1_PUB_KEY_TYPE_URL: &str = "/cosmos.crypto.secp256k1.PubKey";
+pub const ED25519_PUB_KEY_TYPE_URL: &str = "/cosmos.crypto.ed25519.PubKey";
+
+/// The public key of a solo machine, as encoded by the Cosmos SDK.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum PublicKey {
+    /// A SEC1 encoded (compressed) secp256k1 public key.
+    Secp256k1(Vec<u8>),
+
+    /// A raw ed25519 public key.
+    Ed25519(Vec<u8>),
+}
+
+impl PublicKey {
+    /// Verifies that `signature` is a valid signature of `message` with this public key.
+    pub fn verify_signature(&self, message: &[u8], signature: &[u8]) -> Result<(), Error> {
+        match self {
+            PublicKey::Secp256k1(key) => {
+                let key = k256::ecdsa::VerifyingKey::from_sec1_bytes(key)
+                    .map_err(|e| Kind::InvalidPublicKey.context(e.to_string()))?;
+                let signature = k256::ecdsa::Signature::try_from(signature)
+                    .map_err(|e| Kind::InvalidSignatureData.context(e.to_string()))?;
+
+                k256::ecdsa::signature::Verifier::verify(&key, message, &signature)
+                    .map_err(|e| Kind::SignatureVerificationFailure.context(e.to_string()))?;
+            }
+            PublicKey::Ed25519(key) => {
+                let key = ed25519_dalek::PublicKey::from_bytes(key)
+                    .map_err(|e| Kind::InvalidPublicKey.context(e.to_string()))?;
+                let signature = ed25519_dalek::Signature::try_from(signature)
+                    .map_err(|e| Kind::InvalidSignatureData.context(e.to_string()))?;
+
+                ed25519_dalek::Verifier::verify(&key, message, &signature)
+                    .map_err(|e| Kind::SignatureVerificationFailure.context(e.to_string()))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The `PubKey` message shared by the secp256k1 and ed25519 keys of the Cosmos SDK.
+#[derive(Clone, PartialEq, prost::Message)]
+struct RawPubKey {
+    #[prost(bytes = "vec", tag = "1")]
+    key: Vec<u8>,
+}
+
+/// Public keys are derived from arbitrary ed25519 secret keys, so that they are valid.
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for PublicKey {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let secret: [u8; 32] = u.arbitrary()?;
+        let secret = ed25519_dalek::SecretKey::from_bytes(&secret)
+            .map_err(|_| arbitrary::Error::IncorrectFormat)?;
+        let public = ed25519_dalek::PublicKey::from(&secret);
+        Ok(PublicKey::Ed25519(public.to_bytes().to_vec()))
+    }
+}
+
+impl Protobuf<Any> for PublicKey {}
+
+impl TryFrom<Any> for PublicKey {
+    type Error = Error;
+
+    fn try_from(raw: Any) -> Result<Self, Self::Error> {
+        let key = <RawPubKey as prost::Message>::decode(raw.value.as_slice())
+            .map_err(|e| Kind::InvalidPublicKey.context(e))?
+            .key;
+
+        match raw.type_url.as_str() {
+            SECP256K1_PUB_KEY_TYPE_URL => Ok(PublicKey::Secp256k1(key)),
+            ED25519_PUB_KEY_TYPE_URL => Ok(PublicKey::Ed25519(key)),
+            _ => Err(Kind::UnsupportedPublicKeyType(raw.type_url).into()),
+        }
+    }
+}
+
+impl From<PublicKey> for Any {
+    fn from(value: PublicKey) -> Self {
+        let (type_url, key) = match value {
+            PublicKey::Secp256k1(key) => (SECP256K1_PUB_KEY_TYPE_URL, key),
+            PublicKey::Ed25519(key) => (ED25519_PUB_KEY_TYPE_URL, key),
+        };
+
+        let mut value = Vec::new();
+        prost::Message::encode(&RawPubKey { key }, &mut value)
+            .expect("encoding to `Any` from `PublicKey`");
+
+        Any {
+            type_url: type_url.to_string(),
+            value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use ed25519_dalek::Signer;
+    use prost_types::Any;
+    use test_env_log::test;
+
+    use crate::ics06_solomachine::error::Kind;
+    use crate::ics06_solomachine::public_key::PublicKey;
+    use crate::ics06_solomachine::test_util::get_dummy_keypair;
+
+    #[test]
+    fn public_key_any_roundtrip() {
+        let keys = [
+            PublicKey::Secp256k1(vec![2; 33]),
+            PublicKey::Ed25519(get_dummy_keypair().public.to_bytes().to_vec()),
+        ];
+
+        for key in keys.iter() {
+            let any = Any::from(key.clone());
+            assert_eq!(&PublicKey::try_from(any).unwrap(), key);
+        }
+    }
+
+    #[test]
+    fn verify_ed25519_signature() {
+        let keypair = get_dummy_keypair();
+        let public_key = PublicKey::Ed25519(keypair.public.to_bytes().to_vec());
+        let signature = keypair.sign(b"message").to_bytes();
+
+        assert!(public_key.verify_signature(b"message", &signature).is_ok());
+
+        let err = public_key
+            .verify_signature(b"other message", &signature)
+            .unwrap_err();
+        assert!(matches!(err.kind(), Kind::SignatureVerificationFailure));
+    }
+}
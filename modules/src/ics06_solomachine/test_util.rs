@@ -0,0 +1,81 @@
+use ed25519_dalek::{Keypair, PublicKey as Ed25519PublicKey, SecretKey, Signer};
+use ibc_proto::cosmos::tx::signing::v1beta1::signature_descriptor::{
+    data::{Single, Sum},
+    Data as SignatureData,
+};
+use ibc_proto::cosmos::tx::signing::v1beta1::SignMode;
+use ibc_proto::ibc::lightclients::solomachine::v1::{DataType, TimestampedSignatureData};
+
+use crate::ics06_solomachine::client_state::ClientState;
+use crate::ics06_solomachine::consensus_state::ConsensusState;
+use crate::ics06_solomachine::header::Header;
+use crate::ics06_solomachine::proof::{encode, header_data, sign_bytes};
+use crate::ics06_solomachine::public_key::PublicKey;
+
+/// The timestamp (in nanoseconds since the epoch) of the dummy consensus state.
+pub const DUMMY_TIMESTAMP: u64 = 1_600_000_000_000_000_000;
+
+/// Returns the (deterministic) ed25519 key pair of the dummy solo machine.
+pub fn get_dummy_keypair() -> Keypair {
+    let secret = SecretKey::from_bytes(&[7u8; 32]).unwrap();
+    let public = Ed25519PublicKey::from(&secret);
+    Keypair { secret, public }
+}
+
+pub fn get_dummy_public_key() -> PublicKey {
+    PublicKey::Ed25519(get_dummy_keypair().public.to_bytes().to_vec())
+}
+
+pub fn get_dummy_solomachine_consensus_state() -> ConsensusState {
+    ConsensusState::new(
+        get_dummy_public_key(),
+        "diversifier".to_string(),
+        DUMMY_TIMESTAMP,
+    )
+}
+
+pub fn get_dummy_solomachine_client_state(sequence: u64) -> ClientState {
+    ClientState::new(sequence, get_dummy_solomachine_consensus_state())
+}
+
+/// Signs `sign_bytes` with `keypair`, and returns the proto encoded signature data of a single
+/// signer, as produced by a solo machine.
+pub fn sign(keypair: &Keypair, sign_bytes: &[u8]) -> Vec<u8> {
+    encode(&SignatureData {
+        sum: Some(Sum::Single(Single {
+            mode: SignMode::Direct as i32,
+            signature: keypair.sign(sign_bytes).to_bytes().to_vec(),
+        })),
+    })
+}
+
+/// Returns a proof of `data`, as signed by the dummy solo machine at `sequence`.
+pub fn get_dummy_proof(sequence: u64, data_type: DataType, data: Vec<u8>) -> Vec<u8> {
+    let sign_bytes = sign_bytes(sequence, DUMMY_TIMESTAMP, "diversifier", data_type, data);
+
+    encode(&TimestampedSignatureData {
+        signature_data: sign(&get_dummy_keypair(), &sign_bytes),
+        timestamp: DUMMY_TIMESTAMP,
+    })
+}
+
+/// Returns a header of the dummy solo machine at `sequence`, which keeps the dummy public key
+/// and switches to `new_diversifier`.
+pub fn get_dummy_header(sequence: u64, timestamp: u64, new_diversifier: &str) -> Header {
+    let new_public_key = get_dummy_public_key();
+    let sign_bytes = sign_bytes(
+        sequence,
+        timestamp,
+        "diversifier",
+        DataType::Header,
+        header_data(&new_public_key, new_diversifier),
+    );
+
+    Header {
+        sequence,
+        timestamp,
+        signature: sign(&get_dummy_keypair(), &sign_bytes),
+        new_public_key,
+        new_diversifier: new_diversifier.to_string(),
+    }
+}
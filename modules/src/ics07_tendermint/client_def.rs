@@ -1,19 +1,40 @@
-use ibc_proto::ibc::core::commitment::v1::MerkleProof;
+use std::convert::TryFrom;
+
+use ibc_proto::ibc::core::commitment::v1::MerkleProof as RawMerkleProof;
+use tendermint_light_client::operations::commit_validator::ProdCommitValidator;
+use tendermint_light_client::operations::hasher::{Hasher, ProdHasher};
+use tendermint_light_client::operations::voting_power::ProdVotingPowerCalculator;
+use tendermint_light_client::predicates::{ProdPredicates, VerificationPredicates};
+use tendermint_proto::Protobuf;
 
 use crate::ics02_client::client_consensus::AnyConsensusState;
 use crate::ics02_client::client_def::ClientDef;
-use crate::ics02_client::client_state::AnyClientState;
+use crate::ics02_client::client_state::{AnyClientState, Status};
+use crate::ics02_client::context::ClientReader;
+use crate::ics02_client::misbehaviour::Misbehaviour as _;
 use crate::ics03_connection::connection::ConnectionEnd;
+use crate::ics03_connection::context::ConnectionReader;
 use crate::ics04_channel::channel::ChannelEnd;
+use crate::ics04_channel::commitment::{AcknowledgementCommitment, PacketCommitment};
+use crate::ics04_channel::context::ChannelReader;
 use crate::ics04_channel::packet::Sequence;
 use crate::ics07_tendermint::client_state::ClientState;
 use crate::ics07_tendermint::consensus_state::ConsensusState;
+use crate::ics07_tendermint::error::{Error, Kind};
 use crate::ics07_tendermint::header::Header;
+use crate::ics07_tendermint::misbehaviour::Misbehaviour;
 use crate::ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes, CommitmentRoot};
+use crate::ics23_commitment::error::Error as Ics23Error;
+use crate::ics23_commitment::merkle::{apply_prefix, MerkleProof};
 use crate::ics24_host::identifier::ConnectionId;
 use crate::ics24_host::identifier::{ChannelId, ClientId, PortId};
+use crate::ics24_host::{ClientUpgradePath, Path};
+use crate::timestamp::{Expiry, Timestamp};
 use crate::Height;
 
+/// The root of the consensus state of an upgraded client, which cannot be used to verify proofs.
+pub const SENTINEL_ROOT: &str = "sentinel_root";
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct TendermintClient;
 
@@ -21,9 +42,12 @@ impl ClientDef for TendermintClient {
     type Header = Header;
     type ClientState = ClientState;
     type ConsensusState = ConsensusState;
+    type Misbehaviour = Misbehaviour;
 
     fn check_header_and_update_state(
         &self,
+        ctx: &dyn ClientReader,
+        client_id: ClientId,
         client_state: Self::ClientState,
         header: Self::Header,
     ) -> Result<(Self::ClientState, Self::ConsensusState), Box<dyn std::error::Error>> {
@@ -34,7 +58,7 @@ impl ClientDef for TendermintClient {
             );
         }
 
-        // TODO: Additional verifications should be implemented here.
+        verify_header(ctx, &client_id, &client_state, &header)?;
 
         Ok((
             client_state.with_header(header.clone()),
@@ -42,114 +66,1135 @@ impl ClientDef for TendermintClient {
         ))
     }
 
+    fn check_misbehaviour_and_update_state(
+        &self,
+        ctx: &dyn ClientReader,
+        client_id: ClientId,
+        client_state: Self::ClientState,
+        misbehaviour: Self::Misbehaviour,
+    ) -> Result<Self::ClientState, Box<dyn std::error::Error>> {
+        let header1 = &misbehaviour.header1;
+        let header2 = &misbehaviour.header2;
+
+        if header1.height() < header2.height() {
+            return Err(Error::from(Kind::MisbehaviourHeadersHeightMismatch(
+                header1.height(),
+                header2.height(),
+            ))
+            .into());
+        }
+
+        if header1.height() == header2.height() {
+            // Two headers at the same height are evidence of a fork only if they commit to
+            // different blocks.
+            if header1.signed_header.commit.block_id.hash
+                == header2.signed_header.commit.block_id.hash
+            {
+                return Err(Error::from(Kind::MisbehaviourHeadersBlockHashEqual).into());
+            }
+        } else {
+            // Header1 is higher than header2, so its time must not be after the time of header2
+            // for the pair to be a violation of BFT time monotonicity.
+            let time1 = Timestamp::from_datetime(header1.time().into());
+            let time2 = Timestamp::from_datetime(header2.time().into());
+            if let Expiry::Expired = time1.check_expiry(&time2) {
+                return Err(Error::from(Kind::MisbehaviourHeadersNotConflicting).into());
+            }
+        }
+
+        // Both headers must be valid with respect to the trusted states of the client.
+        verify_header(ctx, &client_id, &client_state, header1)?;
+        verify_header(ctx, &client_id, &client_state, header2)?;
+
+        Ok(client_state.with_frozen_height(misbehaviour.height()))
+    }
+
     fn verify_client_consensus_state(
         &self,
-        _client_state: &Self::ClientState,
-        _height: Height,
-        _prefix: &CommitmentPrefix,
-        _proof: &CommitmentProofBytes,
-        _client_id: &ClientId,
-        _consensus_height: Height,
-        _expected_consensus_state: &AnyConsensusState,
+        _ctx: &dyn ConnectionReader,
+        client_state: &Self::ClientState,
+        height: Height,
+        prefix: &CommitmentPrefix,
+        proof: &CommitmentProofBytes,
+        root: &CommitmentRoot,
+        client_id: &ClientId,
+        consensus_height: Height,
+        expected_consensus_state: &AnyConsensusState,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        todo!()
+        verify_height(client_state, height)?;
+
+        let path = Path::ClientConsensusState {
+            client_id: client_id.clone(),
+            epoch: consensus_height.revision_number,
+            height: consensus_height.revision_height,
+        };
+        let value = expected_consensus_state
+            .encode_vec()
+            .map_err(|e| Kind::VerificationFailure.context(e))?;
+
+        verify_membership(client_state, prefix, proof, root, path, value)
     }
 
     fn verify_connection_state(
         &self,
-        _client_state: &Self::ClientState,
-        _height: Height,
-        _prefix: &CommitmentPrefix,
-        _proof: &CommitmentProofBytes,
-        _connection_id: Option<&ConnectionId>,
-        _expected_connection_end: &ConnectionEnd,
+        _ctx: &dyn ConnectionReader,
+        client_state: &Self::ClientState,
+        height: Height,
+        prefix: &CommitmentPrefix,
+        proof: &CommitmentProofBytes,
+        root: &CommitmentRoot,
+        connection_id: Option<&ConnectionId>,
+        expected_connection_end: &ConnectionEnd,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        todo!()
+        verify_height(client_state, height)?;
+
+        let connection_id = connection_id.ok_or_else(|| {
+            Kind::VerificationFailure.context("missing counterparty connection identifier")
+        })?;
+        let path = Path::Connections(connection_id.clone());
+        let value = expected_connection_end
+            .encode_vec()
+            .map_err(|e| Kind::VerificationFailure.context(e))?;
+
+        verify_membership(client_state, prefix, proof, root, path, value)
     }
 
     fn verify_channel_state(
         &self,
-        _client_state: &Self::ClientState,
-        _height: Height,
-        _prefix: &CommitmentPrefix,
-        _proof: &CommitmentProofBytes,
-        _port_id: &PortId,
-        _channel_id: &ChannelId,
-        _expected_channel_end: &ChannelEnd,
+        _ctx: &dyn ChannelReader,
+        client_state: &Self::ClientState,
+        height: Height,
+        prefix: &CommitmentPrefix,
+        proof: &CommitmentProofBytes,
+        root: &CommitmentRoot,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        expected_channel_end: &ChannelEnd,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        todo!()
+        verify_height(client_state, height)?;
+
+        let path = Path::ChannelEnds(port_id.clone(), channel_id.clone());
+        let value = expected_channel_end
+            .encode_vec()
+            .map_err(|e| Kind::VerificationFailure.context(e))?;
+
+        verify_membership(client_state, prefix, proof, root, path, value)
     }
 
     fn verify_client_full_state(
         &self,
-        _client_state: &Self::ClientState,
-        _height: Height,
-        _root: &CommitmentRoot,
-        _prefix: &CommitmentPrefix,
-        _client_id: &ClientId,
-        _proof: &CommitmentProofBytes,
-        _expected_client_state: &AnyClientState,
+        _ctx: &dyn ConnectionReader,
+        client_state: &Self::ClientState,
+        height: Height,
+        root: &CommitmentRoot,
+        prefix: &CommitmentPrefix,
+        client_id: &ClientId,
+        proof: &CommitmentProofBytes,
+        expected_client_state: &AnyClientState,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        unimplemented!()
+        verify_height(client_state, height)?;
+
+        let path = Path::ClientState(client_id.clone());
+        let value = expected_client_state
+            .encode_vec()
+            .map_err(|e| Kind::VerificationFailure.context(e))?;
+
+        verify_membership(client_state, prefix, proof, root, path, value)
     }
 
     fn verify_packet_data(
         &self,
-        _client_state: &Self::ClientState,
-        _height: Height,
-        _proof: &CommitmentProofBytes,
-        _port_id: &PortId,
-        _channel_id: &ChannelId,
-        _seq: &Sequence,
-        _data: String,
+        _ctx: &dyn ChannelReader,
+        client_state: &Self::ClientState,
+        height: Height,
+        prefix: &CommitmentPrefix,
+        proof: &CommitmentProofBytes,
+        root: &CommitmentRoot,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        seq: &Sequence,
+        commitment: PacketCommitment,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        todo!()
+        verify_height(client_state, height)?;
+
+        let path = Path::Commitments {
+            port_id: port_id.clone(),
+            channel_id: channel_id.clone(),
+            sequence: *seq,
+        };
+
+        verify_membership(
+            client_state,
+            prefix,
+            proof,
+            root,
+            path,
+            commitment.into_vec(),
+        )
     }
 
     fn verify_packet_acknowledgement(
         &self,
-        _client_state: &Self::ClientState,
-        _height: Height,
-        _proof: &CommitmentProofBytes,
-        _port_id: &PortId,
-        _channel_id: &ChannelId,
-        _seq: &Sequence,
-        _data: Vec<u8>,
+        _ctx: &dyn ChannelReader,
+        client_state: &Self::ClientState,
+        height: Height,
+        prefix: &CommitmentPrefix,
+        proof: &CommitmentProofBytes,
+        root: &CommitmentRoot,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        seq: &Sequence,
+        ack: Vec<u8>,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        todo!()
+        verify_height(client_state, height)?;
+
+        let path = Path::Acks {
+            port_id: port_id.clone(),
+            channel_id: channel_id.clone(),
+            sequence: *seq,
+        };
+
+        let ack_commitment = AcknowledgementCommitment::compute(&ack);
+        verify_membership(
+            client_state,
+            prefix,
+            proof,
+            root,
+            path,
+            ack_commitment.into_vec(),
+        )
     }
 
     fn verify_next_sequence_recv(
         &self,
-        _client_state: &Self::ClientState,
-        _height: Height,
-        _proof: &CommitmentProofBytes,
-        _port_id: &PortId,
-        _channel_id: &ChannelId,
-        _seq: &Sequence,
+        _ctx: &dyn ChannelReader,
+        client_state: &Self::ClientState,
+        height: Height,
+        prefix: &CommitmentPrefix,
+        proof: &CommitmentProofBytes,
+        root: &CommitmentRoot,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        seq: &Sequence,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        todo!()
+        verify_height(client_state, height)?;
+
+        let path = Path::SeqRecvs(port_id.clone(), channel_id.clone());
+        let seq_bytes = u64::from(*seq).to_be_bytes().to_vec();
+
+        verify_membership(client_state, prefix, proof, root, path, seq_bytes)
     }
 
     fn verify_packet_receipt_absence(
         &self,
-        _client_state: &Self::ClientState,
-        _height: Height,
-        _proof: &CommitmentProofBytes,
-        _port_id: &PortId,
-        _channel_id: &ChannelId,
-        _seq: &Sequence,
+        _ctx: &dyn ChannelReader,
+        client_state: &Self::ClientState,
+        height: Height,
+        prefix: &CommitmentPrefix,
+        proof: &CommitmentProofBytes,
+        root: &CommitmentRoot,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        seq: &Sequence,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        todo!()
+        verify_height(client_state, height)?;
+
+        let path = Path::Receipts {
+            port_id: port_id.clone(),
+            channel_id: channel_id.clone(),
+            sequence: *seq,
+        };
+
+        verify_non_membership(client_state, prefix, proof, root, path)
     }
 
     fn verify_upgrade_and_update_state(
         &self,
-        _client_state: &Self::ClientState,
-        _consensus_state: &Self::ConsensusState,
-        _proof_upgrade_client: MerkleProof,
-        _proof_upgrade_consensus_state: MerkleProof,
+        ctx: &dyn ClientReader,
+        client_id: &ClientId,
+        client_state: &Self::ClientState,
+        upgraded_client_state: &Self::ClientState,
+        upgraded_consensus_state: &Self::ConsensusState,
+        proof_upgrade_client: RawMerkleProof,
+        proof_upgrade_consensus_state: RawMerkleProof,
     ) -> Result<(Self::ClientState, Self::ConsensusState), Box<dyn std::error::Error>> {
-        todo!()
+        Ok(verify_upgrade(
+            ctx,
+            client_id,
+            client_state,
+            upgraded_client_state,
+            upgraded_consensus_state,
+            proof_upgrade_client,
+            proof_upgrade_consensus_state,
+        )?)
+    }
+
+    fn check_substitute_and_update_state(
+        &self,
+        ctx: &dyn ClientReader,
+        subject_client_id: &ClientId,
+        subject_client_state: &Self::ClientState,
+        substitute_client_state: &Self::ClientState,
+    ) -> Result<Self::ClientState, Box<dyn std::error::Error>> {
+        Ok(check_substitute(
+            ctx.client_status(subject_client_id),
+            subject_client_state,
+            substitute_client_state,
+        )?)
+    }
+}
+
+/// Checks that a subject client with status `subject_status` may be recovered with the state of
+/// the substitute client, as allowed by the `AllowUpdate` flags of the subject: a frozen client
+/// can be recovered only if it allows updates after misbehaviour, an expired one only if it
+/// allows updates after expiry.
+///
+/// The substitute must match the subject in all parameters but the chain identifier, the trusting
+/// period and the latest and frozen heights, which the recovered subject takes from the
+/// substitute, so that it tracks the (possibly restarted) chain from the latest height of the
+/// substitute.
+fn check_substitute(
+    subject_status: Status,
+    subject_client_state: &ClientState,
+    substitute_client_state: &ClientState,
+) -> Result<ClientState, Error> {
+    match subject_status {
+        Status::Frozen if !subject_client_state.allow_update.after_misbehaviour => {
+            return Err(Kind::UpdateAfterMisbehaviourNotAllowed.into());
+        }
+        Status::Expired if !subject_client_state.allow_update.after_expiry => {
+            return Err(Kind::UpdateAfterExpiryNotAllowed.into());
+        }
+        Status::Frozen | Status::Expired => {}
+        Status::Active | Status::Unknown => return Err(Kind::ClientNotRecoverable.into()),
+    }
+
+    let recovered_client_state = ClientState {
+        chain_id: substitute_client_state.chain_id.clone(),
+        trusting_period: substitute_client_state.trusting_period,
+        latest_height: substitute_client_state.latest_height,
+        frozen_height: Height::zero(),
+        ..subject_client_state.clone()
+    };
+
+    // Both clients must agree on the parameters which the subject keeps.
+    let matching_substitute = ClientState {
+        frozen_height: Height::zero(),
+        ..substitute_client_state.clone()
+    };
+    if recovered_client_state != matching_substitute {
+        return Err(Kind::MismatchedSubstituteClientState.into());
+    }
+
+    Ok(recovered_client_state)
+}
+
+/// Verifies that the chain committed, at the latest height of the client, to upgrade to the given
+/// client and consensus states. The chain stores these in its upgrade store, under the
+/// `upgradedIBCState` key, with their custom fields zeroed. The first key of the upgrade path of
+/// the client is the one of the upgrade store.
+///
+/// The upgraded client takes the chain-specific fields of `upgraded_client_state` (e.g., the new
+/// chain identifier, revision and unbonding period), and keeps the custom fields chosen by the
+/// relayer who created the client. The upgraded consensus state serves only as a trusted kernel
+/// against which to verify the headers of the upgraded chain, hence its root is a sentinel.
+fn verify_upgrade(
+    ctx: &dyn ClientReader,
+    client_id: &ClientId,
+    client_state: &ClientState,
+    upgraded_client_state: &ClientState,
+    upgraded_consensus_state: &ConsensusState,
+    proof_upgrade_client: RawMerkleProof,
+    proof_upgrade_consensus_state: RawMerkleProof,
+) -> Result<(ClientState, ConsensusState), Error> {
+    let upgrade_store = client_state
+        .upgrade_path
+        .first()
+        .filter(|key| !key.is_empty())
+        .ok_or(Kind::MissingUpgradePath)?;
+    let prefix = CommitmentPrefix::from(upgrade_store.as_bytes().to_vec());
+
+    let latest_height = client_state.latest_height;
+    if upgraded_client_state.latest_height <= latest_height {
+        return Err(
+            Kind::LowUpgradeHeight(latest_height, upgraded_client_state.latest_height).into(),
+        );
+    }
+
+    // The proofs are verified against the latest consensus state, which commits to the latest
+    // upgrade plan of the chain.
+    let root = match ctx.consensus_state(client_id, latest_height) {
+        Some(AnyConsensusState::Tendermint(consensus_state)) => consensus_state.root,
+        _ => return Err(Kind::MissingLatestConsensusState(latest_height).into()),
+    };
+
+    let path = Path::Upgrade(ClientUpgradePath::UpgradedClientState(
+        latest_height.revision_height,
+    ));
+    let value = AnyClientState::Tendermint(upgraded_client_state.clone())
+        .encode_vec()
+        .map_err(|e| Kind::InvalidUpgradeClientProof.context(e))?;
+    verify_membership(
+        client_state,
+        &prefix,
+        &proof_upgrade_client.into(),
+        &root,
+        path,
+        value,
+    )
+    .map_err(|e| Kind::InvalidUpgradeClientProof.context(e))?;
+
+    let path = Path::Upgrade(ClientUpgradePath::UpgradedClientConsensusState(
+        latest_height.revision_height,
+    ));
+    let value = AnyConsensusState::Tendermint(upgraded_consensus_state.clone())
+        .encode_vec()
+        .map_err(|e| Kind::InvalidUpgradeConsensusStateProof.context(e))?;
+    verify_membership(
+        client_state,
+        &prefix,
+        &proof_upgrade_consensus_state.into(),
+        &root,
+        path,
+        value,
+    )
+    .map_err(|e| Kind::InvalidUpgradeConsensusStateProof.context(e))?;
+
+    let new_client_state = ClientState::new(
+        upgraded_client_state.chain_id.clone(),
+        client_state.trust_level,
+        client_state.trusting_period,
+        upgraded_client_state.unbonding_period,
+        client_state.max_clock_drift,
+        upgraded_client_state.latest_height,
+        Height::zero(),
+        upgraded_client_state.proof_specs.clone(),
+        upgraded_client_state.upgrade_path.clone(),
+        client_state.allow_update,
+    )?;
+
+    let new_consensus_state = ConsensusState::new(
+        CommitmentRoot::from_bytes(SENTINEL_ROOT.as_bytes()),
+        upgraded_consensus_state.timestamp,
+        upgraded_consensus_state.next_validators_hash,
+    );
+
+    Ok((new_client_state, new_consensus_state))
+}
+
+/// Verifies `header` against the consensus state of client `client_id` at the header's trusted
+/// height, following the Tendermint light client rules: the trusted state must be within the
+/// trusting period, the header must not come from the future, and the header must be signed by
+/// enough voting power of the trusted (and of its own) validator set.
+fn verify_header(
+    ctx: &dyn ClientReader,
+    client_id: &ClientId,
+    client_state: &ClientState,
+    header: &Header,
+) -> Result<(), Error> {
+    if !client_state.frozen_height.is_zero() {
+        return Err(Kind::FrozenClient.into());
+    }
+
+    let untrusted_header = &header.signed_header.header;
+    if untrusted_header.chain_id.as_str() != client_state.chain_id.as_str() {
+        return Err(Kind::MismatchedChainId(
+            client_state.chain_id.to_string(),
+            untrusted_header.chain_id.to_string(),
+        )
+        .into());
+    }
+
+    // Updates cannot cross revisions, and heights must increase.
+    let revision_number = client_state.latest_height.revision_number;
+    if header.height().revision_number != revision_number {
+        return Err(
+            Kind::MismatchedRevisions(revision_number, header.height().revision_number).into(),
+        );
+    }
+    if header.trusted_height.revision_number != revision_number {
+        return Err(Kind::MismatchedRevisions(
+            revision_number,
+            header.trusted_height.revision_number,
+        )
+        .into());
+    }
+    if header.height() <= header.trusted_height {
+        return Err(Kind::InvalidTrustedHeight(header.height(), header.trusted_height).into());
+    }
+
+    let trusted_consensus_state = match ctx.consensus_state(client_id, header.trusted_height) {
+        Some(AnyConsensusState::Tendermint(consensus_state)) => consensus_state,
+        _ => return Err(Kind::MissingTrustedConsensusState(header.trusted_height).into()),
+    };
+
+    let trusted_timestamp = Timestamp::from_datetime(trusted_consensus_state.timestamp.into());
+    let header_timestamp = Timestamp::from_datetime(header.time().into());
+    // Without the host time, neither the trusting period nor the clock drift can be checked.
+    let now = ctx.host_timestamp();
+    if now == Timestamp::none() {
+        return Err(Kind::MissingHostTimestamp.into());
+    }
+
+    // The trusted state must still be within the trusting period.
+    let expiry = (trusted_timestamp + client_state.trusting_period)
+        .map_err(|e| Kind::HeaderVerificationFailure.context(e))?;
+    if let Expiry::Expired = now.check_expiry(&expiry) {
+        return Err(Kind::TrustedStateExpired(expiry, now).into());
+    }
+
+    // The header must not come from the future, up to the maximum clock drift.
+    let drift_bound = (now + client_state.max_clock_drift)
+        .map_err(|e| Kind::HeaderVerificationFailure.context(e))?;
+    if let Expiry::Expired = header_timestamp.check_expiry(&drift_bound) {
+        return Err(Kind::HeaderFromFuture(header_timestamp, drift_bound).into());
+    }
+
+    // BFT time must increase.
+    if let Expiry::NotExpired = header_timestamp.check_expiry(&trusted_timestamp) {
+        return Err(Kind::NonMonotonicHeaderTime(header_timestamp, trusted_timestamp).into());
+    }
+
+    let predicates = ProdPredicates;
+    let hasher = ProdHasher;
+    let commit_validator = ProdCommitValidator::default();
+    let calculator = ProdVotingPowerCalculator::default();
+
+    // The trusted validator set supplied with the header must be the one committed to by the
+    // trusted consensus state.
+    if hasher.hash_validator_set(&header.trusted_validator_set)
+        != trusted_consensus_state.next_validators_hash
+    {
+        return Err(Kind::InvalidValidatorSet
+            .context("trusted validator set does not match the trusted consensus state")
+            .into());
+    }
+
+    // The validator set supplied with the header must be the one the header commits to.
+    if hasher.hash_validator_set(&header.validator_set) != untrusted_header.validators_hash {
+        return Err(Kind::InvalidValidatorSet
+            .context("validator set does not match the header validators hash")
+            .into());
+    }
+
+    predicates
+        .header_matches_commit(&header.signed_header, &hasher)
+        .map_err(|e| Kind::HeaderVerificationFailure.context(e.to_string()))?;
+
+    predicates
+        .valid_commit(
+            &header.signed_header,
+            &header.validator_set,
+            &commit_validator,
+        )
+        .map_err(|e| Kind::HeaderVerificationFailure.context(e.to_string()))?;
+
+    if header.height() == header.trusted_height.increment() {
+        // For adjacent headers, the validator set must be the one announced by the trusted state.
+        if untrusted_header.validators_hash != trusted_consensus_state.next_validators_hash {
+            return Err(Kind::InvalidValidatorSet
+                .context("header validators do not match the trusted next validators")
+                .into());
+        }
+    } else {
+        // Otherwise, the trusted validators must hold enough of the voting power that signed
+        // the header, as given by the trust level of the client.
+        predicates
+            .has_sufficient_validators_overlap(
+                &header.signed_header,
+                &header.trusted_validator_set,
+                &client_state.trust_level,
+                &calculator,
+            )
+            .map_err(|e| Kind::HeaderVerificationFailure.context(e.to_string()))?;
+    }
+
+    // More than two thirds of the header validator set must have signed the header.
+    predicates
+        .has_sufficient_signers_overlap(&header.signed_header, &header.validator_set, &calculator)
+        .map_err(|e| Kind::HeaderVerificationFailure.context(e.to_string()))?;
+
+    Ok(())
+}
+
+/// Checks that the client is able to verify proofs computed at the given `height`: the client
+/// must have a consensus state at least as recent as `height`, and must not be frozen at (or
+/// below) `height`.
+fn verify_height(client_state: &ClientState, height: Height) -> Result<(), Error> {
+    if client_state.latest_height < height {
+        return Err(Kind::InsufficientHeight(client_state.latest_height, height).into());
+    }
+
+    if !client_state.frozen_height.is_zero() && client_state.frozen_height <= height {
+        return Err(Kind::FrozenHeight(client_state.frozen_height, height).into());
+    }
+
+    Ok(())
+}
+
+/// Verifies that `value` is stored at `path` (under `prefix`) in the store committed to by `root`.
+fn verify_membership(
+    client_state: &ClientState,
+    prefix: &CommitmentPrefix,
+    proof: &CommitmentProofBytes,
+    root: &CommitmentRoot,
+    path: Path,
+    value: Vec<u8>,
+) -> Result<(), Error> {
+    let merkle_path = apply_prefix(prefix, vec![path.to_string()])
+        .map_err(|e| Kind::InvalidMerkleProof.context(e.to_string()))?;
+    let merkle_proof = MerkleProof::try_from(proof.clone())
+        .map_err(|e| Kind::InvalidCommitmentProof.context(e))?;
+
+    merkle_proof
+        .verify_membership(&client_state.proof_specs, root, merkle_path, value, 0)
+        .map_err(merkle_error)
+}
+
+/// Verifies that nothing is stored at `path` (under `prefix`) in the store committed to by
+/// `root`.
+fn verify_non_membership(
+    client_state: &ClientState,
+    prefix: &CommitmentPrefix,
+    proof: &CommitmentProofBytes,
+    root: &CommitmentRoot,
+    path: Path,
+) -> Result<(), Error> {
+    let merkle_path = apply_prefix(prefix, vec![path.to_string()])
+        .map_err(|e| Kind::InvalidMerkleProof.context(e.to_string()))?;
+    let merkle_proof = MerkleProof::try_from(proof.clone())
+        .map_err(|e| Kind::InvalidCommitmentProof.context(e))?;
+
+    merkle_proof
+        .verify_non_membership(&client_state.proof_specs, root, merkle_path)
+        .map_err(merkle_error)
+}
+
+fn merkle_error(e: Ics23Error) -> Error {
+    match e {
+        Ics23Error::NumberOfSpecsMismatch(proofs, specs) => {
+            Kind::NumberOfSpecsMismatch(proofs, specs).into()
+        }
+        Ics23Error::NumberOfKeysMismatch(proofs, keys) => {
+            Kind::NumberOfKeysMismatch(proofs, keys).into()
+        }
+        Ics23Error::VerificationFailure | Ics23Error::RootMismatch => {
+            Kind::VerificationFailure.context(e).into()
+        }
+        _ => Kind::InvalidMerkleProof.context(e).into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ibc_proto::ibc::core::commitment::v1::MerkleProof;
+    use tendermint::trust_threshold::TrustThresholdFraction;
+    use tendermint_proto::Protobuf;
+
+    use crate::ics02_client::client_consensus::AnyConsensusState;
+    use crate::ics02_client::client_def::ClientDef;
+    use crate::ics02_client::client_state::{AnyClientState, Status};
+    use crate::ics02_client::client_type::ClientType;
+    use crate::ics02_client::context::{ClientKeeper, ClientReader};
+    use crate::ics04_channel::packet::Sequence;
+    use crate::ics07_tendermint::client_def::{check_substitute, TendermintClient};
+    use crate::ics07_tendermint::client_state::test_util::get_dummy_tendermint_client_state;
+    use crate::ics07_tendermint::client_state::ClientState;
+    use crate::ics07_tendermint::consensus_state::ConsensusState;
+    use crate::ics07_tendermint::error::{Error, Kind};
+    use crate::ics07_tendermint::header::test_util::{
+        get_dummy_ics07_header, get_dummy_tendermint_header,
+    };
+    use crate::ics07_tendermint::header::Header;
+    use crate::ics07_tendermint::misbehaviour::Misbehaviour;
+    use crate::ics23_commitment::commitment::{CommitmentPrefix, CommitmentRoot};
+    use crate::ics23_commitment::merkle::test_util::{
+        get_cosmos_membership_proofs, get_cosmos_non_membership_proof,
+    };
+    use crate::ics24_host::identifier::{ChainId, ChannelId, ClientId, PortId};
+    use crate::ics24_host::{ClientUpgradePath, Path};
+    use crate::mock::context::MockContext;
+    use crate::mock::host::HostBlock;
+    use crate::test_utils::get_dummy_proof;
+    use crate::timestamp::Timestamp;
+    use crate::Height;
+
+    fn dummy_client_state() -> ClientState {
+        match get_dummy_tendermint_client_state(get_dummy_tendermint_header()) {
+            AnyClientState::Tendermint(client_state) => client_state,
+            _ => panic!("expected a tendermint client state"),
+        }
+    }
+
+    fn verify_next_seq_recv_at(
+        client_state: &ClientState,
+        height: Height,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        TendermintClient.verify_next_sequence_recv(
+            &MockContext::default(),
+            client_state,
+            height,
+            &CommitmentPrefix::from(b"ibc".to_vec()),
+            &get_dummy_proof().into(),
+            &CommitmentRoot::from(vec![0]),
+            &PortId::default(),
+            &ChannelId::default(),
+            &Sequence::from(1),
+        )
+    }
+
+    /// Returns a misbehaviour made of two headers of the same chain, at heights `height1` and
+    /// `height2` respectively.
+    fn misbehaviour(height1: u64, height2: u64) -> Misbehaviour {
+        let chain_id = ChainId::new("mockgaiaB".to_string(), 1);
+        let header = |height| {
+            let mut header = Header::from(HostBlock::generate_tm_block(chain_id.clone(), height));
+            header.trusted_height = Height::new(1, 1);
+            header
+        };
+
+        Misbehaviour {
+            client_id: ClientId::default(),
+            header1: header(height1),
+            header2: header(height2),
+        }
+    }
+
+    /// Returns a context whose host time is `host_seconds` after the Unix epoch (or unset, if
+    /// zero), with a Tendermint client of chain `mockgaiaB` at height 20. The timestamp of a
+    /// synthetic block at height `h` is `h` seconds after the Unix epoch, and the trusting period
+    /// of the client is 64000 seconds.
+    fn context_with_client_at(host_seconds: u64) -> (MockContext, ClientState) {
+        let ctx = MockContext::default()
+            .with_client_parametrized_with_chain_id(
+                ChainId::new("mockgaiaB".to_string(), 1),
+                &ClientId::default(),
+                Height::new(1, 20),
+                Some(ClientType::Tendermint),
+                None,
+            )
+            .with_timestamp(Timestamp::from_nanoseconds(host_seconds * 1_000_000_000).unwrap());
+
+        let client_state = match ctx.client_state(&ClientId::default()) {
+            Some(AnyClientState::Tendermint(client_state)) => client_state,
+            _ => panic!("expected a tendermint client state"),
+        };
+        (ctx, client_state)
+    }
+
+    /// Returns a header of chain `mockgaiaB` at `height`, trusting the state at height 20.
+    fn header_at(height: u64) -> Header {
+        let chain_id = ChainId::new("mockgaiaB".to_string(), 1);
+        let mut header = Header::from(HostBlock::generate_tm_block(chain_id, height));
+        header.trusted_height = Height::new(1, 20);
+        header
+    }
+
+    fn update(
+        ctx: &MockContext,
+        client_state: &ClientState,
+        header: Header,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        TendermintClient
+            .check_header_and_update_state(ctx, ClientId::default(), client_state.clone(), header)
+            .map(|_| ())
+    }
+
+    fn error_kind(err: Box<dyn std::error::Error>) -> Kind {
+        err.downcast_ref::<Error>()
+            .expect("expected a tendermint client error")
+            .kind()
+            .clone()
+    }
+
+    #[test]
+    fn verify_rejects_proof_above_latest_height() {
+        let client_state = dummy_client_state();
+        let proof_height = client_state.latest_height.increment();
+
+        let err = verify_next_seq_recv_at(&client_state, proof_height).unwrap_err();
+        assert!(matches!(error_kind(err), Kind::InsufficientHeight(_, _)));
+    }
+
+    #[test]
+    fn verify_rejects_proof_on_frozen_client() {
+        let mut client_state = dummy_client_state();
+        client_state.frozen_height = client_state.latest_height;
+
+        let err = verify_next_seq_recv_at(&client_state, client_state.latest_height).unwrap_err();
+        assert!(matches!(error_kind(err), Kind::FrozenHeight(_, _)));
+    }
+
+    #[test]
+    fn verify_rejects_malformed_proof() {
+        let client_state = dummy_client_state();
+
+        let err = verify_next_seq_recv_at(&client_state, client_state.latest_height).unwrap_err();
+        assert!(matches!(
+            error_kind(err),
+            Kind::InvalidCommitmentProof | Kind::NumberOfSpecsMismatch(_, _)
+        ));
+    }
+
+    #[test]
+    fn verify_valid_proofs() {
+        let ctx = MockContext::default();
+        let client_state = dummy_client_state();
+        let height = client_state.latest_height;
+        let prefix = CommitmentPrefix::from(b"ibc".to_vec());
+        let port_id = PortId::default();
+        let channel_id = ChannelId::default();
+
+        // The next sequence to receive is stored as a big-endian integer.
+        let path = Path::SeqRecvs(port_id.clone(), channel_id.clone());
+        let entries = vec![(path.to_string(), 5u64.to_be_bytes().to_vec())];
+        let (root, proofs) = get_cosmos_membership_proofs(&prefix, &entries);
+        let proof = proofs[0].clone().into();
+
+        let verify_next_seq_recv = |seq: u64| {
+            TendermintClient.verify_next_sequence_recv(
+                &ctx,
+                &client_state,
+                height,
+                &prefix,
+                &proof,
+                &root,
+                &port_id,
+                &channel_id,
+                &Sequence::from(seq),
+            )
+        };
+        verify_next_seq_recv(5).unwrap();
+        let err = verify_next_seq_recv(6).unwrap_err();
+        assert!(matches!(error_kind(err), Kind::VerificationFailure));
+
+        // The receipt of sequence 1 is absent, while the one of sequence 2 is stored.
+        let receipt_path = |sequence: u64| {
+            Path::Receipts {
+                port_id: port_id.clone(),
+                channel_id: channel_id.clone(),
+                sequence: Sequence::from(sequence),
+            }
+            .to_string()
+        };
+        let (root, proof) =
+            get_cosmos_non_membership_proof(&prefix, &receipt_path(1), &receipt_path(2));
+        let proof = proof.into();
+
+        let verify_receipt_absence = |seq: u64| {
+            TendermintClient.verify_packet_receipt_absence(
+                &ctx,
+                &client_state,
+                height,
+                &prefix,
+                &proof,
+                &root,
+                &port_id,
+                &channel_id,
+                &Sequence::from(seq),
+            )
+        };
+        verify_receipt_absence(1).unwrap();
+        let err = verify_receipt_absence(2).unwrap_err();
+        assert!(matches!(error_kind(err), Kind::VerificationFailure));
+    }
+
+    #[test]
+    fn header_verification_requires_host_timestamp() {
+        let (ctx, client_state) = context_with_client_at(0);
+
+        let err = update(&ctx, &client_state, header_at(21)).unwrap_err();
+        assert!(matches!(error_kind(err), Kind::MissingHostTimestamp));
+    }
+
+    #[test]
+    fn header_verification_checks_trusting_period_and_clock_drift() {
+        let (ctx, client_state) = context_with_client_at(100);
+        update(&ctx, &client_state, header_at(21)).unwrap();
+
+        // Headers may be ahead of the host by at most the maximum clock drift (3 seconds).
+        update(&ctx, &client_state, header_at(103)).unwrap();
+        let err = update(&ctx, &client_state, header_at(104)).unwrap_err();
+        assert!(matches!(error_kind(err), Kind::HeaderFromFuture(_, _)));
+
+        // The trusted state, at 20 seconds, expires at 64020 seconds.
+        let (ctx, client_state) = context_with_client_at(64020);
+        update(&ctx, &client_state, header_at(64020)).unwrap();
+        let (ctx, client_state) = context_with_client_at(64021);
+        let err = update(&ctx, &client_state, header_at(64021)).unwrap_err();
+        assert!(matches!(error_kind(err), Kind::TrustedStateExpired(_, _)));
+    }
+
+    #[test]
+    fn header_verification_checks_validator_sets() {
+        let (ctx, client_state) = context_with_client_at(100);
+        let foreign_validator_set = get_dummy_ics07_header().validator_set;
+
+        // The validator set must be the one the header commits to.
+        let mut header = header_at(21);
+        header.validator_set = foreign_validator_set.clone();
+        let err = update(&ctx, &client_state, header).unwrap_err();
+        assert!(matches!(error_kind(err), Kind::InvalidValidatorSet));
+
+        // The trusted validator set must be the one the trusted consensus state commits to.
+        let mut header = header_at(30);
+        header.trusted_validator_set = foreign_validator_set;
+        let err = update(&ctx, &client_state, header).unwrap_err();
+        assert!(matches!(error_kind(err), Kind::InvalidValidatorSet));
+    }
+
+    #[test]
+    fn header_verification_checks_trust_threshold() {
+        let (ctx, mut client_state) = context_with_client_at(100);
+        update(&ctx, &client_state, header_at(30)).unwrap();
+
+        // No validators can hold more than the whole voting power which signed the header.
+        client_state.trust_level = TrustThresholdFraction {
+            numerator: 1,
+            denominator: 1,
+        };
+
+        // Adjacent headers are signed by the trusted next validators, whatever the trust level.
+        update(&ctx, &client_state, header_at(21)).unwrap();
+        let err = update(&ctx, &client_state, header_at(30)).unwrap_err();
+        assert!(matches!(error_kind(err), Kind::HeaderVerificationFailure));
+    }
+
+    #[test]
+    fn misbehaviour_rejects_non_conflicting_headers() {
+        let ctx = MockContext::default();
+        let client_id = ClientId::default();
+
+        // Header1 must not be lower than header2.
+        let err = TendermintClient
+            .check_misbehaviour_and_update_state(
+                &ctx,
+                client_id.clone(),
+                dummy_client_state(),
+                misbehaviour(5, 10),
+            )
+            .unwrap_err();
+        assert!(matches!(
+            error_kind(err),
+            Kind::MisbehaviourHeadersHeightMismatch(_, _)
+        ));
+
+        // The same block, submitted twice, is not a fork.
+        let err = TendermintClient
+            .check_misbehaviour_and_update_state(
+                &ctx,
+                client_id.clone(),
+                dummy_client_state(),
+                misbehaviour(10, 10),
+            )
+            .unwrap_err();
+        assert!(matches!(
+            error_kind(err),
+            Kind::MisbehaviourHeadersBlockHashEqual
+        ));
+
+        // Headers at increasing heights with increasing times do not violate BFT time.
+        let err = TendermintClient
+            .check_misbehaviour_and_update_state(
+                &ctx,
+                client_id,
+                dummy_client_state(),
+                misbehaviour(10, 5),
+            )
+            .unwrap_err();
+        assert!(matches!(
+            error_kind(err),
+            Kind::MisbehaviourHeadersNotConflicting
+        ));
+    }
+
+    #[test]
+    fn upgrade_rejects_unverifiable_upgrades() {
+        let client_id = ClientId::default();
+        let mut client_state = dummy_client_state();
+        let latest_height = client_state.latest_height;
+
+        let mut upgraded_client_state = ClientState::zero_custom_fields(client_state.clone());
+        upgraded_client_state.chain_id = ChainId::new("upgraded".to_string(), 1);
+        upgraded_client_state.latest_height = Height::new(latest_height.revision_number + 1, 1);
+        let upgraded_consensus_state = ConsensusState::from(get_dummy_tendermint_header());
+
+        let upgrade =
+            |ctx: &MockContext, client_state: &ClientState, upgraded_client_state: &ClientState| {
+                TendermintClient
+                    .verify_upgrade_and_update_state(
+                        ctx,
+                        &client_id,
+                        client_state,
+                        upgraded_client_state,
+                        &upgraded_consensus_state,
+                        MerkleProof::default(),
+                        MerkleProof::default(),
+                    )
+                    .unwrap_err()
+            };
+
+        // The dummy client has no upgrade path.
+        let ctx = MockContext::default();
+        let err = upgrade(&ctx, &client_state, &upgraded_client_state);
+        assert!(matches!(error_kind(err), Kind::MissingUpgradePath));
+
+        client_state.upgrade_path = vec!["upgrade".to_string(), "upgradedIBCState".to_string()];
+
+        // The upgrade must be at a greater height.
+        let mut low_client_state = upgraded_client_state.clone();
+        low_client_state.latest_height = latest_height;
+        let err = upgrade(&ctx, &client_state, &low_client_state);
+        assert!(matches!(error_kind(err), Kind::LowUpgradeHeight(_, _)));
+
+        // The proofs are verified against the latest consensus state of the client.
+        let err = upgrade(&ctx, &client_state, &upgraded_client_state);
+        assert!(matches!(
+            error_kind(err),
+            Kind::MissingLatestConsensusState(h) if h == latest_height
+        ));
+
+        let ctx = ctx.with_client_parametrized(
+            &client_id,
+            latest_height,
+            Some(ClientType::Tendermint),
+            None,
+        );
+        let err = upgrade(&ctx, &client_state, &upgraded_client_state);
+        assert!(matches!(error_kind(err), Kind::InvalidUpgradeClientProof));
+    }
+
+    #[test]
+    fn upgrade_with_valid_proofs() {
+        let client_id = ClientId::default();
+        let mut client_state = dummy_client_state();
+        client_state.upgrade_path = vec!["upgrade".to_string(), "upgradedIBCState".to_string()];
+        let latest_height = client_state.latest_height;
+
+        let mut upgraded_client_state = ClientState::zero_custom_fields(client_state.clone());
+        upgraded_client_state.chain_id = ChainId::new("upgraded".to_string(), 2);
+        upgraded_client_state.latest_height = Height::new(latest_height.revision_number + 1, 1);
+        upgraded_client_state.unbonding_period = 2 * client_state.unbonding_period;
+        let upgraded_consensus_state = ConsensusState::from(get_dummy_tendermint_header());
+
+        // The chain commits to both upgraded states in its upgrade store, at the latest height of
+        // the client.
+        let client_path = Path::Upgrade(ClientUpgradePath::UpgradedClientState(
+            latest_height.revision_height,
+        ));
+        let consensus_path = Path::Upgrade(ClientUpgradePath::UpgradedClientConsensusState(
+            latest_height.revision_height,
+        ));
+        let mut entries = vec![
+            (
+                client_path.to_string(),
+                AnyClientState::Tendermint(upgraded_client_state.clone())
+                    .encode_vec()
+                    .unwrap(),
+            ),
+            (
+                consensus_path.to_string(),
+                AnyConsensusState::Tendermint(upgraded_consensus_state.clone())
+                    .encode_vec()
+                    .unwrap(),
+            ),
+        ];
+        entries.sort();
+        let prefix = CommitmentPrefix::from(b"upgrade".to_vec());
+        let (root, proofs) = get_cosmos_membership_proofs(&prefix, &entries);
+        let proof_of = |path: &Path| {
+            let index = entries
+                .iter()
+                .position(|(key, _)| *key == path.to_string())
+                .unwrap();
+            MerkleProof::from(proofs[index].clone())
+        };
+
+        let mut ctx = MockContext::default();
+        let latest_consensus_state = ConsensusState::new(
+            root,
+            upgraded_consensus_state.timestamp,
+            upgraded_consensus_state.next_validators_hash,
+        );
+        ctx.store_consensus_state(
+            client_id.clone(),
+            latest_height,
+            AnyConsensusState::Tendermint(latest_consensus_state),
+        )
+        .unwrap();
+
+        let upgrade = |proof_upgrade_client: MerkleProof,
+                       proof_upgrade_consensus_state: MerkleProof| {
+            TendermintClient.verify_upgrade_and_update_state(
+                &ctx,
+                &client_id,
+                &client_state,
+                &upgraded_client_state,
+                &upgraded_consensus_state,
+                proof_upgrade_client,
+                proof_upgrade_consensus_state,
+            )
+        };
+
+        let (new_client_state, new_consensus_state) =
+            upgrade(proof_of(&client_path), proof_of(&consensus_path)).unwrap();
+
+        // The client tracks the upgraded chain, and keeps its custom fields.
+        assert_eq!(new_client_state.chain_id, upgraded_client_state.chain_id);
+        assert_eq!(
+            new_client_state.latest_height,
+            upgraded_client_state.latest_height
+        );
+        assert_eq!(
+            new_client_state.unbonding_period,
+            upgraded_client_state.unbonding_period
+        );
+        assert_eq!(new_client_state.trust_level, client_state.trust_level);
+        assert_eq!(
+            new_client_state.trusting_period,
+            client_state.trusting_period
+        );
+        assert_eq!(
+            new_consensus_state.next_validators_hash,
+            upgraded_consensus_state.next_validators_hash
+        );
+
+        // Each proof must prove its own upgraded state.
+        let err = upgrade(proof_of(&consensus_path), proof_of(&client_path)).unwrap_err();
+        assert!(matches!(error_kind(err), Kind::InvalidUpgradeClientProof));
+        let err = upgrade(proof_of(&client_path), proof_of(&client_path)).unwrap_err();
+        assert!(matches!(
+            error_kind(err),
+            Kind::InvalidUpgradeConsensusStateProof
+        ));
+    }
+
+    #[test]
+    fn substitute_is_checked_against_subject() {
+        let subject = dummy_client_state();
+        let mut substitute = subject.clone();
+        substitute.chain_id = ChainId::new("substitute".to_string(), 1);
+        substitute.latest_height = subject.latest_height.increment();
+        substitute.trusting_period = subject.trusting_period / 2;
+
+        // The flags of the subject determine whether it can be recovered.
+        let err = check_substitute(Status::Expired, &subject, &substitute).unwrap_err();
+        assert!(matches!(err.kind(), Kind::UpdateAfterExpiryNotAllowed));
+        let err = check_substitute(Status::Active, &subject, &substitute).unwrap_err();
+        assert!(matches!(err.kind(), Kind::ClientNotRecoverable));
+
+        let frozen_height = subject.latest_height;
+        let mut subject = subject.with_frozen_height(frozen_height);
+        subject.allow_update.after_misbehaviour = true;
+        substitute.allow_update.after_misbehaviour = true;
+
+        // The recovered client tracks the chain of the substitute, from its latest height.
+        let recovered = check_substitute(Status::Frozen, &subject, &substitute).unwrap();
+        assert_eq!(recovered, substitute);
+
+        // The other parameters of the clients must match.
+        substitute.unbonding_period = 2 * subject.unbonding_period;
+        let err = check_substitute(Status::Frozen, &subject, &substitute).unwrap_err();
+        assert!(matches!(err.kind(), Kind::MismatchedSubstituteClientState));
     }
 }
@@ -10,13 +10,14 @@ use tendermint_proto::Protobuf;
 
 use ibc_proto::ibc::lightclients::tendermint::v1::{ClientState as RawClientState, Fraction};
 
-use crate::ics02_client::client_state::AnyClientState;
+use crate::ics02_client::client_consensus::AnyConsensusState;
+use crate::ics02_client::client_state::{AnyClientState, Status};
 use crate::ics02_client::client_type::ClientType;
 use crate::ics07_tendermint::error::{Error, Kind};
 use crate::ics07_tendermint::header::Header;
 use crate::ics23_commitment::specs::ProofSpecs;
 use crate::ics24_host::identifier::ChainId;
-use crate::timestamp::ZERO_DURATION;
+use crate::timestamp::{Timestamp, ZERO_DURATION};
 use crate::Height;
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -28,7 +29,7 @@ pub struct ClientState {
     pub max_clock_drift: Duration,
     pub frozen_height: Height,
     pub latest_height: Height,
-    // pub proof_specs: ::std::vec::Vec<super::super::super::super::ics23::ProofSpec>,
+    pub proof_specs: ProofSpecs,
     pub upgrade_path: Vec<String>,
     pub allow_update: AllowUpdate,
 }
@@ -51,6 +52,7 @@ impl ClientState {
         max_clock_drift: Duration,
         latest_height: Height,
         frozen_height: Height,
+        proof_specs: ProofSpecs,
         upgrade_path: Vec<String>,
         allow_update: AllowUpdate,
     ) -> Result<ClientState, Error> {
@@ -84,6 +86,13 @@ impl ClientState {
                 .into());
         }
 
+        // Basic validation for the proof_specs parameter.
+        if proof_specs.specs().is_empty() {
+            return Err(Kind::ValidationError
+                .context("ClientState proof specs cannot be empty")
+                .into());
+        }
+
         Ok(Self {
             chain_id,
             trust_level,
@@ -92,6 +101,7 @@ impl ClientState {
             max_clock_drift,
             frozen_height,
             latest_height,
+            proof_specs,
             upgrade_path,
             allow_update,
         })
@@ -111,6 +121,14 @@ impl ClientState {
         }
     }
 
+    /// Returns a copy of this client state, frozen at height `h`.
+    pub fn with_frozen_height(self, h: Height) -> Self {
+        ClientState {
+            frozen_height: h,
+            ..self
+        }
+    }
+
     /// Helper function to verify the upgrade client procedure.
     /// Resets all fields except the blockchain-specific ones.
     pub fn zero_custom_fields(mut client_state: Self) -> Self {
@@ -126,6 +144,81 @@ impl ClientState {
         client_state
     }
 
+    /// Checks that this client state, as stored by a counterparty chain, is a valid client of the
+    /// host chain, a Tendermint chain with identifier `host_chain_id` at height `host_height`.
+    /// The client must track the host at its current revision, below `host_height`, not be frozen,
+    /// and agree with the host on the unbonding period, the proof specifications and the upgrade
+    /// path, if it has one.
+    pub fn validate_self_client(
+        &self,
+        host_chain_id: &ChainId,
+        host_height: Height,
+        host_unbonding_period: Duration,
+        host_proof_specs: &ProofSpecs,
+        host_upgrade_path: &[String],
+    ) -> Result<(), Error> {
+        if !self.frozen_height.is_zero() {
+            return Err(Kind::FrozenClient.into());
+        }
+
+        if &self.chain_id != host_chain_id {
+            return Err(Kind::MismatchedChainId(
+                host_chain_id.to_string(),
+                self.chain_id.to_string(),
+            )
+            .into());
+        }
+
+        if self.latest_height.revision_number != host_height.revision_number {
+            return Err(Kind::MismatchedRevisions(
+                host_height.revision_number,
+                self.latest_height.revision_number,
+            )
+            .into());
+        }
+
+        // The block at the host height is not committed yet, hence cannot be tracked either.
+        if self.latest_height >= host_height {
+            return Err(Kind::ClientHeightFromFuture(self.latest_height, host_height).into());
+        }
+
+        let TrustThreshold {
+            numerator,
+            denominator,
+        } = self.trust_level;
+        if denominator == 0 || 3 * numerator < denominator || numerator > denominator {
+            return Err(Kind::InvalidTrustThreshold(numerator, denominator).into());
+        }
+
+        if self.unbonding_period != host_unbonding_period {
+            return Err(Kind::MismatchedUnbondingPeriod(
+                self.unbonding_period,
+                host_unbonding_period,
+            )
+            .into());
+        }
+
+        if self.trusting_period >= self.unbonding_period {
+            return Err(Kind::InvalidTrustingPeriod
+                .context("trusting period must be smaller than unbonding period")
+                .into());
+        }
+
+        if &self.proof_specs != host_proof_specs {
+            return Err(Kind::MismatchedProofSpecs.into());
+        }
+
+        if !self.upgrade_path.is_empty() && self.upgrade_path != host_upgrade_path {
+            return Err(Kind::MismatchedUpgradePath(
+                self.upgrade_path.clone(),
+                host_upgrade_path.to_vec(),
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+
     /// Get the refresh time to ensure the state does not expire
     pub fn refresh_time(&self) -> Option<Duration> {
         Some(2 * self.trusting_period / 3)
@@ -138,6 +231,36 @@ impl ClientState {
     }
 }
 
+/// Client states are generated with a trust threshold of 1/3 and with consistent periods, so that
+/// they pass validation, with the Cosmos-SDK proof specifications and without upgrade path.
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for ClientState {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let trusting_period = Duration::from_secs(u.int_in_range(1..=14 * 24 * 3600)?);
+        let unbonding_period = trusting_period + Duration::from_secs(u.int_in_range(1..=3600)?);
+
+        Self::new(
+            u.arbitrary()?,
+            TrustThreshold {
+                numerator: 1,
+                denominator: 3,
+            },
+            trusting_period,
+            unbonding_period,
+            Duration::from_secs(u.int_in_range(0..=60)?),
+            u.arbitrary()?,
+            Height::zero(),
+            ProofSpecs::cosmos(),
+            vec![],
+            AllowUpdate {
+                after_expiry: u.arbitrary()?,
+                after_misbehaviour: u.arbitrary()?,
+            },
+        )
+        .map_err(|_| arbitrary::Error::IncorrectFormat)
+    }
+}
+
 impl crate::ics02_client::client_state::ClientState for ClientState {
     fn chain_id(&self) -> ChainId {
         self.chain_id.clone()
@@ -156,6 +279,31 @@ impl crate::ics02_client::client_state::ClientState for ClientState {
         !self.frozen_height.is_zero()
     }
 
+    fn status(
+        &self,
+        host_timestamp: Timestamp,
+        latest_consensus_state: Option<&AnyConsensusState>,
+    ) -> Status {
+        if !self.frozen_height.is_zero() {
+            return Status::Frozen;
+        }
+
+        let latest_consensus_state = match latest_consensus_state {
+            Some(consensus_state) => consensus_state,
+            None => return Status::Expired,
+        };
+
+        // The expiry cannot be determined if the host timestamp is not set.
+        if host_timestamp == Timestamp::none() {
+            return Status::Unknown;
+        }
+
+        match host_timestamp.duration_since(&latest_consensus_state.timestamp()) {
+            Some(elapsed) if self.expired(elapsed) => Status::Expired,
+            _ => Status::Active,
+        }
+    }
+
     fn wrap_any(self) -> AnyClientState {
         AnyClientState::Tendermint(self)
     }
@@ -202,6 +350,7 @@ impl TryFrom<RawClientState> for ClientState {
                 .ok_or_else(|| Kind::InvalidRawClientState.context("missing frozen height"))?
                 .try_into()
                 .map_err(|_| Kind::InvalidRawHeight)?,
+            proof_specs: raw.proof_specs.into(),
             upgrade_path: raw.upgrade_path,
             allow_update: AllowUpdate {
                 after_expiry: raw.allow_update_after_expiry,
@@ -224,9 +373,9 @@ impl From<ClientState> for RawClientState {
             max_clock_drift: Some(value.max_clock_drift.into()),
             frozen_height: Some(value.frozen_height.into()),
             latest_height: Some(value.latest_height.into()),
-            proof_specs: ProofSpecs::cosmos().into(),
-            allow_update_after_expiry: false,
-            allow_update_after_misbehaviour: false,
+            proof_specs: value.proof_specs.into(),
+            allow_update_after_expiry: value.allow_update.after_expiry,
+            allow_update_after_misbehaviour: value.allow_update.after_misbehaviour,
             upgrade_path: value.upgrade_path,
         }
     }
@@ -238,12 +387,21 @@ mod tests {
     use test_env_log::test;
 
     use tendermint::trust_threshold::TrustThresholdFraction as TrustThreshold;
+    use tendermint_proto::Protobuf;
     use tendermint_rpc::endpoint::abci_query::AbciQuery;
 
+    use crate::ics02_client::client_consensus::AnyConsensusState;
+    use crate::ics02_client::client_state::{
+        AnyClientState, ClientState as ClientStateTrait, Status,
+    };
+    use crate::ics07_tendermint::client_state::test_util::get_dummy_tendermint_client_state;
     use crate::ics07_tendermint::client_state::{AllowUpdate, ClientState};
+    use crate::ics07_tendermint::consensus_state::ConsensusState;
+    use crate::ics07_tendermint::header::test_util::get_dummy_tendermint_header;
+    use crate::ics23_commitment::specs::ProofSpecs;
     use crate::ics24_host::identifier::ChainId;
     use crate::test::test_serialization_roundtrip;
-    use crate::timestamp::ZERO_DURATION;
+    use crate::timestamp::{Timestamp, ZERO_DURATION};
     use crate::Height;
 
     #[test]
@@ -272,6 +430,7 @@ mod tests {
             max_clock_drift: Duration,
             latest_height: Height,
             frozen_height: Height,
+            proof_specs: ProofSpecs,
             upgrade_path: Vec<String>,
             allow_update: AllowUpdate,
         }
@@ -288,6 +447,7 @@ mod tests {
             max_clock_drift: Duration::new(3, 0),
             latest_height: Height::new(0, 10),
             frozen_height: Height::default(),
+            proof_specs: ProofSpecs::cosmos(),
             upgrade_path: vec!["".to_string()],
             allow_update: AllowUpdate {
                 after_expiry: false,
@@ -336,6 +496,14 @@ mod tests {
                 params: ClientStateParams {
                     trusting_period: Duration::new(11, 0),
                     unbonding_period: Duration::new(10, 0),
+                    ..default_params.clone()
+                },
+                want_pass: false,
+            },
+            Test {
+                name: "Invalid (empty) proof specs".to_string(),
+                params: ClientStateParams {
+                    proof_specs: ProofSpecs::from(Vec::<ics23::ProofSpec>::new()),
                     ..default_params
                 },
                 want_pass: false,
@@ -355,6 +523,7 @@ mod tests {
                 p.max_clock_drift,
                 p.latest_height,
                 p.frozen_height,
+                p.proof_specs,
                 p.upgrade_path,
                 p.allow_update,
             );
@@ -369,6 +538,164 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn proof_specs_survive_encoding() {
+        let client_state = match get_dummy_tendermint_client_state(get_dummy_tendermint_header()) {
+            AnyClientState::Tendermint(client_state) => ClientState {
+                proof_specs: ProofSpecs::from(vec![ics23::tendermint_spec()]),
+                ..client_state
+            },
+            _ => unreachable!(),
+        };
+
+        let decoded = ClientState::decode_vec(&client_state.encode_vec().unwrap()).unwrap();
+        assert_eq!(decoded, client_state);
+
+        let json = serde_json::to_string(&client_state).unwrap();
+        assert_eq!(
+            serde_json::from_str::<ClientState>(&json).unwrap(),
+            client_state
+        );
+    }
+
+    #[test]
+    fn client_status() {
+        let header = get_dummy_tendermint_header();
+        let client_state = match get_dummy_tendermint_client_state(header.clone()) {
+            AnyClientState::Tendermint(client_state) => client_state,
+            _ => unreachable!(),
+        };
+        let consensus_state = AnyConsensusState::Tendermint(ConsensusState::from(header));
+        let consensus_timestamp = consensus_state.timestamp();
+        let after = |duration| (consensus_timestamp + duration).unwrap();
+
+        let status = |host_timestamp, consensus_state| {
+            ClientStateTrait::status(&client_state, host_timestamp, consensus_state)
+        };
+
+        // The trusting period of the dummy client state is 64000 seconds.
+        assert_eq!(
+            status(after(Duration::from_secs(100)), Some(&consensus_state)),
+            Status::Active
+        );
+        assert_eq!(
+            status(after(Duration::from_secs(64001)), Some(&consensus_state)),
+            Status::Expired
+        );
+        // The expiry cannot be determined without a host timestamp.
+        assert_eq!(
+            status(Timestamp::none(), Some(&consensus_state)),
+            Status::Unknown
+        );
+        // A client without its latest consensus state cannot verify anything.
+        assert_eq!(
+            status(after(Duration::from_secs(100)), None),
+            Status::Expired
+        );
+
+        let frozen = client_state.clone().with_frozen_height(Height::new(0, 1));
+        assert_eq!(
+            ClientStateTrait::status(
+                &frozen,
+                after(Duration::from_secs(100)),
+                Some(&consensus_state)
+            ),
+            Status::Frozen
+        );
+    }
+
+    #[test]
+    fn validate_self_client() {
+        let host_chain_id = ChainId::new("ibc".to_string(), 1);
+        let host_height = Height::new(1, 20);
+        let host_unbonding_period = Duration::new(128000, 0);
+        let host_upgrade_path = vec!["upgrade".to_string(), "upgradedIBCState".to_string()];
+
+        let client_state = ClientState::new(
+            host_chain_id.clone(),
+            TrustThreshold {
+                numerator: 1,
+                denominator: 3,
+            },
+            Duration::new(64000, 0),
+            host_unbonding_period,
+            Duration::new(3, 0),
+            Height::new(1, 10),
+            Height::zero(),
+            ProofSpecs::cosmos(),
+            host_upgrade_path.clone(),
+            AllowUpdate {
+                after_expiry: false,
+                after_misbehaviour: false,
+            },
+        )
+        .unwrap();
+
+        let validate = |client_state: &ClientState| {
+            client_state.validate_self_client(
+                &host_chain_id,
+                host_height,
+                host_unbonding_period,
+                &ProofSpecs::cosmos(),
+                &host_upgrade_path,
+            )
+        };
+
+        assert!(validate(&client_state).is_ok());
+        // A client without an upgrade path cannot be upgraded, but is otherwise valid.
+        assert!(validate(&ClientState {
+            upgrade_path: vec![],
+            ..client_state.clone()
+        })
+        .is_ok());
+
+        let invalid_client_states = vec![
+            client_state.clone().with_frozen_height(Height::new(1, 5)),
+            ClientState {
+                chain_id: ChainId::new("other".to_string(), 1),
+                ..client_state.clone()
+            },
+            ClientState {
+                latest_height: Height::new(0, 10),
+                ..client_state.clone()
+            },
+            ClientState {
+                latest_height: host_height,
+                ..client_state.clone()
+            },
+            ClientState {
+                latest_height: host_height.increment(),
+                ..client_state.clone()
+            },
+            ClientState {
+                trust_level: TrustThreshold {
+                    numerator: 1,
+                    denominator: 4,
+                },
+                ..client_state.clone()
+            },
+            ClientState {
+                unbonding_period: Duration::new(64001, 0),
+                ..client_state.clone()
+            },
+            ClientState {
+                proof_specs: ProofSpecs::from(vec![ics23::tendermint_spec()]),
+                ..client_state.clone()
+            },
+            ClientState {
+                upgrade_path: vec!["upgrade".to_string()],
+                ..client_state
+            },
+        ];
+        for client_state in invalid_client_states {
+            assert!(
+                validate(&client_state).is_err(),
+                "invalid self client was accepted: {:?}",
+                client_state
+            );
+        }
+    }
 }
 
 #[cfg(any(test, feature = "mocks"))]
@@ -380,6 +707,7 @@ pub mod test_util {
     use crate::ics02_client::client_state::AnyClientState;
     use crate::ics02_client::height::Height;
     use crate::ics07_tendermint::client_state::{AllowUpdate, ClientState};
+    use crate::ics23_commitment::specs::ProofSpecs;
     use crate::ics24_host::identifier::ChainId;
 
     pub fn get_dummy_tendermint_client_state(tm_header: Header) -> AnyClientState {
@@ -395,6 +723,7 @@ pub mod test_util {
                     u64::from(tm_header.height),
                 ),
                 Height::zero(),
+                ProofSpecs::cosmos(),
                 vec!["".to_string()],
                 AllowUpdate {
                     after_expiry: false,
@@ -1,5 +1,5 @@
 use std::convert::TryFrom;
-use std::time::SystemTime;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use chrono::{TimeZone, Utc};
 use prost_types::Timestamp;
@@ -42,7 +42,17 @@ impl crate::ics02_client::client_consensus::ConsensusState for ConsensusState {
     }
 
     fn validate_basic(&self) -> Result<(), Box<dyn std::error::Error>> {
-        unimplemented!()
+        if self.root.as_bytes().is_empty() {
+            return Err(Kind::InvalidRawConsensusState
+                .context("root cannot be empty")
+                .into());
+        }
+        if SystemTime::from(self.timestamp) <= UNIX_EPOCH {
+            return Err(Kind::InvalidRawConsensusState
+                .context("timestamp must be a positive Unix time")
+                .into());
+        }
+        Ok(())
     }
 
     fn wrap_any(self) -> AnyConsensusState {
@@ -50,6 +60,25 @@ impl crate::ics02_client::client_consensus::ConsensusState for ConsensusState {
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for ConsensusState {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let root: [u8; 32] = u.arbitrary()?;
+        let next_validators_hash: [u8; 32] = u.arbitrary()?;
+        let timestamp = Utc
+            .timestamp_opt(u.int_in_range(1..=i32::MAX as i64)?, 0)
+            .single()
+            .ok_or(arbitrary::Error::IncorrectFormat)?;
+
+        Ok(Self::new(
+            CommitmentRoot::from_bytes(&root),
+            timestamp.into(),
+            Hash::from_bytes(Algorithm::Sha256, &next_validators_hash)
+                .map_err(|_| arbitrary::Error::IncorrectFormat)?,
+        ))
+    }
+}
+
 impl Protobuf<RawConsensusState> for ConsensusState {}
 
 impl TryFrom<RawConsensusState> for ConsensusState {
@@ -67,7 +96,9 @@ impl TryFrom<RawConsensusState> for ConsensusState {
                 .hash
                 .into(),
             timestamp: Utc
-                .timestamp(proto_timestamp.seconds, proto_timestamp.nanos as u32)
+                .timestamp_opt(proto_timestamp.seconds, proto_timestamp.nanos as u32)
+                .single()
+                .ok_or_else(|| Kind::InvalidRawConsensusState.context("invalid timestamp"))?
                 .into(),
             next_validators_hash: Hash::from_bytes(Algorithm::Sha256, &raw.next_validators_hash)
                 .map_err(|e| Kind::InvalidRawConsensusState.context(e.to_string()))?,
@@ -1,7 +1,11 @@
+use std::time::Duration;
+
 use anomaly::{BoxError, Context};
 use thiserror::Error;
 
 use crate::ics24_host::error::ValidationKind;
+use crate::timestamp::Timestamp;
+use crate::Height;
 
 pub type Error = anomaly::Error<Kind>;
 
@@ -39,6 +43,113 @@ pub enum Kind {
 
     #[error("invalid raw misbehaviour")]
     InvalidRawMisbehaviour,
+
+    #[error("the client latest height {0} is lower than the proof height {1}")]
+    InsufficientHeight(Height, Height),
+
+    #[error("the client is frozen at height {0}, cannot verify proofs at height {1}")]
+    FrozenHeight(Height, Height),
+
+    #[error("invalid commitment proof bytes")]
+    InvalidCommitmentProof,
+
+    #[error("invalid merkle proof")]
+    InvalidMerkleProof,
+
+    #[error("the number of proofs ({0}) does not match the number of proof specs ({1})")]
+    NumberOfSpecsMismatch(usize, usize),
+
+    #[error("the number of proofs ({0}) does not match the number of keys ({1})")]
+    NumberOfKeysMismatch(usize, usize),
+
+    #[error("commitment proof verification failed")]
+    VerificationFailure,
+
+    #[error("the client is frozen")]
+    FrozenClient,
+
+    #[error("the header chain identifier {1} does not match the client chain identifier {0}")]
+    MismatchedChainId(String, String),
+
+    #[error("the header revision number {1} does not match the client revision number {0}")]
+    MismatchedRevisions(u64, u64),
+
+    #[error("the header height {0} must be greater than the trusted height {1}")]
+    InvalidTrustedHeight(Height, Height),
+
+    #[error("no consensus state found at the trusted height {0}")]
+    MissingTrustedConsensusState(Height),
+
+    #[error("the host timestamp is not set")]
+    MissingHostTimestamp,
+
+    #[error("the trusted consensus state expired at {0}, the host time is {1}")]
+    TrustedStateExpired(Timestamp, Timestamp),
+
+    #[error("the header timestamp {0} exceeds the host time plus the maximum clock drift {1}")]
+    HeaderFromFuture(Timestamp, Timestamp),
+
+    #[error("the header timestamp {0} is not after the trusted timestamp {1}")]
+    NonMonotonicHeaderTime(Timestamp, Timestamp),
+
+    #[error("invalid validator set")]
+    InvalidValidatorSet,
+
+    #[error("header verification failed")]
+    HeaderVerificationFailure,
+
+    #[error("cannot upgrade the client, as it has no upgrade path")]
+    MissingUpgradePath,
+
+    #[error("the upgraded client height {1} must be greater than the client latest height {0}")]
+    LowUpgradeHeight(Height, Height),
+
+    #[error("no consensus state found at the client latest height {0}")]
+    MissingLatestConsensusState(Height),
+
+    #[error("invalid proof for the upgraded client state")]
+    InvalidUpgradeClientProof,
+
+    #[error("invalid proof for the upgraded consensus state")]
+    InvalidUpgradeConsensusStateProof,
+
+    #[error("the client latest height {0} is not lower than the host height {1}")]
+    ClientHeightFromFuture(Height, Height),
+
+    #[error("the trust level {0}/{1} is not within [1/3, 1]")]
+    InvalidTrustThreshold(u64, u64),
+
+    #[error("the client unbonding period {0:?} does not match the host unbonding period {1:?}")]
+    MismatchedUnbondingPeriod(Duration, Duration),
+
+    #[error("the client proof specifications do not match the host proof specifications")]
+    MismatchedProofSpecs,
+
+    #[error("the client upgrade path {0:?} does not match the host upgrade path {1:?}")]
+    MismatchedUpgradePath(Vec<String>, Vec<String>),
+
+    #[error("the misbehaviour header1 height {0} is lower than the header2 height {1}")]
+    MisbehaviourHeadersHeightMismatch(Height, Height),
+
+    #[error("the misbehaviour headers at the same height have the same block hash")]
+    MisbehaviourHeadersBlockHashEqual,
+
+    #[error("the misbehaviour headers at different heights have monotonically increasing time")]
+    MisbehaviourHeadersNotConflicting,
+
+    #[error("the client cannot be recovered after expiry, as its parameters do not allow it")]
+    UpdateAfterExpiryNotAllowed,
+
+    #[error(
+        "the client cannot be recovered after misbehaviour, as its parameters do not allow it"
+    )]
+    UpdateAfterMisbehaviourNotAllowed,
+
+    #[error("the client cannot be recovered, as it is neither frozen nor expired")]
+    ClientNotRecoverable,
+
+    #[error("the substitute client parameters do not match the subject client parameters")]
+    MismatchedSubstituteClientState,
 }
 
 impl Kind {
@@ -76,6 +76,22 @@ impl crate::ics02_client::header::Header for Header {
     }
 }
 
+/// Headers are synthetic light blocks signed by a single validator, as generated for the mock
+/// Tendermint chains, so that they are well-formed and validly signed.
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Header {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let chain_id: ChainId = u.arbitrary()?;
+        let height = u.int_in_range(1..=32)?;
+        let light_block = crate::mock::host::HostBlock::generate_tm_block(chain_id, height);
+
+        Ok(Header {
+            trusted_height: u.arbitrary()?,
+            ..Header::from(light_block)
+        })
+    }
+}
+
 impl Protobuf<RawHeader> for Header {}
 
 impl TryFrom<RawHeader> for Header {
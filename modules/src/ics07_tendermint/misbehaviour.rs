@@ -11,6 +11,7 @@ use crate::ics24_host::identifier::ClientId;
 use crate::Height;
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Misbehaviour {
     pub client_id: ClientId,
     pub header1: Header,
@@ -0,0 +1,629 @@
+use ibc_proto::ibc::core::commitment::v1::MerkleProof;
+
+use crate::ics02_client::client_consensus::AnyConsensusState;
+use crate::ics02_client::client_def::ClientDef;
+use crate::ics02_client::client_state::AnyClientState;
+use crate::ics02_client::context::ClientReader;
+use crate::ics02_client::misbehaviour::AnyMisbehaviour;
+use crate::ics03_connection::connection::ConnectionEnd;
+use crate::ics03_connection::context::ConnectionReader;
+use crate::ics04_channel::channel::ChannelEnd;
+use crate::ics04_channel::commitment::{AcknowledgementCommitment, PacketCommitment};
+use crate::ics04_channel::context::ChannelReader;
+use crate::ics04_channel::packet::Sequence;
+use crate::ics09_localhost::client_state::ClientState;
+use crate::ics09_localhost::consensus_state::ConsensusState;
+use crate::ics09_localhost::error::{Error, Kind};
+use crate::ics09_localhost::header::Header;
+use crate::ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes, CommitmentRoot};
+use crate::ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId};
+use crate::ics24_host::Path;
+use crate::Height;
+
+/// A client of the host chain itself. Instead of verifying proofs against the commitment root of
+/// a consensus state, the verification functions read the values from the store of the host, as
+/// exposed by the context. Proofs, prefixes and roots are ignored.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LocalhostClient;
+
+impl ClientDef for LocalhostClient {
+    type Header = Header;
+    type ClientState = ClientState;
+    type ConsensusState = ConsensusState;
+    type Misbehaviour = AnyMisbehaviour;
+
+    fn check_header_and_update_state(
+        &self,
+        ctx: &dyn ClientReader,
+        _client_id: ClientId,
+        client_state: Self::ClientState,
+        header: Self::Header,
+    ) -> Result<(Self::ClientState, Self::ConsensusState), Box<dyn std::error::Error>> {
+        let latest_height = client_state.latest_height();
+        if header.height <= latest_height {
+            return Err(Error::from(Kind::LowHeaderHeight(header.height, latest_height)).into());
+        }
+
+        let host_height = ctx.host_height();
+        if header.height != host_height {
+            return Err(Error::from(Kind::HeaderHeightMismatch(host_height, header.height)).into());
+        }
+
+        Ok((
+            client_state.with_height(header.height),
+            ConsensusState::new(ctx.host_timestamp()),
+        ))
+    }
+
+    fn check_misbehaviour_and_update_state(
+        &self,
+        _ctx: &dyn ClientReader,
+        _client_id: ClientId,
+        _client_state: Self::ClientState,
+        _misbehaviour: Self::Misbehaviour,
+    ) -> Result<Self::ClientState, Box<dyn std::error::Error>> {
+        Err(Error::from(Kind::MisbehaviourNotSupported).into())
+    }
+
+    /// The consensus state of the client `client_id` at `consensus_height` is read from the host
+    /// store and compared with the expected one. A localhost client records only the timestamp of
+    /// the host, hence its consensus state matches if it has the timestamp of the expected one.
+    fn verify_client_consensus_state(
+        &self,
+        ctx: &dyn ConnectionReader,
+        _client_state: &Self::ClientState,
+        _height: Height,
+        _prefix: &CommitmentPrefix,
+        _proof: &CommitmentProofBytes,
+        _root: &CommitmentRoot,
+        client_id: &ClientId,
+        consensus_height: Height,
+        expected_consensus_state: &AnyConsensusState,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let path = Path::ClientConsensusState {
+            client_id: client_id.clone(),
+            epoch: consensus_height.revision_number,
+            height: consensus_height.revision_height,
+        };
+
+        let consensus_state = ctx
+            .client_consensus_state(client_id, consensus_height)
+            .ok_or_else(|| -> Error { Kind::MissingValue(path.to_string()).into() })?;
+
+        let matches = match &consensus_state {
+            AnyConsensusState::Localhost(cs) => {
+                cs.timestamp() == expected_consensus_state.timestamp()
+            }
+            cs => cs == expected_consensus_state,
+        };
+        if !matches {
+            return Err(Error::from(Kind::ValueMismatch(path.to_string())).into());
+        }
+
+        Ok(())
+    }
+
+    fn verify_connection_state(
+        &self,
+        ctx: &dyn ConnectionReader,
+        _client_state: &Self::ClientState,
+        _height: Height,
+        _prefix: &CommitmentPrefix,
+        _proof: &CommitmentProofBytes,
+        _root: &CommitmentRoot,
+        connection_id: Option<&ConnectionId>,
+        expected_connection_end: &ConnectionEnd,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let connection_id =
+            connection_id.ok_or_else(|| -> Error { Kind::MissingConnectionId.into() })?;
+        let path = Path::Connections(connection_id.clone());
+
+        Ok(check_value(
+            path,
+            ctx.connection_end(connection_id),
+            expected_connection_end,
+        )?)
+    }
+
+    fn verify_channel_state(
+        &self,
+        ctx: &dyn ChannelReader,
+        _client_state: &Self::ClientState,
+        _height: Height,
+        _prefix: &CommitmentPrefix,
+        _proof: &CommitmentProofBytes,
+        _root: &CommitmentRoot,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        expected_channel_end: &ChannelEnd,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let path = Path::ChannelEnds(port_id.clone(), channel_id.clone());
+
+        Ok(check_value(
+            path,
+            ctx.channel_end(&(port_id.clone(), channel_id.clone())),
+            expected_channel_end,
+        )?)
+    }
+
+    fn verify_client_full_state(
+        &self,
+        ctx: &dyn ConnectionReader,
+        _client_state: &Self::ClientState,
+        _height: Height,
+        _root: &CommitmentRoot,
+        _prefix: &CommitmentPrefix,
+        client_id: &ClientId,
+        _proof: &CommitmentProofBytes,
+        expected_client_state: &AnyClientState,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let path = Path::ClientState(client_id.clone());
+
+        Ok(check_value(
+            path,
+            ctx.client_state(client_id),
+            expected_client_state,
+        )?)
+    }
+
+    fn verify_packet_data(
+        &self,
+        ctx: &dyn ChannelReader,
+        _client_state: &Self::ClientState,
+        _height: Height,
+        _prefix: &CommitmentPrefix,
+        _proof: &CommitmentProofBytes,
+        _root: &CommitmentRoot,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        seq: &Sequence,
+        commitment: PacketCommitment,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let path = Path::Commitments {
+            port_id: port_id.clone(),
+            channel_id: channel_id.clone(),
+            sequence: *seq,
+        };
+
+        Ok(check_value(
+            path,
+            ctx.get_packet_commitment(&(port_id.clone(), channel_id.clone(), *seq)),
+            &commitment,
+        )?)
+    }
+
+    fn verify_packet_acknowledgement(
+        &self,
+        ctx: &dyn ChannelReader,
+        _client_state: &Self::ClientState,
+        _height: Height,
+        _prefix: &CommitmentPrefix,
+        _proof: &CommitmentProofBytes,
+        _root: &CommitmentRoot,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        seq: &Sequence,
+        ack: Vec<u8>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let path = Path::Acks {
+            port_id: port_id.clone(),
+            channel_id: channel_id.clone(),
+            sequence: *seq,
+        };
+
+        Ok(check_value(
+            path,
+            ctx.get_packet_acknowledgement(&(port_id.clone(), channel_id.clone(), *seq)),
+            &AcknowledgementCommitment::compute(&ack),
+        )?)
+    }
+
+    fn verify_next_sequence_recv(
+        &self,
+        ctx: &dyn ChannelReader,
+        _client_state: &Self::ClientState,
+        _height: Height,
+        _prefix: &CommitmentPrefix,
+        _proof: &CommitmentProofBytes,
+        _root: &CommitmentRoot,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        seq: &Sequence,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let path = Path::SeqRecvs(port_id.clone(), channel_id.clone());
+
+        Ok(check_value(
+            path,
+            ctx.get_next_sequence_recv(&(port_id.clone(), channel_id.clone())),
+            seq,
+        )?)
+    }
+
+    fn verify_packet_receipt_absence(
+        &self,
+        ctx: &dyn ChannelReader,
+        _client_state: &Self::ClientState,
+        _height: Height,
+        _prefix: &CommitmentPrefix,
+        _proof: &CommitmentProofBytes,
+        _root: &CommitmentRoot,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        seq: &Sequence,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let path = Path::Receipts {
+            port_id: port_id.clone(),
+            channel_id: channel_id.clone(),
+            sequence: *seq,
+        };
+
+        match ctx.get_packet_receipt(&(port_id.clone(), channel_id.clone(), *seq)) {
+            Some(_) => Err(Error::from(Kind::UnexpectedValue(path.to_string())).into()),
+            None => Ok(()),
+        }
+    }
+
+    fn verify_upgrade_and_update_state(
+        &self,
+        _ctx: &dyn ClientReader,
+        _client_id: &ClientId,
+        _client_state: &Self::ClientState,
+        _upgraded_client_state: &Self::ClientState,
+        _upgraded_consensus_state: &Self::ConsensusState,
+        _proof_upgrade_client: MerkleProof,
+        _proof_upgrade_consensus_state: MerkleProof,
+    ) -> Result<(Self::ClientState, Self::ConsensusState), Box<dyn std::error::Error>> {
+        Err(Error::from(Kind::UpgradeNotSupported).into())
+    }
+
+    fn check_substitute_and_update_state(
+        &self,
+        _ctx: &dyn ClientReader,
+        _subject_client_id: &ClientId,
+        _subject_client_state: &Self::ClientState,
+        _substitute_client_state: &Self::ClientState,
+    ) -> Result<Self::ClientState, Box<dyn std::error::Error>> {
+        Err(Error::from(Kind::RecoveryNotSupported).into())
+    }
+}
+
+/// Checks that the `value` read from the host store under `path` is equal to `expected`.
+fn check_value<T: PartialEq>(path: Path, value: Option<T>, expected: &T) -> Result<(), Error> {
+    match value {
+        None => Err(Kind::MissingValue(path.to_string()).into()),
+        Some(value) if &value != expected => Err(Kind::ValueMismatch(path.to_string()).into()),
+        Some(_) => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use std::time::Duration;
+    use test_env_log::test;
+
+    use crate::application::ics20_fungible_token_transfer::module::{
+        Ics20Module, MODULE_ID, VERSION,
+    };
+    use crate::ics02_client::client_consensus::AnyConsensusState;
+    use crate::ics02_client::client_def::ClientDef;
+    use crate::ics02_client::client_type::ClientType;
+    use crate::ics02_client::context::ClientReader;
+    use crate::ics02_client::header::AnyHeader;
+    use crate::ics02_client::msgs::update_client::MsgUpdateAnyClient;
+    use crate::ics02_client::msgs::ClientMsg;
+    use crate::ics03_connection::connection::{
+        Counterparty as ConnectionCounterparty, State as ConnectionState,
+    };
+    use crate::ics03_connection::context::ConnectionReader;
+    use crate::ics03_connection::msgs::conn_open_ack::MsgConnectionOpenAck;
+    use crate::ics03_connection::msgs::conn_open_confirm::MsgConnectionOpenConfirm;
+    use crate::ics03_connection::msgs::conn_open_init::MsgConnectionOpenInit;
+    use crate::ics03_connection::msgs::conn_open_try::MsgConnectionOpenTry;
+    use crate::ics03_connection::msgs::ConnectionMsg;
+    use crate::ics04_channel::channel::{ChannelEnd, Counterparty, Order, State};
+    use crate::ics04_channel::context::ChannelReader;
+    use crate::ics04_channel::msgs::chan_open_ack::MsgChannelOpenAck;
+    use crate::ics04_channel::msgs::chan_open_confirm::MsgChannelOpenConfirm;
+    use crate::ics04_channel::msgs::chan_open_init::MsgChannelOpenInit;
+    use crate::ics04_channel::msgs::chan_open_try::MsgChannelOpenTry;
+    use crate::ics04_channel::msgs::ChannelMsg;
+    use crate::ics05_port::capabilities::ModuleId;
+    use crate::ics09_localhost::client_def::LocalhostClient;
+    use crate::ics09_localhost::client_state::ClientState;
+    use crate::ics09_localhost::consensus_state::ConsensusState;
+    use crate::ics09_localhost::error::{Error, Kind};
+    use crate::ics09_localhost::header::Header;
+    use crate::ics23_commitment::commitment::{CommitmentPrefix, CommitmentRoot};
+    use crate::ics24_host::identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId};
+    use crate::ics26_routing::msgs::Ics26Envelope;
+    use crate::mock::context::MockContext;
+    use crate::mock::host::HostType;
+    use crate::proofs::{ConsensusProof, Proofs};
+    use crate::test_utils::{get_dummy_account_id, get_dummy_proof};
+    use crate::timestamp::Timestamp;
+    use crate::Height;
+
+    fn error_kind(err: Box<dyn std::error::Error>) -> Kind {
+        err.downcast_ref::<Error>()
+            .expect("expected a localhost client error")
+            .kind()
+            .clone()
+    }
+
+    #[test]
+    fn localhost_client_update() {
+        let chain_id = ChainId::new("localhost".to_string(), 1);
+        let ctx = MockContext::new(chain_id.clone(), HostType::Mock, 5, Height::new(1, 10));
+        let client_state = ClientState::new(chain_id, Height::new(1, 8));
+
+        // A localhost client can be updated to the host height only.
+        let (new_state, _) = LocalhostClient
+            .check_header_and_update_state(
+                &ctx,
+                ClientId::default(),
+                client_state.clone(),
+                Header::new(Height::new(1, 10)),
+            )
+            .unwrap();
+        assert_eq!(new_state.latest_height(), Height::new(1, 10));
+
+        let err = LocalhostClient
+            .check_header_and_update_state(
+                &ctx,
+                ClientId::default(),
+                client_state.clone(),
+                Header::new(Height::new(1, 9)),
+            )
+            .unwrap_err();
+        assert!(matches!(error_kind(err), Kind::HeaderHeightMismatch(_, _)));
+
+        let err = LocalhostClient
+            .check_header_and_update_state(
+                &ctx,
+                ClientId::default(),
+                client_state,
+                Header::new(Height::new(1, 7)),
+            )
+            .unwrap_err();
+        assert!(matches!(error_kind(err), Kind::LowHeaderHeight(_, _)));
+    }
+
+    #[test]
+    fn localhost_client_verifies_consensus_state() {
+        let chain_id = ChainId::new("localhost".to_string(), 1);
+        let client_id = ClientId::new(ClientType::Localhost, 0).unwrap();
+        let height = Height::new(1, 5);
+        let timestamp = Timestamp::from_nanoseconds(10).unwrap();
+
+        let ctx = MockContext::new(chain_id.clone(), HostType::Mock, 5, height)
+            .with_timestamp(timestamp)
+            .with_client_parametrized(&client_id, height, Some(ClientType::Localhost), None);
+        let client_state = ClientState::new(chain_id, height);
+
+        let verify = |consensus_height: Height, expected: ConsensusState| {
+            LocalhostClient.verify_client_consensus_state(
+                &ctx,
+                &client_state,
+                height,
+                &CommitmentPrefix::from(vec![]),
+                &get_dummy_proof().into(),
+                &CommitmentRoot::from(vec![]),
+                &client_id,
+                consensus_height,
+                &AnyConsensusState::Localhost(expected),
+            )
+        };
+
+        assert!(verify(height, ConsensusState::new(timestamp)).is_ok());
+
+        let other_timestamp = Timestamp::from_nanoseconds(11).unwrap();
+        let err = verify(height, ConsensusState::new(other_timestamp)).unwrap_err();
+        assert!(matches!(error_kind(err), Kind::ValueMismatch(_)));
+
+        let err = verify(Height::new(1, 4), ConsensusState::new(timestamp)).unwrap_err();
+        assert!(matches!(error_kind(err), Kind::MissingValue(_)));
+    }
+
+    /// Produces a new block on the host chain, and updates the localhost client `client_id` to it.
+    /// Returns the height the client was updated to. The block carries a timestamp of its own,
+    /// such that the consensus state of the host at this height is the one the client records.
+    fn update_localhost(ctx: &mut MockContext, client_id: &ClientId) -> Height {
+        let next_height = ClientReader::host_height(ctx).increment();
+        let timestamp = Timestamp::from_nanoseconds(next_height.revision_height).unwrap();
+        ctx.advance_host_chain_height_at(timestamp);
+
+        let height = ClientReader::host_height(ctx);
+        let msg = MsgUpdateAnyClient::new(
+            client_id.clone(),
+            AnyHeader::Localhost(Header::new(height)),
+            get_dummy_account_id(),
+        );
+        ctx.deliver(Ics26Envelope::Ics2Msg(ClientMsg::UpdateClient(msg)))
+            .unwrap();
+        height
+    }
+
+    /// Returns the proofs of a loopback handshake message at `height`. The localhost client
+    /// ignores the proof bytes.
+    fn loopback_proofs(height: Height, with_client_proofs: bool) -> Proofs {
+        let (client_proof, consensus_proof) = if with_client_proofs {
+            (
+                Some(get_dummy_proof().into()),
+                Some(ConsensusProof::new(get_dummy_proof().into(), height).unwrap()),
+            )
+        } else {
+            (None, None)
+        };
+
+        Proofs::new(
+            get_dummy_proof().into(),
+            client_proof,
+            consensus_proof,
+            None,
+            height,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn loopback_connection_and_channel_handshake() {
+        let chain_id = ChainId::new("localhost".to_string(), 1);
+        let client_id = ClientId::new(ClientType::Localhost, 0).unwrap();
+        let port_a = PortId::from_str("transfer").unwrap();
+        let port_b = PortId::from_str("loopback").unwrap();
+        let version = VERSION.to_string();
+
+        let mut ctx = MockContext::new(chain_id, HostType::Mock, 10, Height::new(1, 5))
+            .with_client_parametrized(
+                &client_id,
+                Height::new(1, 5),
+                Some(ClientType::Localhost),
+                None,
+            )
+            .with_module(ModuleId::new(MODULE_ID), port_a.clone(), Ics20Module)
+            .with_module(ModuleId::new(MODULE_ID), port_b.clone(), Ics20Module);
+
+        let conn_a = ConnectionId::new(0);
+        let conn_b = ConnectionId::new(1);
+        let chan_a = ChannelId::new(0);
+        let chan_b = ChannelId::new(1);
+
+        // The connection handshake, between two connection ends on top of the same client.
+        let msg = MsgConnectionOpenInit {
+            client_id: client_id.clone(),
+            counterparty: ConnectionCounterparty::new(
+                client_id.clone(),
+                None,
+                ConnectionReader::commitment_prefix(&ctx),
+            ),
+            version: ConnectionReader::get_compatible_versions(&ctx)[0].clone(),
+            delay_period: Duration::from_secs(0),
+            signer: get_dummy_account_id(),
+        };
+        ctx.deliver(Ics26Envelope::Ics3Msg(ConnectionMsg::ConnectionOpenInit(
+            msg,
+        )))
+        .unwrap();
+
+        let height = update_localhost(&mut ctx, &client_id);
+        let msg = MsgConnectionOpenTry {
+            previous_connection_id: None,
+            client_id: client_id.clone(),
+            client_state: ClientReader::client_state(&ctx, &client_id),
+            counterparty: ConnectionCounterparty::new(
+                client_id.clone(),
+                Some(conn_a.clone()),
+                ConnectionReader::commitment_prefix(&ctx),
+            ),
+            counterparty_versions: ConnectionReader::get_compatible_versions(&ctx),
+            proofs: loopback_proofs(height, true),
+            delay_period: Duration::from_secs(0),
+            signer: get_dummy_account_id(),
+        };
+        ctx.deliver(Ics26Envelope::Ics3Msg(ConnectionMsg::ConnectionOpenTry(
+            Box::new(msg),
+        )))
+        .unwrap();
+
+        let height = update_localhost(&mut ctx, &client_id);
+        let msg = MsgConnectionOpenAck {
+            connection_id: conn_a.clone(),
+            counterparty_connection_id: conn_b.clone(),
+            client_state: ClientReader::client_state(&ctx, &client_id),
+            proofs: loopback_proofs(height, true),
+            version: ConnectionReader::connection_end(&ctx, &conn_b)
+                .unwrap()
+                .versions()[0]
+                .clone(),
+            signer: get_dummy_account_id(),
+        };
+        ctx.deliver(Ics26Envelope::Ics3Msg(ConnectionMsg::ConnectionOpenAck(
+            Box::new(msg),
+        )))
+        .unwrap();
+
+        let height = update_localhost(&mut ctx, &client_id);
+        let msg = MsgConnectionOpenConfirm {
+            connection_id: conn_b.clone(),
+            proofs: loopback_proofs(height, false),
+            signer: get_dummy_account_id(),
+        };
+        ctx.deliver(Ics26Envelope::Ics3Msg(
+            ConnectionMsg::ConnectionOpenConfirm(msg),
+        ))
+        .unwrap();
+
+        for conn_id in [&conn_a, &conn_b].iter() {
+            let conn_end = ConnectionReader::connection_end(&ctx, conn_id).unwrap();
+            assert!(conn_end.state_matches(&ConnectionState::Open));
+        }
+
+        // The channel handshake, between the modules bound to `port_a` and `port_b`. As the
+        // localhost client reads the host store directly, the client need not be updated.
+        let msg = MsgChannelOpenInit::new(
+            port_a.clone(),
+            ChannelEnd::new(
+                State::Init,
+                Order::Unordered,
+                Counterparty::new(port_b.clone(), None),
+                vec![conn_a.clone()],
+                version.clone(),
+            ),
+            get_dummy_account_id(),
+        );
+        ctx.deliver(Ics26Envelope::Ics4ChannelMsg(ChannelMsg::ChannelOpenInit(
+            msg,
+        )))
+        .unwrap();
+
+        let msg = MsgChannelOpenTry::new(
+            port_b.clone(),
+            None,
+            ChannelEnd::new(
+                State::TryOpen,
+                Order::Unordered,
+                Counterparty::new(port_a.clone(), Some(chan_a.clone())),
+                vec![conn_b.clone()],
+                version.clone(),
+            ),
+            version.clone(),
+            loopback_proofs(height, false),
+            get_dummy_account_id(),
+        );
+        ctx.deliver(Ics26Envelope::Ics4ChannelMsg(ChannelMsg::ChannelOpenTry(
+            msg,
+        )))
+        .unwrap();
+
+        let msg = MsgChannelOpenAck::new(
+            port_a.clone(),
+            chan_a.clone(),
+            chan_b.clone(),
+            version,
+            loopback_proofs(height, false),
+            get_dummy_account_id(),
+        );
+        ctx.deliver(Ics26Envelope::Ics4ChannelMsg(ChannelMsg::ChannelOpenAck(
+            msg,
+        )))
+        .unwrap();
+
+        let msg = MsgChannelOpenConfirm::new(
+            port_b.clone(),
+            chan_b.clone(),
+            loopback_proofs(height, false),
+            get_dummy_account_id(),
+        );
+        ctx.deliver(Ics26Envelope::Ics4ChannelMsg(
+            ChannelMsg::ChannelOpenConfirm(msg),
+        ))
+        .unwrap();
+
+        for (port_id, channel_id) in [(port_a, chan_a), (port_b, chan_b)].iter() {
+            let channel_end =
+                ChannelReader::channel_end(&ctx, &(port_id.clone(), channel_id.clone())).unwrap();
+            assert!(channel_end.state_matches(&State::Open));
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use std::convert::{TryFrom, TryInto};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use tendermint_proto::Protobuf;
+
+use ibc_proto::ibc::lightclients::localhost::v1::ClientState as RawClientState;
+
+use crate::ics02_client::client_state::AnyClientState;
+use crate::ics02_client::client_type::ClientType;
+use crate::ics09_localhost::error::{Error, Kind};
+use crate::ics24_host::identifier::ChainId;
+use crate::utils::UnwrapInfallible;
+use crate::Height;
+
+/// The state of a localhost client, which tracks the host chain itself.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct ClientState {
+    /// The identifier of the host chain.
+    pub chain_id: ChainId,
+    /// The latest height of the host chain the client was updated to.
+    pub height: Height,
+}
+
+impl ClientState {
+    pub fn new(chain_id: ChainId, height: Height) -> Self {
+        Self { chain_id, height }
+    }
+
+    pub fn latest_height(&self) -> Height {
+        self.height
+    }
+
+    /// Returns a copy of this client state, updated to height `height`.
+    pub fn with_height(self, height: Height) -> Self {
+        Self { height, ..self }
+    }
+}
+
+impl crate::ics02_client::client_state::ClientState for ClientState {
+    fn chain_id(&self) -> ChainId {
+        self.chain_id.clone()
+    }
+
+    fn client_type(&self) -> ClientType {
+        ClientType::Localhost
+    }
+
+    fn latest_height(&self) -> Height {
+        self.height
+    }
+
+    /// A localhost client cannot be frozen, the host chain cannot misbehave towards itself.
+    fn is_frozen(&self) -> bool {
+        false
+    }
+
+    fn wrap_any(self) -> AnyClientState {
+        AnyClientState::Localhost(self)
+    }
+}
+
+impl Protobuf<RawClientState> for ClientState {}
+
+impl TryFrom<RawClientState> for ClientState {
+    type Error = Error;
+
+    fn try_from(raw: RawClientState) -> Result<Self, Self::Error> {
+        let chain_id = ChainId::from_str(&raw.chain_id)
+            .map_err(|e| Kind::InvalidChainId(raw.chain_id.clone()).context(e))?;
+
+        let height: Height = raw
+            .height
+            .ok_or_else(|| Kind::InvalidRawClientState.context("missing height"))?
+            .try_into()
+            .unwrap_infallible();
+
+        Ok(Self { chain_id, height })
+    }
+}
+
+impl From<ClientState> for RawClientState {
+    fn from(value: ClientState) -> Self {
+        RawClientState {
+            chain_id: value.chain_id.to_string(),
+            height: Some(value.height.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+    use test_env_log::test;
+
+    use prost_types::Any;
+
+    use crate::ics02_client::client_state::AnyClientState;
+    use crate::ics09_localhost::client_state::ClientState;
+    use crate::ics24_host::identifier::ChainId;
+    use crate::Height;
+
+    #[test]
+    fn any_localhost_client_state_roundtrip() {
+        let client_state = AnyClientState::Localhost(ClientState::new(
+            ChainId::new("localhost".to_string(), 1),
+            Height::new(1, 10),
+        ));
+
+        let raw: Any = client_state.clone().into();
+        assert_eq!(AnyClientState::try_from(raw).unwrap(), client_state);
+    }
+}
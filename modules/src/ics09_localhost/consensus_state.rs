@@ -0,0 +1,93 @@
+use std::convert::TryFrom;
+
+use serde::{Deserialize, Serialize};
+use tendermint_proto::Protobuf;
+
+use crate::ics02_client::client_consensus::AnyConsensusState;
+use crate::ics02_client::client_type::ClientType;
+use crate::ics09_localhost::error::{Error, Kind};
+use crate::ics23_commitment::commitment::CommitmentRoot;
+use crate::timestamp::Timestamp;
+
+/// The consensus state of a localhost client. A localhost client reads the host store directly
+/// instead of verifying proofs against a commitment root, hence it only records the timestamp of
+/// the host chain when the client was updated, against which packet timeouts are checked.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConsensusState {
+    pub timestamp: Timestamp,
+    /// This is always empty.
+    #[serde(skip, default = "empty_root")]
+    root: CommitmentRoot,
+}
+
+fn empty_root() -> CommitmentRoot {
+    CommitmentRoot::from(vec![])
+}
+
+impl ConsensusState {
+    pub fn new(timestamp: Timestamp) -> Self {
+        Self {
+            timestamp,
+            root: empty_root(),
+        }
+    }
+
+    pub fn timestamp(&self) -> Timestamp {
+        self.timestamp
+    }
+}
+
+impl crate::ics02_client::client_consensus::ConsensusState for ConsensusState {
+    fn client_type(&self) -> ClientType {
+        ClientType::Localhost
+    }
+
+    fn root(&self) -> &CommitmentRoot {
+        &self.root
+    }
+
+    fn validate_basic(&self) -> Result<(), Box<dyn std::error::Error>> {
+        Ok(())
+    }
+
+    fn wrap_any(self) -> AnyConsensusState {
+        AnyConsensusState::Localhost(self)
+    }
+}
+
+/// The `ibc.lightclients.localhost.v1` package does not define a consensus state, since the
+/// Cosmos SDK localhost client does not store any. This is its encoding in this crate.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct RawConsensusState {
+    /// The timestamp in nanoseconds, 0 if unknown.
+    #[prost(uint64, tag = "1")]
+    pub timestamp: u64,
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for ConsensusState {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self::new(u.arbitrary()?))
+    }
+}
+
+impl Protobuf<RawConsensusState> for ConsensusState {}
+
+impl TryFrom<RawConsensusState> for ConsensusState {
+    type Error = Error;
+
+    fn try_from(raw: RawConsensusState) -> Result<Self, Self::Error> {
+        let timestamp = Timestamp::from_nanoseconds(raw.timestamp)
+            .map_err(|e| Kind::InvalidRawConsensusState.context(e))?;
+
+        Ok(Self::new(timestamp))
+    }
+}
+
+impl From<ConsensusState> for RawConsensusState {
+    fn from(value: ConsensusState) -> Self {
+        RawConsensusState {
+            timestamp: value.timestamp.as_nanoseconds(),
+        }
+    }
+}
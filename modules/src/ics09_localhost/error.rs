@@ -0,0 +1,54 @@
+use anomaly::{BoxError, Context};
+use thiserror::Error;
+
+use crate::Height;
+
+pub type Error = anomaly::Error<Kind>;
+
+#[derive(Clone, Debug, Error)]
+pub enum Kind {
+    #[error("invalid raw client state")]
+    InvalidRawClientState,
+
+    #[error("invalid raw client consensus state")]
+    InvalidRawConsensusState,
+
+    #[error("invalid raw header")]
+    InvalidRawHeader,
+
+    #[error("invalid chain identifier: {0}")]
+    InvalidChainId(String),
+
+    #[error("header height {0} is not higher than the latest client height {1}")]
+    LowHeaderHeight(Height, Height),
+
+    #[error("localhost clients can only be updated to the host height {0}, got header height {1}")]
+    HeaderHeightMismatch(Height, Height),
+
+    #[error("localhost clients do not support misbehaviour")]
+    MisbehaviourNotSupported,
+
+    #[error("localhost clients cannot be upgraded")]
+    UpgradeNotSupported,
+
+    #[error("localhost clients cannot be recovered")]
+    RecoveryNotSupported,
+
+    #[error("missing counterparty connection identifier")]
+    MissingConnectionId,
+
+    #[error("no value is stored under path {0}")]
+    MissingValue(String),
+
+    #[error("the value stored under path {0} does not match the expected value")]
+    ValueMismatch(String),
+
+    #[error("a value is unexpectedly stored under path {0}")]
+    UnexpectedValue(String),
+}
+
+impl Kind {
+    pub fn context(self, source: impl Into<BoxError>) -> Context<Self> {
+        Context::new(self, Some(source.into()))
+    }
+}
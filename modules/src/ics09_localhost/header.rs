@@ -0,0 +1,71 @@
+use std::convert::{TryFrom, TryInto};
+
+use serde::{Deserialize, Serialize};
+use tendermint_proto::Protobuf;
+
+use ibc_proto::ibc::core::client::v1::Height as RawHeight;
+
+use crate::ics02_client::client_type::ClientType;
+use crate::ics02_client::header::AnyHeader;
+use crate::ics09_localhost::error::{Error, Kind};
+use crate::utils::UnwrapInfallible;
+use crate::Height;
+
+/// A localhost header, which updates a localhost client to the current height of the host chain.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct Header {
+    pub height: Height,
+}
+
+impl Header {
+    pub fn new(height: Height) -> Self {
+        Self { height }
+    }
+}
+
+impl crate::ics02_client::header::Header for Header {
+    fn client_type(&self) -> ClientType {
+        ClientType::Localhost
+    }
+
+    fn height(&self) -> Height {
+        self.height
+    }
+
+    fn wrap_any(self) -> AnyHeader {
+        AnyHeader::Localhost(self)
+    }
+}
+
+/// The `ibc.lightclients.localhost.v1` package does not define a header, since the Cosmos SDK
+/// updates localhost clients at the beginning of every block. This is its encoding in this crate.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct RawHeader {
+    #[prost(message, optional, tag = "1")]
+    pub height: Option<RawHeight>,
+}
+
+impl Protobuf<RawHeader> for Header {}
+
+impl TryFrom<RawHeader> for Header {
+    type Error = Error;
+
+    fn try_from(raw: RawHeader) -> Result<Self, Self::Error> {
+        let height: Height = raw
+            .height
+            .ok_or_else(|| Kind::InvalidRawHeader.context("missing height"))?
+            .try_into()
+            .unwrap_infallible();
+
+        Ok(Self::new(height))
+    }
+}
+
+impl From<Header> for RawHeader {
+    fn from(value: Header) -> Self {
+        RawHeader {
+            height: Some(value.height.into()),
+        }
+    }
+}
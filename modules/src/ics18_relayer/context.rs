@@ -3,9 +3,14 @@ use prost_types::Any;
 use crate::events::IbcEvent;
 use crate::ics02_client::client_state::AnyClientState;
 use crate::ics02_client::header::AnyHeader;
+use crate::ics03_connection::connection::ConnectionEnd;
+use crate::ics04_channel::channel::ChannelEnd;
+use crate::ics04_channel::packet::Sequence;
 
 use crate::ics18_relayer::error::Error;
-use crate::ics24_host::identifier::ClientId;
+use crate::ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes};
+use crate::ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId};
+use crate::ics24_host::Path;
 use crate::signer::Signer;
 use crate::Height;
 
@@ -25,6 +30,27 @@ pub trait Ics18Context {
     /// Returns the most advanced header of this chain.
     fn query_latest_header(&self) -> Option<AnyHeader>;
 
+    /// Returns the connection end for the given `connection_id` on this chain.
+    fn query_connection(&self, connection_id: &ConnectionId) -> Option<ConnectionEnd>;
+
+    /// Returns the channel end for the given `port_id` and `channel_id` on this chain.
+    fn query_channel(&self, port_id: &PortId, channel_id: &ChannelId) -> Option<ChannelEnd>;
+
+    /// Returns the sequence number of the next packet this chain expects to receive on the given
+    /// channel.
+    fn query_next_sequence_recv(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+    ) -> Option<Sequence>;
+
+    /// Returns the prefix under which this chain commits to its IBC state.
+    fn query_commitment_prefix(&self) -> CommitmentPrefix;
+
+    /// Returns the proof of the value at `path` (or of its absence) in the state of this chain at
+    /// `height`. Wrapper over the `/abci_query?path=..&prove=true` endpoint.
+    fn query_proof(&self, path: &Path, height: Height) -> Option<CommitmentProofBytes>;
+
     /// Interface that the relayer uses to submit a datagram to this chain.
     /// One can think of this as wrapping around the `/broadcast_tx_commit` ABCI endpoint.
     fn send(&mut self, msgs: Vec<Any>) -> Result<Vec<IbcEvent>, Error>;
@@ -1,4 +1,4 @@
-use crate::ics24_host::identifier::ClientId;
+use crate::ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId};
 use crate::Height;
 use anomaly::{BoxError, Context};
 use thiserror::Error;
@@ -18,6 +18,30 @@ pub enum Kind {
 
     #[error("transaction processing by modules failed")]
     TransactionFailed,
+
+    #[error("connection {0} not found on source chain")]
+    ConnectionNotFound(ConnectionId),
+
+    #[error("the counterparty connection of connection {0} is not set on source chain")]
+    MissingCounterpartyConnection(ConnectionId),
+
+    #[error("channel {1} on port {0} not found on source chain")]
+    ChannelNotFound(PortId, ChannelId),
+
+    #[error("the counterparty channel of channel {1} on port {0} is not set on source chain")]
+    MissingCounterpartyChannel(PortId, ChannelId),
+
+    #[error("channel {1} on port {0} has no connection on source chain")]
+    MissingConnectionHop(PortId, ChannelId),
+
+    #[error("next receive sequence of channel {1} on port {0} not found on source chain")]
+    NextSequenceRecvNotFound(PortId, ChannelId),
+
+    #[error("proof of path {0} at height {1} not found on source chain")]
+    ProofNotFound(String, Height),
+
+    #[error("malformed proofs")]
+    MalformedProofs,
 }
 
 impl Kind {
@@ -1,9 +1,30 @@
+use crate::ics02_client::client_state::AnyClientState;
 use crate::ics02_client::header::{AnyHeader, Header};
 use crate::ics02_client::msgs::update_client::MsgUpdateAnyClient;
 use crate::ics02_client::msgs::ClientMsg;
+use crate::ics03_connection::connection::{ConnectionEnd, Counterparty as ConnectionCounterparty};
+use crate::ics03_connection::msgs::conn_open_ack::MsgConnectionOpenAck;
+use crate::ics03_connection::msgs::conn_open_confirm::MsgConnectionOpenConfirm;
+use crate::ics03_connection::msgs::conn_open_try::MsgConnectionOpenTry;
+use crate::ics03_connection::msgs::ConnectionMsg;
+use crate::ics04_channel::channel::{
+    ChannelEnd, Counterparty as ChannelCounterparty, Order, State,
+};
+use crate::ics04_channel::msgs::acknowledgement::MsgAcknowledgement;
+use crate::ics04_channel::msgs::chan_open_ack::MsgChannelOpenAck;
+use crate::ics04_channel::msgs::chan_open_confirm::MsgChannelOpenConfirm;
+use crate::ics04_channel::msgs::chan_open_try::MsgChannelOpenTry;
+use crate::ics04_channel::msgs::recv_packet::MsgRecvPacket;
+use crate::ics04_channel::msgs::timeout::MsgTimeout;
+use crate::ics04_channel::msgs::{ChannelMsg, PacketMsg};
+use crate::ics04_channel::packet::Packet;
 use crate::ics18_relayer::context::Ics18Context;
 use crate::ics18_relayer::error::{Error, Kind};
-use crate::ics24_host::identifier::ClientId;
+use crate::ics23_commitment::commitment::CommitmentProofBytes;
+use crate::ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId};
+use crate::ics24_host::Path;
+use crate::proofs::{ConsensusProof, Proofs};
+use crate::Height;
 
 /// Builds a `ClientMsg::UpdateClient` for a client with id `client_id` running on the `dest`
 /// context, assuming that the latest header on the source context is `src_header`.
@@ -41,27 +62,620 @@ where
         .into());
     };
 
+    // Tendermint headers are verified against a consensus state the destination client already
+    // has, so we let them point to the latest height of the destination client.
+    let header = match src_header {
+        AnyHeader::Tendermint(mut tm_header) => {
+            tm_header.trusted_height = dest_client_latest_height;
+            AnyHeader::Tendermint(tm_header)
+        }
+        other => other,
+    };
+
     // Client on destination chain can be updated.
     Ok(ClientMsg::UpdateClient(MsgUpdateAnyClient {
         client_id: client_id.clone(),
-        header: src_header,
+        header,
         signer: dest.signer(),
     }))
 }
 
+// The builders below produce the datagrams which relay the connection and channel handshakes,
+// and the packets, from a source chain `src` to a destination chain `dest`. The proofs are of the
+// state of `src` at its latest height, hence the client of `src` on `dest` must be updated to that
+// height (see `build_client_update_datagram`) before the datagrams are submitted.
+
+/// Builds a `ConnectionMsg::ConnectionOpenTry` for `dest`, from the connection `src_connection_id`
+/// which `src` initialized.
+pub fn build_conn_open_try<Dest, Src>(
+    dest: &Dest,
+    src: &Src,
+    src_connection_id: &ConnectionId,
+) -> Result<ConnectionMsg, Error>
+where
+    Dest: Ics18Context,
+    Src: Ics18Context,
+{
+    let src_connection = query_connection(src, src_connection_id)?;
+    let (client_state, proofs) = connection_proofs(src, src_connection_id, &src_connection)?;
+    let counterparty = src_connection.counterparty();
+
+    Ok(ConnectionMsg::ConnectionOpenTry(Box::new(
+        MsgConnectionOpenTry {
+            previous_connection_id: counterparty.connection_id().cloned(),
+            client_id: counterparty.client_id().clone(),
+            client_state: Some(client_state),
+            counterparty: ConnectionCounterparty::new(
+                src_connection.client_id().clone(),
+                Some(src_connection_id.clone()),
+                src.query_commitment_prefix(),
+            ),
+            counterparty_versions: src_connection.versions(),
+            proofs,
+            delay_period: src_connection.delay_period(),
+            signer: dest.signer(),
+        },
+    )))
+}
+
+/// Builds a `ConnectionMsg::ConnectionOpenAck` for `dest`, from the connection `src_connection_id`
+/// which `src` opened in response to the initialization on `dest`.
+pub fn build_conn_open_ack<Dest, Src>(
+    dest: &Dest,
+    src: &Src,
+    src_connection_id: &ConnectionId,
+) -> Result<ConnectionMsg, Error>
+where
+    Dest: Ics18Context,
+    Src: Ics18Context,
+{
+    let src_connection = query_connection(src, src_connection_id)?;
+    let (client_state, proofs) = connection_proofs(src, src_connection_id, &src_connection)?;
+
+    Ok(ConnectionMsg::ConnectionOpenAck(Box::new(
+        MsgConnectionOpenAck {
+            connection_id: counterparty_connection_id(src_connection_id, &src_connection)?,
+            counterparty_connection_id: src_connection_id.clone(),
+            client_state: Some(client_state),
+            proofs,
+            // The version was picked by `src`.
+            version: src_connection
+                .versions()
+                .into_iter()
+                .next()
+                .unwrap_or_default(),
+            signer: dest.signer(),
+        },
+    )))
+}
+
+/// Builds a `ConnectionMsg::ConnectionOpenConfirm` for `dest`, from the connection
+/// `src_connection_id` which `src` opened.
+pub fn build_conn_open_confirm<Dest, Src>(
+    dest: &Dest,
+    src: &Src,
+    src_connection_id: &ConnectionId,
+) -> Result<ConnectionMsg, Error>
+where
+    Dest: Ics18Context,
+    Src: Ics18Context,
+{
+    let src_connection = query_connection(src, src_connection_id)?;
+    let height = src.query_latest_height();
+    let object_proof = query_proof(src, Path::Connections(src_connection_id.clone()), height)?;
+
+    Ok(ConnectionMsg::ConnectionOpenConfirm(
+        MsgConnectionOpenConfirm {
+            connection_id: counterparty_connection_id(src_connection_id, &src_connection)?,
+            proofs: proofs(object_proof, height)?,
+            signer: dest.signer(),
+        },
+    ))
+}
+
+/// Builds a `ChannelMsg::ChannelOpenTry` for `dest`, from the channel `src_channel_id` on port
+/// `src_port_id` which `src` initialized.
+pub fn build_chan_open_try<Dest, Src>(
+    dest: &Dest,
+    src: &Src,
+    src_port_id: &PortId,
+    src_channel_id: &ChannelId,
+) -> Result<ChannelMsg, Error>
+where
+    Dest: Ics18Context,
+    Src: Ics18Context,
+{
+    let src_channel = query_channel(src, src_port_id, src_channel_id)?;
+    let src_connection_id = src_channel
+        .connection_hops()
+        .first()
+        .ok_or_else(|| Kind::MissingConnectionHop(src_port_id.clone(), src_channel_id.clone()))?;
+    let src_connection = query_connection(src, src_connection_id)?;
+    let dest_connection_id = counterparty_connection_id(src_connection_id, &src_connection)?;
+    let counterparty = src_channel.counterparty();
+
+    let channel = ChannelEnd::new(
+        State::TryOpen,
+        *src_channel.ordering(),
+        ChannelCounterparty::new(src_port_id.clone(), Some(src_channel_id.clone())),
+        vec![dest_connection_id],
+        src_channel.version(),
+    );
+
+    Ok(ChannelMsg::ChannelOpenTry(MsgChannelOpenTry::new(
+        counterparty.port_id().clone(),
+        counterparty.channel_id().cloned(),
+        channel,
+        src_channel.version(),
+        channel_proofs(src, src_port_id, src_channel_id)?,
+        dest.signer(),
+    )))
+}
+
+/// Builds a `ChannelMsg::ChannelOpenAck` for `dest`, from the channel `src_channel_id` on port
+/// `src_port_id` which `src` opened in response to the initialization on `dest`.
+pub fn build_chan_open_ack<Dest, Src>(
+    dest: &Dest,
+    src: &Src,
+    src_port_id: &PortId,
+    src_channel_id: &ChannelId,
+) -> Result<ChannelMsg, Error>
+where
+    Dest: Ics18Context,
+    Src: Ics18Context,
+{
+    let src_channel = query_channel(src, src_port_id, src_channel_id)?;
+    let counterparty = src_channel.counterparty();
+
+    Ok(ChannelMsg::ChannelOpenAck(MsgChannelOpenAck::new(
+        counterparty.port_id().clone(),
+        counterparty_channel_id(src_port_id, src_channel_id, &src_channel)?,
+        src_channel_id.clone(),
+        src_channel.version(),
+        channel_proofs(src, src_port_id, src_channel_id)?,
+        dest.signer(),
+    )))
+}
+
+/// Builds a `ChannelMsg::ChannelOpenConfirm` for `dest`, from the channel `src_channel_id` on port
+/// `src_port_id` which `src` opened.
+pub fn build_chan_open_confirm<Dest, Src>(
+    dest: &Dest,
+    src: &Src,
+    src_port_id: &PortId,
+    src_channel_id: &ChannelId,
+) -> Result<ChannelMsg, Error>
+where
+    Dest: Ics18Context,
+    Src: Ics18Context,
+{
+    let src_channel = query_channel(src, src_port_id, src_channel_id)?;
+
+    Ok(ChannelMsg::ChannelOpenConfirm(MsgChannelOpenConfirm::new(
+        src_channel.counterparty().port_id().clone(),
+        counterparty_channel_id(src_port_id, src_channel_id, &src_channel)?,
+        channel_proofs(src, src_port_id, src_channel_id)?,
+        dest.signer(),
+    )))
+}
+
+/// Builds a `PacketMsg::RecvPacket` for `dest`, from the `packet` which `src` sent.
+pub fn build_recv_packet<Dest, Src>(
+    dest: &Dest,
+    src: &Src,
+    packet: Packet,
+) -> Result<PacketMsg, Error>
+where
+    Dest: Ics18Context,
+    Src: Ics18Context,
+{
+    let height = src.query_latest_height();
+    let path = Path::Commitments {
+        port_id: packet.source_port.clone(),
+        channel_id: packet.source_channel.clone(),
+        sequence: packet.sequence,
+    };
+    let object_proof = query_proof(src, path, height)?;
+
+    Ok(PacketMsg::RecvPacket(MsgRecvPacket::new(
+        packet,
+        proofs(object_proof, height)?,
+        dest.signer(),
+    )))
+}
+
+/// Builds a `PacketMsg::AckPacket` for `dest`, from the `acknowledgement` which `src` wrote
+/// upon receiving `packet`.
+pub fn build_ack_packet<Dest, Src>(
+    dest: &Dest,
+    src: &Src,
+    packet: Packet,
+    acknowledgement: Vec<u8>,
+) -> Result<PacketMsg, Error>
+where
+    Dest: Ics18Context,
+    Src: Ics18Context,
+{
+    let height = src.query_latest_height();
+    let path = Path::Acks {
+        port_id: packet.destination_port.clone(),
+        channel_id: packet.destination_channel.clone(),
+        sequence: packet.sequence,
+    };
+    let object_proof = query_proof(src, path, height)?;
+
+    Ok(PacketMsg::AckPacket(MsgAcknowledgement::new(
+        packet,
+        acknowledgement,
+        proofs(object_proof, height)?,
+        dest.signer(),
+    )))
+}
+
+/// Builds a `PacketMsg::ToPacket` for `dest`, from the state of `src`, which did not receive
+/// `packet` before it timed out. Depending on the ordering of the channel, the proof is either of
+/// the next sequence number which `src` expects to receive, or of the absence of a receipt.
+pub fn build_timeout_packet<Dest, Src>(
+    dest: &Dest,
+    src: &Src,
+    packet: Packet,
+) -> Result<PacketMsg, Error>
+where
+    Dest: Ics18Context,
+    Src: Ics18Context,
+{
+    let (port_id, channel_id) = (&packet.destination_port, &packet.destination_channel);
+    let src_channel = query_channel(src, port_id, channel_id)?;
+    let height = src.query_latest_height();
+
+    let (next_sequence_recv, path) = if src_channel.order_matches(&Order::Ordered) {
+        let next_sequence_recv = src
+            .query_next_sequence_recv(port_id, channel_id)
+            .ok_or_else(|| Kind::NextSequenceRecvNotFound(port_id.clone(), channel_id.clone()))?;
+        let path = Path::SeqRecvs(port_id.clone(), channel_id.clone());
+        (next_sequence_recv, path)
+    } else {
+        let path = Path::Receipts {
+            port_id: port_id.clone(),
+            channel_id: channel_id.clone(),
+            sequence: packet.sequence,
+        };
+        (packet.sequence, path)
+    };
+    let object_proof = query_proof(src, path, height)?;
+
+    Ok(PacketMsg::ToPacket(MsgTimeout::new(
+        packet,
+        next_sequence_recv,
+        proofs(object_proof, height)?,
+        dest.signer(),
+    )))
+}
+
+fn query_connection<Ctx: Ics18Context>(
+    ctx: &Ctx,
+    connection_id: &ConnectionId,
+) -> Result<ConnectionEnd, Error> {
+    ctx.query_connection(connection_id)
+        .ok_or_else(|| Kind::ConnectionNotFound(connection_id.clone()).into())
+}
+
+fn query_channel<Ctx: Ics18Context>(
+    ctx: &Ctx,
+    port_id: &PortId,
+    channel_id: &ChannelId,
+) -> Result<ChannelEnd, Error> {
+    ctx.query_channel(port_id, channel_id)
+        .ok_or_else(|| Kind::ChannelNotFound(port_id.clone(), channel_id.clone()).into())
+}
+
+fn query_proof<Ctx: Ics18Context>(
+    ctx: &Ctx,
+    path: Path,
+    height: Height,
+) -> Result<CommitmentProofBytes, Error> {
+    ctx.query_proof(&path, height)
+        .ok_or_else(|| Kind::ProofNotFound(path.to_string(), height).into())
+}
+
+fn counterparty_connection_id(
+    connection_id: &ConnectionId,
+    connection_end: &ConnectionEnd,
+) -> Result<ConnectionId, Error> {
+    connection_end
+        .counterparty()
+        .connection_id()
+        .cloned()
+        .ok_or_else(|| Kind::MissingCounterpartyConnection(connection_id.clone()).into())
+}
+
+fn counterparty_channel_id(
+    port_id: &PortId,
+    channel_id: &ChannelId,
+    channel_end: &ChannelEnd,
+) -> Result<ChannelId, Error> {
+    channel_end
+        .counterparty()
+        .channel_id()
+        .cloned()
+        .ok_or_else(|| Kind::MissingCounterpartyChannel(port_id.clone(), channel_id.clone()).into())
+}
+
+/// Returns the proofs of a datagram which only proves a single object of the source chain.
+fn proofs(object_proof: CommitmentProofBytes, height: Height) -> Result<Proofs, Error> {
+    Proofs::new(object_proof, None, None, None, height)
+        .map_err(|e| Kind::MalformedProofs.context(e).into())
+}
+
+/// Returns the client of `dest` which the connection `connection_id` of `src` uses, together with
+/// the proofs of the connection, of the client and of its latest consensus state.
+fn connection_proofs<Ctx: Ics18Context>(
+    src: &Ctx,
+    connection_id: &ConnectionId,
+    connection_end: &ConnectionEnd,
+) -> Result<(AnyClientState, Proofs), Error> {
+    let client_id = connection_end.client_id();
+    let client_state = src
+        .query_client_full_state(client_id)
+        .ok_or_else(|| Kind::ClientStateNotFound(client_id.clone()))?;
+    let consensus_height = client_state.latest_height();
+    let height = src.query_latest_height();
+
+    let object_proof = query_proof(src, Path::Connections(connection_id.clone()), height)?;
+    let client_proof = query_proof(src, Path::ClientState(client_id.clone()), height)?;
+    let consensus_path = Path::ClientConsensusState {
+        client_id: client_id.clone(),
+        epoch: consensus_height.revision_number,
+        height: consensus_height.revision_height,
+    };
+    let consensus_proof =
+        ConsensusProof::new(query_proof(src, consensus_path, height)?, consensus_height)
+            .map_err(|e| Kind::MalformedProofs.context(e))?;
+
+    let proofs = Proofs::new(
+        object_proof,
+        Some(client_proof),
+        Some(consensus_proof),
+        None,
+        height,
+    )
+    .map_err(|e| Kind::MalformedProofs.context(e))?;
+    Ok((client_state, proofs))
+}
+
+fn channel_proofs<Ctx: Ics18Context>(
+    src: &Ctx,
+    port_id: &PortId,
+    channel_id: &ChannelId,
+) -> Result<Proofs, Error> {
+    let height = src.query_latest_height();
+    let path = Path::ChannelEnds(port_id.clone(), channel_id.clone());
+    proofs(query_proof(src, path, height)?, height)
+}
+
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
+    use crate::application::ics20_fungible_token_transfer::coin::Coin;
+    use crate::application::ics20_fungible_token_transfer::context::BankKeeper;
+    use crate::application::ics20_fungible_token_transfer::module::{
+        Ics20Module, MODULE_ID, VERSION,
+    };
+    use crate::application::ics20_fungible_token_transfer::msgs::transfer::MsgTransfer;
+    use crate::events::IbcEvent;
     use crate::ics02_client::client_type::ClientType;
     use crate::ics02_client::header::Header;
+    use crate::ics02_client::msgs::ClientMsg;
+    use crate::ics03_connection::connection::{Counterparty as ConnectionCounterparty, State};
+    use crate::ics03_connection::msgs::conn_open_init::MsgConnectionOpenInit;
+    use crate::ics03_connection::msgs::ConnectionMsg;
+    use crate::ics03_connection::version::Version;
+    use crate::ics04_channel::channel::{
+        ChannelEnd, Counterparty as ChannelCounterparty, Order, State as ChannelState,
+    };
+    use crate::ics04_channel::context::ChannelReader;
+    use crate::ics04_channel::msgs::chan_open_init::MsgChannelOpenInit;
+    use crate::ics04_channel::msgs::ChannelMsg;
+    use crate::ics04_channel::packet::Packet;
+    use crate::ics05_port::capabilities::ModuleId;
     use crate::ics18_relayer::context::Ics18Context;
-    use crate::ics18_relayer::utils::build_client_update_datagram;
-    use crate::ics24_host::identifier::{ChainId, ClientId};
+    use crate::ics18_relayer::utils::{
+        build_ack_packet, build_chan_open_ack, build_chan_open_confirm, build_chan_open_try,
+        build_client_update_datagram, build_conn_open_ack, build_conn_open_confirm,
+        build_conn_open_try, build_recv_packet, build_timeout_packet,
+    };
+    use crate::ics24_host::identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId};
+    use crate::ics26_routing::handler::dispatch;
     use crate::ics26_routing::msgs::Ics26Envelope;
     use crate::mock::context::MockContext;
     use crate::mock::host::HostType;
+    use crate::test_utils::get_dummy_account_id;
+    use crate::timestamp::Timestamp;
+    use crate::tx_msg::Msg;
     use crate::Height;
     use test_env_log::test;
 
+    /// Updates the client `client_id` of `src` on `dest` to the latest height of `src`, unless it
+    /// is there already.
+    fn update_client(dest: &mut MockContext, src: &MockContext, client_id: &ClientId) {
+        let client_height = dest
+            .query_client_full_state(client_id)
+            .unwrap()
+            .latest_height();
+        if client_height < src.query_latest_height() {
+            let header = src.query_latest_header().unwrap();
+            let msg = match build_client_update_datagram(dest, client_id, header).unwrap() {
+                ClientMsg::UpdateClient(msg) => msg,
+                _ => unreachable!(),
+            };
+            dest.send(vec![msg.to_any()]).unwrap();
+        }
+    }
+
+    /// Delivers `msg` to `dest` in a new block, and returns the events it emitted.
+    fn relay(dest: &mut MockContext, msg: Ics26Envelope) -> Vec<IbcEvent> {
+        let output = dispatch(dest, msg).unwrap();
+        dest.advance_host_chain_height();
+        output.events
+    }
+
+    /// Returns the packet which the events of a token transfer carry.
+    fn sent_packet(events: Vec<IbcEvent>) -> Packet {
+        events
+            .into_iter()
+            .find_map(|event| match event {
+                IbcEvent::SendPacket(ev) => Some(ev.packet),
+                _ => None,
+            })
+            .expect("no packet was sent")
+    }
+
+    #[test]
+    /// Drives two chains, which keep provable stores, from the initialization of a connection to
+    /// the acknowledgement and the timeout of token transfers, only relaying what they committed.
+    fn relay_handshakes_and_packets() {
+        let chain_id_a = ChainId::new("mockgaiaA".to_string(), 1);
+        let chain_id_b = ChainId::new("mockgaiaB".to_string(), 1);
+        let client_on_a_for_b = ClientId::new(ClientType::Mock, 0).unwrap();
+        let client_on_b_for_a = ClientId::new(ClientType::Mock, 0).unwrap();
+        let port_id = PortId::default();
+        let signer = get_dummy_account_id();
+        let coin = Coin::new("uatom", 10);
+
+        let mut ctx_a = MockContext::new(chain_id_a, HostType::Mock, 10, Height::new(1, 5))
+            .with_client(&client_on_a_for_b, Height::new(1, 2))
+            .with_module(ModuleId::new(MODULE_ID), port_id.clone(), Ics20Module)
+            .with_balance(&signer, Coin::new(coin.denom.clone(), 2 * coin.amount))
+            .with_provable_store();
+        let mut ctx_b = MockContext::new(chain_id_b, HostType::Mock, 10, Height::new(1, 5))
+            .with_client(&client_on_b_for_a, Height::new(1, 2))
+            .with_module(ModuleId::new(MODULE_ID), port_id.clone(), Ics20Module)
+            .with_provable_store();
+
+        // Connection handshake, initialized on A.
+        let conn_a = ConnectionId::new(0);
+        let conn_b = ConnectionId::new(0);
+        let msg = MsgConnectionOpenInit {
+            client_id: client_on_a_for_b.clone(),
+            counterparty: ConnectionCounterparty::new(
+                client_on_b_for_a.clone(),
+                None,
+                ctx_b.query_commitment_prefix(),
+            ),
+            version: Version::default(),
+            delay_period: Duration::from_secs(0),
+            signer: signer.clone(),
+        };
+        relay(
+            &mut ctx_a,
+            Ics26Envelope::Ics3Msg(ConnectionMsg::ConnectionOpenInit(msg)),
+        );
+
+        update_client(&mut ctx_a, &ctx_b, &client_on_a_for_b);
+        update_client(&mut ctx_b, &ctx_a, &client_on_b_for_a);
+        let msg = build_conn_open_try(&ctx_b, &ctx_a, &conn_a).unwrap();
+        relay(&mut ctx_b, Ics26Envelope::Ics3Msg(msg));
+
+        update_client(&mut ctx_b, &ctx_a, &client_on_b_for_a);
+        update_client(&mut ctx_a, &ctx_b, &client_on_a_for_b);
+        let msg = build_conn_open_ack(&ctx_a, &ctx_b, &conn_b).unwrap();
+        relay(&mut ctx_a, Ics26Envelope::Ics3Msg(msg));
+
+        update_client(&mut ctx_b, &ctx_a, &client_on_b_for_a);
+        let msg = build_conn_open_confirm(&ctx_b, &ctx_a, &conn_a).unwrap();
+        relay(&mut ctx_b, Ics26Envelope::Ics3Msg(msg));
+
+        assert!(ctx_a
+            .query_connection(&conn_a)
+            .unwrap()
+            .state_matches(&State::Open));
+        assert!(ctx_b
+            .query_connection(&conn_b)
+            .unwrap()
+            .state_matches(&State::Open));
+
+        // Channel handshake, initialized on A.
+        let chan_a = ChannelId::new(0);
+        let chan_b = ChannelId::new(0);
+        let channel = ChannelEnd::new(
+            ChannelState::Init,
+            Order::Unordered,
+            ChannelCounterparty::new(port_id.clone(), None),
+            vec![conn_a],
+            VERSION.to_string(),
+        );
+        let msg = MsgChannelOpenInit::new(port_id.clone(), channel, signer.clone());
+        relay(
+            &mut ctx_a,
+            Ics26Envelope::Ics4ChannelMsg(ChannelMsg::ChannelOpenInit(msg)),
+        );
+
+        update_client(&mut ctx_b, &ctx_a, &client_on_b_for_a);
+        let msg = build_chan_open_try(&ctx_b, &ctx_a, &port_id, &chan_a).unwrap();
+        relay(&mut ctx_b, Ics26Envelope::Ics4ChannelMsg(msg));
+
+        update_client(&mut ctx_a, &ctx_b, &client_on_a_for_b);
+        let msg = build_chan_open_ack(&ctx_a, &ctx_b, &port_id, &chan_b).unwrap();
+        relay(&mut ctx_a, Ics26Envelope::Ics4ChannelMsg(msg));
+
+        update_client(&mut ctx_b, &ctx_a, &client_on_b_for_a);
+        let msg = build_chan_open_confirm(&ctx_b, &ctx_a, &port_id, &chan_a).unwrap();
+        relay(&mut ctx_b, Ics26Envelope::Ics4ChannelMsg(msg));
+
+        assert!(ctx_a.query_channel(&port_id, &chan_a).unwrap().is_open());
+        assert!(ctx_b.query_channel(&port_id, &chan_b).unwrap().is_open());
+
+        // A token transfer from A to B, received and acknowledged.
+        let transfer = MsgTransfer {
+            source_port: port_id.clone(),
+            source_channel: chan_a.clone(),
+            token: Some(coin.clone().into()),
+            sender: signer.clone(),
+            receiver: signer.clone(),
+            timeout_height: Height::new(1, 1000),
+            timeout_timestamp: Timestamp::none(),
+        };
+        let packet = sent_packet(relay(&mut ctx_a, Ics26Envelope::Ics20Msg(transfer.clone())));
+
+        update_client(&mut ctx_b, &ctx_a, &client_on_b_for_a);
+        let msg = build_recv_packet(&ctx_b, &ctx_a, packet.clone()).unwrap();
+        let ack = relay(&mut ctx_b, Ics26Envelope::Ics4PacketMsg(msg))
+            .into_iter()
+            .find_map(|event| match event {
+                IbcEvent::WriteAcknowledgement(ev) => Some(ev.ack),
+                _ => None,
+            })
+            .expect("no acknowledgement was written");
+
+        update_client(&mut ctx_a, &ctx_b, &client_on_a_for_b);
+        let msg = build_ack_packet(&ctx_a, &ctx_b, packet.clone(), ack).unwrap();
+        relay(&mut ctx_a, Ics26Envelope::Ics4PacketMsg(msg));
+
+        let packet_key = (port_id.clone(), chan_a.clone(), packet.sequence);
+        assert!(ctx_a.get_packet_commitment(&packet_key).is_none());
+        let escrow = ctx_a.get_escrow_address(&port_id, &chan_a);
+        assert_eq!(ctx_a.balance(&escrow, &coin.denom), coin.amount);
+
+        // A token transfer from A to B, which times out before B receives it.
+        let transfer = MsgTransfer {
+            timeout_height: ctx_b.query_latest_height().increment(),
+            ..transfer
+        };
+        let packet = sent_packet(relay(&mut ctx_a, Ics26Envelope::Ics20Msg(transfer)));
+        ctx_b.advance_host_chain_height();
+        ctx_b.advance_host_chain_height();
+
+        update_client(&mut ctx_a, &ctx_b, &client_on_a_for_b);
+        let msg = build_timeout_packet(&ctx_a, &ctx_b, packet.clone()).unwrap();
+        relay(&mut ctx_a, Ics26Envelope::Ics4PacketMsg(msg));
+
+        let packet_key = (port_id, chan_a, packet.sequence);
+        assert!(ctx_a.get_packet_commitment(&packet_key).is_none());
+        // The tokens of the second transfer were refunded.
+        assert_eq!(ctx_a.balance(&signer, &coin.denom), coin.amount);
+    }
+
     #[test]
     /// Serves to test both ICS 26 `dispatch` & `build_client_update_datagram` functions.
     /// Implements a "ping pong" of client update messages, so that two chains repeatedly
@@ -76,21 +690,23 @@ mod tests {
         let client_on_a_for_b = ClientId::new(ClientType::Tendermint, 0).unwrap();
         let client_on_b_for_a = ClientId::new(ClientType::Mock, 0).unwrap();
 
-        // Create two mock contexts, one for each chain.
-        let mut ctx_a = MockContext::new(
-            ChainId::new("mockgaiaA".to_string(), 1),
-            HostType::Mock,
-            5,
-            chain_a_start_height,
-        )
-        .with_client_parametrized(
-            &client_on_a_for_b,
-            client_on_a_for_b_height,
-            Some(ClientType::Tendermint), // The target host chain (B) is synthetic TM.
-            Some(client_on_a_for_b_height),
-        );
+        let chain_id_a = ChainId::new("mockgaiaA".to_string(), 1);
+        let chain_id_b = ChainId::new("mockgaiaB".to_string(), 1);
+
+        // Create two mock contexts, one for each chain. The Tendermint client on chain A checks
+        // the headers of chain B against the time of A, and the timestamp of a synthetic block
+        // at height `h` is `h` seconds after the Unix epoch.
+        let mut ctx_a = MockContext::new(chain_id_a, HostType::Mock, 5, chain_a_start_height)
+            .with_client_parametrized_with_chain_id(
+                chain_id_b.clone(),
+                &client_on_a_for_b,
+                client_on_a_for_b_height,
+                Some(ClientType::Tendermint), // The target host chain (B) is synthetic TM.
+                Some(client_on_a_for_b_height),
+            )
+            .with_timestamp(Timestamp::from_nanoseconds(1000 * 1_000_000_000).unwrap());
         let mut ctx_b = MockContext::new(
-            ChainId::new("mockgaiaB".to_string(), 1),
+            chain_id_b,
             HostType::SyntheticTendermint,
             5,
             chain_b_start_height,
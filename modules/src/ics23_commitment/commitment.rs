@@ -1,4 +1,5 @@
 use crate::ics23_commitment::error::Error;
+use crate::ics23_commitment::merkle::MerkleProof;
 use ibc_proto::ibc::core::commitment::v1::MerkleProof as RawMerkleProof;
 use serde::{Deserialize, Serialize};
 use std::{convert::TryFrom, fmt};
@@ -56,6 +57,16 @@ impl CommitmentProofBytes {
     }
 }
 
+/// Proofs are never generated empty, as the messages carrying empty proofs are malformed.
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for CommitmentProofBytes {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut bytes = vec![u.arbitrary()?];
+        bytes.extend(u.arbitrary::<Vec<u8>>()?);
+        Ok(bytes.into())
+    }
+}
+
 impl From<Vec<u8>> for CommitmentProofBytes {
     fn from(bytes: Vec<u8>) -> Self {
         Self { bytes }
@@ -68,12 +79,12 @@ impl From<CommitmentProofBytes> for Vec<u8> {
     }
 }
 
-// impl From<MerkleProof> for CommitmentProofBytes {
-//     fn from(proof: MerkleProof) -> Self {
-//         let raw_proof: RawMerkleProof = proof.into();
-//         raw_proof.into()
-//     }
-// }
+impl From<MerkleProof> for CommitmentProofBytes {
+    fn from(proof: MerkleProof) -> Self {
+        let raw_proof: RawMerkleProof = proof.into();
+        raw_proof.into()
+    }
+}
 
 impl From<RawMerkleProof> for CommitmentProofBytes {
     fn from(proof: RawMerkleProof) -> Self {
@@ -95,6 +106,7 @@ impl TryFrom<CommitmentProofBytes> for RawMerkleProof {
 }
 
 #[derive(Clone, PartialEq, Eq, Hash, Deserialize, Default)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct CommitmentPrefix {
     bytes: Vec<u8>,
 }
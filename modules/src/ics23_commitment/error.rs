@@ -8,4 +8,25 @@ pub enum Error {
 
     #[error("failed to decode commitment proof")]
     CommitmentProofDecodingFailed(DecodeError),
+
+    #[error("empty merkle proof")]
+    EmptyMerkleProof,
+
+    #[error("empty merkle path")]
+    EmptyMerklePath,
+
+    #[error("the number of proofs ({0}) does not match the number of proof specs ({1})")]
+    NumberOfSpecsMismatch(usize, usize),
+
+    #[error("the number of proofs ({0}) does not match the number of keys ({1})")]
+    NumberOfKeysMismatch(usize, usize),
+
+    #[error("invalid merkle proof: {0}")]
+    InvalidMerkleProof(String),
+
+    #[error("merkle proof verification failed")]
+    VerificationFailure,
+
+    #[error("the computed root does not match the commitment root")]
+    RootMismatch,
 }
@@ -1,10 +1,15 @@
-use tendermint::merkle::proof::Proof;
+use std::convert::{TryFrom, TryInto};
+
+use ics23::commitment_proof::Proof;
+use ics23::{CommitmentProof, NonExistenceProof};
+use tendermint::merkle::proof::Proof as TmProof;
 
 use ibc_proto::ibc::core::commitment::v1::MerklePath;
 use ibc_proto::ibc::core::commitment::v1::MerkleProof as RawMerkleProof;
 
-use crate::ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes};
+use crate::ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes, CommitmentRoot};
 use crate::ics23_commitment::error::Error;
+use crate::ics23_commitment::specs::ProofSpecs;
 
 pub fn apply_prefix(
     prefix: &CommitmentPrefix,
@@ -20,9 +25,178 @@ pub fn apply_prefix(
     Ok(MerklePath { key_path: result })
 }
 
+/// A chain of `ics23` commitment proofs, ordered from the proof of the innermost store (e.g., the
+/// IAVL store of an SDK module) up to the proof which resolves to the commitment root (e.g., the
+/// Tendermint app hash). Each proof is checked against the corresponding specification of a
+/// `ProofSpecs`, and the root computed by each proof is the value proven by the next one.
 #[derive(Clone, Debug, PartialEq)]
 pub struct MerkleProof {
-    pub proof: Vec<CommitmentProofBytes>,
+    pub proofs: Vec<CommitmentProof>,
+}
+
+impl MerkleProof {
+    /// Verifies that `value` is stored at the path `keys`, as returned by `apply_prefix`, in the
+    /// store committed to by `root`. The proofs below `start_index` are skipped, and `value` is
+    /// then the root of the store proven by the proof at `start_index`.
+    pub fn verify_membership(
+        &self,
+        specs: &ProofSpecs,
+        root: &CommitmentRoot,
+        keys: MerklePath,
+        value: Vec<u8>,
+        start_index: usize,
+    ) -> Result<(), Error> {
+        self.check_lengths(specs, &keys)?;
+
+        let mut value = value;
+        for ((proof, spec), key) in self
+            .proofs
+            .iter()
+            .zip(specs.specs().iter())
+            .zip(keys.key_path.iter().rev())
+            .skip(start_index)
+        {
+            let subroot = match &proof.proof {
+                Some(Proof::Exist(existence_proof)) => {
+                    ics23::calculate_existence_root(existence_proof)
+                        .map_err(|e| Error::InvalidMerkleProof(e.to_string()))?
+                }
+                _ => {
+                    return Err(Error::InvalidMerkleProof(
+                        "expected an existence proof".into(),
+                    ))
+                }
+            };
+
+            if !ics23::verify_membership(proof, spec, &subroot, key.as_bytes(), &value) {
+                return Err(Error::VerificationFailure);
+            }
+
+            value = subroot;
+        }
+
+        if root.as_bytes() != value.as_slice() {
+            return Err(Error::RootMismatch);
+        }
+
+        Ok(())
+    }
+
+    /// Verifies that nothing is stored at the path `keys`, as returned by `apply_prefix`, in the
+    /// store committed to by `root`. The innermost proof must be a non-existence proof, while the
+    /// remaining proofs prove the existence of the innermost store root up to `root`.
+    pub fn verify_non_membership(
+        &self,
+        specs: &ProofSpecs,
+        root: &CommitmentRoot,
+        keys: MerklePath,
+    ) -> Result<(), Error> {
+        self.check_lengths(specs, &keys)?;
+
+        // The key of the innermost store is the last one in the path.
+        let key = keys.key_path.last().ok_or(Error::EmptyMerklePath)?;
+        let subroot = match &self.proofs[0].proof {
+            Some(Proof::Nonexist(non_existence_proof)) => {
+                calculate_non_existence_root(non_existence_proof)?
+            }
+            _ => {
+                return Err(Error::InvalidMerkleProof(
+                    "expected a non-existence proof".into(),
+                ))
+            }
+        };
+
+        if !ics23::verify_non_membership(
+            &self.proofs[0],
+            &specs.specs()[0],
+            &subroot,
+            key.as_bytes(),
+        ) {
+            return Err(Error::VerificationFailure);
+        }
+
+        self.verify_membership(specs, root, keys, subroot, 1)
+    }
+
+    fn check_lengths(&self, specs: &ProofSpecs, keys: &MerklePath) -> Result<(), Error> {
+        if self.proofs.len() != specs.specs().len() {
+            return Err(Error::NumberOfSpecsMismatch(
+                self.proofs.len(),
+                specs.specs().len(),
+            ));
+        }
+
+        if self.proofs.len() != keys.key_path.len() {
+            return Err(Error::NumberOfKeysMismatch(
+                self.proofs.len(),
+                keys.key_path.len(),
+            ));
+        }
+
+        if self.proofs.is_empty() {
+            return Err(Error::EmptyMerkleProof);
+        }
+
+        Ok(())
+    }
+}
+
+fn calculate_non_existence_root(proof: &NonExistenceProof) -> Result<Vec<u8>, Error> {
+    let existence_proof = proof
+        .left
+        .as_ref()
+        .or_else(|| proof.right.as_ref())
+        .ok_or_else(|| Error::InvalidMerkleProof("empty non-existence proof".into()))?;
+
+    ics23::calculate_existence_root(existence_proof)
+        .map_err(|e| Error::InvalidMerkleProof(e.to_string()))
+}
+
+impl TryFrom<RawMerkleProof> for MerkleProof {
+    type Error = Error;
+
+    fn try_from(value: RawMerkleProof) -> Result<Self, Self::Error> {
+        let proofs = value
+            .proofs
+            .iter()
+            .map(|raw| {
+                // Both `CommitmentProof` types implement `prost::Message` and have identical
+                // structures, hence convert by encoding and decoding into the destination type.
+                // Encoding into a vector cannot fail.
+                let mut encoded = Vec::new();
+                prost::Message::encode(raw, &mut encoded).unwrap();
+                prost::Message::decode(&*encoded).map_err(Error::CommitmentProofDecodingFailed)
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(MerkleProof { proofs })
+    }
+}
+
+impl From<MerkleProof> for RawMerkleProof {
+    fn from(value: MerkleProof) -> Self {
+        let proofs = value
+            .proofs
+            .iter()
+            .map(|proof| {
+                // Safety note: the source and target data structures are identical, hence the
+                // encode/decode conversion here should be infallible.
+                let mut encoded = Vec::new();
+                prost::Message::encode(proof, &mut encoded).unwrap();
+                prost::Message::decode(&*encoded).unwrap()
+            })
+            .collect();
+
+        RawMerkleProof { proofs }
+    }
+}
+
+impl TryFrom<CommitmentProofBytes> for MerkleProof {
+    type Error = Error;
+
+    fn try_from(value: CommitmentProofBytes) -> Result<Self, Self::Error> {
+        RawMerkleProof::try_from(value)?.try_into()
+    }
 }
 
 // Merkle Proof serialization notes:
@@ -30,28 +204,21 @@ pub struct MerkleProof {
 // - TmProof: in tendermint-rs/src/merkle/proof.rs:Proof
 // - RawProofOps: in tendermint-proto/tendermint.cyrpto.rs:ProofOps
 // - RawMerkleProof: in ibc-proto/ibc.core.commitment.v1.rs:MerkleProof
-//     - structure that includes a RawProofOps in its only `proof` field.
-//         #[derive(Clone, PartialEq, ::prost::Message)]
-//         pub struct MerkleProof {
-//             #[prost(message, optional, tag="1")]
-//             pub proof: ::std::option::Option<::tendermint_proto::crypto::ProofOps>,
-//         }
+//     - structure that includes the chain of raw `ics23` proofs in its only `proofs` field.
 //  - Vec<u8>: RawMerkleProof is not explicitly used but, serialized as Vec<u8>, it is
 //       included in all handshake messages that require proofs (i.e. all except the two `OpenInit`),
 //       and also in all queries that require proofs
-//  - MerkleProof: Domain type for RawMerkleProof, currently not used and identical to RawMerkleProof.
-//       This will change with verification implementation.
+//  - MerkleProof: Domain type for RawMerkleProof, which holds the `ics23` crate proofs and
+//       implements the verification.
 //  - CommitmentProof: Defined in ibc-rs as Vec<u8> and currently used in all its messages
 //
 // Here are a couple of flows that illustrate the different conversions:
 // IBC Messages and Handlers: sink happens in the handle verification
-//    Vec<u8> -> CommitmentProof -> RawMerkleProof -> MerkleProof
+//    Vec<u8> -> CommitmentProof -> RawMerkleProof -> MerkleProof -> verify()
 //
 // Relayer: from the proof in the  query response to the proof being included in a message
 //    TmProof -> RawProofOps => RawMerkleProof -> MerkleProof -> verify()
 //      -> MerkleProof -> RawMerkleProof -> CommitmentProof -> Vec<u8>
-// Note: current implementation for ^ is simplified since verification is not yet implemented:
-//    TmProof -> RawProofOps => RawMerkleProof -> CommitmentProof -> Vec<u8>
 //
 // Implementations of (de)serializers and conversions:
 //  - commitment.rs:
@@ -59,25 +226,13 @@ pub struct MerkleProof {
 //      CommitmentProof <-> RawMerkleProof
 //  - merkle.rs:
 //      RawMerkleProof <-> MerkleProof
+//      CommitmentProof <-> MerkleProof
 //  - tendermint-rs/src/merkle/proof.rs:
 //      TmProof <-> RawProofOps
 //  - cosmos.rs:abci_query() converts from query proof to Merkle proof:
 //      RawProofOps => RawMerkleProof
-//
-// impl TryFrom<RawMerkleProof> for MerkleProof {
-//     type Error = Error;
-//     fn try_from(value: RawMerkleProof) -> Result<Self, Self::Error> {
-//         Ok(MerkleProof { proof: value.proofs.into_iter().map(|v| v.into()).collect() })
-//     }
-// }
-//
-// impl From<MerkleProof> for RawMerkleProof {
-//     fn from(value: MerkleProof) -> Self {
-//         RawMerkleProof { proof: value.proof }
-//     }
-// }
 
-pub fn convert_tm_to_ics_merkle_proof(tm_proof: &Proof) -> Result<RawMerkleProof, Error> {
+pub fn convert_tm_to_ics_merkle_proof(tm_proof: &TmProof) -> Result<RawMerkleProof, Error> {
     let mut proofs = vec![];
 
     for op in &tm_proof.ops {
@@ -90,3 +245,263 @@ pub fn convert_tm_to_ics_merkle_proof(tm_proof: &Proof) -> Result<RawMerkleProof
 
     Ok(RawMerkleProof { proofs })
 }
+
+#[cfg(test)]
+pub mod test_util {
+    use ics23::commitment_proof::Proof;
+    use ics23::{
+        CommitmentProof, ExistenceProof, HashOp, InnerOp, LeafOp, LengthOp, NonExistenceProof,
+    };
+
+    use crate::ics23_commitment::commitment::{CommitmentPrefix, CommitmentRoot};
+    use crate::ics23_commitment::merkle::MerkleProof;
+
+    /// A leaf of an IAVL tree, with its height (0), size (1) and version (1) encoded as zig-zag
+    /// varints in the prefix of the leaf operation.
+    fn iavl_leaf(key: &str, value: &[u8]) -> ExistenceProof {
+        ExistenceProof {
+            key: key.as_bytes().to_vec(),
+            value: value.to_vec(),
+            leaf: Some(LeafOp {
+                hash: HashOp::Sha256 as i32,
+                prehash_key: HashOp::NoHash as i32,
+                prehash_value: HashOp::Sha256 as i32,
+                length: LengthOp::VarProto as i32,
+                prefix: vec![0, 2, 2],
+            }),
+            path: vec![],
+        }
+    }
+
+    /// An inner node of an IAVL tree of height 1, size 2 and version 1, with the (length
+    /// prefixed) hash of the sibling of the proven child.
+    fn iavl_inner(sibling: &[u8], left_child: bool) -> InnerOp {
+        let mut prefix = vec![2, 4, 2, 32];
+        let mut suffix = vec![];
+        if left_child {
+            suffix.push(32);
+            suffix.extend_from_slice(sibling);
+        } else {
+            prefix.extend_from_slice(sibling);
+            prefix.push(32);
+        }
+
+        InnerOp {
+            hash: HashOp::Sha256 as i32,
+            prefix,
+            suffix,
+        }
+    }
+
+    /// Chains the proof of a key in the IAVL store with the proof of the root of this store,
+    /// `store_root`, which is the only leaf of the Tendermint tree of the stores under `prefix`.
+    fn chain(
+        prefix: &CommitmentPrefix,
+        store_proof: Proof,
+        store_root: Vec<u8>,
+    ) -> (CommitmentRoot, MerkleProof) {
+        let prefix_proof = ExistenceProof {
+            key: format!("{:?}", prefix).into_bytes(),
+            value: store_root,
+            leaf: Some(LeafOp {
+                hash: HashOp::Sha256 as i32,
+                prehash_key: HashOp::NoHash as i32,
+                prehash_value: HashOp::Sha256 as i32,
+                length: LengthOp::VarProto as i32,
+                prefix: vec![0],
+            }),
+            path: vec![],
+        };
+        let root = ics23::calculate_existence_root(&prefix_proof).unwrap();
+
+        let proof = MerkleProof {
+            proofs: vec![
+                CommitmentProof {
+                    proof: Some(store_proof),
+                },
+                CommitmentProof {
+                    proof: Some(Proof::Exist(prefix_proof)),
+                },
+            ],
+        };
+        (CommitmentRoot::from_bytes(&root), proof)
+    }
+
+    /// Commits to `entries`, one or two of them with distinct keys in increasing order, in an IAVL
+    /// store which is the only store under `prefix`, as in a Cosmos SDK chain. Returns the
+    /// commitment root together with the proofs of the entries, which satisfy
+    /// `ProofSpecs::cosmos()`.
+    pub fn get_cosmos_membership_proofs(
+        prefix: &CommitmentPrefix,
+        entries: &[(String, Vec<u8>)],
+    ) -> (CommitmentRoot, Vec<MerkleProof>) {
+        let mut leaves: Vec<ExistenceProof> = entries
+            .iter()
+            .map(|(key, value)| iavl_leaf(key, value))
+            .collect();
+
+        match leaves.as_mut_slice() {
+            [_] => {}
+            [left, right] => {
+                assert!(left.key < right.key);
+                let left_hash = ics23::calculate_existence_root(left).unwrap();
+                let right_hash = ics23::calculate_existence_root(right).unwrap();
+                left.path.push(iavl_inner(&right_hash, true));
+                right.path.push(iavl_inner(&left_hash, false));
+            }
+            _ => panic!("expected one or two entries"),
+        }
+
+        let store_root = ics23::calculate_existence_root(&leaves[0]).unwrap();
+        let proofs: Vec<(CommitmentRoot, MerkleProof)> = leaves
+            .into_iter()
+            .map(|leaf| chain(prefix, Proof::Exist(leaf), store_root.clone()))
+            .collect();
+
+        let root = proofs[0].0.clone();
+        (root, proofs.into_iter().map(|(_, proof)| proof).collect())
+    }
+
+    /// Commits to a single entry, whose key `stored_key` must be greater than `key`, as in
+    /// `get_cosmos_membership_proofs`. Returns the commitment root together with the proof that
+    /// nothing is stored at `key`.
+    pub fn get_cosmos_non_membership_proof(
+        prefix: &CommitmentPrefix,
+        key: &str,
+        stored_key: &str,
+    ) -> (CommitmentRoot, MerkleProof) {
+        assert!(key < stored_key);
+        let right = iavl_leaf(stored_key, &[1]);
+        let store_root = ics23::calculate_existence_root(&right).unwrap();
+
+        let store_proof = Proof::Nonexist(NonExistenceProof {
+            key: key.as_bytes().to_vec(),
+            left: None,
+            right: Some(right),
+        });
+        chain(prefix, store_proof, store_root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use ibc_proto::ibc::core::commitment::v1::MerkleProof as RawMerkleProof;
+    use test_env_log::test;
+
+    use crate::ics23_commitment::commitment::test_util::get_dummy_merkle_proof;
+    use crate::ics23_commitment::commitment::{CommitmentPrefix, CommitmentRoot};
+    use crate::ics23_commitment::error::Error;
+    use crate::ics23_commitment::merkle::test_util::{
+        get_cosmos_membership_proofs, get_cosmos_non_membership_proof,
+    };
+    use crate::ics23_commitment::merkle::{apply_prefix, MerkleProof};
+    use crate::ics23_commitment::specs::ProofSpecs;
+
+    #[test]
+    fn raw_merkle_proof_roundtrip() {
+        let raw = get_dummy_merkle_proof();
+        let proof = MerkleProof::try_from(raw.clone()).unwrap();
+        assert_eq!(proof.proofs.len(), 1);
+        assert_eq!(RawMerkleProof::from(proof), raw);
+    }
+
+    #[test]
+    fn verify_rejects_malformed_proofs() {
+        let specs = ProofSpecs::cosmos();
+        let root = CommitmentRoot::from_bytes(&[1; 32]);
+        let prefix = CommitmentPrefix::from(b"ibc".to_vec());
+        let keys =
+            apply_prefix(&prefix, vec!["clients/07-tendermint-0/clientState".into()]).unwrap();
+
+        // A single proof cannot be chained up to the root of a Cosmos SDK chain.
+        let proof = MerkleProof::try_from(get_dummy_merkle_proof()).unwrap();
+        let err = proof
+            .verify_membership(&specs, &root, keys.clone(), vec![1], 0)
+            .unwrap_err();
+        assert_eq!(err, Error::NumberOfSpecsMismatch(1, 2));
+
+        let mut raw = get_dummy_merkle_proof();
+        raw.proofs.push(raw.proofs[0].clone());
+        let proof = MerkleProof::try_from(raw).unwrap();
+
+        let mut long_keys = keys.clone();
+        long_keys.key_path.push("extra".into());
+        let err = proof
+            .verify_non_membership(&specs, &root, long_keys)
+            .unwrap_err();
+        assert_eq!(err, Error::NumberOfKeysMismatch(2, 3));
+
+        // Empty proofs are neither existence nor non-existence proofs.
+        let err = proof
+            .verify_membership(&specs, &root, keys.clone(), vec![1], 0)
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidMerkleProof(_)));
+        let err = proof
+            .verify_non_membership(&specs, &root, keys)
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidMerkleProof(_)));
+    }
+
+    #[test]
+    fn verify_membership_of_cosmos_proofs() {
+        let specs = ProofSpecs::cosmos();
+        let prefix = CommitmentPrefix::from(b"ibc".to_vec());
+        let keys = |key: &str| apply_prefix(&prefix, vec![key.to_string()]).unwrap();
+
+        let entries = vec![
+            ("clients/07-tendermint-0/clientState".to_string(), vec![1]),
+            ("connections/connection-0".to_string(), vec![2]),
+        ];
+        let (root, proofs) = get_cosmos_membership_proofs(&prefix, &entries);
+
+        for ((key, value), proof) in entries.iter().zip(proofs.iter()) {
+            proof
+                .verify_membership(&specs, &root, keys(key), value.clone(), 0)
+                .unwrap();
+
+            // The proof binds the key to its value, and chains up to the root.
+            let err = proof
+                .verify_membership(&specs, &root, keys(key), vec![3], 0)
+                .unwrap_err();
+            assert_eq!(err, Error::VerificationFailure);
+            let wrong_root = CommitmentRoot::from_bytes(&[0; 32]);
+            let err = proof
+                .verify_membership(&specs, &wrong_root, keys(key), value.clone(), 0)
+                .unwrap_err();
+            assert_eq!(err, Error::RootMismatch);
+        }
+
+        // A proof of one key does not prove the other one.
+        let err = proofs[0]
+            .verify_membership(&specs, &root, keys(&entries[1].0), vec![2], 0)
+            .unwrap_err();
+        assert_eq!(err, Error::VerificationFailure);
+    }
+
+    #[test]
+    fn verify_non_membership_of_cosmos_proofs() {
+        let specs = ProofSpecs::cosmos();
+        let prefix = CommitmentPrefix::from(b"ibc".to_vec());
+        let keys = |key: &str| apply_prefix(&prefix, vec![key.to_string()]).unwrap();
+
+        let absent_key = "receipts/ports/transfer/channels/channel-0/sequences/1";
+        let stored_key = "receipts/ports/transfer/channels/channel-0/sequences/2";
+        let (root, proof) = get_cosmos_non_membership_proof(&prefix, absent_key, stored_key);
+
+        proof
+            .verify_non_membership(&specs, &root, keys(absent_key))
+            .unwrap();
+
+        // The proof does not prove the absence of the stored key, nor the existence of any key.
+        let err = proof
+            .verify_non_membership(&specs, &root, keys(stored_key))
+            .unwrap_err();
+        assert_eq!(err, Error::VerificationFailure);
+        let err = proof
+            .verify_membership(&specs, &root, keys(absent_key), vec![1], 0)
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidMerkleProof(_)));
+    }
+}
@@ -1,4 +1,5 @@
 use ics23::ProofSpec;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use ibc_proto::ics23::ProofSpec as ProtoProofSpec;
 
@@ -9,6 +10,7 @@ use ibc_proto::ics23::ProofSpec as ProtoProofSpec;
 /// Additionally, this type also aids in the conversion from `ProofSpec` types from crate `ics23`
 /// into proof specifications as represented in the `ibc_proto` type; see the
 /// `From` trait(s) below.
+#[derive(Clone, Debug, PartialEq)]
 pub struct ProofSpecs {
     specs: Vec<ProofSpec>,
 }
@@ -23,6 +25,56 @@ impl ProofSpecs {
             ],
         }
     }
+
+    /// Returns the individual specifications, ordered from the innermost store to the root.
+    pub fn specs(&self) -> &[ProofSpec] {
+        &self.specs
+    }
+}
+
+// The specifications hold no floating point numbers, hence equality is an equivalence relation.
+impl Eq for ProofSpecs {}
+
+/// The `ics23` types do not implement `serde`, hence the specifications are serialized as their
+/// protobuf encodings.
+impl Serialize for ProofSpecs {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let encoded: Vec<Vec<u8>> = self
+            .specs
+            .iter()
+            .map(|spec| {
+                let mut bytes = Vec::new();
+                // Encoding into a vector cannot fail.
+                prost::Message::encode(spec, &mut bytes).unwrap();
+                bytes
+            })
+            .collect();
+        encoded.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ProofSpecs {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let encoded = Vec::<Vec<u8>>::deserialize(deserializer)?;
+        let specs = encoded
+            .iter()
+            .map(|bytes| prost::Message::decode(bytes.as_slice()))
+            .collect::<Result<_, _>>()
+            .map_err(serde::de::Error::custom)?;
+        Ok(Self { specs })
+    }
+}
+
+impl From<Vec<ProofSpec>> for ProofSpecs {
+    fn from(specs: Vec<ProofSpec>) -> Self {
+        Self { specs }
+    }
 }
 
 /// Converts from the domain type (which is represented as a vector of `ics23::ProofSpec`
@@ -44,3 +96,20 @@ impl From<ProofSpecs> for Vec<ProtoProofSpec> {
         raw_specs
     }
 }
+
+/// Converts from the proto type (vector of `ibc_proto::ProofSpec`) to the domain type, the
+/// inverse of the conversion above.
+impl From<Vec<ProtoProofSpec>> for ProofSpecs {
+    fn from(raw_specs: Vec<ProtoProofSpec>) -> Self {
+        let specs = raw_specs
+            .iter()
+            .map(|rs| {
+                // As above, both types are identical, hence the conversion cannot fail.
+                let mut encoded = Vec::new();
+                prost::Message::encode(rs, &mut encoded).unwrap();
+                prost::Message::decode(&*encoded).unwrap()
+            })
+            .collect();
+        Self { specs }
+    }
+}
@@ -9,6 +9,11 @@ use crate::ics24_host::error::ValidationKind;
 use super::error::ValidationError;
 use super::validate::*;
 
+/// The largest counter of the client, connection and channel identifiers which are generated for
+/// fuzzing and property tests.
+#[cfg(feature = "arbitrary")]
+const ARBITRARY_MAX_COUNTER: u64 = 3;
+
 /// This type is subject to future changes.
 ///
 /// TODO: ChainId validation is not standardized yet.
@@ -129,6 +134,16 @@ impl Default for ChainId {
     }
 }
 
+/// Chain identifiers are generated from a couple of names and small versions, such that the
+/// generated values often match one another. The same goes for the other identifiers below.
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for ChainId {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let name = u.choose(&["mockgaia", "ibc"])?;
+        Ok(Self::new(name.to_string(), u.int_in_range(0..=1)?))
+    }
+}
+
 impl TryFrom<String> for ChainId {
     type Error = ValidationKind;
 
@@ -169,6 +184,8 @@ impl ClientId {
     pub fn prefix(client_type: ClientType) -> &'static str {
         match client_type {
             ClientType::Tendermint => ClientType::Tendermint.as_string(),
+            ClientType::SoloMachine => ClientType::SoloMachine.as_string(),
+            ClientType::Localhost => ClientType::Localhost.as_string(),
 
             #[cfg(any(test, feature = "mocks"))]
             ClientType::Mock => ClientType::Mock.as_string(),
@@ -202,6 +219,14 @@ impl Default for ClientId {
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for ClientId {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Self::new(u.arbitrary()?, u.int_in_range(0..=ARBITRARY_MAX_COUNTER)?)
+            .map_err(|_| arbitrary::Error::IncorrectFormat)
+    }
+}
+
 /// Equality check against string literal (satisfies &ClientId == &str).
 /// ```
 /// use std::str::FromStr;
@@ -272,6 +297,13 @@ impl Default for ConnectionId {
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for ConnectionId {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self::new(u.int_in_range(0..=ARBITRARY_MAX_COUNTER)?))
+    }
+}
+
 /// Equality check against string literal (satisfies &ConnectionId == &str).
 /// ```
 /// use std::str::FromStr;
@@ -322,6 +354,14 @@ impl Default for PortId {
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for PortId {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let id = u.choose(&["defaultPort", "transfer"])?;
+        Self::from_str(id).map_err(|_| arbitrary::Error::IncorrectFormat)
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct ChannelId(String);
 
@@ -378,6 +418,13 @@ impl Default for ChannelId {
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for ChannelId {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self::new(u.int_in_range(0..=ARBITRARY_MAX_COUNTER)?))
+    }
+}
+
 /// Equality check against string literal (satisfies &ChannelId == &str).
 impl PartialEq<str> for ChannelId {
     fn eq(&self, other: &str) -> bool {
@@ -1,8 +1,8 @@
-use crate::application::ics20_fungible_token_transfer::context::Ics20Context;
 use crate::ics02_client::context::{ClientKeeper, ClientReader};
 use crate::ics03_connection::context::{ConnectionKeeper, ConnectionReader};
 use crate::ics04_channel::context::{ChannelKeeper, ChannelReader};
 use crate::ics05_port::context::PortReader;
+use crate::ics26_routing::router::Router;
 
 /// This trait captures all the functional dependencies (i.e., context) which the ICS26 module
 /// requires to be able to dispatch and process IBC messages. In other words, this is the
@@ -15,7 +15,22 @@ pub trait Ics26Context:
     + ChannelKeeper
     + ChannelReader
     + PortReader
-    + Ics20Context
     + Clone
 {
+    /// Returns the router which binds the ports of this chain to their application modules.
+    fn router(&self) -> &Router<Self>;
+
+    /// Returns a branch of this context, to which the messages of a transaction are applied
+    /// before being committed all at once with `commit_branch`, much like the Cosmos SDK caches
+    /// the writes of a transaction over its store. By default, the branch is a clone of the
+    /// context: hosts should either make cloning cheap, e.g. by sharing their stores copy-on-write
+    /// like `MockContext` does, or return a cache over their store.
+    fn branch(&self) -> Self {
+        self.clone()
+    }
+
+    /// Applies the writes of `branch`, which was returned by `branch`, to this context.
+    fn commit_branch(&mut self, branch: Self) {
+        *self = branch;
+    }
 }
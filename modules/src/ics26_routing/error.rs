@@ -1,6 +1,8 @@
 use anomaly::{BoxError, Context};
 use thiserror::Error;
 
+use crate::ics24_host::identifier::PortId;
+
 pub type Error = anomaly::Error<Kind>;
 
 #[derive(Clone, Debug, Error, PartialEq, Eq)]
@@ -16,6 +18,12 @@ pub enum Kind {
 
     #[error("the message is malformed and cannot be decoded")]
     MalformedMessageBytes,
+
+    #[error("a module is already bound to port {0}")]
+    DuplicateRoute(PortId),
+
+    #[error("no module is bound to port {0}")]
+    RouteNotFound(PortId),
 }
 
 impl Kind {
@@ -2,167 +2,182 @@ use prost_types::Any;
 use tendermint_proto::Protobuf;
 
 use crate::application::ics20_fungible_token_transfer::msgs::transfer;
-use crate::application::ics20_fungible_token_transfer::relay_application_logic::send_transfer::send_transfer as ics20_msg_dispatcher;
+use crate::handler::HandlerOutput;
+use crate::ics02_client::client_state::AnyClientState;
 use crate::ics02_client::handler::dispatch as ics2_msg_dispatcher;
-use crate::ics02_client::msgs::{create_client, update_client, upgrade_client, ClientMsg};
+use crate::ics02_client::handler::recover_client::process as ics2_recover_client;
+use crate::ics02_client::msgs::{
+    create_client, misbehavior, update_client, upgrade_client, ClientMsg,
+};
 use crate::ics03_connection::handler::dispatch as ics3_msg_dispatcher;
 use crate::ics03_connection::msgs::{
     conn_open_ack, conn_open_confirm, conn_open_init, conn_open_try, ConnectionMsg,
 };
+use crate::ics04_channel::handler::channel_callback as ics4_channel_callback;
+use crate::ics04_channel::handler::channel_capability as ics4_channel_capability;
 use crate::ics04_channel::handler::channel_dispatch as ics4_msg_dispatcher;
+use crate::ics04_channel::handler::packet_callback as ics04_packet_callback;
+use crate::ics04_channel::handler::packet_capability as ics04_packet_capability;
 use crate::ics04_channel::handler::packet_dispatch as ics04_packet_msg_dispatcher;
-use crate::{events::IbcEvent, handler::HandlerOutput};
 
 use crate::ics04_channel::msgs::{
     acknowledgement, chan_close_confirm, chan_close_init, chan_open_ack, chan_open_confirm,
     chan_open_init, chan_open_try, recv_packet, timeout, timeout_on_close, ChannelMsg, PacketMsg,
 };
+use crate::ics24_host::identifier::ClientId;
 use crate::ics26_routing::context::Ics26Context;
 use crate::ics26_routing::error::{Error, Kind};
 use crate::ics26_routing::msgs::Ics26Envelope::{
     self, Ics20Msg, Ics2Msg, Ics3Msg, Ics4ChannelMsg, Ics4PacketMsg,
 };
+use crate::tx_msg::Msg;
 
 /// Mimics the DeliverTx ABCI interface, but a slightly lower level. No need for authentication
 /// info or signature checks here.
 /// https://github.com/cosmos/cosmos-sdk/tree/master/docs/basics
-/// Returns a vector of all events that got generated as a byproduct of processing `messages`.
-pub fn deliver<Ctx>(ctx: &mut Ctx, messages: Vec<Any>) -> Result<Vec<IbcEvent>, Error>
+///
+/// The messages are applied all-or-nothing, like those of a Cosmos SDK transaction: they are
+/// processed in order against a branch of the context (see `Ics26Context::branch`), which is
+/// committed only once all of them succeeded. Returns the log and the events of each message.
+pub fn deliver<Ctx>(ctx: &mut Ctx, messages: Vec<Any>) -> Result<Vec<HandlerOutput<()>>, Error>
 where
     Ctx: Ics26Context,
 {
-    // Create a clone, which will store each intermediary stage of applying txs.
-    let mut ctx_interim = ctx.clone();
-
-    // A buffer for all the events, to be used as return value.
-    let mut res: Vec<IbcEvent> = vec![];
-
-    for any_msg in messages {
-        // Decode the proto message into a domain message, creating an ICS26 envelope.
-        let envelope = match any_msg.type_url.as_str() {
-            // ICS2 messages
-            create_client::TYPE_URL => {
-                // Pop out the message and then wrap it in the corresponding type.
-                let domain_msg = create_client::MsgCreateAnyClient::decode_vec(&any_msg.value)
-                    .map_err(|e| Kind::MalformedMessageBytes.context(e))?;
-                Ok(Ics2Msg(ClientMsg::CreateClient(domain_msg)))
-            }
-            update_client::TYPE_URL => {
-                let domain_msg = update_client::MsgUpdateAnyClient::decode_vec(&any_msg.value)
-                    .map_err(|e| Kind::MalformedMessageBytes.context(e))?;
-                Ok(Ics2Msg(ClientMsg::UpdateClient(domain_msg)))
-            }
-            upgrade_client::TYPE_URL => {
-                let domain_msg = upgrade_client::MsgUpgradeAnyClient::decode_vec(&any_msg.value)
-                    .map_err(|e| Kind::MalformedMessageBytes.context(e))?;
-                Ok(Ics2Msg(ClientMsg::UpgradeClient(domain_msg)))
-            }
+    let mut branch = ctx.branch();
 
-            // ICS03
-            conn_open_init::TYPE_URL => {
-                let domain_msg = conn_open_init::MsgConnectionOpenInit::decode_vec(&any_msg.value)
-                    .map_err(|e| Kind::MalformedMessageBytes.context(e))?;
-                Ok(Ics3Msg(ConnectionMsg::ConnectionOpenInit(domain_msg)))
-            }
-            conn_open_try::TYPE_URL => {
-                let domain_msg = conn_open_try::MsgConnectionOpenTry::decode_vec(&any_msg.value)
-                    .map_err(|e| Kind::MalformedMessageBytes.context(e))?;
-                Ok(Ics3Msg(ConnectionMsg::ConnectionOpenTry(Box::new(
-                    domain_msg,
-                ))))
-            }
-            conn_open_ack::TYPE_URL => {
-                let domain_msg = conn_open_ack::MsgConnectionOpenAck::decode_vec(&any_msg.value)
-                    .map_err(|e| Kind::MalformedMessageBytes.context(e))?;
-                Ok(Ics3Msg(ConnectionMsg::ConnectionOpenAck(Box::new(
-                    domain_msg,
-                ))))
-            }
-            conn_open_confirm::TYPE_URL => {
-                let domain_msg =
-                    conn_open_confirm::MsgConnectionOpenConfirm::decode_vec(&any_msg.value)
-                        .map_err(|e| Kind::MalformedMessageBytes.context(e))?;
-                Ok(Ics3Msg(ConnectionMsg::ConnectionOpenConfirm(domain_msg)))
-            }
+    let outputs = messages
+        .into_iter()
+        .map(|any_msg| dispatch(&mut branch, decode(any_msg)?))
+        .collect::<Result<Vec<_>, _>>()?;
 
-            // ICS04 channel messages
-            chan_open_init::TYPE_URL => {
-                let domain_msg = chan_open_init::MsgChannelOpenInit::decode_vec(&any_msg.value)
-                    .map_err(|e| Kind::MalformedMessageBytes.context(e))?;
-                Ok(Ics4ChannelMsg(ChannelMsg::ChannelOpenInit(domain_msg)))
-            }
-            chan_open_try::TYPE_URL => {
-                let domain_msg = chan_open_try::MsgChannelOpenTry::decode_vec(&any_msg.value)
-                    .map_err(|e| Kind::MalformedMessageBytes.context(e))?;
-                Ok(Ics4ChannelMsg(ChannelMsg::ChannelOpenTry(domain_msg)))
-            }
-            chan_open_ack::TYPE_URL => {
-                let domain_msg = chan_open_ack::MsgChannelOpenAck::decode_vec(&any_msg.value)
-                    .map_err(|e| Kind::MalformedMessageBytes.context(e))?;
-                Ok(Ics4ChannelMsg(ChannelMsg::ChannelOpenAck(domain_msg)))
-            }
-            chan_open_confirm::TYPE_URL => {
-                let domain_msg =
-                    chan_open_confirm::MsgChannelOpenConfirm::decode_vec(&any_msg.value)
-                        .map_err(|e| Kind::MalformedMessageBytes.context(e))?;
-                Ok(Ics4ChannelMsg(ChannelMsg::ChannelOpenConfirm(domain_msg)))
-            }
-            chan_close_init::TYPE_URL => {
-                let domain_msg = chan_close_init::MsgChannelCloseInit::decode_vec(&any_msg.value)
-                    .map_err(|e| Kind::MalformedMessageBytes.context(e))?;
-                Ok(Ics4ChannelMsg(ChannelMsg::ChannelCloseInit(domain_msg)))
-            }
-            chan_close_confirm::TYPE_URL => {
-                let domain_msg =
-                    chan_close_confirm::MsgChannelCloseConfirm::decode_vec(&any_msg.value)
-                        .map_err(|e| Kind::MalformedMessageBytes.context(e))?;
-                Ok(Ics4ChannelMsg(ChannelMsg::ChannelCloseConfirm(domain_msg)))
-            }
-            // ICS20 - 04 - Send packet
-            transfer::TYPE_URL => {
-                let domain_msg = transfer::MsgTransfer::decode_vec(&any_msg.value)
-                    .map_err(|e| Kind::MalformedMessageBytes.context(e))?;
-                Ok(Ics20Msg(domain_msg))
-            }
-            // ICS04 packet messages
-            recv_packet::TYPE_URL => {
-                let domain_msg = recv_packet::MsgRecvPacket::decode_vec(&any_msg.value)
-                    .map_err(|e| Kind::MalformedMessageBytes.context(e))?;
-                Ok(Ics4PacketMsg(PacketMsg::RecvPacket(domain_msg)))
-            }
-            acknowledgement::TYPE_URL => {
-                let domain_msg = acknowledgement::MsgAcknowledgement::decode_vec(&any_msg.value)
-                    .map_err(|e| Kind::MalformedMessageBytes.context(e))?;
-                Ok(Ics4PacketMsg(PacketMsg::AckPacket(domain_msg)))
-            }
-            timeout::TYPE_URL => {
-                let domain_msg = timeout::MsgTimeout::decode_vec(&any_msg.value)
-                    .map_err(|e| Kind::MalformedMessageBytes.context(e))?;
-                Ok(Ics4PacketMsg(PacketMsg::ToPacket(domain_msg)))
-            }
-            timeout_on_close::TYPE_URL => {
-                let domain_msg = timeout_on_close::MsgTimeoutOnClose::decode_vec(&any_msg.value)
+    // No error has surfaced, so we now apply the changes permanently to the original context.
+    ctx.commit_branch(branch);
+    Ok(outputs)
+}
+
+/// Decodes a proto message into a domain message, wrapped in an ICS26 envelope.
+pub fn decode(any_msg: Any) -> Result<Ics26Envelope, Error> {
+    match any_msg.type_url.as_str() {
+        // ICS2 messages
+        create_client::TYPE_URL => {
+            // Pop out the message and then wrap it in the corresponding type.
+            let domain_msg = create_client::MsgCreateAnyClient::decode_vec(&any_msg.value)
+                .map_err(|e| Kind::MalformedMessageBytes.context(e))?;
+            Ok(Ics2Msg(ClientMsg::CreateClient(domain_msg)))
+        }
+        update_client::TYPE_URL => {
+            let domain_msg = update_client::MsgUpdateAnyClient::decode_vec(&any_msg.value)
+                .map_err(|e| Kind::MalformedMessageBytes.context(e))?;
+            Ok(Ics2Msg(ClientMsg::UpdateClient(domain_msg)))
+        }
+        upgrade_client::TYPE_URL => {
+            let domain_msg = upgrade_client::MsgUpgradeAnyClient::decode_vec(&any_msg.value)
+                .map_err(|e| Kind::MalformedMessageBytes.context(e))?;
+            Ok(Ics2Msg(ClientMsg::UpgradeClient(domain_msg)))
+        }
+        misbehavior::TYPE_URL => {
+            let domain_msg = misbehavior::MsgSubmitAnyMisbehaviour::decode_vec(&any_msg.value)
+                .map_err(|e| Kind::MalformedMessageBytes.context(e))?;
+            Ok(Ics2Msg(ClientMsg::Misbehaviour(domain_msg)))
+        }
+
+        // ICS03
+        conn_open_init::TYPE_URL => {
+            let domain_msg = conn_open_init::MsgConnectionOpenInit::decode_vec(&any_msg.value)
+                .map_err(|e| Kind::MalformedMessageBytes.context(e))?;
+            Ok(Ics3Msg(ConnectionMsg::ConnectionOpenInit(domain_msg)))
+        }
+        conn_open_try::TYPE_URL => {
+            let domain_msg = conn_open_try::MsgConnectionOpenTry::decode_vec(&any_msg.value)
+                .map_err(|e| Kind::MalformedMessageBytes.context(e))?;
+            Ok(Ics3Msg(ConnectionMsg::ConnectionOpenTry(Box::new(
+                domain_msg,
+            ))))
+        }
+        conn_open_ack::TYPE_URL => {
+            let domain_msg = conn_open_ack::MsgConnectionOpenAck::decode_vec(&any_msg.value)
+                .map_err(|e| Kind::MalformedMessageBytes.context(e))?;
+            Ok(Ics3Msg(ConnectionMsg::ConnectionOpenAck(Box::new(
+                domain_msg,
+            ))))
+        }
+        conn_open_confirm::TYPE_URL => {
+            let domain_msg =
+                conn_open_confirm::MsgConnectionOpenConfirm::decode_vec(&any_msg.value)
                     .map_err(|e| Kind::MalformedMessageBytes.context(e))?;
-                Ok(Ics4PacketMsg(PacketMsg::ToClosePacket(domain_msg)))
-            }
+            Ok(Ics3Msg(ConnectionMsg::ConnectionOpenConfirm(domain_msg)))
+        }
 
-            _ => Err(Kind::UnknownMessageTypeUrl(any_msg.type_url)),
-        }?;
+        // ICS04 channel messages
+        chan_open_init::TYPE_URL => {
+            let domain_msg = chan_open_init::MsgChannelOpenInit::decode_vec(&any_msg.value)
+                .map_err(|e| Kind::MalformedMessageBytes.context(e))?;
+            Ok(Ics4ChannelMsg(ChannelMsg::ChannelOpenInit(domain_msg)))
+        }
+        chan_open_try::TYPE_URL => {
+            let domain_msg = chan_open_try::MsgChannelOpenTry::decode_vec(&any_msg.value)
+                .map_err(|e| Kind::MalformedMessageBytes.context(e))?;
+            Ok(Ics4ChannelMsg(ChannelMsg::ChannelOpenTry(domain_msg)))
+        }
+        chan_open_ack::TYPE_URL => {
+            let domain_msg = chan_open_ack::MsgChannelOpenAck::decode_vec(&any_msg.value)
+                .map_err(|e| Kind::MalformedMessageBytes.context(e))?;
+            Ok(Ics4ChannelMsg(ChannelMsg::ChannelOpenAck(domain_msg)))
+        }
+        chan_open_confirm::TYPE_URL => {
+            let domain_msg = chan_open_confirm::MsgChannelOpenConfirm::decode_vec(&any_msg.value)
+                .map_err(|e| Kind::MalformedMessageBytes.context(e))?;
+            Ok(Ics4ChannelMsg(ChannelMsg::ChannelOpenConfirm(domain_msg)))
+        }
+        chan_close_init::TYPE_URL => {
+            let domain_msg = chan_close_init::MsgChannelCloseInit::decode_vec(&any_msg.value)
+                .map_err(|e| Kind::MalformedMessageBytes.context(e))?;
+            Ok(Ics4ChannelMsg(ChannelMsg::ChannelCloseInit(domain_msg)))
+        }
+        chan_close_confirm::TYPE_URL => {
+            let domain_msg = chan_close_confirm::MsgChannelCloseConfirm::decode_vec(&any_msg.value)
+                .map_err(|e| Kind::MalformedMessageBytes.context(e))?;
+            Ok(Ics4ChannelMsg(ChannelMsg::ChannelCloseConfirm(domain_msg)))
+        }
+        // ICS20 - 04 - Send packet
+        transfer::TYPE_URL => {
+            let domain_msg = transfer::MsgTransfer::decode_vec(&any_msg.value)
+                .map_err(|e| Kind::MalformedMessageBytes.context(e))?;
+            Ok(Ics20Msg(domain_msg))
+        }
+        // ICS04 packet messages
+        recv_packet::TYPE_URL => {
+            let domain_msg = recv_packet::MsgRecvPacket::decode_vec(&any_msg.value)
+                .map_err(|e| Kind::MalformedMessageBytes.context(e))?;
+            Ok(Ics4PacketMsg(PacketMsg::RecvPacket(domain_msg)))
+        }
+        acknowledgement::TYPE_URL => {
+            let domain_msg = acknowledgement::MsgAcknowledgement::decode_vec(&any_msg.value)
+                .map_err(|e| Kind::MalformedMessageBytes.context(e))?;
+            Ok(Ics4PacketMsg(PacketMsg::AckPacket(domain_msg)))
+        }
+        timeout::TYPE_URL => {
+            let domain_msg = timeout::MsgTimeout::decode_vec(&any_msg.value)
+                .map_err(|e| Kind::MalformedMessageBytes.context(e))?;
+            Ok(Ics4PacketMsg(PacketMsg::ToPacket(domain_msg)))
+        }
+        timeout_on_close::TYPE_URL => {
+            let domain_msg = timeout_on_close::MsgTimeoutOnClose::decode_vec(&any_msg.value)
+                .map_err(|e| Kind::MalformedMessageBytes.context(e))?;
+            Ok(Ics4PacketMsg(PacketMsg::ToClosePacket(domain_msg)))
+        }
 
-        // Process the envelope, and accumulate any events that were generated.
-        let mut output = dispatch(&mut ctx_interim, envelope)?;
-        // TODO: output.log and output.result are discarded
-        res.append(&mut output.events);
+        _ => Err(Kind::UnknownMessageTypeUrl(any_msg.type_url).into()),
     }
-
-    // No error has surfaced, so we now apply the changes permanently to the original context.
-    *ctx = ctx_interim;
-    Ok(res)
 }
 
 /// Top-level ICS dispatch function. Routes incoming IBC messages to their corresponding module.
 /// Returns a handler output with empty result of type `HandlerOutput<()>` which contains the log
 /// and events produced after processing the input `msg`.
+///
+/// Channel and packet messages are handed over to the application module bound to their port
+/// (see `Ics26Context::router`). So is the only application message which ICS26 decodes, the ICS20
+/// `MsgTransfer`, which goes to the module bound to its source port.
 pub fn dispatch<Ctx>(ctx: &mut Ctx, msg: Ics26Envelope) -> Result<HandlerOutput<()>, Error>
 where
     Ctx: Ics26Context,
@@ -187,8 +202,10 @@ where
                 ics3_msg_dispatcher(ctx, msg).map_err(|e| Kind::HandlerRaisedError.context(e))?;
 
             // Apply any results to the host chain store.
+            let client_state = handler_output.result.client_state.clone();
             ctx.store_connection_result(handler_output.result)
                 .map_err(|e| Kind::KeeperRaisedError.context(e))?;
+            store_verifying_client_state(ctx, client_state)?;
 
             HandlerOutput::builder()
                 .with_log(handler_output.log)
@@ -197,12 +214,21 @@ where
         }
 
         Ics4ChannelMsg(msg) => {
-            let handler_output =
-                ics4_msg_dispatcher(ctx, msg).map_err(|e| Kind::HandlerRaisedError.context(e))?;
+            // The handshake is processed on behalf of the application module bound to the port.
+            let capability = ics4_channel_capability(ctx, &msg)
+                .map_err(|e| Kind::HandlerRaisedError.context(e))?;
+            let mut handler_output = ics4_msg_dispatcher(ctx, &capability, msg.clone())
+                .map_err(|e| Kind::HandlerRaisedError.context(e))?;
+
+            // Let the application module bound to the port accept the handshake.
+            ics4_channel_callback(ctx, &msg, &mut handler_output.result)
+                .map_err(|e| Kind::HandlerRaisedError.context(e))?;
 
             // Apply any results to the host chain store.
+            let client_state = handler_output.result.client_state.clone();
             ctx.store_channel_result(handler_output.result)
                 .map_err(|e| Kind::KeeperRaisedError.context(e))?;
+            store_verifying_client_state(ctx, client_state)?;
 
             HandlerOutput::builder()
                 .with_log(handler_output.log)
@@ -211,12 +237,15 @@ where
         }
 
         Ics20Msg(msg) => {
-            let handler_output =
-                ics20_msg_dispatcher(ctx, msg).map_err(|e| Kind::HandlerRaisedError.context(e))?;
-
-            // Apply any results to the host chain store.
-            ctx.store_packet_result(handler_output.result)
-                .map_err(|e| Kind::KeeperRaisedError.context(e))?;
+            // The transfer is handed over to the application module bound to its source port,
+            // which also stores the packet it sends.
+            let module = ctx
+                .router()
+                .route(&msg.source_port)
+                .ok_or_else(|| Kind::RouteNotFound(msg.source_port.clone()))?;
+            let handler_output = module
+                .deliver(ctx, msg.to_any())
+                .map_err(|e| Kind::HandlerRaisedError.context(e))?;
 
             HandlerOutput::builder()
                 .with_log(handler_output.log)
@@ -225,16 +254,27 @@ where
         }
 
         Ics4PacketMsg(msg) => {
-            let handler_output = ics04_packet_msg_dispatcher(ctx, msg)
+            // The packet is processed on behalf of the application module which owns its channel.
+            let capability = ics04_packet_capability(ctx, &msg)
+                .map_err(|e| Kind::HandlerRaisedError.context(e))?;
+            let handler_output = ics04_packet_msg_dispatcher(ctx, &capability, msg.clone())
                 .map_err(|e| Kind::HandlerRaisedError.context(e))?;
 
             // Apply any results to the host chain store.
+            let client_state = handler_output.result.client_state().cloned();
             ctx.store_packet_result(handler_output.result)
                 .map_err(|e| Kind::KeeperRaisedError.context(e))?;
+            store_verifying_client_state(ctx, client_state)?;
+
+            // Hand the packet over to the application module.
+            let module_output = ics04_packet_callback(ctx, &msg)
+                .map_err(|e| Kind::HandlerRaisedError.context(e))?;
 
             HandlerOutput::builder()
                 .with_log(handler_output.log)
                 .with_events(handler_output.events)
+                .with_log(module_output.log)
+                .with_events(module_output.events)
                 .with_result(())
         }
     };
@@ -242,22 +282,96 @@ where
     Ok(output)
 }
 
+/// Stores the new state of the client which verified the proofs of a connection, channel or packet
+/// message, if verifying them changed it (e.g., a solo machine advances its sequence). No consensus
+/// state is stored for the new height, as such clients verify proofs against the consensus state
+/// in their own state (see `AnyClientState::own_consensus_state`).
+fn store_verifying_client_state<Ctx>(
+    ctx: &mut Ctx,
+    client_state: Option<(ClientId, AnyClientState)>,
+) -> Result<(), Error>
+where
+    Ctx: Ics26Context,
+{
+    if let Some((client_id, client_state)) = client_state {
+        ctx.store_client_state(client_id, client_state)
+            .map_err(|e| Kind::KeeperRaisedError.context(e))?;
+    }
+
+    Ok(())
+}
+
+/// Recovers the frozen or expired client `subject_client_id` with the state of the active client
+/// `substitute_client_id`, and applies the result to the host chain store. Unlike the IBC messages,
+/// this is meant to be invoked by the host chain itself, e.g., once a client update proposal
+/// passes governance. The subject client keeps its identifier, so that the connections and
+/// channels which use it resume once it is recovered.
+pub fn recover_client<Ctx>(
+    ctx: &mut Ctx,
+    subject_client_id: ClientId,
+    substitute_client_id: ClientId,
+) -> Result<HandlerOutput<()>, Error>
+where
+    Ctx: Ics26Context,
+{
+    let handler_output = ics2_recover_client(&*ctx, subject_client_id, substitute_client_id)
+        .map_err(|e| Kind::HandlerRaisedError.context(e))?;
+
+    // Apply the result to the context (host chain store).
+    ctx.store_client_result(handler_output.result)
+        .map_err(|e| Kind::KeeperRaisedError.context(e))?;
+
+    Ok(HandlerOutput::builder()
+        .with_log(handler_output.log)
+        .with_events(handler_output.events)
+        .with_result(()))
+}
+
 #[cfg(test)]
 mod tests {
     use std::convert::TryFrom;
+
+    use ibc_proto::ibc::lightclients::solomachine::v1::{
+        ChannelStateData, ClientStateData, ConnectionStateData, ConsensusStateData, DataType,
+        PacketCommitmentData,
+    };
+    use prost_types::Any;
     use test_env_log::test;
 
+    use crate::application::ics20_fungible_token_transfer::coin::Coin;
+    use crate::application::ics20_fungible_token_transfer::context::BankKeeper;
+    use crate::application::ics20_fungible_token_transfer::module::{
+        Ics20Module, MODULE_ID, VERSION,
+    };
+    use crate::application::ics20_fungible_token_transfer::msgs::transfer::test_util::{
+        get_dummy_coin, get_dummy_msg_transfer,
+    };
+    use crate::application::ics20_fungible_token_transfer::packet::FungibleTokenPacketData;
     use crate::events::IbcEvent;
     use crate::ics02_client::client_consensus::AnyConsensusState;
     use crate::ics02_client::client_state::AnyClientState;
-    use crate::{
-        application::ics20_fungible_token_transfer::msgs::transfer::test_util::get_dummy_msg_transfer,
-        ics23_commitment::commitment::test_util::get_dummy_merkle_proof,
+    use crate::ics02_client::client_type::ClientType;
+    use crate::ics02_client::context::ClientReader;
+    use crate::ics02_client::misbehaviour::AnyMisbehaviour;
+    use crate::ics03_connection::connection::{
+        ConnectionEnd, Counterparty as ConnectionCounterparty, State as ConnectionState,
+    };
+    use crate::ics03_connection::context::ConnectionReader;
+    use crate::ics04_channel::channel::{
+        ChannelEnd, Counterparty as ChannelCounterparty, State as ChannelState,
     };
+    use crate::ics04_channel::commitment::PacketCommitment;
+    use crate::ics04_channel::context::ChannelReader;
+    use crate::ics05_port::capabilities::ModuleId;
+    use crate::ics06_solomachine::client_def::signed_path;
+    use crate::ics06_solomachine::proof::encode;
+    use crate::ics06_solomachine::test_util::get_dummy_proof;
+    use crate::ics23_commitment::commitment::test_util::get_dummy_merkle_proof;
+    use crate::ics23_commitment::commitment::CommitmentPrefix;
 
     use crate::ics02_client::msgs::{
-        create_client::MsgCreateAnyClient, update_client::MsgUpdateAnyClient,
-        upgrade_client::MsgUpgradeAnyClient, ClientMsg,
+        create_client, create_client::MsgCreateAnyClient, misbehavior::MsgSubmitAnyMisbehaviour,
+        update_client::MsgUpdateAnyClient, upgrade_client::MsgUpgradeAnyClient, ClientMsg,
     };
     use crate::ics03_connection::msgs::{
         conn_open_ack::{test_util::get_dummy_raw_msg_conn_open_ack, MsgConnectionOpenAck},
@@ -278,13 +392,18 @@ mod tests {
         ChannelMsg, PacketMsg,
     };
 
-    use crate::ics24_host::identifier::ConnectionId;
-    use crate::ics26_routing::handler::dispatch;
+    use crate::ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId};
+    use crate::ics24_host::Path;
+    use crate::ics26_routing::handler::{deliver, dispatch};
     use crate::ics26_routing::msgs::Ics26Envelope;
     use crate::mock::client_state::{MockClientState, MockConsensusState};
     use crate::mock::context::MockContext;
     use crate::mock::header::MockHeader;
+    use crate::mock::misbehaviour::Misbehaviour as MockMisbehaviour;
+    use crate::proofs::{ConsensusProof, Proofs};
     use crate::test_utils::get_dummy_account_id;
+    use crate::timestamp::Timestamp;
+    use crate::tx_msg::Msg;
     use crate::Height;
 
     #[test]
@@ -311,12 +430,22 @@ mod tests {
 
         let upgrade_client_height_second = Height::new(1, 1);
 
-        // We reuse this same context across all tests. Nothing in particular needs parametrizing.
-        let mut ctx = MockContext::default();
+        // We reuse this same context across all tests. The only parametrization is that the
+        // sender of the two token transfers is funded with the exact amount they need, and that
+        // the transfer module is bound to the port of the channel.
+        let transfer_coin = get_dummy_coin();
+        let mut ctx = MockContext::default()
+            .with_balance(
+                &default_signer,
+                Coin::new(transfer_coin.denom.clone(), 2 * transfer_coin.amount),
+            )
+            .with_module(ModuleId::new(MODULE_ID), PortId::default(), Ics20Module);
 
         let create_client_msg = MsgCreateAnyClient::new(
-            AnyClientState::from(MockClientState(MockHeader::new(start_client_height))),
-            AnyConsensusState::from(MockConsensusState(MockHeader::new(start_client_height))),
+            AnyClientState::from(MockClientState::new(MockHeader::new(start_client_height))),
+            AnyConsensusState::from(MockConsensusState::new(MockHeader::new(
+                start_client_height,
+            ))),
             default_signer.clone(),
         )
         .unwrap();
@@ -348,18 +477,23 @@ mod tests {
         //
         // Channel handshake messages.
         //
-        let msg_chan_init =
+        // The channel is bound to the transfer module, which only accepts the ICS20 version.
+        let mut msg_chan_init =
             MsgChannelOpenInit::try_from(get_dummy_raw_msg_chan_open_init()).unwrap();
+        msg_chan_init.channel.version = VERSION.to_string();
 
         // The handler will fail to process this b/c the associated connection does not exist
         let mut incorrect_msg_chan_init = msg_chan_init.clone();
         incorrect_msg_chan_init.channel.connection_hops = vec![ConnectionId::new(590)];
 
-        let msg_chan_try =
+        let mut msg_chan_try =
             MsgChannelOpenTry::try_from(get_dummy_raw_msg_chan_open_try(client_height)).unwrap();
+        msg_chan_try.channel.version = VERSION.to_string();
+        msg_chan_try.counterparty_version = VERSION.to_string();
 
-        let msg_chan_ack =
+        let mut msg_chan_ack =
             MsgChannelOpenAck::try_from(get_dummy_raw_msg_chan_open_ack(client_height)).unwrap();
+        msg_chan_ack.counterparty_version = VERSION.to_string();
 
         let msg_chan_close_init =
             MsgChannelCloseInit::try_from(get_dummy_raw_msg_chan_close_init()).unwrap();
@@ -370,6 +504,10 @@ mod tests {
 
         let msg_transfer = get_dummy_msg_transfer(35);
 
+        // No module is bound to the source port of this transfer.
+        let mut msg_transfer_unbound = get_dummy_msg_transfer(35);
+        msg_transfer_unbound.source_port = "unbound".parse().unwrap();
+
         let msg_transfer_two = get_dummy_msg_transfer(36);
 
         let mut msg_to_on_close =
@@ -377,6 +515,13 @@ mod tests {
         msg_to_on_close.packet.sequence = 2.into();
         msg_to_on_close.packet.timeout_height = msg_transfer_two.timeout_height;
         msg_to_on_close.packet.timeout_timestamp = msg_transfer_two.timeout_timestamp;
+        msg_to_on_close.packet.data = FungibleTokenPacketData {
+            amount: transfer_coin.amount,
+            denom: transfer_coin.denom.clone(),
+            receiver: msg_transfer_two.receiver.clone(),
+            sender: msg_transfer_two.sender.clone(),
+        }
+        .to_bytes();
 
         let msg_recv_packet = MsgRecvPacket::try_from(get_dummy_raw_msg_recv_packet(35)).unwrap();
 
@@ -408,6 +553,21 @@ mod tests {
             event => panic!("unexpected IBC event: {:?}", event),
         };
 
+        // Two distinct headers at the same height, evidence of misbehaviour of the client.
+        let misbehaviour_msg = MsgSubmitAnyMisbehaviour {
+            client_id: client_id.clone(),
+            misbehaviour: AnyMisbehaviour::Mock(MockMisbehaviour {
+                client_id: client_id.clone(),
+                header1: MockHeader {
+                    height: upgrade_client_height,
+                    timestamp: Timestamp::now(),
+                    root: None,
+                },
+                header2: MockHeader::new(upgrade_client_height),
+            }),
+            signer: default_signer.clone(),
+        };
+
         let tests: Vec<Test> = vec![
             // Test some ICS2 client functionality.
             Test {
@@ -481,6 +641,11 @@ mod tests {
                 want_pass: true,
             },
             //ICS20-04-packet
+            Test {
+                name: "Packet send from an unbound port".to_string(),
+                msg: Ics26Envelope::Ics20Msg(msg_transfer_unbound),
+                want_pass: false,
+            },
             Test {
                 name: "Packet send".to_string(),
                 msg: Ics26Envelope::Ics20Msg(msg_transfer),
@@ -523,18 +688,18 @@ mod tests {
             },
             //ICS04-close channel
             Test {
-                name: "Channel close init succeeds".to_string(),
+                name: "Channel close init fails because the transfer module vetoes it".to_string(),
                 msg: Ics26Envelope::Ics4ChannelMsg(ChannelMsg::ChannelCloseInit(
                     msg_chan_close_init,
                 )),
-                want_pass: true,
+                want_pass: false,
             },
             Test {
-                name: "Channel close confirm fails cause channel is already closed".to_string(),
+                name: "Channel close confirm succeeds".to_string(),
                 msg: Ics26Envelope::Ics4ChannelMsg(ChannelMsg::ChannelCloseConfirm(
                     msg_chan_close_confirm,
                 )),
-                want_pass: false,
+                want_pass: true,
             },
             //ICS04-to_on_close
             Test {
@@ -546,8 +711,10 @@ mod tests {
                 name: "Client upgrade successful".to_string(),
                 msg: Ics26Envelope::Ics2Msg(ClientMsg::UpgradeClient(MsgUpgradeAnyClient::new(
                     client_id.clone(),
-                    AnyClientState::Mock(MockClientState(MockHeader::new(upgrade_client_height))),
-                    AnyConsensusState::Mock(MockConsensusState(MockHeader::new(
+                    AnyClientState::Mock(MockClientState::new(MockHeader::new(
+                        upgrade_client_height,
+                    ))),
+                    AnyConsensusState::Mock(MockConsensusState::new(MockHeader::new(
                         upgrade_client_height,
                     ))),
                     get_dummy_merkle_proof(),
@@ -559,19 +726,38 @@ mod tests {
             Test {
                 name: "Client upgrade un-successful".to_string(),
                 msg: Ics26Envelope::Ics2Msg(ClientMsg::UpgradeClient(MsgUpgradeAnyClient::new(
-                    client_id,
-                    AnyClientState::Mock(MockClientState(MockHeader::new(
+                    client_id.clone(),
+                    AnyClientState::Mock(MockClientState::new(MockHeader::new(
                         upgrade_client_height_second,
                     ))),
-                    AnyConsensusState::Mock(MockConsensusState(MockHeader::new(
+                    AnyConsensusState::Mock(MockConsensusState::new(MockHeader::new(
                         upgrade_client_height_second,
                     ))),
                     get_dummy_merkle_proof(),
                     get_dummy_merkle_proof(),
-                    default_signer,
+                    default_signer.clone(),
                 ))),
                 want_pass: false,
             },
+            Test {
+                name: "Client misbehaviour successful".to_string(),
+                msg: Ics26Envelope::Ics2Msg(ClientMsg::Misbehaviour(misbehaviour_msg.clone())),
+                want_pass: true,
+            },
+            Test {
+                name: "Client update fails due to frozen client".to_string(),
+                msg: Ics26Envelope::Ics2Msg(ClientMsg::UpdateClient(MsgUpdateAnyClient {
+                    client_id,
+                    header: MockHeader::new(upgrade_client_height.increment()).into(),
+                    signer: default_signer.clone(),
+                })),
+                want_pass: false,
+            },
+            Test {
+                name: "Client misbehaviour fails due to frozen client".to_string(),
+                msg: Ics26Envelope::Ics2Msg(ClientMsg::Misbehaviour(misbehaviour_msg)),
+                want_pass: false,
+            },
         ]
         .into_iter()
         .collect();
@@ -588,5 +774,268 @@ mod tests {
                 res
             );
         }
+
+        // Both transfers escrowed the tokens of the sender, as these are native to the host chain.
+        // The tokens of the second transfer were refunded once its packet timed out.
+        let escrow_address = ctx.get_escrow_address(&PortId::default(), &ChannelId::default());
+        assert_eq!(
+            ctx.balance(&default_signer, &transfer_coin.denom),
+            transfer_coin.amount
+        );
+        assert_eq!(
+            ctx.balance(&escrow_address, &transfer_coin.denom),
+            transfer_coin.amount
+        );
+    }
+
+    #[test]
+    /// The messages delivered together are applied all-or-nothing.
+    fn deliver_is_atomic() {
+        let height = Height::new(0, 5);
+        let create_client_msg = MsgCreateAnyClient::new(
+            AnyClientState::from(MockClientState::new(MockHeader::new(height))),
+            AnyConsensusState::from(MockConsensusState::new(MockHeader::new(height))),
+            get_dummy_account_id(),
+        )
+        .unwrap();
+        let malformed_msg = Any {
+            type_url: create_client::TYPE_URL.to_string(),
+            value: vec![0xff],
+        };
+
+        let mut ctx = MockContext::default();
+
+        // The client created by the first message is discarded, as the second message fails.
+        let res = deliver(
+            &mut ctx,
+            vec![create_client_msg.clone().to_any(), malformed_msg],
+        );
+        assert!(res.is_err());
+        assert_eq!(ctx.client_counter(), 0);
+
+        let outputs = deliver(
+            &mut ctx,
+            vec![
+                create_client_msg.clone().to_any(),
+                create_client_msg.to_any(),
+            ],
+        )
+        .unwrap();
+        assert_eq!(ctx.client_counter(), 2);
+        assert_eq!(outputs.len(), 2);
+        for output in outputs {
+            assert!(matches!(
+                output.events.as_slice(),
+                [IbcEvent::CreateClient(_)]
+            ));
+        }
+    }
+
+    #[test]
+    /// A solo machine client verifies the proofs of a connection handshake, a channel handshake and
+    /// a packet, each signed at the next sequence of the machine, against the public key which the
+    /// client keeps in its state.
+    fn solomachine_connection_channel_and_packet() {
+        let client_id = ClientId::new(ClientType::SoloMachine, 0).unwrap();
+        let counterparty_prefix = CommitmentPrefix::from(b"ibc".to_vec());
+        let mut ctx = MockContext::default()
+            .with_client_parametrized(
+                &client_id,
+                Height::new(0, 1),
+                Some(ClientType::SoloMachine),
+                None,
+            )
+            .with_module(ModuleId::new(MODULE_ID), PortId::default(), Ics20Module);
+
+        // Connection handshake.
+        let msg_conn_init = MsgConnectionOpenInit::try_from(get_dummy_raw_msg_conn_open_init())
+            .unwrap()
+            .with_client_id(client_id.clone());
+        dispatch(
+            &mut ctx,
+            Ics26Envelope::Ics3Msg(ConnectionMsg::ConnectionOpenInit(msg_conn_init)),
+        )
+        .unwrap();
+
+        // The connection, client and consensus proofs are signed at sequences 1, 2 and 3.
+        let mut msg_conn_ack =
+            MsgConnectionOpenAck::try_from(get_dummy_raw_msg_conn_open_ack(1, 5)).unwrap();
+        let conn_end = ConnectionReader::connection_end(&ctx, &msg_conn_ack.connection_id).unwrap();
+        let expected_conn = ConnectionEnd::new(
+            ConnectionState::TryOpen,
+            conn_end.counterparty().client_id().clone(),
+            ConnectionCounterparty::new(
+                client_id.clone(),
+                Some(msg_conn_ack.connection_id.clone()),
+                ConnectionReader::commitment_prefix(&ctx),
+            ),
+            vec![msg_conn_ack.version.clone()],
+            conn_end.delay_period(),
+        );
+        let conn_proof = get_dummy_proof(
+            1,
+            DataType::ConnectionState,
+            encode(&ConnectionStateData {
+                path: signed_path(
+                    &counterparty_prefix,
+                    Path::Connections(msg_conn_ack.counterparty_connection_id.clone()),
+                )
+                .unwrap(),
+                connection: Some(expected_conn.into()),
+            }),
+        );
+
+        let self_client_state =
+            AnyClientState::from(MockClientState::new(MockHeader::new(Height::new(0, 4))));
+        let client_proof = get_dummy_proof(
+            2,
+            DataType::ClientState,
+            encode(&ClientStateData {
+                path: signed_path(
+                    &counterparty_prefix,
+                    Path::ClientState(conn_end.counterparty().client_id().clone()),
+                )
+                .unwrap(),
+                client_state: Some(self_client_state.clone().into()),
+            }),
+        );
+
+        let consensus_height = msg_conn_ack.consensus_height();
+        let self_consensus_state =
+            ConnectionReader::host_consensus_state(&ctx, consensus_height).unwrap();
+        let consensus_proof = get_dummy_proof(
+            3,
+            DataType::ConsensusState,
+            encode(&ConsensusStateData {
+                path: signed_path(
+                    &counterparty_prefix,
+                    Path::ClientConsensusState {
+                        client_id: conn_end.counterparty().client_id().clone(),
+                        epoch: consensus_height.revision_number,
+                        height: consensus_height.revision_height,
+                    },
+                )
+                .unwrap(),
+                consensus_state: Some(self_consensus_state.into()),
+            }),
+        );
+
+        msg_conn_ack.client_state = Some(self_client_state);
+        msg_conn_ack.proofs = Proofs::new(
+            conn_proof.into(),
+            Some(client_proof.into()),
+            Some(ConsensusProof::new(consensus_proof.into(), consensus_height).unwrap()),
+            None,
+            Height::new(0, 1),
+        )
+        .unwrap();
+        dispatch(
+            &mut ctx,
+            Ics26Envelope::Ics3Msg(ConnectionMsg::ConnectionOpenAck(Box::new(msg_conn_ack))),
+        )
+        .unwrap();
+
+        // Channel handshake. The channel proof is signed at sequence 4.
+        let mut msg_chan_init =
+            MsgChannelOpenInit::try_from(get_dummy_raw_msg_chan_open_init()).unwrap();
+        msg_chan_init.channel.version = VERSION.to_string();
+        dispatch(
+            &mut ctx,
+            Ics26Envelope::Ics4ChannelMsg(ChannelMsg::ChannelOpenInit(msg_chan_init)),
+        )
+        .unwrap();
+
+        let mut msg_chan_ack =
+            MsgChannelOpenAck::try_from(get_dummy_raw_msg_chan_open_ack(4)).unwrap();
+        msg_chan_ack.counterparty_version = VERSION.to_string();
+        let chan_end = ChannelReader::channel_end(
+            &ctx,
+            &(
+                msg_chan_ack.port_id.clone(),
+                msg_chan_ack.channel_id.clone(),
+            ),
+        )
+        .unwrap();
+        let conn_end =
+            ConnectionReader::connection_end(&ctx, &chan_end.connection_hops()[0]).unwrap();
+        let expected_chan = ChannelEnd::new(
+            ChannelState::TryOpen,
+            *chan_end.ordering(),
+            ChannelCounterparty::new(
+                msg_chan_ack.port_id.clone(),
+                Some(msg_chan_ack.channel_id.clone()),
+            ),
+            vec![conn_end.counterparty().connection_id().unwrap().clone()],
+            msg_chan_ack.counterparty_version.clone(),
+        );
+        let chan_proof = get_dummy_proof(
+            4,
+            DataType::ChannelState,
+            encode(&ChannelStateData {
+                path: signed_path(
+                    &counterparty_prefix,
+                    Path::ChannelEnds(
+                        chan_end.counterparty().port_id().clone(),
+                        msg_chan_ack.counterparty_channel_id.clone(),
+                    ),
+                )
+                .unwrap(),
+                channel: Some(expected_chan.into()),
+            }),
+        );
+        msg_chan_ack.proofs =
+            Proofs::new(chan_proof.into(), None, None, None, Height::new(0, 4)).unwrap();
+        dispatch(
+            &mut ctx,
+            Ics26Envelope::Ics4ChannelMsg(ChannelMsg::ChannelOpenAck(msg_chan_ack)),
+        )
+        .unwrap();
+
+        // Packet. The packet commitment is signed at sequence 5.
+        let mut msg_recv_packet =
+            MsgRecvPacket::try_from(get_dummy_raw_msg_recv_packet(100)).unwrap();
+        let packet = &msg_recv_packet.packet;
+        let commitment = PacketCommitment::compute(
+            &packet.data,
+            packet.timeout_height,
+            packet.timeout_timestamp,
+        );
+        let packet_proof = get_dummy_proof(
+            5,
+            DataType::PacketCommitment,
+            encode(&PacketCommitmentData {
+                path: signed_path(
+                    &counterparty_prefix,
+                    Path::Commitments {
+                        port_id: packet.source_port.clone(),
+                        channel_id: packet.source_channel.clone(),
+                        sequence: packet.sequence,
+                    },
+                )
+                .unwrap(),
+                commitment: commitment.into_vec(),
+            }),
+        );
+        msg_recv_packet.proofs =
+            Proofs::new(packet_proof.into(), None, None, None, Height::new(0, 5)).unwrap();
+        dispatch(
+            &mut ctx,
+            Ics26Envelope::Ics4PacketMsg(PacketMsg::RecvPacket(msg_recv_packet.clone())),
+        )
+        .unwrap();
+
+        // The signature of the packet cannot be replayed.
+        assert!(dispatch(
+            &mut ctx,
+            Ics26Envelope::Ics4PacketMsg(PacketMsg::RecvPacket(msg_recv_packet)),
+        )
+        .is_err());
+
+        match ClientReader::client_state(&ctx, &client_id) {
+            Some(AnyClientState::SoloMachine(client_state)) => {
+                assert_eq!(client_state.sequence, 6)
+            }
+            client_state => panic!("unexpected client state: {:?}", client_state),
+        }
     }
 }
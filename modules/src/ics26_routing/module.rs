@@ -0,0 +1,116 @@
+//! The interface between the IBC handlers and the applications built on top of them, i.e., the
+//! callbacks which ICS26 specifies for application modules.
+
+use std::fmt::Debug;
+
+use prost_types::Any;
+
+use crate::handler::HandlerOutput;
+use crate::ics04_channel::channel::{Counterparty, Order};
+use crate::ics04_channel::error::{Error, Kind};
+use crate::ics04_channel::packet::Packet;
+use crate::ics24_host::identifier::{ChannelId, ConnectionId, PortId};
+
+/// An IBC application, bound to one or more ports of the host chain through the `Router`.
+///
+/// The channel handshake callbacks are invoked once the corresponding ICS4 handler has processed
+/// the message, but before its result is stored, so any error they return aborts the handshake.
+/// By default, all handshakes are accepted and the proposed version is kept.
+///
+/// The packet callbacks are invoked once the ICS4 handler has processed and stored the packet.
+///
+/// Messages of the application itself, such as the ICS20 `MsgTransfer`, are handed over to the
+/// module bound to the port they send from, through `deliver`.
+pub trait Module<Ctx: ?Sized>: Debug + Send + Sync {
+    /// Processes a message of the application, and stores its results, such as the packets the
+    /// module sends. By default, the module accepts no messages.
+    fn deliver(&self, _ctx: &mut Ctx, msg: Any) -> Result<HandlerOutput<()>, Error> {
+        Err(Kind::UnsupportedAppMessage(msg.type_url).into())
+    }
+
+    /// Returns the version of the new channel, which is the proposed `version` unless the module
+    /// negotiates another one.
+    #[allow(clippy::too_many_arguments)]
+    fn on_chan_open_init(
+        &self,
+        _ctx: &mut Ctx,
+        _order: Order,
+        _connection_hops: &[ConnectionId],
+        _port_id: &PortId,
+        _channel_id: &ChannelId,
+        _counterparty: &Counterparty,
+        version: &str,
+    ) -> Result<String, Error> {
+        Ok(version.to_string())
+    }
+
+    /// Returns the version of the new channel, as negotiated from the `version` proposed by the
+    /// relayer and the `counterparty_version` of the channel end on the counterparty chain.
+    #[allow(clippy::too_many_arguments)]
+    fn on_chan_open_try(
+        &self,
+        _ctx: &mut Ctx,
+        _order: Order,
+        _connection_hops: &[ConnectionId],
+        _port_id: &PortId,
+        _channel_id: &ChannelId,
+        _counterparty: &Counterparty,
+        version: &str,
+        _counterparty_version: &str,
+    ) -> Result<String, Error> {
+        Ok(version.to_string())
+    }
+
+    fn on_chan_open_ack(
+        &self,
+        _ctx: &mut Ctx,
+        _port_id: &PortId,
+        _channel_id: &ChannelId,
+        _counterparty_version: &str,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn on_chan_open_confirm(
+        &self,
+        _ctx: &mut Ctx,
+        _port_id: &PortId,
+        _channel_id: &ChannelId,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn on_chan_close_init(
+        &self,
+        _ctx: &mut Ctx,
+        _port_id: &PortId,
+        _channel_id: &ChannelId,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn on_chan_close_confirm(
+        &self,
+        _ctx: &mut Ctx,
+        _port_id: &PortId,
+        _channel_id: &ChannelId,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Processes a received packet. The result holds the acknowledgement, which is written right
+    /// away; `None` means that the module will write the acknowledgement asynchronously.
+    fn on_recv_packet(&self, ctx: &mut Ctx, packet: &Packet) -> HandlerOutput<Option<Vec<u8>>>;
+
+    fn on_acknowledgement_packet(
+        &self,
+        ctx: &mut Ctx,
+        packet: &Packet,
+        acknowledgement: &[u8],
+    ) -> Result<HandlerOutput<()>, Error>;
+
+    /// Invoked when a packet timed out, either because of its timeout height or timestamp, or
+    /// because its channel was closed.
+    fn on_timeout_packet(&self, ctx: &mut Ctx, packet: &Packet)
+        -> Result<HandlerOutput<()>, Error>;
+}
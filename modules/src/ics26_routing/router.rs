@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::ics24_host::identifier::PortId;
+use crate::ics26_routing::error::{Error, Kind};
+use crate::ics26_routing::module::Module;
+
+/// Maps each port of the host chain to the application module bound to it.
+pub struct Router<Ctx: ?Sized> {
+    routes: HashMap<PortId, Arc<dyn Module<Ctx>>>,
+}
+
+impl<Ctx: ?Sized> Router<Ctx> {
+    pub fn new() -> Self {
+        Self {
+            routes: HashMap::new(),
+        }
+    }
+
+    /// Binds `module` to `port_id`. Fails if another module is already bound to that port.
+    pub fn add_route(
+        &mut self,
+        port_id: PortId,
+        module: impl Module<Ctx> + 'static,
+    ) -> Result<(), Error> {
+        if self.routes.contains_key(&port_id) {
+            return Err(Kind::DuplicateRoute(port_id).into());
+        }
+        self.routes.insert(port_id, Arc::new(module));
+        Ok(())
+    }
+
+    /// Returns the module bound to `port_id`, if any. The module is handed out as a shared
+    /// pointer, so that the caller can then borrow the context mutably to invoke its callbacks.
+    pub fn route(&self, port_id: &PortId) -> Option<Arc<dyn Module<Ctx>>> {
+        self.routes.get(port_id).cloned()
+    }
+
+    pub fn has_route(&self, port_id: &PortId) -> bool {
+        self.routes.contains_key(port_id)
+    }
+}
+
+impl<Ctx: ?Sized> Default for Router<Ctx> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Ctx: ?Sized> Clone for Router<Ctx> {
+    fn clone(&self) -> Self {
+        Self {
+            routes: self.routes.clone(),
+        }
+    }
+}
+
+impl<Ctx: ?Sized> fmt::Debug for Router<Ctx> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.routes.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_env_log::test;
+
+    use crate::application::ics20_fungible_token_transfer::module::Ics20Module;
+    use crate::ics24_host::identifier::PortId;
+    use crate::ics26_routing::error::Kind;
+    use crate::mock::context::MockContext;
+
+    use super::Router;
+
+    #[test]
+    fn router_binds_one_module_per_port() {
+        let port_id = PortId::default();
+        let mut router = Router::<MockContext>::new();
+        assert!(router.route(&port_id).is_none());
+
+        router.add_route(port_id.clone(), Ics20Module).unwrap();
+        assert!(router.has_route(&port_id));
+        assert!(router.route(&port_id).is_some());
+
+        let res = router.add_route(port_id.clone(), Ics20Module);
+        assert_eq!(res.unwrap_err().kind(), &Kind::DuplicateRoute(port_id));
+    }
+}
@@ -1,17 +1,27 @@
-use ibc_proto::ibc::core::commitment::v1::MerkleProof;
+use std::convert::TryFrom;
+
+use ibc_proto::ibc::core::commitment::v1::MerkleProof as RawMerkleProof;
+use tendermint_proto::Protobuf;
 
 use crate::ics02_client::client_consensus::AnyConsensusState;
 use crate::ics02_client::client_def::ClientDef;
 use crate::ics02_client::client_state::AnyClientState;
+use crate::ics02_client::context::ClientReader;
+use crate::ics02_client::misbehaviour::Misbehaviour as _;
 use crate::ics03_connection::connection::ConnectionEnd;
+use crate::ics03_connection::context::ConnectionReader;
 use crate::ics04_channel::channel::ChannelEnd;
+use crate::ics04_channel::commitment::{AcknowledgementCommitment, PacketCommitment};
+use crate::ics04_channel::context::ChannelReader;
 use crate::ics04_channel::packet::Sequence;
 use crate::ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes, CommitmentRoot};
-use crate::ics23_commitment::merkle::apply_prefix;
+use crate::ics23_commitment::merkle::{apply_prefix, MerkleProof};
 use crate::ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId};
 use crate::ics24_host::Path;
 use crate::mock::client_state::{MockClientState, MockConsensusState};
 use crate::mock::header::MockHeader;
+use crate::mock::misbehaviour::Misbehaviour;
+use crate::mock::store::mock_proof_specs;
 use crate::Height;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -21,9 +31,12 @@ impl ClientDef for MockClient {
     type Header = MockHeader;
     type ClientState = MockClientState;
     type ConsensusState = MockConsensusState;
+    type Misbehaviour = Misbehaviour;
 
     fn check_header_and_update_state(
         &self,
+        _ctx: &dyn ClientReader,
+        _client_id: ClientId,
         client_state: Self::ClientState,
         header: Self::Header,
     ) -> Result<(Self::ClientState, Self::ConsensusState), Box<dyn std::error::Error>> {
@@ -32,130 +45,269 @@ impl ClientDef for MockClient {
                 "received header height is lower than (or equal to) client latest height".into(),
             );
         }
-        Ok((MockClientState(header), MockConsensusState(header)))
+        Ok((
+            MockClientState {
+                header,
+                ..client_state
+            },
+            MockConsensusState::new(header),
+        ))
+    }
+
+    fn check_misbehaviour_and_update_state(
+        &self,
+        _ctx: &dyn ClientReader,
+        _client_id: ClientId,
+        client_state: Self::ClientState,
+        misbehaviour: Self::Misbehaviour,
+    ) -> Result<Self::ClientState, Box<dyn std::error::Error>> {
+        if misbehaviour.header1 == misbehaviour.header2 {
+            return Err("misbehaviour headers are identical".into());
+        }
+        Ok(client_state.with_frozen_height(misbehaviour.height()))
     }
 
     fn verify_client_consensus_state(
         &self,
-        _client_state: &Self::ClientState,
-        height: Height,
+        _ctx: &dyn ConnectionReader,
+        client_state: &Self::ClientState,
+        _height: Height,
         prefix: &CommitmentPrefix,
-        _proof: &CommitmentProofBytes,
+        proof: &CommitmentProofBytes,
+        root: &CommitmentRoot,
         client_id: &ClientId,
-        _consensus_height: Height,
-        _expected_consensus_state: &AnyConsensusState,
+        consensus_height: Height,
+        expected_consensus_state: &AnyConsensusState,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let client_prefixed_path = Path::ClientConsensusState {
+        let path = Path::ClientConsensusState {
             client_id: client_id.clone(),
-            epoch: height.revision_number,
-            height: height.revision_height,
-        }
-        .to_string();
-
-        let _path = apply_prefix(prefix, vec![client_prefixed_path])?;
-
-        // TODO - add ctx to all client verification functions
-        // let cs = ctx.fetch_self_consensus_state(height);
-        // TODO - implement this
-        // proof.verify_membership(cs.root(), path, expected_consensus_state)
-
-        Ok(())
+            epoch: consensus_height.revision_number,
+            height: consensus_height.revision_height,
+        };
+        let value = expected_consensus_state.encode_vec()?;
+        verify_membership(client_state, prefix, proof, root, path, value)
     }
 
     fn verify_connection_state(
         &self,
-        _client_state: &Self::ClientState,
+        _ctx: &dyn ConnectionReader,
+        client_state: &Self::ClientState,
         _height: Height,
-        _prefix: &CommitmentPrefix,
-        _proof: &CommitmentProofBytes,
-        _connection_id: Option<&ConnectionId>,
-        _expected_connection_end: &ConnectionEnd,
+        prefix: &CommitmentPrefix,
+        proof: &CommitmentProofBytes,
+        root: &CommitmentRoot,
+        connection_id: Option<&ConnectionId>,
+        expected_connection_end: &ConnectionEnd,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        Ok(())
+        let connection_id = connection_id.ok_or("missing counterparty connection identifier")?;
+        let path = Path::Connections(connection_id.clone());
+        let value = expected_connection_end.encode_vec()?;
+        verify_membership(client_state, prefix, proof, root, path, value)
     }
 
     fn verify_channel_state(
         &self,
-        _client_state: &Self::ClientState,
+        _ctx: &dyn ChannelReader,
+        client_state: &Self::ClientState,
         _height: Height,
-        _prefix: &CommitmentPrefix,
-        _proof: &CommitmentProofBytes,
-        _port_id: &PortId,
-        _channel_id: &ChannelId,
-        _expected_channel_end: &ChannelEnd,
+        prefix: &CommitmentPrefix,
+        proof: &CommitmentProofBytes,
+        root: &CommitmentRoot,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        expected_channel_end: &ChannelEnd,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        Ok(())
+        let path = Path::ChannelEnds(port_id.clone(), channel_id.clone());
+        let value = expected_channel_end.encode_vec()?;
+        verify_membership(client_state, prefix, proof, root, path, value)
     }
 
     fn verify_client_full_state(
         &self,
-        _client_state: &Self::ClientState,
+        _ctx: &dyn ConnectionReader,
+        client_state: &Self::ClientState,
         _height: Height,
-        _root: &CommitmentRoot,
-        _prefix: &CommitmentPrefix,
-        _client_id: &ClientId,
-        _proof: &CommitmentProofBytes,
-        _expected_client_state: &AnyClientState,
+        root: &CommitmentRoot,
+        prefix: &CommitmentPrefix,
+        client_id: &ClientId,
+        proof: &CommitmentProofBytes,
+        expected_client_state: &AnyClientState,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        Ok(())
+        let path = Path::ClientState(client_id.clone());
+        let value = expected_client_state.encode_vec()?;
+        verify_membership(client_state, prefix, proof, root, path, value)
     }
 
     fn verify_packet_data(
         &self,
-        _client_state: &Self::ClientState,
+        _ctx: &dyn ChannelReader,
+        client_state: &Self::ClientState,
         _height: Height,
-        _proof: &CommitmentProofBytes,
-        _port_id: &PortId,
-        _channel_id: &ChannelId,
-        _seq: &Sequence,
-        _data: String,
+        prefix: &CommitmentPrefix,
+        proof: &CommitmentProofBytes,
+        root: &CommitmentRoot,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        seq: &Sequence,
+        commitment: PacketCommitment,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        Ok(())
+        let path = Path::Commitments {
+            port_id: port_id.clone(),
+            channel_id: channel_id.clone(),
+            sequence: *seq,
+        };
+        verify_membership(
+            client_state,
+            prefix,
+            proof,
+            root,
+            path,
+            commitment.into_vec(),
+        )
     }
 
     fn verify_packet_acknowledgement(
         &self,
-        _client_state: &Self::ClientState,
+        _ctx: &dyn ChannelReader,
+        client_state: &Self::ClientState,
         _height: Height,
-        _proof: &CommitmentProofBytes,
-        _port_id: &PortId,
-        _channel_id: &ChannelId,
-        _seq: &Sequence,
-        _data: Vec<u8>,
+        prefix: &CommitmentPrefix,
+        proof: &CommitmentProofBytes,
+        root: &CommitmentRoot,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        seq: &Sequence,
+        ack: Vec<u8>,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        Ok(())
+        let path = Path::Acks {
+            port_id: port_id.clone(),
+            channel_id: channel_id.clone(),
+            sequence: *seq,
+        };
+        verify_membership(
+            client_state,
+            prefix,
+            proof,
+            root,
+            path,
+            AcknowledgementCommitment::compute(&ack).into_vec(),
+        )
     }
 
     fn verify_next_sequence_recv(
         &self,
-        _client_state: &Self::ClientState,
+        _ctx: &dyn ChannelReader,
+        client_state: &Self::ClientState,
         _height: Height,
-        _proof: &CommitmentProofBytes,
-        _port_id: &PortId,
-        _channel_id: &ChannelId,
-        _seq: &Sequence,
+        prefix: &CommitmentPrefix,
+        proof: &CommitmentProofBytes,
+        root: &CommitmentRoot,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        seq: &Sequence,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        Ok(())
+        let path = Path::SeqRecvs(port_id.clone(), channel_id.clone());
+        let seq_bytes = u64::from(*seq).to_be_bytes().to_vec();
+        verify_membership(client_state, prefix, proof, root, path, seq_bytes)
     }
 
     fn verify_packet_receipt_absence(
         &self,
-        _client_state: &Self::ClientState,
+        _ctx: &dyn ChannelReader,
+        client_state: &Self::ClientState,
         _height: Height,
-        _proof: &CommitmentProofBytes,
-        _port_id: &PortId,
-        _channel_id: &ChannelId,
-        _seq: &Sequence,
+        prefix: &CommitmentPrefix,
+        proof: &CommitmentProofBytes,
+        root: &CommitmentRoot,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        seq: &Sequence,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        Ok(())
+        let path = Path::Receipts {
+            port_id: port_id.clone(),
+            channel_id: channel_id.clone(),
+            sequence: *seq,
+        };
+        verify_non_membership(client_state, prefix, proof, root, path)
     }
+
     fn verify_upgrade_and_update_state(
         &self,
-        client_state: &Self::ClientState,
-        consensus_state: &Self::ConsensusState,
-        _proof_upgrade_client: MerkleProof,
-        _proof_upgrade_consensus_state: MerkleProof,
+        _ctx: &dyn ClientReader,
+        _client_id: &ClientId,
+        _client_state: &Self::ClientState,
+        upgraded_client_state: &Self::ClientState,
+        upgraded_consensus_state: &Self::ConsensusState,
+        _proof_upgrade_client: RawMerkleProof,
+        _proof_upgrade_consensus_state: RawMerkleProof,
     ) -> Result<(Self::ClientState, Self::ConsensusState), Box<dyn std::error::Error>> {
-        Ok((*client_state, *consensus_state))
+        Ok((*upgraded_client_state, upgraded_consensus_state.clone()))
+    }
+
+    fn check_substitute_and_update_state(
+        &self,
+        _ctx: &dyn ClientReader,
+        _subject_client_id: &ClientId,
+        subject_client_state: &Self::ClientState,
+        substitute_client_state: &Self::ClientState,
+    ) -> Result<Self::ClientState, Box<dyn std::error::Error>> {
+        // Mock clients do not track the identifier of their chain, so the substitute must at least
+        // track the same revision, and a chain which keeps a provable store iff the one of the
+        // subject does.
+        if substitute_client_state.latest_height().revision_number
+            != subject_client_state.latest_height().revision_number
+        {
+            return Err("substitute client tracks another revision than the subject client".into());
+        }
+        if substitute_client_state.verifies_proofs() != subject_client_state.verifies_proofs() {
+            return Err(
+                "substitute client tracks another kind of chain than the subject client".into(),
+            );
+        }
+
+        // Like Tendermint clients, the recovered client takes the trusting period of the substitute.
+        Ok(MockClientState {
+            frozen_height: None,
+            ..*substitute_client_state
+        })
+    }
+}
+
+/// Verifies that `value` is stored at `path` (under `prefix`) in the provable store of the chain
+/// tracked by the client, if the chain keeps one. Otherwise, the proof is not checked.
+fn verify_membership(
+    client_state: &MockClientState,
+    prefix: &CommitmentPrefix,
+    proof: &CommitmentProofBytes,
+    root: &CommitmentRoot,
+    path: Path,
+    value: Vec<u8>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if !client_state.verifies_proofs() {
+        return Ok(());
     }
+
+    let merkle_path = apply_prefix(prefix, vec![path.to_string()])?;
+    let merkle_proof = MerkleProof::try_from(proof.clone())?;
+    merkle_proof.verify_membership(&mock_proof_specs(), root, merkle_path, value, 0)?;
+    Ok(())
+}
+
+/// Verifies that nothing is stored at `path` (under `prefix`) in the provable store of the chain
+/// tracked by the client, if the chain keeps one. Otherwise, the proof is not checked.
+fn verify_non_membership(
+    client_state: &MockClientState,
+    prefix: &CommitmentPrefix,
+    proof: &CommitmentProofBytes,
+    root: &CommitmentRoot,
+    path: Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if !client_state.verifies_proofs() {
+        return Ok(());
+    }
+
+    let merkle_path = apply_prefix(prefix, vec![path.to_string()])?;
+    let merkle_proof = MerkleProof::try_from(proof.clone())?;
+    merkle_proof.verify_non_membership(&mock_proof_specs(), root, merkle_path)?;
+    Ok(())
 }
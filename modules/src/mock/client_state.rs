@@ -9,7 +9,7 @@ use ibc_proto::ibc::mock::ClientState as RawMockClientState;
 use ibc_proto::ibc::mock::ConsensusState as RawMockConsensusState;
 
 use crate::ics02_client::client_consensus::{AnyConsensusState, ConsensusState};
-use crate::ics02_client::client_state::{AnyClientState, ClientState};
+use crate::ics02_client::client_state::{AnyClientState, ClientState, Status};
 use crate::ics02_client::client_type::ClientType;
 use crate::ics02_client::error::Error;
 use crate::ics02_client::error::Kind as ClientKind;
@@ -31,26 +31,74 @@ pub struct MockClientRecord {
 
     /// Mapping of heights to consensus states for this client.
     pub consensus_states: HashMap<Height, AnyConsensusState>,
+
+    /// Mapping of heights to the times at which the consensus states were stored on the host.
+    pub processed_times: HashMap<Height, Timestamp>,
+
+    /// Mapping of heights to the host heights at which the consensus states were stored.
+    pub processed_heights: HashMap<Height, Height>,
 }
 
 /// A mock of a client state. For an example of a real structure that this mocks, you can see
 /// `ClientState` of ics07_tendermint/client_state.rs.
-// TODO: `MockClientState` should evolve, at the very least needs a `is_frozen` boolean field.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
-pub struct MockClientState(pub MockHeader);
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct MockClientState {
+    pub header: MockHeader,
+
+    /// The height at which the client was frozen due to misbehaviour, if any. The raw mock
+    /// client state has no such field, so this is not preserved across encoding.
+    pub frozen_height: Option<Height>,
+
+    /// The period for which the latest consensus state of the client is trusted, if the client
+    /// can expire at all. Like the frozen height, this is not preserved across encoding.
+    pub trusting_period: Option<Duration>,
+}
 
 impl Protobuf<RawMockClientState> for MockClientState {}
 
 impl MockClientState {
+    pub fn new(header: MockHeader) -> Self {
+        Self {
+            header,
+            frozen_height: None,
+            trusting_period: None,
+        }
+    }
+
     pub fn latest_height(&self) -> Height {
-        (self.0).height
+        self.header.height
+    }
+
+    /// Returns true if the client tracks a chain which keeps a provable store, in which case the
+    /// client verifies the proofs of the chain state. Otherwise, any proof is accepted.
+    pub fn verifies_proofs(&self) -> bool {
+        self.header.root.is_some()
+    }
+
+    /// Returns a copy of this client state, frozen at height `frozen_height`.
+    pub fn with_frozen_height(self, frozen_height: Height) -> Self {
+        Self {
+            frozen_height: Some(frozen_height),
+            ..self
+        }
+    }
+
+    /// Returns a copy of this client state, which expires once its latest consensus state is
+    /// older than `trusting_period`.
+    pub fn with_trusting_period(self, trusting_period: Duration) -> Self {
+        Self {
+            trusting_period: Some(trusting_period),
+            ..self
+        }
     }
 
     pub fn refresh_time(&self) -> Option<Duration> {
-        None
+        self.trusting_period.map(|period| 2 * period / 3)
     }
-    pub fn expired(&self, _elapsed: Duration) -> bool {
-        false
+    pub fn expired(&self, elapsed: Duration) -> bool {
+        self.trusting_period
+            .map_or(false, |trusting_period| elapsed > trusting_period)
     }
 }
 
@@ -64,24 +112,26 @@ impl TryFrom<RawMockClientState> for MockClientState {
     type Error = Error;
 
     fn try_from(raw: RawMockClientState) -> Result<Self, Self::Error> {
-        Ok(MockClientState(raw.header.unwrap().try_into()?))
+        let raw_header = raw
+            .header
+            .ok_or_else(|| ClientKind::InvalidRawClientState.context("missing header"))?;
+
+        Ok(MockClientState::new(raw_header.try_into()?))
     }
 }
 
 impl From<MockClientState> for RawMockClientState {
     fn from(value: MockClientState) -> Self {
         RawMockClientState {
-            header: Some(ibc_proto::ibc::mock::Header {
-                height: Some(value.0.height().into()),
-                timestamp: (value.0).timestamp.as_nanoseconds(),
-            }),
+            header: Some(value.header.into()),
         }
     }
 }
 
 impl ClientState for MockClientState {
+    /// Mock clients do not track the identifier of their chain.
     fn chain_id(&self) -> ChainId {
-        todo!()
+        ChainId::default()
     }
 
     fn client_type(&self) -> ClientType {
@@ -89,12 +139,29 @@ impl ClientState for MockClientState {
     }
 
     fn latest_height(&self) -> Height {
-        self.0.height()
+        self.header.height()
     }
 
     fn is_frozen(&self) -> bool {
-        // TODO
-        false
+        self.frozen_height.is_some()
+    }
+
+    fn status(
+        &self,
+        host_timestamp: Timestamp,
+        latest_consensus_state: Option<&AnyConsensusState>,
+    ) -> Status {
+        if self.is_frozen() {
+            return Status::Frozen;
+        }
+
+        // The expiry is inconclusive if either timestamp is not set.
+        match latest_consensus_state
+            .and_then(|consensus_state| host_timestamp.duration_since(&consensus_state.timestamp()))
+        {
+            Some(elapsed) if self.expired(elapsed) => Status::Expired,
+            _ => Status::Active,
+        }
     }
 
     fn wrap_any(self) -> AnyClientState {
@@ -104,16 +171,35 @@ impl ClientState for MockClientState {
 
 impl From<MockConsensusState> for MockClientState {
     fn from(cs: MockConsensusState) -> Self {
-        Self(cs.0)
+        Self::new(cs.header)
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
-pub struct MockConsensusState(pub MockHeader);
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct MockConsensusState {
+    pub header: MockHeader,
+    pub root: CommitmentRoot,
+}
 
 impl MockConsensusState {
+    pub fn new(header: MockHeader) -> Self {
+        let root = match header.root {
+            Some(root) => CommitmentRoot::from_bytes(&root),
+            None => CommitmentRoot::from(vec![0]),
+        };
+
+        MockConsensusState { header, root }
+    }
+
     pub fn timestamp(&self) -> Timestamp {
-        (self.0).timestamp
+        self.header.timestamp
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for MockConsensusState {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self::new(u.arbitrary()?))
     }
 }
 
@@ -127,17 +213,14 @@ impl TryFrom<RawMockConsensusState> for MockConsensusState {
             .header
             .ok_or_else(|| ClientKind::InvalidRawConsensusState.context("missing header"))?;
 
-        Ok(Self(MockHeader::try_from(raw_header)?))
+        Ok(Self::new(MockHeader::try_from(raw_header)?))
     }
 }
 
 impl From<MockConsensusState> for RawMockConsensusState {
     fn from(value: MockConsensusState) -> Self {
         RawMockConsensusState {
-            header: Some(ibc_proto::ibc::mock::Header {
-                height: Some(value.0.height().into()),
-                timestamp: (value.0).timestamp.as_nanoseconds(),
-            }),
+            header: Some(value.header.into()),
         }
     }
 }
@@ -154,11 +237,11 @@ impl ConsensusState for MockConsensusState {
     }
 
     fn root(&self) -> &CommitmentRoot {
-        todo!()
+        &self.root
     }
 
     fn validate_basic(&self) -> Result<(), Box<dyn std::error::Error>> {
-        todo!()
+        Ok(())
     }
 
     fn wrap_any(self) -> AnyConsensusState {
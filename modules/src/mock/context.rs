@@ -3,43 +3,81 @@
 use std::cmp::min;
 use std::collections::HashMap;
 use std::error::Error;
+use std::sync::Arc;
+use std::time::Duration;
 
 use prost_types::Any;
-use sha2::Digest;
-
-use crate::application::ics20_fungible_token_transfer::context::Ics20Context;
+use tendermint_proto::Protobuf;
+
+use crate::application::ics20_fungible_token_transfer::coin::Coin;
+use crate::application::ics20_fungible_token_transfer::context::{
+    BankKeeper, Ics20Context, Ics20Keeper, Ics20Reader,
+};
+use crate::application::ics20_fungible_token_transfer::denom::DenomTrace;
+use crate::application::ics20_fungible_token_transfer::error::{
+    Error as Ics20Error, Kind as Ics20Kind,
+};
+use crate::application::ics20_fungible_token_transfer::msgs::transfer;
+use crate::application::ics27_interchain_accounts::context::{
+    Ics27ControllerContext, Ics27HostContext, InterchainAccountKeeper, InterchainAccountReader,
+    MessageExecutor,
+};
+use crate::application::ics27_interchain_accounts::error::{
+    Error as Ics27Error, Kind as Ics27Kind,
+};
 use crate::events::IbcEvent;
 use crate::ics02_client::client_consensus::{AnyConsensusState, AnyConsensusStateWithHeight};
-use crate::ics02_client::client_state::AnyClientState;
+use crate::ics02_client::client_state::{AnyClientState, ClientState};
 use crate::ics02_client::client_type::ClientType;
 use crate::ics02_client::context::{ClientKeeper, ClientReader};
-use crate::ics02_client::error::Error as Ics02Error;
+use crate::ics02_client::error::{Error as Ics02Error, Kind as Ics02Kind};
 use crate::ics02_client::header::AnyHeader;
 use crate::ics03_connection::connection::ConnectionEnd;
 use crate::ics03_connection::context::{ConnectionKeeper, ConnectionReader};
-use crate::ics03_connection::error::Error as Ics3Error;
+use crate::ics03_connection::error::{Error as Ics3Error, Kind as Ics3Kind};
 use crate::ics04_channel::channel::ChannelEnd;
+use crate::ics04_channel::commitment::{AcknowledgementCommitment, PacketCommitment};
 use crate::ics04_channel::context::{ChannelKeeper, ChannelReader};
 use crate::ics04_channel::error::{Error as Ics4Error, Kind as Ics4Kind};
 use crate::ics04_channel::packet::{Receipt, Sequence};
-use crate::ics05_port::capabilities::Capability;
-use crate::ics05_port::context::PortReader;
+use crate::ics05_port::capabilities::{Capability, CapabilityName, CapabilityStore, ModuleId};
+use crate::ics05_port::context::{CapabilityKeeper, CapabilityReader, PortKeeper, PortReader};
+use crate::ics05_port::error::Error as Ics05Error;
+use crate::ics06_solomachine::test_util::get_dummy_solomachine_client_state;
 use crate::ics07_tendermint::client_state::test_util::get_dummy_tendermint_client_state;
+use crate::ics09_localhost::client_state::ClientState as LocalhostClientState;
+use crate::ics09_localhost::consensus_state::ConsensusState as LocalhostConsensusState;
 use crate::ics18_relayer::context::Ics18Context;
 use crate::ics18_relayer::error::{Error as Ics18Error, Kind as Ics18ErrorKind};
-use crate::ics23_commitment::commitment::CommitmentPrefix;
+use crate::ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes};
+use crate::ics23_commitment::specs::ProofSpecs;
 use crate::ics24_host::identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId};
+use crate::ics24_host::Path;
 use crate::ics26_routing::context::Ics26Context;
 use crate::ics26_routing::handler::{deliver, dispatch};
+use crate::ics26_routing::module::Module;
 use crate::ics26_routing::msgs::Ics26Envelope;
+use crate::ics26_routing::router::Router;
 use crate::mock::client_state::{MockClientRecord, MockClientState, MockConsensusState};
 use crate::mock::header::MockHeader;
-use crate::mock::host::{HostBlock, HostType};
+use crate::mock::host::{
+    HostBlock, HostType, TENDERMINT_HOST_UNBONDING_PERIOD, TENDERMINT_HOST_UPGRADE_PATH,
+};
+use crate::mock::store::ProvableStore;
 use crate::signer::Signer;
 use crate::timestamp::Timestamp;
 use crate::Height;
 
+/// The commitment prefix of the chains which keep a provable store.
+pub const PROVABLE_STORE_PREFIX: &[u8] = b"ibc";
+
+/// The maximum expected time per block of the mock chains, unless set otherwise.
+pub const DEFAULT_MAX_EXPECTED_TIME_PER_BLOCK: Duration = Duration::from_secs(30);
+
 /// A context implementing the dependencies necessary for testing any IBC module.
+///
+/// The stores are shared copy-on-write between the clones of a context, so that a branch of the
+/// context (see `Ics26Context::branch`) only copies the stores written to in the branch.
 #[derive(Clone, Debug)]
 pub struct MockContext {
     /// The type of host chain underlying this mock context.
@@ -57,54 +95,78 @@ pub struct MockContext {
     /// Highest timestamp, i.e., of the most recent block in the history.
     timestamp: Timestamp,
 
+    /// Maximum expected time between two blocks, from which the number of blocks of a connection
+    /// delay period is derived.
+    max_expected_time_per_block: Duration,
+
     /// The chain of blocks underlying this context. A vector of size up to `max_history_size`
     /// blocks, ascending order by their height (latest block is on the last position).
     history: Vec<HostBlock>,
 
     /// The set of all clients, indexed by their id.
-    clients: HashMap<ClientId, MockClientRecord>,
+    clients: Arc<HashMap<ClientId, MockClientRecord>>,
 
     /// Counter for the client identifiers, necessary for `increase_client_counter` and the
     /// `client_counter` methods.
     client_ids_counter: u64,
 
     /// Association between client ids and connection ids.
-    client_connections: HashMap<ClientId, ConnectionId>,
+    client_connections: Arc<HashMap<ClientId, ConnectionId>>,
 
     /// All the connections in the store.
-    connections: HashMap<ConnectionId, ConnectionEnd>,
+    connections: Arc<HashMap<ConnectionId, ConnectionEnd>>,
 
     /// Counter for connection identifiers (see `increase_connection_counter`).
     connection_ids_counter: u64,
 
     /// Association between connection ids and channel ids.
-    connection_channels: HashMap<ConnectionId, Vec<(PortId, ChannelId)>>,
+    connection_channels: Arc<HashMap<ConnectionId, Vec<(PortId, ChannelId)>>>,
 
     /// Counter for channel identifiers (see `increase_channel_counter`).
     channel_ids_counter: u64,
 
     /// All the channels in the store. TODO Make new key PortId X ChanneId
-    channels: HashMap<(PortId, ChannelId), ChannelEnd>,
+    channels: Arc<HashMap<(PortId, ChannelId), ChannelEnd>>,
 
     /// Tracks the sequence number for the next packet to be sent.
-    next_sequence_send: HashMap<(PortId, ChannelId), Sequence>,
+    next_sequence_send: Arc<HashMap<(PortId, ChannelId), Sequence>>,
 
     /// Tracks the sequence number for the next packet to be received.
-    next_sequence_recv: HashMap<(PortId, ChannelId), Sequence>,
+    next_sequence_recv: Arc<HashMap<(PortId, ChannelId), Sequence>>,
 
     /// Tracks the sequence number for the next packet to be acknowledged.
-    next_sequence_ack: HashMap<(PortId, ChannelId), Sequence>,
+    next_sequence_ack: Arc<HashMap<(PortId, ChannelId), Sequence>>,
 
-    packet_acknowledgement: HashMap<(PortId, ChannelId, Sequence), String>,
+    packet_acknowledgement: Arc<HashMap<(PortId, ChannelId, Sequence), AcknowledgementCommitment>>,
 
     /// Maps ports to their capabilities
-    port_capabilities: HashMap<PortId, Capability>,
+    capabilities: Arc<CapabilityStore>,
 
     /// Constant-size commitments to packets data fields
-    packet_commitment: HashMap<(PortId, ChannelId, Sequence), String>,
+    packet_commitment: Arc<HashMap<(PortId, ChannelId, Sequence), PacketCommitment>>,
 
     // Used by unordered channel
-    packet_receipt: HashMap<(PortId, ChannelId, Sequence), Receipt>,
+    packet_receipt: Arc<HashMap<(PortId, ChannelId, Sequence), Receipt>>,
+
+    /// The balances of all accounts, indexed by account and denomination.
+    balances: Arc<HashMap<Signer, HashMap<String, u64>>>,
+
+    /// The denomination traces of the ICS20 vouchers known to this chain, indexed by their hash.
+    denom_traces: Arc<HashMap<String, DenomTrace>>,
+
+    /// The interchain accounts registered by (or, on a host chain, for) the controller ports,
+    /// indexed by connection and controller port.
+    interchain_accounts: Arc<HashMap<(ConnectionId, PortId), Signer>>,
+
+    /// The channels over which the controller ports reach their interchain accounts.
+    active_channels: Arc<HashMap<(ConnectionId, PortId), ChannelId>>,
+
+    /// Binds ports to the application modules which handle their channels and packets.
+    router: Router<MockContext>,
+
+    /// The store committing to the state of the chain at each height, if the chain keeps one. The
+    /// headers of the chain then carry the commitment roots, and the state can be proven.
+    store: Option<Arc<ProvableStore>>,
 }
 
 /// Returns a MockContext with bare minimum initialization: no clients, no connections and no channels are
@@ -154,6 +216,7 @@ impl MockContext {
             max_history_size,
             latest_height,
             timestamp: Default::default(),
+            max_expected_time_per_block: DEFAULT_MAX_EXPECTED_TIME_PER_BLOCK,
             history: (0..n)
                 .rev()
                 .map(|i| {
@@ -173,10 +236,16 @@ impl MockContext {
             next_sequence_send: Default::default(),
             next_sequence_recv: Default::default(),
             next_sequence_ack: Default::default(),
-            port_capabilities: Default::default(),
+            capabilities: Default::default(),
             packet_commitment: Default::default(),
             packet_receipt: Default::default(),
             packet_acknowledgement: Default::default(),
+            balances: Default::default(),
+            denom_traces: Default::default(),
+            interchain_accounts: Default::default(),
+            active_channels: Default::default(),
+            router: Default::default(),
+            store: None,
             connection_ids_counter: 0,
             channel_ids_counter: 0,
         }
@@ -196,7 +265,28 @@ impl MockContext {
     /// `consensus_state_height` is None, then the client will be initialized with a consensus
     /// state matching the same height as the client state (`client_state_height`).
     pub fn with_client_parametrized(
+        self,
+        client_id: &ClientId,
+        client_state_height: Height,
+        client_type: Option<ClientType>,
+        consensus_state_height: Option<Height>,
+    ) -> Self {
+        let chain_id = self.host_chain_id.clone();
+        self.with_client_parametrized_with_chain_id(
+            chain_id,
+            client_id,
+            client_state_height,
+            client_type,
+            consensus_state_height,
+        )
+    }
+
+    /// Similar to `with_client_parametrized`, but for a Tendermint client the states are generated
+    /// for the (counterparty) chain `chain_id` instead of the host chain identifier. Headers
+    /// submitted to a Tendermint client must match the chain identifier of its client state.
+    pub fn with_client_parametrized_with_chain_id(
         mut self,
+        chain_id: ChainId,
         client_id: &ClientId,
         client_state_height: Height,
         client_type: Option<ClientType>,
@@ -208,15 +298,12 @@ impl MockContext {
         let (client_state, consensus_state) = match client_type {
             // If it's a mock client, create the corresponding mock states.
             ClientType::Mock => (
-                Some(MockClientState(MockHeader::new(client_state_height)).into()),
-                MockConsensusState(MockHeader::new(cs_height)).into(),
+                Some(MockClientState::new(MockHeader::new(client_state_height)).into()),
+                MockConsensusState::new(MockHeader::new(cs_height)).into(),
             ),
             // If it's a Tendermint client, we need TM states.
             ClientType::Tendermint => {
-                let light_block = HostBlock::generate_tm_block(
-                    self.host_chain_id.clone(),
-                    cs_height.revision_height,
-                );
+                let light_block = HostBlock::generate_tm_block(chain_id, cs_height.revision_height);
                 let consensus_state = AnyConsensusState::from(light_block.clone());
                 let client_state =
                     get_dummy_tendermint_client_state(light_block.signed_header.header);
@@ -224,15 +311,58 @@ impl MockContext {
                 // Return the tuple.
                 (Some(client_state), consensus_state)
             }
+            // A solo machine client tracks the sequence of the (dummy) solo machine.
+            ClientType::SoloMachine => {
+                let client_state =
+                    get_dummy_solomachine_client_state(client_state_height.revision_height);
+                let consensus_state = client_state.consensus_state.clone();
+
+                (
+                    Some(AnyClientState::SoloMachine(client_state)),
+                    AnyConsensusState::SoloMachine(consensus_state),
+                )
+            }
+            // A localhost client tracks the chain `chain_id` (normally the host) at its own height.
+            ClientType::Localhost => (
+                Some(AnyClientState::Localhost(LocalhostClientState::new(
+                    chain_id,
+                    client_state_height,
+                ))),
+                AnyConsensusState::Localhost(LocalhostConsensusState::new(self.timestamp)),
+            ),
         };
         let consensus_states = vec![(cs_height, consensus_state)].into_iter().collect();
+        let processed_times = vec![(cs_height, self.timestamp)].into_iter().collect();
+        let processed_heights = vec![(cs_height, self.latest_height)].into_iter().collect();
 
         let client_record = MockClientRecord {
             client_type,
             client_state,
             consensus_states,
+            processed_times,
+            processed_heights,
         };
-        self.clients.insert(client_id.clone(), client_record);
+        Arc::make_mut(&mut self.clients).insert(client_id.clone(), client_record);
+        self
+    }
+
+    /// Makes this chain keep a provable store, committing to its state from the latest height on.
+    /// State added through the other builders is committed once the chain advances. Only the
+    /// headers of `Mock` hosts carry commitment roots.
+    pub fn with_provable_store(mut self) -> Self {
+        assert!(
+            matches!(self.host_chain_type, HostType::Mock),
+            "Only mock host chains can keep a provable store"
+        );
+
+        self.store = Some(Arc::new(ProvableStore::new(CommitmentPrefix::from(
+            PROVABLE_STORE_PREFIX.to_vec(),
+        ))));
+        if let Some(root) = self.commit_provable_state(self.latest_height) {
+            if let Some(HostBlock::Mock(header)) = self.history.last_mut() {
+                header.root = Some(root);
+            }
+        }
         self
     }
 
@@ -242,73 +372,89 @@ impl MockContext {
         connection_id: ConnectionId,
         connection_end: ConnectionEnd,
     ) -> Self {
-        self.connections.insert(connection_id, connection_end);
+        Arc::make_mut(&mut self.connections).insert(connection_id, connection_end);
         self
     }
 
+    /// Binds `port_id` to a module named after the port, unless the port is bound already.
     pub fn with_port_capability(mut self, port_id: PortId) -> Self {
-        self.port_capabilities.insert(port_id, Capability::new());
+        self.add_port(port_id);
         self
     }
 
-    /// Associates a channel (in an arbitrary state) to this context.
+    /// Binds `module`, identified by `module_id`, to the port `port_id`: the module owns the
+    /// capability of the port, and it is routed the channels and packets of the port. Panics if
+    /// the port is bound already.
+    pub fn with_module(
+        mut self,
+        module_id: ModuleId,
+        port_id: PortId,
+        module: impl Module<MockContext> + 'static,
+    ) -> Self {
+        self.bind_port(&module_id, &port_id).unwrap();
+        self.router.add_route(port_id, module).unwrap();
+        self
+    }
+
+    /// Associates a channel (in an arbitrary state) to this context. If the port of the channel is
+    /// bound, the module which owns the port is given the capability of the channel.
     pub fn with_channel(
-        self,
+        mut self,
         port_id: PortId,
         chan_id: ChannelId,
         channel_end: ChannelEnd,
     ) -> Self {
-        let mut channels = self.channels.clone();
-        channels.insert((port_id, chan_id), channel_end);
-        Self { channels, ..self }
+        if self.channel_capability(&port_id, &chan_id).is_none()
+            && self.lookup_module_by_port(&port_id).is_some()
+        {
+            self.create_channel_capability((port_id.clone(), chan_id.clone()))
+                .unwrap();
+        }
+        Arc::make_mut(&mut self.channels).insert((port_id, chan_id), channel_end);
+        self
     }
 
     pub fn with_send_sequence(
-        self,
+        mut self,
         port_id: PortId,
         chan_id: ChannelId,
         seq_number: Sequence,
     ) -> Self {
-        let mut next_sequence_send = self.next_sequence_send.clone();
-        next_sequence_send.insert((port_id, chan_id), seq_number);
-        Self {
-            next_sequence_send,
-            ..self
-        }
+        Arc::make_mut(&mut self.next_sequence_send).insert((port_id, chan_id), seq_number);
+        self
     }
 
     pub fn with_recv_sequence(
-        self,
+        mut self,
         port_id: PortId,
         chan_id: ChannelId,
         seq_number: Sequence,
     ) -> Self {
-        let mut next_sequence_recv = self.next_sequence_recv.clone();
-        next_sequence_recv.insert((port_id, chan_id), seq_number);
-        Self {
-            next_sequence_recv,
-            ..self
-        }
+        Arc::make_mut(&mut self.next_sequence_recv).insert((port_id, chan_id), seq_number);
+        self
     }
 
     pub fn with_ack_sequence(
-        self,
+        mut self,
         port_id: PortId,
         chan_id: ChannelId,
         seq_number: Sequence,
     ) -> Self {
-        let mut next_sequence_ack = self.next_sequence_send.clone();
-        next_sequence_ack.insert((port_id, chan_id), seq_number);
-        Self {
-            next_sequence_ack,
-            ..self
-        }
+        Arc::make_mut(&mut self.next_sequence_ack).insert((port_id, chan_id), seq_number);
+        self
     }
 
     pub fn with_timestamp(self, timestamp: Timestamp) -> Self {
         Self { timestamp, ..self }
     }
 
+    pub fn with_max_expected_time_per_block(self, max_expected_time_per_block: Duration) -> Self {
+        Self {
+            max_expected_time_per_block,
+            ..self
+        }
+    }
+
     pub fn with_height(self, target_height: Height) -> Self {
         if target_height.revision_number > self.latest_height.revision_number {
             unimplemented!()
@@ -330,18 +476,49 @@ impl MockContext {
     }
 
     pub fn with_packet_commitment(
-        self,
+        mut self,
         port_id: PortId,
         chan_id: ChannelId,
         seq: Sequence,
-        data: String,
+        commitment: PacketCommitment,
     ) -> Self {
-        let mut packet_commitment = self.packet_commitment.clone();
-        packet_commitment.insert((port_id, chan_id, seq), data);
-        Self {
-            packet_commitment,
-            ..self
-        }
+        Arc::make_mut(&mut self.packet_commitment).insert((port_id, chan_id, seq), commitment);
+        self
+    }
+
+    /// Credits `coin` to the balance of `account`.
+    pub fn with_balance(mut self, account: &Signer, coin: Coin) -> Self {
+        *Arc::make_mut(&mut self.balances)
+            .entry(account.clone())
+            .or_default()
+            .entry(coin.denom)
+            .or_default() += coin.amount;
+        self
+    }
+
+    /// Registers a denomination trace, such that vouchers denominated `trace.ibc_denom()` can
+    /// be sent by this chain.
+    pub fn with_denom_trace(mut self, trace: DenomTrace) -> Self {
+        Arc::make_mut(&mut self.denom_traces).insert(trace.hash(), trace);
+        self
+    }
+
+    /// Returns the balance of `account` in denomination `denom`.
+    pub fn balance(&self, account: &Signer, denom: &str) -> u64 {
+        self.balances
+            .get(account)
+            .and_then(|balances| balances.get(denom))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Returns the total amount held in denomination `denom`, over all the accounts of the chain
+    /// (escrow accounts included).
+    pub fn total_supply(&self, denom: &str) -> u64 {
+        self.balances
+            .values()
+            .filter_map(|balances| balances.get(denom))
+            .sum()
     }
 
     /// Accessor for a block of the local (host) chain from this context.
@@ -360,11 +537,31 @@ impl MockContext {
 
     /// Triggers the advancing of the host chain, by extending the history of blocks (or headers).
     pub fn advance_host_chain_height(&mut self) {
-        let new_block = HostBlock::generate_block(
+        self.produce_block(None)
+    }
+
+    /// Advances the host chain like `advance_host_chain_height`, with a block produced at
+    /// `timestamp`, which becomes the current timestamp of the host chain. Only the blocks of a
+    /// Mock host carry the timestamp; synthetic Tendermint blocks keep their generated one.
+    pub fn advance_host_chain_height_at(&mut self, timestamp: Timestamp) {
+        self.timestamp = timestamp;
+        self.produce_block(Some(timestamp))
+    }
+
+    fn produce_block(&mut self, timestamp: Option<Timestamp>) {
+        let new_height = self.latest_height.increment();
+        let mut new_block = HostBlock::generate_block(
             self.host_chain_id.clone(),
             self.host_chain_type,
-            self.latest_height.increment().revision_height,
+            new_height.revision_height,
         );
+        let root = self.commit_provable_state(new_height);
+        if let HostBlock::Mock(header) = &mut new_block {
+            header.root = root;
+            if let Some(timestamp) = timestamp {
+                header.timestamp = timestamp;
+            }
+        }
 
         // Append the new header at the tip of the history.
         if self.history.len() >= self.max_history_size {
@@ -375,7 +572,113 @@ impl MockContext {
             // History is not full yet.
             self.history.push(new_block);
         }
-        self.latest_height = self.latest_height.increment();
+        self.latest_height = new_height;
+
+        let oldest_height = self.history[0].height();
+        if let Some(store) = self.store.as_mut() {
+            Arc::make_mut(store).prune(oldest_height);
+        }
+    }
+
+    /// Commits the state of the chain at `height` to the provable store, if the chain keeps one,
+    /// and returns the commitment root.
+    fn commit_provable_state(&mut self, height: Height) -> Option<[u8; 32]> {
+        let state = self.provable_state();
+        self.store
+            .as_mut()
+            .map(|store| Arc::make_mut(store).commit(height, state))
+    }
+
+    /// Returns the provable state of the chain, with values encoded like the clients of the
+    /// chain expect them.
+    fn provable_state(&self) -> Vec<(Path, Vec<u8>)> {
+        if self.store.is_none() {
+            return vec![];
+        }
+
+        let mut state = vec![];
+        // Encoding into a vector cannot fail.
+        for (client_id, record) in self.clients.iter() {
+            if let Some(client_state) = &record.client_state {
+                state.push((
+                    Path::ClientState(client_id.clone()),
+                    client_state.encode_vec().unwrap(),
+                ));
+            }
+            for (height, consensus_state) in record.consensus_states.iter() {
+                let path = Path::ClientConsensusState {
+                    client_id: client_id.clone(),
+                    epoch: height.revision_number,
+                    height: height.revision_height,
+                };
+                state.push((path, consensus_state.encode_vec().unwrap()));
+            }
+        }
+        for (connection_id, connection_end) in self.connections.iter() {
+            state.push((
+                Path::Connections(connection_id.clone()),
+                connection_end.encode_vec().unwrap(),
+            ));
+        }
+        for ((port_id, channel_id), channel_end) in self.channels.iter() {
+            state.push((
+                Path::ChannelEnds(port_id.clone(), channel_id.clone()),
+                channel_end.encode_vec().unwrap(),
+            ));
+        }
+        for ((port_id, channel_id), seq) in self.next_sequence_send.iter() {
+            state.push((
+                Path::SeqSends(port_id.clone(), channel_id.clone()),
+                u64::from(*seq).to_be_bytes().to_vec(),
+            ));
+        }
+        for ((port_id, channel_id), seq) in self.next_sequence_recv.iter() {
+            state.push((
+                Path::SeqRecvs(port_id.clone(), channel_id.clone()),
+                u64::from(*seq).to_be_bytes().to_vec(),
+            ));
+        }
+        for ((port_id, channel_id), seq) in self.next_sequence_ack.iter() {
+            state.push((
+                Path::SeqAcks(port_id.clone(), channel_id.clone()),
+                u64::from(*seq).to_be_bytes().to_vec(),
+            ));
+        }
+        for ((port_id, channel_id, sequence), commitment) in self.packet_commitment.iter() {
+            let path = Path::Commitments {
+                port_id: port_id.clone(),
+                channel_id: channel_id.clone(),
+                sequence: *sequence,
+            };
+            state.push((path, commitment.as_bytes().to_vec()));
+        }
+        for ((port_id, channel_id, sequence), ack_commitment) in self.packet_acknowledgement.iter()
+        {
+            let path = Path::Acks {
+                port_id: port_id.clone(),
+                channel_id: channel_id.clone(),
+                sequence: *sequence,
+            };
+            state.push((path, ack_commitment.as_bytes().to_vec()));
+        }
+        for (port_id, channel_id, sequence) in self.packet_receipt.keys() {
+            let path = Path::Receipts {
+                port_id: port_id.clone(),
+                channel_id: channel_id.clone(),
+                sequence: *sequence,
+            };
+            state.push((path, vec![1]));
+        }
+        state
+    }
+
+    /// Returns the proof of the value at `path` (or of its absence) in the state of the chain at
+    /// `height`, if the chain keeps a provable store and the state at `height` was not pruned.
+    pub fn query_proof(&self, path: &Path, height: Height) -> Option<CommitmentProofBytes> {
+        self.store
+            .as_ref()
+            .and_then(|store| store.prove(height, path))
+            .map(Into::into)
     }
 
     /// A datagram passes from the relayer to the IBC module (on host chain).
@@ -388,7 +691,8 @@ impl MockContext {
         Ok(())
     }
 
-    /// Validates this context. Should be called after the context is mutated by a test.
+    /// Validates this context, i.e., the history of the host chain and the references between the
+    /// objects in its store. Should be called after the context is mutated by a test.
     pub fn validate(&self) -> Result<(), Box<dyn Error>> {
         // Check that the number of entries is not higher than window size.
         if self.history.len() > self.max_history_size {
@@ -413,11 +717,84 @@ impl MockContext {
                 return Err("headers in history not sequential".to_string().into());
             }
         }
+
+        // Check that the connections and channels only reference objects in the store.
+        for (conn_id, conn) in self.connections.iter() {
+            if !self.clients.contains_key(conn.client_id()) {
+                return Err(format!("connection {} references a missing client", conn_id).into());
+            }
+        }
+        for (client_id, conn_id) in self.client_connections.iter() {
+            if !self.connections.contains_key(conn_id) {
+                return Err(format!("client {} references a missing connection", client_id).into());
+            }
+        }
+        for ((port_id, chan_id), chan) in self.channels.iter() {
+            match chan.connection_hops().as_slice() {
+                [conn_id] if self.connections.contains_key(conn_id) => {}
+                _ => {
+                    return Err(format!(
+                        "channel {}/{} does not have a single, existing connection hop",
+                        port_id, chan_id
+                    )
+                    .into())
+                }
+            }
+        }
+        for (conn_id, channels) in self.connection_channels.iter() {
+            if channels.iter().any(|key| !self.channels.contains_key(key)) {
+                return Err(format!("connection {} references a missing channel", conn_id).into());
+            }
+        }
+
+        // Check that the packet state only pertains to channels in the store.
+        let sequences = self
+            .next_sequence_send
+            .iter()
+            .chain(self.next_sequence_recv.iter())
+            .chain(self.next_sequence_ack.iter());
+        for ((port_id, chan_id), seq) in sequences {
+            if !self
+                .channels
+                .contains_key(&(port_id.clone(), chan_id.clone()))
+            {
+                return Err(format!("sequence of missing channel {}/{}", port_id, chan_id).into());
+            }
+            if u64::from(*seq) == 0 {
+                return Err(format!("zero sequence on channel {}/{}", port_id, chan_id).into());
+            }
+        }
+        let packets = self
+            .packet_commitment
+            .keys()
+            .chain(self.packet_receipt.keys())
+            .chain(self.packet_acknowledgement.keys());
+        for (port_id, chan_id, seq) in packets {
+            if !self
+                .channels
+                .contains_key(&(port_id.clone(), chan_id.clone()))
+            {
+                return Err(
+                    format!("packet {} of missing channel {}/{}", seq, port_id, chan_id).into(),
+                );
+            }
+        }
+
         Ok(())
     }
 
+    /// Binds `port_id` to a module named after the port, unless the port is bound already.
     pub fn add_port(&mut self, port_id: PortId) {
-        self.port_capabilities.insert(port_id, Capability::new());
+        if self.lookup_module_by_port(&port_id).is_none() {
+            self.bind_port(&ModuleId::new(port_id.as_str()), &port_id)
+                .unwrap();
+        }
+    }
+
+    /// Returns the capability of the given channel held by the module which owns its port.
+    pub fn channel_capability(&self, port_id: &PortId, chan_id: &ChannelId) -> Option<Capability> {
+        let (module_id, _) = self.lookup_module_by_port(port_id)?;
+        self.get_capability(&module_id, &CapabilityName::channel(port_id, chan_id))
     }
 
     pub fn consensus_states(&self, client_id: &ClientId) -> Vec<AnyConsensusStateWithHeight> {
@@ -432,20 +809,167 @@ impl MockContext {
     }
 }
 
-impl Ics26Context for MockContext {}
+impl Ics26Context for MockContext {
+    fn router(&self) -> &Router<Self> {
+        &self.router
+    }
+}
 
 impl Ics20Context for MockContext {}
 
-impl PortReader for MockContext {
-    fn lookup_module_by_port(&self, port_id: &PortId) -> Option<Capability> {
-        self.port_capabilities.get(port_id).cloned()
+impl Ics20Reader for MockContext {
+    fn get_denom_trace(&self, denom_hash: &str) -> Option<DenomTrace> {
+        self.denom_traces.get(denom_hash).cloned()
     }
+}
 
-    fn authenticate(&self, _cap: &Capability, _port_id: &PortId) -> bool {
-        true
+impl Ics20Keeper for MockContext {
+    fn store_denom_trace(&mut self, trace: DenomTrace) -> Result<(), Ics20Error> {
+        Arc::make_mut(&mut self.denom_traces).insert(trace.hash(), trace);
+        Ok(())
     }
 }
 
+impl BankKeeper for MockContext {
+    fn send_coins(&mut self, from: &Signer, to: &Signer, coin: &Coin) -> Result<(), Ics20Error> {
+        self.burn_coins(from, coin)?;
+        self.mint_coins(to, coin)
+    }
+
+    fn mint_coins(&mut self, account: &Signer, coin: &Coin) -> Result<(), Ics20Error> {
+        let balance = Arc::make_mut(&mut self.balances)
+            .entry(account.clone())
+            .or_default()
+            .entry(coin.denom.clone())
+            .or_default();
+        *balance = balance.checked_add(coin.amount).ok_or_else(|| {
+            Ics20Kind::BankError.context(format!("balance overflow for account {}", account))
+        })?;
+        Ok(())
+    }
+
+    fn burn_coins(&mut self, account: &Signer, coin: &Coin) -> Result<(), Ics20Error> {
+        let balance = Arc::make_mut(&mut self.balances)
+            .get_mut(account)
+            .and_then(|balances| balances.get_mut(&coin.denom))
+            .filter(|balance| **balance >= coin.amount)
+            .ok_or_else(|| Ics20Kind::InsufficientFunds(account.clone(), coin.denom.clone()))?;
+        *balance -= coin.amount;
+        Ok(())
+    }
+
+    fn get_escrow_address(&self, port_id: &PortId, channel_id: &ChannelId) -> Signer {
+        Signer::new(format!("escrow/{}/{}", port_id, channel_id))
+    }
+}
+
+impl Ics27ControllerContext for MockContext {}
+
+impl Ics27HostContext for MockContext {}
+
+impl InterchainAccountReader for MockContext {
+    fn get_interchain_account(
+        &self,
+        connection_id: &ConnectionId,
+        port_id: &PortId,
+    ) -> Option<Signer> {
+        self.interchain_accounts
+            .get(&(connection_id.clone(), port_id.clone()))
+            .cloned()
+    }
+
+    fn get_active_channel(
+        &self,
+        connection_id: &ConnectionId,
+        port_id: &PortId,
+    ) -> Option<ChannelId> {
+        self.active_channels
+            .get(&(connection_id.clone(), port_id.clone()))
+            .cloned()
+    }
+}
+
+impl InterchainAccountKeeper for MockContext {
+    fn store_interchain_account(
+        &mut self,
+        connection_id: ConnectionId,
+        port_id: PortId,
+        address: Signer,
+    ) -> Result<(), Ics27Error> {
+        Arc::make_mut(&mut self.interchain_accounts).insert((connection_id, port_id), address);
+        Ok(())
+    }
+
+    fn store_active_channel(
+        &mut self,
+        connection_id: ConnectionId,
+        port_id: PortId,
+        channel_id: ChannelId,
+    ) -> Result<(), Ics27Error> {
+        Arc::make_mut(&mut self.active_channels).insert((connection_id, port_id), channel_id);
+        Ok(())
+    }
+}
+
+/// Interchain accounts can only send tokens, with `MsgTransfer`.
+impl MessageExecutor for MockContext {
+    fn is_allowed(&self, type_url: &str) -> bool {
+        type_url == transfer::TYPE_URL
+    }
+
+    fn execute(&mut self, account: &Signer, message: &Any) -> Result<(), Ics27Error> {
+        let failed = || Ics27Kind::MessageExecutionFailed(message.type_url.clone());
+
+        let msg =
+            transfer::MsgTransfer::decode_vec(&message.value).map_err(|e| failed().context(e))?;
+        if &msg.sender != account {
+            return Err(failed()
+                .context(format!("message not signed by account {}", account))
+                .into());
+        }
+
+        dispatch(self, Ics26Envelope::Ics20Msg(msg)).map_err(|e| failed().context(e))?;
+        Ok(())
+    }
+}
+
+impl CapabilityReader for MockContext {
+    fn get_capability(&self, module_id: &ModuleId, name: &CapabilityName) -> Option<Capability> {
+        self.capabilities.get_capability(module_id, name)
+    }
+
+    fn authenticate_capability(&self, name: &CapabilityName, capability: &Capability) -> bool {
+        self.capabilities.authenticate_capability(name, capability)
+    }
+
+    fn lookup_modules(&self, name: &CapabilityName) -> Vec<ModuleId> {
+        self.capabilities.lookup_modules(name)
+    }
+}
+
+impl CapabilityKeeper for MockContext {
+    fn new_capability(
+        &mut self,
+        module_id: &ModuleId,
+        name: CapabilityName,
+    ) -> Result<Capability, Ics05Error> {
+        Arc::make_mut(&mut self.capabilities).new_capability(module_id, name)
+    }
+
+    fn claim_capability(
+        &mut self,
+        module_id: &ModuleId,
+        name: CapabilityName,
+        capability: &Capability,
+    ) -> Result<(), Ics05Error> {
+        Arc::make_mut(&mut self.capabilities).claim_capability(module_id, name, capability)
+    }
+}
+
+impl PortReader for MockContext {}
+
+impl PortKeeper for MockContext {}
+
 impl ChannelReader for MockContext {
     fn channel_end(&self, pcid: &(PortId, ChannelId)) -> Option<ChannelEnd> {
         self.channels.get(pcid).cloned()
@@ -471,18 +995,25 @@ impl ChannelReader for MockContext {
         ClientReader::consensus_state(self, client_id, height)
     }
 
-    fn authenticated_capability(&self, port_id: &PortId) -> Result<Capability, Ics4Error> {
-        let cap = PortReader::lookup_module_by_port(self, port_id);
-        match cap {
-            Some(key) => {
-                if !PortReader::authenticate(self, &key, port_id) {
-                    Err(Ics4Kind::InvalidPortCapability.into())
-                } else {
-                    Ok(key)
-                }
-            }
-            None => Err(Ics4Kind::NoPortCapability(port_id.clone()).into()),
-        }
+    fn client_update_time(&self, client_id: &ClientId, height: Height) -> Option<Timestamp> {
+        ClientReader::client_update_time(self, client_id, height)
+    }
+
+    fn client_update_height(&self, client_id: &ClientId, height: Height) -> Option<Height> {
+        ClientReader::client_update_height(self, client_id, height)
+    }
+
+    fn authenticate_port_capability(&self, port_id: &PortId, capability: &Capability) -> bool {
+        PortReader::authenticate(self, capability, port_id)
+    }
+
+    fn authenticate_channel_capability(
+        &self,
+        port_channel_id: &(PortId, ChannelId),
+        capability: &Capability,
+    ) -> bool {
+        let name = CapabilityName::channel(&port_channel_id.0, &port_channel_id.1);
+        self.authenticate_capability(&name, capability)
     }
 
     fn get_next_sequence_send(&self, port_channel_id: &(PortId, ChannelId)) -> Option<Sequence> {
@@ -497,7 +1028,10 @@ impl ChannelReader for MockContext {
         self.next_sequence_ack.get(port_channel_id).cloned()
     }
 
-    fn get_packet_commitment(&self, key: &(PortId, ChannelId, Sequence)) -> Option<String> {
+    fn get_packet_commitment(
+        &self,
+        key: &(PortId, ChannelId, Sequence),
+    ) -> Option<PacketCommitment> {
         self.packet_commitment.get(key).cloned()
     }
 
@@ -505,15 +1039,13 @@ impl ChannelReader for MockContext {
         self.packet_receipt.get(key).cloned()
     }
 
-    fn get_packet_acknowledgement(&self, key: &(PortId, ChannelId, Sequence)) -> Option<String> {
+    fn get_packet_acknowledgement(
+        &self,
+        key: &(PortId, ChannelId, Sequence),
+    ) -> Option<AcknowledgementCommitment> {
         self.packet_acknowledgement.get(key).cloned()
     }
 
-    fn hash(&self, input: String) -> String {
-        let r = sha2::Sha256::digest(input.as_bytes());
-        format!("{:x}", r)
-    }
-
     fn host_height(&self) -> Height {
         self.latest_height
     }
@@ -522,33 +1054,47 @@ impl ChannelReader for MockContext {
         self.timestamp
     }
 
+    fn max_expected_time_per_block(&self) -> Duration {
+        self.max_expected_time_per_block
+    }
+
     fn channel_counter(&self) -> u64 {
         self.channel_ids_counter
     }
 }
 
 impl ChannelKeeper for MockContext {
+    fn create_channel_capability(
+        &mut self,
+        port_channel_id: (PortId, ChannelId),
+    ) -> Result<Capability, Ics4Error> {
+        let (port_id, channel_id) = port_channel_id;
+        let (module_id, _) = self
+            .lookup_module_by_port(&port_id)
+            .ok_or_else(|| Ics4Kind::NoPortCapability(port_id.clone()))?;
+        self.new_capability(&module_id, CapabilityName::channel(&port_id, &channel_id))
+            .map_err(|e| {
+                Ics4Kind::InvalidChannelCapability(port_id, channel_id)
+                    .context(e)
+                    .into()
+            })
+    }
+
     fn store_packet_commitment(
         &mut self,
         key: (PortId, ChannelId, Sequence),
-        timeout_timestamp: Timestamp,
-        timeout_height: Height,
-        data: Vec<u8>,
+        commitment: PacketCommitment,
     ) -> Result<(), Ics4Error> {
-        let input = format!("{:?},{:?},{:?}", timeout_timestamp, timeout_height, data);
-        self.packet_commitment
-            .insert(key, ChannelReader::hash(self, input));
+        Arc::make_mut(&mut self.packet_commitment).insert(key, commitment);
         Ok(())
     }
 
     fn store_packet_acknowledgement(
         &mut self,
         key: (PortId, ChannelId, Sequence),
-        ack: Vec<u8>,
+        ack_commitment: AcknowledgementCommitment,
     ) -> Result<(), Ics4Error> {
-        let input = format!("{:?}", ack);
-        self.packet_acknowledgement
-            .insert(key, ChannelReader::hash(self, input));
+        Arc::make_mut(&mut self.packet_acknowledgement).insert(key, ack_commitment);
         Ok(())
     }
 
@@ -556,7 +1102,7 @@ impl ChannelKeeper for MockContext {
         &mut self,
         key: (PortId, ChannelId, Sequence),
     ) -> Result<(), Ics4Error> {
-        self.packet_acknowledgement.remove(&key);
+        Arc::make_mut(&mut self.packet_acknowledgement).remove(&key);
         Ok(())
     }
 
@@ -565,7 +1111,7 @@ impl ChannelKeeper for MockContext {
         cid: ConnectionId,
         port_channel_id: &(PortId, ChannelId),
     ) -> Result<(), Ics4Error> {
-        self.connection_channels
+        Arc::make_mut(&mut self.connection_channels)
             .entry(cid)
             .or_insert_with(Vec::new)
             .push(port_channel_id.clone());
@@ -577,7 +1123,7 @@ impl ChannelKeeper for MockContext {
         port_channel_id: (PortId, ChannelId),
         channel_end: &ChannelEnd,
     ) -> Result<(), Ics4Error> {
-        self.channels.insert(port_channel_id, channel_end.clone());
+        Arc::make_mut(&mut self.channels).insert(port_channel_id, channel_end.clone());
         Ok(())
     }
 
@@ -586,7 +1132,7 @@ impl ChannelKeeper for MockContext {
         port_channel_id: (PortId, ChannelId),
         seq: Sequence,
     ) -> Result<(), Ics4Error> {
-        self.next_sequence_send.insert(port_channel_id, seq);
+        Arc::make_mut(&mut self.next_sequence_send).insert(port_channel_id, seq);
         Ok(())
     }
 
@@ -595,7 +1141,7 @@ impl ChannelKeeper for MockContext {
         port_channel_id: (PortId, ChannelId),
         seq: Sequence,
     ) -> Result<(), Ics4Error> {
-        self.next_sequence_recv.insert(port_channel_id, seq);
+        Arc::make_mut(&mut self.next_sequence_recv).insert(port_channel_id, seq);
         Ok(())
     }
 
@@ -604,7 +1150,7 @@ impl ChannelKeeper for MockContext {
         port_channel_id: (PortId, ChannelId),
         seq: Sequence,
     ) -> Result<(), Ics4Error> {
-        self.next_sequence_ack.insert(port_channel_id, seq);
+        Arc::make_mut(&mut self.next_sequence_ack).insert(port_channel_id, seq);
         Ok(())
     }
 
@@ -616,7 +1162,7 @@ impl ChannelKeeper for MockContext {
         &mut self,
         key: (PortId, ChannelId, Sequence),
     ) -> Result<(), Ics4Error> {
-        self.packet_commitment.remove(&key);
+        Arc::make_mut(&mut self.packet_commitment).remove(&key);
         Ok(())
     }
 
@@ -625,7 +1171,7 @@ impl ChannelKeeper for MockContext {
         key: (PortId, ChannelId, Sequence),
         receipt: Receipt,
     ) -> Result<(), Ics4Error> {
-        self.packet_receipt.insert(key, receipt);
+        Arc::make_mut(&mut self.packet_receipt).insert(key, receipt);
         Ok(())
     }
 }
@@ -644,13 +1190,20 @@ impl ConnectionReader for MockContext {
         self.latest_height
     }
 
+    fn host_timestamp(&self) -> Timestamp {
+        self.timestamp
+    }
+
     fn host_oldest_height(&self) -> Height {
         // history must be non-empty, so `self.history[0]` is valid
         self.history[0].height()
     }
 
     fn commitment_prefix(&self) -> CommitmentPrefix {
-        CommitmentPrefix::from(vec![])
+        match &self.store {
+            Some(store) => store.prefix().clone(),
+            None => CommitmentPrefix::from(vec![]),
+        }
     }
 
     fn client_consensus_state(
@@ -667,6 +1220,72 @@ impl ConnectionReader for MockContext {
         block_ref.cloned().map(Into::into)
     }
 
+    fn validate_self_client(
+        &self,
+        counterparty_client_state: &AnyClientState,
+    ) -> Result<(), Ics3Error> {
+        match (self.host_chain_type, counterparty_client_state) {
+            // A Tendermint host behaves like a Cosmos SDK chain.
+            (HostType::SyntheticTendermint, AnyClientState::Tendermint(client_state)) => {
+                let upgrade_path: Vec<String> = TENDERMINT_HOST_UPGRADE_PATH
+                    .iter()
+                    .map(|key| key.to_string())
+                    .collect();
+
+                client_state
+                    .validate_self_client(
+                        &self.host_chain_id,
+                        self.latest_height,
+                        TENDERMINT_HOST_UNBONDING_PERIOD,
+                        &ProofSpecs::cosmos(),
+                        &upgrade_path,
+                    )
+                    .map_err(|e| Ics3Kind::InvalidSelfClient.context(e).into())
+            }
+            // Mock clients carry no parameters besides their height. A localhost client can only
+            // track the host chain itself, whatever its type.
+            (HostType::Mock, AnyClientState::Mock(_)) | (_, AnyClientState::Localhost(_)) => {
+                if let AnyClientState::Localhost(client_state) = counterparty_client_state {
+                    if client_state.chain_id != self.host_chain_id {
+                        return Err(Ics3Kind::InvalidSelfClient
+                            .context(format!(
+                                "the client tracks chain {}, not the host chain {}",
+                                client_state.chain_id, self.host_chain_id
+                            ))
+                            .into());
+                    }
+                }
+
+                if counterparty_client_state.is_frozen() {
+                    return Err(Ics3Kind::InvalidSelfClient
+                        .context("the client is frozen")
+                        .into());
+                }
+
+                let latest_height = counterparty_client_state.latest_height();
+                if latest_height.revision_number != self.latest_height.revision_number
+                    || latest_height >= self.latest_height
+                {
+                    return Err(Ics3Kind::InvalidSelfClient
+                        .context(format!(
+                            "the client height {} is not below the host height {}",
+                            latest_height, self.latest_height
+                        ))
+                        .into());
+                }
+
+                Ok(())
+            }
+            _ => Err(Ics3Kind::InvalidSelfClient
+                .context(format!(
+                    "a {} client cannot track a host of type {:?}",
+                    counterparty_client_state.client_type(),
+                    self.host_chain_type
+                ))
+                .into()),
+        }
+    }
+
     fn connection_counter(&self) -> u64 {
         self.connection_ids_counter
     }
@@ -678,8 +1297,7 @@ impl ConnectionKeeper for MockContext {
         connection_id: ConnectionId,
         connection_end: &ConnectionEnd,
     ) -> Result<(), Ics3Error> {
-        self.connections
-            .insert(connection_id, connection_end.clone());
+        Arc::make_mut(&mut self.connections).insert(connection_id, connection_end.clone());
         Ok(())
     }
 
@@ -688,8 +1306,7 @@ impl ConnectionKeeper for MockContext {
         connection_id: ConnectionId,
         client_id: &ClientId,
     ) -> Result<(), Ics3Error> {
-        self.client_connections
-            .insert(client_id.clone(), connection_id);
+        Arc::make_mut(&mut self.client_connections).insert(client_id.clone(), connection_id);
         Ok(())
     }
 
@@ -723,6 +1340,36 @@ impl ClientReader for MockContext {
         }
     }
 
+    fn consensus_state_heights(&self, client_id: &ClientId) -> Vec<Height> {
+        let mut heights: Vec<Height> = self
+            .clients
+            .get(client_id)
+            .map(|client_record| client_record.consensus_states.keys().cloned().collect())
+            .unwrap_or_default();
+        heights.sort();
+        heights
+    }
+
+    fn host_timestamp(&self) -> Timestamp {
+        self.timestamp
+    }
+
+    fn host_height(&self) -> Height {
+        self.latest_height
+    }
+
+    fn client_update_time(&self, client_id: &ClientId, height: Height) -> Option<Timestamp> {
+        self.clients
+            .get(client_id)
+            .and_then(|client_record| client_record.processed_times.get(&height).cloned())
+    }
+
+    fn client_update_height(&self, client_id: &ClientId, height: Height) -> Option<Height> {
+        self.clients
+            .get(client_id)
+            .and_then(|client_record| client_record.processed_heights.get(&height).cloned())
+    }
+
     fn client_counter(&self) -> u64 {
         self.client_ids_counter
     }
@@ -734,11 +1381,16 @@ impl ClientKeeper for MockContext {
         client_id: ClientId,
         client_type: ClientType,
     ) -> Result<(), Ics02Error> {
-        let mut client_record = self.clients.entry(client_id).or_insert(MockClientRecord {
-            client_type,
-            consensus_states: Default::default(),
-            client_state: Default::default(),
-        });
+        let mut client_record =
+            Arc::make_mut(&mut self.clients)
+                .entry(client_id)
+                .or_insert(MockClientRecord {
+                    client_type,
+                    consensus_states: Default::default(),
+                    client_state: Default::default(),
+                    processed_times: Default::default(),
+                    processed_heights: Default::default(),
+                });
 
         client_record.client_type = client_type;
         Ok(())
@@ -749,11 +1401,16 @@ impl ClientKeeper for MockContext {
         client_id: ClientId,
         client_state: AnyClientState,
     ) -> Result<(), Ics02Error> {
-        let mut client_record = self.clients.entry(client_id).or_insert(MockClientRecord {
-            client_type: client_state.client_type(),
-            consensus_states: Default::default(),
-            client_state: Default::default(),
-        });
+        let mut client_record =
+            Arc::make_mut(&mut self.clients)
+                .entry(client_id)
+                .or_insert(MockClientRecord {
+                    client_type: client_state.client_type(),
+                    consensus_states: Default::default(),
+                    client_state: Default::default(),
+                    processed_times: Default::default(),
+                    processed_heights: Default::default(),
+                });
 
         client_record.client_state = Some(client_state);
         Ok(())
@@ -765,11 +1422,16 @@ impl ClientKeeper for MockContext {
         height: Height,
         consensus_state: AnyConsensusState,
     ) -> Result<(), Ics02Error> {
-        let client_record = self.clients.entry(client_id).or_insert(MockClientRecord {
-            client_type: ClientType::Mock,
-            consensus_states: Default::default(),
-            client_state: Default::default(),
-        });
+        let client_record =
+            Arc::make_mut(&mut self.clients)
+                .entry(client_id)
+                .or_insert(MockClientRecord {
+                    client_type: ClientType::Mock,
+                    consensus_states: Default::default(),
+                    client_state: Default::default(),
+                    processed_times: Default::default(),
+                    processed_heights: Default::default(),
+                });
 
         client_record
             .consensus_states
@@ -777,6 +1439,73 @@ impl ClientKeeper for MockContext {
         Ok(())
     }
 
+    fn store_update_time(
+        &mut self,
+        client_id: ClientId,
+        height: Height,
+        timestamp: Timestamp,
+    ) -> Result<(), Ics02Error> {
+        let client_record = Arc::make_mut(&mut self.clients)
+            .get_mut(&client_id)
+            .ok_or_else(|| Ics02Kind::ClientNotFound(client_id.clone()))?;
+
+        client_record.processed_times.insert(height, timestamp);
+        Ok(())
+    }
+
+    fn store_update_height(
+        &mut self,
+        client_id: ClientId,
+        height: Height,
+        host_height: Height,
+    ) -> Result<(), Ics02Error> {
+        let client_record = Arc::make_mut(&mut self.clients)
+            .get_mut(&client_id)
+            .ok_or_else(|| Ics02Kind::ClientNotFound(client_id.clone()))?;
+
+        client_record.processed_heights.insert(height, host_height);
+        Ok(())
+    }
+
+    fn delete_consensus_state(
+        &mut self,
+        client_id: ClientId,
+        height: Height,
+    ) -> Result<(), Ics02Error> {
+        let client_record = Arc::make_mut(&mut self.clients)
+            .get_mut(&client_id)
+            .ok_or_else(|| Ics02Kind::ClientNotFound(client_id.clone()))?;
+
+        client_record.consensus_states.remove(&height);
+        Ok(())
+    }
+
+    fn delete_update_time(
+        &mut self,
+        client_id: ClientId,
+        height: Height,
+    ) -> Result<(), Ics02Error> {
+        let client_record = Arc::make_mut(&mut self.clients)
+            .get_mut(&client_id)
+            .ok_or_else(|| Ics02Kind::ClientNotFound(client_id.clone()))?;
+
+        client_record.processed_times.remove(&height);
+        Ok(())
+    }
+
+    fn delete_update_height(
+        &mut self,
+        client_id: ClientId,
+        height: Height,
+    ) -> Result<(), Ics02Error> {
+        let client_record = Arc::make_mut(&mut self.clients)
+            .get_mut(&client_id)
+            .ok_or_else(|| Ics02Kind::ClientNotFound(client_id.clone()))?;
+
+        client_record.processed_heights.remove(&height);
+        Ok(())
+    }
+
     fn increase_client_counter(&mut self) {
         self.client_ids_counter += 1
     }
@@ -797,10 +1526,39 @@ impl Ics18Context for MockContext {
         block_ref.cloned().map(Into::into)
     }
 
+    fn query_connection(&self, connection_id: &ConnectionId) -> Option<ConnectionEnd> {
+        ConnectionReader::connection_end(self, connection_id)
+    }
+
+    fn query_channel(&self, port_id: &PortId, channel_id: &ChannelId) -> Option<ChannelEnd> {
+        self.channel_end(&(port_id.clone(), channel_id.clone()))
+    }
+
+    fn query_next_sequence_recv(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+    ) -> Option<Sequence> {
+        self.get_next_sequence_recv(&(port_id.clone(), channel_id.clone()))
+    }
+
+    fn query_commitment_prefix(&self) -> CommitmentPrefix {
+        ConnectionReader::commitment_prefix(self)
+    }
+
+    /// Only chains which keep a provable store (see `MockContext::with_provable_store`) can prove
+    /// their state.
+    fn query_proof(&self, path: &Path, height: Height) -> Option<CommitmentProofBytes> {
+        MockContext::query_proof(self, path, height)
+    }
+
     fn send(&mut self, msgs: Vec<Any>) -> Result<Vec<IbcEvent>, Ics18Error> {
         // Forward call to Ics26 delivery method.
-        let events =
-            deliver(self, msgs).map_err(|e| Ics18ErrorKind::TransactionFailed.context(e))?;
+        let events = deliver(self, msgs)
+            .map_err(|e| Ics18ErrorKind::TransactionFailed.context(e))?
+            .into_iter()
+            .flat_map(|output| output.events)
+            .collect();
 
         self.advance_host_chain_height(); // Advance chain height
         Ok(events)
@@ -813,9 +1571,21 @@ impl Ics18Context for MockContext {
 
 #[cfg(test)]
 mod tests {
-    use crate::ics24_host::identifier::ChainId;
+    use std::sync::Arc;
+
+    use crate::ics02_client::client_def::ClientDef;
+    use crate::ics03_connection::context::ConnectionReader;
+    use crate::ics04_channel::commitment::PacketCommitment;
+    use crate::ics04_channel::context::ChannelKeeper;
+    use crate::ics04_channel::packet::Sequence;
+    use crate::ics23_commitment::commitment::{CommitmentPrefix, CommitmentRoot};
+    use crate::ics24_host::identifier::{ChainId, ChannelId, ClientId, PortId};
+    use crate::ics24_host::Path;
+    use crate::ics26_routing::context::Ics26Context;
+    use crate::mock::client_def::MockClient;
+    use crate::mock::client_state::{MockClientState, MockConsensusState};
     use crate::mock::context::MockContext;
-    use crate::mock::host::HostType;
+    use crate::mock::host::{HostBlock, HostType};
     use crate::Height;
     use test_env_log::test;
 
@@ -957,4 +1727,119 @@ mod tests {
             }
         }
     }
+    #[test]
+    fn provable_store_proofs_are_verified() {
+        let port_id = PortId::default();
+        let channel_id = ChannelId::default();
+        let sequence = Sequence::from(1);
+        let commitment = PacketCommitment::from(vec![1]);
+
+        let mut ctx = MockContext::default()
+            .with_provable_store()
+            .with_packet_commitment(
+                port_id.clone(),
+                channel_id.clone(),
+                sequence,
+                commitment.clone(),
+            );
+        let previous_height = ctx.host_current_height();
+        ctx.advance_host_chain_height();
+        let height = ctx.host_current_height();
+
+        let header_at = |height| match ctx.host_block(height).cloned() {
+            Some(HostBlock::Mock(header)) => header,
+            _ => panic!("missing mock header at {:?}", height),
+        };
+        let client_state = MockClientState::new(header_at(height));
+        assert!(client_state.verifies_proofs());
+        let root = MockConsensusState::new(header_at(height)).root;
+        let previous_root = MockConsensusState::new(header_at(previous_height)).root;
+
+        let prefix = ctx.commitment_prefix();
+        let path = Path::Commitments {
+            port_id: port_id.clone(),
+            channel_id: channel_id.clone(),
+            sequence,
+        };
+        let proof = ctx.query_proof(&path, height).unwrap();
+
+        let verify = |prefix: &CommitmentPrefix, root: &CommitmentRoot, sequence: Sequence| {
+            MockClient.verify_packet_data(
+                &ctx,
+                &client_state,
+                height,
+                prefix,
+                &proof,
+                root,
+                &port_id,
+                &channel_id,
+                &sequence,
+                commitment.clone(),
+            )
+        };
+        assert!(verify(&prefix, &root, sequence).is_ok());
+        // Wrong keys, wrong prefixes and wrong heights are all caught.
+        assert!(verify(&prefix, &root, Sequence::from(2)).is_err());
+        assert!(verify(&CommitmentPrefix::from(b"xyz".to_vec()), &root, sequence).is_err());
+        assert!(verify(&prefix, &previous_root, sequence).is_err());
+
+        // No receipt was stored, and the absence of the receipt can be proven.
+        let receipt_path = Path::Receipts {
+            port_id: port_id.clone(),
+            channel_id: channel_id.clone(),
+            sequence,
+        };
+        let receipt_proof = ctx.query_proof(&receipt_path, height).unwrap();
+        assert!(MockClient
+            .verify_packet_receipt_absence(
+                &ctx,
+                &client_state,
+                height,
+                &prefix,
+                &receipt_proof,
+                &root,
+                &port_id,
+                &channel_id,
+                &sequence,
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn branch_copies_only_the_written_stores() {
+        let port_id = PortId::default();
+        let channel_id = ChannelId::default();
+        let key = (port_id.clone(), channel_id.clone(), Sequence::from(1));
+
+        let mut ctx = MockContext::default()
+            .with_provable_store()
+            .with_client(&ClientId::default(), Height::new(0, 5))
+            .with_packet_commitment(
+                port_id,
+                channel_id,
+                Sequence::from(2),
+                PacketCommitment::from(vec![2]),
+            );
+
+        let mut branch = ctx.branch();
+        branch
+            .store_packet_commitment(key.clone(), PacketCommitment::from(vec![1]))
+            .unwrap();
+
+        // Only the written store was copied, into the branch.
+        assert!(Arc::ptr_eq(&branch.clients, &ctx.clients));
+        assert!(Arc::ptr_eq(
+            branch.store.as_ref().unwrap(),
+            ctx.store.as_ref().unwrap()
+        ));
+        assert!(!Arc::ptr_eq(
+            &branch.packet_commitment,
+            &ctx.packet_commitment
+        ));
+        assert!(!ctx.packet_commitment.contains_key(&key));
+
+        ctx.commit_branch(branch);
+        assert!(ctx.packet_commitment.contains_key(&key));
+        assert_eq!(ctx.packet_commitment.len(), 2);
+    }
 }
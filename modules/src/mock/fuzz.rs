@@ -0,0 +1,462 @@
+//! Support for fuzzing the message handlers with arbitrary messages.
+//!
+//! A [`Harness`] delivers batches of [`FuzzMsg`] to a mock chain, which is seeded with clients, a
+//! connection and an ICS20 channel for the messages to refer to. Messages are generated as domain
+//! types, so that they mostly pass decoding and reach deep into the handlers, or as raw bytes,
+//! so that decoding itself is exercised. After every batch, the harness checks that the chain is
+//! consistent, that a rejected batch leaves it untouched, and that connections, channels and
+//! sequences never move backwards. Any violation, like any panic in the handlers, is a bug.
+//!
+//! The fuzz targets in `modules/fuzz` drive the harness with `cargo fuzz`, whereas the tests of
+//! this module drive it with a deterministic stream of bytes.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use prost_types::Any;
+
+use crate::application::ics20_fungible_token_transfer::module::{Ics20Module, MODULE_ID, VERSION};
+use crate::application::ics20_fungible_token_transfer::msgs::transfer::{self, MsgTransfer};
+use crate::ics02_client::client_type::ClientType;
+use crate::ics02_client::context::{ClientKeeper, ClientReader};
+use crate::ics02_client::msgs::create_client::{self, MsgCreateAnyClient};
+use crate::ics02_client::msgs::misbehavior::{self, MsgSubmitAnyMisbehaviour};
+use crate::ics02_client::msgs::update_client::{self, MsgUpdateAnyClient};
+use crate::ics02_client::msgs::upgrade_client::{self, MsgUpgradeAnyClient};
+use crate::ics03_connection::connection::{
+    ConnectionEnd, Counterparty as ConnectionCounterparty, State as ConnectionState,
+};
+use crate::ics03_connection::context::{ConnectionKeeper, ConnectionReader};
+use crate::ics03_connection::msgs::conn_open_ack::{self, MsgConnectionOpenAck};
+use crate::ics03_connection::msgs::conn_open_confirm::{self, MsgConnectionOpenConfirm};
+use crate::ics03_connection::msgs::conn_open_init::{self, MsgConnectionOpenInit};
+use crate::ics03_connection::msgs::conn_open_try::{self, MsgConnectionOpenTry};
+use crate::ics03_connection::version::Version;
+use crate::ics04_channel::channel::{
+    ChannelEnd, Counterparty as ChannelCounterparty, Order, State as ChannelState,
+};
+use crate::ics04_channel::context::{ChannelKeeper, ChannelReader};
+use crate::ics04_channel::msgs::acknowledgement::{self, MsgAcknowledgement};
+use crate::ics04_channel::msgs::chan_close_confirm::{self, MsgChannelCloseConfirm};
+use crate::ics04_channel::msgs::chan_close_init::{self, MsgChannelCloseInit};
+use crate::ics04_channel::msgs::chan_open_ack::{self, MsgChannelOpenAck};
+use crate::ics04_channel::msgs::chan_open_confirm::{self, MsgChannelOpenConfirm};
+use crate::ics04_channel::msgs::chan_open_init::{self, MsgChannelOpenInit};
+use crate::ics04_channel::msgs::chan_open_try::{self, MsgChannelOpenTry};
+use crate::ics04_channel::msgs::recv_packet::{self, MsgRecvPacket};
+use crate::ics04_channel::msgs::timeout::{self, MsgTimeout};
+use crate::ics04_channel::msgs::timeout_on_close::{self, MsgTimeoutOnClose};
+use crate::ics04_channel::packet::Sequence;
+use crate::ics05_port::capabilities::ModuleId;
+use crate::ics23_commitment::commitment::CommitmentPrefix;
+use crate::ics24_host::identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId};
+use crate::ics26_routing::handler::deliver;
+use crate::mock::context::MockContext;
+use crate::mock::host::HostType;
+use crate::tx_msg::Msg;
+use crate::Height;
+
+/// The type URLs of all the messages which the handlers decode.
+const TYPE_URLS: [&str; 19] = [
+    create_client::TYPE_URL,
+    update_client::TYPE_URL,
+    upgrade_client::TYPE_URL,
+    misbehavior::TYPE_URL,
+    conn_open_init::TYPE_URL,
+    conn_open_try::TYPE_URL,
+    conn_open_ack::TYPE_URL,
+    conn_open_confirm::TYPE_URL,
+    chan_open_init::TYPE_URL,
+    chan_open_try::TYPE_URL,
+    chan_open_ack::TYPE_URL,
+    chan_open_confirm::TYPE_URL,
+    chan_close_init::TYPE_URL,
+    chan_close_confirm::TYPE_URL,
+    transfer::TYPE_URL,
+    recv_packet::TYPE_URL,
+    acknowledgement::TYPE_URL,
+    timeout::TYPE_URL,
+    timeout_on_close::TYPE_URL,
+];
+
+/// The ports which the generated identifiers refer to (see `PortId`).
+const PORTS: [&str; 2] = ["defaultPort", "transfer"];
+
+/// A message for the handlers, generated as a domain type or as raw bytes.
+#[derive(Clone, Debug, arbitrary::Arbitrary)]
+pub enum FuzzMsg {
+    CreateClient(MsgCreateAnyClient),
+    UpdateClient(MsgUpdateAnyClient),
+    UpgradeClient(MsgUpgradeAnyClient),
+    SubmitMisbehaviour(MsgSubmitAnyMisbehaviour),
+    ConnectionOpenInit(MsgConnectionOpenInit),
+    ConnectionOpenTry(Box<MsgConnectionOpenTry>),
+    ConnectionOpenAck(Box<MsgConnectionOpenAck>),
+    ConnectionOpenConfirm(MsgConnectionOpenConfirm),
+    ChannelOpenInit(MsgChannelOpenInit),
+    ChannelOpenTry(MsgChannelOpenTry),
+    ChannelOpenAck(MsgChannelOpenAck),
+    ChannelOpenConfirm(MsgChannelOpenConfirm),
+    ChannelCloseInit(MsgChannelCloseInit),
+    ChannelCloseConfirm(MsgChannelCloseConfirm),
+    RecvPacket(MsgRecvPacket),
+    Acknowledgement(MsgAcknowledgement),
+    Timeout(MsgTimeout),
+    TimeoutOnClose(MsgTimeoutOnClose),
+    Transfer(MsgTransfer),
+    Raw(RawMsg),
+}
+
+impl FuzzMsg {
+    /// Encodes this message, as the handlers receive it.
+    pub fn into_any(self) -> Any {
+        match self {
+            Self::CreateClient(msg) => msg.to_any(),
+            Self::UpdateClient(msg) => msg.to_any(),
+            Self::UpgradeClient(msg) => msg.to_any(),
+            Self::SubmitMisbehaviour(msg) => msg.to_any(),
+            Self::ConnectionOpenInit(msg) => msg.to_any(),
+            Self::ConnectionOpenTry(msg) => (*msg).to_any(),
+            Self::ConnectionOpenAck(msg) => (*msg).to_any(),
+            Self::ConnectionOpenConfirm(msg) => msg.to_any(),
+            Self::ChannelOpenInit(msg) => msg.to_any(),
+            Self::ChannelOpenTry(msg) => msg.to_any(),
+            Self::ChannelOpenAck(msg) => msg.to_any(),
+            Self::ChannelOpenConfirm(msg) => msg.to_any(),
+            Self::ChannelCloseInit(msg) => msg.to_any(),
+            Self::ChannelCloseConfirm(msg) => msg.to_any(),
+            Self::RecvPacket(msg) => msg.to_any(),
+            Self::Acknowledgement(msg) => msg.to_any(),
+            Self::Timeout(msg) => msg.to_any(),
+            Self::TimeoutOnClose(msg) => msg.to_any(),
+            Self::Transfer(msg) => msg.to_any(),
+            Self::Raw(msg) => msg.into(),
+        }
+    }
+}
+
+/// Arbitrary bytes, mostly under the type URL of a message which the handlers decode.
+#[derive(Clone, Debug)]
+pub struct RawMsg {
+    pub type_url: String,
+    pub value: Vec<u8>,
+}
+
+impl<'a> arbitrary::Arbitrary<'a> for RawMsg {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let type_url = if u.ratio(1, 8)? {
+            u.arbitrary()?
+        } else {
+            u.choose(&TYPE_URLS)?.to_string()
+        };
+
+        Ok(RawMsg {
+            type_url,
+            value: u.arbitrary()?,
+        })
+    }
+}
+
+impl From<RawMsg> for Any {
+    fn from(msg: RawMsg) -> Self {
+        Any {
+            type_url: msg.type_url,
+            value: msg.value,
+        }
+    }
+}
+
+/// The state of the connections and channels of a chain, which the handlers must only move
+/// forward, and all at once for a batch of messages.
+#[derive(Clone, Debug, PartialEq)]
+struct Snapshot {
+    counters: (u64, u64, u64),
+    connections: BTreeMap<u64, ConnectionEnd>,
+    channels: BTreeMap<(String, u64), ChannelEnd>,
+    sequences: BTreeMap<(String, u64), [Option<Sequence>; 3]>,
+}
+
+impl Snapshot {
+    fn of(ctx: &MockContext) -> Self {
+        let counters = (
+            ctx.client_counter(),
+            ctx.connection_counter(),
+            ctx.channel_counter(),
+        );
+
+        let connections = (0..counters.1)
+            .filter_map(|i| {
+                ConnectionReader::connection_end(ctx, &ConnectionId::new(i)).map(|conn| (i, conn))
+            })
+            .collect();
+
+        let mut channels = BTreeMap::new();
+        let mut sequences = BTreeMap::new();
+        for port in PORTS.iter() {
+            for i in 0..counters.2 {
+                let key = (port.parse::<PortId>().unwrap(), ChannelId::new(i));
+                if let Some(chan) = ctx.channel_end(&key) {
+                    channels.insert((port.to_string(), i), chan);
+                }
+                let seqs = [
+                    ctx.get_next_sequence_send(&key),
+                    ctx.get_next_sequence_recv(&key),
+                    ctx.get_next_sequence_ack(&key),
+                ];
+                if seqs.iter().any(Option::is_some) {
+                    sequences.insert((port.to_string(), i), seqs);
+                }
+            }
+        }
+
+        Snapshot {
+            counters,
+            connections,
+            channels,
+            sequences,
+        }
+    }
+
+    /// Checks that `next` is a state of the chain which follows from this one.
+    fn check_progress(&self, next: &Snapshot) -> Result<(), String> {
+        let (clients, connections, channels) = next.counters;
+        if clients < self.counters.0 || connections < self.counters.1 || channels < self.counters.2
+        {
+            return Err(format!(
+                "counters decreased from {:?} to {:?}",
+                self.counters, next.counters
+            ));
+        }
+        for (id, conn) in self.connections.iter() {
+            let next_state = next.connections.get(id).map(|c| c.state().clone());
+            match &next_state {
+                Some(state) if state.clone() as i32 >= conn.state().clone() as i32 => {}
+                _ => {
+                    return Err(format!(
+                        "connection-{} moved from {:?} to {:?}",
+                        id,
+                        conn.state(),
+                        next_state
+                    ))
+                }
+            }
+        }
+        for (key, chan) in self.channels.iter() {
+            let next_state = next.channels.get(key).map(|c| *c.state());
+            match next_state {
+                Some(state) if state as i32 >= *chan.state() as i32 => {}
+                _ => {
+                    return Err(format!(
+                        "channel {:?} moved from {:?} to {:?}",
+                        key,
+                        chan.state(),
+                        next_state
+                    ))
+                }
+            }
+        }
+        for (key, seqs) in self.sequences.iter() {
+            let next_seqs = next.sequences.get(key).copied().unwrap_or([None; 3]);
+            if seqs
+                .iter()
+                .zip(next_seqs.iter())
+                .any(|(seq, next_seq)| seq.is_some() && next_seq < seq)
+            {
+                return Err(format!(
+                    "sequences of channel {:?} moved from {:?} to {:?}",
+                    key, seqs, next_seqs
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A mock chain to deliver arbitrary messages to, and check after every batch. The chain has two
+/// Mock clients, an open connection over the first one, and an open, unordered ICS20 channel over
+/// the connection, bound to the default port. The clients do not verify any proof.
+#[derive(Clone, Debug)]
+pub struct Harness {
+    ctx: MockContext,
+}
+
+impl Harness {
+    pub fn new() -> Self {
+        let client_height = Height::new(1, 5);
+        let client_id = ClientId::new(ClientType::Mock, 0).unwrap();
+        let counterparty_client_id = ClientId::new(ClientType::Mock, 1).unwrap();
+        let conn_id = ConnectionId::new(0);
+        let port_id = PortId::default();
+        let chan_id = ChannelId::new(0);
+
+        let conn_end = ConnectionEnd::new(
+            ConnectionState::Open,
+            client_id.clone(),
+            ConnectionCounterparty::new(
+                counterparty_client_id.clone(),
+                Some(conn_id.clone()),
+                CommitmentPrefix::from(vec![]),
+            ),
+            vec![Version::default()],
+            Duration::from_secs(0),
+        );
+        let chan_end = ChannelEnd::new(
+            ChannelState::Open,
+            Order::Unordered,
+            ChannelCounterparty::new(port_id.clone(), Some(chan_id.clone())),
+            vec![conn_id.clone()],
+            VERSION.to_string(),
+        );
+
+        let mut ctx = MockContext::new(
+            ChainId::new("mockgaia".to_string(), 1),
+            HostType::Mock,
+            5,
+            Height::new(1, 5),
+        )
+        .with_client(&client_id, client_height)
+        .with_client(&counterparty_client_id, client_height)
+        .with_connection(conn_id, conn_end)
+        .with_module(ModuleId::new(MODULE_ID), port_id.clone(), Ics20Module)
+        .with_channel(port_id.clone(), chan_id.clone(), chan_end)
+        .with_send_sequence(port_id.clone(), chan_id.clone(), 1.into())
+        .with_recv_sequence(port_id.clone(), chan_id.clone(), 1.into())
+        .with_ack_sequence(port_id, chan_id, 1.into());
+
+        // The builders do not allocate identifiers, so the seeded objects must be accounted for.
+        ctx.increase_client_counter();
+        ctx.increase_client_counter();
+        ctx.increase_connection_counter();
+        ctx.increase_channel_counter();
+
+        Harness { ctx }
+    }
+
+    pub fn context(&self) -> &MockContext {
+        &self.ctx
+    }
+
+    /// Delivers `msgs` to the chain as one transaction, and returns whether it was accepted.
+    ///
+    /// Panics if the chain is inconsistent afterwards, if a rejected transaction modified it, or
+    /// if an accepted one moved a connection, a channel or a sequence backwards.
+    pub fn deliver(&mut self, msgs: Vec<FuzzMsg>) -> bool {
+        let before = Snapshot::of(&self.ctx);
+        let result = deliver(
+            &mut self.ctx,
+            msgs.into_iter().map(FuzzMsg::into_any).collect(),
+        );
+        let after = Snapshot::of(&self.ctx);
+
+        if let Err(e) = self.ctx.validate() {
+            panic!("inconsistent chain: {}", e);
+        }
+        match result {
+            Ok(_) => {
+                if let Err(e) = before.check_progress(&after) {
+                    panic!("accepted transaction moved backwards: {}", e);
+                }
+                true
+            }
+            Err(_) => {
+                assert_eq!(before, after, "rejected transaction modified the chain");
+                false
+            }
+        }
+    }
+}
+
+impl Default for Harness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arbitrary::{Arbitrary, Unstructured};
+    use test_env_log::test;
+
+    use super::{FuzzMsg, Harness, RawMsg};
+    use crate::application::ics20_fungible_token_transfer::module::VERSION;
+    use crate::ics04_channel::channel::{ChannelEnd, Counterparty, Order, State};
+    use crate::ics04_channel::msgs::chan_close_confirm::MsgChannelCloseConfirm;
+    use crate::ics04_channel::msgs::chan_open_init::MsgChannelOpenInit;
+    use crate::ics18_relayer::context::Ics18Context;
+    use crate::ics24_host::identifier::{ChannelId, ConnectionId, PortId};
+    use crate::ics26_routing::handler::decode;
+    use crate::mock::context::MockContext;
+    use crate::mock::simulator::SimRng;
+    use crate::proofs::Proofs;
+    use crate::Height;
+
+    #[test]
+    fn decode_arbitrary_bytes() {
+        for seed in 0..1000 {
+            let data = SimRng::new(seed).bytes(256);
+            let mut u = Unstructured::new(&data);
+            let msg = RawMsg::arbitrary(&mut u).unwrap();
+
+            // Decoding may fail, but must not panic.
+            let _ = decode(msg.into());
+        }
+    }
+
+    #[test]
+    fn deliver_arbitrary_messages() {
+        let mut accepted = 0;
+        for seed in 0..500 {
+            let data = SimRng::new(seed).bytes(4096);
+            let mut u = Unstructured::new(&data);
+            let mut harness = Harness::new();
+
+            for _ in 0..8 {
+                match Vec::<FuzzMsg>::arbitrary(&mut u) {
+                    Ok(msgs) if !msgs.is_empty() => {
+                        if harness.deliver(msgs) {
+                            accepted += 1;
+                        }
+                    }
+                    _ => break,
+                }
+            }
+        }
+
+        // The messages are not all garbage, or the handlers would not be exercised at all.
+        assert!(accepted > 0, "no transaction was accepted");
+    }
+
+    #[test]
+    fn harness_is_consistent() {
+        let harness = Harness::new();
+        assert!(harness.context().validate().is_ok());
+    }
+
+    #[test]
+    fn close_confirm_on_init_channel() {
+        let mut harness = Harness::new();
+        let signer = MockContext::default().signer();
+        let port_id = PortId::default();
+
+        // Open a second channel, which stays in `Init` as its counterparty is unknown.
+        let channel = ChannelEnd::new(
+            State::Init,
+            Order::Unordered,
+            Counterparty::new(port_id.clone(), None),
+            vec![ConnectionId::new(0)],
+            VERSION.to_string(),
+        );
+        let msg = MsgChannelOpenInit::new(port_id.clone(), channel, signer.clone());
+        assert!(harness.deliver(vec![FuzzMsg::ChannelOpenInit(msg)]));
+
+        // Confirming the closing of the channel is rejected, as the channel has no counterparty
+        // to prove the state of.
+        let msg = MsgChannelCloseConfirm {
+            port_id,
+            channel_id: ChannelId::new(1),
+            proofs: Proofs::new(vec![1].into(), None, None, None, Height::new(1, 5)).unwrap(),
+            signer,
+        };
+        assert!(!harness.deliver(vec![FuzzMsg::ChannelCloseConfirm(msg)]));
+    }
+}
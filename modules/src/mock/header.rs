@@ -18,6 +18,10 @@ use crate::Height;
 pub struct MockHeader {
     pub height: Height,
     pub timestamp: Timestamp,
+
+    /// The commitment root of the state of the chain at this height, if the chain keeps a
+    /// provable store. The raw mock header encodes a missing root as empty bytes.
+    pub root: Option<[u8; 32]>,
 }
 
 impl Protobuf<RawMockHeader> for MockHeader {}
@@ -34,13 +38,24 @@ impl TryFrom<RawMockHeader> for MockHeader {
                 .map_err(|e| error::Kind::InvalidRawHeader.context(e))?,
             timestamp: Timestamp::from_nanoseconds(raw.timestamp)
                 .map_err(|_| error::Kind::InvalidPacketTimestamp)?,
+            root: match raw.root.as_slice() {
+                [] => None,
+                root => Some(root.try_into().map_err(|_| {
+                    error::Kind::InvalidRawHeader
+                        .context(format!("invalid root length {}", root.len()))
+                })?),
+            },
         })
     }
 }
 
 impl From<MockHeader> for RawMockHeader {
     fn from(value: MockHeader) -> Self {
-        value.into()
+        RawMockHeader {
+            height: Some(value.height.into()),
+            timestamp: value.timestamp.as_nanoseconds(),
+            root: value.root.map(|root| root.to_vec()).unwrap_or_default(),
+        }
     }
 }
 
@@ -52,10 +67,24 @@ impl MockHeader {
         Self {
             height,
             timestamp: Default::default(),
+            root: None,
         }
     }
 }
 
+/// Headers are generated without a root, so that the mock clients built from them accept any
+/// proof, like the arbitrary proofs of the generated messages.
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for MockHeader {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(MockHeader {
+            height: u.arbitrary()?,
+            timestamp: u.arbitrary()?,
+            root: None,
+        })
+    }
+}
+
 impl From<MockHeader> for AnyHeader {
     fn from(mh: MockHeader) -> Self {
         Self::Mock(mh)
@@ -68,16 +97,48 @@ impl Header for MockHeader {
     }
 
     fn height(&self) -> Height {
-        todo!()
+        self.height
     }
 
     fn wrap_any(self) -> AnyHeader {
-        todo!()
+        AnyHeader::Mock(self)
     }
 }
 
 impl From<MockHeader> for AnyConsensusState {
     fn from(h: MockHeader) -> Self {
-        AnyConsensusState::Mock(MockConsensusState(h))
+        AnyConsensusState::Mock(MockConsensusState::new(h))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use test_env_log::test;
+
+    use ibc_proto::ibc::mock::Header as RawMockHeader;
+    use tendermint_proto::Protobuf;
+
+    use super::MockHeader;
+    use crate::Height;
+
+    #[test]
+    fn root_survives_encoding() {
+        let header = MockHeader::new(Height::new(0, 5));
+        let decoded = MockHeader::decode_vec(&header.encode_vec().unwrap()).unwrap();
+        assert_eq!(decoded, header);
+
+        let header = MockHeader {
+            root: Some([0xab; 32]),
+            ..header
+        };
+        let decoded = MockHeader::decode_vec(&header.encode_vec().unwrap()).unwrap();
+        assert_eq!(decoded.root, header.root);
+
+        // A root is a 32-byte hash.
+        let mut raw = RawMockHeader::from(header);
+        raw.root.pop();
+        assert!(MockHeader::try_from(raw).is_err());
     }
 }
@@ -1,10 +1,9 @@
 //! Host chain types and methods, used by context mock.
 
-use std::convert::TryFrom;
+use std::time::Duration;
 
-use tendermint::chain::Id as TMChainId;
 use tendermint_testgen::light_block::TmLightBlock;
-use tendermint_testgen::{Generator, LightBlock as TestgenLightBlock};
+use tendermint_testgen::{Commit as TestgenCommit, Generator, LightBlock as TestgenLightBlock};
 
 use crate::ics02_client::client_consensus::AnyConsensusState;
 use crate::ics02_client::header::AnyHeader;
@@ -15,6 +14,12 @@ use crate::mock::header::MockHeader;
 use crate::timestamp::Timestamp;
 use crate::Height;
 
+/// The unbonding period of the synthetic Tendermint host chains.
+pub const TENDERMINT_HOST_UNBONDING_PERIOD: Duration = Duration::from_secs(128000);
+
+/// The upgrade path under which the synthetic Tendermint host chains store their upgraded states.
+pub const TENDERMINT_HOST_UPGRADE_PATH: &[&str] = &["upgrade", "upgradedIBCState"];
+
 /// Defines the different types of host chains that a mock context can emulate.
 /// The variants are as follows:
 /// - `Mock` defines that the context history consists of `MockHeader` blocks.
@@ -52,6 +57,7 @@ impl HostBlock {
             HostType::Mock => HostBlock::Mock(MockHeader {
                 height: Height::new(chain_id.version(), height),
                 timestamp: Timestamp::from_nanoseconds(1).unwrap(),
+                root: None,
             }),
             HostType::SyntheticTendermint => {
                 HostBlock::SyntheticTendermint(Box::new(Self::generate_tm_block(chain_id, height)))
@@ -60,10 +66,19 @@ impl HostBlock {
     }
 
     pub fn generate_tm_block(chain_id: ChainId, height: u64) -> TmLightBlock {
-        let mut block = TestgenLightBlock::new_default(height).generate().unwrap();
-        block.signed_header.header.chain_id = TMChainId::try_from(chain_id.to_string()).unwrap();
+        let mut light_block = TestgenLightBlock::new_default(height);
+
+        // The chain identifier is part of the signed header, so it must be set before the commit
+        // is generated for the signatures to be valid.
+        let header = light_block
+            .header
+            .take()
+            .unwrap()
+            .chain_id(chain_id.as_str());
+        light_block.commit = Some(TestgenCommit::new(header.clone(), 1));
+        light_block.header = Some(header);
 
-        block
+        light_block.generate().unwrap()
     }
 }
 
@@ -11,6 +11,7 @@ use crate::mock::header::MockHeader;
 use crate::Height;
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Misbehaviour {
     pub client_id: ClientId,
     pub header1: MockHeader,
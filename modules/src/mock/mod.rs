@@ -3,6 +3,10 @@
 pub mod client_def;
 pub mod client_state;
 pub mod context;
+#[cfg(feature = "arbitrary")]
+pub mod fuzz;
 pub mod header;
 pub mod host;
 pub mod misbehaviour;
+pub mod simulator;
+pub mod store;
@@ -0,0 +1,1115 @@
+//! A deterministic simulator of a network of mock chains, connected by an in-process relayer.
+//!
+//! The chains of the network advance together on a simulated clock, producing one block per
+//! round. In every round, the relayer derives the datagrams to relay from the state of the chains,
+//! and the network delivers them subject to the injected faults: datagrams can be dropped,
+//! duplicated or reordered, links can stall past the timeouts of their packets, chains can
+//! equivocate and clients can expire. All the randomness derives from a seed, so that a scenario
+//! replays identically, and the simulator checks protocol invariants over the whole network.
+//!
+//! The chains keep provable stores, so that the clients of the network verify all the proofs
+//! which the relayer submits. Tokens are transferred over unordered ICS20 channels, bound to the
+//! default port of every chain.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::time::Duration;
+
+use crate::application::ics20_fungible_token_transfer::coin::Coin;
+use crate::application::ics20_fungible_token_transfer::context::BankKeeper;
+use crate::application::ics20_fungible_token_transfer::denom::DenomTrace;
+use crate::application::ics20_fungible_token_transfer::module::{Ics20Module, MODULE_ID, VERSION};
+use crate::application::ics20_fungible_token_transfer::msgs::transfer::MsgTransfer;
+use crate::application::ics20_fungible_token_transfer::packet::FungibleTokenPacketData;
+use crate::events::IbcEvent;
+use crate::ics02_client::client_consensus::AnyConsensusState;
+use crate::ics02_client::client_state::AnyClientState;
+use crate::ics02_client::header::AnyHeader;
+use crate::ics02_client::misbehaviour::AnyMisbehaviour;
+use crate::ics02_client::msgs::create_client::MsgCreateAnyClient;
+use crate::ics02_client::msgs::misbehavior::MsgSubmitAnyMisbehaviour;
+use crate::ics02_client::msgs::ClientMsg;
+use crate::ics03_connection::connection::Counterparty as ConnectionCounterparty;
+use crate::ics03_connection::msgs::conn_open_init::MsgConnectionOpenInit;
+use crate::ics03_connection::msgs::ConnectionMsg;
+use crate::ics03_connection::version::Version;
+use crate::ics04_channel::acknowledgement::Acknowledgement;
+use crate::ics04_channel::channel::{
+    ChannelEnd, Counterparty as ChannelCounterparty, Order, State,
+};
+use crate::ics04_channel::context::ChannelReader;
+use crate::ics04_channel::msgs::chan_open_init::MsgChannelOpenInit;
+use crate::ics04_channel::msgs::ChannelMsg;
+use crate::ics04_channel::packet::{Packet, Sequence};
+use crate::ics05_port::capabilities::ModuleId;
+use crate::ics18_relayer::context::Ics18Context;
+use crate::ics18_relayer::error::{Error as Ics18Error, Kind as Ics18ErrorKind};
+use crate::ics18_relayer::utils::{
+    build_ack_packet, build_chan_open_ack, build_chan_open_confirm, build_chan_open_try,
+    build_client_update_datagram, build_conn_open_ack, build_conn_open_confirm,
+    build_conn_open_try, build_recv_packet, build_timeout_packet,
+};
+use crate::ics24_host::identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId};
+use crate::ics26_routing::handler::dispatch;
+use crate::ics26_routing::msgs::Ics26Envelope;
+use crate::mock::client_state::{MockClientState, MockConsensusState};
+use crate::mock::context::MockContext;
+use crate::mock::header::MockHeader;
+use crate::mock::host::HostType;
+use crate::mock::misbehaviour::Misbehaviour;
+use crate::signer::Signer;
+use crate::timestamp::{Expiry, Timestamp};
+use crate::Height;
+
+/// The number of blocks which the chains of the simulated network keep in their history.
+pub const SIMULATOR_HISTORY_SIZE: usize = 100;
+
+/// The time between two blocks of the simulated network, unless set otherwise.
+pub const DEFAULT_BLOCK_TIME: Duration = Duration::from_secs(5);
+
+/// The time at which the simulated network starts, in nanoseconds since the Unix epoch.
+const GENESIS_TIME: u64 = 1_600_000_000_000_000_000;
+
+/// Identifies a packet by its source chain (i.e., the position of the chain in the network), and
+/// by its source port, source channel and sequence.
+type PacketKey = (usize, PortId, ChannelId, Sequence);
+
+/// The faults which the network injects into the delivery of the datagrams, at random.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Faults {
+    /// The probability, in percent, that a datagram is lost in transit.
+    pub drop_percent: u8,
+
+    /// The probability, in percent, that a datagram is delivered twice.
+    pub duplicate_percent: u8,
+
+    /// Whether the datagrams relayed in one round are delivered in a random order.
+    pub reorder: bool,
+}
+
+/// Counters of the datagrams relayed over the simulated network.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// Messages which the destination chain processed successfully.
+    pub delivered: u64,
+
+    /// Messages which the destination chain rejected.
+    pub rejected: u64,
+
+    /// Datagrams lost in transit.
+    pub dropped: u64,
+
+    /// Datagrams delivered twice.
+    pub duplicated: u64,
+}
+
+/// One end of a link, namely a chain of the network, together with its client of the chain at
+/// the other end, and its connection and ICS20 channel to that chain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LinkEnd {
+    pub chain: usize,
+    pub client_id: ClientId,
+    pub connection_id: ConnectionId,
+    pub port_id: PortId,
+    pub channel_id: ChannelId,
+}
+
+/// A pair of chains connected by an open channel, over which the relayer relays packets.
+#[derive(Clone, Debug)]
+pub struct Link {
+    pub a: LinkEnd,
+    pub b: LinkEnd,
+
+    /// The round until which the relayer does not relay over this link.
+    stalled_until: u64,
+}
+
+impl Link {
+    /// Returns the end of this link on `chain`, and the end on the other chain.
+    fn ends(&self, chain: usize) -> (&LinkEnd, &LinkEnd) {
+        if self.a.chain == chain {
+            (&self.a, &self.b)
+        } else if self.b.chain == chain {
+            (&self.b, &self.a)
+        } else {
+            panic!("chain {} is not an end of the link", chain)
+        }
+    }
+}
+
+/// A packet sent over a link, as known to the relayer.
+#[derive(Clone, Debug)]
+struct SentPacket {
+    link: usize,
+    dest: usize,
+    packet: Packet,
+}
+
+/// The ways in which the source chain closes a packet, removing its commitment.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Outcome {
+    Acknowledged,
+    TimedOut,
+}
+
+/// Messages to be delivered, in order, to chain `dest`.
+#[derive(Clone, Debug)]
+struct Datagram {
+    dest: usize,
+    msgs: Vec<Ics26Envelope>,
+}
+
+/// A pseudo-random generator (SplitMix64), such that scenarios are reproducible from a seed.
+#[derive(Clone, Debug)]
+pub(crate) struct SimRng(u64);
+
+impl SimRng {
+    pub(crate) fn new(seed: u64) -> Self {
+        SimRng(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns true with a probability of `percent` percent.
+    fn chance(&mut self, percent: u8) -> bool {
+        self.next_u64() % 100 < u64::from(percent)
+    }
+
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = (self.next_u64() % (i as u64 + 1)) as usize;
+            items.swap(i, j);
+        }
+    }
+
+    /// Returns `len` pseudo-random bytes, the input of the fuzzing tests.
+    #[cfg(all(test, feature = "arbitrary"))]
+    pub(crate) fn bytes(&mut self, len: usize) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(len + 8);
+        while bytes.len() < len {
+            bytes.extend_from_slice(&self.next_u64().to_le_bytes());
+        }
+        bytes.truncate(len);
+        bytes
+    }
+}
+
+/// A network of mock chains, advancing in rounds on a simulated clock, and connected by links
+/// over which an in-process relayer relays packets, subject to the injected faults.
+#[derive(Clone, Debug)]
+pub struct Simulator {
+    chains: Vec<MockContext>,
+    links: Vec<Link>,
+    faults: Faults,
+    rng: SimRng,
+
+    /// The simulated clock, in nanoseconds since the Unix epoch.
+    now: u64,
+    block_time: Duration,
+    round: u64,
+
+    /// The trusting period of the clients created by `connect`, if they can expire at all.
+    trusting_period: Option<Duration>,
+
+    /// The total amount of each native denomination of each chain, which transfers conserve.
+    supplies: BTreeMap<(usize, String), u64>,
+
+    /// All the packets sent over the links, and the acknowledgements written for them.
+    packets: BTreeMap<PacketKey, SentPacket>,
+    acks: BTreeMap<PacketKey, Vec<u8>>,
+
+    /// How many times each packet was received, and closed on its source chain.
+    receipts: BTreeMap<PacketKey, u64>,
+    outcomes: BTreeMap<PacketKey, Vec<Outcome>>,
+
+    stats: Stats,
+}
+
+impl Simulator {
+    /// Creates a network of `chain_count` Mock chains, not connected to one another. The faults
+    /// injected into the network are drawn from a generator seeded with `seed`.
+    pub fn new(seed: u64, chain_count: usize) -> Self {
+        let genesis = Timestamp::from_nanoseconds(GENESIS_TIME).unwrap();
+        let chains = (0..chain_count)
+            .map(|i| {
+                let mut chain = MockContext::new(
+                    ChainId::new(format!("simchain{}", i), 1),
+                    HostType::Mock,
+                    SIMULATOR_HISTORY_SIZE,
+                    Height::new(1, 1),
+                )
+                .with_provable_store()
+                .with_module(
+                    ModuleId::new(MODULE_ID),
+                    PortId::default(),
+                    Ics20Module,
+                );
+                chain.advance_host_chain_height_at(genesis);
+                chain
+            })
+            .collect();
+
+        Simulator {
+            chains,
+            links: vec![],
+            faults: Faults::default(),
+            rng: SimRng::new(seed),
+            now: GENESIS_TIME,
+            block_time: DEFAULT_BLOCK_TIME,
+            round: 0,
+            trusting_period: None,
+            supplies: BTreeMap::new(),
+            packets: BTreeMap::new(),
+            acks: BTreeMap::new(),
+            receipts: BTreeMap::new(),
+            outcomes: BTreeMap::new(),
+            stats: Stats::default(),
+        }
+    }
+
+    pub fn with_faults(self, faults: Faults) -> Self {
+        Self { faults, ..self }
+    }
+
+    pub fn with_block_time(self, block_time: Duration) -> Self {
+        Self { block_time, ..self }
+    }
+
+    /// The clients which `connect` creates afterwards expire once they are not updated for
+    /// longer than `trusting_period`.
+    pub fn with_trusting_period(self, trusting_period: Duration) -> Self {
+        Self {
+            trusting_period: Some(trusting_period),
+            ..self
+        }
+    }
+
+    /// Credits `coin`, in a denomination native to chain `chain`, to the balance of `account`.
+    pub fn with_balance(mut self, chain: usize, account: &Signer, coin: Coin) -> Self {
+        *self
+            .supplies
+            .entry((chain, coin.denom.clone()))
+            .or_default() += coin.amount;
+        self.chains[chain] = self.chains[chain].clone().with_balance(account, coin);
+        self
+    }
+
+    pub fn chain(&self, chain: usize) -> &MockContext {
+        &self.chains[chain]
+    }
+
+    pub fn link(&self, link: usize) -> &Link {
+        &self.links[link]
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    /// Changes the faults injected into the rounds to come.
+    pub fn set_faults(&mut self, faults: Faults) {
+        self.faults = faults;
+    }
+
+    /// Connects chains `a` and `b`, by creating a client of each chain on the other one, then
+    /// opening a connection and an ICS20 channel between them. The handshakes are relayed
+    /// without faults, one message per round. Returns the index of the new link.
+    pub fn connect(&mut self, a: usize, b: usize) -> Result<usize, Ics18Error> {
+        let port_id = PortId::default();
+        let client_a = self.create_client(a, b)?;
+        let client_b = self.create_client(b, a)?;
+
+        let msg = MsgConnectionOpenInit {
+            client_id: client_a.clone(),
+            counterparty: ConnectionCounterparty::new(
+                client_b.clone(),
+                None,
+                self.chains[b].query_commitment_prefix(),
+            ),
+            version: Version::default(),
+            delay_period: Duration::from_secs(0),
+            signer: self.chains[a].signer(),
+        };
+        let events = self.execute(
+            a,
+            Ics26Envelope::Ics3Msg(ConnectionMsg::ConnectionOpenInit(msg)),
+        )?;
+        let conn_a = find_event(events, |event| match event {
+            IbcEvent::OpenInitConnection(ev) => ev.connection_id().clone(),
+            _ => None,
+        })?;
+
+        let msg = build_conn_open_try(&self.chains[b], &self.chains[a], &conn_a)?;
+        let events = self.relay_now(b, &client_b, a, Ics26Envelope::Ics3Msg(msg))?;
+        let conn_b = find_event(events, |event| match event {
+            IbcEvent::OpenTryConnection(ev) => ev.connection_id().clone(),
+            _ => None,
+        })?;
+
+        let msg = build_conn_open_ack(&self.chains[a], &self.chains[b], &conn_b)?;
+        self.relay_now(a, &client_a, b, Ics26Envelope::Ics3Msg(msg))?;
+        let msg = build_conn_open_confirm(&self.chains[b], &self.chains[a], &conn_a)?;
+        self.relay_now(b, &client_b, a, Ics26Envelope::Ics3Msg(msg))?;
+
+        let channel = ChannelEnd::new(
+            State::Init,
+            Order::Unordered,
+            ChannelCounterparty::new(port_id.clone(), None),
+            vec![conn_a.clone()],
+            VERSION.to_string(),
+        );
+        let msg = MsgChannelOpenInit::new(port_id.clone(), channel, self.chains[a].signer());
+        let events = self.execute(
+            a,
+            Ics26Envelope::Ics4ChannelMsg(ChannelMsg::ChannelOpenInit(msg)),
+        )?;
+        let chan_a = find_event(events, |event| match event {
+            IbcEvent::OpenInitChannel(ev) => ev.channel_id().cloned(),
+            _ => None,
+        })?;
+
+        let msg = build_chan_open_try(&self.chains[b], &self.chains[a], &port_id, &chan_a)?;
+        let events = self.relay_now(b, &client_b, a, Ics26Envelope::Ics4ChannelMsg(msg))?;
+        let chan_b = find_event(events, |event| match event {
+            IbcEvent::OpenTryChannel(ev) => ev.channel_id().cloned(),
+            _ => None,
+        })?;
+
+        let msg = build_chan_open_ack(&self.chains[a], &self.chains[b], &port_id, &chan_b)?;
+        self.relay_now(a, &client_a, b, Ics26Envelope::Ics4ChannelMsg(msg))?;
+        let msg = build_chan_open_confirm(&self.chains[b], &self.chains[a], &port_id, &chan_a)?;
+        self.relay_now(b, &client_b, a, Ics26Envelope::Ics4ChannelMsg(msg))?;
+
+        self.links.push(Link {
+            a: LinkEnd {
+                chain: a,
+                client_id: client_a,
+                connection_id: conn_a,
+                port_id: port_id.clone(),
+                channel_id: chan_a,
+            },
+            b: LinkEnd {
+                chain: b,
+                client_id: client_b,
+                connection_id: conn_b,
+                port_id,
+                channel_id: chan_b,
+            },
+            stalled_until: 0,
+        });
+        Ok(self.links.len() - 1)
+    }
+
+    /// Transfers `coin` from `sender` on chain `source` to `receiver` on the other end of `link`.
+    /// The packet times out once the destination chain is `timeout_blocks` blocks higher than it
+    /// is now. The transfer is processed in the current round, and relayed from the next one on.
+    pub fn transfer(
+        &mut self,
+        link: usize,
+        source: usize,
+        sender: &Signer,
+        receiver: &Signer,
+        coin: Coin,
+        timeout_blocks: u64,
+    ) -> Result<Packet, Ics18Error> {
+        let (end, counterparty) = self.links[link].ends(source);
+        let msg = MsgTransfer {
+            source_port: end.port_id.clone(),
+            source_channel: end.channel_id.clone(),
+            token: Some(coin.into()),
+            sender: sender.clone(),
+            receiver: receiver.clone(),
+            timeout_height: self.chains[counterparty.chain]
+                .query_latest_height()
+                .add(timeout_blocks),
+            timeout_timestamp: Timestamp::none(),
+        };
+
+        let events = self.submit(source, Ics26Envelope::Ics20Msg(msg))?;
+        find_event(events, |event| match event {
+            IbcEvent::SendPacket(ev) => Some(ev.packet.clone()),
+            _ => None,
+        })
+    }
+
+    /// Runs `rounds` rounds of the network, checking the invariants after each one.
+    pub fn run(&mut self, rounds: u64) -> Result<(), Box<dyn Error>> {
+        for _ in 0..rounds {
+            self.step();
+            self.check_invariants()?;
+        }
+        Ok(())
+    }
+
+    /// Runs one round of the network: all the chains produce a block, committing to their state,
+    /// then the relayer relays what the new state calls for, and the network delivers it.
+    pub fn step(&mut self) {
+        self.produce_blocks();
+
+        let mut datagrams = vec![];
+        for datagram in self.pending_datagrams() {
+            if self.rng.chance(self.faults.drop_percent) {
+                self.stats.dropped += 1;
+                continue;
+            }
+            if self.rng.chance(self.faults.duplicate_percent) {
+                self.stats.duplicated += 1;
+                datagrams.push(datagram.clone());
+            }
+            datagrams.push(datagram);
+        }
+        if self.faults.reorder {
+            self.rng.shuffle(&mut datagrams);
+        }
+
+        for datagram in datagrams {
+            for msg in datagram.msgs {
+                // Rejected messages are expected under faults; the relayer retries what is still
+                // pending in the next round.
+                match self.submit(datagram.dest, msg) {
+                    Ok(_) => self.stats.delivered += 1,
+                    Err(_) => self.stats.rejected += 1,
+                }
+            }
+        }
+    }
+
+    /// Stops relaying over `link` for the next `rounds` rounds, e.g. until its packets time out.
+    pub fn stall(&mut self, link: usize, rounds: u64) {
+        self.links[link].stalled_until = self.round + rounds;
+    }
+
+    /// Moves the clock `duration` forward, such that the next blocks are produced that much later,
+    /// e.g. to let the clients of the network expire.
+    pub fn advance_clock(&mut self, duration: Duration) {
+        self.now += duration.as_nanos() as u64;
+    }
+
+    /// Makes chain `chain` equivocate, by forking its latest header, and submits both headers as
+    /// evidence of misbehaviour to the client of `chain` at the other end of `link`, which
+    /// freezes it.
+    pub fn fork(&mut self, link: usize, chain: usize) -> Result<(), Ics18Error> {
+        let (_, counterparty) = self.links[link].ends(chain);
+        let (dest, client_id) = (counterparty.chain, counterparty.client_id.clone());
+
+        let header = match self.chains[chain].query_latest_header() {
+            Some(AnyHeader::Mock(header)) => header,
+            _ => panic!("chain {} has no mock header", chain),
+        };
+        let forked_header = MockHeader {
+            timestamp: Timestamp::from_nanoseconds(header.timestamp.as_nanoseconds() + 1).unwrap(),
+            root: Some([0xff; 32]),
+            ..header
+        };
+
+        let msg = MsgSubmitAnyMisbehaviour {
+            client_id: client_id.clone(),
+            misbehaviour: AnyMisbehaviour::Mock(Misbehaviour {
+                client_id,
+                header1: header,
+                header2: forked_header,
+            }),
+            signer: self.chains[dest].signer(),
+        };
+        self.submit(dest, Ics26Envelope::Ics2Msg(ClientMsg::Misbehaviour(msg)))?;
+        Ok(())
+    }
+
+    /// Checks the protocol invariants over the whole network, namely that
+    ///  - no packet is received more than once,
+    ///  - the commitment of a packet is removed exactly when the packet is acknowledged or times
+    ///    out, and a packet is acknowledged only if it was received, and times out only if not,
+    ///  - transfers conserve the supply of every native denomination, and the tokens escrowed
+    ///    for a channel back the vouchers minted on its counterparty, or are in flight.
+    pub fn check_invariants(&self) -> Result<(), Box<dyn Error>> {
+        for (key, sent) in self.packets.iter() {
+            let packet = &sent.packet;
+            let receipts = self.receipts.get(key).copied().unwrap_or_default();
+            if receipts > 1 {
+                return Err(format!("packet {:?} was received {} times", key, receipts).into());
+            }
+
+            let outcomes = self.outcomes.get(key).map_or(&[][..], Vec::as_slice);
+            if outcomes.len() > 1 {
+                return Err(format!("packet {:?} was closed twice: {:?}", key, outcomes).into());
+            }
+
+            let committed = self.chains[key.0]
+                .get_packet_commitment(&(key.1.clone(), key.2.clone(), key.3))
+                .is_some();
+            if committed != outcomes.is_empty() {
+                return Err(format!(
+                    "commitment of packet {:?} is inconsistent with its outcome {:?}",
+                    key, outcomes
+                )
+                .into());
+            }
+
+            let dest = &self.chains[sent.dest];
+            let dest_key = (
+                packet.destination_port.clone(),
+                packet.destination_channel.clone(),
+                packet.sequence,
+            );
+            let received = dest.get_packet_receipt(&dest_key).is_some();
+            if received != dest.get_packet_acknowledgement(&dest_key).is_some() {
+                return Err(format!(
+                    "packet {:?} has a receipt without an ack or vice versa",
+                    key
+                )
+                .into());
+            }
+            match outcomes.first() {
+                Some(Outcome::Acknowledged) if !received => {
+                    return Err(
+                        format!("packet {:?} was acknowledged but never received", key).into(),
+                    );
+                }
+                Some(Outcome::TimedOut) if received => {
+                    return Err(format!("packet {:?} timed out but was received", key).into());
+                }
+                _ => {}
+            }
+        }
+
+        for ((chain, denom), supply) in self.supplies.iter() {
+            let total = self.chains[*chain].total_supply(denom);
+            if total != *supply {
+                return Err(format!(
+                    "supply of {} on chain {} changed from {} to {}",
+                    denom, chain, supply, total
+                )
+                .into());
+            }
+        }
+
+        for (index, link) in self.links.iter().enumerate() {
+            for (native, remote) in [(&link.a, &link.b), (&link.b, &link.a)].iter() {
+                let denoms = self
+                    .supplies
+                    .keys()
+                    .filter(|(chain, _)| *chain == native.chain)
+                    .map(|(_, denom)| denom);
+                for denom in denoms {
+                    let escrow = self.chains[native.chain]
+                        .get_escrow_address(&native.port_id, &native.channel_id);
+                    let escrowed = self.chains[native.chain].balance(&escrow, denom);
+                    let voucher =
+                        DenomTrace::new(format!("{}/{}", remote.port_id, remote.channel_id), denom);
+                    let vouchers = self.chains[remote.chain].total_supply(&voucher.ibc_denom());
+                    let in_flight = self.in_flight(index, native, remote, denom);
+
+                    if escrowed != vouchers + in_flight {
+                        return Err(format!(
+                            "{} {} escrowed on chain {} back {} vouchers and {} in flight",
+                            escrowed, denom, native.chain, vouchers, in_flight
+                        )
+                        .into());
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the amount of the native denomination `denom` of `native` which is in flight over
+    /// `link`, i.e., carried by packets which are still committed on their source chain, but
+    /// were not credited on their destination. Only tokens which take a single hop are tracked.
+    fn in_flight(&self, link: usize, native: &LinkEnd, remote: &LinkEnd, denom: &str) -> u64 {
+        let returning = format!("{}/{}/{}", remote.port_id, remote.channel_id, denom);
+        self.packets
+            .iter()
+            .filter(|(_, sent)| sent.link == link)
+            .filter(|(key, _)| {
+                self.chains[key.0]
+                    .get_packet_commitment(&(key.1.clone(), key.2.clone(), key.3))
+                    .is_some()
+            })
+            .filter(|(key, _)| {
+                !self
+                    .acks
+                    .get(*key)
+                    .and_then(|ack| Acknowledgement::from_bytes(ack).ok())
+                    .map_or(false, |ack| ack.is_success())
+            })
+            .filter_map(|(key, sent)| {
+                let data = FungibleTokenPacketData::from_bytes(&sent.packet.data).ok()?;
+                let carries_denom = if key.0 == native.chain {
+                    data.denom == denom
+                } else {
+                    data.denom == returning
+                };
+                Some(data.amount).filter(|_| carries_denom)
+            })
+            .sum()
+    }
+
+    /// Creates a client of chain `tracked` on chain `host`, from the latest header of `tracked`.
+    fn create_client(&mut self, host: usize, tracked: usize) -> Result<ClientId, Ics18Error> {
+        let header = match self.chains[tracked].query_latest_header() {
+            Some(AnyHeader::Mock(header)) => header,
+            _ => panic!("chain {} has no mock header", tracked),
+        };
+        let mut client_state = MockClientState::new(header);
+        if let Some(trusting_period) = self.trusting_period {
+            client_state = client_state.with_trusting_period(trusting_period);
+        }
+
+        let msg = MsgCreateAnyClient::new(
+            AnyClientState::Mock(client_state),
+            AnyConsensusState::Mock(MockConsensusState::new(header)),
+            self.chains[host].signer(),
+        )
+        .map_err(|e| Ics18ErrorKind::TransactionFailed.context(e))?;
+        let events = self.execute(host, Ics26Envelope::Ics2Msg(ClientMsg::CreateClient(msg)))?;
+        find_event(events, |event| match event {
+            IbcEvent::CreateClient(ev) => Some(ev.client_id().clone()),
+            _ => None,
+        })
+    }
+
+    /// Returns the datagrams which the relayer relays in the current round. For every packet
+    /// still committed on its source chain, this is either its acknowledgement, its timeout or
+    /// the packet itself, preceded by an update of the client which verifies the proof, if the
+    /// client is behind.
+    fn pending_datagrams(&self) -> Vec<Datagram> {
+        let mut updated_clients = BTreeSet::new();
+        let mut datagrams = vec![];
+
+        for (key, sent) in self.packets.iter() {
+            let link = &self.links[sent.link];
+            if self.round < link.stalled_until {
+                continue;
+            }
+
+            let (source, dest) = (&self.chains[key.0], &self.chains[sent.dest]);
+            let packet = sent.packet.clone();
+            if source
+                .get_packet_commitment(&(key.1.clone(), key.2.clone(), key.3))
+                .is_none()
+            {
+                continue;
+            }
+            let dest_key = (
+                packet.destination_port.clone(),
+                packet.destination_channel.clone(),
+                packet.sequence,
+            );
+
+            let (target, origin, msg) = if let Some(ack) = self.acks.get(key) {
+                let msg = build_ack_packet(source, dest, packet, ack.clone());
+                (key.0, sent.dest, msg)
+            } else if dest.get_packet_receipt(&dest_key).is_some() {
+                continue;
+            } else if timed_out(dest, &packet) {
+                (key.0, sent.dest, build_timeout_packet(source, dest, packet))
+            } else {
+                (sent.dest, key.0, build_recv_packet(dest, source, packet))
+            };
+            let msg = match msg {
+                Ok(msg) => msg,
+                Err(_) => continue,
+            };
+
+            let client_id = &link.ends(target).0.client_id;
+            let mut msgs = vec![];
+            if updated_clients.insert((target, client_id.clone())) {
+                msgs.extend(self.client_update(target, client_id, origin));
+            }
+            msgs.push(Ics26Envelope::Ics4PacketMsg(msg));
+            datagrams.push(Datagram { dest: target, msgs });
+        }
+
+        datagrams
+    }
+
+    /// Returns the update of the client `client_id` on chain `dest` to the latest header of chain
+    /// `src`, unless the client is already at that height or higher.
+    fn client_update(
+        &self,
+        dest: usize,
+        client_id: &ClientId,
+        src: usize,
+    ) -> Option<Ics26Envelope> {
+        let header = self.chains[src].query_latest_header()?;
+        build_client_update_datagram(&self.chains[dest], client_id, header)
+            .ok()
+            .map(Ics26Envelope::Ics2Msg)
+    }
+
+    /// Delivers `msg`, built from the state of chain `src` at its latest height, to chain `dest`
+    /// in a new round, after updating the client `client_id` of `src` on `dest` if need be.
+    fn relay_now(
+        &mut self,
+        dest: usize,
+        client_id: &ClientId,
+        src: usize,
+        msg: Ics26Envelope,
+    ) -> Result<Vec<IbcEvent>, Ics18Error> {
+        if let Some(update) = self.client_update(dest, client_id, src) {
+            self.submit(dest, update)?;
+        }
+        self.execute(dest, msg)
+    }
+
+    /// Delivers `msg` to chain `chain`, then produces the blocks of a new round.
+    fn execute(&mut self, chain: usize, msg: Ics26Envelope) -> Result<Vec<IbcEvent>, Ics18Error> {
+        let events = self.submit(chain, msg)?;
+        self.produce_blocks();
+        Ok(events)
+    }
+
+    /// Delivers `msg` to chain `chain` in the current round, and records the packets and
+    /// acknowledgements it gives rise to.
+    fn submit(&mut self, chain: usize, msg: Ics26Envelope) -> Result<Vec<IbcEvent>, Ics18Error> {
+        let output = dispatch(&mut self.chains[chain], msg)
+            .map_err(|e| Ics18ErrorKind::TransactionFailed.context(e))?;
+        for event in output.events.iter() {
+            self.record_event(chain, event);
+        }
+        Ok(output.events)
+    }
+
+    fn record_event(&mut self, chain: usize, event: &IbcEvent) {
+        match event {
+            IbcEvent::SendPacket(ev) => {
+                if let Some((link, dest)) =
+                    self.counterparty(chain, &ev.packet.source_port, &ev.packet.source_channel)
+                {
+                    let sent = SentPacket {
+                        link,
+                        dest,
+                        packet: ev.packet.clone(),
+                    };
+                    self.packets.insert(source_key(chain, &ev.packet), sent);
+                }
+            }
+            IbcEvent::ReceivePacket(ev) => {
+                if let Some(key) = self.dest_key(chain, &ev.packet) {
+                    *self.receipts.entry(key).or_default() += 1;
+                }
+            }
+            IbcEvent::WriteAcknowledgement(ev) => {
+                if let Some(key) = self.dest_key(chain, &ev.packet) {
+                    self.acks.insert(key, ev.ack.clone());
+                }
+            }
+            IbcEvent::AcknowledgePacket(ev) => self
+                .outcomes
+                .entry(source_key(chain, &ev.packet))
+                .or_default()
+                .push(Outcome::Acknowledged),
+            IbcEvent::TimeoutPacket(ev) => self
+                .outcomes
+                .entry(source_key(chain, &ev.packet))
+                .or_default()
+                .push(Outcome::TimedOut),
+            _ => {}
+        }
+    }
+
+    /// Returns the link whose end on chain `chain` is the given port and channel, and the chain
+    /// at its other end.
+    fn counterparty(
+        &self,
+        chain: usize,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+    ) -> Option<(usize, usize)> {
+        self.links.iter().enumerate().find_map(|(index, link)| {
+            [(&link.a, &link.b), (&link.b, &link.a)]
+                .iter()
+                .find(|(end, _)| {
+                    end.chain == chain && &end.port_id == port_id && &end.channel_id == channel_id
+                })
+                .map(|(_, counterparty)| (index, counterparty.chain))
+        })
+    }
+
+    /// Returns the key of `packet`, as observed on its destination chain `chain`.
+    fn dest_key(&self, chain: usize, packet: &Packet) -> Option<PacketKey> {
+        let (_, source) =
+            self.counterparty(chain, &packet.destination_port, &packet.destination_channel)?;
+        Some(source_key(source, packet))
+    }
+
+    /// Advances the clock by one block time, and all the chains by one block.
+    fn produce_blocks(&mut self) {
+        self.now += self.block_time.as_nanos() as u64;
+        self.round += 1;
+
+        let timestamp = Timestamp::from_nanoseconds(self.now).unwrap();
+        for chain in self.chains.iter_mut() {
+            chain.advance_host_chain_height_at(timestamp);
+        }
+    }
+}
+
+fn source_key(chain: usize, packet: &Packet) -> PacketKey {
+    (
+        chain,
+        packet.source_port.clone(),
+        packet.source_channel.clone(),
+        packet.sequence,
+    )
+}
+
+/// Returns true if `packet` can no longer be received by chain `dest`, whose latest height and
+/// timestamp passed the timeout of the packet.
+fn timed_out(dest: &MockContext, packet: &Packet) -> bool {
+    let height_passed =
+        !packet.timeout_height.is_zero() && packet.timeout_height <= dest.query_latest_height();
+    let timestamp_passed = matches!(
+        ChannelReader::host_timestamp(dest).check_expiry(&packet.timeout_timestamp),
+        Expiry::Expired
+    );
+    height_passed || timestamp_passed
+}
+
+/// Returns the first value which `f` extracts from `events`, or an error if there is none.
+fn find_event<T>(
+    events: Vec<IbcEvent>,
+    f: impl Fn(&IbcEvent) -> Option<T>,
+) -> Result<T, Ics18Error> {
+    events.iter().find_map(f).ok_or_else(|| {
+        Ics18ErrorKind::TransactionFailed
+            .context("the transaction did not emit the expected event")
+            .into()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::application::ics20_fungible_token_transfer::coin::Coin;
+    use crate::application::ics20_fungible_token_transfer::denom::DenomTrace;
+    use crate::ics02_client::client_state::Status;
+    use crate::ics02_client::context::ClientReader;
+    use crate::ics04_channel::context::ChannelReader;
+    use crate::ics04_channel::packet::Packet;
+    use crate::mock::simulator::{Faults, LinkEnd, Simulator, Stats};
+    use crate::signer::Signer;
+    use test_env_log::test;
+
+    fn voucher_denom(end: &LinkEnd, base_denom: &str) -> String {
+        DenomTrace::new(format!("{}/{}", end.port_id, end.channel_id), base_denom).ibc_denom()
+    }
+
+    fn is_committed(sim: &Simulator, chain: usize, packet: &Packet) -> bool {
+        sim.chain(chain)
+            .get_packet_commitment(&(
+                packet.source_port.clone(),
+                packet.source_channel.clone(),
+                packet.sequence,
+            ))
+            .is_some()
+    }
+
+    /// Runs a scenario of transfers back and forth over two links between three chains, under
+    /// `faults`, then lets the network settle without faults. Returns the network, and the
+    /// packets sent from each chain.
+    fn transfer_scenario(seed: u64, faults: Faults) -> (Simulator, Vec<(usize, Packet)>) {
+        let (alice, bob, carol) = (
+            Signer::new("alice"),
+            Signer::new("bob"),
+            Signer::new("carol"),
+        );
+        let mut sim = Simulator::new(seed, 3)
+            .with_balance(0, &alice, Coin::new("uatom", 1000))
+            .with_balance(1, &bob, Coin::new("ustake", 1000))
+            .with_balance(2, &carol, Coin::new("uosmo", 1000));
+        let link_01 = sim.connect(0, 1).unwrap();
+        let link_12 = sim.connect(1, 2).unwrap();
+        sim.set_faults(faults);
+
+        let mut packets = vec![];
+        for i in 0..8 {
+            // Every other packet has a short timeout, such that faults can make it time out.
+            let timeout_blocks = if i % 2 == 0 { 50 } else { 3 };
+            let transfers = vec![
+                (link_01, 0, &alice, &bob, Coin::new("uatom", 10 + i)),
+                (link_01, 1, &bob, &alice, Coin::new("ustake", 20 + i)),
+                (link_12, 2, &carol, &bob, Coin::new("uosmo", 5 + i)),
+            ];
+            for (link, source, sender, receiver, coin) in transfers {
+                let packet = sim
+                    .transfer(link, source, sender, receiver, coin, timeout_blocks)
+                    .unwrap();
+                packets.push((source, packet));
+            }
+            sim.run(2).unwrap();
+        }
+
+        sim.set_faults(Faults::default());
+        sim.run(10).unwrap();
+        (sim, packets)
+    }
+
+    #[test]
+    fn reliable_network_relays_transfers() {
+        let (alice, bob) = (Signer::new("alice"), Signer::new("bob"));
+        let mut sim = Simulator::new(0, 2)
+            .with_balance(0, &alice, Coin::new("uatom", 100))
+            .with_balance(1, &bob, Coin::new("ustake", 100));
+        let link = sim.connect(0, 1).unwrap();
+        let (end_a, end_b) = (sim.link(link).a.clone(), sim.link(link).b.clone());
+
+        let to_bob = sim
+            .transfer(link, 0, &alice, &bob, Coin::new("uatom", 10), 50)
+            .unwrap();
+        let to_alice = sim
+            .transfer(link, 1, &bob, &alice, Coin::new("ustake", 20), 50)
+            .unwrap();
+        sim.run(3).unwrap();
+
+        assert!(!is_committed(&sim, 0, &to_bob));
+        assert!(!is_committed(&sim, 1, &to_alice));
+        assert_eq!(sim.chain(0).balance(&alice, "uatom"), 90);
+        assert_eq!(
+            sim.chain(1).balance(&bob, &voucher_denom(&end_b, "uatom")),
+            10
+        );
+        assert_eq!(sim.chain(1).balance(&bob, "ustake"), 80);
+        assert_eq!(
+            sim.chain(0)
+                .balance(&alice, &voucher_denom(&end_a, "ustake")),
+            20
+        );
+
+        // Vouchers return to their source chain, which releases the escrowed tokens.
+        let voucher = Coin::new(voucher_denom(&end_b, "uatom"), 4);
+        sim.transfer(link, 1, &bob, &alice, voucher, 50).unwrap();
+        sim.run(3).unwrap();
+
+        assert_eq!(sim.chain(0).balance(&alice, "uatom"), 94);
+        assert_eq!(
+            sim.chain(1).balance(&bob, &voucher_denom(&end_b, "uatom")),
+            6
+        );
+        assert_eq!(sim.stats().rejected, 0);
+    }
+
+    #[test]
+    fn faulty_networks_preserve_invariants() {
+        let faults = Faults {
+            drop_percent: 30,
+            duplicate_percent: 20,
+            reorder: true,
+        };
+
+        let mut total = Stats::default();
+        for seed in 0..5 {
+            let (sim, packets) = transfer_scenario(seed, faults);
+
+            // Once the faults stop, every packet is eventually acknowledged or times out.
+            for (source, packet) in packets.iter() {
+                assert!(!is_committed(&sim, *source, packet));
+            }
+
+            let stats = sim.stats();
+            total.dropped += stats.dropped;
+            total.duplicated += stats.duplicated;
+            total.rejected += stats.rejected;
+        }
+        assert!(total.dropped > 0);
+        assert!(total.duplicated > 0);
+        assert!(total.rejected > 0);
+    }
+
+    #[test]
+    fn scenarios_replay_identically() {
+        let faults = Faults {
+            drop_percent: 25,
+            duplicate_percent: 25,
+            reorder: true,
+        };
+        let (first, _) = transfer_scenario(42, faults);
+        let (second, _) = transfer_scenario(42, faults);
+
+        assert_eq!(first.stats(), second.stats());
+        for chain in 0..3 {
+            for account in ["alice", "bob", "carol"].iter() {
+                for denom in ["uatom", "ustake", "uosmo"].iter() {
+                    let account = Signer::new(*account);
+                    assert_eq!(
+                        first.chain(chain).balance(&account, denom),
+                        second.chain(chain).balance(&account, denom)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn stalled_link_times_out_packets() {
+        let (alice, bob) = (Signer::new("alice"), Signer::new("bob"));
+        let mut sim = Simulator::new(1, 2).with_balance(0, &alice, Coin::new("uatom", 100));
+        let link = sim.connect(0, 1).unwrap();
+
+        let packet = sim
+            .transfer(link, 0, &alice, &bob, Coin::new("uatom", 10), 3)
+            .unwrap();
+        sim.stall(link, 10);
+        sim.run(5).unwrap();
+        assert!(is_committed(&sim, 0, &packet));
+        assert_eq!(sim.chain(0).balance(&alice, "uatom"), 90);
+
+        sim.run(8).unwrap();
+        assert!(!is_committed(&sim, 0, &packet));
+        assert_eq!(sim.chain(0).balance(&alice, "uatom"), 100);
+        let dest_key = (
+            packet.destination_port.clone(),
+            packet.destination_channel.clone(),
+            packet.sequence,
+        );
+        assert!(sim.chain(1).get_packet_receipt(&dest_key).is_none());
+    }
+
+    #[test]
+    fn forked_headers_freeze_clients() {
+        let (alice, bob) = (Signer::new("alice"), Signer::new("bob"));
+        let mut sim = Simulator::new(2, 2).with_balance(0, &alice, Coin::new("uatom", 100));
+        let link = sim.connect(0, 1).unwrap();
+        let client_id = sim.link(link).b.client_id.clone();
+
+        sim.fork(link, 0).unwrap();
+        assert_eq!(sim.chain(1).client_status(&client_id), Status::Frozen);
+
+        // The frozen client cannot verify the packet, which stays escrowed.
+        let packet = sim
+            .transfer(link, 0, &alice, &bob, Coin::new("uatom", 10), 50)
+            .unwrap();
+        sim.run(5).unwrap();
+        assert!(is_committed(&sim, 0, &packet));
+        assert_eq!(sim.chain(0).balance(&alice, "uatom"), 90);
+        assert!(sim.stats().rejected > 0);
+    }
+
+    #[test]
+    fn idle_clients_expire() {
+        let (alice, bob) = (Signer::new("alice"), Signer::new("bob"));
+        let mut sim = Simulator::new(3, 2)
+            .with_trusting_period(Duration::from_secs(60))
+            .with_balance(0, &alice, Coin::new("uatom", 100));
+        let link = sim.connect(0, 1).unwrap();
+        let client_id = sim.link(link).b.client_id.clone();
+
+        let packet = sim
+            .transfer(link, 0, &alice, &bob, Coin::new("uatom", 10), 50)
+            .unwrap();
+        sim.advance_clock(Duration::from_secs(120));
+        sim.run(5).unwrap();
+
+        assert_eq!(sim.chain(1).client_status(&client_id), Status::Expired);
+        assert!(is_committed(&sim, 0, &packet));
+        assert_eq!(sim.chain(0).balance(&alice, "uatom"), 90);
+    }
+}
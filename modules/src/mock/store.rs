@@ -0,0 +1,344 @@
+//! An in-memory store for the mock context, which commits to the state of the chain at every
+//! height and proves it with `ics23` commitment proofs, like the store of a Cosmos SDK chain.
+//!
+//! At each height, the state is committed in a simple Merkle tree (as computed by Tendermint)
+//! over the paths of the state, in lexicographic order. The root of this tree is in turn stored
+//! under the commitment prefix in a second tree, whose root is the commitment root of the chain.
+//! Proofs therefore chain two sub-proofs, both checked against the Tendermint proof specification.
+
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+
+use ics23::commitment_proof::Proof;
+use ics23::{
+    CommitmentProof, ExistenceProof, HashOp, InnerOp, LeafOp, LengthOp, NonExistenceProof,
+};
+use sha2::{Digest, Sha256};
+
+use crate::ics23_commitment::commitment::{CommitmentPrefix, CommitmentRoot};
+use crate::ics23_commitment::merkle::MerkleProof;
+use crate::ics23_commitment::specs::ProofSpecs;
+use crate::ics24_host::Path;
+use crate::Height;
+
+/// The proof specifications of the proofs of a `ProvableStore`, ordered from the store of the
+/// paths to the root.
+pub fn mock_proof_specs() -> ProofSpecs {
+    ProofSpecs::from(vec![ics23::tendermint_spec(), ics23::tendermint_spec()])
+}
+
+/// The state of the chain at some height, i.e., the values of the paths, indexed by the string
+/// representation of the paths.
+type State = BTreeMap<String, Vec<u8>>;
+
+/// A store which keeps the state of the chain at every height since the oldest one which was not
+/// pruned, together with the corresponding commitment roots.
+#[derive(Clone, Debug)]
+pub struct ProvableStore {
+    prefix: CommitmentPrefix,
+    versions: BTreeMap<Height, (State, [u8; 32])>,
+}
+
+impl ProvableStore {
+    pub fn new(prefix: CommitmentPrefix) -> Self {
+        Self {
+            prefix,
+            versions: Default::default(),
+        }
+    }
+
+    pub fn prefix(&self) -> &CommitmentPrefix {
+        &self.prefix
+    }
+
+    /// Commits to `state`, the state of the chain at `height`, and returns the commitment root.
+    pub fn commit(
+        &mut self,
+        height: Height,
+        state: impl IntoIterator<Item = (Path, Vec<u8>)>,
+    ) -> [u8; 32] {
+        let state: State = state
+            .into_iter()
+            .map(|(path, value)| (path.to_string(), value))
+            .collect();
+
+        let store_root = tree_root(&leaves(&state));
+        let root = leaf_hash(self.prefix_key().as_bytes(), &store_root);
+
+        // A SHA-256 digest is 32 bytes long.
+        let root = <[u8; 32]>::try_from(root.as_slice()).unwrap();
+        self.versions.insert(height, (state, root));
+        root
+    }
+
+    /// Removes the state at all the heights lower than `oldest_height`.
+    pub fn prune(&mut self, oldest_height: Height) {
+        self.versions = self.versions.split_off(&oldest_height);
+    }
+
+    /// Returns the commitment root of the state at `height`.
+    pub fn root(&self, height: Height) -> Option<CommitmentRoot> {
+        self.versions
+            .get(&height)
+            .map(|(_, root)| CommitmentRoot::from_bytes(root))
+    }
+
+    /// Returns the value stored at `path` at `height`.
+    pub fn get(&self, height: Height, path: &Path) -> Option<&Vec<u8>> {
+        self.versions
+            .get(&height)
+            .and_then(|(state, _)| state.get(&path.to_string()))
+    }
+
+    /// Returns a proof that the value at `path` is stored at `height`, if there is such a value,
+    /// or a proof that nothing is stored at `path` otherwise. Returns `None` if the state at
+    /// `height` was pruned, or if nothing at all is stored at `height`.
+    pub fn prove(&self, height: Height, path: &Path) -> Option<MerkleProof> {
+        let (state, _) = self.versions.get(&height)?;
+        let key = path.to_string();
+
+        let store_proof = match state.get(&key) {
+            Some(_) => Proof::Exist(existence_proof(state, &key)),
+            None => {
+                let left = state.range::<String, _>(..&key).next_back();
+                let right = state.range::<String, _>(&key..).next();
+                if left.is_none() && right.is_none() {
+                    return None;
+                }
+
+                Proof::Nonexist(NonExistenceProof {
+                    key: key.into_bytes(),
+                    left: left.map(|(left_key, _)| existence_proof(state, left_key)),
+                    right: right.map(|(right_key, _)| existence_proof(state, right_key)),
+                })
+            }
+        };
+
+        // The tree of the prefix has a single leaf, hence its proof has no inner nodes.
+        let prefix_proof = Proof::Exist(ExistenceProof {
+            key: self.prefix_key().into_bytes(),
+            value: tree_root(&leaves(state)),
+            leaf: Some(leaf_op()),
+            path: vec![],
+        });
+
+        Some(MerkleProof {
+            proofs: vec![
+                CommitmentProof {
+                    proof: Some(store_proof),
+                },
+                CommitmentProof {
+                    proof: Some(prefix_proof),
+                },
+            ],
+        })
+    }
+
+    /// The key of the store root in the tree of the prefix, which is the first key of the paths
+    /// returned by `apply_prefix`.
+    fn prefix_key(&self) -> String {
+        format!("{:?}", self.prefix)
+    }
+}
+
+/// The leaf operation of the Tendermint proof specification.
+fn leaf_op() -> LeafOp {
+    LeafOp {
+        hash: HashOp::Sha256 as i32,
+        prehash_key: HashOp::NoHash as i32,
+        prehash_value: HashOp::Sha256 as i32,
+        length: LengthOp::VarProto as i32,
+        prefix: vec![0],
+    }
+}
+
+fn leaf_hash(key: &[u8], value: &[u8]) -> Vec<u8> {
+    let value_hash = Sha256::digest(value);
+
+    let mut data = vec![0];
+    prost::encoding::encode_varint(key.len() as u64, &mut data);
+    data.extend_from_slice(key);
+    prost::encoding::encode_varint(value_hash.len() as u64, &mut data);
+    data.extend_from_slice(&value_hash);
+
+    Sha256::digest(&data).to_vec()
+}
+
+fn inner_hash(left: &[u8], right: &[u8]) -> Vec<u8> {
+    let mut data = vec![1];
+    data.extend_from_slice(left);
+    data.extend_from_slice(right);
+
+    Sha256::digest(&data).to_vec()
+}
+
+fn leaves(state: &State) -> Vec<Vec<u8>> {
+    state
+        .iter()
+        .map(|(key, value)| leaf_hash(key.as_bytes(), value))
+        .collect()
+}
+
+/// The number of leaves in the left subtree of a tree of `n > 1` leaves, i.e., the largest power
+/// of two smaller than `n`.
+fn split_point(n: usize) -> usize {
+    let mut k = 1;
+    while k * 2 < n {
+        k *= 2;
+    }
+    k
+}
+
+fn tree_root(leaves: &[Vec<u8>]) -> Vec<u8> {
+    match leaves.len() {
+        0 => Sha256::digest(&[]).to_vec(),
+        1 => leaves[0].clone(),
+        n => {
+            let k = split_point(n);
+            inner_hash(&tree_root(&leaves[..k]), &tree_root(&leaves[k..]))
+        }
+    }
+}
+
+/// Returns the inner nodes on the path from the leaf at `index` up to the root of the tree, each
+/// holding the hash of the sibling subtree.
+fn tree_path(leaves: &[Vec<u8>], index: usize) -> Vec<InnerOp> {
+    if leaves.len() <= 1 {
+        return vec![];
+    }
+
+    let k = split_point(leaves.len());
+    let (mut path, inner_op) = if index < k {
+        let inner_op = InnerOp {
+            hash: HashOp::Sha256 as i32,
+            prefix: vec![1],
+            suffix: tree_root(&leaves[k..]),
+        };
+        (tree_path(&leaves[..k], index), inner_op)
+    } else {
+        let mut prefix = vec![1];
+        prefix.extend(tree_root(&leaves[..k]));
+        let inner_op = InnerOp {
+            hash: HashOp::Sha256 as i32,
+            prefix,
+            suffix: vec![],
+        };
+        (tree_path(&leaves[k..], index - k), inner_op)
+    };
+
+    path.push(inner_op);
+    path
+}
+
+/// Returns the existence proof of `key`, which must be stored in `state`.
+fn existence_proof(state: &State, key: &str) -> ExistenceProof {
+    let index = state.keys().position(|k| k == key).unwrap();
+
+    ExistenceProof {
+        key: key.as_bytes().to_vec(),
+        value: state[key].clone(),
+        leaf: Some(leaf_op()),
+        path: tree_path(&leaves(state), index),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use test_env_log::test;
+
+    use crate::ics04_channel::packet::Sequence;
+    use crate::ics23_commitment::commitment::{CommitmentPrefix, CommitmentRoot};
+    use crate::ics23_commitment::merkle::apply_prefix;
+    use crate::ics24_host::identifier::{ChannelId, ClientId, PortId};
+    use crate::ics24_host::Path;
+    use crate::Height;
+
+    use super::{mock_proof_specs, ProvableStore};
+
+    fn receipt_path(sequence: u64) -> Path {
+        Path::Receipts {
+            port_id: PortId::from_str("transfer").unwrap(),
+            channel_id: ChannelId::default(),
+            sequence: Sequence::from(sequence),
+        }
+    }
+
+    #[test]
+    fn proofs_verify_against_root() {
+        let prefix = CommitmentPrefix::from(b"ibc".to_vec());
+        let mut store = ProvableStore::new(prefix.clone());
+        let height = Height::new(0, 10);
+
+        // An odd number of paths makes for an unbalanced tree.
+        let state: Vec<(Path, Vec<u8>)> = vec![
+            (Path::ClientState(ClientId::default()), vec![1]),
+            (receipt_path(2), vec![1]),
+            (receipt_path(4), vec![1]),
+            (receipt_path(6), vec![1]),
+            (receipt_path(8), vec![1]),
+        ];
+        let root = store.commit(height, state.clone());
+        let root = CommitmentRoot::from_bytes(&root);
+        assert_eq!(store.root(height), Some(root.clone()));
+
+        let specs = mock_proof_specs();
+        let keys = |path: &Path| apply_prefix(&prefix, vec![path.to_string()]).unwrap();
+
+        for (path, value) in state {
+            let proof = store.prove(height, &path).unwrap();
+            assert!(proof
+                .verify_membership(&specs, &root, keys(&path), value, 0)
+                .is_ok());
+            // The proof of a path proves neither another value, nor absence.
+            assert!(proof
+                .verify_membership(&specs, &root, keys(&path), vec![2], 0)
+                .is_err());
+            assert!(proof
+                .verify_non_membership(&specs, &root, keys(&path))
+                .is_err());
+        }
+
+        // Absent paths before, between and after the stored ones.
+        for sequence in &[1, 3, 7, 9] {
+            let path = receipt_path(*sequence);
+            let proof = store.prove(height, &path).unwrap();
+            assert!(proof
+                .verify_non_membership(&specs, &root, keys(&path))
+                .is_ok());
+            // The proof of absence does not prove the absence of a stored path.
+            assert!(proof
+                .verify_non_membership(&specs, &root, keys(&receipt_path(4)))
+                .is_err());
+        }
+
+        // Proofs are bound to the prefix and to the root.
+        let path = receipt_path(4);
+        let proof = store.prove(height, &path).unwrap();
+        let wrong_prefix = CommitmentPrefix::from(b"xyz".to_vec());
+        let wrong_keys = apply_prefix(&wrong_prefix, vec![path.to_string()]).unwrap();
+        assert!(proof
+            .verify_membership(&specs, &root, wrong_keys, vec![1], 0)
+            .is_err());
+        let wrong_root = CommitmentRoot::from_bytes(&[0; 32]);
+        assert!(proof
+            .verify_membership(&specs, &wrong_root, keys(&path), vec![1], 0)
+            .is_err());
+    }
+
+    #[test]
+    fn pruned_heights_are_not_provable() {
+        let mut store = ProvableStore::new(CommitmentPrefix::from(b"ibc".to_vec()));
+        let path = receipt_path(1);
+        for h in 1..=3 {
+            store.commit(Height::new(0, h), vec![(path.clone(), vec![h as u8])]);
+        }
+
+        store.prune(Height::new(0, 3));
+        assert!(store.root(Height::new(0, 2)).is_none());
+        assert!(store.prove(Height::new(0, 2), &path).is_none());
+        assert_eq!(store.get(Height::new(0, 3), &path), Some(&vec![3]));
+        assert!(store.prove(Height::new(0, 3), &path).is_some());
+    }
+}
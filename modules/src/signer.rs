@@ -3,6 +3,7 @@ use std::{convert::Infallible, fmt::Display, str::FromStr};
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Signer(String);
 
 impl Signer {
@@ -137,7 +137,10 @@ impl Add<Duration> for Timestamp {
             Some(datetime) => {
                 let duration2 =
                     chrono::Duration::from_std(duration).map_err(|_| TimestampOverflowError)?;
-                Ok(Self::from_datetime(datetime + duration2))
+                datetime
+                    .checked_add_signed(duration2)
+                    .map(Self::from_datetime)
+                    .ok_or(TimestampOverflowError)
             }
             None => Ok(self),
         }
@@ -152,7 +155,10 @@ impl Sub<Duration> for Timestamp {
             Some(datetime) => {
                 let duration2 =
                     chrono::Duration::from_std(duration).map_err(|_| TimestampOverflowError)?;
-                Ok(Self::from_datetime(datetime - duration2))
+                datetime
+                    .checked_sub_signed(duration2)
+                    .map(Self::from_datetime)
+                    .ok_or(TimestampOverflowError)
             }
             None => Ok(self),
         }
@@ -187,6 +193,14 @@ impl Default for Timestamp {
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Timestamp {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let nanoseconds = u.int_in_range(0..=i64::MAX as u64)?;
+        Self::from_nanoseconds(nanoseconds).map_err(|_| arbitrary::Error::IncorrectFormat)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::convert::TryInto;
@@ -250,6 +264,11 @@ mod tests {
         assert_eq!(time3, (time1 - duration).unwrap());
         assert_eq!(time0, (time0 + duration).unwrap());
         assert_eq!(time0, (time0 - duration).unwrap());
+
+        // Durations reaching past the range of dates overflow, rather than panic.
+        let eon = Duration::from_secs(1 << 50);
+        assert!((time1 + eon).is_err());
+        assert!((time1 - eon).is_err());
     }
 
     #[test]
@@ -5,6 +5,7 @@ use std::error::Error;
 use std::fmt::{Debug, Display};
 use std::time::Duration;
 
+use ibc::handler::HandlerOutput;
 use ibc::ics02_client::client_consensus::AnyConsensusState;
 use ibc::ics02_client::client_state::AnyClientState;
 use ibc::ics02_client::client_type::ClientType;
@@ -22,12 +23,31 @@ use ibc::ics03_connection::msgs::conn_open_init::MsgConnectionOpenInit;
 use ibc::ics03_connection::msgs::conn_open_try::MsgConnectionOpenTry;
 use ibc::ics03_connection::msgs::ConnectionMsg;
 use ibc::ics03_connection::version::Version;
-use ibc::ics04_channel::context::ChannelReader;
+use ibc::ics04_channel::channel::{
+    ChannelEnd, Counterparty as ChannelCounterparty, Order, State as ChannelState,
+};
+use ibc::ics04_channel::context::{ChannelKeeper, ChannelReader};
+use ibc::ics04_channel::error::{Error as Ics04Error, Kind as Ics04ErrorKind};
+use ibc::ics04_channel::handler::send_packet::send_packet;
+use ibc::ics04_channel::msgs::acknowledgement::MsgAcknowledgement;
+use ibc::ics04_channel::msgs::chan_close_confirm::MsgChannelCloseConfirm;
+use ibc::ics04_channel::msgs::chan_close_init::MsgChannelCloseInit;
+use ibc::ics04_channel::msgs::chan_open_ack::MsgChannelOpenAck;
+use ibc::ics04_channel::msgs::chan_open_confirm::MsgChannelOpenConfirm;
+use ibc::ics04_channel::msgs::chan_open_init::MsgChannelOpenInit;
+use ibc::ics04_channel::msgs::chan_open_try::MsgChannelOpenTry;
+use ibc::ics04_channel::msgs::recv_packet::MsgRecvPacket;
+use ibc::ics04_channel::msgs::timeout::MsgTimeout;
+use ibc::ics04_channel::msgs::{ChannelMsg, PacketMsg};
+use ibc::ics04_channel::packet::Packet;
+use ibc::ics05_port::capabilities::ModuleId;
+use ibc::ics05_port::context::PortReader;
 use ibc::ics18_relayer::context::Ics18Context;
 use ibc::ics18_relayer::error::{Error as Ics18Error, Kind as Ics18ErrorKind};
 use ibc::ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes};
-use ibc::ics24_host::identifier::{ChainId, ClientId, ConnectionId};
+use ibc::ics24_host::identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId};
 use ibc::ics26_routing::error::{Error as Ics26Error, Kind as Ics26ErrorKind};
+use ibc::ics26_routing::module::Module;
 use ibc::ics26_routing::msgs::Ics26Envelope;
 use ibc::mock::client_state::{MockClientState, MockConsensusState};
 use ibc::mock::context::MockContext;
@@ -35,10 +55,42 @@ use ibc::mock::header::MockHeader;
 use ibc::mock::host::HostType;
 use ibc::proofs::{ConsensusProof, Proofs};
 use ibc::signer::Signer;
-use ibc::timestamp::ZERO_DURATION;
+use ibc::timestamp::{Timestamp, ZERO_DURATION};
 use ibc::Height;
 use step::{Action, ActionOutcome, Chain, Step};
 
+/// The application bound to the port of all channels in the model. It accepts every channel
+/// handshake and acknowledges every packet it receives.
+#[derive(Debug)]
+struct TestModule;
+
+impl Module<MockContext> for TestModule {
+    fn on_recv_packet(
+        &self,
+        _ctx: &mut MockContext,
+        _packet: &Packet,
+    ) -> HandlerOutput<Option<Vec<u8>>> {
+        HandlerOutput::builder().with_result(Some(IbcTestRunner::acknowledgement()))
+    }
+
+    fn on_acknowledgement_packet(
+        &self,
+        _ctx: &mut MockContext,
+        _packet: &Packet,
+        _acknowledgement: &[u8],
+    ) -> Result<HandlerOutput<()>, Ics04Error> {
+        Ok(HandlerOutput::builder().with_result(()))
+    }
+
+    fn on_timeout_packet(
+        &self,
+        _ctx: &mut MockContext,
+        _packet: &Packet,
+    ) -> Result<HandlerOutput<()>, Ics04Error> {
+        Ok(HandlerOutput::builder().with_result(()))
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct IbcTestRunner {
     // mapping from chain identifier to its context
@@ -63,7 +115,8 @@ impl IbcTestRunner {
             HostType::Mock,
             max_history_size,
             Height::new(Self::revision(), initial_height),
-        );
+        )
+        .with_module(ModuleId::new("test"), Self::port_id(), TestModule);
         assert!(self.contexts.insert(chain_id, ctx).is_none());
     }
 
@@ -135,6 +188,14 @@ impl IbcTestRunner {
         ConnectionId::new(connection_id)
     }
 
+    pub fn port_id() -> PortId {
+        PortId::default()
+    }
+
+    pub fn channel_id(channel_id: u64) -> ChannelId {
+        ChannelId::new(channel_id)
+    }
+
     pub fn height(height: u64) -> Height {
         Height::new(Self::revision(), height)
     }
@@ -148,11 +209,11 @@ impl IbcTestRunner {
     }
 
     pub fn client_state(height: u64) -> AnyClientState {
-        AnyClientState::Mock(MockClientState(Self::mock_header(height)))
+        AnyClientState::Mock(MockClientState::new(Self::mock_header(height)))
     }
 
     pub fn consensus_state(height: u64) -> AnyConsensusState {
-        AnyConsensusState::Mock(MockConsensusState(Self::mock_header(height)))
+        AnyConsensusState::Mock(MockConsensusState::new(Self::mock_header(height)))
     }
 
     fn signer() -> Signer {
@@ -166,6 +227,77 @@ impl IbcTestRunner {
         Counterparty::new(client_id, connection_id, prefix)
     }
 
+    pub fn channel_version() -> String {
+        "mbt-1".to_string()
+    }
+
+    pub fn channel_counterparty(channel_id: Option<u64>) -> ChannelCounterparty {
+        ChannelCounterparty::new(Self::port_id(), channel_id.map(Self::channel_id))
+    }
+
+    pub fn channel_end(
+        state: ChannelState,
+        order: Order,
+        connection_id: u64,
+        counterparty_channel_id: Option<u64>,
+    ) -> ChannelEnd {
+        ChannelEnd::new(
+            state,
+            order,
+            Self::channel_counterparty(counterparty_channel_id),
+            vec![Self::connection_id(connection_id)],
+            Self::channel_version(),
+        )
+    }
+
+    /// Packets are sent from a channel to a channel bound to the same port, and only time out
+    /// at a given height of the destination chain.
+    pub fn packet(
+        sequence: u64,
+        source_channel_id: u64,
+        destination_channel_id: u64,
+        timeout_height: u64,
+    ) -> Packet {
+        Packet {
+            sequence: sequence.into(),
+            source_port: Self::port_id(),
+            source_channel: Self::channel_id(source_channel_id),
+            destination_port: Self::port_id(),
+            destination_channel: Self::channel_id(destination_channel_id),
+            data: vec![0],
+            timeout_height: Self::height(timeout_height),
+            timeout_timestamp: Timestamp::none(),
+        }
+    }
+
+    pub fn acknowledgement() -> Vec<u8> {
+        vec![1]
+    }
+
+    /// Sends `packet` on behalf of the module bound to the port of the channels. As packets are
+    /// sent by applications, and not by messages, the outcome is reported as if the packet had
+    /// been sent by a message delivered to the chain: a new block is created if the packet is
+    /// sent, and the errors are wrapped like in `MockContext::deliver`.
+    fn send_packet(ctx: &mut MockContext, packet: Packet) -> Result<(), Ics18Error> {
+        // if the channel does not exist, then there's no capability for it; the handler rejects
+        // the packet regardless of the capability in that case, hence the port one is presented
+        let channel_cap = ctx
+            .channel_capability(&packet.source_port, &packet.source_channel)
+            .or_else(|| {
+                ctx.lookup_module_by_port(&packet.source_port)
+                    .map(|(_, port_cap)| port_cap)
+            })
+            .expect("the port of the channels should be bound");
+        send_packet(&*ctx, &channel_cap, packet)
+            .and_then(|output| ctx.store_packet_result(output.result))
+            .map_err(|e| {
+                let e: Ics26Error = Ics26ErrorKind::HandlerRaisedError.context(e).into();
+                Ics18ErrorKind::TransactionFailed.context(e)
+            })?;
+        ctx.advance_host_chain_height();
+        Ok(())
+    }
+
     pub fn delay_period() -> Duration {
         ZERO_DURATION
     }
@@ -290,7 +422,81 @@ impl IbcTestRunner {
                         }
                     });
 
-            heights_match && clients_match && connections_match
+            // check that channels match
+            let channels_match = chain.channels.into_iter().all(|(channel_id, channel)| {
+                let port_channel_id = (Self::port_id(), Self::channel_id(channel_id));
+                if channel.state == ChannelState::Uninitialized {
+                    // if the channel has not yet been initialized, then
+                    // there's nothing to check
+                    true
+                } else if let Some(channel_end) = ctx.channel_end(&port_channel_id) {
+                    // states must match
+                    let states_match = channel_end.state == channel.state;
+
+                    // orders must match
+                    let orders_match = *channel_end.ordering() == channel.order;
+
+                    // connection ids must match
+                    let connection_ids =
+                        channel_end.connection_hops_matches(&vec![Self::connection_id(
+                            channel.connection_id.unwrap(),
+                        )]);
+
+                    // counterparty channel ids must match
+                    let counterparty_channel_ids = channel_end.counterparty().channel_id()
+                        == channel
+                            .counterparty_channel_id
+                            .map(Self::channel_id)
+                            .as_ref();
+
+                    // next sequence numbers must match
+                    let sequences_match = ctx.get_next_sequence_send(&port_channel_id)
+                        == Some(channel.next_send_seq.into())
+                        && ctx.get_next_sequence_recv(&port_channel_id)
+                            == Some(channel.next_recv_seq.into())
+                        && ctx.get_next_sequence_ack(&port_channel_id)
+                            == Some(channel.next_ack_seq.into());
+
+                    states_match
+                        && orders_match
+                        && connection_ids
+                        && counterparty_channel_ids
+                        && sequences_match
+                } else {
+                    // if the channel exists in the model, then it must also
+                    // exist in the implementation; in this case it doesn't,
+                    // so we fail the verification
+                    false
+                }
+            });
+
+            // check that each packet commitment from the model exists
+            // TODO: check that no other packet commitment exists
+            let packet_commitments_match = chain.packet_commitments.into_iter().all(|packet| {
+                ctx.get_packet_commitment(&(
+                    Self::port_id(),
+                    Self::channel_id(packet.source_channel_id),
+                    packet.sequence.into(),
+                ))
+                .is_some()
+            });
+
+            // check that each packet receipt from the model exists
+            let packet_receipts_match = chain.packet_receipts.into_iter().all(|packet| {
+                ctx.get_packet_receipt(&(
+                    Self::port_id(),
+                    Self::channel_id(packet.dest_channel_id),
+                    packet.sequence.into(),
+                ))
+                .is_some()
+            });
+
+            heights_match
+                && clients_match
+                && connections_match
+                && channels_match
+                && packet_commitments_match
+                && packet_receipts_match
         })
     }
 
@@ -425,6 +631,239 @@ impl IbcTestRunner {
                 ));
                 ctx.deliver(msg)
             }
+            Action::Ics04ChannelOpenInit {
+                chain_id,
+                connection_id,
+                order,
+            } => {
+                // get chain's context
+                let ctx = self.chain_context_mut(chain_id);
+
+                // create ICS26 message and deliver it
+                let msg = Ics26Envelope::Ics4ChannelMsg(ChannelMsg::ChannelOpenInit(
+                    MsgChannelOpenInit::new(
+                        Self::port_id(),
+                        Self::channel_end(ChannelState::Init, order, connection_id, None),
+                        Self::signer(),
+                    ),
+                ));
+                ctx.deliver(msg)
+            }
+            Action::Ics04ChannelOpenTry {
+                chain_id,
+                connection_id,
+                order,
+                proof_height,
+                counterparty_chain_id: _,
+                counterparty_channel_id,
+            } => {
+                // get chain's context
+                let ctx = self.chain_context_mut(chain_id);
+
+                // create ICS26 message and deliver it
+                let msg = Ics26Envelope::Ics4ChannelMsg(ChannelMsg::ChannelOpenTry(
+                    MsgChannelOpenTry::new(
+                        Self::port_id(),
+                        None,
+                        Self::channel_end(
+                            ChannelState::TryOpen,
+                            order,
+                            connection_id,
+                            Some(counterparty_channel_id),
+                        ),
+                        Self::channel_version(),
+                        Self::proofs(proof_height),
+                        Self::signer(),
+                    ),
+                ));
+                ctx.deliver(msg)
+            }
+            Action::Ics04ChannelOpenAck {
+                chain_id,
+                channel_id,
+                proof_height,
+                counterparty_chain_id: _,
+                counterparty_channel_id,
+            } => {
+                // get chain's context
+                let ctx = self.chain_context_mut(chain_id);
+
+                // create ICS26 message and deliver it
+                let msg = Ics26Envelope::Ics4ChannelMsg(ChannelMsg::ChannelOpenAck(
+                    MsgChannelOpenAck::new(
+                        Self::port_id(),
+                        Self::channel_id(channel_id),
+                        Self::channel_id(counterparty_channel_id),
+                        Self::channel_version(),
+                        Self::proofs(proof_height),
+                        Self::signer(),
+                    ),
+                ));
+                ctx.deliver(msg)
+            }
+            Action::Ics04ChannelOpenConfirm {
+                chain_id,
+                channel_id,
+                proof_height,
+                counterparty_chain_id: _,
+                counterparty_channel_id: _,
+            } => {
+                // get chain's context
+                let ctx = self.chain_context_mut(chain_id);
+
+                // create ICS26 message and deliver it
+                let msg = Ics26Envelope::Ics4ChannelMsg(ChannelMsg::ChannelOpenConfirm(
+                    MsgChannelOpenConfirm::new(
+                        Self::port_id(),
+                        Self::channel_id(channel_id),
+                        Self::proofs(proof_height),
+                        Self::signer(),
+                    ),
+                ));
+                ctx.deliver(msg)
+            }
+            Action::Ics04ChannelCloseInit {
+                chain_id,
+                channel_id,
+            } => {
+                // get chain's context
+                let ctx = self.chain_context_mut(chain_id);
+
+                // create ICS26 message and deliver it
+                let msg = Ics26Envelope::Ics4ChannelMsg(ChannelMsg::ChannelCloseInit(
+                    MsgChannelCloseInit::new(
+                        Self::port_id(),
+                        Self::channel_id(channel_id),
+                        Self::signer(),
+                    ),
+                ));
+                ctx.deliver(msg)
+            }
+            Action::Ics04ChannelCloseConfirm {
+                chain_id,
+                channel_id,
+                proof_height,
+                counterparty_chain_id: _,
+                counterparty_channel_id: _,
+            } => {
+                // get chain's context
+                let ctx = self.chain_context_mut(chain_id);
+
+                // create ICS26 message and deliver it
+                let msg = Ics26Envelope::Ics4ChannelMsg(ChannelMsg::ChannelCloseConfirm(
+                    MsgChannelCloseConfirm::new(
+                        Self::port_id(),
+                        Self::channel_id(channel_id),
+                        Self::proofs(proof_height),
+                        Self::signer(),
+                    ),
+                ));
+                ctx.deliver(msg)
+            }
+            Action::Ics04SendPacket {
+                chain_id,
+                channel_id,
+                counterparty_chain_id: _,
+                counterparty_channel_id,
+                sequence,
+                timeout_height,
+            } => {
+                // get chain's context
+                let ctx = self.chain_context_mut(chain_id);
+
+                // create the packet and send it; if the channel does not
+                // know its counterparty channel, then the packet is sent to
+                // an arbitrary channel, which the handler rejects
+                let packet = Self::packet(
+                    sequence,
+                    channel_id,
+                    counterparty_channel_id.unwrap_or_default(),
+                    timeout_height,
+                );
+                Self::send_packet(ctx, packet)
+            }
+            Action::Ics04RecvPacket {
+                chain_id,
+                channel_id,
+                proof_height,
+                counterparty_chain_id: _,
+                counterparty_channel_id,
+                sequence,
+                timeout_height,
+            } => {
+                // get chain's context
+                let ctx = self.chain_context_mut(chain_id);
+
+                // create ICS26 message and deliver it
+                let packet = Self::packet(
+                    sequence,
+                    counterparty_channel_id,
+                    channel_id,
+                    timeout_height,
+                );
+                let msg = Ics26Envelope::Ics4PacketMsg(PacketMsg::RecvPacket(MsgRecvPacket::new(
+                    packet,
+                    Self::proofs(proof_height),
+                    Self::signer(),
+                )));
+                ctx.deliver(msg)
+            }
+            Action::Ics04Acknowledgement {
+                chain_id,
+                channel_id,
+                proof_height,
+                counterparty_chain_id: _,
+                counterparty_channel_id,
+                sequence,
+                timeout_height,
+            } => {
+                // get chain's context
+                let ctx = self.chain_context_mut(chain_id);
+
+                // create ICS26 message and deliver it
+                let packet = Self::packet(
+                    sequence,
+                    channel_id,
+                    counterparty_channel_id,
+                    timeout_height,
+                );
+                let msg =
+                    Ics26Envelope::Ics4PacketMsg(PacketMsg::AckPacket(MsgAcknowledgement::new(
+                        packet,
+                        Self::acknowledgement(),
+                        Self::proofs(proof_height),
+                        Self::signer(),
+                    )));
+                ctx.deliver(msg)
+            }
+            Action::Ics04Timeout {
+                chain_id,
+                channel_id,
+                proof_height,
+                counterparty_chain_id: _,
+                counterparty_channel_id,
+                sequence,
+                timeout_height,
+                next_sequence_recv,
+            } => {
+                // get chain's context
+                let ctx = self.chain_context_mut(chain_id);
+
+                // create ICS26 message and deliver it
+                let packet = Self::packet(
+                    sequence,
+                    channel_id,
+                    counterparty_channel_id,
+                    timeout_height,
+                );
+                let msg = Ics26Envelope::Ics4PacketMsg(PacketMsg::ToPacket(MsgTimeout::new(
+                    packet,
+                    next_sequence_recv.into(),
+                    Self::proofs(proof_height),
+                    Self::signer(),
+                )));
+                ctx.deliver(msg)
+            }
         }
     }
 }
@@ -490,6 +929,69 @@ impl modelator::runner::TestRunner<Step> for IbcTestRunner {
                 Ics03ErrorKind::UninitializedConnection(_)
             ),
             ActionOutcome::Ics03ConnectionOpenConfirmOk => result.is_ok(),
+            ActionOutcome::Ics04ChannelOpenInitOk => result.is_ok(),
+            ActionOutcome::Ics04MissingConnection => matches!(
+                Self::extract_handler_error_kind::<Ics04ErrorKind>(result),
+                Ics04ErrorKind::MissingConnection(_)
+            ),
+            ActionOutcome::Ics04ChannelOpenTryOk => result.is_ok(),
+            ActionOutcome::Ics04ConnectionNotOpen => matches!(
+                Self::extract_handler_error_kind::<Ics04ErrorKind>(result),
+                Ics04ErrorKind::ConnectionNotOpen(_)
+            ),
+            // the channel handshake handlers report the errors raised while
+            // verifying proofs as proof verification failures
+            ActionOutcome::Ics04MissingClientConsensusState => matches!(
+                Self::extract_handler_error_kind::<Ics04ErrorKind>(result),
+                Ics04ErrorKind::MissingClientConsensusState(_, _)
+                    | Ics04ErrorKind::FailedChanneOpenTryVerification
+                    | Ics04ErrorKind::ChanOpenAckProofVerification
+                    | Ics04ErrorKind::ChanOpenConfirmProofVerification
+            ),
+            ActionOutcome::Ics04ChannelOpenAckOk => result.is_ok(),
+            ActionOutcome::Ics04ChannelNotFound => matches!(
+                Self::extract_handler_error_kind::<Ics04ErrorKind>(result),
+                Ics04ErrorKind::ChannelNotFound(_, _)
+            ),
+            ActionOutcome::Ics04InvalidChannelState => matches!(
+                Self::extract_handler_error_kind::<Ics04ErrorKind>(result),
+                Ics04ErrorKind::InvalidChannelState(_, _)
+            ),
+            ActionOutcome::Ics04ChannelOpenConfirmOk => result.is_ok(),
+            ActionOutcome::Ics04ChannelCloseInitOk => result.is_ok(),
+            ActionOutcome::Ics04ChannelCloseConfirmOk => result.is_ok(),
+            ActionOutcome::Ics04ChannelClosed => matches!(
+                Self::extract_handler_error_kind::<Ics04ErrorKind>(result),
+                Ics04ErrorKind::ChannelClosed(_)
+            ),
+            ActionOutcome::Ics04SendPacketOk => result.is_ok(),
+            ActionOutcome::Ics04InvalidPacketCounterparty => matches!(
+                Self::extract_handler_error_kind::<Ics04ErrorKind>(result),
+                Ics04ErrorKind::InvalidPacketCounterparty(_, _)
+            ),
+            ActionOutcome::Ics04LowPacketHeight => matches!(
+                Self::extract_handler_error_kind::<Ics04ErrorKind>(result),
+                Ics04ErrorKind::LowPacketHeight(_, _)
+            ),
+            ActionOutcome::Ics04RecvPacketOk => result.is_ok(),
+            ActionOutcome::Ics04InvalidPacketSequence => matches!(
+                Self::extract_handler_error_kind::<Ics04ErrorKind>(result),
+                Ics04ErrorKind::InvalidPacketSequence(_, _)
+            ),
+            ActionOutcome::Ics04PacketAlreadyReceived => matches!(
+                Self::extract_handler_error_kind::<Ics04ErrorKind>(result),
+                Ics04ErrorKind::PacketAlreadyReceived(_)
+            ),
+            ActionOutcome::Ics04AcknowledgementOk => result.is_ok(),
+            ActionOutcome::Ics04PacketCommitmentNotFound => matches!(
+                Self::extract_handler_error_kind::<Ics04ErrorKind>(result),
+                Ics04ErrorKind::PacketCommitmentNotFound(_)
+            ),
+            ActionOutcome::Ics04TimeoutOk => result.is_ok(),
+            ActionOutcome::Ics04PacketTimeoutHeightNotReached => matches!(
+                Self::extract_handler_error_kind::<Ics04ErrorKind>(result),
+                Ics04ErrorKind::PacketTimeoutHeightNotReached(_, _)
+            ),
         };
         // also check the state of chains
         outcome_matches && self.validate_chains() && self.check_chain_states(step.chains)
@@ -1,4 +1,5 @@
 use ibc::ics03_connection::connection::State as ConnectionState;
+use ibc::ics04_channel::channel::{Order, State as ChannelState};
 use serde::{Deserialize, Deserializer};
 use std::collections::HashMap;
 use std::fmt::Debug;
@@ -104,6 +105,173 @@ pub enum Action {
         #[serde(alias = "counterpartyConnectionId")]
         counterparty_connection_id: u64,
     },
+    Ics04ChannelOpenInit {
+        #[serde(alias = "chainId")]
+        chain_id: String,
+
+        #[serde(alias = "connectionId")]
+        connection_id: u64,
+
+        order: Order,
+    },
+    Ics04ChannelOpenTry {
+        #[serde(alias = "chainId")]
+        chain_id: String,
+
+        #[serde(alias = "connectionId")]
+        connection_id: u64,
+
+        order: Order,
+
+        #[serde(alias = "proofHeight")]
+        proof_height: u64,
+
+        #[serde(alias = "counterpartyChainId")]
+        counterparty_chain_id: String,
+
+        #[serde(alias = "counterpartyChannelId")]
+        counterparty_channel_id: u64,
+    },
+    Ics04ChannelOpenAck {
+        #[serde(alias = "chainId")]
+        chain_id: String,
+
+        #[serde(alias = "channelId")]
+        channel_id: u64,
+
+        #[serde(alias = "proofHeight")]
+        proof_height: u64,
+
+        #[serde(alias = "counterpartyChainId")]
+        counterparty_chain_id: String,
+
+        #[serde(alias = "counterpartyChannelId")]
+        counterparty_channel_id: u64,
+    },
+    Ics04ChannelOpenConfirm {
+        #[serde(alias = "chainId")]
+        chain_id: String,
+
+        #[serde(alias = "channelId")]
+        channel_id: u64,
+
+        #[serde(alias = "proofHeight")]
+        proof_height: u64,
+
+        #[serde(alias = "counterpartyChainId")]
+        counterparty_chain_id: String,
+
+        #[serde(alias = "counterpartyChannelId")]
+        counterparty_channel_id: u64,
+    },
+    Ics04ChannelCloseInit {
+        #[serde(alias = "chainId")]
+        chain_id: String,
+
+        #[serde(alias = "channelId")]
+        channel_id: u64,
+    },
+    Ics04ChannelCloseConfirm {
+        #[serde(alias = "chainId")]
+        chain_id: String,
+
+        #[serde(alias = "channelId")]
+        channel_id: u64,
+
+        #[serde(alias = "proofHeight")]
+        proof_height: u64,
+
+        #[serde(alias = "counterpartyChainId")]
+        counterparty_chain_id: String,
+
+        #[serde(alias = "counterpartyChannelId")]
+        counterparty_channel_id: u64,
+    },
+    Ics04SendPacket {
+        #[serde(alias = "chainId")]
+        chain_id: String,
+
+        #[serde(alias = "channelId")]
+        channel_id: u64,
+
+        #[serde(alias = "counterpartyChainId")]
+        counterparty_chain_id: String,
+
+        #[serde(alias = "counterpartyChannelId")]
+        #[serde(default, deserialize_with = "deserialize_id")]
+        counterparty_channel_id: Option<u64>,
+
+        sequence: u64,
+
+        #[serde(alias = "timeoutHeight")]
+        timeout_height: u64,
+    },
+    Ics04RecvPacket {
+        #[serde(alias = "chainId")]
+        chain_id: String,
+
+        #[serde(alias = "channelId")]
+        channel_id: u64,
+
+        #[serde(alias = "proofHeight")]
+        proof_height: u64,
+
+        #[serde(alias = "counterpartyChainId")]
+        counterparty_chain_id: String,
+
+        #[serde(alias = "counterpartyChannelId")]
+        counterparty_channel_id: u64,
+
+        sequence: u64,
+
+        #[serde(alias = "timeoutHeight")]
+        timeout_height: u64,
+    },
+    Ics04Acknowledgement {
+        #[serde(alias = "chainId")]
+        chain_id: String,
+
+        #[serde(alias = "channelId")]
+        channel_id: u64,
+
+        #[serde(alias = "proofHeight")]
+        proof_height: u64,
+
+        #[serde(alias = "counterpartyChainId")]
+        counterparty_chain_id: String,
+
+        #[serde(alias = "counterpartyChannelId")]
+        counterparty_channel_id: u64,
+
+        sequence: u64,
+
+        #[serde(alias = "timeoutHeight")]
+        timeout_height: u64,
+    },
+    Ics04Timeout {
+        #[serde(alias = "chainId")]
+        chain_id: String,
+
+        #[serde(alias = "channelId")]
+        channel_id: u64,
+
+        #[serde(alias = "proofHeight")]
+        proof_height: u64,
+
+        #[serde(alias = "counterpartyChainId")]
+        counterparty_chain_id: String,
+
+        #[serde(alias = "counterpartyChannelId")]
+        counterparty_channel_id: u64,
+
+        sequence: u64,
+
+        #[serde(alias = "timeoutHeight")]
+        timeout_height: u64,
+
+        #[serde(alias = "nextSequenceRecv")]
+        next_sequence_recv: u64,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
@@ -124,6 +292,28 @@ pub enum ActionOutcome {
     Ics03ConnectionOpenAckOk,
     Ics03UninitializedConnection,
     Ics03ConnectionOpenConfirmOk,
+    Ics04ChannelOpenInitOk,
+    Ics04MissingConnection,
+    Ics04ChannelOpenTryOk,
+    Ics04ConnectionNotOpen,
+    Ics04MissingClientConsensusState,
+    Ics04ChannelOpenAckOk,
+    Ics04ChannelNotFound,
+    Ics04InvalidChannelState,
+    Ics04ChannelOpenConfirmOk,
+    Ics04ChannelCloseInitOk,
+    Ics04ChannelCloseConfirmOk,
+    Ics04ChannelClosed,
+    Ics04SendPacketOk,
+    Ics04InvalidPacketCounterparty,
+    Ics04LowPacketHeight,
+    Ics04RecvPacketOk,
+    Ics04InvalidPacketSequence,
+    Ics04PacketAlreadyReceived,
+    Ics04AcknowledgementOk,
+    Ics04PacketCommitmentNotFound,
+    Ics04TimeoutOk,
+    Ics04PacketTimeoutHeightNotReached,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
@@ -133,6 +323,14 @@ pub struct Chain {
     pub clients: HashMap<u64, Client>,
 
     pub connections: HashMap<u64, Connection>,
+
+    pub channels: HashMap<u64, Channel>,
+
+    #[serde(alias = "packetCommitments")]
+    pub packet_commitments: Vec<Packet>,
+
+    #[serde(alias = "packetReceipts")]
+    pub packet_receipts: Vec<Packet>,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
@@ -161,8 +359,43 @@ pub struct Connection {
     pub state: ConnectionState,
 }
 
-/// On the model, a non-existing `client_id` and a `connection_id` is
-/// represented with -1.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Channel {
+    pub state: ChannelState,
+
+    pub order: Order,
+
+    #[serde(alias = "connectionId")]
+    #[serde(default, deserialize_with = "deserialize_id")]
+    pub connection_id: Option<u64>,
+
+    #[serde(alias = "counterpartyChannelId")]
+    #[serde(default, deserialize_with = "deserialize_id")]
+    pub counterparty_channel_id: Option<u64>,
+
+    #[serde(alias = "nextSendSeq")]
+    pub next_send_seq: u64,
+
+    #[serde(alias = "nextRecvSeq")]
+    pub next_recv_seq: u64,
+
+    #[serde(alias = "nextAckSeq")]
+    pub next_ack_seq: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Packet {
+    #[serde(alias = "sourceChannelId")]
+    pub source_channel_id: u64,
+
+    #[serde(alias = "destChannelId")]
+    pub dest_channel_id: u64,
+
+    pub sequence: u64,
+}
+
+/// On the model, a non-existing `client_id`, `connection_id` and `channel_id`
+/// is represented with -1.
 /// For this reason, this function maps a `Some(-1)` to a `None`.
 fn deserialize_id<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
 where
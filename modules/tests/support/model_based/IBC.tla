@@ -1,6 +1,6 @@
 --------------------------------- MODULE IBC ----------------------------------
 
-EXTENDS ICS02, ICS03
+EXTENDS ICS02, ICS03, ICS04
 
 \* ids of existing chains
 CONSTANT ChainIds
@@ -13,6 +13,12 @@ ASSUME MaxClientsPerChain >= 0
 \* max number of connections to be created per chain
 CONSTANT MaxConnectionsPerChain
 ASSUME MaxConnectionsPerChain >= 0
+\* max number of channels to be created per chain
+CONSTANT MaxChannelsPerChain
+ASSUME MaxChannelsPerChain >= 0
+\* max number of packets to be sent per channel
+CONSTANT MaxPacketsPerChannel
+ASSUME MaxPacketsPerChannel >= 0
 
 \* mapping from chain id to its data
 VARIABLE chains
@@ -35,6 +41,25 @@ ConnectionStates == {
     "TryOpen",
     "Open"
 }
+\* set of possible channel identifiers
+ChannelIds == 0..(MaxChannelsPerChain - 1)
+\* set of possible channel states
+ChannelStates == {
+    "Uninitialized",
+    "Init",
+    "TryOpen",
+    "Open",
+    "Closed"
+}
+\* set of possible channel orders
+ChannelOrders == {
+    "Ordered",
+    "Unordered"
+}
+\* set of possible packet sequences
+Sequences == 1..MaxPacketsPerChannel
+\* set of possible next sequence numbers of a channel
+SequenceNumbers == 1..(MaxPacketsPerChannel + 1)
 
 \* set of possible actions
 NoneActions == [
@@ -103,10 +128,126 @@ ConnectionActions ==
     ConnectionOpenAckActions \union
     ConnectionOpenConfirmActions
 
+ChannelOpenInitActions == [
+    type: {"Ics04ChannelOpenInit"},
+    chainId: ChainIds,
+    connectionId: ConnectionIds,
+    order: ChannelOrders,
+    channelId: ChannelIds,
+    \* `counterpartyChainId` is none if the connection does not exist
+    counterpartyChainId: ChainIds \union {ChainIdNone}
+] <: {ActionType}
+ChannelOpenTryActions == [
+    type: {"Ics04ChannelOpenTry"},
+    chainId: ChainIds,
+    connectionId: ConnectionIds,
+    order: ChannelOrders,
+    channelId: ChannelIds,
+    \* `proofHeight` is the height of the consensus state used to verify
+    \* the proofs of the counterparty chain
+    proofHeight: Heights,
+    counterpartyChainId: ChainIds,
+    counterpartyChannelId: ChannelIds
+] <: {ActionType}
+ChannelOpenAckActions == [
+    type: {"Ics04ChannelOpenAck"},
+    chainId: ChainIds,
+    channelId: ChannelIds,
+    proofHeight: Heights,
+    counterpartyChainId: ChainIds,
+    counterpartyChannelId: ChannelIds
+] <: {ActionType}
+ChannelOpenConfirmActions == [
+    type: {"Ics04ChannelOpenConfirm"},
+    chainId: ChainIds,
+    channelId: ChannelIds,
+    proofHeight: Heights,
+    counterpartyChainId: ChainIds,
+    counterpartyChannelId: ChannelIds
+] <: {ActionType}
+ChannelCloseInitActions == [
+    type: {"Ics04ChannelCloseInit"},
+    chainId: ChainIds,
+    channelId: ChannelIds,
+    \* the counterparty is none if the channel does not exist or, in the
+    \* case of `counterpartyChannelId`, if the channel is not yet open
+    counterpartyChainId: ChainIds \union {ChainIdNone},
+    counterpartyChannelId: ChannelIds \union {ChannelIdNone}
+] <: {ActionType}
+ChannelCloseConfirmActions == [
+    type: {"Ics04ChannelCloseConfirm"},
+    chainId: ChainIds,
+    channelId: ChannelIds,
+    proofHeight: Heights,
+    counterpartyChainId: ChainIds,
+    counterpartyChannelId: ChannelIds
+] <: {ActionType}
+ChannelActions ==
+    ChannelOpenInitActions \union
+    ChannelOpenTryActions \union
+    ChannelOpenAckActions \union
+    ChannelOpenConfirmActions \union
+    ChannelCloseInitActions \union
+    ChannelCloseConfirmActions
+
+SendPacketActions == [
+    type: {"Ics04SendPacket"},
+    chainId: ChainIds,
+    channelId: ChannelIds,
+    \* the destination of the packet is none if the channel does not exist
+    \* or, in the case of `counterpartyChannelId`, if the channel does not
+    \* know its counterparty channel yet
+    counterpartyChainId: ChainIds \union {ChainIdNone},
+    counterpartyChannelId: ChannelIds \union {ChannelIdNone},
+    sequence: Sequences,
+    \* `timeoutHeight` is a height of the destination chain
+    timeoutHeight: Heights
+] <: {ActionType}
+RecvPacketActions == [
+    type: {"Ics04RecvPacket"},
+    chainId: ChainIds,
+    channelId: ChannelIds,
+    proofHeight: Heights,
+    counterpartyChainId: ChainIds,
+    counterpartyChannelId: ChannelIds,
+    sequence: Sequences,
+    timeoutHeight: Heights
+] <: {ActionType}
+AcknowledgementActions == [
+    type: {"Ics04Acknowledgement"},
+    chainId: ChainIds,
+    channelId: ChannelIds,
+    proofHeight: Heights,
+    counterpartyChainId: ChainIds,
+    counterpartyChannelId: ChannelIds,
+    sequence: Sequences,
+    timeoutHeight: Heights
+] <: {ActionType}
+TimeoutActions == [
+    type: {"Ics04Timeout"},
+    chainId: ChainIds,
+    channelId: ChannelIds,
+    proofHeight: Heights,
+    counterpartyChainId: ChainIds,
+    counterpartyChannelId: ChannelIds,
+    sequence: Sequences,
+    timeoutHeight: Heights,
+    \* `nextSequenceRecv` is the next receive sequence number of the
+    \* counterparty channel
+    nextSequenceRecv: SequenceNumbers
+] <: {ActionType}
+PacketActions ==
+    SendPacketActions \union
+    RecvPacketActions \union
+    AcknowledgementActions \union
+    TimeoutActions
+
 Actions ==
     NoneActions \union
     ClientActions \union
-    ConnectionActions
+    ConnectionActions \union
+    ChannelActions \union
+    PacketActions
 
 \* set of possible action outcomes
 ActionOutcomes == {
@@ -132,7 +273,39 @@ ActionOutcomes == {
     "Ics03ConnectionOpenAckOk",
     "Ics03UninitializedConnection",
     \* ICS03_ConnectionOpenConfirm outcomes:
-    "Ics03ConnectionOpenConfirmOk"
+    "Ics03ConnectionOpenConfirmOk",
+    \* ICS04_ChannelOpenInit outcomes:
+    "Ics04ChannelOpenInitOk",
+    "Ics04MissingConnection",
+    \* ICS04_ChannelOpenTry outcomes:
+    "Ics04ChannelOpenTryOk",
+    "Ics04ConnectionNotOpen",
+    "Ics04MissingClientConsensusState",
+    \* ICS04_ChannelOpenAck outcomes:
+    "Ics04ChannelOpenAckOk",
+    "Ics04ChannelNotFound",
+    "Ics04InvalidChannelState",
+    \* ICS04_ChannelOpenConfirm outcomes:
+    "Ics04ChannelOpenConfirmOk",
+    \* ICS04_ChannelCloseInit outcomes:
+    "Ics04ChannelCloseInitOk",
+    \* ICS04_ChannelCloseConfirm outcomes:
+    "Ics04ChannelCloseConfirmOk",
+    "Ics04ChannelClosed",
+    \* ICS04_SendPacket outcomes:
+    "Ics04SendPacketOk",
+    "Ics04InvalidPacketCounterparty",
+    "Ics04LowPacketHeight",
+    \* ICS04_RecvPacket outcomes:
+    "Ics04RecvPacketOk",
+    "Ics04InvalidPacketSequence",
+    "Ics04PacketAlreadyReceived",
+    \* ICS04_Acknowledgement outcomes:
+    "Ics04AcknowledgementOk",
+    "Ics04PacketCommitmentNotFound",
+    \* ICS04_Timeout outcomes:
+    "Ics04TimeoutOk",
+    "Ics04PacketTimeoutHeightNotReached"
 }
 \* TODO: the current generation of tests cannot distinguish between a
 \*       "Ics03ConnectionMismatch" generated in conn open try, one generated
@@ -164,6 +337,33 @@ Connection == [
 Connections == [
     ConnectionIds -> Connection
 ]
+\* data kept per channel
+Channel == [
+    state: ChannelStates,
+    order: ChannelOrders \union {ChannelOrderNone},
+    \* `chainId` is not strictly necessary but it's kept for consistency
+    chainId: ChainIds \union {ChainIdNone},
+    connectionId: ConnectionIds \union {ConnectionIdNone},
+    channelId: ChannelIds \union {ChannelIdNone},
+    counterpartyChainId: ChainIds \union {ChainIdNone},
+    counterpartyChannelId: ChannelIds \union {ChannelIdNone},
+    nextSendSeq: SequenceNumbers,
+    nextRecvSeq: SequenceNumbers,
+    nextAckSeq: SequenceNumbers
+]
+\* mapping from channel identifier to its data
+Channels == [
+    ChannelIds -> Channel
+]
+\* data kept per packet
+Packet == [
+    sourceChainId: ChainIds,
+    sourceChannelId: ChannelIds,
+    destChainId: ChainIds,
+    destChannelId: ChannelIds,
+    sequence: Sequences,
+    timeoutHeight: Heights
+]
 \* data kept per chain
 Chain == [
     height: Heights,
@@ -171,7 +371,15 @@ Chain == [
     clientIdCounter: 0..MaxClientsPerChain,
     connections: Connections,
     connectionIdCounter: 0..MaxConnectionsPerChain,
-    connectionProofs: SUBSET ConnectionActions
+    connectionProofs: SUBSET ConnectionActions,
+    channels: Channels,
+    channelIdCounter: 0..MaxChannelsPerChain,
+    channelProofs: SUBSET ChannelActions,
+    \* packets sent and neither acknowledged nor timed out
+    packetCommitments: SUBSET Packet,
+    \* packets received on unordered channels
+    packetReceipts: SUBSET Packet,
+    packetProofs: SUBSET PacketActions
 ]
 \* mapping from chain identifier to its data
 Chains == [
@@ -194,6 +402,26 @@ UpdateConnectionProofs(connectionProofs, result, okOutcome) ==
     ELSE
         connectionProofs
 
+\* update the channel proofs of the counterparty chain of the action if outcome
+\* was ok
+UpdateChannelProofs(chains_, result, okOutcome) ==
+    IF result.outcome = okOutcome THEN
+        LET counterpartyChainId == result.action.counterpartyChainId IN
+        [chains_ EXCEPT
+            ![counterpartyChainId].channelProofs = @ \union {result.action}]
+    ELSE
+        chains_
+
+\* update the packet proofs of the counterparty chain of the action if outcome
+\* was ok
+UpdatePacketProofs(chains_, result, okOutcome) ==
+    IF result.outcome = okOutcome THEN
+        LET counterpartyChainId == result.action.counterpartyChainId IN
+        [chains_ EXCEPT
+            ![counterpartyChainId].packetProofs = @ \union {result.action}]
+    ELSE
+        chains_
+
 CreateClient(chainId, height) ==
     LET chain == chains[chainId] IN
     LET result == ICS02_CreateClient(chain, chainId, height) IN
@@ -352,6 +580,296 @@ ConnectionOpenConfirm(
     /\ action' = result.action
     /\ actionOutcome' = result.outcome
 
+ChannelOpenInit(chainId, connectionId, order) ==
+    LET chain == chains[chainId] IN
+    LET result == ICS04_ChannelOpenInit(
+        chain,
+        chainId,
+        connectionId,
+        order
+    ) IN
+    \* update the chain
+    LET updatedChain == [chain EXCEPT
+        !.height = UpdateChainHeight(@, result, "Ics04ChannelOpenInitOk"),
+        !.channels = result.channels,
+        !.channelIdCounter = result.channelIdCounter
+    ] IN
+    \* update `chains` (where the counterparty chain is updated with a proof),
+    \* set the `action` and its `actionOutcome`
+    /\ chains' = UpdateChannelProofs(
+        [chains EXCEPT ![chainId] = updatedChain],
+        result,
+        "Ics04ChannelOpenInitOk"
+    )
+    /\ action' = result.action
+    /\ actionOutcome' = result.outcome
+
+ChannelOpenTry(
+    chainId,
+    connectionId,
+    order,
+    height,
+    counterpartyChainId,
+    counterpartyChannelId
+) ==
+    LET chain == chains[chainId] IN
+    LET result == ICS04_ChannelOpenTry(
+        chain,
+        chainId,
+        connectionId,
+        order,
+        height,
+        counterpartyChainId,
+        counterpartyChannelId
+    ) IN
+    \* update the chain
+    LET updatedChain == [chain EXCEPT
+        !.height = UpdateChainHeight(@, result, "Ics04ChannelOpenTryOk"),
+        !.channels = result.channels,
+        !.channelIdCounter = result.channelIdCounter
+    ] IN
+    \* update `chains` (where the counterparty chain is updated with a proof),
+    \* set the `action` and its `actionOutcome`
+    /\ chains' = UpdateChannelProofs(
+        [chains EXCEPT ![chainId] = updatedChain],
+        result,
+        "Ics04ChannelOpenTryOk"
+    )
+    /\ action' = result.action
+    /\ actionOutcome' = result.outcome
+
+ChannelOpenAck(
+    chainId,
+    channelId,
+    height,
+    counterpartyChainId,
+    counterpartyChannelId
+) ==
+    LET chain == chains[chainId] IN
+    LET result == ICS04_ChannelOpenAck(
+        chain,
+        chainId,
+        channelId,
+        height,
+        counterpartyChainId,
+        counterpartyChannelId
+    ) IN
+    \* update the chain
+    LET updatedChain == [chain EXCEPT
+        !.height = UpdateChainHeight(@, result, "Ics04ChannelOpenAckOk"),
+        !.channels = result.channels
+    ] IN
+    \* update `chains` (where the counterparty chain is updated with a proof),
+    \* set the `action` and its `actionOutcome`
+    /\ chains' = UpdateChannelProofs(
+        [chains EXCEPT ![chainId] = updatedChain],
+        result,
+        "Ics04ChannelOpenAckOk"
+    )
+    /\ action' = result.action
+    /\ actionOutcome' = result.outcome
+
+ChannelOpenConfirm(
+    chainId,
+    channelId,
+    height,
+    counterpartyChainId,
+    counterpartyChannelId
+) ==
+    LET chain == chains[chainId] IN
+    LET result == ICS04_ChannelOpenConfirm(
+        chain,
+        chainId,
+        channelId,
+        height,
+        counterpartyChainId,
+        counterpartyChannelId
+    ) IN
+    \* update the chain
+    LET updatedChain == [chain EXCEPT
+        !.height = UpdateChainHeight(@, result, "Ics04ChannelOpenConfirmOk"),
+        !.channels = result.channels
+    ] IN
+    \* no need to update the counterparty chain with a proof (as in the other
+    \* channel open handlers)
+    \* update `chains`, set the `action` and its `actionOutcome`
+    /\ chains' = [chains EXCEPT ![chainId] = updatedChain]
+    /\ action' = result.action
+    /\ actionOutcome' = result.outcome
+
+ChannelCloseInit(chainId, channelId) ==
+    LET chain == chains[chainId] IN
+    LET result == ICS04_ChannelCloseInit(
+        chain,
+        chainId,
+        channelId
+    ) IN
+    \* update the chain
+    LET updatedChain == [chain EXCEPT
+        !.height = UpdateChainHeight(@, result, "Ics04ChannelCloseInitOk"),
+        !.channels = result.channels
+    ] IN
+    \* update `chains` (where the counterparty chain is updated with a proof),
+    \* set the `action` and its `actionOutcome`
+    /\ chains' = UpdateChannelProofs(
+        [chains EXCEPT ![chainId] = updatedChain],
+        result,
+        "Ics04ChannelCloseInitOk"
+    )
+    /\ action' = result.action
+    /\ actionOutcome' = result.outcome
+
+ChannelCloseConfirm(
+    chainId,
+    channelId,
+    height,
+    counterpartyChainId,
+    counterpartyChannelId
+) ==
+    LET chain == chains[chainId] IN
+    LET result == ICS04_ChannelCloseConfirm(
+        chain,
+        chainId,
+        channelId,
+        height,
+        counterpartyChainId,
+        counterpartyChannelId
+    ) IN
+    \* update the chain
+    LET updatedChain == [chain EXCEPT
+        !.height = UpdateChainHeight(@, result, "Ics04ChannelCloseConfirmOk"),
+        !.channels = result.channels
+    ] IN
+    \* update `chains`, set the `action` and its `actionOutcome`
+    /\ chains' = [chains EXCEPT ![chainId] = updatedChain]
+    /\ action' = result.action
+    /\ actionOutcome' = result.outcome
+
+SendPacket(chainId, channelId, timeoutHeight) ==
+    LET chain == chains[chainId] IN
+    LET result == ICS04_SendPacket(
+        chain,
+        chainId,
+        channelId,
+        timeoutHeight
+    ) IN
+    \* update the chain
+    LET updatedChain == [chain EXCEPT
+        !.height = UpdateChainHeight(@, result, "Ics04SendPacketOk"),
+        !.channels = result.channels,
+        !.packetCommitments = result.packetCommitments
+    ] IN
+    \* update `chains` (where the counterparty chain is updated with a proof),
+    \* set the `action` and its `actionOutcome`
+    /\ chains' = UpdatePacketProofs(
+        [chains EXCEPT ![chainId] = updatedChain],
+        result,
+        "Ics04SendPacketOk"
+    )
+    /\ action' = result.action
+    /\ actionOutcome' = result.outcome
+
+RecvPacket(
+    chainId,
+    channelId,
+    height,
+    counterpartyChainId,
+    counterpartyChannelId,
+    sequence,
+    timeoutHeight
+) ==
+    LET chain == chains[chainId] IN
+    LET result == ICS04_RecvPacket(
+        chain,
+        chainId,
+        channelId,
+        height,
+        counterpartyChainId,
+        counterpartyChannelId,
+        sequence,
+        timeoutHeight
+    ) IN
+    \* update the chain
+    LET updatedChain == [chain EXCEPT
+        !.height = UpdateChainHeight(@, result, "Ics04RecvPacketOk"),
+        !.channels = result.channels,
+        !.packetReceipts = result.packetReceipts
+    ] IN
+    \* update `chains` (where the counterparty chain is updated with a proof
+    \* of the acknowledgement written upon receiving the packet), set the
+    \* `action` and its `actionOutcome`
+    /\ chains' = UpdatePacketProofs(
+        [chains EXCEPT ![chainId] = updatedChain],
+        result,
+        "Ics04RecvPacketOk"
+    )
+    /\ action' = result.action
+    /\ actionOutcome' = result.outcome
+
+Acknowledgement(
+    chainId,
+    channelId,
+    height,
+    counterpartyChainId,
+    counterpartyChannelId,
+    sequence,
+    timeoutHeight
+) ==
+    LET chain == chains[chainId] IN
+    LET result == ICS04_Acknowledgement(
+        chain,
+        chainId,
+        channelId,
+        height,
+        counterpartyChainId,
+        counterpartyChannelId,
+        sequence,
+        timeoutHeight
+    ) IN
+    \* update the chain
+    LET updatedChain == [chain EXCEPT
+        !.height = UpdateChainHeight(@, result, "Ics04AcknowledgementOk"),
+        !.channels = result.channels,
+        !.packetCommitments = result.packetCommitments
+    ] IN
+    \* update `chains`, set the `action` and its `actionOutcome`
+    /\ chains' = [chains EXCEPT ![chainId] = updatedChain]
+    /\ action' = result.action
+    /\ actionOutcome' = result.outcome
+
+Timeout(
+    chainId,
+    channelId,
+    height,
+    counterpartyChainId,
+    counterpartyChannelId,
+    sequence,
+    timeoutHeight,
+    nextSequenceRecv
+) ==
+    LET chain == chains[chainId] IN
+    LET result == ICS04_Timeout(
+        chain,
+        chainId,
+        channelId,
+        height,
+        counterpartyChainId,
+        counterpartyChannelId,
+        sequence,
+        timeoutHeight,
+        nextSequenceRecv
+    ) IN
+    \* update the chain
+    LET updatedChain == [chain EXCEPT
+        !.height = UpdateChainHeight(@, result, "Ics04TimeoutOk"),
+        !.channels = result.channels,
+        !.packetCommitments = result.packetCommitments
+    ] IN
+    \* update `chains`, set the `action` and its `actionOutcome`
+    /\ chains' = [chains EXCEPT ![chainId] = updatedChain]
+    /\ action' = result.action
+    /\ actionOutcome' = result.outcome
+
 CreateClientAction(chainId) ==
     \* select a height for the client to be created at
     \E height \in Heights:
@@ -463,6 +981,187 @@ ConnectionOpenConfirmAction(chainId) ==
         ELSE
             UNCHANGED vars
 
+\* The mock clients do not verify proofs, so the datagrams of the channel and
+\* packet protocols are, like a relayer would do, built from the actions of the
+\* counterparty chain that can be proven on this chain. Only the claimed height
+\* of the client and, in the case of channel open try, the connection, are not
+\* taken from the proof.
+
+ChannelOpenInitAction(chainId) ==
+    \* select a connection id
+    \E connectionId \in ConnectionIds:
+    \* select a channel order
+    \E order \in ChannelOrders:
+        \* only create channel if the model constant `MaxChannelsPerChain`
+        \* allows it
+        LET allowed ==
+            chains[chainId].channelIdCounter < MaxChannelsPerChain IN
+        IF allowed THEN
+            ChannelOpenInit(chainId, connectionId, order)
+        ELSE
+            UNCHANGED vars
+
+ChannelOpenTryAction(chainId) ==
+    \* select a connection id
+    \E connectionId \in ConnectionIds:
+    \* select a claimed height for the client
+    \E height \in Heights:
+    \* select a proof of a channel open init at the counterparty chain
+    \E proof \in chains[chainId].channelProofs:
+        \* only create channel if the model constant `MaxChannelsPerChain`
+        \* allows it
+        LET allowed ==
+            chains[chainId].channelIdCounter < MaxChannelsPerChain IN
+        IF proof.type = "Ics04ChannelOpenInit" /\ allowed THEN
+            ChannelOpenTry(
+                chainId,
+                connectionId,
+                proof.order,
+                height,
+                proof.chainId,
+                proof.channelId
+            )
+        ELSE
+            UNCHANGED vars
+
+ChannelOpenAckAction(chainId) ==
+    \* select a claimed height for the client
+    \E height \in Heights:
+    \* select a proof of a channel open try at the counterparty chain
+    \E proof \in chains[chainId].channelProofs:
+        IF proof.type = "Ics04ChannelOpenTry" THEN
+            ChannelOpenAck(
+                chainId,
+                proof.counterpartyChannelId,
+                height,
+                proof.chainId,
+                proof.channelId
+            )
+        ELSE
+            UNCHANGED vars
+
+ChannelOpenConfirmAction(chainId) ==
+    \* select a claimed height for the client
+    \E height \in Heights:
+    \* select a proof of a channel open ack at the counterparty chain
+    \E proof \in chains[chainId].channelProofs:
+        IF proof.type = "Ics04ChannelOpenAck" THEN
+            ChannelOpenConfirm(
+                chainId,
+                proof.counterpartyChannelId,
+                height,
+                proof.chainId,
+                proof.channelId
+            )
+        ELSE
+            UNCHANGED vars
+
+ChannelCloseInitAction(chainId) ==
+    \* select a channel id
+    \E channelId \in ChannelIds:
+        ChannelCloseInit(chainId, channelId)
+
+ChannelCloseConfirmAction(chainId) ==
+    \* select a claimed height for the client
+    \E height \in Heights:
+    \* select a proof of a channel close init at the counterparty chain
+    \E proof \in chains[chainId].channelProofs:
+        \* the channel closed at the counterparty chain may not know its
+        \* counterparty channel, in which case there's no channel to close
+        IF /\ proof.type = "Ics04ChannelCloseInit"
+           /\ proof.counterpartyChannelId /= ChannelIdNone THEN
+            ChannelCloseConfirm(
+                chainId,
+                proof.counterpartyChannelId,
+                height,
+                proof.chainId,
+                proof.channelId
+            )
+        ELSE
+            UNCHANGED vars
+
+SendPacketAction(chainId) ==
+    \* select a channel id
+    \E channelId \in ChannelIds:
+    \* select a timeout height
+    \E timeoutHeight \in Heights:
+        \* only send packet if the model constant `MaxPacketsPerChannel`
+        \* allows it
+        LET allowed ==
+            chains[chainId].channels[channelId].nextSendSeq <= MaxPacketsPerChannel IN
+        IF allowed THEN
+            SendPacket(chainId, channelId, timeoutHeight)
+        ELSE
+            UNCHANGED vars
+
+RecvPacketAction(chainId) ==
+    \* select a claimed height for the client
+    \E height \in Heights:
+    \* select a proof of a packet sent by the counterparty chain
+    \E proof \in chains[chainId].packetProofs:
+        IF proof.type = "Ics04SendPacket" THEN
+            RecvPacket(
+                chainId,
+                proof.counterpartyChannelId,
+                height,
+                proof.chainId,
+                proof.channelId,
+                proof.sequence,
+                proof.timeoutHeight
+            )
+        ELSE
+            UNCHANGED vars
+
+AcknowledgementAction(chainId) ==
+    \* select a claimed height for the client
+    \E height \in Heights:
+    \* select a proof of a packet received by the counterparty chain
+    \E proof \in chains[chainId].packetProofs:
+        IF proof.type = "Ics04RecvPacket" THEN
+            Acknowledgement(
+                chainId,
+                proof.counterpartyChannelId,
+                height,
+                proof.chainId,
+                proof.channelId,
+                proof.sequence,
+                proof.timeoutHeight
+            )
+        ELSE
+            UNCHANGED vars
+
+TimeoutAction(chainId) ==
+    \* select a claimed height for the client
+    \E height \in Heights:
+    \* select a counterparty chain id
+    \E counterpartyChainId \in ChainIds:
+    \* select a packet sent to the counterparty chain
+    \E proof \in chains[counterpartyChainId].packetProofs:
+        LET counterpartyChain == chains[counterpartyChainId] IN
+        LET counterpartyChannel ==
+            counterpartyChain.channels[proof.counterpartyChannelId] IN
+        \* the mock clients do not verify that a packet has not been received,
+        \* so the packets received on unordered channels are not timed out;
+        \* on ordered channels, the next receive sequence number of the
+        \* counterparty channel tells whether the packet has been received
+        LET received ==
+            ICS04_SentPacket(proof) \in counterpartyChain.packetReceipts IN
+        IF /\ proof.type = "Ics04SendPacket"
+           /\ proof.chainId = chainId
+           /\ ~received THEN
+            Timeout(
+                chainId,
+                proof.channelId,
+                height,
+                counterpartyChainId,
+                proof.counterpartyChannelId,
+                proof.sequence,
+                proof.timeoutHeight,
+                counterpartyChannel.nextRecvSeq
+            )
+        ELSE
+            UNCHANGED vars
+
 Init ==
     \* create a client and a connection with none values
     LET clientNone == [
@@ -477,6 +1176,18 @@ Init ==
         counterpartyClientId |-> ClientIdNone,
         counterpartyConnectionId |-> ConnectionIdNone
     ] IN
+    LET channelNone == [
+        state |-> "Uninitialized",
+        order |-> ChannelOrderNone,
+        chainId |-> ChainIdNone,
+        connectionId |-> ConnectionIdNone,
+        channelId |-> ChannelIdNone,
+        counterpartyChainId |-> ChainIdNone,
+        counterpartyChannelId |-> ChannelIdNone,
+        nextSendSeq |-> 1,
+        nextRecvSeq |-> 1,
+        nextAckSeq |-> 1
+    ] IN
     \* create an empty chain
     LET emptyChain == [
         height |-> 1,
@@ -484,7 +1195,13 @@ Init ==
         clientIdCounter |-> 0,
         connections |-> [connectionId \in ConnectionIds |-> connectionNone],
         connectionIdCounter |-> 0,
-        connectionProofs |-> AsSetAction({})
+        connectionProofs |-> AsSetAction({}),
+        channels |-> [channelId \in ChannelIds |-> channelNone],
+        channelIdCounter |-> 0,
+        channelProofs |-> AsSetAction({}),
+        packetCommitments |-> AsSetPacket({}),
+        packetReceipts |-> AsSetPacket({}),
+        packetProofs |-> AsSetAction({})
     ] IN
     /\ chains = [chainId \in ChainIds |-> emptyChain]
     /\ action = AsAction([type |-> "None"])
@@ -502,6 +1219,16 @@ Next ==
             \/ ConnectionOpenTryAction(chainId)
             \/ ConnectionOpenAckAction(chainId)
             \/ ConnectionOpenConfirmAction(chainId)
+            \/ ChannelOpenInitAction(chainId)
+            \/ ChannelOpenTryAction(chainId)
+            \/ ChannelOpenAckAction(chainId)
+            \/ ChannelOpenConfirmAction(chainId)
+            \/ ChannelCloseInitAction(chainId)
+            \/ ChannelCloseConfirmAction(chainId)
+            \/ SendPacketAction(chainId)
+            \/ RecvPacketAction(chainId)
+            \/ AcknowledgementAction(chainId)
+            \/ TimeoutAction(chainId)
             \/ UNCHANGED vars
         ELSE
             \/ UNCHANGED vars
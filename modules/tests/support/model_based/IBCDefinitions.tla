@@ -14,12 +14,30 @@ ActionType == [
     clientId |-> Int,
     header |-> Int,
     previousConnectionId |-> Int,
+    connectionId |-> Int,
+    channelId |-> Int,
+    order |-> STRING,
+    proofHeight |-> Int,
+    sequence |-> Int,
+    timeoutHeight |-> Int,
+    nextSequenceRecv |-> Int,
     counterpartyChainId |-> STRING,
     counterpartyClientId |-> Int,
-    counterpartyConnectionId |-> Int
+    counterpartyConnectionId |-> Int,
+    counterpartyChannelId |-> Int
 ]
 AsAction(a) == a <: ActionType
 AsSetAction(S) == S <: {ActionType}
+PacketType == [
+    sourceChainId |-> STRING,
+    sourceChannelId |-> Int,
+    destChainId |-> STRING,
+    destChannelId |-> Int,
+    sequence |-> Int,
+    timeoutHeight |-> Int
+]
+AsPacket(p) == p <: PacketType
+AsSetPacket(S) == S <: {PacketType}
 AsSetInt(S) == S <: {Int}
 (******************* END OF TYPE ANNOTATIONS FOR APALACHE ********************)
 
@@ -33,5 +51,9 @@ ChainIdNone == "-1"
 ClientIdNone == -1
 \* if a connection identifier is not set then it is -1
 ConnectionIdNone == -1
+\* if a channel identifier is not set then it is -1
+ChannelIdNone == -1
+\* if a channel order is not set then it is "None"
+ChannelOrderNone == "None"
 
 ===============================================================================
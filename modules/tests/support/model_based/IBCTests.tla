@@ -55,4 +55,95 @@ ICS03UninitializedConnectionTest ==
 ICS03ConnectionOpenConfirmOKTest ==
     /\ actionOutcome = "Ics03ConnectionOpenConfirmOk"
 
+\* ICS04ChannelOpenInit tests
+ICS04ChannelOpenInitOKTest ==
+    /\ actionOutcome = "Ics04ChannelOpenInitOk"
+
+ICS04MissingConnectionTest ==
+    /\ actionOutcome = "Ics04MissingConnection"
+
+\* ICS04ChannelOpenTry tests
+ICS04ChannelOpenTryOKTest ==
+    /\ actionOutcome = "Ics04ChannelOpenTryOk"
+
+ICS04ConnectionNotOpenTest ==
+    /\ actionOutcome = "Ics04ConnectionNotOpen"
+
+ICS04MissingClientConsensusStateTest ==
+    /\ actionOutcome = "Ics04MissingClientConsensusState"
+
+\* ICS04ChannelOpenAck tests
+ICS04ChannelOpenAckOKTest ==
+    /\ actionOutcome = "Ics04ChannelOpenAckOk"
+
+ICS04InvalidChannelStateTest ==
+    /\ actionOutcome = "Ics04InvalidChannelState"
+
+\* ICS04ChannelOpenConfirm tests
+ICS04ChannelOpenConfirmOKTest ==
+    /\ actionOutcome = "Ics04ChannelOpenConfirmOk"
+
+\* ICS04ChannelCloseInit tests
+ICS04ChannelCloseInitOKTest ==
+    /\ actionOutcome = "Ics04ChannelCloseInitOk"
+
+ICS04ChannelNotFoundTest ==
+    /\ actionOutcome = "Ics04ChannelNotFound"
+
+\* ICS04ChannelCloseConfirm tests
+ICS04ChannelCloseConfirmOKTest ==
+    /\ actionOutcome = "Ics04ChannelCloseConfirmOk"
+
+ICS04ChannelClosedTest ==
+    /\ actionOutcome = "Ics04ChannelClosed"
+
+\* ICS04SendPacket tests
+ICS04SendPacketOKTest ==
+    /\ actionOutcome = "Ics04SendPacketOk"
+
+ICS04InvalidPacketCounterpartyTest ==
+    /\ actionOutcome = "Ics04InvalidPacketCounterparty"
+
+ICS04LowPacketHeightTest ==
+    /\ actionOutcome = "Ics04LowPacketHeight"
+
+\* ICS04RecvPacket tests
+ICS04OrderedRecvPacketOKTest ==
+    /\ actionOutcome = "Ics04RecvPacketOk"
+    /\ chains[action.chainId].channels[action.channelId].order = "Ordered"
+
+ICS04UnorderedRecvPacketOKTest ==
+    /\ actionOutcome = "Ics04RecvPacketOk"
+    /\ chains[action.chainId].channels[action.channelId].order = "Unordered"
+
+ICS04InvalidPacketSequenceTest ==
+    /\ actionOutcome = "Ics04InvalidPacketSequence"
+
+ICS04PacketAlreadyReceivedTest ==
+    /\ actionOutcome = "Ics04PacketAlreadyReceived"
+
+\* ICS04Acknowledgement tests
+ICS04OrderedAcknowledgementOKTest ==
+    /\ actionOutcome = "Ics04AcknowledgementOk"
+    /\ chains[action.chainId].channels[action.channelId].order = "Ordered"
+
+ICS04UnorderedAcknowledgementOKTest ==
+    /\ actionOutcome = "Ics04AcknowledgementOk"
+    /\ chains[action.chainId].channels[action.channelId].order = "Unordered"
+
+ICS04PacketCommitmentNotFoundTest ==
+    /\ actionOutcome = "Ics04PacketCommitmentNotFound"
+
+\* ICS04Timeout tests
+ICS04OrderedTimeoutOKTest ==
+    /\ actionOutcome = "Ics04TimeoutOk"
+    /\ chains[action.chainId].channels[action.channelId].order = "Ordered"
+
+ICS04UnorderedTimeoutOKTest ==
+    /\ actionOutcome = "Ics04TimeoutOk"
+    /\ chains[action.chainId].channels[action.channelId].order = "Unordered"
+
+ICS04PacketTimeoutHeightNotReachedTest ==
+    /\ actionOutcome = "Ics04PacketTimeoutHeightNotReached"
+
 ===============================================================================
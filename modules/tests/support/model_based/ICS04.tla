@@ -0,0 +1,979 @@
+------------------------------ MODULE ICS04 -----------------------------------
+
+EXTENDS ICS03
+
+\* retrieves `channelId`'s data
+ICS04_GetChannel(channels, channelId) ==
+    channels[channelId]
+
+\* check if `channelId` exists
+ICS04_ChannelExists(channels, channelId) ==
+    ICS04_GetChannel(channels, channelId).state /= "Uninitialized"
+
+\* update `channelId`'s data
+ICS04_SetChannel(channels, channelId, channel) ==
+    [channels EXCEPT ![channelId] = channel]
+
+\* check if the client underlying `connection` has a consensus state at `height`
+ICS04_ConsensusStateExists(chain, connection, height) ==
+    height \in ICS02_GetClient(chain.clients, connection.clientId).heights
+
+\* the packet carried by a packet action, where the action takes place on the
+\* chain `sourceChainId`, i.e., it sends, acknowledges or times out the packet
+ICS04_SentPacket(action_) ==
+    AsPacket([
+        sourceChainId |-> action_.chainId,
+        sourceChannelId |-> action_.channelId,
+        destChainId |-> action_.counterpartyChainId,
+        destChannelId |-> action_.counterpartyChannelId,
+        sequence |-> action_.sequence,
+        timeoutHeight |-> action_.timeoutHeight
+    ])
+
+\* the packet carried by a packet action, where the action takes place on the
+\* chain `destChainId`, i.e., it receives the packet
+ICS04_ReceivedPacket(action_) ==
+    AsPacket([
+        sourceChainId |-> action_.counterpartyChainId,
+        sourceChannelId |-> action_.counterpartyChannelId,
+        destChainId |-> action_.chainId,
+        destChannelId |-> action_.channelId,
+        sequence |-> action_.sequence,
+        timeoutHeight |-> action_.timeoutHeight
+    ])
+
+ICS04_ChannelOpenInit(
+    chain,
+    chainId,
+    connectionId,
+    order
+) ==
+    LET connections == chain.connections IN
+    LET connection == ICS03_GetConnection(connections, connectionId) IN
+    LET action_ == AsAction([
+        type |-> "Ics04ChannelOpenInit",
+        chainId |-> chainId,
+        connectionId |-> connectionId,
+        order |-> order,
+        \* the identifier of the channel to be created and the counterparty
+        \* chain are not chosen by the relayer; they are recorded so that the
+        \* action can be proven to the counterparty chain
+        channelId |-> chain.channelIdCounter,
+        counterpartyChainId |-> connection.counterpartyChainId
+    ]) IN
+    \* check if the connection exists
+    IF ~ICS03_ConnectionExists(connections, connectionId) THEN
+        \* if the connection does not exist, then set an error outcome
+        [
+            channels |-> chain.channels,
+            channelIdCounter |-> chain.channelIdCounter,
+            action |-> action_,
+            outcome |-> "Ics04MissingConnection"
+        ]
+    ELSE
+        \* if the connection exists, then check if the channel exists (it
+        \* shouldn't)
+        IF ICS04_ChannelExists(chain.channels, chain.channelIdCounter) THEN
+            \* if the channel to be created already exists,
+            \* then there's an error in the model
+            [
+                channels |-> chain.channels,
+                channelIdCounter |-> chain.channelIdCounter,
+                action |-> action_,
+                outcome |-> "ModelError"
+            ]
+        ELSE
+            \* if it doesn't, create it; note that, unlike the other channel
+            \* handlers, the connection is not required to be open
+            LET channel == [
+                state |-> "Init",
+                order |-> order,
+                chainId |-> chainId,
+                connectionId |-> connectionId,
+                \* generate a new channel identifier
+                channelId |-> chain.channelIdCounter,
+                counterpartyChainId |-> connection.counterpartyChainId,
+                counterpartyChannelId |-> ChannelIdNone,
+                nextSendSeq |-> 1,
+                nextRecvSeq |-> 1,
+                nextAckSeq |-> 1
+            ] IN
+            \* return result with updated state
+            [
+                channels |-> ICS04_SetChannel(
+                    chain.channels,
+                    chain.channelIdCounter,
+                    channel
+                ),
+                channelIdCounter |-> chain.channelIdCounter + 1,
+                action |-> action_,
+                outcome |-> "Ics04ChannelOpenInitOk"
+            ]
+
+ICS04_ChannelOpenTry(
+    chain,
+    chainId,
+    connectionId,
+    order,
+    height,
+    counterpartyChainId,
+    counterpartyChannelId
+) ==
+    LET action_ == AsAction([
+        type |-> "Ics04ChannelOpenTry",
+        chainId |-> chainId,
+        connectionId |-> connectionId,
+        order |-> order,
+        \* the identifier of the channel to be created is not chosen by the
+        \* relayer; it is recorded so that the action can be proven to the
+        \* counterparty chain
+        channelId |-> chain.channelIdCounter,
+        proofHeight |-> height,
+        counterpartyChainId |-> counterpartyChainId,
+        counterpartyChannelId |-> counterpartyChannelId
+    ]) IN
+    LET connections == chain.connections IN
+    \* check if the connection exists
+    IF ~ICS03_ConnectionExists(connections, connectionId) THEN
+        \* if the connection does not exist, then set an error outcome
+        [
+            channels |-> chain.channels,
+            channelIdCounter |-> chain.channelIdCounter,
+            action |-> action_,
+            outcome |-> "Ics04MissingConnection"
+        ]
+    ELSE
+        \* if the connection exists, check that it is open
+        LET connection == ICS03_GetConnection(connections, connectionId) IN
+        IF connection.state /= "Open" THEN
+            \* if the connection is not open, then set an error outcome
+            [
+                channels |-> chain.channels,
+                channelIdCounter |-> chain.channelIdCounter,
+                action |-> action_,
+                outcome |-> "Ics04ConnectionNotOpen"
+            ]
+        ELSE
+            \* check if the client has a consensus state with this height
+            IF ~ICS04_ConsensusStateExists(chain, connection, height) THEN
+                \* if the client does have a consensus state with this
+                \* height, then set an error outcome
+                [
+                    channels |-> chain.channels,
+                    channelIdCounter |-> chain.channelIdCounter,
+                    action |-> action_,
+                    outcome |-> "Ics04MissingClientConsensusState"
+                ]
+            ELSE
+                \* check if the channel to be created already exists (it
+                \* shouldn't)
+                IF ICS04_ChannelExists(chain.channels, chain.channelIdCounter) THEN
+                    \* if the channel to be created already exists,
+                    \* then there's an error in the model
+                    [
+                        channels |-> chain.channels,
+                        channelIdCounter |-> chain.channelIdCounter,
+                        action |-> action_,
+                        outcome |-> "ModelError"
+                    ]
+                ELSE
+                    \* verification passed; create channel
+                    LET channel == [
+                        state |-> "TryOpen",
+                        order |-> order,
+                        chainId |-> chainId,
+                        connectionId |-> connectionId,
+                        \* generate a new channel identifier
+                        channelId |-> chain.channelIdCounter,
+                        counterpartyChainId |-> counterpartyChainId,
+                        counterpartyChannelId |-> counterpartyChannelId,
+                        nextSendSeq |-> 1,
+                        nextRecvSeq |-> 1,
+                        nextAckSeq |-> 1
+                    ] IN
+                    \* return result with updated state
+                    [
+                        channels |-> ICS04_SetChannel(
+                            chain.channels,
+                            chain.channelIdCounter,
+                            channel
+                        ),
+                        channelIdCounter |-> chain.channelIdCounter + 1,
+                        action |-> action_,
+                        outcome |-> "Ics04ChannelOpenTryOk"
+                    ]
+
+ICS04_ChannelOpenAck(
+    chain,
+    chainId,
+    channelId,
+    height,
+    counterpartyChainId,
+    counterpartyChannelId
+) ==
+    LET action_ == AsAction([
+        type |-> "Ics04ChannelOpenAck",
+        chainId |-> chainId,
+        channelId |-> channelId,
+        proofHeight |-> height,
+        counterpartyChainId |-> counterpartyChainId,
+        counterpartyChannelId |-> counterpartyChannelId
+    ]) IN
+    LET channels == chain.channels IN
+    \* check if the channel exists
+    IF ~ICS04_ChannelExists(channels, channelId) THEN
+        \* if the channel does not exist, then set an error outcome
+        [
+            channels |-> channels,
+            action |-> action_,
+            outcome |-> "Ics04ChannelNotFound"
+        ]
+    ELSE
+        \* if the channel exists, verify that is either Init or TryOpen
+        LET channel == ICS04_GetChannel(channels, channelId) IN
+        IF channel.state \notin {"Init", "TryOpen"} THEN
+            \* if the channel is in any other state, then set an error outcome
+            [
+                channels |-> channels,
+                action |-> action_,
+                outcome |-> "Ics04InvalidChannelState"
+            ]
+        ELSE
+            \* check that the connection of the channel is open
+            LET connection == ICS03_GetConnection(
+                chain.connections,
+                channel.connectionId
+            ) IN
+            IF connection.state /= "Open" THEN
+                \* if the connection is not open, then set an error outcome
+                [
+                    channels |-> channels,
+                    action |-> action_,
+                    outcome |-> "Ics04ConnectionNotOpen"
+                ]
+            ELSE
+                \* check if the client has a consensus state with this height
+                IF ~ICS04_ConsensusStateExists(chain, connection, height) THEN
+                    \* if the client does have a consensus state with this
+                    \* height, then set an error outcome
+                    [
+                        channels |-> channels,
+                        action |-> action_,
+                        outcome |-> "Ics04MissingClientConsensusState"
+                    ]
+                ELSE
+                    \* verification passed; update the channel state to "Open"
+                    \* and record the channel identifier of the counterparty
+                    LET updatedChannel == [channel EXCEPT
+                        !.state = "Open",
+                        !.counterpartyChannelId = counterpartyChannelId
+                    ] IN
+                    \* return result with updated state
+                    [
+                        channels |-> ICS04_SetChannel(
+                            channels,
+                            channelId,
+                            updatedChannel
+                        ),
+                        action |-> action_,
+                        outcome |-> "Ics04ChannelOpenAckOk"
+                    ]
+
+ICS04_ChannelOpenConfirm(
+    chain,
+    chainId,
+    channelId,
+    height,
+    counterpartyChainId,
+    counterpartyChannelId
+) ==
+    LET action_ == AsAction([
+        type |-> "Ics04ChannelOpenConfirm",
+        chainId |-> chainId,
+        channelId |-> channelId,
+        proofHeight |-> height,
+        counterpartyChainId |-> counterpartyChainId,
+        counterpartyChannelId |-> counterpartyChannelId
+    ]) IN
+    LET channels == chain.channels IN
+    \* check if the channel exists
+    IF ~ICS04_ChannelExists(channels, channelId) THEN
+        \* if the channel does not exist, then set an error outcome
+        [
+            channels |-> channels,
+            action |-> action_,
+            outcome |-> "Ics04ChannelNotFound"
+        ]
+    ELSE
+        \* if the channel exists, verify that is TryOpen
+        LET channel == ICS04_GetChannel(channels, channelId) IN
+        IF channel.state /= "TryOpen" THEN
+            \* if the channel is in any other state, then set an error outcome
+            [
+                channels |-> channels,
+                action |-> action_,
+                outcome |-> "Ics04InvalidChannelState"
+            ]
+        ELSE
+            \* check that the connection of the channel is open
+            LET connection == ICS03_GetConnection(
+                chain.connections,
+                channel.connectionId
+            ) IN
+            IF connection.state /= "Open" THEN
+                \* if the connection is not open, then set an error outcome
+                [
+                    channels |-> channels,
+                    action |-> action_,
+                    outcome |-> "Ics04ConnectionNotOpen"
+                ]
+            ELSE
+                \* check if the client has a consensus state with this height
+                IF ~ICS04_ConsensusStateExists(chain, connection, height) THEN
+                    \* if the client does have a consensus state with this
+                    \* height, then set an error outcome
+                    [
+                        channels |-> channels,
+                        action |-> action_,
+                        outcome |-> "Ics04MissingClientConsensusState"
+                    ]
+                ELSE
+                    \* verification passed; update the channel state to "Open"
+                    LET updatedChannel == [channel EXCEPT
+                        !.state = "Open"
+                    ] IN
+                    \* return result with updated state
+                    [
+                        channels |-> ICS04_SetChannel(
+                            channels,
+                            channelId,
+                            updatedChannel
+                        ),
+                        action |-> action_,
+                        outcome |-> "Ics04ChannelOpenConfirmOk"
+                    ]
+
+ICS04_ChannelCloseInit(
+    chain,
+    chainId,
+    channelId
+) ==
+    LET channels == chain.channels IN
+    LET channel == ICS04_GetChannel(channels, channelId) IN
+    LET action_ == AsAction([
+        type |-> "Ics04ChannelCloseInit",
+        chainId |-> chainId,
+        channelId |-> channelId,
+        \* the counterparty of the channel is recorded so that the action can
+        \* be proven to the counterparty chain
+        counterpartyChainId |-> channel.counterpartyChainId,
+        counterpartyChannelId |-> channel.counterpartyChannelId
+    ]) IN
+    \* check if the channel exists
+    IF ~ICS04_ChannelExists(channels, channelId) THEN
+        \* if the channel does not exist, then set an error outcome
+        [
+            channels |-> channels,
+            action |-> action_,
+            outcome |-> "Ics04ChannelNotFound"
+        ]
+    ELSE
+        \* if the channel exists, verify that it is not yet closed
+        IF channel.state = "Closed" THEN
+            \* if the channel is already closed, then set an error outcome
+            [
+                channels |-> channels,
+                action |-> action_,
+                outcome |-> "Ics04InvalidChannelState"
+            ]
+        ELSE
+            \* check that the connection of the channel is open
+            LET connection == ICS03_GetConnection(
+                chain.connections,
+                channel.connectionId
+            ) IN
+            IF connection.state /= "Open" THEN
+                \* if the connection is not open, then set an error outcome
+                [
+                    channels |-> channels,
+                    action |-> action_,
+                    outcome |-> "Ics04ConnectionNotOpen"
+                ]
+            ELSE
+                \* update the channel state to "Closed"
+                LET updatedChannel == [channel EXCEPT
+                    !.state = "Closed"
+                ] IN
+                \* return result with updated state
+                [
+                    channels |-> ICS04_SetChannel(
+                        channels,
+                        channelId,
+                        updatedChannel
+                    ),
+                    action |-> action_,
+                    outcome |-> "Ics04ChannelCloseInitOk"
+                ]
+
+ICS04_ChannelCloseConfirm(
+    chain,
+    chainId,
+    channelId,
+    height,
+    counterpartyChainId,
+    counterpartyChannelId
+) ==
+    LET action_ == AsAction([
+        type |-> "Ics04ChannelCloseConfirm",
+        chainId |-> chainId,
+        channelId |-> channelId,
+        proofHeight |-> height,
+        counterpartyChainId |-> counterpartyChainId,
+        counterpartyChannelId |-> counterpartyChannelId
+    ]) IN
+    LET channels == chain.channels IN
+    \* check if the channel exists
+    IF ~ICS04_ChannelExists(channels, channelId) THEN
+        \* if the channel does not exist, then set an error outcome
+        [
+            channels |-> channels,
+            action |-> action_,
+            outcome |-> "Ics04ChannelNotFound"
+        ]
+    ELSE
+        \* if the channel exists, verify that it is not yet closed
+        LET channel == ICS04_GetChannel(channels, channelId) IN
+        IF channel.state = "Closed" THEN
+            \* if the channel is already closed, then set an error outcome
+            [
+                channels |-> channels,
+                action |-> action_,
+                outcome |-> "Ics04ChannelClosed"
+            ]
+        ELSE
+            \* check that the connection of the channel is open
+            LET connection == ICS03_GetConnection(
+                chain.connections,
+                channel.connectionId
+            ) IN
+            IF connection.state /= "Open" THEN
+                \* if the connection is not open, then set an error outcome
+                [
+                    channels |-> channels,
+                    action |-> action_,
+                    outcome |-> "Ics04ConnectionNotOpen"
+                ]
+            ELSE
+                \* check if the client has a consensus state with this height
+                IF ~ICS04_ConsensusStateExists(chain, connection, height) THEN
+                    \* if the client does have a consensus state with this
+                    \* height, then set an error outcome
+                    [
+                        channels |-> channels,
+                        action |-> action_,
+                        outcome |-> "Ics04MissingClientConsensusState"
+                    ]
+                ELSE
+                    \* verification passed; update the channel state to
+                    \* "Closed"
+                    LET updatedChannel == [channel EXCEPT
+                        !.state = "Closed"
+                    ] IN
+                    \* return result with updated state
+                    [
+                        channels |-> ICS04_SetChannel(
+                            channels,
+                            channelId,
+                            updatedChannel
+                        ),
+                        action |-> action_,
+                        outcome |-> "Ics04ChannelCloseConfirmOk"
+                    ]
+
+ICS04_SendPacket(
+    chain,
+    chainId,
+    channelId,
+    timeoutHeight
+) ==
+    LET channels == chain.channels IN
+    LET channel == ICS04_GetChannel(channels, channelId) IN
+    LET action_ == AsAction([
+        type |-> "Ics04SendPacket",
+        chainId |-> chainId,
+        channelId |-> channelId,
+        \* the destination and the sequence number of the packet are
+        \* determined by the channel
+        counterpartyChainId |-> channel.counterpartyChainId,
+        counterpartyChannelId |-> channel.counterpartyChannelId,
+        sequence |-> channel.nextSendSeq,
+        timeoutHeight |-> timeoutHeight
+    ]) IN
+    \* check if the channel exists
+    IF ~ICS04_ChannelExists(channels, channelId) THEN
+        \* if the channel does not exist, then set an error outcome
+        [
+            channels |-> channels,
+            packetCommitments |-> chain.packetCommitments,
+            action |-> action_,
+            outcome |-> "Ics04ChannelNotFound"
+        ]
+    ELSE
+        \* if the channel exists, verify that it is not closed
+        IF channel.state = "Closed" THEN
+            \* if the channel is closed, then set an error outcome
+            [
+                channels |-> channels,
+                packetCommitments |-> chain.packetCommitments,
+                action |-> action_,
+                outcome |-> "Ics04ChannelClosed"
+            ]
+        ELSE
+            \* check that the channel knows its counterparty channel, as the
+            \* packet is addressed to it
+            IF channel.counterpartyChannelId = ChannelIdNone THEN
+                \* if the counterparty channel is unknown, then set an error
+                \* outcome
+                [
+                    channels |-> channels,
+                    packetCommitments |-> chain.packetCommitments,
+                    action |-> action_,
+                    outcome |-> "Ics04InvalidPacketCounterparty"
+                ]
+            ELSE
+                \* check that the packet can still be received, i.e., that the
+                \* timeout height is higher than the highest height of the
+                \* client of the counterparty chain
+                LET connection == ICS03_GetConnection(
+                    chain.connections,
+                    channel.connectionId
+                ) IN
+                LET client == ICS02_GetClient(chain.clients, connection.clientId) IN
+                IF timeoutHeight <= Max(client.heights) THEN
+                    \* if the packet has timed out already, then set an error
+                    \* outcome
+                    [
+                        channels |-> channels,
+                        packetCommitments |-> chain.packetCommitments,
+                        action |-> action_,
+                        outcome |-> "Ics04LowPacketHeight"
+                    ]
+                ELSE
+                    \* store a commitment to the packet and increment the next
+                    \* send sequence number
+                    LET updatedChannel == [channel EXCEPT
+                        !.nextSendSeq = @ + 1
+                    ] IN
+                    \* return result with updated state
+                    [
+                        channels |-> ICS04_SetChannel(
+                            channels,
+                            channelId,
+                            updatedChannel
+                        ),
+                        packetCommitments |->
+                            chain.packetCommitments \union {ICS04_SentPacket(action_)},
+                        action |-> action_,
+                        outcome |-> "Ics04SendPacketOk"
+                    ]
+
+ICS04_RecvPacket(
+    chain,
+    chainId,
+    channelId,
+    height,
+    counterpartyChainId,
+    counterpartyChannelId,
+    sequence,
+    timeoutHeight
+) ==
+    LET action_ == AsAction([
+        type |-> "Ics04RecvPacket",
+        chainId |-> chainId,
+        channelId |-> channelId,
+        proofHeight |-> height,
+        counterpartyChainId |-> counterpartyChainId,
+        counterpartyChannelId |-> counterpartyChannelId,
+        sequence |-> sequence,
+        timeoutHeight |-> timeoutHeight
+    ]) IN
+    LET channels == chain.channels IN
+    LET packet == ICS04_ReceivedPacket(action_) IN
+    \* check if the channel exists
+    IF ~ICS04_ChannelExists(channels, channelId) THEN
+        \* if the channel does not exist, then set an error outcome
+        [
+            channels |-> channels,
+            packetReceipts |-> chain.packetReceipts,
+            action |-> action_,
+            outcome |-> "Ics04ChannelNotFound"
+        ]
+    ELSE
+        \* if the channel exists, verify that it is open
+        LET channel == ICS04_GetChannel(channels, channelId) IN
+        IF channel.state /= "Open" THEN
+            \* if the channel is not open, then set an error outcome
+            [
+                channels |-> channels,
+                packetReceipts |-> chain.packetReceipts,
+                action |-> action_,
+                outcome |-> "Ics04InvalidChannelState"
+            ]
+        ELSE
+            \* check that the packet was sent by the counterparty channel
+            IF channel.counterpartyChannelId /= counterpartyChannelId THEN
+                \* if the packet comes from another channel, then set an error
+                \* outcome
+                [
+                    channels |-> channels,
+                    packetReceipts |-> chain.packetReceipts,
+                    action |-> action_,
+                    outcome |-> "Ics04InvalidPacketCounterparty"
+                ]
+            ELSE
+                \* check that the connection of the channel is open
+                LET connection == ICS03_GetConnection(
+                    chain.connections,
+                    channel.connectionId
+                ) IN
+                IF connection.state /= "Open" THEN
+                    \* if the connection is not open, then set an error outcome
+                    [
+                        channels |-> channels,
+                        packetReceipts |-> chain.packetReceipts,
+                        action |-> action_,
+                        outcome |-> "Ics04ConnectionNotOpen"
+                    ]
+                ELSE
+                    \* check that the packet has not timed out yet
+                    IF timeoutHeight <= chain.height THEN
+                        \* if the packet has timed out, then set an error
+                        \* outcome
+                        [
+                            channels |-> channels,
+                            packetReceipts |-> chain.packetReceipts,
+                            action |-> action_,
+                            outcome |-> "Ics04LowPacketHeight"
+                        ]
+                    ELSE
+                        \* check if the client has a consensus state with this
+                        \* height
+                        IF ~ICS04_ConsensusStateExists(chain, connection, height) THEN
+                            \* if the client does have a consensus state with
+                            \* this height, then set an error outcome
+                            [
+                                channels |-> channels,
+                                packetReceipts |-> chain.packetReceipts,
+                                action |-> action_,
+                                outcome |-> "Ics04MissingClientConsensusState"
+                            ]
+                        ELSE
+                            IF channel.order = "Ordered" THEN
+                                \* on ordered channels, packets are received
+                                \* in the order they were sent
+                                IF sequence /= channel.nextRecvSeq THEN
+                                    \* if the packet is not the next one, then
+                                    \* set an error outcome
+                                    [
+                                        channels |-> channels,
+                                        packetReceipts |-> chain.packetReceipts,
+                                        action |-> action_,
+                                        outcome |-> "Ics04InvalidPacketSequence"
+                                    ]
+                                ELSE
+                                    \* increment the next receive sequence
+                                    \* number
+                                    LET updatedChannel == [channel EXCEPT
+                                        !.nextRecvSeq = @ + 1
+                                    ] IN
+                                    \* return result with updated state
+                                    [
+                                        channels |-> ICS04_SetChannel(
+                                            channels,
+                                            channelId,
+                                            updatedChannel
+                                        ),
+                                        packetReceipts |-> chain.packetReceipts,
+                                        action |-> action_,
+                                        outcome |-> "Ics04RecvPacketOk"
+                                    ]
+                            ELSE
+                                \* on unordered channels, packets are received
+                                \* in any order, but only once
+                                IF packet \in chain.packetReceipts THEN
+                                    \* if the packet has been received already,
+                                    \* then set an error outcome
+                                    [
+                                        channels |-> channels,
+                                        packetReceipts |-> chain.packetReceipts,
+                                        action |-> action_,
+                                        outcome |-> "Ics04PacketAlreadyReceived"
+                                    ]
+                                ELSE
+                                    \* store a receipt for the packet
+                                    [
+                                        channels |-> channels,
+                                        packetReceipts |->
+                                            chain.packetReceipts \union {packet},
+                                        action |-> action_,
+                                        outcome |-> "Ics04RecvPacketOk"
+                                    ]
+
+ICS04_Acknowledgement(
+    chain,
+    chainId,
+    channelId,
+    height,
+    counterpartyChainId,
+    counterpartyChannelId,
+    sequence,
+    timeoutHeight
+) ==
+    LET action_ == AsAction([
+        type |-> "Ics04Acknowledgement",
+        chainId |-> chainId,
+        channelId |-> channelId,
+        proofHeight |-> height,
+        counterpartyChainId |-> counterpartyChainId,
+        counterpartyChannelId |-> counterpartyChannelId,
+        sequence |-> sequence,
+        timeoutHeight |-> timeoutHeight
+    ]) IN
+    LET channels == chain.channels IN
+    LET packet == ICS04_SentPacket(action_) IN
+    \* check if the channel exists
+    IF ~ICS04_ChannelExists(channels, channelId) THEN
+        \* if the channel does not exist, then set an error outcome
+        [
+            channels |-> channels,
+            packetCommitments |-> chain.packetCommitments,
+            action |-> action_,
+            outcome |-> "Ics04ChannelNotFound"
+        ]
+    ELSE
+        \* if the channel exists, verify that it is open
+        LET channel == ICS04_GetChannel(channels, channelId) IN
+        IF channel.state /= "Open" THEN
+            \* if the channel is not open, then set an error outcome
+            [
+                channels |-> channels,
+                packetCommitments |-> chain.packetCommitments,
+                action |-> action_,
+                outcome |-> "Ics04ChannelClosed"
+            ]
+        ELSE
+            \* check that the packet was sent to the counterparty channel
+            IF channel.counterpartyChannelId /= counterpartyChannelId THEN
+                \* if the packet was sent to another channel, then set an error
+                \* outcome
+                [
+                    channels |-> channels,
+                    packetCommitments |-> chain.packetCommitments,
+                    action |-> action_,
+                    outcome |-> "Ics04InvalidPacketCounterparty"
+                ]
+            ELSE
+                \* check that the connection of the channel is open
+                LET connection == ICS03_GetConnection(
+                    chain.connections,
+                    channel.connectionId
+                ) IN
+                IF connection.state /= "Open" THEN
+                    \* if the connection is not open, then set an error outcome
+                    [
+                        channels |-> channels,
+                        packetCommitments |-> chain.packetCommitments,
+                        action |-> action_,
+                        outcome |-> "Ics04ConnectionNotOpen"
+                    ]
+                ELSE
+                    \* check that the packet is still awaiting its
+                    \* acknowledgement
+                    IF packet \notin chain.packetCommitments THEN
+                        \* if the packet was acknowledged or timed out
+                        \* already, then set an error outcome
+                        [
+                            channels |-> channels,
+                            packetCommitments |-> chain.packetCommitments,
+                            action |-> action_,
+                            outcome |-> "Ics04PacketCommitmentNotFound"
+                        ]
+                    ELSE
+                        \* check if the client has a consensus state with this
+                        \* height
+                        IF ~ICS04_ConsensusStateExists(chain, connection, height) THEN
+                            \* if the client does have a consensus state with
+                            \* this height, then set an error outcome
+                            [
+                                channels |-> channels,
+                                packetCommitments |-> chain.packetCommitments,
+                                action |-> action_,
+                                outcome |-> "Ics04MissingClientConsensusState"
+                            ]
+                        ELSE
+                            \* on ordered channels, packets are acknowledged
+                            \* in the order they were sent
+                            IF /\ channel.order = "Ordered"
+                               /\ sequence /= channel.nextAckSeq THEN
+                                \* if the packet is not the next one, then set
+                                \* an error outcome
+                                [
+                                    channels |-> channels,
+                                    packetCommitments |-> chain.packetCommitments,
+                                    action |-> action_,
+                                    outcome |-> "Ics04InvalidPacketSequence"
+                                ]
+                            ELSE
+                                \* delete the commitment to the packet and, on
+                                \* ordered channels, increment the next
+                                \* acknowledgement sequence number
+                                LET updatedChannel ==
+                                    IF channel.order = "Ordered" THEN
+                                        [channel EXCEPT !.nextAckSeq = @ + 1]
+                                    ELSE
+                                        channel IN
+                                \* return result with updated state
+                                [
+                                    channels |-> ICS04_SetChannel(
+                                        channels,
+                                        channelId,
+                                        updatedChannel
+                                    ),
+                                    packetCommitments |->
+                                        chain.packetCommitments \ {packet},
+                                    action |-> action_,
+                                    outcome |-> "Ics04AcknowledgementOk"
+                                ]
+
+ICS04_Timeout(
+    chain,
+    chainId,
+    channelId,
+    height,
+    counterpartyChainId,
+    counterpartyChannelId,
+    sequence,
+    timeoutHeight,
+    nextSequenceRecv
+) ==
+    LET action_ == AsAction([
+        type |-> "Ics04Timeout",
+        chainId |-> chainId,
+        channelId |-> channelId,
+        proofHeight |-> height,
+        counterpartyChainId |-> counterpartyChainId,
+        counterpartyChannelId |-> counterpartyChannelId,
+        sequence |-> sequence,
+        timeoutHeight |-> timeoutHeight,
+        nextSequenceRecv |-> nextSequenceRecv
+    ]) IN
+    LET channels == chain.channels IN
+    LET packet == ICS04_SentPacket(action_) IN
+    \* check if the channel exists
+    IF ~ICS04_ChannelExists(channels, channelId) THEN
+        \* if the channel does not exist, then set an error outcome
+        [
+            channels |-> channels,
+            packetCommitments |-> chain.packetCommitments,
+            action |-> action_,
+            outcome |-> "Ics04ChannelNotFound"
+        ]
+    ELSE
+        \* if the channel exists, verify that it is open
+        LET channel == ICS04_GetChannel(channels, channelId) IN
+        IF channel.state /= "Open" THEN
+            \* if the channel is not open, then set an error outcome
+            [
+                channels |-> channels,
+                packetCommitments |-> chain.packetCommitments,
+                action |-> action_,
+                outcome |-> "Ics04ChannelClosed"
+            ]
+        ELSE
+            \* check that the packet was sent to the counterparty channel
+            IF channel.counterpartyChannelId /= counterpartyChannelId THEN
+                \* if the packet was sent to another channel, then set an error
+                \* outcome
+                [
+                    channels |-> channels,
+                    packetCommitments |-> chain.packetCommitments,
+                    action |-> action_,
+                    outcome |-> "Ics04InvalidPacketCounterparty"
+                ]
+            ELSE
+                \* check that the counterparty chain has reached the timeout
+                \* height of the packet at the height of the proof
+                IF timeoutHeight > height THEN
+                    \* if the timeout height has not been reached, then set an
+                    \* error outcome
+                    [
+                        channels |-> channels,
+                        packetCommitments |-> chain.packetCommitments,
+                        action |-> action_,
+                        outcome |-> "Ics04PacketTimeoutHeightNotReached"
+                    ]
+                ELSE
+                    \* check if the client has a consensus state with this
+                    \* height
+                    LET connection == ICS03_GetConnection(
+                        chain.connections,
+                        channel.connectionId
+                    ) IN
+                    IF ~ICS04_ConsensusStateExists(chain, connection, height) THEN
+                        \* if the client does have a consensus state with this
+                        \* height, then set an error outcome
+                        [
+                            channels |-> channels,
+                            packetCommitments |-> chain.packetCommitments,
+                            action |-> action_,
+                            outcome |-> "Ics04MissingClientConsensusState"
+                        ]
+                    ELSE
+                        \* check that the packet is still awaiting its
+                        \* acknowledgement
+                        IF packet \notin chain.packetCommitments THEN
+                            \* if the packet was acknowledged or timed out
+                            \* already, then set an error outcome
+                            [
+                                channels |-> channels,
+                                packetCommitments |-> chain.packetCommitments,
+                                action |-> action_,
+                                outcome |-> "Ics04PacketCommitmentNotFound"
+                            ]
+                        ELSE
+                            \* on ordered channels, check that the packet has
+                            \* not been received by the counterparty chain
+                            IF /\ channel.order = "Ordered"
+                               /\ sequence < nextSequenceRecv THEN
+                                \* if the packet has been received, then set an
+                                \* error outcome
+                                [
+                                    channels |-> channels,
+                                    packetCommitments |-> chain.packetCommitments,
+                                    action |-> action_,
+                                    outcome |-> "Ics04InvalidPacketSequence"
+                                ]
+                            ELSE
+                                \* delete the commitment to the packet; as
+                                \* the packets of an ordered channel can no
+                                \* longer be received in order, the channel
+                                \* is closed
+                                LET updatedChannel ==
+                                    IF channel.order = "Ordered" THEN
+                                        [channel EXCEPT !.state = "Closed"]
+                                    ELSE
+                                        channel IN
+                                \* return result with updated state
+                                [
+                                    channels |-> ICS04_SetChannel(
+                                        channels,
+                                        channelId,
+                                        updatedChannel
+                                    ),
+                                    packetCommitments |->
+                                        chain.packetCommitments \ {packet},
+                                    action |-> action_,
+                                    outcome |-> "Ics04TimeoutOk"
+                                ]
+
+===============================================================================